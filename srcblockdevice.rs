@@ -14,6 +14,10 @@ use crate::{
 
 use core::result::Result;
 use core::cmp::min; // core::cmp::min kullanıldı (std yerine)
+use core::fmt;
+
+use alloc::format;
+use alloc::string::String;
 
 // std::io::SeekFrom yerine kendi tanımımızı kullanıyoruz (no_std uyumluluğu için)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -23,37 +27,96 @@ pub enum SeekFrom {
     Current(i64),
 }
 
+// Blok aygıtı işlemlerinin hata türü.
+// std ortamında IoError std::io::Error'ı, no_std ortamında SahneError'ı sarar.
+#[derive(Debug)]
+pub enum BlockDeviceError {
+    #[cfg(feature = "std")]
+    IoError(std::io::Error),
+    #[cfg(not(feature = "std"))]
+    IoError(SahneError),
+    BlockSizeError(String), // Tampon boyutu blok boyutuyla uyuşmuyor
+    InvalidParameter(String), // Örneğin blok numarası aygıt dışında
+    NotSupported(String),
+    TimedOut,
+    DeviceNotFound(String),
+    PermissionDenied(String),
+    DeviceError(String), // Aygıtın bildirdiği diğer hatalar
+    WriteProtected(String),
+}
+
+impl fmt::Display for BlockDeviceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockDeviceError::IoError(e) => write!(f, "I/O error: {:?}", e),
+            BlockDeviceError::BlockSizeError(msg) => write!(f, "Block size error: {}", msg),
+            BlockDeviceError::InvalidParameter(msg) => write!(f, "Invalid parameter: {}", msg),
+            BlockDeviceError::NotSupported(msg) => write!(f, "Not supported: {}", msg),
+            BlockDeviceError::TimedOut => write!(f, "Timed out"),
+            BlockDeviceError::DeviceNotFound(msg) => write!(f, "Device not found: {}", msg),
+            BlockDeviceError::PermissionDenied(msg) => write!(f, "Permission denied: {}", msg),
+            BlockDeviceError::DeviceError(msg) => write!(f, "Device error: {}", msg),
+            BlockDeviceError::WriteProtected(msg) => write!(f, "Write protected: {}", msg),
+        }
+    }
+}
+
+/// SahneError'ı BlockDeviceError'a çevirir (std ortamında io::Error içine sarılır).
+pub fn map_sahne_error_to_block_device_error(e: SahneError) -> BlockDeviceError {
+    #[cfg(feature = "std")]
+    {
+        BlockDeviceError::IoError(std::io::Error::new(std::io::ErrorKind::Other, format!("{:?}", e)))
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        BlockDeviceError::IoError(e)
+    }
+}
+
 // Blok aygıtı için temel arayüz
-// Bu trait, blok aygıtlarının ofset ve seek gibi beklenen özelliklerini tanımlar.
-// Ancak, alttaki Sahne64 API'sı bu özelliklerin tamamını doğrudan desteklemeyebilir.
+// Sürücüler (SATA, NVMe, SAS, UFS, USB, eMMC...) ve dosya sistemi katmanları bu trait üzerinden
+// blok numarasıyla adreslenen okuma/yazma yapar. Tampon uzunluğu her zaman blok boyutuna eşittir.
 pub trait BlockDevice {
-    /// Blok boyutunu döndürür.
-    fn block_size(&self) -> u64;
-
-    /// Blok aygıtından belirtilen ofsetten başlayarak belirtilen boyutta veri okur.
-    /// Okunan byte sayısını döner.
-    /// Sahne64 resource API'sında doğrudan offsetli okuma yoksa, bu metodun
-    /// doğru çalışması için Seek + Read kombinasyonu veya resource::control gibi
-    /// özel bir mekanizma gereklidir. Mevcut implementasyon bu eksikliği yansıtacaktır.
-    fn read(&mut self, offset: u64, buf: &mut [u8]) -> Result<usize, SahneError>;
-
-    /// Blok aygıtına belirtilen ofsetten başlayarak belirtilen boyutta veri yazar.
-    /// Yazılan byte sayısını döner.
-    /// Sahne64 resource API'sında doğrudan offsetli yazma yoksa, bu metodun
-    /// doğru çalışması için Seek + Write kombinasyonu veya resource::control gibi
-    /// özel bir mekanizma gereklidir. Mevcut implementasyon bu eksikliği yansıtacaktır.
-    fn write(&mut self, offset: u64, buf: &[u8]) -> Result<usize, SahneError>;
-
-    /// Blok aygıtının toplam boyutunu döndürür.
-    /// Sahne64 resource API'sında boyutu almak için bir syscall yoksa, bu
-    /// desteklenmiyor olarak işaretlenecektir.
-    fn size(&self) -> Result<u64, SahneError>;
-
-    /// Blok aygıtında belirtilen konuma (ofset) konumlanır.
-    /// Dönüş değeri yeni konumu belirtir.
-    /// Sahne64 resource API'sında seek için bir syscall yoksa, bu
-    /// desteklenmiyor olarak işaretlenecektir.
-    fn seek(&mut self, pos: SeekFrom) -> Result<u64, SahneError>;
+    /// `block_id` numaralı bloğu `buf`a okur.
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> Result<(), BlockDeviceError>;
+
+    /// `buf`u `block_id` numaralı bloğa yazar.
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> Result<(), BlockDeviceError>;
+
+    /// Blok boyutunu (bayt) döndürür.
+    fn block_size(&self) -> usize;
+
+    /// Aygıttaki blok sayısını döndürür.
+    fn block_count(&self) -> u64;
+
+    /// Aygıtın toplam boyutunu (bayt) döndürür.
+    fn size(&self) -> u64 {
+        self.block_count() * self.block_size() as u64
+    }
+
+    /// Bir blok aralığının artık kullanılmadığını aygıta bildirir (SSD/NVMe için TRIM/UNMAP/deallocate).
+    /// Discard desteklemeyen aygıtlar (örneğin dönen diskler) için varsayılan olarak hiçbir şey yapmaz.
+    fn discard_blocks(&mut self, start_block: u64, block_count: u64) -> Result<(), BlockDeviceError> {
+        let _ = (start_block, block_count);
+        Ok(())
+    }
+
+    /// Aygıtın geçici yazma önbelleğini kalıcı ortama boşaltır. Önbelleği olmayan aygıtlar
+    /// varsayılanı kullanır.
+    fn flush(&mut self) -> Result<(), BlockDeviceError> {
+        Ok(())
+    }
+}
+
+// Blok numarası ve tampon boyutu denetimi; blok aygıtlarının ortak ön koşulu.
+pub(crate) fn check_block_access(block_id: u64, len: usize, block_size: usize, block_count: u64) -> Result<(), BlockDeviceError> {
+    if block_id >= block_count {
+        return Err(BlockDeviceError::InvalidParameter(format!("Block ID {} is out of bounds. Total blocks: {}", block_id, block_count)));
+    }
+    if len != block_size {
+        return Err(BlockDeviceError::BlockSizeError(format!("Buffer size ({}) must match block size ({}).", len, block_size)));
+    }
+    Ok(())
 }
 
 // Bellek tabanlı blok aygıtı (std veya alloc gerektirir)
@@ -73,15 +136,9 @@ impl MemBlockDevice {
             block_size,
         }
     }
-}
-
-#[cfg(any(feature = "std", feature = "alloc"))]
-impl BlockDevice for MemBlockDevice {
-    fn block_size(&self) -> u64 {
-        self.block_size
-    }
 
-    fn read(&mut self, offset: u64, buf: &mut [u8]) -> Result<usize, SahneError> {
+    /// Bayt ofsetinden okur (blok sınırlarından bağımsız). Okunan byte sayısını döner.
+    pub fn read(&mut self, offset: u64, buf: &mut [u8]) -> Result<usize, SahneError> {
         let offset_usize = offset as usize;
         let len = buf.len();
 
@@ -94,7 +151,8 @@ impl BlockDevice for MemBlockDevice {
         Ok(read_len)
     }
 
-    fn write(&mut self, offset: u64, buf: &[u8]) -> Result<usize, SahneError> {
+    /// Bayt ofsetine yazar (blok sınırlarından bağımsız). Yazılan byte sayısını döner.
+    pub fn write(&mut self, offset: u64, buf: &[u8]) -> Result<usize, SahneError> {
         let offset_usize = offset as usize;
         let len = buf.len();
 
@@ -107,11 +165,7 @@ impl BlockDevice for MemBlockDevice {
         Ok(write_len)
     }
 
-    fn size(&self) -> Result<u64, SahneError> {
-        Ok(self.data.len() as u64)
-    }
-
-    fn seek(&mut self, pos: SeekFrom) -> Result<u64, SahneError> {
+    pub fn seek(&mut self, pos: SeekFrom) -> Result<u64, SahneError> {
         // Bellek içi aygıtta seek işleminin anlamı sınırlıdır, her zaman 0'a döner.
         // Gerçek bir implementasyonda current_pos alanını tutmak gerekebilir.
         // Bu trait'teki seek tanımı, alttaki Sahne64 API'sının yetenekleriyle
@@ -149,39 +203,84 @@ impl BlockDevice for MemBlockDevice {
 }
 
 
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl BlockDevice for MemBlockDevice {
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> Result<(), BlockDeviceError> {
+        check_block_access(block_id, buf.len(), self.block_size(), self.block_count())?;
+        let start = block_id as usize * buf.len();
+        buf.copy_from_slice(&self.data[start..start + buf.len()]);
+        Ok(())
+    }
+
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> Result<(), BlockDeviceError> {
+        check_block_access(block_id, buf.len(), self.block_size(), self.block_count())?;
+        let start = block_id as usize * buf.len();
+        self.data[start..start + buf.len()].copy_from_slice(buf);
+        Ok(())
+    }
+
+    fn block_size(&self) -> usize {
+        self.block_size as usize
+    }
+
+    fn block_count(&self) -> u64 {
+        self.data.len() as u64 / self.block_size
+    }
+
+    fn discard_blocks(&mut self, start_block: u64, block_count: u64) -> Result<(), BlockDeviceError> {
+        // Bellek içi aygıtta discard edilen alan sıfırlanır (TRIM sonrası okuma sıfır döner).
+        let start = min(start_block.saturating_mul(self.block_size) as usize, self.data.len());
+        let end = min(start.saturating_add(block_count.saturating_mul(self.block_size) as usize), self.data.len());
+        for byte in &mut self.data[start..end] {
+            *byte = 0;
+        }
+        Ok(())
+    }
+}
+
 // Dosya tabanlı blok aygıtı (HDD, SSD, vb.) - Sahne64'e özel implementasyon
 pub struct ResourceBlockDevice { // FileBlockDevice yerine ResourceBlockDevice
     handle: Handle, // Sahne64 kaynak Handle'ı
     block_size: u64,
+    block_count: u64, // Açılışta verilir; kaynak boyutu sorgulanamıyor (bkz. `size`)
     // Not: Sahne64 API'sında kaynağın boyutunu almak için doğrudan bir syscall yok gibi görünüyor.
     // Bu nedenle 'size' metodu doğru implemente edilemeyebilir.
     // Benzer şekilde, 'seek' metodu için de doğrudan bir syscall yok.
 }
 
 impl ResourceBlockDevice {
-    /// Belirtilen Sahne64 kaynağını blok aygıt olarak açar.
-    pub fn new(resource_id: &str, block_size: u64) -> Result<Self, SahneError> {
+    /// Belirtilen Sahne64 kaynağını `block_count` bloklu bir blok aygıt olarak açar. Kaynak
+    /// boyutu API'den alınamadığı için blok sayısını çağıran verir (örneğin bölüm tablosundan).
+    pub fn new(resource_id: &str, block_size: u64, block_count: u64) -> Result<Self, SahneError> {
+        if block_size == 0 || block_count == 0 {
+            return Err(SahneError::InvalidParameter);
+        }
         // Sahne64 resource::acquire fonksiyonunu kullanıyoruz
         let flags = resource::MODE_READ | resource::MODE_WRITE | resource::MODE_CREATE; // MODE_TRUNCATE isteğe bağlı
         let acquire_result = resource::acquire(resource_id, flags); // fs::open yerine resource::acquire
 
         match acquire_result {
-            Ok(handle) => Ok(ResourceBlockDevice { handle, block_size }), // fd yerine handle
+            Ok(handle) => Ok(ResourceBlockDevice { handle, block_size, block_count }), // fd yerine handle
             Err(e) => Err(e),
         }
     }
 
+    // Blok numarasını bayt ofsetine çevirir.
+    fn block_offset(&self, block_id: u64, len: usize) -> Result<u64, BlockDeviceError> {
+        check_block_access(block_id, len, self.block_size as usize, self.block_count)?;
+        block_id
+            .checked_mul(self.block_size)
+            .ok_or_else(|| BlockDeviceError::InvalidParameter(format!("Block ID {} is out of range", block_id)))
+    }
+
     /// Cihaz Handle'ını kapatır.
     pub fn close(&mut self) -> Result<(), SahneError> {
         resource::release(self.handle) // fs::close yerine resource::release
     }
 }
 
-impl BlockDevice for ResourceBlockDevice {
-    fn block_size(&self) -> u64 {
-        self.block_size
-    }
-
+// Bayt ofsetli erişim; blok arayüzü aşağıda bunların üzerine kurulur.
+impl ResourceBlockDevice {
     // --- DİKKAT: Sahne64 API Kısıtlaması ---
     // Sahne64 resource::read syscall'ı doğrudan offset parametresi almaz.
     // resource::read muhtemelen kaynağın mevcut konumundan okur/yazar.
@@ -190,7 +289,7 @@ impl BlockDevice for ResourceBlockDevice {
     // kaynağın mevcut konumundan (muhtemelen başlangıcından, API'ye bağlı) okur.
     // Gerçek bir blok aygıtı gibi çalışması için Sahne64 API'sında
     // offsetli okuma/yazma veya seek syscall'ı eklenmelidir.
-    fn read(&mut self, offset: u64, buf: &mut [u8]) -> Result<usize, SahneError> {
+    pub fn read(&mut self, offset: u64, buf: &mut [u8]) -> Result<usize, SahneError> {
         // TODO: Eğer Sahne64'te seek benzeri bir resource::control komutu varsa,
         // burada önce o komut çağrılarak offset ayarlanmalıdır:
         // resource::control(self.handle, resource::CONTROL_SEEK, offset)?;
@@ -213,7 +312,7 @@ impl BlockDevice for ResourceBlockDevice {
     // kaynağın mevcut konumundan (muhtemelen başlangıcından, API'ye bağlı) yazar.
     // Gerçek bir blok aygıtı gibi çalışması için Sahne64 API'sında
     // offsetli okuma/yazma veya seek syscall'ı eklenmelidir.
-    fn write(&mut self, offset: u64, buf: &[u8]) -> Result<usize, SahneError> {
+    pub fn write(&mut self, offset: u64, buf: &[u8]) -> Result<usize, SahneError> {
         // TODO: Eğer Sahne64'te seek benzeri bir resource::control komutu varsa,
         // burada önce o komut çağrılarak offset ayarlanmalıdır:
          resource::control(self.handle, resource::CONTROL_SEEK, offset)?;
@@ -230,7 +329,7 @@ impl BlockDevice for ResourceBlockDevice {
 
     // --- DİKKAT: Sahne64 API Kısıtlaması ---
     // Sahne64 API'sında kaynağın boyutunu almak için doğrudan bir syscall yok gibi.
-    fn size(&self) -> Result<u64, SahneError> {
+    pub fn size(&self) -> Result<u64, SahneError> {
         // TODO: Sahne64'te resource::control ile size almak mümkünse, implemente et.
         // Örneğin: resource::control(self.handle, resource::CONTROL_GET_SIZE, 0) gibi.
         Err(SahneError::NotSupported) // Veya uygun hata
@@ -238,7 +337,7 @@ impl BlockDevice for ResourceBlockDevice {
 
     // --- DİKKAT: Sahne64 API Kısıtlaması ---
     // Sahne64 API'sında seek işlevi için doğrudan bir syscall yok gibi.
-    fn seek(&mut self, pos: SeekFrom) -> Result<u64, SahneError> {
+    pub fn seek(&mut self, pos: SeekFrom) -> Result<u64, SahneError> {
         // TODO: Sahne64'te seek için bir resource::control komutu varsa, implemente et.
         // Örneğin: resource::control(self.handle, resource::CONTROL_SEEK, offset_value) gibi.
         println!("WARN: ResourceBlockDevice::seek henüz desteklenmiyor!"); // no_std print makrosu
@@ -246,6 +345,34 @@ impl BlockDevice for ResourceBlockDevice {
     }
 }
 
+impl BlockDevice for ResourceBlockDevice {
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> Result<(), BlockDeviceError> {
+        let offset = self.block_offset(block_id, buf.len())?;
+        let n = self.read(offset, buf).map_err(map_sahne_error_to_block_device_error)?;
+        if n != buf.len() {
+            return Err(BlockDeviceError::DeviceError(format!("Short read of block {} ({} of {} bytes)", block_id, n, buf.len())));
+        }
+        Ok(())
+    }
+
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> Result<(), BlockDeviceError> {
+        let offset = self.block_offset(block_id, buf.len())?;
+        let n = self.write(offset, buf).map_err(map_sahne_error_to_block_device_error)?;
+        if n != buf.len() {
+            return Err(BlockDeviceError::DeviceError(format!("Short write of block {} ({} of {} bytes)", block_id, n, buf.len())));
+        }
+        Ok(())
+    }
+
+    fn block_size(&self) -> usize {
+        self.block_size as usize
+    }
+
+    fn block_count(&self) -> u64 {
+        self.block_count
+    }
+}
+
 // Örnek kullanım (Bu fonksiyonun kendisi std veya alloc gerektirebilir,
 // ancak ResourceBlockDevice'ın kullanımı no_std uyumludur)
 // Gerçek bir Sahne64 uygulamasında entry point başka bir yerde olacaktır.
@@ -267,7 +394,7 @@ fn main() -> Result<(), SahneError> {
         let bytes_read_2 = mem_device.read(512, &mut mem_buf).unwrap();
         println!("MemBlockDevice (Offset 512): Okunan {} byte: {:?}", bytes_read_2, &mem_buf[..bytes_read_2]);
 
-        println!("MemBlockDevice boyutu: {:?}", mem_device.size().unwrap());
+        println!("MemBlockDevice boyutu: {:?}", mem_device.size());
          // Seek MemBlockDevice için tam olarak implemente edilmediğini unutmayın.
           let new_pos = mem_device.seek(SeekFrom::Start(256)).unwrap();
           println!("MemBlockDevice seek sonucu: {}", new_pos);
@@ -282,7 +409,7 @@ fn main() -> Result<(), SahneError> {
     let block_size = 512;
 
     // Cihazı aç
-    let mut resource_device = match ResourceBlockDevice::new(resource_id, block_size) {
+    let mut resource_device = match ResourceBlockDevice::new(resource_id, block_size, 2048) {
         Ok(dev) => dev,
         Err(e) => {
             eprintln!("ResourceBlockDevice açma hatası: {:?}", e);
@@ -1,63 +1,864 @@
 #![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
 
-// no_std ortamında print/println kullanabilmek için makroları içeri aktar
-// Eğer std feature'ı aktifse, Rust'ın kendi println!'i kullanılır.
-// Değilse, bizim tanımladığımız no_std uyumlu makrolar kullanılır.
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+// (aygıt listesi, yollar ve hata mesajları String/Vec kullanır)
 #[cfg_attr(not(feature = "std"), macro_use)]
-extern crate alloc; // Eğer MemBlockDevice gibi alloc kullanan yapılar config dosyasında da kullanılacaksa gerekebilir.
-                     // Ancak config dosyasının kendisi genellikle alloc kullanmaz.
-                     // Sadece print macro'su için alloc gerekebilir format_args! içinde.
-                     // Emin olmak için alloc crate'ini kullanıyoruz.
+extern crate alloc;
 
+// SADAK yapılandırması
+// Aygıt geometrisi, disk yerleşimi ve günlük kaydı ayarları derleme zamanı sabitleri değil,
+// bir TOML dosyasından (srcfiletoml.rs'deki TomlFile) okunan SadakConfig yapısıdır.
+// Biçimlendirici (Superblock::from_config), bağlama (mount_devices, Superblock::check_config)
+// ve günlükçü (Logger::from_config) bu yapıyı kullanır.
+//
+// Örnek dosya:
+//
+//   [log]
+//   level = "info"
+//   file = "/var/log/sadak.log"
+//   max_bytes = 1048576
+//   keep = 4
+//   [log.modules]
+//   vfs = "debug"
+//   archivefs = "off"
+//
+//   [defaults]                  # bütün aygıtlara uygulanır
+//   block_size = 4096
+//
+//   [device.disk0]
+//   path = "sahne://devices/disk0"
+//   total_blocks = 1048576
+//   mount_point = "/"
+//
+//   [device.backup]
+//   path = "sahne://devices/disk1"
+//   block_size = 1024
+//   total_blocks = 65536
+//   mount_point = "/backup"
+//   read_only = true
+//
+// Öncelik sırası: yerleşik varsayılanlar < [defaults] < [device.<ad>] < ortam değişkenleri.
+// Ortam değişkenleri SADAK_LOG_<ANAHTAR> (ör. SADAK_LOG_LEVEL, SADAK_LOG_FILTER="warn,vfs=debug")
+// ve SADAK_DEVICE_<AD>_<ANAHTAR> (ör. SADAK_DEVICE_DISK0_BLOCK_SIZE=1024) biçimindedir.
+// Sahne64 API'sı geleneksel /dev/sda yolları yerine kaynak URI'ları kullanır; aygıt yolları bu biçimdedir.
 
-// Donanım aygıtı yapılandırması
-// Sahne64 API'sı geleneksel /dev/sda yolları yerine kaynak URI'ları kullanır.
-// Bu nedenle, aygıt yolu Sahne64'e özgü bir kaynak tanımlayıcısı olmalıdır.
-pub const DEVICE_PATH: &str = "sahne://devices/disk0"; // Sahne64 kaynak tanımlayıcısı
-pub const BLOCK_SIZE: u32 = 4096; // Blok boyutu (bayt cinsinden)
-pub const TOTAL_BLOCKS: u64 = 1048576; // Toplam blok sayısı
+use crate::filetoml::TomlFile;
+use crate::inodetable::Inode;
+use crate::log::{LogFilter, RotationPolicy, DEFAULT_RING_CAPACITY};
+use crate::vfs::{map_vfs_error_to_fs_error, normalize_path, FileSystem, Vfs, VfsError, O_RDONLY};
+use crate::FileSystemError;
 
-// Dosya sistemi yapılandırması
-pub const SUPERBLOCK_LOCATION: u64 = 0; // Superblock'un blok numarası
-pub const INODE_TABLE_LOCATION: u64 = 1; // Inode tablosunun başlangıç blok numarası
-pub const DATA_BLOCKS_LOCATION: u64 = 1024; // Veri bloklarının başlangıç blok numarası
-pub const INODES_COUNT: u32 = 1024; // Inode sayısı
-pub const ROOT_INODE: u32 = 0; // Kök dizinin inode numarası
+use toml::value::Table;
+use toml::Value;
 
-// Boş alan yönetimi yapılandırması
-pub const FREE_SPACE_MAP_LOCATION: u64 = 2; // Boş alan haritasının başlangıç blok numarası
+use alloc::string::{String, ToString}; // Requires alloc
+use alloc::sync::Arc;
+use alloc::vec::Vec; // Requires alloc
+use alloc::format; // Requires alloc
 
-// Hata ayıklama yapılandırması
-pub const LOG_LEVEL: LogLevel = LogLevel::Debug; // Günlük kaydı seviyesi
+use core::fmt;
+use core::result::Result;
 
-// Günlük kaydı seviyesi enum'u
-#[derive(Debug, PartialEq)]
-pub enum LogLevel {
-    Error,
-    Warning,
-    Info,
-    Debug,
+// Günlük kaydı seviyesi: günlükçünün (srclog.rs) seviyesi kullanılır
+pub use crate::log::LogLevel;
+
+// Yerleşik varsayılanlar (dosyada ve ortamda verilmeyen ayarlar için)
+pub const DEFAULT_DEVICE_NAME: &str = "disk0"; // Dosyada hiç aygıt yoksa kullanılan aygıt
+pub const DEFAULT_DEVICE_PATH: &str = "sahne://devices/disk0"; // Sahne64 kaynak tanımlayıcısı
+pub const DEFAULT_BLOCK_SIZE: u32 = 4096; // Blok boyutu (bayt cinsinden)
+pub const DEFAULT_TOTAL_BLOCKS: u64 = 1048576; // Toplam blok sayısı
+pub const DEFAULT_INODES_COUNT: u32 = 1024; // Inode sayısı
+pub const DEFAULT_ROOT_INODE: u32 = 0; // Kök dizinin inode numarası
+pub const DEFAULT_SUPERBLOCK_LOCATION: u64 = 0; // Superblock'un blok numarası
+pub const DEFAULT_LOG_LEVEL: LogLevel = LogLevel::Debug; // Günlük kaydı seviyesi
+
+// Geçerli blok boyutu aralığı (ikinin kuvveti olmalı)
+pub const MIN_BLOCK_SIZE: u32 = 512;
+pub const MAX_BLOCK_SIZE: u32 = 65536;
+
+// Ortam değişkeni önekleri
+pub const ENV_LOG_PREFIX: &str = "SADAK_LOG_";
+pub const ENV_DEVICE_PREFIX: &str = "SADAK_DEVICE_";
+
+/// Yapılandırma hataları. Anahtarlar dosyadaki yoluyla ("device.disk0.block_size") ya da
+/// ortam değişkeninin adıyla verilir.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    Parse(String),                                           // TOML sözdizimi hatası
+    UnknownKey(String),                                      // Bilinmeyen anahtar veya bölüm
+    InvalidType { key: String, expected: &'static str },     // Değer beklenen türde değil
+    InvalidValue { key: String, message: String },           // Değer türü doğru ama geçersiz
+    UnknownDevice(String),                                   // Ortam değişkeni tanımsız bir aygıtı gösteriyor
+    Duplicate { what: &'static str, value: String },         // Aynı aygıt adı veya bağlama noktası
+    Overlap { device: String, first: &'static str, second: &'static str }, // Disk bölgeleri çakışıyor
+    OutOfDevice { device: String, region: &'static str },    // Bölge aygıtın sonunu aşıyor
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Parse(msg) => write!(f, "Yapılandırma ayrıştırılamadı: {}", msg),
+            ConfigError::UnknownKey(key) => write!(f, "Bilinmeyen yapılandırma anahtarı: {}", key),
+            ConfigError::InvalidType { key, expected } => write!(f, "{}: {} bekleniyordu", key, expected),
+            ConfigError::InvalidValue { key, message } => write!(f, "{}: {}", key, message),
+            ConfigError::UnknownDevice(name) => write!(f, "Tanımsız aygıt: {}", name),
+            ConfigError::Duplicate { what, value } => write!(f, "Yinelenen {}: {}", what, value),
+            ConfigError::Overlap { device, first, second } => {
+                write!(f, "{}: {} ile {} bölgeleri çakışıyor", device, first, second)
+            }
+            ConfigError::OutOfDevice { device, region } => write!(f, "{}: {} bölgesi aygıtın dışına taşıyor", device, region),
+        }
+    }
+}
+
+// Helper function to map ConfigError to FileSystemError
+pub fn map_config_error_to_fs_error(e: ConfigError) -> FileSystemError {
+    FileSystemError::InvalidData(format!("{}", e))
+}
+
+fn invalid_type(key: &str, expected: &'static str) -> ConfigError {
+    ConfigError::InvalidType { key: key.to_string(), expected }
+}
+
+fn invalid_value(key: &str, message: &str) -> ConfigError {
+    ConfigError::InvalidValue { key: key.to_string(), message: message.to_string() }
+}
+
+// Bir ayarın kaynağı: TOML değeri veya ortam değişkeninin metni.
+// Ortamdan gelen metin, anahtarın beklediği türe göre ayrıştırılır.
+#[derive(Clone, Copy)]
+enum RawValue<'a> {
+    Toml(&'a Value),
+    Env(&'a str),
+}
+
+impl<'a> RawValue<'a> {
+    fn as_u64(&self, key: &str) -> Result<u64, ConfigError> {
+        match self {
+            RawValue::Toml(value) => value
+                .as_integer()
+                .and_then(|v| u64::try_from(v).ok())
+                .ok_or_else(|| invalid_type(key, "negatif olmayan tamsayı")),
+            RawValue::Env(text) => text.trim().parse().map_err(|_| invalid_type(key, "negatif olmayan tamsayı")),
+        }
+    }
+
+    fn as_u32(&self, key: &str) -> Result<u32, ConfigError> {
+        u32::try_from(self.as_u64(key)?).map_err(|_| invalid_value(key, "değer çok büyük"))
+    }
+
+    fn as_str(&self, key: &str) -> Result<&'a str, ConfigError> {
+        match self {
+            RawValue::Toml(value) => value.as_str().ok_or_else(|| invalid_type(key, "metin")),
+            RawValue::Env(text) => Ok(text),
+        }
+    }
+
+    fn as_bool(&self, key: &str) -> Result<bool, ConfigError> {
+        match self {
+            RawValue::Toml(value) => value.as_bool().ok_or_else(|| invalid_type(key, "mantıksal değer")),
+            RawValue::Env(text) => match text.trim().to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => Ok(true),
+                "0" | "false" | "no" | "off" => Ok(false),
+                _ => Err(invalid_type(key, "mantıksal değer")),
+            },
+        }
+    }
+
+    // Günlük seviyesi; "off" günlüğü kapatır
+    fn as_level(&self, key: &str) -> Result<Option<LogLevel>, ConfigError> {
+        let name = self.as_str(key)?;
+        if name.trim().eq_ignore_ascii_case("off") {
+            return Ok(None);
+        }
+        LogLevel::parse(name).map(Some).ok_or_else(|| invalid_type(key, "günlük seviyesi (debug, info, warning, error, off)"))
+    }
+}
+
+fn as_table<'a>(value: &'a Value, key: &str) -> Result<&'a Table, ConfigError> {
+    value.as_table().ok_or_else(|| invalid_type(key, "tablo"))
+}
+
+/// Bir aygıtın disk yerleşimi (blok numaraları). Süperblok bir blok kaplar; veri bölgesi
+/// aygıtın sonuna kadar uzanır.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskLayout {
+    pub superblock: u64,
+    pub free_space_map: u64,
+    pub free_space_map_blocks: u64,
+    pub inode_table: u64,
+    pub inode_table_blocks: u64,
+    pub data_blocks: u64,
+    pub data_blocks_count: u64,
+}
+
+/// Tek bir aygıtın ayarları (`[device.<ad>]` bölümü).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceConfig {
+    pub name: String,
+    pub path: String,
+    pub block_size: u32,
+    pub total_blocks: u64,
+    pub inodes_count: u32,
+    pub root_inode: u32,
+    pub superblock_location: u64,
+    // Verilmeyen bölgeler bir önceki bölgenin hemen arkasına yerleştirilir
+    pub free_space_map_location: Option<u64>,
+    pub inode_table_location: Option<u64>,
+    pub data_blocks_location: Option<u64>,
+    pub mount_point: Option<String>,
+    pub read_only: bool,
+}
+
+impl DeviceConfig {
+    /// Varsayılan geometriyle yeni bir aygıt; yolu "sahne://devices/<ad>" olur.
+    pub fn new(name: &str) -> Self {
+        DeviceConfig {
+            name: name.to_string(),
+            path: format!("sahne://devices/{}", name),
+            block_size: DEFAULT_BLOCK_SIZE,
+            total_blocks: DEFAULT_TOTAL_BLOCKS,
+            inodes_count: DEFAULT_INODES_COUNT,
+            root_inode: DEFAULT_ROOT_INODE,
+            superblock_location: DEFAULT_SUPERBLOCK_LOCATION,
+            free_space_map_location: None,
+            inode_table_location: None,
+            data_blocks_location: None,
+            mount_point: None,
+            read_only: false,
+        }
+    }
+
+    fn set(&mut self, key: &str, value: RawValue, full_key: &str) -> Result<(), ConfigError> {
+        match key {
+            "path" => self.path = value.as_str(full_key)?.to_string(),
+            "block_size" => self.block_size = value.as_u32(full_key)?,
+            "total_blocks" => self.total_blocks = value.as_u64(full_key)?,
+            "inodes_count" => self.inodes_count = value.as_u32(full_key)?,
+            "root_inode" => self.root_inode = value.as_u32(full_key)?,
+            "superblock_location" => self.superblock_location = value.as_u64(full_key)?,
+            "free_space_map_location" => self.free_space_map_location = Some(value.as_u64(full_key)?),
+            "inode_table_location" => self.inode_table_location = Some(value.as_u64(full_key)?),
+            "data_blocks_location" => self.data_blocks_location = Some(value.as_u64(full_key)?),
+            "mount_point" => self.mount_point = Some(value.as_str(full_key)?.to_string()),
+            "read_only" => self.read_only = value.as_bool(full_key)?,
+            _ => return Err(ConfigError::UnknownKey(full_key.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Blok bitmap'i, inode tablosu ve veri bölgesinin yerlerini hesaplar ve çakışmaları denetler.
+    pub fn layout(&self) -> Result<DiskLayout, ConfigError> {
+        let block_size = self.block_size as u64;
+        if block_size == 0 {
+            return Err(invalid_value(&self.key("block_size"), "blok boyutu sıfır olamaz"));
+        }
+        let free_space_map_blocks = self.total_blocks.div_ceil(block_size * 8);
+        let inode_table_bytes = self.inodes_count as u64 * Inode::size() as u64;
+        let inode_table_blocks = inode_table_bytes.div_ceil(block_size);
+
+        // Verilmeyen bölge bir öncekinin ardından başlar; konumlar ortam değişkeninden de gelebilir
+        let after = |start: u64, count: u64, region: &'static str| {
+            start.checked_add(count).ok_or_else(|| ConfigError::OutOfDevice { device: self.name.clone(), region })
+        };
+        let superblock = self.superblock_location;
+        let free_space_map = self.free_space_map_location.map_or_else(|| after(superblock, 1, "free_space_map"), Ok)?;
+        let inode_table =
+            self.inode_table_location.map_or_else(|| after(free_space_map, free_space_map_blocks, "inode_table"), Ok)?;
+        let data_blocks = self.data_blocks_location.map_or_else(|| after(inode_table, inode_table_blocks, "data_blocks"), Ok)?;
+
+        let regions: [(&'static str, u64, u64); 3] = [
+            ("superblock", superblock, 1),
+            ("free_space_map", free_space_map, free_space_map_blocks),
+            ("inode_table", inode_table, inode_table_blocks),
+        ];
+        for &(region, start, count) in regions.iter() {
+            if start.checked_add(count).is_none_or(|end| end > self.total_blocks) {
+                return Err(ConfigError::OutOfDevice { device: self.name.clone(), region });
+            }
+        }
+        if data_blocks >= self.total_blocks {
+            return Err(ConfigError::OutOfDevice { device: self.name.clone(), region: "data_blocks" });
+        }
+        let data_blocks_count = self.total_blocks - data_blocks;
+        let all: [(&'static str, u64, u64); 4] =
+            [regions[0], regions[1], regions[2], ("data_blocks", data_blocks, data_blocks_count)];
+        for (i, &(first, start_a, count_a)) in all.iter().enumerate() {
+            for &(second, start_b, count_b) in all[i + 1..].iter() {
+                if start_a < start_b + count_b && start_b < start_a + count_a {
+                    return Err(ConfigError::Overlap { device: self.name.clone(), first, second });
+                }
+            }
+        }
+
+        Ok(DiskLayout {
+            superblock,
+            free_space_map,
+            free_space_map_blocks,
+            inode_table,
+            inode_table_blocks,
+            data_blocks,
+            data_blocks_count,
+        })
+    }
+
+    /// Aygıt ayarlarını denetler (geometri, yerleşim ve bağlama noktası).
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.name.is_empty() || !self.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(invalid_value(&self.key("name"), "aygıt adı yalnızca harf, rakam, '_' ve '-' içerebilir"));
+        }
+        if self.path.is_empty() {
+            return Err(invalid_value(&self.key("path"), "aygıt yolu boş olamaz"));
+        }
+        if !self.block_size.is_power_of_two() || self.block_size < MIN_BLOCK_SIZE || self.block_size > MAX_BLOCK_SIZE {
+            return Err(invalid_value(
+                &self.key("block_size"),
+                &format!("blok boyutu {}..={} aralığında ikinin kuvveti olmalı", MIN_BLOCK_SIZE, MAX_BLOCK_SIZE),
+            ));
+        }
+        if self.inodes_count == 0 {
+            return Err(invalid_value(&self.key("inodes_count"), "en az bir inode gerekli"));
+        }
+        if self.root_inode >= self.inodes_count {
+            return Err(invalid_value(&self.key("root_inode"), "kök inode, inode sayısından küçük olmalı"));
+        }
+        if let Some(mount_point) = &self.mount_point {
+            if normalize_path(mount_point).is_err() {
+                return Err(invalid_value(&self.key("mount_point"), "bağlama noktası mutlak bir yol olmalı"));
+            }
+        }
+        self.layout().map(|_| ())
+    }
+
+    // Bağlama noktasının normalleştirilmiş biçimi ("/a/./b/" -> "/a/b")
+    fn normalized_mount_point(&self) -> Option<String> {
+        let components = normalize_path(self.mount_point.as_deref()?).ok()?;
+        let mut path = String::new();
+        for component in components.iter() {
+            path.push('/');
+            path.push_str(component);
+        }
+        if path.is_empty() {
+            path.push('/');
+        }
+        Some(path)
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("device.{}.{}", self.name, key)
+    }
+
+    // Ortam değişkenlerinde kullanılan ad ("disk-0" -> "DISK_0")
+    fn env_name(&self) -> String {
+        self.name.to_ascii_uppercase().replace('-', "_")
+    }
+}
+
+/// Günlük kaydı ayarları (`[log]` bölümü).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogConfig {
+    /// `level` ve `[log.modules]` ayarlarından oluşan modül süzgeci.
+    pub filter: LogFilter,
+    /// Dönüşümlü günlük dosyasının VFS yolu; yoksa dosyaya yazılmaz.
+    pub file: Option<String>,
+    pub rotation: RotationPolicy,
+    pub ring_capacity: usize,
+    pub ring_level: Option<LogLevel>,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig {
+            filter: LogFilter::new(Some(DEFAULT_LOG_LEVEL)),
+            file: None,
+            rotation: RotationPolicy::default(),
+            ring_capacity: DEFAULT_RING_CAPACITY,
+            ring_level: Some(LogLevel::Debug),
+        }
+    }
+}
+
+impl LogConfig {
+    fn set(&mut self, key: &str, value: RawValue, full_key: &str) -> Result<(), ConfigError> {
+        match key {
+            "level" => self.filter.set_default_level(value.as_level(full_key)?),
+            // Süzgecin tamamı tek metin olarak ("warn,vfs=debug"); önceki modül ayarlarının yerini alır
+            "filter" => {
+                self.filter = LogFilter::parse(value.as_str(full_key)?).map_err(|e| invalid_value(full_key, &format!("{}", e)))?
+            }
+            "modules" => {
+                let modules = match value {
+                    RawValue::Toml(value) => as_table(value, full_key)?,
+                    RawValue::Env(_) => return Err(invalid_type(full_key, "tablo")),
+                };
+                for (module, level) in modules.iter() {
+                    let level = RawValue::Toml(level).as_level(&format!("{}.{}", full_key, module))?;
+                    self.filter.set_module_level(module, level);
+                }
+            }
+            "file" => {
+                let file = value.as_str(full_key)?;
+                self.file = if file.is_empty() { None } else { Some(file.to_string()) };
+            }
+            // 0: sınır yok
+            "max_bytes" => self.rotation.max_bytes = Some(value.as_u64(full_key)?).filter(|&max| max > 0),
+            "max_records" => self.rotation.max_records = Some(value.as_u64(full_key)?).filter(|&max| max > 0),
+            "keep" => self.rotation.keep = value.as_u32(full_key)? as usize,
+            "ring_capacity" => self.ring_capacity = value.as_u32(full_key)? as usize,
+            "ring_level" => self.ring_level = value.as_level(full_key)?,
+            _ => return Err(ConfigError::UnknownKey(full_key.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Günlük dosyasının dizini ve adı ("/var/log/sadak.log" -> ("/var/log", "sadak.log")).
+    pub fn file_location(&self) -> Option<(String, String)> {
+        let file = self.file.as_deref()?;
+        let (dir, name) = file.rsplit_once('/')?;
+        let dir = if dir.is_empty() { "/" } else { dir };
+        Some((dir.to_string(), name.to_string()))
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if let Some(file) = &self.file {
+            let valid = normalize_path(file).is_ok() && !file.ends_with('/');
+            if !valid {
+                return Err(invalid_value("log.file", "günlük dosyası mutlak bir dosya yolu olmalı"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// SADAK yapılandırması: günlük ayarları ve aygıtlar (en az bir tane).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SadakConfig {
+    pub log: LogConfig,
+    pub devices: Vec<DeviceConfig>,
+}
+
+impl Default for SadakConfig {
+    /// Eski derleme zamanı sabitlerine karşılık gelen yapılandırma: tek aygıt (disk0).
+    fn default() -> Self {
+        SadakConfig { log: LogConfig::default(), devices: vec![DeviceConfig::new(DEFAULT_DEVICE_NAME)] }
+    }
+}
+
+impl SadakConfig {
+    /// Ayrıştırılmış bir TOML dosyasından yapılandırmayı kurar (denetlemeden).
+    /// `[defaults]` ayarları her aygıta, aygıtın kendi bölümünden önce uygulanır.
+    pub fn from_toml(file: &TomlFile) -> Result<Self, ConfigError> {
+        let root = as_table(&file.data, "<kök>")?;
+        let mut log = LogConfig::default();
+        let mut defaults: Option<&Table> = None;
+        let mut sections: Option<&Table> = None;
+        for (key, value) in root.iter() {
+            match key.as_str() {
+                "log" => {
+                    for (name, value) in as_table(value, "log")?.iter() {
+                        log.set(name, RawValue::Toml(value), &format!("log.{}", name))?;
+                    }
+                }
+                "defaults" => defaults = Some(as_table(value, "defaults")?),
+                "device" => sections = Some(as_table(value, "device")?),
+                _ => return Err(ConfigError::UnknownKey(key.clone())),
+            }
+        }
+
+        let mut names: Vec<String> = sections.map(|s| s.keys().cloned().collect()).unwrap_or_default();
+        if names.is_empty() {
+            names.push(DEFAULT_DEVICE_NAME.to_string());
+        }
+        let mut devices = Vec::with_capacity(names.len());
+        for name in names.iter() {
+            let mut device = DeviceConfig::new(name);
+            if let Some(defaults) = defaults {
+                for (key, value) in defaults.iter() {
+                    let full_key = format!("defaults.{}", key);
+                    if key == "path" || key == "mount_point" {
+                        return Err(invalid_value(&full_key, "aygıta özgü ayar [defaults] içinde kullanılamaz"));
+                    }
+                    device.set(key, RawValue::Toml(value), &full_key)?;
+                }
+            }
+            if let Some(section) = sections.and_then(|s| s.get(name)) {
+                for (key, value) in as_table(section, &format!("device.{}", name))?.iter() {
+                    device.set(key, RawValue::Toml(value), &device.key(key))?;
+                }
+            }
+            devices.push(device);
+        }
+        Ok(SadakConfig { log, devices })
+    }
+
+    /// TOML metnini ayrıştırır ve denetler (ortam değişkenleri uygulanmaz).
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let file = TomlFile::parse_str(text).map_err(|e| ConfigError::Parse(format!("{}", e)))?;
+        let config = SadakConfig::from_toml(&file)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Yapılandırma dosyasını VFS ad alanından okur ve denetler.
+    pub fn load_from_vfs(vfs: &Vfs, path: &str) -> Result<Self, FileSystemError> {
+        let mut file = vfs.open(path, O_RDONLY, 0).map_err(map_vfs_error_to_fs_error)?;
+        let size = file.metadata().map_err(map_vfs_error_to_fs_error)?.size as usize;
+        let mut data = vec![0u8; size];
+        let mut done = 0;
+        while done < data.len() {
+            let count = file.read_at(done as u64, &mut data[done..]).map_err(map_vfs_error_to_fs_error)?;
+            if count == 0 {
+                return Err(map_vfs_error_to_fs_error(VfsError::InvalidData(format!("{}: beklenmedik dosya sonu", path))));
+            }
+            done += count;
+        }
+        let text = String::from_utf8(data)
+            .map_err(|_| FileSystemError::InvalidData(format!("{}: geçersiz UTF-8 verisi", path)))?;
+        SadakConfig::parse(&text).map_err(map_config_error_to_fs_error)
+    }
+
+    /// Yapılandırma dosyasını açar, ortam değişkenlerini uygular ve denetler.
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self, FileSystemError> {
+        let file = crate::filetoml::open_toml_file(path)?;
+        let mut config = SadakConfig::from_toml(&file).map_err(map_config_error_to_fs_error)?;
+        config.apply_env().map_err(map_config_error_to_fs_error)?;
+        config.validate().map_err(map_config_error_to_fs_error)?;
+        Ok(config)
+    }
+
+    /// Yapılandırma dosyasını bir Sahne64 kaynağından okur ve denetler. no_std ortamında
+    /// ortam değişkeni yoktur; çekirdek komut satırı gibi kaynaklar `apply_overrides` ile uygulanır.
+    #[cfg(not(feature = "std"))]
+    pub fn load(path: &str) -> Result<Self, FileSystemError> {
+        let file = crate::filetoml::open_toml_file(path)?;
+        let config = SadakConfig::from_toml(&file).map_err(map_config_error_to_fs_error)?;
+        config.validate().map_err(map_config_error_to_fs_error)?;
+        Ok(config)
+    }
+
+    /// SADAK_LOG_* ve SADAK_DEVICE_* değişkenlerini uygular; diğer değişkenler yok sayılır.
+    /// Uygulanan değişken sayısını döndürür. Sonuç ayrıca `validate` ile denetlenmelidir.
+    pub fn apply_overrides<I, K, V>(&mut self, vars: I) -> Result<usize, ConfigError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut applied = 0;
+        for (name, value) in vars {
+            let (name, value) = (name.as_ref(), value.as_ref());
+            if let Some(key) = name.strip_prefix(ENV_LOG_PREFIX) {
+                self.log.set(&key.to_ascii_lowercase(), RawValue::Env(value), name)?;
+            } else if let Some(rest) = name.strip_prefix(ENV_DEVICE_PREFIX) {
+                // Aygıt adı da '_' içerebilir; en uzun eşleşen ad seçilir
+                let device = self
+                    .devices
+                    .iter_mut()
+                    .filter(|d| {
+                        let env_name = d.env_name();
+                        rest.len() > env_name.len() + 1 && rest.starts_with(&env_name) && rest.as_bytes()[env_name.len()] == b'_'
+                    })
+                    .max_by_key(|d| d.name.len())
+                    .ok_or_else(|| ConfigError::UnknownDevice(name.to_string()))?;
+                let key = rest[device.name.len() + 1..].to_ascii_lowercase();
+                device.set(&key, RawValue::Env(value), name)?;
+            } else {
+                continue;
+            }
+            applied += 1;
+        }
+        Ok(applied)
+    }
+
+    /// Süreç ortamındaki SADAK_* değişkenlerini uygular.
+    #[cfg(feature = "std")]
+    pub fn apply_env(&mut self) -> Result<usize, ConfigError> {
+        self.apply_overrides(std::env::vars())
+    }
+
+    /// Bütün yapılandırmayı denetler: aygıt geometrileri ve yerleşimleri, yinelenen aygıt
+    /// adları ve bağlama noktaları, günlük ayarları.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.devices.is_empty() {
+            return Err(invalid_value("device", "en az bir aygıt gerekli"));
+        }
+        for (i, device) in self.devices.iter().enumerate() {
+            device.validate()?;
+            let env_name = device.env_name();
+            if self.devices[..i].iter().any(|d| d.env_name() == env_name) {
+                return Err(ConfigError::Duplicate { what: "aygıt adı", value: device.name.clone() });
+            }
+            if let Some(mount_point) = device.normalized_mount_point() {
+                if self.devices[..i].iter().any(|d| d.normalized_mount_point().as_deref() == Some(mount_point.as_str())) {
+                    return Err(ConfigError::Duplicate { what: "bağlama noktası", value: mount_point });
+                }
+            }
+        }
+        self.log.validate()
+    }
+
+    pub fn device(&self, name: &str) -> Option<&DeviceConfig> {
+        self.devices.iter().find(|d| d.name == name)
+    }
+}
+
+/// Bağlama noktası olan aygıtları, üst bağlamalar önce olacak şekilde `vfs`ye bağlar.
+/// `open`, aygıtı açıp dosya sistemini döndürür (ör. süperbloğu okuyup
+/// `Superblock::check_config` ile geometriyi denetleyerek); `read_only` ayarlı aygıtlar için
+/// salt okunur bir dosya sistemi döndürmelidir. Eksik bağlama noktası dizinleri oluşturulur.
+/// Bağlanan aygıt sayısını döndürür.
+pub fn mount_devices<F>(vfs: &Vfs, config: &SadakConfig, mut open: F) -> Result<usize, FileSystemError>
+where
+    F: FnMut(&DeviceConfig, &DiskLayout) -> Result<Arc<dyn FileSystem>, FileSystemError>,
+{
+    let mut targets: Vec<(Vec<String>, String, &DeviceConfig)> = Vec::new();
+    for device in config.devices.iter() {
+        if let Some(mount_point) = device.normalized_mount_point() {
+            let components = normalize_path(&mount_point).map_err(map_vfs_error_to_fs_error)?;
+            targets.push((components, mount_point, device));
+        }
+    }
+    targets.sort_by_key(|(components, _, _)| components.len());
+
+    for (components, mount_point, device) in targets.iter() {
+        let layout = device.layout().map_err(map_config_error_to_fs_error)?;
+        if !components.is_empty() {
+            let mut path = String::new();
+            for component in components.iter() {
+                path.push('/');
+                path.push_str(component);
+                match vfs.metadata(&path) {
+                    Ok(_) => {}
+                    Err(VfsError::NotFound) => {
+                        vfs.mkdir(&path, 0o755).map_err(map_vfs_error_to_fs_error)?;
+                    }
+                    Err(e) => return Err(map_vfs_error_to_fs_error(e)),
+                }
+            }
+        }
+        let fs = open(device, &layout)?;
+        if device.read_only && !fs.is_read_only() {
+            return Err(FileSystemError::InvalidParameter(format!(
+                "{}: salt okunur olarak yapılandırılmış aygıt yazılabilir açıldı",
+                device.name
+            )));
+        }
+        vfs.mount(mount_point, fs).map_err(map_vfs_error_to_fs_error)?;
+    }
+    Ok(targets.len())
 }
 
 // Yapılandırma parametrelerini yazdırma işlevi
 // Bu fonksiyon, no_std ortamında da çalışan println! makromuzu kullanacaktır.
-pub fn print_config() {
-    // println! makrosu no_std ortamında tanımlı değilse, bizimkini kullanır.
-    println!("Donanım Aygıtı Yapılandırması:");
-    println!("  Aygıt Yolu: {}", DEVICE_PATH);
-    println!("  Blok Boyutu: {} bayt", BLOCK_SIZE);
-    println!("  Toplam Blok Sayısı: {}", TOTAL_BLOCKS);
-
-    println!("\nDosya Sistemi Yapılandırması:");
-    println!("  Superblock Konumu: Blok {}", SUPERBLOCK_LOCATION);
-    println!("  Inode Tablosu Konumu: Blok {}", INODE_TABLE_LOCATION);
-    println!("  Veri Blokları Konumu: Blok {}", DATA_BLOCKS_LOCATION);
-    println!("  Inode Sayısı: {}", INODES_COUNT);
-    println!("  Kök Inode: {}", ROOT_INODE);
-
-    println!("\nBoş Alan Yönetimi Yapılandırması:");
-    println!("  Boş Alan Haritası Konumu: Blok {}", FREE_SPACE_MAP_LOCATION);
-
-    println!("\nHata Ayıklama Yapılandırması:");
-    println!("  Günlük Kaydı Seviyesi: {:?}", LOG_LEVEL);
+pub fn print_config(config: &SadakConfig) {
+    for device in config.devices.iter() {
+        println!("Donanım Aygıtı Yapılandırması ({}):", device.name);
+        println!("  Aygıt Yolu: {}", device.path);
+        println!("  Blok Boyutu: {} bayt", device.block_size);
+        println!("  Toplam Blok Sayısı: {}", device.total_blocks);
+        match &device.mount_point {
+            Some(mount_point) => println!("  Bağlama Noktası: {}{}", mount_point, if device.read_only { " (salt okunur)" } else { "" }),
+            None => println!("  Bağlama Noktası: yok"),
+        }
+
+        println!("\nDosya Sistemi Yapılandırması:");
+        match device.layout() {
+            Ok(layout) => {
+                println!("  Superblock Konumu: Blok {}", layout.superblock);
+                println!("  Boş Alan Haritası Konumu: Blok {} ({} blok)", layout.free_space_map, layout.free_space_map_blocks);
+                println!("  Inode Tablosu Konumu: Blok {} ({} blok)", layout.inode_table, layout.inode_table_blocks);
+                println!("  Veri Blokları Konumu: Blok {} ({} blok)", layout.data_blocks, layout.data_blocks_count);
+            }
+            Err(e) => println!("  Geçersiz yerleşim: {}", e),
+        }
+        println!("  Inode Sayısı: {}", device.inodes_count);
+        println!("  Kök Inode: {}\n", device.root_inode);
+    }
+
+    println!("Hata Ayıklama Yapılandırması:");
+    println!("  Günlük Süzgeci: {}", config.log.filter);
+    if let Some(file) = &config.log.file {
+        println!("  Günlük Dosyası: {}", file);
+    }
 }
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+    use crate::log::Logger;
+    use crate::vfs::MemFs;
+
+    const EXAMPLE: &str = r#"
+[log]
+level = "info"
+file = "/var/log/sadak.log"
+max_bytes = 0
+keep = 2
+
+[log.modules]
+vfs = "debug"
+archivefs = "off"
+
+[defaults]
+block_size = 1024
+inodes_count = 256
+
+[device.disk0]
+total_blocks = 8192
+mount_point = "/"
+
+[device.backup]
+path = "sahne://devices/disk1"
+block_size = 512
+total_blocks = 4096
+mount_point = "/mnt/./backup/"
+read_only = true
+"#;
+
+    #[test]
+    fn test_parse_defaults_and_layout() {
+        let config = SadakConfig::parse(EXAMPLE).unwrap();
+        assert_eq!(config.devices.len(), 2);
+
+        let disk0 = config.device("disk0").unwrap();
+        assert_eq!(disk0.path, DEFAULT_DEVICE_PATH);
+        assert_eq!((disk0.block_size, disk0.total_blocks, disk0.inodes_count), (1024, 8192, 256));
+        let layout = disk0.layout().unwrap();
+        let inode_blocks = (256 * Inode::size() as u64).div_ceil(1024);
+        assert_eq!(layout.superblock, 0);
+        assert_eq!((layout.free_space_map, layout.free_space_map_blocks), (1, 1));
+        assert_eq!((layout.inode_table, layout.inode_table_blocks), (2, inode_blocks));
+        assert_eq!(layout.data_blocks, 2 + inode_blocks);
+        assert_eq!(layout.data_blocks_count, 8192 - 2 - inode_blocks);
+
+        let backup = config.device("backup").unwrap();
+        assert_eq!((backup.block_size, backup.inodes_count), (512, 256));
+        assert!(backup.read_only);
+        assert_eq!(backup.normalized_mount_point().as_deref(), Some("/mnt/backup"));
+
+        assert_eq!(config.log.filter.level_for("vfs::mount"), Some(LogLevel::Debug));
+        assert_eq!(config.log.filter.level_for("archivefs"), None);
+        assert_eq!(config.log.filter.level_for("raid"), Some(LogLevel::Info));
+        assert_eq!(config.log.rotation.max_bytes, None);
+        assert_eq!(config.log.rotation.keep, 2);
+        assert_eq!(config.log.file_location(), Some(("/var/log".to_string(), "sadak.log".to_string())));
+
+        // Dosya boşsa eski sabitlere karşılık gelen varsayılan yapılandırma
+        assert_eq!(SadakConfig::parse("").unwrap(), SadakConfig::default());
+
+        // Yazım hataları sessizce yok sayılmaz
+        assert_eq!(
+            SadakConfig::parse("[device.disk0]\nblok_size = 4096\n"),
+            Err(ConfigError::UnknownKey("device.disk0.blok_size".to_string()))
+        );
+        assert!(matches!(SadakConfig::parse("[log]\nlevel = 3\n"), Err(ConfigError::InvalidType { .. })));
+        assert!(matches!(SadakConfig::parse("[defaults]\npath = \"x\"\n"), Err(ConfigError::InvalidValue { .. })));
+        assert!(matches!(SadakConfig::parse("[device.disk0\n"), Err(ConfigError::Parse(_))));
+    }
+
+    #[test]
+    fn test_overrides_and_validation() {
+        let mut config = SadakConfig::parse(EXAMPLE).unwrap();
+        let vars = [
+            ("HOME", "/root"),
+            ("SADAK_LOG_LEVEL", "warning"),
+            ("SADAK_LOG_MAX_BYTES", "4096"),
+            ("SADAK_DEVICE_DISK0_BLOCK_SIZE", "2048"),
+            ("SADAK_DEVICE_BACKUP_READ_ONLY", "off"),
+        ];
+        assert_eq!(config.apply_overrides(vars.iter().copied()), Ok(4));
+        config.validate().unwrap();
+        assert_eq!(config.log.filter.level_for("raid"), Some(LogLevel::Warning));
+        assert_eq!(config.log.filter.level_for("vfs"), Some(LogLevel::Debug));
+        assert_eq!(config.log.rotation.max_bytes, Some(4096));
+        assert_eq!(config.device("disk0").unwrap().block_size, 2048);
+        assert!(!config.device("backup").unwrap().read_only);
+
+        assert_eq!(config.apply_overrides([("SADAK_LOG_FILTER", "error,raid=debug")]), Ok(1));
+        assert_eq!(config.log.filter.level_for("vfs"), Some(LogLevel::Error));
+        assert_eq!(config.log.filter.level_for("raid"), Some(LogLevel::Debug));
+
+        assert_eq!(
+            config.clone().apply_overrides([("SADAK_DEVICE_DISK9_BLOCK_SIZE", "512")]),
+            Err(ConfigError::UnknownDevice("SADAK_DEVICE_DISK9_BLOCK_SIZE".to_string()))
+        );
+        assert!(matches!(
+            config.clone().apply_overrides([("SADAK_DEVICE_DISK0_TOTAL_BLOCKS", "many")]),
+            Err(ConfigError::InvalidType { .. })
+        ));
+
+        let mut bad = config.clone();
+        bad.apply_overrides([("SADAK_DEVICE_DISK0_BLOCK_SIZE", "3000")]).unwrap();
+        assert!(matches!(bad.validate(), Err(ConfigError::InvalidValue { .. })));
+
+        let mut bad = config.clone();
+        bad.apply_overrides([("SADAK_DEVICE_DISK0_INODE_TABLE_LOCATION", "1")]).unwrap();
+        assert_eq!(
+            bad.validate(),
+            Err(ConfigError::Overlap { device: "disk0".to_string(), first: "free_space_map", second: "inode_table" })
+        );
+
+        let mut bad = config.clone();
+        bad.apply_overrides([("SADAK_DEVICE_BACKUP_TOTAL_BLOCKS", "3")]).unwrap();
+        assert!(matches!(bad.validate(), Err(ConfigError::OutOfDevice { .. })));
+
+        let mut bad = config.clone();
+        bad.apply_overrides([("SADAK_DEVICE_DISK0_SUPERBLOCK_LOCATION", &u64::MAX.to_string())]).unwrap();
+        assert_eq!(
+            bad.validate(),
+            Err(ConfigError::OutOfDevice { device: "disk0".to_string(), region: "free_space_map" })
+        );
+
+        let mut bad = config.clone();
+        bad.apply_overrides([("SADAK_DEVICE_BACKUP_MOUNT_POINT", "/")]).unwrap();
+        assert!(matches!(bad.validate(), Err(ConfigError::Duplicate { .. })));
+    }
+
+    #[test]
+    fn test_mount_devices_and_logger() {
+        let config = SadakConfig::parse(EXAMPLE).unwrap();
+        let vfs = Arc::new(Vfs::new());
+        let mut opened = Vec::new();
+        let count = mount_devices(&vfs, &config, |device, layout| {
+            opened.push((device.name.clone(), layout.data_blocks));
+            let fs = MemFs::new();
+            fs.set_read_only(device.read_only);
+            Ok(Arc::new(fs) as Arc<dyn FileSystem>)
+        })
+        .unwrap();
+        assert_eq!(count, 2);
+        // Kök önce bağlanır; /mnt/backup dizini kök dosya sisteminde oluşturulur
+        assert_eq!(opened[0].0, "disk0");
+        assert_eq!(opened[1].0, "backup");
+        assert_eq!(vfs.mount_points(), vec!["/".to_string(), "/mnt/backup".to_string()]);
+        assert_eq!(vfs.mkdir("/mnt/backup/x", 0o755).err(), Some(VfsError::ReadOnly));
+
+        // Salt okunur aygıtı yazılabilir açan bir açıcı reddedilir
+        let other = Vfs::new();
+        let result = mount_devices(&other, &config, |_, _| Ok(Arc::new(MemFs::new()) as Arc<dyn FileSystem>));
+        assert!(result.is_err());
+
+        vfs.mkdir("/var", 0o755).unwrap();
+        vfs.mkdir("/var/log", 0o755).unwrap();
+        let logger = Logger::from_config(&config.log, &vfs).unwrap();
+        logger.info("raid", "kept").unwrap();
+        logger.debug("raid", "filtered").unwrap();
+        logger.debug("vfs::mount", "kept too").unwrap();
+        logger.flush().unwrap();
+        let size = vfs.metadata("/var/log/sadak.log").unwrap().size;
+        let mut data = vec![0u8; size as usize];
+        vfs.open("/var/log/sadak.log", O_RDONLY, 0).unwrap().read_at(0, &mut data).unwrap();
+        let text = String::from_utf8(data).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.contains("kept too"));
+        assert_eq!(logger.recent().len(), 3);
+    }
+}
+
+// Removed redundant print module and panic handler boilerplate.
+// The empty lib module configuration is kept.
+#[cfg(not(any(feature = "std", test)))] // Only when not building std or test
+pub mod lib {} // Keep the empty lib module if needed for the crate structure
@@ -0,0 +1,766 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// Bu modül SADAK'ın ihtiyaç duyduğu kriptografik yapı taşlarını saf Rust ile sağlar:
+// AES-256 (blok şifre), AES-256-XTS (veri blokları), AES-256-CBC (dosya adları),
+// SHA-256, HMAC-SHA256, HKDF-SHA256 (anahtar türetme) ve CRC-32 (bütünlük sağlaması,
+// kriptografik değildir).
+// Harici bir crate'e bağımlı değildir, no_std ortamında çalışır.
+// UYARI: Bu implementasyon tablo tabanlıdır ve sabit zamanlı (constant-time) değildir.
+
+use alloc::vec::Vec;
+
+use core::result::Result;
+
+/// AES blok boyutu (bayt).
+pub const AES_BLOCK_SIZE: usize = 16;
+/// AES-256 anahtar boyutu (bayt).
+pub const AES256_KEY_SIZE: usize = 32;
+/// AES-256-XTS anahtar boyutu (iki adet AES-256 anahtarı).
+pub const AES256_XTS_KEY_SIZE: usize = 64;
+/// SHA-256 özet boyutu (bayt).
+pub const SHA256_DIGEST_SIZE: usize = 32;
+
+const AES256_ROUNDS: usize = 14;
+
+// AES S-box
+const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+// AES ters S-box
+const INV_SBOX: [u8; 256] = [
+    0x52, 0x09, 0x6a, 0xd5, 0x30, 0x36, 0xa5, 0x38, 0xbf, 0x40, 0xa3, 0x9e, 0x81, 0xf3, 0xd7, 0xfb,
+    0x7c, 0xe3, 0x39, 0x82, 0x9b, 0x2f, 0xff, 0x87, 0x34, 0x8e, 0x43, 0x44, 0xc4, 0xde, 0xe9, 0xcb,
+    0x54, 0x7b, 0x94, 0x32, 0xa6, 0xc2, 0x23, 0x3d, 0xee, 0x4c, 0x95, 0x0b, 0x42, 0xfa, 0xc3, 0x4e,
+    0x08, 0x2e, 0xa1, 0x66, 0x28, 0xd9, 0x24, 0xb2, 0x76, 0x5b, 0xa2, 0x49, 0x6d, 0x8b, 0xd1, 0x25,
+    0x72, 0xf8, 0xf6, 0x64, 0x86, 0x68, 0x98, 0x16, 0xd4, 0xa4, 0x5c, 0xcc, 0x5d, 0x65, 0xb6, 0x92,
+    0x6c, 0x70, 0x48, 0x50, 0xfd, 0xed, 0xb9, 0xda, 0x5e, 0x15, 0x46, 0x57, 0xa7, 0x8d, 0x9d, 0x84,
+    0x90, 0xd8, 0xab, 0x00, 0x8c, 0xbc, 0xd3, 0x0a, 0xf7, 0xe4, 0x58, 0x05, 0xb8, 0xb3, 0x45, 0x06,
+    0xd0, 0x2c, 0x1e, 0x8f, 0xca, 0x3f, 0x0f, 0x02, 0xc1, 0xaf, 0xbd, 0x03, 0x01, 0x13, 0x8a, 0x6b,
+    0x3a, 0x91, 0x11, 0x41, 0x4f, 0x67, 0xdc, 0xea, 0x97, 0xf2, 0xcf, 0xce, 0xf0, 0xb4, 0xe6, 0x73,
+    0x96, 0xac, 0x74, 0x22, 0xe7, 0xad, 0x35, 0x85, 0xe2, 0xf9, 0x37, 0xe8, 0x1c, 0x75, 0xdf, 0x6e,
+    0x47, 0xf1, 0x1a, 0x71, 0x1d, 0x29, 0xc5, 0x89, 0x6f, 0xb7, 0x62, 0x0e, 0xaa, 0x18, 0xbe, 0x1b,
+    0xfc, 0x56, 0x3e, 0x4b, 0xc6, 0xd2, 0x79, 0x20, 0x9a, 0xdb, 0xc0, 0xfe, 0x78, 0xcd, 0x5a, 0xf4,
+    0x1f, 0xdd, 0xa8, 0x33, 0x88, 0x07, 0xc7, 0x31, 0xb1, 0x12, 0x10, 0x59, 0x27, 0x80, 0xec, 0x5f,
+    0x60, 0x51, 0x7f, 0xa9, 0x19, 0xb5, 0x4a, 0x0d, 0x2d, 0xe5, 0x7a, 0x9f, 0x93, 0xc9, 0x9c, 0xef,
+    0xa0, 0xe0, 0x3b, 0x4d, 0xae, 0x2a, 0xf5, 0xb0, 0xc8, 0xeb, 0xbb, 0x3c, 0x83, 0x53, 0x99, 0x61,
+    0x17, 0x2b, 0x04, 0x7e, 0xba, 0x77, 0xd6, 0x26, 0xe1, 0x69, 0x14, 0x63, 0x55, 0x21, 0x0c, 0x7d,
+];
+
+// Anahtar genişletme için round sabitleri
+const RCON: [u8; 7] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40];
+
+/// Kriptografik işlemler sırasında oluşan hatalar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CryptoError {
+    /// Anahtar uzunluğu beklenen boyutta değil.
+    InvalidKeyLength,
+    /// Veri uzunluğu blok boyutunun katı değil veya çok kısa.
+    InvalidDataLength,
+    /// Dolgu (padding) veya kodlama geçersiz.
+    InvalidEncoding,
+}
+
+// GF(2^8) içinde x ile çarpma
+#[inline]
+fn xtime(b: u8) -> u8 {
+    (b << 1) ^ (((b >> 7) & 1) * 0x1b)
+}
+
+// GF(2^8) içinde genel çarpma (ters MixColumns için)
+#[inline]
+fn gmul(mut a: u8, mut b: u8) -> u8 {
+    let mut p = 0u8;
+    while b != 0 {
+        if b & 1 != 0 {
+            p ^= a;
+        }
+        a = xtime(a);
+        b >>= 1;
+    }
+    p
+}
+
+/// Genişletilmiş anahtarı tutan AES-256 blok şifresi.
+#[derive(Clone)]
+pub struct Aes256 {
+    round_keys: [[u8; 16]; AES256_ROUNDS + 1],
+}
+
+impl Aes256 {
+    /// Verilen 32 baytlık anahtardan yeni bir AES-256 örneği oluşturur.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: 32 baytlık AES-256 anahtarı.
+    ///
+    /// # Returns
+    ///
+    /// Genişletilmiş anahtarla hazırlanmış Aes256 veya CryptoError::InvalidKeyLength.
+    pub fn new(key: &[u8]) -> Result<Self, CryptoError> {
+        if key.len() != AES256_KEY_SIZE {
+            return Err(CryptoError::InvalidKeyLength);
+        }
+
+        // 60 kelimelik (4 bayt) anahtar programı
+        let mut w = [[0u8; 4]; 4 * (AES256_ROUNDS + 1)];
+        for i in 0..8 {
+            w[i].copy_from_slice(&key[4 * i..4 * i + 4]);
+        }
+        for i in 8..w.len() {
+            let mut temp = w[i - 1];
+            if i % 8 == 0 {
+                temp = [SBOX[temp[1] as usize], SBOX[temp[2] as usize], SBOX[temp[3] as usize], SBOX[temp[0] as usize]];
+                temp[0] ^= RCON[i / 8 - 1];
+            } else if i % 8 == 4 {
+                for b in temp.iter_mut() {
+                    *b = SBOX[*b as usize];
+                }
+            }
+            for j in 0..4 {
+                w[i][j] = w[i - 8][j] ^ temp[j];
+            }
+        }
+
+        let mut round_keys = [[0u8; 16]; AES256_ROUNDS + 1];
+        for (r, rk) in round_keys.iter_mut().enumerate() {
+            for c in 0..4 {
+                rk[4 * c..4 * c + 4].copy_from_slice(&w[4 * r + c]);
+            }
+        }
+
+        Ok(Aes256 { round_keys })
+    }
+
+    fn add_round_key(state: &mut [u8; 16], rk: &[u8; 16]) {
+        for i in 0..16 {
+            state[i] ^= rk[i];
+        }
+    }
+
+    fn shift_rows(s: &mut [u8; 16]) {
+        let t = *s;
+        for c in 0..4 {
+            for r in 0..4 {
+                s[4 * c + r] = t[4 * ((c + r) % 4) + r];
+            }
+        }
+    }
+
+    fn inv_shift_rows(s: &mut [u8; 16]) {
+        let t = *s;
+        for c in 0..4 {
+            for r in 0..4 {
+                s[4 * ((c + r) % 4) + r] = t[4 * c + r];
+            }
+        }
+    }
+
+    fn mix_columns(s: &mut [u8; 16]) {
+        for c in 0..4 {
+            let a = [s[4 * c], s[4 * c + 1], s[4 * c + 2], s[4 * c + 3]];
+            let all = a[0] ^ a[1] ^ a[2] ^ a[3];
+            for r in 0..4 {
+                s[4 * c + r] = a[r] ^ all ^ xtime(a[r] ^ a[(r + 1) % 4]);
+            }
+        }
+    }
+
+    fn inv_mix_columns(s: &mut [u8; 16]) {
+        for c in 0..4 {
+            let a = [s[4 * c], s[4 * c + 1], s[4 * c + 2], s[4 * c + 3]];
+            for r in 0..4 {
+                s[4 * c + r] = gmul(a[r], 14)
+                    ^ gmul(a[(r + 1) % 4], 11)
+                    ^ gmul(a[(r + 2) % 4], 13)
+                    ^ gmul(a[(r + 3) % 4], 9);
+            }
+        }
+    }
+
+    /// Tek bir 16 baytlık bloğu yerinde şifreler.
+    pub fn encrypt_block(&self, block: &mut [u8; 16]) {
+        Self::add_round_key(block, &self.round_keys[0]);
+        for round in 1..AES256_ROUNDS {
+            for b in block.iter_mut() {
+                *b = SBOX[*b as usize];
+            }
+            Self::shift_rows(block);
+            Self::mix_columns(block);
+            Self::add_round_key(block, &self.round_keys[round]);
+        }
+        for b in block.iter_mut() {
+            *b = SBOX[*b as usize];
+        }
+        Self::shift_rows(block);
+        Self::add_round_key(block, &self.round_keys[AES256_ROUNDS]);
+    }
+
+    /// Tek bir 16 baytlık bloğun şifresini yerinde çözer.
+    pub fn decrypt_block(&self, block: &mut [u8; 16]) {
+        Self::add_round_key(block, &self.round_keys[AES256_ROUNDS]);
+        for round in (1..AES256_ROUNDS).rev() {
+            Self::inv_shift_rows(block);
+            for b in block.iter_mut() {
+                *b = INV_SBOX[*b as usize];
+            }
+            Self::add_round_key(block, &self.round_keys[round]);
+            Self::inv_mix_columns(block);
+        }
+        Self::inv_shift_rows(block);
+        for b in block.iter_mut() {
+            *b = INV_SBOX[*b as usize];
+        }
+        Self::add_round_key(block, &self.round_keys[0]);
+    }
+}
+
+/// AES-256-XTS (IEEE 1619) veri birimi şifrelemesi.
+/// Her veri birimi (SADAK'ta bir dosya sistemi bloğu) kendi tweak değeriyle şifrelenir.
+/// Veri birimi uzunluğu 16'nın katı olmalıdır; ciphertext stealing desteklenmez
+/// çünkü blok boyutları her zaman 16'nın katıdır.
+#[derive(Clone)]
+pub struct Aes256Xts {
+    data_cipher: Aes256,
+    tweak_cipher: Aes256,
+}
+
+impl Aes256Xts {
+    /// 64 baytlık XTS anahtarından (K1 || K2) yeni bir örnek oluşturur.
+    pub fn new(key: &[u8]) -> Result<Self, CryptoError> {
+        if key.len() != AES256_XTS_KEY_SIZE {
+            return Err(CryptoError::InvalidKeyLength);
+        }
+        Ok(Aes256Xts {
+            data_cipher: Aes256::new(&key[..AES256_KEY_SIZE])?,
+            tweak_cipher: Aes256::new(&key[AES256_KEY_SIZE..])?,
+        })
+    }
+
+    // Tweak'i GF(2^128) içinde alfa (x) ile çarpar (küçük-endian bit sırası).
+    fn mul_alpha(tweak: &mut [u8; 16]) {
+        let mut carry = 0u8;
+        for b in tweak.iter_mut() {
+            let next_carry = *b >> 7;
+            *b = (*b << 1) | carry;
+            carry = next_carry;
+        }
+        if carry != 0 {
+            tweak[0] ^= 0x87;
+        }
+    }
+
+    fn initial_tweak(&self, data_unit: u64) -> [u8; 16] {
+        let mut tweak = [0u8; 16];
+        tweak[..8].copy_from_slice(&data_unit.to_le_bytes());
+        self.tweak_cipher.encrypt_block(&mut tweak);
+        tweak
+    }
+
+    fn process(&self, data_unit: u64, data: &mut [u8], encrypt: bool) -> Result<(), CryptoError> {
+        if data.is_empty() || data.len() % AES_BLOCK_SIZE != 0 {
+            return Err(CryptoError::InvalidDataLength);
+        }
+        let mut tweak = self.initial_tweak(data_unit);
+        for chunk in data.chunks_exact_mut(AES_BLOCK_SIZE) {
+            let mut block = [0u8; 16];
+            for i in 0..16 {
+                block[i] = chunk[i] ^ tweak[i];
+            }
+            if encrypt {
+                self.data_cipher.encrypt_block(&mut block);
+            } else {
+                self.data_cipher.decrypt_block(&mut block);
+            }
+            for i in 0..16 {
+                chunk[i] = block[i] ^ tweak[i];
+            }
+            Self::mul_alpha(&mut tweak);
+        }
+        Ok(())
+    }
+
+    /// Bir veri birimini yerinde şifreler.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_unit`: Veri birimi numarası (tweak). SADAK'ta dosya içindeki mantıksal blok numarasıdır.
+    /// * `data`: Şifrelenecek veri. Uzunluğu 16'nın katı olmalıdır.
+    pub fn encrypt_data_unit(&self, data_unit: u64, data: &mut [u8]) -> Result<(), CryptoError> {
+        self.process(data_unit, data, true)
+    }
+
+    /// Bir veri biriminin şifresini yerinde çözer.
+    pub fn decrypt_data_unit(&self, data_unit: u64, data: &mut [u8]) -> Result<(), CryptoError> {
+        self.process(data_unit, data, false)
+    }
+}
+
+/// AES-256-CBC, sıfır IV ile (dosya adı şifrelemesi için).
+/// Girdi uzunluğu 16'nın katı olmalıdır; dolgu çağıranın sorumluluğundadır.
+pub fn aes256_cbc_encrypt(cipher: &Aes256, data: &mut [u8]) -> Result<(), CryptoError> {
+    if data.len() % AES_BLOCK_SIZE != 0 {
+        return Err(CryptoError::InvalidDataLength);
+    }
+    let mut prev = [0u8; 16];
+    for chunk in data.chunks_exact_mut(AES_BLOCK_SIZE) {
+        let mut block = [0u8; 16];
+        for i in 0..16 {
+            block[i] = chunk[i] ^ prev[i];
+        }
+        cipher.encrypt_block(&mut block);
+        chunk.copy_from_slice(&block);
+        prev = block;
+    }
+    Ok(())
+}
+
+/// AES-256-CBC şifre çözme, sıfır IV ile.
+pub fn aes256_cbc_decrypt(cipher: &Aes256, data: &mut [u8]) -> Result<(), CryptoError> {
+    if data.len() % AES_BLOCK_SIZE != 0 {
+        return Err(CryptoError::InvalidDataLength);
+    }
+    let mut prev = [0u8; 16];
+    for chunk in data.chunks_exact_mut(AES_BLOCK_SIZE) {
+        let mut block = [0u8; 16];
+        block.copy_from_slice(chunk);
+        let saved = block;
+        cipher.decrypt_block(&mut block);
+        for i in 0..16 {
+            chunk[i] = block[i] ^ prev[i];
+        }
+        prev = saved;
+    }
+    Ok(())
+}
+
+// SHA-256 round sabitleri
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Artımlı (streaming) SHA-256 hesaplayıcısı.
+#[derive(Clone)]
+pub struct Sha256 {
+    state: [u32; 8],
+    buffer: [u8; 64],
+    buffer_len: usize,
+    total_len: u64,
+}
+
+impl Sha256 {
+    /// Yeni bir SHA-256 bağlamı oluşturur.
+    pub fn new() -> Self {
+        Sha256 {
+            state: [
+                0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+                0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+            ],
+            buffer: [0u8; 64],
+            buffer_len: 0,
+            total_len: 0,
+        }
+    }
+
+    fn compress(state: &mut [u32; 8], block: &[u8]) {
+        let mut w = [0u32; 64];
+        for i in 0..16 {
+            w[i] = u32::from_be_bytes([block[4 * i], block[4 * i + 1], block[4 * i + 2], block[4 * i + 3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(SHA256_K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        state[0] = state[0].wrapping_add(a);
+        state[1] = state[1].wrapping_add(b);
+        state[2] = state[2].wrapping_add(c);
+        state[3] = state[3].wrapping_add(d);
+        state[4] = state[4].wrapping_add(e);
+        state[5] = state[5].wrapping_add(f);
+        state[6] = state[6].wrapping_add(g);
+        state[7] = state[7].wrapping_add(h);
+    }
+
+    /// Özet hesabına veri ekler.
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total_len = self.total_len.wrapping_add(data.len() as u64);
+
+        if self.buffer_len > 0 {
+            let take = core::cmp::min(64 - self.buffer_len, data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+            if self.buffer_len == 64 {
+                let block = self.buffer;
+                Self::compress(&mut self.state, &block);
+                self.buffer_len = 0;
+            }
+        }
+
+        while data.len() >= 64 {
+            Self::compress(&mut self.state, &data[..64]);
+            data = &data[64..];
+        }
+
+        if !data.is_empty() {
+            self.buffer[..data.len()].copy_from_slice(data);
+            self.buffer_len = data.len();
+        }
+    }
+
+    /// Hesabı tamamlar ve 32 baytlık özeti döndürür.
+    pub fn finalize(mut self) -> [u8; SHA256_DIGEST_SIZE] {
+        let bit_len = self.total_len.wrapping_mul(8);
+        self.update(&[0x80]);
+        while self.buffer_len != 56 {
+            self.update(&[0x00]);
+        }
+        self.update(&bit_len.to_be_bytes());
+
+        let mut out = [0u8; SHA256_DIGEST_SIZE];
+        for (i, word) in self.state.iter().enumerate() {
+            out[4 * i..4 * i + 4].copy_from_slice(&word.to_be_bytes());
+        }
+        out
+    }
+
+    /// Tek seferde bir verinin SHA-256 özetini hesaplar.
+    pub fn digest(data: &[u8]) -> [u8; SHA256_DIGEST_SIZE] {
+        let mut hasher = Sha256::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+/// Artımlı HMAC-SHA256 hesaplayıcısı.
+#[derive(Clone)]
+pub struct HmacSha256 {
+    inner: Sha256,
+    outer_key_pad: [u8; 64],
+}
+
+impl HmacSha256 {
+    /// Verilen anahtarla yeni bir HMAC bağlamı oluşturur. Anahtar herhangi bir uzunlukta olabilir.
+    pub fn new(key: &[u8]) -> Self {
+        let mut block_key = [0u8; 64];
+        if key.len() > 64 {
+            block_key[..SHA256_DIGEST_SIZE].copy_from_slice(&Sha256::digest(key));
+        } else {
+            block_key[..key.len()].copy_from_slice(key);
+        }
+
+        let mut inner_key_pad = [0u8; 64];
+        let mut outer_key_pad = [0u8; 64];
+        for i in 0..64 {
+            inner_key_pad[i] = block_key[i] ^ 0x36;
+            outer_key_pad[i] = block_key[i] ^ 0x5c;
+        }
+
+        let mut inner = Sha256::new();
+        inner.update(&inner_key_pad);
+        HmacSha256 { inner, outer_key_pad }
+    }
+
+    /// MAC hesabına veri ekler.
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Hesabı tamamlar ve 32 baytlık MAC değerini döndürür.
+    pub fn finalize(self) -> [u8; SHA256_DIGEST_SIZE] {
+        let inner_hash = self.inner.finalize();
+        let mut outer = Sha256::new();
+        outer.update(&self.outer_key_pad);
+        outer.update(&inner_hash);
+        outer.finalize()
+    }
+
+    /// Tek seferde HMAC-SHA256 hesaplar.
+    pub fn mac(key: &[u8], data: &[u8]) -> [u8; SHA256_DIGEST_SIZE] {
+        let mut hmac = HmacSha256::new(key);
+        hmac.update(data);
+        hmac.finalize()
+    }
+}
+
+/// HKDF-SHA256 (RFC 5869): extract + expand.
+///
+/// # Arguments
+///
+/// * `salt`: İsteğe bağlı tuz değeri (boş olabilir).
+/// * `ikm`: Girdi anahtar materyali (ör. ana anahtar).
+/// * `info`: Bağlam bilgisi; farklı amaçlar için türetilen anahtarları ayırır.
+/// * `out`: Türetilen anahtarın yazılacağı arabellek (en fazla 255 * 32 bayt).
+pub fn hkdf_sha256(salt: &[u8], ikm: &[u8], info: &[u8], out: &mut [u8]) -> Result<(), CryptoError> {
+    if out.len() > 255 * SHA256_DIGEST_SIZE {
+        return Err(CryptoError::InvalidDataLength);
+    }
+
+    let prk = HmacSha256::mac(salt, ikm);
+
+    let mut previous: Vec<u8> = Vec::new(); // Requires alloc
+    let mut written = 0;
+    let mut counter: u8 = 1;
+    while written < out.len() {
+        let mut hmac = HmacSha256::new(&prk);
+        hmac.update(&previous);
+        hmac.update(info);
+        hmac.update(&[counter]);
+        let t = hmac.finalize();
+
+        let take = core::cmp::min(SHA256_DIGEST_SIZE, out.len() - written);
+        out[written..written + take].copy_from_slice(&t[..take]);
+        written += take;
+
+        previous.clear();
+        previous.extend_from_slice(&t);
+        counter = counter.wrapping_add(1);
+    }
+    Ok(())
+}
+
+/// İki bayt dizisini sabit zamanda karşılaştırır (MAC doğrulaması için).
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+    diff == 0
+}
+
+
+// CRC-32 (IEEE 802.3, yansıtılmış polinom 0xEDB88320) tablosu derleme zamanında üretilir.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Artımlı CRC-32 hesaplayıcısı (ZIP, GPT ve PNG ile aynı CRC).
+#[derive(Debug, Clone, Copy)]
+pub struct Crc32 {
+    state: u32,
+}
+
+impl Crc32 {
+    /// Yeni bir CRC-32 bağlamı oluşturur.
+    pub fn new() -> Self {
+        Crc32 { state: 0xFFFF_FFFF }
+    }
+
+    /// Veriyi hesaplamaya ekler.
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.state = CRC32_TABLE[((self.state ^ byte as u32) & 0xFF) as usize] ^ (self.state >> 8);
+        }
+    }
+
+    /// Sonuç CRC değerini döndürür.
+    pub fn finalize(self) -> u32 {
+        self.state ^ 0xFFFF_FFFF
+    }
+
+    /// Tek seferlik CRC-32 hesaplar.
+    pub fn checksum(data: &[u8]) -> u32 {
+        let mut crc = Crc32::new();
+        crc.update(data);
+        crc.finalize()
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_aes256_fips197_vector() {
+        // FIPS-197 Ek C.3
+        let key = hex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+        let cipher = Aes256::new(&key).unwrap();
+        let mut block = [0u8; 16];
+        block.copy_from_slice(&hex("00112233445566778899aabbccddeeff"));
+
+        cipher.encrypt_block(&mut block);
+        assert_eq!(block.to_vec(), hex("8ea2b7ca516745bfeafc49904b496089"));
+
+        cipher.decrypt_block(&mut block);
+        assert_eq!(block.to_vec(), hex("00112233445566778899aabbccddeeff"));
+    }
+
+    #[test]
+    fn test_aes256_xts_round_trip_and_tweak() {
+        let key: Vec<u8> = (0..64u8).collect();
+        let xts = Aes256Xts::new(&key).unwrap();
+
+        let plain = vec![0xA5u8; 4096];
+        let mut unit0 = plain.clone();
+        let mut unit1 = plain.clone();
+        xts.encrypt_data_unit(0, &mut unit0).unwrap();
+        xts.encrypt_data_unit(1, &mut unit1).unwrap();
+
+        // Aynı düz metin farklı veri birimlerinde farklı şifreli metin üretmeli
+        assert_ne!(unit0, plain);
+        assert_ne!(unit0, unit1);
+        // Aynı blok içindeki tekrar eden 16 baytlık parçalar da farklı olmalı
+        assert_ne!(unit0[..16], unit0[16..32]);
+
+        xts.decrypt_data_unit(0, &mut unit0).unwrap();
+        xts.decrypt_data_unit(1, &mut unit1).unwrap();
+        assert_eq!(unit0, plain);
+        assert_eq!(unit1, plain);
+
+        let mut odd = vec![0u8; 17];
+        assert_eq!(xts.encrypt_data_unit(0, &mut odd), Err(CryptoError::InvalidDataLength));
+        assert!(Aes256Xts::new(&key[..32]).is_err());
+    }
+
+    #[test]
+    fn test_aes256_xts_ieee1619_vector10() {
+        // IEEE 1619-2007 Ek B, XTS-AES-256 Vektör 10 (veri birimi 0xff, 512 bayt)
+        let mut key = hex("2718281828459045235360287471352662497757247093699959574966967627");
+        key.extend(hex("3141592653589793238462643383279502884197169399375105820974944592"));
+        let xts = Aes256Xts::new(&key).unwrap();
+
+        let plain: Vec<u8> = (0..512u32).map(|i| i as u8).collect();
+        let expected = hex(&[
+            "1c3b3a102f770386e4836c99e370cf9bea00803f5e482357a4ae12d414a3e63b",
+            "5d31e276f8fe4a8d66b317f9ac683f44680a86ac35adfc3345befecb4bb188fd",
+            "5776926c49a3095eb108fd1098baec70aaa66999a72a82f27d848b21d4a741b0",
+            "c5cd4d5fff9dac89aeba122961d03a757123e9870f8acf1000020887891429ca",
+            "2a3e7a7d7df7b10355165c8b9a6d0a7de8b062c4500dc4cd120c0f7418dae3d0",
+            "b5781c34803fa75421c790dfe1de1834f280d7667b327f6c8cd7557e12ac3a0f",
+            "93ec05c52e0493ef31a12d3d9260f79a289d6a379bc70c50841473d1a8cc81ec",
+            "583e9645e07b8d9670655ba5bbcfecc6dc3966380ad8fecb17b6ba02469a020a",
+            "84e18e8f84252070c13e9f1f289be54fbc481457778f616015e1327a02b140f1",
+            "505eb309326d68378f8374595c849d84f4c333ec4423885143cb47bd71c5edae",
+            "9be69a2ffeceb1bec9de244fbe15992b11b77c040f12bd8f6a975a44a0f90c29",
+            "a9abc3d4d893927284c58754cce294529f8614dcd2aba991925fedc4ae74ffac",
+            "6e333b93eb4aff0479da9a410e4450e0dd7ae4c6e2910900575da401fc07059f",
+            "645e8b7e9bfdef33943054ff84011493c27b3429eaedb4ed5376441a77ed4385",
+            "1ad77f16f541dfd269d50d6a5f14fb0aab1cbb4c1550be97f7ab4066193c4caa",
+            "773dad38014bd2092fa755c824bb5e54c4f36ffda9fcea70b9c6e693e148c151",
+        ]
+        .concat());
+
+        let mut data = plain.clone();
+        xts.encrypt_data_unit(0xff, &mut data).unwrap();
+        assert_eq!(data, expected);
+
+        xts.decrypt_data_unit(0xff, &mut data).unwrap();
+        assert_eq!(data, plain);
+    }
+
+    #[test]
+    fn test_sha256_and_hmac_vectors() {
+        assert_eq!(
+            Sha256::digest(b"abc").to_vec(),
+            hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(
+            Sha256::digest(b"").to_vec(),
+            hex("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        );
+        // Parça parça besleme tek seferlik özetle aynı olmalı
+        let data = vec![0x61u8; 1000];
+        let mut hasher = Sha256::new();
+        for chunk in data.chunks(37) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize(), Sha256::digest(&data));
+
+        // RFC 4231 Test Case 2
+        assert_eq!(
+            HmacSha256::mac(b"Jefe", b"what do ya want for nothing?").to_vec(),
+            hex("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+        );
+    }
+
+    #[test]
+    fn test_hkdf_sha256_rfc5869_case1() {
+        let ikm = vec![0x0bu8; 22];
+        let salt = hex("000102030405060708090a0b0c");
+        let info = hex("f0f1f2f3f4f5f6f7f8f9");
+        let mut okm = [0u8; 42];
+        hkdf_sha256(&salt, &ikm, &info, &mut okm).unwrap();
+        assert_eq!(
+            okm.to_vec(),
+            hex("3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865")
+        );
+    }
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(Crc32::checksum(b"123456789"), 0xCBF4_3926);
+        assert_eq!(Crc32::checksum(b""), 0);
+        let mut crc = Crc32::new();
+        crc.update(b"1234");
+        crc.update(b"56789");
+        assert_eq!(crc.finalize(), 0xCBF4_3926);
+    }
+}
@@ -0,0 +1,332 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// Blok düzeyinde paylaşım: reflink kopyaları ve çevrimdışı tekilleştirme (dedup).
+//
+// - `reflink_copy` hedef inode'u kaynak inode'un veri bloklarına yönlendirir ve her bloğun
+//   referans sayısını FreeSpaceManager içinde artırır. Veri kopyalanmaz.
+// - Paylaşılan bir bloğa yazılmadan önce `prepare_block_for_write` çağrılır; blok paylaşılıyorsa
+//   yeni bir blok ayrılır, içerik kopyalanır ve eski bloğun referansı bırakılır (copy-on-write).
+// - `dedup_pass` verilen inode'ların veri bloklarını SHA-256 ile özetler, içeriği birebir aynı
+//   olan blokları tek bir bloğa birleştirir ve boşalan blokları serbest bırakır.
+// - Referans sayıları bitmap ile birlikte boş alan meta verisine yazılır ve bağlanırken geri
+//   okunur (`FreeSpaceManager::raw_metadata` / `load_from_metadata`).
+//
+// Blok işaretçisi 0, "blok yok" anlamına gelir (Inode::new ile aynı kural).
+// Inode `#[repr(C, packed)]` olduğundan alanlarına referans alınmaz; diziler önce yerel bir
+// kopyaya alınır (`let blocks = { inode.direct_blocks };`).
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(not(feature = "std"))]
+use hashbrown::HashMap;
+
+use crate::FileSystemError; // Assuming FileSystemError is in crate
+use crate::blockdevice::{BlockDevice, BlockDeviceError}; // Assuming these are in crate::blockdevice
+use crate::crypto::{Sha256, SHA256_DIGEST_SIZE};
+use crate::freespacemanagement::FreeSpaceManager;
+use crate::inodetable::{Inode, InodeTable};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::format;
+
+use core::result::Result;
+
+// Helper function to map BlockDeviceError to FileSystemError
+fn map_block_device_error_to_fs_error(e: BlockDeviceError) -> FileSystemError {
+    FileSystemError::IOError(format!("Block device error: {:?}", e)) // Using Debug format for BlockDeviceError
+}
+
+/// Bir çevrimdışı tekilleştirme geçişinin sonucu.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DedupReport {
+    /// Okunup özetlenen veri bloğu işaretçisi sayısı.
+    pub blocks_scanned: u64,
+    /// Başka bir bloğa yönlendirilen (birleştirilen) işaretçi sayısı.
+    pub blocks_merged: u64,
+    /// Birleştirme sonucunda tamamen serbest kalan blok sayısı.
+    pub blocks_freed: u64,
+    /// Özeti eşleşen ama içeriği farklı çıkan blok sayısı (SHA-256 çakışması; pratikte 0).
+    pub hash_collisions: u64,
+}
+
+fn lookup_inode(table: &InodeTable, inode_number: usize) -> Result<&Inode, FileSystemError> {
+    table
+        .get_inode(inode_number)
+        .ok_or_else(|| FileSystemError::NotFound(format!("Inode {} not found.", inode_number)))
+}
+
+fn lookup_inode_mut(table: &mut InodeTable, inode_number: usize) -> Result<&mut Inode, FileSystemError> {
+    table
+        .get_inode_mut(inode_number)
+        .ok_or_else(|| FileSystemError::NotFound(format!("Inode {} not found.", inode_number)))
+}
+
+fn ensure_direct_only(inode: &Inode, inode_number: usize) -> Result<(), FileSystemError> {
+    if inode.indirect_block != 0 || inode.double_indirect_block != 0 {
+        // Dolaylı blok tabloları da paylaşım gerektirir; henüz desteklenmiyor.
+        return Err(FileSystemError::NotSupported(format!(
+            "Inode {} uses indirect blocks; block sharing currently supports direct blocks only.",
+            inode_number
+        )));
+    }
+    Ok(())
+}
+
+/// `src` inode'unun verisini `dst` inode'una blok kopyalamadan (copy-on-write) kopyalar.
+/// Hedefin mevcut veri blokları serbest bırakılır; boyut ve blok işaretçileri kaynaktan alınır.
+/// Sahiplik, izinler ve zaman damgaları hedefte olduğu gibi kalır.
+///
+/// # Arguments
+///
+/// * `table`: Inode tablosu.
+/// * `fsm`: Blok referans sayılarını tutan boş alan yöneticisi.
+/// * `src`: Kaynak inode numarası.
+/// * `dst`: Hedef inode numarası.
+///
+/// # Returns
+///
+/// Başarılıysa Ok(()), aksi halde FileSystemError.
+pub fn reflink_copy(
+    table: &mut InodeTable,
+    fsm: &mut FreeSpaceManager,
+    src: usize,
+    dst: usize,
+) -> Result<(), FileSystemError> {
+    if src == dst {
+        return Err(FileSystemError::InvalidParameter(String::from("Source and destination inodes are the same.")));
+    }
+
+    let source = *lookup_inode(table, src)?;
+    ensure_direct_only(&source, src)?;
+    let destination = *lookup_inode(table, dst)?;
+    ensure_direct_only(&destination, dst)?;
+
+    // Önce yeni referansları ekle; hata olursa eklenenleri geri al.
+    let source_blocks = { source.direct_blocks };
+    let mut added: Vec<usize> = Vec::new(); // Requires alloc
+    for &ptr in source_blocks.iter().filter(|p| **p != 0) {
+        match fsm.add_block_reference(ptr as usize) {
+            Ok(_) => added.push(ptr as usize),
+            Err(e) => {
+                for block in added {
+                    let _ = fsm.release_block(block);
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    // Hedefin eski bloklarını bırak
+    let destination_blocks = { destination.direct_blocks };
+    for &ptr in destination_blocks.iter().filter(|p| **p != 0) {
+        fsm.release_block(ptr as usize)?;
+    }
+
+    let inode = lookup_inode_mut(table, dst)?;
+    inode.direct_blocks = source_blocks;
+    inode.size = source.size;
+    inode.blocks = source.blocks;
+    Ok(())
+}
+
+/// Bir inode'un `index` numaralı doğrudan bloğuna yazmadan önce çağrılır.
+/// Blok paylaşılıyorsa özel bir kopya oluşturur ve inode'u ona yönlendirir.
+///
+/// # Returns
+///
+/// Yazmanın yapılacağı blok numarası (paylaşılmıyorsa mevcut blok).
+pub fn prepare_block_for_write<D: BlockDevice>(
+    table: &mut InodeTable,
+    fsm: &mut FreeSpaceManager,
+    device: &mut D,
+    inode_number: usize,
+    index: usize,
+) -> Result<u64, FileSystemError> {
+    let blocks = { lookup_inode(table, inode_number)?.direct_blocks };
+    let current = *blocks
+        .get(index)
+        .ok_or_else(|| FileSystemError::InvalidParameter(format!("Direct block index {} is out of range.", index)))?;
+    if current == 0 || !fsm.is_block_shared(current as usize)? {
+        return Ok(current);
+    }
+
+    let new_block = fsm.allocate_block()? as u64;
+    let mut buf = vec![0u8; device.block_size()]; // Requires alloc
+    let copy_result = device
+        .read_block(current, &mut buf)
+        .and_then(|_| device.write_block(new_block, &buf))
+        .map_err(map_block_device_error_to_fs_error);
+    if let Err(e) = copy_result {
+        let _ = fsm.release_block(new_block as usize);
+        return Err(e);
+    }
+
+    fsm.release_block(current as usize)?;
+    lookup_inode_mut(table, inode_number)?.direct_blocks[index] = new_block;
+    Ok(new_block)
+}
+
+/// Verilen inode'ların veri bloklarını çevrimdışı olarak tekilleştirir.
+/// Dosya sistemi bu sırada bağlı (mount) olmamalı veya bu inode'lar yazmaya kapalı olmalıdır.
+///
+/// # Arguments
+///
+/// * `table`: Inode tablosu.
+/// * `fsm`: Boş alan yöneticisi.
+/// * `device`: Veri bloklarının okunacağı blok aygıtı.
+/// * `inode_numbers`: Taranacak inode numaraları. Dolaylı blok kullanan inode'lar atlanır.
+///
+/// # Returns
+///
+/// Geçişin istatistiklerini içeren DedupReport.
+pub fn dedup_pass<D: BlockDevice>(
+    table: &mut InodeTable,
+    fsm: &mut FreeSpaceManager,
+    device: &mut D,
+    inode_numbers: &[usize],
+) -> Result<DedupReport, FileSystemError> {
+    let block_size = device.block_size();
+    let mut report = DedupReport::default();
+    let mut by_hash: HashMap<[u8; SHA256_DIGEST_SIZE], u64> = HashMap::new(); // Özet -> kanonik blok
+    let mut buf = vec![0u8; block_size]; // Requires alloc
+    let mut canonical_buf = vec![0u8; block_size]; // Requires alloc
+
+    for &inode_number in inode_numbers {
+        let inode = *lookup_inode(table, inode_number)?;
+        if ensure_direct_only(&inode, inode_number).is_err() {
+            continue;
+        }
+
+        let blocks = { inode.direct_blocks };
+        for (index, &ptr) in blocks.iter().enumerate() {
+            if ptr == 0 {
+                continue;
+            }
+            report.blocks_scanned += 1;
+
+            device.read_block(ptr, &mut buf).map_err(map_block_device_error_to_fs_error)?;
+            let digest = Sha256::digest(&buf);
+
+            let canonical = match by_hash.get(&digest) {
+                Some(&canonical) => canonical,
+                None => {
+                    by_hash.insert(digest, ptr);
+                    continue;
+                }
+            };
+            if canonical == ptr {
+                continue; // Zaten paylaşılıyor
+            }
+
+            // Özet eşleşmesi yeterli değil; içeriği birebir doğrula.
+            device.read_block(canonical, &mut canonical_buf).map_err(map_block_device_error_to_fs_error)?;
+            if canonical_buf != buf {
+                report.hash_collisions += 1;
+                continue;
+            }
+
+            fsm.add_block_reference(canonical as usize)?;
+            lookup_inode_mut(table, inode_number)?.direct_blocks[index] = canonical;
+            report.blocks_merged += 1;
+            if fsm.release_block(ptr as usize)? {
+                report.blocks_freed += 1;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+    use crate::testdevice::MemDevice;
+
+    // Bir inode'a verilen içerikte bloklar ayırıp yazar.
+    fn write_file(table: &mut InodeTable, fsm: &mut FreeSpaceManager, dev: &mut MemDevice, inode: usize, contents: &[u8]) {
+        for (i, byte) in contents.iter().enumerate() {
+            let block = fsm.allocate_block().unwrap() as u64;
+            dev.write_block(block, &vec![*byte; dev.block_size]).unwrap();
+            table.get_inode_mut(inode).unwrap().direct_blocks[i] = block;
+        }
+        let inode = table.get_inode_mut(inode).unwrap();
+        inode.size = (contents.len() * dev.block_size) as u64;
+        inode.blocks = contents.len() as u64;
+    }
+
+    fn setup() -> (InodeTable, FreeSpaceManager, MemDevice) {
+        let inodes = (0..4).map(|_| Inode::new(0o100644, 1000, 1000)).collect();
+        let mut fsm = FreeSpaceManager::new(64, 512);
+        fsm.allocate_block().unwrap(); // Blok 0 "blok yok" anlamına geldiği için ayrılmış tutulur
+        (InodeTable::new(inodes), fsm, MemDevice::new(64, 512))
+    }
+
+    #[test]
+    fn test_reflink_copy_shares_blocks_and_copy_on_write() -> Result<(), FileSystemError> {
+        let (mut table, mut fsm, mut dev) = setup();
+        write_file(&mut table, &mut fsm, &mut dev, 1, &[0xAA, 0xBB]);
+        let free_before = (0..64).filter(|b| fsm.is_block_free(*b).unwrap()).count();
+
+        reflink_copy(&mut table, &mut fsm, 1, 2)?;
+
+        // Hiç yeni blok ayrılmamalı; bloklar paylaşılmalı
+        assert_eq!((0..64).filter(|b| fsm.is_block_free(*b).unwrap()).count(), free_before);
+        let src_blocks = table.get_inode(1).unwrap().direct_blocks;
+        assert_eq!({ table.get_inode(2).unwrap().direct_blocks }, src_blocks);
+        assert_eq!({ table.get_inode(2).unwrap().size }, 1024);
+        assert_eq!(fsm.block_reference_count(src_blocks[0] as usize)?, 2);
+
+        // Kopyaya yazmak paylaşılan bloğu ayırmalı (copy-on-write)
+        let target = prepare_block_for_write(&mut table, &mut fsm, &mut dev, 2, 0)?;
+        assert_ne!(target, src_blocks[0]);
+        dev.write_block(target, &vec![0xCC; 512]).unwrap();
+
+        let mut buf = vec![0u8; 512];
+        dev.read_block(src_blocks[0], &mut buf).unwrap();
+        assert_eq!(buf, vec![0xAA; 512]); // Kaynak değişmedi
+        assert_eq!(fsm.block_reference_count(src_blocks[0] as usize)?, 1);
+        assert_eq!({ table.get_inode(2).unwrap().direct_blocks }[1], src_blocks[1]); // İkinci blok hâlâ paylaşılıyor
+
+        // Paylaşılmayan bloğa yazmak kopyalama yapmamalı
+        assert_eq!(prepare_block_for_write(&mut table, &mut fsm, &mut dev, 2, 0)?, target);
+        assert!(reflink_copy(&mut table, &mut fsm, 1, 1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_dedup_pass_merges_identical_blocks() -> Result<(), FileSystemError> {
+        let (mut table, mut fsm, mut dev) = setup();
+        write_file(&mut table, &mut fsm, &mut dev, 1, &[1, 2, 3]);
+        write_file(&mut table, &mut fsm, &mut dev, 2, &[3, 2, 9]);
+        write_file(&mut table, &mut fsm, &mut dev, 3, &[1, 1]);
+
+        let report = dedup_pass(&mut table, &mut fsm, &mut dev, &[1, 2, 3])?;
+        assert_eq!(report.blocks_scanned, 8);
+        assert_eq!(report.blocks_merged, 4); // 3, 2 (inode 2) ve 1, 1 (inode 3)
+        assert_eq!(report.blocks_freed, 4);
+        assert_eq!(report.hash_collisions, 0);
+
+        let f1 = table.get_inode(1).unwrap().direct_blocks;
+        let f2 = table.get_inode(2).unwrap().direct_blocks;
+        let f3 = table.get_inode(3).unwrap().direct_blocks;
+        assert_eq!(f2[0], f1[2]);
+        assert_eq!(f2[1], f1[1]);
+        assert_eq!(f3[0], f1[0]);
+        assert_eq!(f3[1], f1[0]);
+        assert_eq!(fsm.block_reference_count(f1[0] as usize)?, 3);
+
+        // İkinci geçiş hiçbir şey değiştirmemeli
+        let again = dedup_pass(&mut table, &mut fsm, &mut dev, &[1, 2, 3])?;
+        assert_eq!(again.blocks_merged, 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// TRIM/discard: boşalan blokların SSD/NVMe aygıtına bildirilmesi.
+//
+// - Çevrimiçi discard: FreeSpaceManager::set_online_discard(true) ile açılır. Serbest kalan
+//   bloklar FreeSpaceManager içinde kuyruğa alınır; `OnlineDiscard::after_free` kuyruk
+//   `batch_blocks` eşiğine ulaştığında bitişik aralıkları birleştirip aygıta tek seferde gönderir.
+// - Çevrimdışı discard (`fstrim`): bitmap'teki tüm boş aralıkları aygıta bildirir.
+//
+// Dosya sistemi blok numaraları aygıt blok numaralarına, blok boyutları oranı ile çevrilir
+// (dosya sistemi bloğu, aygıt bloğunun tam katı olmalıdır). Discard desteklemeyen aygıtlarda
+// BlockDevice::discard_blocks varsayılan olarak hiçbir şey yapmaz.
+
+use crate::FileSystemError; // Assuming FileSystemError is in crate
+use crate::blockdevice::{BlockDevice, BlockDeviceError}; // Assuming these are in crate::blockdevice
+use crate::freespacemanagement::FreeSpaceManager;
+
+use alloc::vec::Vec;
+use alloc::format;
+
+use core::result::Result;
+
+/// Varsayılan çevrimiçi discard toplu iş boyutu (blok).
+pub const DEFAULT_DISCARD_BATCH_BLOCKS: usize = 64;
+
+// Helper function to map BlockDeviceError to FileSystemError
+fn map_block_device_error_to_fs_error(e: BlockDeviceError) -> FileSystemError {
+    FileSystemError::IOError(format!("Block device error: {:?}", e)) // Using Debug format for BlockDeviceError
+}
+
+/// Bir discard işleminin (çevrimiçi toplu iş veya fstrim) sonucu.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrimReport {
+    /// Aygıta gönderilen aralık sayısı.
+    pub ranges_discarded: u64,
+    /// Discard edilen dosya sistemi bloğu sayısı.
+    pub blocks_discarded: u64,
+}
+
+// Dosya sistemi blok boyutunun kaç aygıt bloğuna karşılık geldiğini hesaplar.
+fn device_blocks_per_fs_block<D: BlockDevice>(fsm: &FreeSpaceManager, device: &D) -> Result<u64, FileSystemError> {
+    let fs_block_size = fsm.block_size();
+    let device_block_size = device.block_size();
+    if device_block_size == 0 || fs_block_size < device_block_size || fs_block_size % device_block_size != 0 {
+        return Err(FileSystemError::InvalidParameter(format!(
+            "Filesystem block size {} is not a multiple of device block size {}.",
+            fs_block_size, device_block_size
+        )));
+    }
+    Ok((fs_block_size / device_block_size) as u64)
+}
+
+// Verilen dosya sistemi aralıklarını aygıta gönderir.
+fn discard_ranges<D: BlockDevice>(
+    fsm: &FreeSpaceManager,
+    device: &mut D,
+    ranges: &[(usize, usize)],
+) -> Result<TrimReport, FileSystemError> {
+    let ratio = device_blocks_per_fs_block(fsm, device)?;
+    let mut report = TrimReport::default();
+    for &(start, count) in ranges {
+        device
+            .discard_blocks(start as u64 * ratio, count as u64 * ratio)
+            .map_err(map_block_device_error_to_fs_error)?;
+        report.ranges_discarded += 1;
+        report.blocks_discarded += count as u64;
+    }
+    Ok(report)
+}
+
+/// Çevrimiçi discard politikası: serbest bırakılan blokları toplu olarak aygıta gönderir.
+#[derive(Debug, Clone, Copy)]
+pub struct OnlineDiscard {
+    batch_blocks: usize,
+}
+
+impl OnlineDiscard {
+    /// Belirtilen toplu iş boyutuyla yeni bir politika oluşturur (en az 1 blok).
+    pub fn new(batch_blocks: usize) -> Self {
+        OnlineDiscard { batch_blocks: if batch_blocks == 0 { 1 } else { batch_blocks } }
+    }
+
+    /// Bir serbest bırakma işleminden sonra çağrılır. Kuyruk eşiğe ulaştıysa aygıta gönderir.
+    ///
+    /// # Returns
+    ///
+    /// Gönderim yapıldıysa Some(TrimReport), kuyruk henüz dolmadıysa None.
+    pub fn after_free<D: BlockDevice>(
+        &self,
+        fsm: &mut FreeSpaceManager,
+        device: &mut D,
+    ) -> Result<Option<TrimReport>, FileSystemError> {
+        if !fsm.online_discard_enabled() || fsm.pending_discard_count() < self.batch_blocks {
+            return Ok(None);
+        }
+        self.flush(fsm, device).map(Some)
+    }
+
+    /// Kuyruktaki tüm blokları eşiğe bakmadan aygıta gönderir (sync/unmount sırasında).
+    pub fn flush<D: BlockDevice>(&self, fsm: &mut FreeSpaceManager, device: &mut D) -> Result<TrimReport, FileSystemError> {
+        let ranges = fsm.take_pending_discards();
+        discard_ranges(fsm, device, &ranges)
+    }
+}
+
+impl Default for OnlineDiscard {
+    fn default() -> Self {
+        OnlineDiscard::new(DEFAULT_DISCARD_BATCH_BLOCKS)
+    }
+}
+
+/// Çevrimdışı discard: bitmap'teki en az `min_blocks` uzunluğundaki tüm boş aralıkları aygıta
+/// bildirir. Çevrimiçi discard kuyruğu da boşaltılır: kısa olduğu için atlanan aralıklardaki
+/// kuyruk blokları ayrıca gönderilir.
+///
+/// # Arguments
+///
+/// * `fsm`: Dosya sisteminin boş alan yöneticisi.
+/// * `device`: Discard komutlarının gönderileceği blok aygıtı.
+/// * `min_blocks`: Bundan kısa boş aralıklar atlanır (0 veya 1: tüm aralıklar).
+///
+/// # Returns
+///
+/// Gönderilen aralık ve blok sayılarını içeren TrimReport.
+pub fn fstrim<D: BlockDevice>(
+    fsm: &mut FreeSpaceManager,
+    device: &mut D,
+    min_blocks: usize,
+) -> Result<TrimReport, FileSystemError> {
+    let ranges = fsm.free_ranges(min_blocks);
+    let mut report = discard_ranges(fsm, device, &ranges)?;
+    // Kuyruk aralıkları boştur, yani tek bir boş aralığın içindedir; o aralık gönderilmediyse
+    // (min_blocks'tan kısa) kuyruktaki bloklar ayrıca gönderilir.
+    let pending: Vec<(usize, usize)> = fsm
+        .take_pending_discards()
+        .into_iter()
+        .filter(|&(start, count)| !ranges.iter().any(|&(s, c)| s <= start && start + count <= s + c))
+        .collect();
+    let queued = discard_ranges(fsm, device, &pending)?;
+    report.ranges_discarded += queued.ranges_discarded;
+    report.blocks_discarded += queued.blocks_discarded;
+    Ok(report)
+}
+
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    // Discard çağrılarını kaydeden sahte aygıt
+    struct RecordingDevice {
+        block_size: usize,
+        block_count: u64,
+        discards: Vec<(u64, u64)>,
+    }
+
+    impl BlockDevice for RecordingDevice {
+        fn read_block(&mut self, _block_id: u64, _buf: &mut [u8]) -> Result<(), BlockDeviceError> {
+            Ok(())
+        }
+        fn write_block(&mut self, _block_id: u64, _buf: &[u8]) -> Result<(), BlockDeviceError> {
+            Ok(())
+        }
+        fn block_size(&self) -> usize {
+            self.block_size
+        }
+        fn block_count(&self) -> u64 {
+            self.block_count
+        }
+        fn discard_blocks(&mut self, start_block: u64, count: u64) -> Result<(), BlockDeviceError> {
+            self.discards.push((start_block, count));
+            Ok(())
+        }
+    }
+
+    // Aygıtın discard'ı desteklemediği (varsayılan no-op) durum
+    struct PlainDevice;
+
+    impl BlockDevice for PlainDevice {
+        fn read_block(&mut self, _block_id: u64, _buf: &mut [u8]) -> Result<(), BlockDeviceError> {
+            Ok(())
+        }
+        fn write_block(&mut self, _block_id: u64, _buf: &[u8]) -> Result<(), BlockDeviceError> {
+            Ok(())
+        }
+        fn block_size(&self) -> usize {
+            4096
+        }
+        fn block_count(&self) -> u64 {
+            16
+        }
+    }
+
+    fn device(block_size: usize) -> RecordingDevice {
+        RecordingDevice { block_size, block_count: 1024, discards: Vec::new() }
+    }
+
+    #[test]
+    fn test_online_discard_batches_and_merges() -> Result<(), FileSystemError> {
+        let mut fsm = FreeSpaceManager::new(16, 4096);
+        let mut dev = device(4096);
+        for _ in 0..8 {
+            fsm.allocate_block()?;
+        }
+        fsm.set_online_discard(true);
+        let policy = OnlineDiscard::new(3);
+
+        fsm.deallocate_block(2)?;
+        fsm.deallocate_block(3)?;
+        assert_eq!(policy.after_free(&mut fsm, &mut dev)?, None); // Eşiğin altında
+        assert!(dev.discards.is_empty());
+
+        fsm.deallocate_block(6)?;
+        let report = policy.after_free(&mut fsm, &mut dev)?.unwrap();
+        assert_eq!(report, TrimReport { ranges_discarded: 2, blocks_discarded: 3 });
+        assert_eq!(dev.discards, vec![(2, 2), (6, 1)]);
+        assert_eq!(fsm.pending_discard_count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reallocated_blocks_are_not_discarded() -> Result<(), FileSystemError> {
+        let mut fsm = FreeSpaceManager::new(8, 4096);
+        let mut dev = device(4096);
+        for _ in 0..4 {
+            fsm.allocate_block()?;
+        }
+        fsm.set_online_discard(true);
+
+        fsm.deallocate_block(1)?;
+        fsm.deallocate_block(2)?;
+        assert_eq!(fsm.allocate_block()?, 1); // Blok 1 tekrar kullanılıyor
+
+        OnlineDiscard::default().flush(&mut fsm, &mut dev)?;
+        assert_eq!(dev.discards, vec![(2, 1)]);
+
+        // Paylaşılan bir bloğun referansını bırakmak discard üretmez
+        fsm.add_block_reference(3)?;
+        fsm.deallocate_block(3)?;
+        assert_eq!(fsm.pending_discard_count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fstrim_discards_free_ranges() -> Result<(), FileSystemError> {
+        let mut fsm = FreeSpaceManager::new(12, 4096);
+        for _ in 0..12 {
+            fsm.allocate_block()?;
+        }
+        for block in [0, 4, 5, 6, 9, 10, 11] {
+            fsm.deallocate_block(block)?;
+        }
+
+        // 512 baytlık aygıt bloklarıyla: her dosya sistemi bloğu 8 aygıt bloğu
+        let mut dev = device(512);
+        let report = fstrim(&mut fsm, &mut dev, 0)?;
+        assert_eq!(report, TrimReport { ranges_discarded: 3, blocks_discarded: 7 });
+        assert_eq!(dev.discards, vec![(0, 8), (32, 24), (72, 24)]);
+
+        // Kısa aralıklar atlanabilir
+        let mut dev = device(4096);
+        fstrim(&mut fsm, &mut dev, 2)?;
+        assert_eq!(dev.discards, vec![(4, 3), (9, 3)]);
+
+        // Aygıt bloğu dosya sistemi bloğundan büyükse hata
+        let mut big = device(8192);
+        assert!(fstrim(&mut fsm, &mut big, 0).is_err());
+
+        // Discard desteklemeyen aygıtta varsayılan no-op
+        fstrim(&mut fsm, &mut PlainDevice, 0)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_fstrim_sends_queued_blocks_in_short_runs() -> Result<(), FileSystemError> {
+        let mut fsm = FreeSpaceManager::new(12, 4096);
+        for _ in 0..12 {
+            fsm.allocate_block()?;
+        }
+        for block in [4, 5, 6] {
+            fsm.deallocate_block(block)?;
+        }
+        fsm.set_online_discard(true);
+        fsm.deallocate_block(9)?; // Kuyrukta, tek bloklu aralıkta
+        fsm.deallocate_block(7)?; // Kuyrukta, gönderilen 4..8 aralığında
+
+        let mut dev = device(4096);
+        let report = fstrim(&mut fsm, &mut dev, 2)?;
+        assert_eq!(dev.discards, vec![(4, 4), (9, 1)]);
+        assert_eq!(report, TrimReport { ranges_discarded: 2, blocks_discarded: 5 });
+        assert_eq!(fsm.pending_discard_count(), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,673 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// SADAK şeffaf dosya şifrelemesi (fscrypt benzeri).
+//
+// - Şifreleme politikası dizin bazında atanır ve o dizinde oluşturulan tüm
+//   dosya/alt dizinlere miras kalır.
+// - Dosya içerikleri AES-256-XTS ile, dosya içindeki mantıksal blok numarası tweak olarak
+//   kullanılarak şifrelenir.
+// - Dizin girdilerindeki dosya adları AES-256-CBC ile şifrelenip base64url ile kodlanır.
+// - Her inode'un kendine ait rastgele bir nonce'u vardır; dosya anahtarı ana anahtardan
+//   HKDF-SHA256(ana anahtar, nonce) ile türetilir. Ana anahtar diske asla yazılmaz,
+//   yalnızca süreç içi anahtarlıkta (Keyring) tutulur.
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(not(feature = "std"))]
+use hashbrown::HashMap;
+
+use crate::FileSystemError; // Assuming FileSystemError is in crate
+use crate::crypto::{
+    aes256_cbc_decrypt, aes256_cbc_encrypt, hkdf_sha256, Aes256, Aes256Xts, CryptoError,
+    AES256_KEY_SIZE, AES256_XTS_KEY_SIZE, AES_BLOCK_SIZE,
+};
+
+use spin::Mutex; // Mutex for concurrency control (from spin crate)
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::sync::Arc;
+use alloc::format;
+
+use core::result::Result;
+use core::fmt;
+
+/// Ana anahtar boyutu (bayt). fscrypt v2 ile aynı şekilde 64 bayt kullanılır.
+pub const MASTER_KEY_SIZE: usize = 64;
+/// Ana anahtar tanımlayıcısı boyutu (bayt).
+pub const KEY_IDENTIFIER_SIZE: usize = 16;
+/// Inode başına nonce boyutu (bayt).
+pub const FILE_NONCE_SIZE: usize = 16;
+/// Diske yazılan şifreleme bağlamının boyutu (bayt).
+pub const ENCRYPTION_CONTEXT_SIZE: usize = 4 + KEY_IDENTIFIER_SIZE + FILE_NONCE_SIZE;
+/// Şifreli bir dosya adının kodlanmış hali için izin verilen azami uzunluk.
+pub const MAX_ENCRYPTED_NAME_LEN: usize = 255;
+/// Dosya adları bu boyutun katlarına NUL ile doldurulur (uzunluk sızıntısını azaltır).
+const FILENAME_PADDING: usize = 16;
+
+// Şifreleme politikası sürümü
+const POLICY_VERSION: u8 = 2;
+
+// HKDF bağlam (info) önekleri; farklı amaçlar için türetilen anahtarları birbirinden ayırır.
+const HKDF_CONTEXT_KEY_IDENTIFIER: &[u8] = b"SADAK-fscrypt\x00key-identifier";
+const HKDF_CONTEXT_CONTENTS_KEY: &[u8] = b"SADAK-fscrypt\x00contents";
+const HKDF_CONTEXT_FILENAMES_KEY: &[u8] = b"SADAK-fscrypt\x00filenames";
+
+// Helper function to map CryptoError to FileSystemError
+fn map_crypto_error_to_fs_error(e: CryptoError) -> FileSystemError {
+    match e {
+        CryptoError::InvalidKeyLength => FileSystemError::EncryptionError(String::from("Invalid key length")),
+        CryptoError::InvalidDataLength => FileSystemError::EncryptionError(String::from("Data length is not a multiple of the AES block size")),
+        CryptoError::InvalidEncoding => FileSystemError::EncryptionError(String::from("Invalid encrypted data encoding")),
+    }
+}
+
+/// Dosya içerikleri için şifreleme modu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ContentsEncryptionMode {
+    Aes256Xts = 1,
+}
+
+/// Dosya adları için şifreleme modu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum FilenamesEncryptionMode {
+    Aes256Cbc = 4,
+}
+
+/// Ana anahtarı tanımlayan 16 baytlık değer (anahtarın kendisinden türetilir).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyIdentifier(pub [u8; KEY_IDENTIFIER_SIZE]);
+
+impl fmt::Display for KeyIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.0.iter() {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl KeyIdentifier {
+    /// Ham ana anahtardan tanımlayıcıyı hesaplar.
+    pub fn from_master_key(master_key: &[u8]) -> Result<Self, FileSystemError> {
+        let mut id = [0u8; KEY_IDENTIFIER_SIZE];
+        hkdf_sha256(&[], master_key, HKDF_CONTEXT_KEY_IDENTIFIER, &mut id).map_err(map_crypto_error_to_fs_error)?;
+        Ok(KeyIdentifier(id))
+    }
+}
+
+/// Bir dizine atanan şifreleme politikası.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncryptionPolicy {
+    pub version: u8,
+    pub contents_mode: ContentsEncryptionMode,
+    pub filenames_mode: FilenamesEncryptionMode,
+    pub flags: u8, // Şimdilik kullanılmıyor, 0 olmalı
+    pub master_key_identifier: KeyIdentifier,
+}
+
+impl EncryptionPolicy {
+    /// Varsayılan modlarla (AES-256-XTS / AES-256-CBC) yeni bir politika oluşturur.
+    pub fn new(master_key_identifier: KeyIdentifier) -> Self {
+        EncryptionPolicy {
+            version: POLICY_VERSION,
+            contents_mode: ContentsEncryptionMode::Aes256Xts,
+            filenames_mode: FilenamesEncryptionMode::Aes256Cbc,
+            flags: 0,
+            master_key_identifier,
+        }
+    }
+}
+
+/// Bir inode'a ait kalıcı şifreleme bağlamı: politika + inode'a özgü nonce.
+/// fscrypt'teki gibi bu yapı inode ile birlikte diske yazılır (ör. genişletilmiş öznitelik olarak).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncryptionContext {
+    pub policy: EncryptionPolicy,
+    pub nonce: [u8; FILE_NONCE_SIZE],
+}
+
+impl EncryptionContext {
+    /// Bağlamı disk üzerindeki biçimine dönüştürür.
+    pub fn to_bytes(&self) -> [u8; ENCRYPTION_CONTEXT_SIZE] {
+        let mut out = [0u8; ENCRYPTION_CONTEXT_SIZE];
+        out[0] = self.policy.version;
+        out[1] = self.policy.contents_mode as u8;
+        out[2] = self.policy.filenames_mode as u8;
+        out[3] = self.policy.flags;
+        out[4..4 + KEY_IDENTIFIER_SIZE].copy_from_slice(&self.policy.master_key_identifier.0);
+        out[4 + KEY_IDENTIFIER_SIZE..].copy_from_slice(&self.nonce);
+        out
+    }
+
+    /// Disk üzerindeki biçimden bağlamı ayrıştırır ve doğrular.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, FileSystemError> {
+        if data.len() != ENCRYPTION_CONTEXT_SIZE {
+            return Err(FileSystemError::EncryptionError(format!(
+                "Encryption context size mismatch. Expected {}, found {}.",
+                ENCRYPTION_CONTEXT_SIZE,
+                data.len()
+            )));
+        }
+        if data[0] != POLICY_VERSION {
+            return Err(FileSystemError::EncryptionError(format!("Unsupported encryption policy version {}", data[0])));
+        }
+        let contents_mode = match data[1] {
+            1 => ContentsEncryptionMode::Aes256Xts,
+            other => return Err(FileSystemError::EncryptionError(format!("Unsupported contents encryption mode {}", other))),
+        };
+        let filenames_mode = match data[2] {
+            4 => FilenamesEncryptionMode::Aes256Cbc,
+            other => return Err(FileSystemError::EncryptionError(format!("Unsupported filenames encryption mode {}", other))),
+        };
+
+        let mut key_id = [0u8; KEY_IDENTIFIER_SIZE];
+        key_id.copy_from_slice(&data[4..4 + KEY_IDENTIFIER_SIZE]);
+        let mut nonce = [0u8; FILE_NONCE_SIZE];
+        nonce.copy_from_slice(&data[4 + KEY_IDENTIFIER_SIZE..]);
+
+        Ok(EncryptionContext {
+            policy: EncryptionPolicy {
+                version: data[0],
+                contents_mode,
+                filenames_mode,
+                flags: data[3],
+                master_key_identifier: KeyIdentifier(key_id),
+            },
+            nonce,
+        })
+    }
+}
+
+/// Süreç içi anahtarlık. Ana anahtarlar yalnızca bellekte tutulur.
+pub struct Keyring {
+    keys: HashMap<KeyIdentifier, Vec<u8>>, // Requires alloc
+}
+
+impl Keyring {
+    /// Boş bir anahtarlık oluşturur.
+    pub fn new() -> Self {
+        Keyring { keys: HashMap::new() }
+    }
+
+    /// Bir ana anahtarı anahtarlığa ekler ve tanımlayıcısını döndürür.
+    ///
+    /// # Arguments
+    ///
+    /// * `master_key`: MASTER_KEY_SIZE uzunluğunda ham ana anahtar.
+    ///
+    /// # Returns
+    ///
+    /// Anahtarın tanımlayıcısı veya anahtar uzunluğu hatalıysa FileSystemError::EncryptionError.
+    pub fn add_key(&mut self, master_key: &[u8]) -> Result<KeyIdentifier, FileSystemError> {
+        if master_key.len() != MASTER_KEY_SIZE {
+            return Err(FileSystemError::EncryptionError(format!(
+                "Master key must be {} bytes, got {}.",
+                MASTER_KEY_SIZE,
+                master_key.len()
+            )));
+        }
+        let id = KeyIdentifier::from_master_key(master_key)?;
+        self.keys.insert(id, master_key.to_vec());
+        Ok(id)
+    }
+
+    /// Bir ana anahtarı anahtarlıktan kaldırır. Bellekteki kopya sıfırlanır.
+    /// Anahtar bulunamazsa false döner.
+    pub fn remove_key(&mut self, id: &KeyIdentifier) -> bool {
+        match self.keys.remove(id) {
+            Some(mut key) => {
+                for b in key.iter_mut() {
+                    // Derleyicinin sıfırlamayı optimize etmemesi için volatile yazma
+                    unsafe { core::ptr::write_volatile(b, 0) };
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Belirtilen anahtarın yüklü olup olmadığını kontrol eder.
+    pub fn has_key(&self, id: &KeyIdentifier) -> bool {
+        self.keys.contains_key(id)
+    }
+
+    fn get(&self, id: &KeyIdentifier) -> Result<&[u8], FileSystemError> {
+        self.keys
+            .get(id)
+            .map(|k| k.as_slice())
+            .ok_or_else(|| FileSystemError::KeyNotAvailable(format!("{}", id)))
+    }
+}
+
+/// Bir inode için türetilmiş anahtarlarla hazırlanmış şifreleyiciler.
+struct InodeCiphers {
+    contents: Aes256Xts,
+    filenames: Aes256,
+}
+
+impl InodeCiphers {
+    fn derive(master_key: &[u8], nonce: &[u8; FILE_NONCE_SIZE]) -> Result<Self, FileSystemError> {
+        let mut contents_key = [0u8; AES256_XTS_KEY_SIZE];
+        let mut info = Vec::with_capacity(HKDF_CONTEXT_CONTENTS_KEY.len() + FILE_NONCE_SIZE);
+        info.extend_from_slice(HKDF_CONTEXT_CONTENTS_KEY);
+        info.extend_from_slice(nonce);
+        hkdf_sha256(&[], master_key, &info, &mut contents_key).map_err(map_crypto_error_to_fs_error)?;
+
+        let mut filenames_key = [0u8; AES256_KEY_SIZE];
+        info.clear();
+        info.extend_from_slice(HKDF_CONTEXT_FILENAMES_KEY);
+        info.extend_from_slice(nonce);
+        hkdf_sha256(&[], master_key, &info, &mut filenames_key).map_err(map_crypto_error_to_fs_error)?;
+
+        let ciphers = InodeCiphers {
+            contents: Aes256Xts::new(&contents_key).map_err(map_crypto_error_to_fs_error)?,
+            filenames: Aes256::new(&filenames_key).map_err(map_crypto_error_to_fs_error)?,
+        };
+
+        contents_key.iter_mut().for_each(|b| *b = 0);
+        filenames_key.iter_mut().for_each(|b| *b = 0);
+        Ok(ciphers)
+    }
+}
+
+/// Inode bazında şifreleme bağlamlarını yöneten ve veri/dosya adı şifrelemesini yapan yapı.
+/// Dosya sistemi katmanı, şifreli inode'ların bloklarını diske yazmadan önce `encrypt_block`,
+/// diskten okuduktan sonra `decrypt_block` çağırır.
+pub struct EncryptionManager {
+    keyring: Arc<Mutex<Keyring>>, // Birden fazla bağlama paylaşılabilir anahtarlık
+    contexts: HashMap<u64, EncryptionContext>, // Inode numarası -> şifreleme bağlamı
+}
+
+impl EncryptionManager {
+    /// Verilen anahtarlığı kullanan yeni bir yönetici oluşturur.
+    pub fn new(keyring: Arc<Mutex<Keyring>>) -> Self {
+        EncryptionManager {
+            keyring,
+            contexts: HashMap::new(),
+        }
+    }
+
+    /// Paylaşılan anahtarlığı döndürür.
+    pub fn keyring(&self) -> Arc<Mutex<Keyring>> {
+        self.keyring.clone()
+    }
+
+    /// Bir dizine şifreleme politikası atar.
+    /// fscrypt'te olduğu gibi politika yalnızca boş bir dizine atanabilir; aynı politika
+    /// tekrar atanırsa işlem başarılı sayılır.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir_inode`: Dizinin inode numarası.
+    /// * `policy`: Atanacak politika. Anahtarı anahtarlıkta yüklü olmalıdır.
+    /// * `dir_is_empty`: Dizinin şu anda boş olup olmadığı (çağıran tarafından kontrol edilir).
+    /// * `nonce`: Dizin için çekirdeğin rastgele sayı kaynağından alınmış nonce.
+    pub fn set_directory_policy(
+        &mut self,
+        dir_inode: u64,
+        policy: EncryptionPolicy,
+        dir_is_empty: bool,
+        nonce: [u8; FILE_NONCE_SIZE],
+    ) -> Result<(), FileSystemError> {
+        if let Some(existing) = self.contexts.get(&dir_inode) {
+            if existing.policy == policy {
+                return Ok(());
+            }
+            return Err(FileSystemError::EncryptionError(format!(
+                "Directory inode {} already has a different encryption policy.",
+                dir_inode
+            )));
+        }
+        if !dir_is_empty {
+            return Err(FileSystemError::EncryptionError(format!(
+                "Encryption policy can only be set on an empty directory (inode {}).",
+                dir_inode
+            )));
+        }
+        if policy.flags != 0 {
+            return Err(FileSystemError::EncryptionError(format!("Unsupported policy flags 0x{:02x}", policy.flags)));
+        }
+        if !self.keyring.lock().has_key(&policy.master_key_identifier) {
+            return Err(FileSystemError::KeyNotAvailable(format!("{}", policy.master_key_identifier)));
+        }
+
+        self.contexts.insert(dir_inode, EncryptionContext { policy, nonce });
+        Ok(())
+    }
+
+    /// Şifreli bir dizinde yeni bir inode oluşturulduğunda çağrılır; üst dizinin politikası
+    /// yeni inode'a taze bir nonce ile miras bırakılır. Üst dizin şifreli değilse hiçbir şey yapmaz.
+    ///
+    /// # Returns
+    ///
+    /// Yeni inode şifreli ise Some(bağlam), değilse None.
+    pub fn inherit_context(
+        &mut self,
+        parent_inode: u64,
+        child_inode: u64,
+        nonce: [u8; FILE_NONCE_SIZE],
+    ) -> Result<Option<EncryptionContext>, FileSystemError> {
+        let parent = match self.contexts.get(&parent_inode) {
+            Some(ctx) => *ctx,
+            None => return Ok(None),
+        };
+        // Şifreli dizinde anahtar olmadan dosya oluşturulamaz (ad şifrelenemez).
+        if !self.keyring.lock().has_key(&parent.policy.master_key_identifier) {
+            return Err(FileSystemError::KeyNotAvailable(format!("{}", parent.policy.master_key_identifier)));
+        }
+        let ctx = EncryptionContext { policy: parent.policy, nonce };
+        self.contexts.insert(child_inode, ctx);
+        Ok(Some(ctx))
+    }
+
+    /// Diskten okunmuş bir bağlamı yöneticiye kaydeder (mount sırasında kullanılır).
+    pub fn load_context(&mut self, inode: u64, raw: &[u8]) -> Result<(), FileSystemError> {
+        let ctx = EncryptionContext::from_bytes(raw)?;
+        self.contexts.insert(inode, ctx);
+        Ok(())
+    }
+
+    /// Inode silindiğinde bağlamını kaldırır.
+    pub fn remove_context(&mut self, inode: u64) {
+        self.contexts.remove(&inode);
+    }
+
+    /// Inode'un şifreleme bağlamını döndürür.
+    pub fn get_context(&self, inode: u64) -> Option<&EncryptionContext> {
+        self.contexts.get(&inode)
+    }
+
+    /// Inode'un şifreli olup olmadığını döndürür.
+    pub fn is_encrypted(&self, inode: u64) -> bool {
+        self.contexts.contains_key(&inode)
+    }
+
+    /// Inode'un anahtarının şu anda yüklü olup olmadığını döndürür.
+    /// Şifresiz inode'lar için her zaman true döner.
+    pub fn is_key_available(&self, inode: u64) -> bool {
+        match self.contexts.get(&inode) {
+            Some(ctx) => self.keyring.lock().has_key(&ctx.policy.master_key_identifier),
+            None => true,
+        }
+    }
+
+    fn ciphers_for(&self, inode: u64) -> Result<Option<InodeCiphers>, FileSystemError> {
+        let ctx = match self.contexts.get(&inode) {
+            Some(ctx) => ctx,
+            None => return Ok(None),
+        };
+        let keyring = self.keyring.lock();
+        let master_key = keyring.get(&ctx.policy.master_key_identifier).map_err(|_| {
+            FileSystemError::KeyNotAvailable(format!(
+                "{} (inode {})",
+                ctx.policy.master_key_identifier, inode
+            ))
+        })?;
+        InodeCiphers::derive(master_key, &ctx.nonce).map(Some)
+    }
+
+    /// Bir veri bloğunu diske yazılmadan önce yerinde şifreler.
+    /// Inode şifreli değilse veriye dokunmaz.
+    ///
+    /// # Arguments
+    ///
+    /// * `inode`: Bloğun ait olduğu inode numarası.
+    /// * `logical_block`: Dosya içindeki mantıksal blok numarası (XTS tweak).
+    /// * `data`: Blok verisi. Uzunluğu 16'nın katı olmalıdır.
+    pub fn encrypt_block(&self, inode: u64, logical_block: u64, data: &mut [u8]) -> Result<(), FileSystemError> {
+        match self.ciphers_for(inode)? {
+            Some(ciphers) => ciphers
+                .contents
+                .encrypt_data_unit(logical_block, data)
+                .map_err(map_crypto_error_to_fs_error),
+            None => Ok(()),
+        }
+    }
+
+    /// Diskten okunan bir veri bloğunun şifresini yerinde çözer.
+    /// Anahtar yüklü değilse FileSystemError::KeyNotAvailable döner.
+    pub fn decrypt_block(&self, inode: u64, logical_block: u64, data: &mut [u8]) -> Result<(), FileSystemError> {
+        match self.ciphers_for(inode)? {
+            Some(ciphers) => ciphers
+                .contents
+                .decrypt_data_unit(logical_block, data)
+                .map_err(map_crypto_error_to_fs_error),
+            None => Ok(()),
+        }
+    }
+
+    /// Bir dizin girdisinin adını, dizinin anahtarıyla şifreleyip kodlar.
+    /// Dizin şifreli değilse ad olduğu gibi döner.
+    pub fn encrypt_filename(&self, dir_inode: u64, name: &str) -> Result<String, FileSystemError> {
+        let ciphers = match self.ciphers_for(dir_inode)? {
+            Some(ciphers) => ciphers,
+            None => return Ok(String::from(name)),
+        };
+
+        let raw = name.as_bytes();
+        if raw.is_empty() || raw.contains(&0) {
+            return Err(FileSystemError::EncryptionError(String::from("File name must be non-empty and must not contain NUL bytes.")));
+        }
+        let padded_len = core::cmp::max(AES_BLOCK_SIZE, (raw.len() + FILENAME_PADDING - 1) / FILENAME_PADDING * FILENAME_PADDING);
+        if base64url_encoded_len(padded_len) > MAX_ENCRYPTED_NAME_LEN {
+            return Err(FileSystemError::EncryptionError(format!("File name is too long to encrypt ({} bytes).", raw.len())));
+        }
+
+        let mut buf = vec![0u8; padded_len]; // Requires alloc
+        buf[..raw.len()].copy_from_slice(raw);
+        aes256_cbc_encrypt(&ciphers.filenames, &mut buf).map_err(map_crypto_error_to_fs_error)?;
+        Ok(base64url_encode(&buf))
+    }
+
+    /// Şifreli bir dizin girdisi adını çözer.
+    /// Anahtar yüklü değilse FileSystemError::KeyNotAvailable döner; çağıran bu durumda
+    /// kodlanmış adı olduğu gibi listeleyebilir.
+    pub fn decrypt_filename(&self, dir_inode: u64, encoded: &str) -> Result<String, FileSystemError> {
+        let ciphers = match self.ciphers_for(dir_inode)? {
+            Some(ciphers) => ciphers,
+            None => return Ok(String::from(encoded)),
+        };
+
+        let mut buf = base64url_decode(encoded).map_err(map_crypto_error_to_fs_error)?;
+        if buf.is_empty() || buf.len() % AES_BLOCK_SIZE != 0 {
+            return Err(map_crypto_error_to_fs_error(CryptoError::InvalidEncoding));
+        }
+        aes256_cbc_decrypt(&ciphers.filenames, &mut buf).map_err(map_crypto_error_to_fs_error)?;
+
+        let end = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+        if buf[end..].iter().any(|b| *b != 0) {
+            return Err(FileSystemError::EncryptionError(String::from("Decrypted file name has invalid padding (wrong key?).")));
+        }
+        buf.truncate(end);
+        String::from_utf8(buf)
+            .map_err(|_| FileSystemError::EncryptionError(String::from("Decrypted file name is not valid UTF-8 (wrong key?).")))
+    }
+}
+
+// --- base64url (dolgusuz) yardımcıları ---
+
+const BASE64URL_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+fn base64url_encoded_len(len: usize) -> usize {
+    (len * 4 + 2) / 3
+}
+
+fn base64url_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(base64url_encoded_len(data.len()));
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+        let chars = chunk.len() + 1;
+        for i in 0..chars {
+            out.push(BASE64URL_ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
+
+fn base64url_decode(s: &str) -> Result<Vec<u8>, CryptoError> {
+    let mut out = Vec::with_capacity(s.len() * 3 / 4);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for c in s.bytes() {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'-' => 62,
+            b'_' => 63,
+            _ => return Err(CryptoError::InvalidEncoding),
+        };
+        acc = (acc << 6) | v as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    if acc != 0 {
+        return Err(CryptoError::InvalidEncoding); // Artık bitler sıfır olmalı
+    }
+    Ok(out)
+}
+
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+
+    fn test_master_key(seed: u8) -> Vec<u8> {
+        (0..MASTER_KEY_SIZE as u8).map(|i| i.wrapping_mul(7).wrapping_add(seed)).collect()
+    }
+
+    fn setup() -> (EncryptionManager, KeyIdentifier) {
+        let keyring = Arc::new(Mutex::new(Keyring::new()));
+        let key_id = keyring.lock().add_key(&test_master_key(1)).unwrap();
+        let mut manager = EncryptionManager::new(keyring);
+        manager
+            .set_directory_policy(10, EncryptionPolicy::new(key_id), true, [0x11; FILE_NONCE_SIZE])
+            .unwrap();
+        (manager, key_id)
+    }
+
+    #[test]
+    fn test_context_serialization_round_trip() {
+        let ctx = EncryptionContext {
+            policy: EncryptionPolicy::new(KeyIdentifier([0xAB; KEY_IDENTIFIER_SIZE])),
+            nonce: [0x42; FILE_NONCE_SIZE],
+        };
+        let raw = ctx.to_bytes();
+        assert_eq!(EncryptionContext::from_bytes(&raw).unwrap(), ctx);
+
+        let mut bad = raw;
+        bad[1] = 9; // Bilinmeyen içerik modu
+        assert!(EncryptionContext::from_bytes(&bad).is_err());
+        assert!(EncryptionContext::from_bytes(&raw[..10]).is_err());
+    }
+
+    #[test]
+    fn test_file_contents_round_trip_and_per_file_keys() -> Result<(), FileSystemError> {
+        let (mut manager, _) = setup();
+        manager.inherit_context(10, 20, [0x01; FILE_NONCE_SIZE])?;
+        manager.inherit_context(10, 21, [0x02; FILE_NONCE_SIZE])?;
+
+        let plain = vec![0x5Au8; 4096];
+        let mut a = plain.clone();
+        let mut b = plain.clone();
+        manager.encrypt_block(20, 3, &mut a)?;
+        manager.encrypt_block(21, 3, &mut b)?;
+
+        // Farklı nonce -> farklı dosya anahtarı -> farklı şifreli metin
+        assert_ne!(a, plain);
+        assert_ne!(a, b);
+
+        manager.decrypt_block(20, 3, &mut a)?;
+        assert_eq!(a, plain);
+
+        // Şifresiz inode'un verisine dokunulmamalı
+        let mut c = plain.clone();
+        manager.encrypt_block(99, 0, &mut c)?;
+        assert_eq!(c, plain);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_without_key_fails_clearly() -> Result<(), FileSystemError> {
+        let (mut manager, key_id) = setup();
+        manager.inherit_context(10, 20, [0x01; FILE_NONCE_SIZE])?;
+
+        let mut data = vec![0u8; 512];
+        manager.encrypt_block(20, 0, &mut data)?;
+
+        assert!(manager.keyring().lock().remove_key(&key_id));
+        assert!(!manager.is_key_available(20));
+
+        match manager.decrypt_block(20, 0, &mut data) {
+            Err(FileSystemError::KeyNotAvailable(msg)) => {
+                assert!(msg.contains(&format!("{}", key_id)));
+                assert!(msg.contains("inode 20"));
+            }
+            other => panic!("Beklenenden farklı sonuç: {:?}", other),
+        }
+        // Anahtarsız dizinde yeni dosya oluşturulamaz
+        assert!(matches!(
+            manager.inherit_context(10, 30, [0x03; FILE_NONCE_SIZE]),
+            Err(FileSystemError::KeyNotAvailable(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_filename_encryption() -> Result<(), FileSystemError> {
+        let (manager, _) = setup();
+
+        let encrypted = manager.encrypt_filename(10, "rapor.docx")?;
+        assert_ne!(encrypted, "rapor.docx");
+        assert!(encrypted.bytes().all(|c| BASE64URL_ALPHABET.contains(&c)));
+        assert_eq!(manager.decrypt_filename(10, &encrypted)?, "rapor.docx");
+
+        // Şifresiz dizinde ad değişmeden kalır
+        assert_eq!(manager.encrypt_filename(5, "acik.txt")?, "acik.txt");
+
+        let too_long: String = core::iter::repeat('a').take(200).collect();
+        assert!(manager.encrypt_filename(10, &too_long).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_policy_rules() {
+        let (mut manager, key_id) = setup();
+        let policy = EncryptionPolicy::new(key_id);
+
+        // Aynı politika tekrar atanabilir
+        assert!(manager.set_directory_policy(10, policy, false, [0; FILE_NONCE_SIZE]).is_ok());
+        // Boş olmayan dizine politika atanamaz
+        assert!(manager.set_directory_policy(11, policy, false, [0; FILE_NONCE_SIZE]).is_err());
+        // Yüklü olmayan anahtarla politika atanamaz
+        let unknown = EncryptionPolicy::new(KeyIdentifier([0xEE; KEY_IDENTIFIER_SIZE]));
+        assert!(matches!(
+            manager.set_directory_policy(12, unknown, true, [0; FILE_NONCE_SIZE]),
+            Err(FileSystemError::KeyNotAvailable(_))
+        ));
+    }
+
+    #[test]
+    fn test_base64url_round_trip() {
+        for len in 0..40 {
+            let data: Vec<u8> = (0..len as u8).collect();
+            assert_eq!(base64url_decode(&base64url_encode(&data)).unwrap(), data);
+        }
+        assert!(base64url_decode("a+b").is_err());
+    }
+}
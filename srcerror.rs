@@ -1,10 +1,18 @@
 #![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
 
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
 // Sahne64 API'sından gelen düşük seviye hatalar için temel tip
 // SADAK dosya sistemi hataları bu tipi sarabilir veya bu tipten gelen hataları
 // kendi hata tiplerine dönüştürebilir.
 use crate::SahneError;
 
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::format;
+
 // no_std ortamında formatlama için core::fmt kullanılır.
 use core::fmt;
 
@@ -41,14 +49,79 @@ impl fmt::Display for DriveType {
     }
 }
 
+/// POSIX errno değerleri (Linux numaraları). `FileSystemError::errno` bu değerleri döndürür;
+/// FUSE ve sunucu protokolü gibi dış arayüzler bunları olduğu gibi iletir.
+pub mod errno {
+    pub const EPERM: i32 = 1;
+    pub const ENOENT: i32 = 2;
+    pub const EIO: i32 = 5;
+    pub const EAGAIN: i32 = 11;
+    pub const EACCES: i32 = 13;
+    pub const EBUSY: i32 = 16;
+    pub const EEXIST: i32 = 17;
+    pub const EXDEV: i32 = 18;
+    pub const ENOTDIR: i32 = 20;
+    pub const EISDIR: i32 = 21;
+    pub const EINVAL: i32 = 22;
+    pub const ENOSPC: i32 = 28;
+    pub const EROFS: i32 = 30;
+    pub const ERANGE: i32 = 34;
+    pub const ENAMETOOLONG: i32 = 36;
+    pub const ENOSYS: i32 = 38;
+    pub const ENOTEMPTY: i32 = 39;
+    pub const ENODATA: i32 = 61;
+    pub const EOPNOTSUPP: i32 = 95;
+    pub const ETIMEDOUT: i32 = 110;
+    pub const EUCLEAN: i32 = 117; // Linux dosya sistemlerinde EFSCORRUPTED
+    pub const EDQUOT: i32 = 122;
+    pub const ENOKEY: i32 = 126;
+}
+
 /// SADAK dosya sistemine özgü hata türleri.
+///
+/// Çağıranlar varyanta göre karar verebilir (ör. `NotFound` ise oluştur). Metin yükü ilgili
+/// nesneyi (yol, ad, aygıt) ya da açıklamayı taşır; boş olabilir. `caused_by` ile bir hata
+/// onu doğuran alt hataya bağlanır, `kind` zincirin en üstündeki hatayı verir.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FileSystemError {
+    /// Dosya, dizin, aygıt veya kaynak bulunamadı.
+    NotFound(String),
+    /// Oluşturulmak istenen ad zaten var.
+    Exists(String),
+    /// Yolun bir bileşeni dizin değil.
+    NotDirectory(String),
+    /// Dosya bekleyen bir işlem dizine uygulandı.
+    IsDirectory(String),
+    /// Silinmek veya üzerine taşınmak istenen dizin boş değil.
+    NotEmpty(String),
+    /// Aygıtta (veya ayrılmış alanda) yer kalmadı.
+    NoSpace(String),
+    /// İzin bitleri, sahiplik veya parola erişime izin vermiyor.
+    PermissionDenied(String),
+    /// Dosya sistemi ya da aygıt salt okunur.
+    ReadOnly(String),
+    /// Ad veya yol izin verilen uzunluğu aşıyor.
+    NameTooLong(String),
+    /// Diskteki bir yapı tutarsız (bozuk bitmap, çift serbest bırakma, hatalı sağlama toplamı).
+    Corrupted { block: u64, message: String },
+    /// Okunan veri beklenen biçimde değil (dosya biçimi ayrıştırma hataları dahil).
+    InvalidData(String),
+    /// Geçersiz argüman (sınır dışı blok numarası, geçersiz bayrak, ...).
+    InvalidParameter(String),
+    /// İşlem bu dosya sistemi, aygıt veya biçim tarafından desteklenmiyor.
+    NotSupported(String),
+    /// İşlem zaman aşımına uğradı.
+    TimedOut(String),
+    /// İşlem engelleyecekti; daha sonra yeniden denenmeli.
+    WouldBlock(String),
+    /// Kaynak kullanımda (ör. altında bağlama olan bağlama noktası).
+    Busy(String),
+    /// İşlem iki farklı dosya sistemi arasında yapılamaz (ör. rename).
+    CrossDevice(String),
     /// Aygıt katmanından gelen hatalar. Hatanın oluştuğu sürücü tipini içerir.
     DeviceError {
         drive_type: DriveType,
         message: String, // Aygıttan gelen hata mesajı veya açıklaması
-        // Orijinal Sahne64::SahneError burada saklanabilir: source: SahneError,
     },
     /// Inode yönetimi ile ilgili hatalar.
     InodeError(String),
@@ -62,14 +135,187 @@ pub enum FileSystemError {
     FreeSpaceError(String),
     /// Genel Giriş/Çıkış (I/O) işlemleri sırasında oluşan hatalar.
     /// Bu hatalar genellikle alttaki aygıttan veya Sahne64 API'sından gelir.
-    IOError(String), // Orijinal Sahne64::SahneError burada string olarak saklanıyor.
+    IOError(String),
+    /// Şifreleme politikası, anahtar türetme veya şifre çözme ile ilgili hatalar.
+    EncryptionError(String),
+    /// Şifreli bir dosyaya erişilmek istendi ancak ana anahtar anahtarlıkta yüklü değil.
+    KeyNotAvailable(String), // Eksik anahtarın tanımlayıcısı (hex)
+    /// Kullanıcı, grup veya proje kotası (blok ya da inode) aşıldı.
+    QuotaExceeded(String),
     /// Tanımlanmamış veya beklenmeyen diğer hatalar.
     Other(String),
+    /// `error`, `cause` yüzünden oluştu. `caused_by` ile kurulur; doğrudan eşleştirmek yerine
+    /// `kind` ve `cause` kullanılmalıdır.
+    Caused { error: Box<FileSystemError>, cause: Box<FileSystemError> },
+}
+
+impl FileSystemError {
+    /// `self`i `cause`a bağlar. `self`in zaten bir nedeni varsa `cause` zincirin sonuna eklenir.
+    pub fn caused_by(self, cause: FileSystemError) -> FileSystemError {
+        match self {
+            FileSystemError::Caused { error, cause: inner } => {
+                FileSystemError::Caused { error, cause: Box::new(inner.caused_by(cause)) }
+            }
+            error => FileSystemError::Caused { error: Box::new(error), cause: Box::new(cause) },
+        }
+    }
+
+    /// Zincirin en üstündeki hata (nedeni olmayan varyant).
+    pub fn kind(&self) -> &FileSystemError {
+        match self {
+            FileSystemError::Caused { error, .. } => error.kind(),
+            error => error,
+        }
+    }
+
+    /// Bu hatayı doğuran hata, varsa.
+    pub fn cause(&self) -> Option<&FileSystemError> {
+        match self {
+            FileSystemError::Caused { cause, .. } => Some(cause),
+            _ => None,
+        }
+    }
+
+    /// Zincirdeki hatalar, en üstteki hatadan en alttaki nedene doğru.
+    pub fn chain(&self) -> ErrorChain<'_> {
+        ErrorChain { next: Some(self) }
+    }
+
+    /// Zincirin en altındaki neden (nedeni olmayan bir hatada kendisi).
+    pub fn root_cause(&self) -> &FileSystemError {
+        self.chain().last().unwrap_or(self)
+    }
+
+    /// Hatanın POSIX errno karşılığı; zincirde en üstteki hata belirleyicidir.
+    pub fn errno(&self) -> i32 {
+        match self.kind() {
+            FileSystemError::NotFound(_) => errno::ENOENT,
+            FileSystemError::Exists(_) => errno::EEXIST,
+            FileSystemError::NotDirectory(_) => errno::ENOTDIR,
+            FileSystemError::IsDirectory(_) => errno::EISDIR,
+            FileSystemError::NotEmpty(_) => errno::ENOTEMPTY,
+            FileSystemError::NoSpace(_) => errno::ENOSPC,
+            FileSystemError::PermissionDenied(_) => errno::EACCES,
+            FileSystemError::ReadOnly(_) => errno::EROFS,
+            FileSystemError::NameTooLong(_) => errno::ENAMETOOLONG,
+            FileSystemError::Corrupted { .. } => errno::EUCLEAN,
+            FileSystemError::InvalidParameter(_) => errno::EINVAL,
+            FileSystemError::NotSupported(_) => errno::EOPNOTSUPP,
+            FileSystemError::TimedOut(_) => errno::ETIMEDOUT,
+            FileSystemError::WouldBlock(_) => errno::EAGAIN,
+            FileSystemError::Busy(_) => errno::EBUSY,
+            FileSystemError::CrossDevice(_) => errno::EXDEV,
+            FileSystemError::KeyNotAvailable(_) => errno::ENOKEY,
+            FileSystemError::QuotaExceeded(_) => errno::EDQUOT,
+            FileSystemError::InvalidData(_)
+            | FileSystemError::DeviceError { .. }
+            | FileSystemError::InodeError(_)
+            | FileSystemError::DataBlockError(_)
+            | FileSystemError::DirectoryError(_)
+            | FileSystemError::SuperblockError(_)
+            | FileSystemError::FreeSpaceError(_)
+            | FileSystemError::IOError(_)
+            | FileSystemError::EncryptionError(_)
+            | FileSystemError::Other(_)
+            | FileSystemError::Caused { .. } => errno::EIO,
+        }
+    }
+
+    /// errno değerinden hata oluşturur (host dosya sistemi veya istemci tarafı için).
+    /// Bilinmeyen değerler IOError olur.
+    pub fn from_errno(code: i32, message: String) -> FileSystemError {
+        match code {
+            errno::ENOENT => FileSystemError::NotFound(message),
+            errno::EEXIST => FileSystemError::Exists(message),
+            errno::ENOTDIR => FileSystemError::NotDirectory(message),
+            errno::EISDIR => FileSystemError::IsDirectory(message),
+            errno::ENOTEMPTY => FileSystemError::NotEmpty(message),
+            errno::ENOSPC => FileSystemError::NoSpace(message),
+            errno::EACCES | errno::EPERM => FileSystemError::PermissionDenied(message),
+            errno::EROFS => FileSystemError::ReadOnly(message),
+            errno::ENAMETOOLONG => FileSystemError::NameTooLong(message),
+            errno::EUCLEAN => FileSystemError::Corrupted { block: 0, message },
+            errno::EINVAL => FileSystemError::InvalidParameter(message),
+            errno::EOPNOTSUPP | errno::ENOSYS => FileSystemError::NotSupported(message),
+            errno::ETIMEDOUT => FileSystemError::TimedOut(message),
+            errno::EAGAIN => FileSystemError::WouldBlock(message),
+            errno::EBUSY => FileSystemError::Busy(message),
+            errno::EXDEV => FileSystemError::CrossDevice(message),
+            errno::ENOKEY => FileSystemError::KeyNotAvailable(message),
+            errno::EDQUOT => FileSystemError::QuotaExceeded(message),
+            _ => FileSystemError::IOError(message),
+        }
+    }
+
+    /// Sahne64 API'sına döndürülecek hata. SahneError daha az ayrıntılı olduğundan
+    /// birden çok varyant aynı SahneError'a düşer; metin IOError/Other içinde korunur.
+    pub fn to_sahne_error(&self) -> SahneError {
+        match self.kind() {
+            FileSystemError::NotFound(_) => SahneError::ResourceNotFound,
+            FileSystemError::PermissionDenied(_) | FileSystemError::ReadOnly(_) | FileSystemError::KeyNotAvailable(_) => {
+                SahneError::PermissionDenied
+            }
+            FileSystemError::NoSpace(_) | FileSystemError::QuotaExceeded(_) => SahneError::OutOfSpace,
+            FileSystemError::InvalidParameter(_) | FileSystemError::NameTooLong(_) => SahneError::InvalidParameter,
+            FileSystemError::NotSupported(_) => SahneError::NotSupported,
+            FileSystemError::TimedOut(_) => SahneError::Timeout,
+            FileSystemError::Exists(_)
+            | FileSystemError::NotDirectory(_)
+            | FileSystemError::IsDirectory(_)
+            | FileSystemError::NotEmpty(_)
+            | FileSystemError::WouldBlock(_)
+            | FileSystemError::Busy(_)
+            | FileSystemError::CrossDevice(_) => SahneError::InvalidOperation,
+            FileSystemError::Other(msg) => SahneError::Other(msg.clone()),
+            _ => SahneError::IOError(format!("{}", self)),
+        }
+    }
+}
+
+/// `FileSystemError::chain` yineleyicisi.
+pub struct ErrorChain<'a> {
+    next: Option<&'a FileSystemError>,
+}
+
+impl<'a> Iterator for ErrorChain<'a> {
+    type Item = &'a FileSystemError;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = current.cause();
+        Some(current.kind())
+    }
+}
+
+// Nesne metni boşsa yalnızca hata adını yazar
+fn write_subject(f: &mut fmt::Formatter<'_>, what: &str, subject: &str) -> fmt::Result {
+    if subject.is_empty() {
+        write!(f, "{}", what)
+    } else {
+        write!(f, "{}: {}", what, subject)
+    }
 }
 
 impl fmt::Display for FileSystemError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            FileSystemError::NotFound(what) => write_subject(f, "Not found", what),
+            FileSystemError::Exists(what) => write_subject(f, "Already exists", what),
+            FileSystemError::NotDirectory(what) => write_subject(f, "Not a directory", what),
+            FileSystemError::IsDirectory(what) => write_subject(f, "Is a directory", what),
+            FileSystemError::NotEmpty(what) => write_subject(f, "Directory not empty", what),
+            FileSystemError::NoSpace(what) => write_subject(f, "No space left on device", what),
+            FileSystemError::PermissionDenied(what) => write_subject(f, "Permission denied", what),
+            FileSystemError::ReadOnly(what) => write_subject(f, "Read-only file system", what),
+            FileSystemError::NameTooLong(what) => write_subject(f, "Name too long", what),
+            FileSystemError::Corrupted { block, message } => write!(f, "Corrupted structure at block {}: {}", block, message),
+            FileSystemError::InvalidData(msg) => write_subject(f, "Invalid data", msg),
+            FileSystemError::InvalidParameter(msg) => write_subject(f, "Invalid parameter", msg),
+            FileSystemError::NotSupported(msg) => write_subject(f, "Not supported", msg),
+            FileSystemError::TimedOut(msg) => write_subject(f, "Timed out", msg),
+            FileSystemError::WouldBlock(msg) => write_subject(f, "Operation would block", msg),
+            FileSystemError::Busy(what) => write_subject(f, "Device or resource busy", what),
+            FileSystemError::CrossDevice(what) => write_subject(f, "Cross-device link", what),
             FileSystemError::DeviceError { drive_type, message } => {
                 write!(f, "Device Error on {}: {}", drive_type, message)
             }
@@ -79,13 +325,103 @@ impl fmt::Display for FileSystemError {
             FileSystemError::SuperblockError(msg) => write!(f, "Superblock Error: {}", msg),
             FileSystemError::FreeSpaceError(msg) => write!(f, "Free Space Error: {}", msg),
             FileSystemError::IOError(msg) => write!(f, "IO Error: {}", msg),
+            FileSystemError::EncryptionError(msg) => write!(f, "Encryption Error: {}", msg),
+            FileSystemError::KeyNotAvailable(key_id) => {
+                write!(f, "Required encryption key {} is not loaded", key_id)
+            }
+            FileSystemError::QuotaExceeded(msg) => write!(f, "Quota Exceeded: {}", msg),
             FileSystemError::Other(msg) => write!(f, "Other Error: {}", msg),
+            FileSystemError::Caused { error, cause } => write!(f, "{}: {}", error, cause),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for FileSystemError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FileSystemError::Caused { cause, .. } => Some(cause.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+// SahneError -> FileSystemError (dosya modüllerinde `?` ile doğrudan kullanılır)
+impl From<SahneError> for FileSystemError {
+    fn from(e: SahneError) -> Self {
+        match e {
+            SahneError::NotFound | SahneError::ResourceNotFound => FileSystemError::NotFound(format!("SahneError: {:?}", e)),
+            SahneError::PermissionDenied => FileSystemError::PermissionDenied(String::from("SahneError: PermissionDenied")),
+            SahneError::OutOfSpace => FileSystemError::NoSpace(String::from("SahneError: OutOfSpace")),
+            SahneError::Timeout => FileSystemError::TimedOut(String::from("SahneError: Timeout")),
+            SahneError::NotSupported => FileSystemError::NotSupported(String::from("SahneError: NotSupported")),
+            SahneError::InvalidParameter | SahneError::InvalidAddress | SahneError::InvalidHandle | SahneError::InvalidOperation => {
+                FileSystemError::InvalidParameter(format!("SahneError: {:?}", e))
+            }
+            SahneError::IOError(msg) => FileSystemError::IOError(msg),
+            SahneError::Other(msg) => FileSystemError::Other(msg),
+            // EndOfFile, CommunicationError ve ileride eklenecek varyantlar
+            #[allow(unreachable_patterns)]
+            other => FileSystemError::IOError(format!("SahneError: {:?}", other)),
+        }
+    }
+}
+
+// std::io::Error -> FileSystemError (host dosyaları; işletim sistemi errno'su varsa o kullanılır)
+#[cfg(feature = "std")]
+impl From<std::io::Error> for FileSystemError {
+    fn from(e: std::io::Error) -> Self {
+        use std::io::ErrorKind;
+        // Bizim ürettiğimiz io::Error'lar (aşağıdaki From) asıl hatayı taşır; kayıpsız geri alınır
+        if let Some(inner) = e.get_ref().and_then(|inner| inner.downcast_ref::<FileSystemError>()) {
+            return inner.clone();
+        }
+        let message = format!("{}", e);
+        if let Some(code) = e.raw_os_error() {
+            return FileSystemError::from_errno(code, message);
+        }
+        match e.kind() {
+            ErrorKind::NotFound => FileSystemError::NotFound(message),
+            ErrorKind::PermissionDenied => FileSystemError::PermissionDenied(message),
+            ErrorKind::AlreadyExists => FileSystemError::Exists(message),
+            ErrorKind::InvalidInput => FileSystemError::InvalidParameter(message),
+            ErrorKind::InvalidData => FileSystemError::InvalidData(message),
+            ErrorKind::TimedOut => FileSystemError::TimedOut(message),
+            ErrorKind::WouldBlock => FileSystemError::WouldBlock(message),
+            ErrorKind::Unsupported => FileSystemError::NotSupported(message),
+            _ => FileSystemError::IOError(message),
         }
     }
 }
 
+// FileSystemError -> std::io::Error (hata ve mesajı korunur; tür errno'dan türetilir)
 #[cfg(feature = "std")]
-impl Error for FileSystemError {}
+impl From<FileSystemError> for std::io::Error {
+    fn from(e: FileSystemError) -> Self {
+        use std::io::ErrorKind;
+        let kind = match e.kind() {
+            FileSystemError::NotFound(_) => ErrorKind::NotFound,
+            FileSystemError::Exists(_) => ErrorKind::AlreadyExists,
+            FileSystemError::NotDirectory(_) => ErrorKind::NotADirectory,
+            FileSystemError::IsDirectory(_) => ErrorKind::IsADirectory,
+            FileSystemError::NotEmpty(_) => ErrorKind::DirectoryNotEmpty,
+            FileSystemError::NoSpace(_) => ErrorKind::StorageFull,
+            FileSystemError::PermissionDenied(_) => ErrorKind::PermissionDenied,
+            FileSystemError::ReadOnly(_) => ErrorKind::ReadOnlyFilesystem,
+            FileSystemError::NameTooLong(_) => ErrorKind::InvalidFilename,
+            FileSystemError::InvalidParameter(_) => ErrorKind::InvalidInput,
+            FileSystemError::Corrupted { .. } | FileSystemError::InvalidData(_) => ErrorKind::InvalidData,
+            FileSystemError::NotSupported(_) => ErrorKind::Unsupported,
+            FileSystemError::TimedOut(_) => ErrorKind::TimedOut,
+            FileSystemError::WouldBlock(_) => ErrorKind::WouldBlock,
+            FileSystemError::Busy(_) => ErrorKind::ResourceBusy,
+            FileSystemError::CrossDevice(_) => ErrorKind::CrossesDevices,
+            FileSystemError::QuotaExceeded(_) => ErrorKind::QuotaExceeded,
+            _ => ErrorKind::Other,
+        };
+        std::io::Error::new(kind, e)
+    }
+}
 
 // Örnek hata oluşturma fonksiyonu
 pub fn create_device_error(drive_type: DriveType, message: String) -> FileSystemError {
@@ -142,4 +478,84 @@ fn main() {
     handle_error(error);
 }
 
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_errno_round_trip_and_cause_chain() {
+        let errors = [
+            FileSystemError::NotFound("/a".to_string()),
+            FileSystemError::Exists("/a".to_string()),
+            FileSystemError::NotDirectory("/a".to_string()),
+            FileSystemError::IsDirectory("/a".to_string()),
+            FileSystemError::NotEmpty("/a".to_string()),
+            FileSystemError::NoSpace(String::new()),
+            FileSystemError::ReadOnly(String::new()),
+            FileSystemError::NameTooLong("x".to_string()),
+            FileSystemError::Corrupted { block: 0, message: "bitmap".to_string() },
+            FileSystemError::QuotaExceeded("uid 1000".to_string()),
+        ];
+        for error in errors.iter() {
+            let message = match error {
+                FileSystemError::Corrupted { message, .. } => message.clone(),
+                FileSystemError::NotFound(s)
+                | FileSystemError::Exists(s)
+                | FileSystemError::NotDirectory(s)
+                | FileSystemError::IsDirectory(s)
+                | FileSystemError::NotEmpty(s)
+                | FileSystemError::NoSpace(s)
+                | FileSystemError::ReadOnly(s)
+                | FileSystemError::NameTooLong(s)
+                | FileSystemError::QuotaExceeded(s) => s.clone(),
+                _ => unreachable!(),
+            };
+            assert_eq!(&FileSystemError::from_errno(error.errno(), message), error);
+        }
+        assert_eq!(FileSystemError::InodeError("x".to_string()).errno(), errno::EIO);
+        assert_eq!(FileSystemError::NotFound(String::new()).to_string(), "Not found");
+
+        let device = FileSystemError::IOError("sector 12".to_string());
+        let error = FileSystemError::Corrupted { block: 7, message: "inode table".to_string() }
+            .caused_by(FileSystemError::InvalidData("bad checksum".to_string()))
+            .caused_by(device.clone());
+        assert!(matches!(error.kind(), FileSystemError::Corrupted { block: 7, .. }));
+        assert_eq!(error.errno(), errno::EUCLEAN);
+        assert_eq!(error.chain().count(), 3);
+        assert_eq!(error.root_cause(), &device);
+        assert_eq!(
+            error.to_string(),
+            "Corrupted structure at block 7: inode table: Invalid data: bad checksum: IO Error: sector 12"
+        );
+        assert!(error.source().is_some());
+        let io = std::io::Error::from(error.clone());
+        assert_eq!(io.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(io.to_string(), error.to_string());
+        assert_eq!(FileSystemError::from(io), error);
+        assert_eq!(std::io::Error::from(FileSystemError::ReadOnly(String::new())).kind(), std::io::ErrorKind::ReadOnlyFilesystem);
+
+        let io = std::io::Error::from_raw_os_error(errno::ENOTEMPTY);
+        assert!(matches!(FileSystemError::from(io), FileSystemError::NotEmpty(_)));
+        let io = std::io::Error::new(std::io::ErrorKind::AlreadyExists, "dup");
+        assert!(matches!(FileSystemError::from(io), FileSystemError::Exists(_)));
+    }
+
+    #[test]
+    fn test_sahne_error_mapping() {
+        assert!(matches!(FileSystemError::from(SahneError::ResourceNotFound), FileSystemError::NotFound(_)));
+        assert!(matches!(FileSystemError::from(SahneError::OutOfSpace), FileSystemError::NoSpace(_)));
+        assert!(matches!(FileSystemError::from(SahneError::InvalidHandle), FileSystemError::InvalidParameter(_)));
+        assert_eq!(FileSystemError::from(SahneError::IOError("x".to_string())), FileSystemError::IOError("x".to_string()));
+
+        assert!(matches!(FileSystemError::ReadOnly(String::new()).to_sahne_error(), SahneError::PermissionDenied));
+        assert!(matches!(FileSystemError::QuotaExceeded(String::new()).to_sahne_error(), SahneError::OutOfSpace));
+        assert!(matches!(FileSystemError::Exists(String::new()).to_sahne_error(), SahneError::InvalidOperation));
+        let wrapped = FileSystemError::NotFound("/x".to_string()).caused_by(FileSystemError::Other("y".to_string()));
+        assert!(matches!(wrapped.to_sahne_error(), SahneError::ResourceNotFound));
+        assert!(matches!(FileSystemError::InodeError("z".to_string()).to_sahne_error(), SahneError::IOError(_)));
+    }
+}
+
 // Tekrarlanan no_std print modülü ve panic handler kaldırıldı.
@@ -108,18 +108,6 @@ fn map_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
     FileSystemError::IOError(alloc::string::String::from(alloc::format!("IO Error: {}", e))) // alloc::format! kullanır
 }
 
-// SahneError -> FileSystemError
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    // SahneError türlerine göre FileSystemError eşlemesi yapılabilir.
-    // Örneğin, ResourceNotFound -> FileSystemError::DeviceError veya IOError
-    // InvalidParameter -> FileSystemError::Other veya daha spesifik
-    // Şimdilik genel bir IOError'a map edelim veya SahneError'ı stringe çevirelim.
-    FileSystemError::IOError(alloc::string::String::from(alloc::format!("SahneError: {:?}", e))) // Debug formatı stringe çevrilir
-     // Daha iyi bir yaklaşım FileSystemError içinde SahneError'ı tutmaktır:
-      FileSystemError::UnderlyingError(e) // Eğer böyle bir varyant varsa
-}
-
 // DecoderError -> FileSystemError (no_std)
 #[cfg(not(feature = "std"))]
 fn map_decode_error_to_fs_error(e: faad_rs::DecodeError) -> FileSystemError {
@@ -182,8 +170,7 @@ pub fn read_aac_file(resource_id: &str) -> Result<Vec<i16>, FileSystemError> { /
      // alloc::string::String ve alloc::format! kullanımı için de.
 
     // Kaynağı edin
-    let handle = resource::acquire(resource_id, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(resource_id, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     // Tüm veriyi belleğe oku (Büyük dosyalar için sorunlu!)
     let mut buffer = Vec::new();
@@ -198,7 +185,7 @@ pub fn read_aac_file(resource_id: &str) -> Result<Vec<i16>, FileSystemError> { /
             }
             Err(e) => {
                  let _ = resource::release(handle); // Kaynağı serbest bırakmayı dene
-                 return Err(map_sahne_error_to_fs_error(e)); // SahneError -> FileSystemError
+                 return Err(FileSystemError::from(e)); // SahneError -> FileSystemError
             }
         }
     }
@@ -207,7 +194,7 @@ pub fn read_aac_file(resource_id: &str) -> Result<Vec<i16>, FileSystemError> { /
     let _ = resource::release(handle).map_err(|e| {
          // Kaynak serbest bırakma hatası (kritik değilse sadece logla)
          println!("WARN: Sahne64 kaynak serbest bırakma hatası: {:?}", e); // no_std print makrosu
-         map_sahne_error_to_fs_error(e) // Yine de hatayı FileSystemError'a çevir
+         FileSystemError::from(e) // Yine de hatayı FileSystemError'a çevir
      });
 
 
@@ -6,7 +6,7 @@
 extern crate alloc;
 
 // VFS trait'leri ve SahneError
-use crate::vfs::{VfsNode, VfsNodeType}; // VFS modülü dışarıdan import edilir
+use crate::vfs::{FileType, Metadata, VfsError, VfsFile}; // VFS modülü dışarıdan import edilir
 use crate::SahneError; // Sahne64 API'sından gelen temel hata tipi (veya genel proje hatası)
 
 // Bellek için Vec
@@ -109,17 +109,25 @@ impl AccdbFile {
      }
 }
 
-impl VfsNode for AccdbFile {
-    /// Düğüm tipini döndürür (File).
-    fn get_type(&self) -> VfsNodeType {
-        VfsNodeType::File
+// Açık dosya olarak VFS'e bağlanır; ofset tabanlı okuma imleci etkilemez.
+impl VfsFile for AccdbFile {
+    /// `offset`ten itibaren `buf`a okur; dosya sonunda 0 döner.
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> Result<usize, VfsError> {
+        if offset >= self.data.len() as u64 {
+            return Ok(0);
+        }
+        let start = offset as usize;
+        let bytes_to_read = cmp::min(buf.len(), self.data.len() - start);
+        buf[..bytes_to_read].copy_from_slice(&self.data[start..start + bytes_to_read]);
+        Ok(bytes_to_read)
     }
 
-    /// Dosyanın boyutunu döndürür.
-    fn get_size(&self) -> usize {
-        self.len() // len() metodunu kullanalım
+    /// Dosya meta verisi (salt okunur düzenli dosya).
+    fn metadata(&self) -> Result<Metadata, VfsError> {
+        let mut meta = Metadata::new(FileType::File, self.len() as u64);
+        meta.mode = 0o444;
+        Ok(meta)
     }
-    // VfsNode trait'i başka metotlar da gerektirebilir (izinler, sahiplik vb.)
 }
 
 // core::io::Read trait implementasyonu
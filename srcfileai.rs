@@ -6,7 +6,7 @@
 extern crate alloc;
 
 // Gerekli temel tipleri içeri aktar
-use crate::{FileSystemError, SahneError, Handle}; // Hata tipleri ve Handle
+use crate::{FileSystemError, Handle}; // Hata tipleri ve Handle
 // Sahne64 resource modülü (no_std implementasyonu için)
 #[cfg(not(feature = "std"))]
 use crate::resource;
@@ -24,11 +24,6 @@ fn map_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
     FileSystemError::IOError(alloc::string::String::from(alloc::format!("IO Error: {}", e)))
 }
 
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(alloc::string::String::from(alloc::format!("SahneError: {:?}", e)))
-}
-
 // no_std ortamında println! ve eprintln! makroları için
 #[cfg(not(feature = "std"))]
 use crate::{println, eprintln}; // crate kökünden veya ortak modülden import edildiği varsayılır
@@ -67,21 +62,20 @@ fn read_ai_file(resource_id: &str) -> Result<(), FileSystemError> { // FileSyste
     // alloc::string::String ve alloc::format! kullanımı için de.
 
     // Kaynağı edin
-    let handle = resource::acquire(resource_id, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(resource_id, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     let mut buffer = [0u8; 4]; // Sadece ilk 4 baytı okumak için sabit boyutlu buffer
     // resource::read Result<usize, SahneError> döner
     let bytes_read = resource::read(handle, &mut buffer).map_err(|e| {
          let _ = resource::release(handle); // Kaynağı serbest bırakmayı dene
-         map_sahne_error_to_fs_error(e)
+         FileSystemError::from(e)
      })?; // En fazla 4 bayt oku
 
     // Kaynağı serbest bırak
     let _ = resource::release(handle).map_err(|e| {
          // Kaynak serbest bırakma hatası (kritik değilse sadece logla)
          eprintln!("WARN: Sahne64 kaynak serbest bırakma hatası: {:?}", e); // no_std print makrosu
-         map_sahne_error_to_fs_error(e) // Yine de hatayı FileSystemError'a çevir
+         FileSystemError::from(e) // Yine de hatayı FileSystemError'a çevir
      });
 
 
@@ -134,12 +134,6 @@ fn map_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
     FileSystemError::IOError(format!("IO Error: {}", e))
 }
 
-// Helper to map SahneError to FileSystemError
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-}
-
 // Helper to map CoreIOError to FileSystemError (for no_std Read/Seek on SahneResourceReader)
 #[cfg(not(feature = "std"))]
 fn map_core_io_error_to_fs_error(e: CoreIOError) -> FileSystemError {
@@ -179,8 +173,7 @@ pub fn read_alac_metadata(file_path: &str) -> Result<AlacMetadata, FileSystemErr
 #[cfg(not(feature = "std"))]
 pub fn read_alac_metadata(resource_id: &str) -> Result<AlacMetadata, FileSystemError> { // FileSystemError döner
     // Kaynağı edin
-    let handle = resource::acquire(resource_id, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(resource_id, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     // Sahne64 Handle'ı için core::io::Read + Seek implementasyonu sağlayan Reader struct'ı oluştur
     let mut reader = SahneResourceReader::new(handle);
@@ -195,7 +188,7 @@ pub fn read_alac_metadata(resource_id: &str) -> Result<AlacMetadata, FileSystemE
     // Kaynağı serbest bırak
     let _ = resource::release(handle).map_err(|e| {
          eprintln!("WARN: Sahne64 kaynak serbest bırakma hatası: {:?}", e); // no_std print makrosu
-         map_sahne_error_to_fs_error(e) // SahneError -> FileSystemError
+         FileSystemError::from(e) // SahneError -> FileSystemError
      });
 
     // Meta veri bulunduysa dön, yoksa hata ver
@@ -261,8 +261,7 @@ pub fn parse_avi(file_path_or_resource_id: &str) -> Result<AviData, FileSystemEr
     #[cfg(not(feature = "std"))]
     {
         // Kaynağı edin
-        let handle = resource::acquire(file_path_or_resource_id, resource::MODE_READ)
-            .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+        let handle = resource::acquire(file_path_or_resource_id, resource::MODE_READ)?; // SahneError -> FileSystemError
 
         // Sahne64 Handle'ı için core::io::Read + Seek implementasyonu sağlayan Reader struct'ı oluştur
         let mut reader = SahneResourceReader::new(handle);
@@ -273,7 +272,7 @@ pub fn parse_avi(file_path_or_resource_id: &str) -> Result<AviData, FileSystemEr
         // Kaynağı serbest bırak
         let _ = resource::release(handle).map_err(|e| {
              eprintln!("WARN: Sahne64 kaynak serbest bırakma hatası: {:?}", e);
-             map_sahne_error_to_fs_error(e)
+             FileSystemError::from(e)
          });
 
         Ok(avi_data_result)
@@ -937,12 +936,6 @@ fn map_core_io_error_to_fs_error(e: CoreIOError) -> FileSystemError {
      // TODO: Implement a proper mapping based on CoreIOErrorKind
 }
 
-// Helper function to map SahneError to FileSystemError (defined earlier, copy here for clarity)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e))
-}
-
 
 // Example main functions
 #[cfg(feature = "example_avi")] // Different feature flag
@@ -18,13 +18,6 @@ use crate::fs;
 use alloc::string::String;
 use alloc::format;
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 
 /// Sahne64 ortamında genel bir binary dosyayı temsil eder.
 /// Offset tabanlı okuma ve dosya boyutu bilgisi sağlar (eğer Sahne64 API destekliyorsa).
@@ -49,15 +42,14 @@ impl BinFile {
     pub fn open<P: AsRef<str>>(path: P) -> Result<BinFile, FileSystemError> { // FileSystemError döner
         let path_str = path.as_ref();
         // Kaynağı edin
-        let handle = resource::acquire(path_str, resource::MODE_READ)
-            .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+        let handle = resource::acquire(path_str, resource::MODE_READ)?; // SahneError -> FileSystemError
 
         // Dosyanın boyutunu almak için fs::fstat syscall'ını kullanalım (varsayım)
         // fs::fstat(handle) Result<FileStat, SahneError> döndürür ve FileStat size alanı içerir.
         let file_stat = fs::fstat(handle)
             .map_err(|e| {
                  let _ = resource::release(handle); // Hata durumunda handle'ı serbest bırak
-                 map_sahne_error_to_fs_error(e)
+                 FileSystemError::from(e)
              })?; // SahneError -> FileSystemError
 
         let size = file_stat.size as usize; // Assuming size is u64 or usize compatible
@@ -102,8 +94,7 @@ impl BinFile {
         }
         let mut buffer = [0u8; 1];
         // fs::read_at(handle, offset, buf) Result<usize, SahneError> döner (varsayım)
-        let bytes_read = fs::read_at(self.handle, offset as u64, &mut buffer)
-            .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+        let bytes_read = fs::read_at(self.handle, offset as u64, &mut buffer)?; // SahneError -> FileSystemError
 
         if bytes_read == 1 {
             Ok(Some(buffer[0]))
@@ -135,8 +126,7 @@ impl BinFile {
             return Ok(None); // Ofset + boyut dosya boyutunun dışında
         }
         let mut buffer = [0u8; size_of::<u16>()];
-        let bytes_read = fs::read_at(self.handle, offset as u64, &mut buffer)
-             .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+        let bytes_read = fs::read_at(self.handle, offset as u64, &mut buffer)?; // SahneError -> FileSystemError
 
         if bytes_read == size_of::<u16>() {
             Ok(Some(u16::from_le_bytes(buffer)))
@@ -165,8 +155,7 @@ impl BinFile {
             return Ok(None); // Ofset + boyut dosya boyutunun dışında
         }
         let mut buffer = [0u8; size_of::<u32>()];
-        let bytes_read = fs::read_at(self.handle, offset as u64, &mut buffer)
-             .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+        let bytes_read = fs::read_at(self.handle, offset as u64, &mut buffer)?; // SahneError -> FileSystemError
 
         if bytes_read == size_of::<u32>() {
             Ok(Some(u32::from_le_bytes(buffer)))
@@ -189,7 +178,7 @@ impl BinFile {
     #[cfg(not(feature = "std"))] // Only for no_std Sahne64
     pub fn close(&mut self) -> Result<(), FileSystemError> { // FileSystemError döner
         resource::release(self.handle)
-            .map_err(map_sahne_error_to_fs_error) // SahneError -> FileSystemError
+            .map_err(FileSystemError::from) // SahneError -> FileSystemError
     }
 
      // Destructor (Drop trait) Sahne64 ortamında kaynak yönetimi için kritik olabilir.
@@ -217,13 +206,6 @@ impl BinFile {
  }
 
 
-// Helper function to map SahneError to FileSystemError (defined earlier, copied for clarity)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e))
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 
 // Example main function (no_std)
 #[cfg(feature = "example_bin")] // Different feature flag
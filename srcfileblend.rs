@@ -7,7 +7,7 @@
 extern crate alloc;
 
 // Gerekli temel Sahne64 tipleri ve modülleri
-use crate::{FileSystemError, SahneError, Handle}; // Hata tipleri ve Handle
+use crate::{FileSystemError, Handle}; // Hata tipleri ve Handle
 // Sahne64 resource modülü
 #[cfg(not(feature = "std"))]
 use crate::resource;
@@ -42,13 +42,6 @@ use core::mem::size_of; // core::mem::size_of
 use crate::{println, eprintln}; // crate kökünden veya ortak modülden import edildiği varsayılır
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -192,14 +185,13 @@ pub fn read_from_file(path: &Path) -> Result<BlendFile, FileSystemError> { // Fi
 #[cfg(not(feature = "std"))]
 pub fn read_from_file(path: &str) -> Result<BlendFile, FileSystemError> { // FileSystemError döner
     // Kaynağı edin
-    let handle = resource::acquire(path, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(path, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     // Dosyanın boyutunu al (SahneResourceReader için gerekli)
      let file_stat = fs::fstat(handle)
          .map_err(|e| {
               let _ = resource::release(handle);
-              map_sahne_error_to_fs_error(e)
+              FileSystemError::from(e)
           })?;
      let file_size = file_stat.size as u64;
 
@@ -223,7 +215,7 @@ pub fn read_from_file(path: &str) -> Result<BlendFile, FileSystemError> { // Fil
         // Kaynağı serbest bırakmadan önce hata dön
          let _ = resource::release(handle).map_err(|e| {
               eprintln!("WARN: Kaynak serbest bırakma hatası: {:?}", e);
-              map_sahne_error_to_fs_error(e)
+              FileSystemError::from(e)
           });
         return Err(FileSystemError::InvalidData(format!("Geçersiz sihirli sayı: {:?}", header.magic)));
     }
@@ -251,7 +243,7 @@ pub fn read_from_file(path: &str) -> Result<BlendFile, FileSystemError> { // Fil
     // Kaynağı serbest bırak
     let _ = resource::release(handle).map_err(|e| {
          eprintln!("WARN: Sahne64 kaynak serbest bırakma hatası: {:?}", e);
-         map_sahne_error_to_fs_error(e)
+         FileSystemError::from(e)
      });
 
     Ok(BlendFile { header, data })
@@ -298,17 +290,16 @@ pub fn write_to_file(&self, path: &Path) -> Result<(), FileSystemError> { // Fil
 #[cfg(not(feature = "std"))]
 pub fn write_to_file(&self, path: &str) -> Result<(), FileSystemError> { // FileSystemError döner
     // Kaynağı yazma modunda edin (O_CREAT | O_WRONLY Sahne64 karşılığı varsayım)
-    let handle = resource::acquire(path, resource::MODE_WRITE | resource::FLAG_CREATE)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(path, resource::MODE_WRITE | resource::FLAG_CREATE)?; // SahneError -> FileSystemError
 
     // Başlığı yaz
     // resource::write(handle, data) Result<usize, SahneError> döner (varsayım)
     resource::write(handle, &self.header.magic)
-        .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+        .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
     resource::write(handle, &self.header.version.to_le_bytes())
-        .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+        .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
     resource::write(handle, &self.header.data_offset.to_le_bytes())
-        .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+        .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
 
     // Veriyi yaz
      // Eğer data_offset header boyutundan (12) büyükse, aradaki boşluğa 0 yazılmalı.
@@ -317,19 +308,18 @@ pub fn write_to_file(&self, path: &str) -> Result<(), FileSystemError> { // File
           let padding_size = (self.header.data_offset - header_size) as usize;
           let padding = vec![0u8; padding_size]; // Requires alloc
           resource::write(handle, &padding)
-             .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+             .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
      } else if self.header.data_offset < header_size {
            eprintln!("WARN: Geçersiz BlendHeader data_offset değeri: {}", self.header.data_offset); // no_std print
            // Hata verilebilir veya sadece loglanabilir
      }
 
      resource::write(handle, &self.data)
-        .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+        .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
 
 
     // Kaynağı serbest bırak
-    resource::release(handle)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    resource::release(handle)?; // SahneError -> FileSystemError
 
     Ok(())
 }
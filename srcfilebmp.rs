@@ -6,7 +6,7 @@
 extern crate alloc;
 
 // Gerekli temel Sahne64 tipleri ve modülleri
-use crate::{FileSystemError, SahneError, Handle}; // Hata tipleri ve Handle
+use crate::{FileSystemError, Handle}; // Hata tipleri ve Handle
 // Sahne64 resource modülü
 #[cfg(not(feature = "std"))]
 use crate::resource;
@@ -43,13 +43,6 @@ use core::convert::TryInto; // core::convert::TryInto
 #[cfg(not(feature = "std"))]
 use crate::{println, eprintln}; // crate kökünden veya ortak modülden import edildiği varsayılır
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -264,14 +257,13 @@ impl BmpImage {
     #[cfg(not(feature = "std"))]
     pub fn read_from_file(filename: &str) -> Result<Self, FileSystemError> { // FileSystemError döner
         // Kaynağı edin
-        let handle = resource::acquire(filename, resource::MODE_READ)
-            .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+        let handle = resource::acquire(filename, resource::MODE_READ)?; // SahneError -> FileSystemError
 
         // Dosyanın boyutunu al (SahneResourceReader için gerekli veya seek(End) için)
          let file_stat = fs::fstat(handle)
              .map_err(|e| {
                   let _ = resource::release(handle);
-                  map_sahne_error_to_fs_error(e)
+                  FileSystemError::from(e)
               })?;
          let file_size_u64 = file_stat.size as u64;
 
@@ -289,7 +281,7 @@ impl BmpImage {
              // Kaynağı serbest bırakmadan önce hata dön
              let _ = resource::release(handle).map_err(|e| {
                   eprintln!("WARN: Kaynak serbest bırakma hatası: {:?}", e);
-                  map_sahne_error_to_fs_error(e)
+                  FileSystemError::from(e)
               });
              return Err(FileSystemError::InvalidData(format!("Geçersiz BMP sihirli sayısı: {:x?}", &file_header_bytes[0..2]))); // FileSystemError
         }
@@ -304,7 +296,7 @@ impl BmpImage {
         let dib_header_size = u32::from_le_bytes(dib_header_size_bytes.try_into().map_err(|_| FileSystemError::InvalidData(format!("DIB başlık boyutu baytları geçersiz")))?);
 
         if dib_header_size < 40 { // Minimum BITMAPINFOHEADER boyutu
-             let _ = resource::release(handle).map_err(|e| { eprintln!("WARN: Kaynak serbest bırakma hatası: {:?}", e); map_sahne_error_to_fs_error(e) });
+             let _ = resource::release(handle).map_err(|e| { eprintln!("WARN: Kaynak serbest bırakma hatası: {:?}", e); FileSystemError::from(e) });
              return Err(FileSystemError::InvalidData(format!("Geçersiz DIB başlık boyutu: {}", dib_header_size)));
         }
 
@@ -343,7 +335,7 @@ impl BmpImage {
         // Kaynağı serbest bırak
         let _ = resource::release(handle).map_err(|e| {
              eprintln!("WARN: Sahne64 kaynak serbest bırakma hatası: {:?}", e);
-             map_sahne_error_to_fs_error(e)
+             FileSystemError::from(e)
          });
 
 
@@ -415,38 +407,37 @@ impl BmpImage {
     #[cfg(not(feature = "std"))]
     pub fn write_to_file(&self, filename: &str) -> Result<(), FileSystemError> { // FileSystemError döner
         // Kaynağı yazma modunda edin (O_CREAT | O_WRONLY Sahne64 karşılığı varsayım)
-        let handle = resource::acquire(filename, resource::MODE_WRITE | resource::FLAG_CREATE)
-            .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+        let handle = resource::acquire(filename, resource::MODE_WRITE | resource::FLAG_CREATE)?; // SahneError -> FileSystemError
 
         // BMP File Header (14 bytes)
         // resource::write(handle, data) Result<usize, SahneError> döner (varsayım)
         resource::write(handle, b"BM")
-             .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+             .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
         resource::write(handle, &self.header.file_size.to_le_bytes())
-             .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+             .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
         resource::write(handle, &[0u8; 4]) // Reserved
-             .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+             .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
         resource::write(handle, &self.header.image_data_offset.to_le_bytes())
-             .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+             .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
 
         // DIB Header (40 bytes for BITMAPINFOHEADER)
         resource::write(handle, &self.header.dib_header_size.to_le_bytes())
-             .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?; // DIB header size
+             .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?; // DIB header size
         resource::write(handle, &self.header.width.to_le_bytes())
-             .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+             .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
         resource::write(handle, &self.header.height.to_le_bytes())
-             .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+             .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
         resource::write(handle, &[1, 0]) // Color planes
-             .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+             .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
         resource::write(handle, &self.header.bits_per_pixel.to_le_bytes())
-             .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+             .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
 
         // Write remaining DIB header fields (assuming BITMAPINFOHEADER size 40)
         let bytes_written_in_dib_header_so_far = 4 + 4 + 4 + 2 + 2;
         let remaining_dib_bytes = self.header.dib_header_size.checked_sub(bytes_written_in_dib_header_so_far as u32)
              .ok_or_else(|| FileSystemError::InvalidData(format!("Geçersiz DIB başlık boyutu veya yazma hatası")))? as usize;
          resource::write(handle, &vec![0u8; remaining_dib_bytes]) // Requires alloc
-              .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+              .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
 
 
         // Pad between DIB header and image data if image_data_offset > (14 + dib_header_size)
@@ -457,21 +448,20 @@ impl BmpImage {
              let padding_size = self.header.image_data_offset.checked_sub(header_end_offset)
                   .ok_or_else(|| FileSystemError::InvalidData(format!("Padding boyutu hesaplanırken taşma")))? as usize;
              resource::write(handle, &vec![0u8; padding_size]) // Requires alloc
-                  .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+                  .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
         } else if self.header.image_data_offset < header_end_offset {
-              let _ = resource::release(handle).map_err(|e| { eprintln!("WARN: Kaynak serbest bırakma hatası: {:?}", e); map_sahne_error_to_fs_error(e) });
+              let _ = resource::release(handle).map_err(|e| { eprintln!("WARN: Kaynak serbest bırakma hatası: {:?}", e); FileSystemError::from(e) });
               return Err(FileSystemError::InvalidData(format!("Görüntü verisi ofseti ({}) başlık sonundan ({}) küçük.", self.header.image_data_offset, header_end_offset)));
         }
 
 
         // Image Data
         resource::write(handle, &self.data)
-             .map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+             .map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
 
 
         // Kaynağı serbest bırak
-        resource::release(handle)
-            .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+        resource::release(handle)?; // SahneError -> FileSystemError
 
         Ok(())
     }
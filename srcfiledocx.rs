@@ -6,7 +6,7 @@
 extern crate alloc;
 
 // Gerekli temel Sahne64 tipleri ve modülleri
-use crate::{FileSystemError, SahneError, Handle}; // Hata tipleri ve Handle
+use crate::{FileSystemError, Handle}; // Hata tipleri ve Handle
 // Sahne64 resource modülü
 #[cfg(not(feature = "std"))]
 use crate::resource;
@@ -17,104 +17,18 @@ use crate::fs;
 // alloc crate for String, Vec
 use alloc::string::String;
 use alloc::vec::Vec;
-use alloc::boxed::Box; // For Box<dyn VfsNode>
 use alloc::format;
 
-// core::io traits and types (might not be strictly needed if using fs::read_at directly, but useful context)
- use core::io::{Read, Seek, SeekFrom, Error as CoreIOError, ErrorKind as CoreIOErrorKind}; // core::io
-
-
-// Helper function to map SahneError to FileSystemError (copied from other files)
+// Ortak VFS trait'leri ve hata tipi
+use crate::vfs::{FileType, Metadata, Vfs, VfsError, VfsFile};
+use crate::archivefs::{mount_archive, ArchiveFs}; // Ortak ZIP arşiv dosya sistemi
+use alloc::boxed::Box;
+use alloc::sync::Arc;
 #[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
+use crate::vfs::map_fs_error_to_vfs_error;
 
-// Helper function to map FileSystemError to VfsError
-fn map_fs_error_to_vfs_error(e: FileSystemError) -> VfsError {
-    match e {
-        // Map specific FileSystemError variants to VfsError variants
-        FileSystemError::NotFound => VfsError::NotFound,
-        FileSystemError::PermissionDenied => VfsError::PermissionDenied,
-        FileSystemError::InvalidData(msg) => VfsError::InvalidData(msg), // Keep message
-        FileSystemError::IOError(msg) => VfsError::IOError(msg), // Keep message
-        FileSystemError::NotSupported => VfsError::NotSupported,
-        // Add other mappings if FileSystemError gains more variants
-        _ => VfsError::IOError(format!("Unknown FileSystemError: {:?}", e)), // Default mapping
-    }
-}
 
-// VfsError tanımı
-#[derive(Debug)]
-pub enum VfsError {
-    NotFound,
-    PermissionDenied,
-    InvalidDescriptor, // Corresponds to an invalid Handle in the underlying layer
-    IOError(String), // Include details for better debugging
-    InvalidData(String), // Include details for parsing issues
-    NotSupported,
-    AlreadyExists, // Example VFS error
-    IsDirectory, // Example VFS error
-    NotDirectory, // Example VFS error
-    NotEmpty, // Example VFS error
-    // ... diğer VFS hataları
-}
-
-// Implement Display for VfsError (useful for error reporting)
-use core::fmt;
-impl fmt::Display for VfsError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            VfsError::NotFound => write!(f, "Entity not found"),
-            VfsError::PermissionDenied => write!(f, "Permission denied"),
-            VfsError::InvalidDescriptor => write!(f, "Invalid descriptor"),
-            VfsError::IOError(msg) => write!(f, "IO error: {}", msg),
-            VfsError::InvalidData(msg) => write!(f, "Invalid data: {}", msg),
-            VfsError::NotSupported => write!(f, "Operation not supported"),
-            VfsError::AlreadyExists => write!(f, "Entity already exists"),
-            VfsError::IsDirectory => write!(f, "Is a directory"),
-            VfsError::NotDirectory => write!(f, "Not a directory"),
-            VfsError::NotEmpty => write!(f, "Directory not empty"),
-        }
-    }
-}
-
-// Example VFS traits (these should ideally be defined in a VFS core module)
-pub trait FileSystem {
-    /// Opens a VFS node (file or directory) at the given path.
-    fn open(&self, path: &str, flags: u32) -> Result<Box<dyn VfsNode>, VfsError>;
-    // Add other file system operations like create, mkdir, remove, etc.
-     fn create(&self, path: &str, flags: u32) -> Result<Box<dyn VfsNode>, VfsError>;
-     fn mkdir(&self, path: &str) -> Result<(), VfsError>;
-     fn remove(&self, path: &str) -> Result<(), VfsError>;
-     fn rename(&self, old_path: &str, new_path: &str) -> Result<(), VfsError>;
-     fn metadata(&self, path: &str) -> Result<Metadata, VfsError>; // Need Metadata struct
-}
-
-// Need a Metadata struct if the trait includes metadata operations
- pub struct Metadata {
-    pub file_size: usize,
-    pub is_directory: bool,
-//    // Add other metadata fields like permissions, timestamps, etc.
- }
-
-
-pub trait VfsNode {
-    /// Reads data from the VFS node into the provided buffer at the specified offset.
-    /// Returns the number of bytes read.
-    fn read(&self, buffer: &mut [u8], offset: usize) -> Result<usize, VfsError>;
-
-    // Add other VFS node operations like write, seek, close, get_size, etc.
-    // fn write(&self, buffer: &[u8], offset: usize) -> Result<usize, VfsError>;
-    // fn seek(&self, pos: SeekFrom) -> Result<u64, VfsError>; // SeekFrom from core::io
-    fn size(&self) -> Result<usize, VfsError>; // Get the size of the node
-    // fn close(&self) -> Result<(), VfsError>; // Explicit close might be needed for resource management
-    // fn sync(&self) -> Result<(), VfsError>; // Flush buffered data
-}
-
-
-/// Represents a DOCX file as a VFS node, providing basic read functionality.
+/// Represents an open DOCX file as a VFS file, providing basic read functionality.
 /// DOCX files are essentially ZIP archives containing XML files.
 /// This implementation focuses on providing access to the raw bytes of the file.
 #[cfg(not(feature = "std"))] // This VFS Node implementation is for no_std/Sahne64
@@ -143,31 +57,36 @@ impl DocxFile {
         DocxFile { handle, file_size }
     }
 
-    // Removed read_all as it's redundant with VfsNode::read
+    /// Releases the underlying Sahne64 resource.
+    pub fn close(self) -> Result<(), VfsError> {
+        resource::release(self.handle)
+            .map_err(FileSystemError::from) // SahneError -> FileSystemError
+            .map_err(map_fs_error_to_vfs_error) // FileSystemError -> VfsError
+    }
 }
 
 #[cfg(not(feature = "std"))]
-impl VfsNode for DocxFile {
+impl VfsFile for DocxFile {
     /// Reads data from the DOCX file (VFS node) into the provided buffer
     /// at the specified offset using the underlying Sahne64 `read_at` syscall.
     ///
     /// # Arguments
     ///
-    /// * `buffer` - The buffer to read data into.
     /// * `offset` - The offset in the file to start reading from.
+    /// * `buffer` - The buffer to read data into.
     ///
     /// # Returns
     ///
     /// The number of bytes read, or a `VfsError` if an error occurred.
-    fn read(&self, buffer: &mut [u8], offset: usize) -> Result<usize, VfsError> {
+    fn read_at(&mut self, offset: u64, buffer: &mut [u8]) -> Result<usize, VfsError> {
         // Check if the requested read is beyond the end of the file
-        if offset >= self.file_size {
+        if offset >= self.file_size as u64 {
             return Ok(0); // Reading at or past EOF
         }
 
         // Calculate how many bytes are actually available to read from the offset
-        let bytes_available = self.file_size.checked_sub(offset).unwrap_or(0); // Should not panic if offset <= file_size
-        let bytes_to_read = cmp::min(buffer.len(), bytes_available);
+        let bytes_available = self.file_size - offset as usize; // offset < file_size checked above
+        let bytes_to_read = core::cmp::min(buffer.len(), bytes_available);
 
         if bytes_to_read == 0 {
             return Ok(0); // No bytes to read
@@ -175,56 +94,70 @@ impl VfsNode for DocxFile {
 
         // Use the assumed Sahne64 fs::read_at syscall
         // fs::read_at(handle, offset, buffer) Result<usize, SahneError> döner (varsayım)
-        let bytes_read = fs::read_at(self.handle, offset as u64, &mut buffer[..bytes_to_read])
-            .map_err(map_sahne_error_to_fs_error) // SahneError -> FileSystemError
+        let bytes_read = fs::read_at(self.handle, offset, &mut buffer[..bytes_to_read])
+            .map_err(FileSystemError::from) // SahneError -> FileSystemError
             .map_err(map_fs_error_to_vfs_error)?; // FileSystemError -> VfsError
 
         Ok(bytes_read)
     }
 
-    /// Returns the size of the DOCX file (VFS node).
-    fn size(&self) -> Result<usize, VfsError> {
-        Ok(self.file_size)
+    /// Returns the metadata of the DOCX file (read-only regular file).
+    fn metadata(&self) -> Result<Metadata, VfsError> {
+        let mut meta = Metadata::new(FileType::File, self.file_size as u64);
+        meta.mode = 0o444;
+        Ok(meta)
     }
+}
 
-    // Add other VfsNode methods if needed, mapping underlying SahneError/FileSystemError to VfsError
-     fn close(&self) -> Result<(), VfsError> {
-          resource::release(self.handle)
-              .map_err(map_sahne_error_to_fs_error) // SahneError -> FileSystemError
-              .map_err(map_fs_error_to_vfs_error) // FileSystemError -> VfsError
-     }
+/// std implementation of `DocxFile`, wrapping a host `std::fs::File`.
+#[cfg(feature = "std")]
+pub struct DocxFile {
+    file: std::fs::File,
+    file_size: usize,
+}
+
+#[cfg(feature = "std")]
+impl DocxFile {
+    pub fn new(file: std::fs::File, file_size: usize) -> Self {
+        DocxFile { file, file_size }
+    }
 }
 
-// TODO: Add a std implementation for DocxFile if needed, potentially wrapping std::fs::File
- #[cfg(feature = "std")]
- pub struct DocxFile {
-     file: std::fs::File, // Or BufReader<File>
-     file_size: usize,
- }
-//
- #[cfg(feature = "std")]
- impl DocxFile {
-     pub fn new(file: std::fs::File, file_size: usize) -> Self {
-         DocxFile { file, file_size }
-     }
- }
-//
- #[cfg(feature = "std")]
- impl VfsNode for DocxFile {
-     fn read(&self, buffer: &mut [u8], offset: usize) -> Result<usize, VfsError> {
-         use std::io::{Read, Seek, SeekFrom};
-//         // Use self.file.seek and self.file.read
-//         // Need to map std::io::Error to VfsError
-//         // This requires a mapping helper similar to map_sahne_error_to_fs_error
-//         // and then mapping FileSystemError to VfsError (or map std::io::Error directly to VfsError)
-         unimplemented!()
-     }
-//
-     fn size(&self) -> Result<usize, VfsError> {
-         Ok(self.file_size)
-     }
-//     // fn close(&self) -> Result<(), VfsError> { ... }
- }
+#[cfg(feature = "std")]
+impl VfsFile for DocxFile {
+    fn read_at(&mut self, offset: u64, buffer: &mut [u8]) -> Result<usize, VfsError> {
+        use std::io::{Read, Seek, SeekFrom};
+        if offset >= self.file_size as u64 {
+            return Ok(0);
+        }
+        let bytes_to_read = core::cmp::min(buffer.len(), self.file_size - offset as usize);
+        self.file.seek(SeekFrom::Start(offset)).map_err(|e| VfsError::IOError(format!("{}", e)))?;
+        let mut total = 0;
+        while total < bytes_to_read {
+            match self.file.read(&mut buffer[total..bytes_to_read]) {
+                Ok(0) => break,
+                Ok(n) => total += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(VfsError::IOError(format!("{}", e))),
+            }
+        }
+        Ok(total)
+    }
+
+    fn metadata(&self) -> Result<Metadata, VfsError> {
+        let mut meta = Metadata::new(FileType::File, self.file_size as u64);
+        meta.mode = 0o444;
+        Ok(meta)
+    }
+}
+
+/// Mounts the DOCX container at `mount_path` as a read-only directory tree
+/// (`word/document.xml`, `word/media/...`, `docProps/...`), decompressing
+/// entries on demand through the shared archive file system.
+/// Files without `word/document.xml` are rejected with `VfsError::InvalidData`.
+pub fn mount_docx(vfs: &Vfs, mount_path: &str, file: DocxFile) -> Result<Arc<ArchiveFs>, VfsError> {
+    mount_archive(vfs, mount_path, Box::new(file), Some("word/document.xml"))
+}
 
 
 // Redundant syscall/module definitions removed - assume they are defined elsewhere in Sahne64 API
@@ -240,50 +173,23 @@ fn main() -> Result<(), VfsError> { // Return VfsError
      // to simulate opening and reading a file via the VFS.
      // This is complex and requires a testing framework or simulation.
 
-     // Hypothetical usage:
-      let mock_fs = MockFileSystem::new(); // A mock that simulates fs/resource syscalls
-      mock_fs.add_file("sahne://files/document.docx", b"PK\x03\x04...".to_vec()); // Add dummy DOCX data
-
-      let docx_node_res = mock_fs.open("sahne://files/document.docx", 0 /* flags */);
-      match docx_node_res {
-          Ok(docx_node) => {
-              let mut buffer = [0u8; 100];
-              match docx_node.read(&mut buffer, 0) {
-                  Ok(bytes_read) => {
-                      println!("Read {} bytes from DOCX file.", bytes_read);
-                      // Process the buffer (check for ZIP magic 'PK\x03\x04')
-                      if bytes_read >= 4 && &buffer[0..4] == b"PK\x03\x04" {
-                          println!("Detected ZIP signature (PK\\x03\\x04).");
-                      } else {
-                          println!("ZIP signature not found.");
-                      }
-                  },
-                  Err(e) => eprintln!("Error reading DOCX file: {:?}", e),
+     // Hypothetical usage (handle obtained from resource::acquire):
+      let mut docx_file = DocxFile::new(handle, file_size);
+      let mut buffer = [0u8; 100];
+      match docx_file.read_at(0, &mut buffer) {
+          Ok(bytes_read) => {
+              println!("Read {} bytes from DOCX file.", bytes_read);
+              // Process the buffer (check for ZIP magic 'PK\x03\x04')
+              if bytes_read >= 4 && &buffer[0..4] == b"PK\x03\x04" {
+                  println!("Detected ZIP signature (PK\\x03\\x04).");
+              } else {
+                  println!("ZIP signature not found.");
               }
-     //         // Need to explicitly close the node if VfsNode has a close method
-               if let Some(closable) = docx_node.as_any().downcast_ref::<dyn VfsNode>() { // Requires downcasting if Box<dyn VfsNode> doesn't expose close
-                    let _ = closable.close(); // Handle error
-               } else {
-                     // If VfsNode doesn't have close, Drop should handle it if DocxFile implements Drop
-     //              // But Drop in no_std is tricky for resources.
-     //              // If DocxFile has a close method, need to call it.
-     //              // Example: If DocxFile implements Closeable trait and VfsNode trait object can be downcasted.
-               }
-     //         // Or, if DocxFile::close() is public:
-               let concrete_node = docx_node.as_any().downcast_ref::<DocxFile>().unwrap(); // Requires Any trait
-               concrete_node.close().unwrap(); // Requires close method on concrete type
-     //
-     //          // Simpler: if VfsNode trait has a close method
-                docx_node.close().unwrap(); // If close is part of VfsNode
-     //
-     //          // Assuming resource release is handled by Drop in DocxFile (as implemented now for the Handle)
-     //          // This requires careful Drop implementation in no_std.
-     //          // Current DocxFile doesn't implement Drop. Explicit release is safer.
-     //          // Let's add a close method to DocxFile and call it.
-               // And add a close method to VfsNode trait.
           },
-          Err(e) => eprintln!("Error opening DOCX file: {:?}", e),
+          Err(e) => eprintln!("Error reading DOCX file: {:?}", e),
       }
+      // The Handle is released explicitly; DocxFile does not implement Drop.
+      docx_file.close()?;
 
      eprintln!("DocxFile example (no_std) needs VFS and Sahne64 mocks to run.");
 
@@ -6,7 +6,7 @@
 extern crate alloc;
 
 // Gerekli temel Sahne64 tipleri ve modülleri
-use crate::{FileSystemError, SahneError, Handle}; // Hata tipleri ve Handle
+use crate::{FileSystemError, Handle}; // Hata tipleri ve Handle
 // Sahne64 resource modülü
 #[cfg(not(feature = "std"))]
 use crate::resource;
@@ -29,13 +29,6 @@ use core::mem; // core::mem
 use crate::{println, eprintln}; // crate kökünden veya ortak modülden import edildiği varsayılır
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map CoreIOError to FileSystemError (copied from other files)
 #[cfg(not(feature = "std"))]
 fn map_core_io_error_to_fs_error(e: CoreIOError) -> FileSystemError {
@@ -174,14 +167,13 @@ impl ElfFile {
     #[cfg(not(feature = "std"))] // Only for no_std Sahne64
     pub fn open(resource_id: &str) -> Result<Self, FileSystemError> { // FileSystemError döner
         // Kaynağı edin
-        let handle = resource::acquire(resource_id, resource::MODE_READ)
-            .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+        let handle = resource::acquire(resource_id, resource::MODE_READ)?; // SahneError -> FileSystemError
 
         // Dosyanın boyutunu al
          let file_stat = fs::fstat(handle)
              .map_err(|e| {
                   let _ = resource::release(handle); // Hata durumunda handle'ı serbest bırak
-                  map_sahne_error_to_fs_error(e)
+                  FileSystemError::from(e)
               })?;
          let file_size = file_stat.size as u64;
 
@@ -207,7 +199,7 @@ impl ElfFile {
         // Kaynağı serbest bırak (ELF verileri bellekte tutulduğu için handle'a artık ihtiyaç yok)
         let _ = resource::release(handle).map_err(|e| {
              eprintln!("WARN: Sahne64 kaynak serbest bırakma hatası: {:?}", e);
-             map_sahne_error_to_fs_error(e)
+             FileSystemError::from(e)
          });
 
 
@@ -6,7 +6,7 @@
 extern crate alloc;
 
 // Gerekli temel Sahne64 tipleri ve modülleri
-use crate::{FileSystemError, SahneError, Handle}; // Hata tipleri ve Handle
+use crate::{FileSystemError, Handle}; // Hata tipleri ve Handle
 // Sahne64 resource modülü
 #[cfg(not(feature = "std"))]
 use crate::resource;
@@ -41,13 +41,6 @@ use byteorder::{LittleEndian, ReadBytesExt, ByteOrder}; // LittleEndian, ReadByt
 use crate::{println, eprintln}; // crate kökünden veya ortak modülden import edildiği varsayılır
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -594,14 +587,13 @@ pub fn read_fbx_file<P: AsRef<Path>>(file_path: P) -> Result<Vec<FbxNode>, FileS
 #[cfg(not(feature = "std"))]
 pub fn read_fbx_file(file_path: &str) -> Result<Vec<FbxNode>, FileSystemError> { // FileSystemError döner
     // Kaynağı edin
-    let handle = resource::acquire(file_path, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(file_path, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     // Dosyanın boyutunu al (SahneResourceReader için gerekli)
      let file_stat = fs::fstat(handle)
          .map_err(|e| {
               let _ = resource::release(handle); // Hata durumunda handle'ı serbest bırak
-              map_sahne_error_to_fs_error(e)
+              FileSystemError::from(e)
           })?;
      let file_size = file_stat.size as u64;
 
@@ -651,7 +643,7 @@ pub fn read_fbx_file(file_path: &str) -> Result<Vec<FbxNode>, FileSystemError> {
     // Kaynağı serbest bırak
     let _ = resource::release(handle).map_err(|e| {
          eprintln!("WARN: Sahne64 kaynak serbest bırakma hatası: {:?}", e);
-         map_sahne_error_to_fs_error(e)
+         FileSystemError::from(e)
      });
 
     Ok(nodes)
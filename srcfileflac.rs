@@ -6,7 +6,7 @@
 extern crate alloc;
 
 // Gerekli temel Sahne64 tipleri ve modülleri
-use crate::{FileSystemError, SahneError, Handle}; // Hata tipleri, SahneError, Handle
+use crate::{FileSystemError, Handle}; // Hata tipleri, Handle
 // Sahne64 resource modülü
 #[cfg(not(feature = "std"))]
 use crate::resource;
@@ -47,13 +47,6 @@ use core::fmt;
 use crate::{println, eprintln}; // crate kökünden veya ortak modülden import edildiği varsayılır
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -129,8 +122,7 @@ impl FlacFile {
         // we can only confirm the file exists and store its path/Handle.
         // For now, we'll acquire and immediately release the Handle.
 
-        let handle = resource::acquire(path, resource::MODE_READ)
-            .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+        let handle = resource::acquire(path, resource::MODE_READ)?; // SahneError -> FileSystemError
 
         // Note: To read metadata, we would need to read specific blocks (like STREAMINFO, VORBIS_COMMENT)
         // from the FLAC file format using the Handle and a Reader/Seeker.
@@ -140,7 +132,7 @@ impl FlacFile {
         // Release the handle immediately as we are not reading data yet.
         let _ = resource::release(handle).map_err(|e| {
              eprintln!("WARN: Sahne64 kaynak serbest bırakma hatası: {:?}", e); // no_std print
-             map_sahne_error_to_fs_error(e) // Return this as a warning, perhaps not critical for file opening success
+             FileSystemError::from(e) // Return this as a warning, perhaps not critical for file opening success
          });
 
         // Metadata fields are None as they cannot be parsed in this no_std environment.
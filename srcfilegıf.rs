@@ -6,7 +6,7 @@
 extern crate alloc;
 
 // Gerekli temel Sahne64 tipleri ve modülleri (assume these are defined elsewhere)
-use crate::{FileSystemError, SahneError, Handle}; // Hata tipleri, SahneError, Handle
+use crate::{FileSystemError, Handle}; // Hata tipleri, Handle
 // Sahne64 resource modülü (assume defined elsewhere)
 #[cfg(not(feature = "std"))]
 use crate::resource;
@@ -48,13 +48,6 @@ use byteorder::{LittleEndian, ReadBytesExt, ByteOrder}; // LittleEndian, ReadByt
 use crate::{println, eprintln}; // crate kökünden veya ortak modülden import edildiği varsayılır
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -258,14 +251,13 @@ pub fn read_gif_header_from_file<P: AsRef<Path>>(file_path: P) -> Result<GifHead
 #[cfg(not(feature = "std"))]
 pub fn read_gif_header_from_file(file_path: &str) -> Result<GifHeader, FileSystemError> { // FileSystemError döner
     // Kaynağı edin
-    let handle = resource::acquire(file_path, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(file_path, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     // Dosyanın boyutunu al (SahneResourceReader için gerekli)
      let file_stat = fs::fstat(handle)
          .map_err(|e| {
               let _ = resource::release(handle); // Hata durumunda handle'ı serbest bırak
-              map_sahne_error_to_fs_error(e)
+              FileSystemError::from(e)
           })?;
      let file_size = file_stat.size as u64;
 
@@ -284,7 +276,7 @@ pub fn read_gif_header_from_file(file_path: &str) -> Result<GifHeader, FileSyste
     // Kaynağı serbest bırak (Sadece başlığı okuduk)
     let _ = resource::release(handle).map_err(|e| {
          eprintln!("WARN: Sahne64 kaynak serbest bırakma hatası: {:?}", e); // no_std print
-         map_sahne_error_to_fs_error(e) // Return this error if crucial, or just log
+         FileSystemError::from(e) // Return this error if crucial, or just log
      });
 
 
@@ -9,7 +9,7 @@
 extern crate alloc;
 
 // Gerekli temel Sahne64 tipleri ve modülleri (assume these are defined elsewhere)
-use crate::{FileSystemError, SahneError, Handle}; // Hata tipleri, SahneError, Handle
+use crate::{FileSystemError, Handle}; // Hata tipleri, Handle
 // Sahne64 resource modülü (assume defined elsewhere)
 #[cfg(not(feature = "std"))]
 use crate::resource;
@@ -50,13 +50,6 @@ use core::cmp; // For min
 use crate::{println, eprintln}; // crate kökünden veya ortak modülünden import edildiği varsayılır
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -212,14 +205,13 @@ impl MdFile {
     #[cfg(not(feature = "std"))]
     pub fn new(path: &str) -> Result<Self, FileSystemError> { // Return FileSystemError
         // Acquire the resource
-        let handle = resource::acquire(path, resource::MODE_READ)
-            .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+        let handle = resource::acquire(path, resource::MODE_READ)?; // SahneError -> FileSystemError
 
         // Get file size to create SahneResourceReader
          let file_stat = fs::fstat(handle)
              .map_err(|e| {
                   let _ = resource::release(handle); // Release on error
-                  map_sahne_error_to_fs_error(e)
+                  FileSystemError::from(e)
               })?;
          let file_size = file_stat.size as u64;
 
@@ -266,7 +258,7 @@ impl MdFile {
         // Release the resource
         let _ = resource::release(handle).map_err(|e| {
              eprintln!("WARN: Sahne64 kaynak serbest bırakma hatası: {:?}", e); // no_std print
-             map_sahne_error_to_fs_error(e) // Return this error if crucial, or just log
+             FileSystemError::from(e) // Return this error if crucial, or just log
          });
 
 
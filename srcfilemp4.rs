@@ -16,7 +16,7 @@ use std::path::Path; // For std file paths
 
 
 // Sahne64 fonksiyonlarını kullanmak için bu modülü içeri aktar (assume these are defined elsewhere)
-use crate::{fs, resource, SahneError, FileSystemError, Handle}; // fs, resource, SahneError, FileSystemError, Handle
+use crate::{fs, resource, FileSystemError, Handle}; // fs, resource, FileSystemError, Handle
 
 // byteorder crate (no_std compatible)
 use byteorder::{BigEndian, ReadBytesExt, ByteOrder}; // BigEndian, ReadBytesExt, ByteOrder trait/types
@@ -42,13 +42,6 @@ use core::io::{Read, Seek, SeekFrom, Error as CoreIOError, ErrorKind as CoreIOEr
 use crate::{println, eprintln}; // crate kökünden veya ortak modülünden import edildiği varsayılır
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -391,14 +384,13 @@ pub fn open_mp4_file<P: AsRef<Path>>(file_path: P) -> Result<Mp4Parser<BufReader
 #[cfg(not(feature = "std"))]
 pub fn open_mp4_file(file_path: &str) -> Result<Mp4Parser<SahneResourceReader>, FileSystemError> { // Return FileSystemError
     // Kaynağı edin
-    let handle = resource::acquire(file_path, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(file_path, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     // Dosyanın boyutunu al (SahneResourceReader için gerekli)
      let file_stat = fs::fstat(handle)
          .map_err(|e| {
               let _ = resource::release(handle); // Hata durumunda handle'ı serbest bırak
-              map_sahne_error_to_fs_error(e)
+              FileSystemError::from(e)
           })?;
      let file_size = file_stat.size as u64;
 
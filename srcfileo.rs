@@ -15,7 +15,7 @@ use std::path::Path; // For std file paths
 
 
 // Gerekli Sahne64 modüllerini ve yapılarını içeri aktar (assume these are defined elsewhere)
-use crate::{fs, resource, SahneError, FileSystemError, Handle}; // fs, resource, SahneError, FileSystemError, Handle
+use crate::{fs, resource, FileSystemError, Handle}; // fs, resource, FileSystemError, Handle
 
 // alloc crate for String, Vec
 use alloc::string::String;
@@ -37,13 +37,6 @@ use core::io::{Read, Seek, SeekFrom, Error as CoreIOError, ErrorKind as CoreIOEr
 use crate::{println, eprintln}; // crate kökünden veya ortak modülünden import edildiği varsayılır
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -162,7 +155,7 @@ impl<R: Read + Seek> OFile<R> {
     /// The resource is also closed automatically when the OFile is dropped.
     pub fn close(&mut self) -> Result<(), FileSystemError> { // Return FileSystemError
         if let Some(handle) = self.handle.take() { // Use take() to prevent double-closing
-             resource::release(handle).map_err(map_sahne_error_to_fs_error)?; // Map SahneError
+             resource::release(handle)?; // Map SahneError
         }
         Ok(())
     }
@@ -215,14 +208,13 @@ pub fn open_file<P: AsRef<Path>>(file_path: P) -> Result<OFile<BufReader<File>>,
 #[cfg(not(feature = "std"))]
 pub fn open_file(file_path: &str) -> Result<OFile<SahneResourceReader>, FileSystemError> { // Return FileSystemError
     // Kaynağı edin
-    let handle = resource::acquire(file_path, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(file_path, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     // Dosyanın boyutunu al (SahneResourceReader için gerekli)
      let file_stat = fs::fstat(handle)
          .map_err(|e| {
               let _ = resource::release(handle); // Hata durumunda handle'ı serbest bırak
-              map_sahne_error_to_fs_error(e)
+              FileSystemError::from(e)
           })?;
      let file_size = file_stat.size as u64;
 
@@ -6,6 +6,14 @@
   fs,
   SahneError,
  };
+ use crate::archivefs::{check_odf_mimetype, map_archive_error_to_vfs_error, ArchiveFs};
+ use crate::vfs::{Vfs, VfsError, VfsFile};
+ use alloc::boxed::Box;
+ use alloc::string::String;
+ use alloc::sync::Arc;
+
+ /// ODT belgelerinin "mimetype" girdisindeki tür.
+ pub const ODT_MIMETYPE: &str = "application/vnd.oasis.opendocument.text";
 
  pub struct OdfFile {
   pub content: String,
@@ -23,6 +31,19 @@
   }
  }
 
+ /// ODT kapsayıcısını `mount_path`e salt okunur dizin ağacı olarak bağlar
+ /// (content.xml, styles.xml, Pictures/...). "mimetype" girdisi ODT değilse
+ /// veya content.xml yoksa bağlanmadan InvalidData döner.
+ pub fn mount_odt(vfs: &Vfs, mount_path: &str, source: Box<dyn VfsFile>) -> Result<Arc<ArchiveFs>, VfsError> {
+  let archive = Arc::new(ArchiveFs::open(source).map_err(map_archive_error_to_vfs_error)?);
+  check_odf_mimetype(&archive, ODT_MIMETYPE)?;
+  if !archive.contains("content.xml") {
+  return Err(VfsError::InvalidData(String::from("ODT içinde content.xml yok")));
+  }
+  vfs.mount(mount_path, archive.clone())?;
+  Ok(archive)
+ }
+
  #[cfg(feature = "std")]
  #[cfg(test)]
  mod tests {
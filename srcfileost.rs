@@ -5,69 +5,35 @@
 #[cfg_attr(not(feature = "std"), macro_use)]
 extern crate alloc;
 
-// Eğer std özelliği aktifse, standart kütüphaneyi kullan.
-#[cfg(feature = "std")]
-use std::io::{Read as StdRead, Seek as StdSeek, SeekFrom as StdSeekFrom, Error as StdIOError, ErrorKind as StdIOErrorKind};
-#[cfg(feature = "std")]
-use std::boxed::Box as StdBox; // Use std Box
-#[cfg(feature = "std")]
-use std::sync::Arc as StdArc; // Use std Arc
-
-// Gerekli Sahne64 modülleri ve yapılarını içeri aktar (assume these are defined elsewhere)
-// Assuming VFile, VFileOps, FileSystem, VFileRef are defined in crate::fs
-// Assuming FileSystemError, SahneError, Handle, resource are defined in crate
-use crate::{fs::{FileSystem, VFile, VFileOps, VFileRef}, FileSystemError, SahneError, resource}; // fs, FileSystemError, SahneError
-
-// core::result, core::fmt, core::io
+// Ortak VFS trait'leri (crate::vfs)
+use crate::vfs::{
+    flags_allow_write, DirEntry, FileSystem, FileType, Metadata, NodeRef, ReadDir, VfsError, VfsFile, VfsNode,
+};
+
+// core::result, core::any
+use core::any::Any;
 use core::result::Result;
-use core::fmt;
-use core::io::{Read, Seek, SeekFrom, Error as CoreIOError, ErrorKind as CoreIOErrorKind}; // core::io
-
-// alloc crate for Box, Arc (if VFileRef is Arc), String, Vec
-use alloc::boxed::Box; // Use alloc Box
-#[cfg(not(feature = "std"))]
-use alloc::sync::Arc; // Use alloc Arc if needed for VFileRef
-
-// Assuming VFileRef is defined as something like Arc<dyn VFileOps + Send + Sync>
-// The bounds Send + Sync are often required by Arc for multi-threading safety.
-// We will add these bounds to the dyn traits used in this file.
-use core::marker::Send;
-#[cfg(any(feature = "std", target_has_atomic = "ptr"))] // Sync requires atomics for no_std
-use core::marker::Sync;
-
-
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
 
-// Helper function to map std::io::Error to FileSystemError (copied from other files)
-#[cfg(feature = "std")]
-fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
-    FileSystemError::IOError(format!("IO Error: {}", e))
-}
+// alloc crate for Box, Arc, String, Vec
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec; // Requires alloc
 
-// Helper function to map CoreIOError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_core_io_error_to_fs_error(e: CoreIOError) -> FileSystemError {
-     FileSystemError::IOError(format!("CoreIOError: {:?}", e))
-     // TODO: Implement a proper mapping based on CoreIOErrorKind
-}
+// Paylaşılan kaynak için spin Mutex
+use spin::Mutex;
 
-// No need to redefine Read, Seek, SeekFrom traits and impls.
-// Use core::io traits directly and map errors where needed.
 
+/// PST verisinin okunduğu kaynak: herhangi bir açık VFS dosyası (ör. SADAK üzerindeki
+/// bir .pst dosyası). Tüm açık `PSTFile`'lar aynı kaynağı paylaşır.
+type PstSource = Arc<Mutex<Box<dyn VfsFile>>>;
 
-/// `PSTFile` structure representing a PST file adapted to VFileOps.
-/// It wraps an underlying `Read + Seek + Send + Sync` data source.
+/// `PSTFile` structure representing an open PST file adapted to `VfsFile`.
+/// It reads through the shared underlying source.
 /// PST files are treated as read-only.
 pub struct PSTFile {
     /// The underlying data source for the PST file.
-    /// `Box<dyn core::io::Read + core::io::Seek + Send + Sync>`.
-    /// The `Send + Sync` bounds are likely required by the VFileRef (Arc).
-    data: Box<dyn core::io::Read + core::io::Seek + Send + Sync>,
+    data: PstSource,
     /// The size of the PST file in bytes.
     size: u64,
 }
@@ -77,103 +43,146 @@ impl PSTFile {
     ///
     /// # Arguments
     ///
-    /// * `data`: The underlying readable and seekable data for the PST file.
-    ///           Must implement `core::io::Read`, `core::io::Seek`, `Send`, and `Sync`.
+    /// * `data`: The shared source the PST bytes are read from.
     /// * `size`: The size of the PST file.
-    pub fn new(data: Box<dyn core::io::Read + core::io::Seek + Send + Sync>, size: u64) -> Self {
+    fn new(data: PstSource, size: u64) -> Self {
         PSTFile { data, size }
     }
 }
 
-// Assuming VFileOps trait is defined like:
- pub trait VFileOps: Send + Sync { // Requires Send + Sync for Arc
-    fn read(&mut self, buf: &mut [u8], offset: u64) -> Result<usize, FileSystemError>;
-    fn write(&mut self, buf: &[u8], offset: u64) -> Result<usize, FileSystemError>;
-    fn size(&self) -> u64;
- }
-// And VFileRef is type alias: type VFileRef = Arc<dyn VFileOps>; or Arc<dyn VFileOps + Send + Sync>;
-
-impl VFileOps for PSTFile {
+impl VfsFile for PSTFile {
     /// Reads data from the file into `buf` starting at the given `offset`.
     ///
     /// # Arguments
     ///
-    /// * `buf`: The byte slice to read data into.
     /// * `offset`: The file offset to start reading from.
+    /// * `buf`: The byte slice to read data into.
     ///
     /// # Returns
     ///
     /// On success, returns `Ok(usize)` number of bytes read.
-    /// On failure, returns a `FileSystemError`.
-    // Assuming the VFileOps::read signature is &mut self
-    fn read(&mut self, buf: &mut [u8], offset: u64) -> Result<usize, FileSystemError> { // Return FileSystemError
-        // Seek to the specified offset. Map core::io::Error to FileSystemError.
-        self.data.seek(core::io::SeekFrom::Start(offset)).map_err(map_core_io_error_to_fs_error)?;
-
-        // Read data into buf and return the result. Map core::io::Error to FileSystemError.
-        self.data.read(buf).map_err(map_core_io_error_to_fs_error)
+    /// On failure, returns a `VfsError`.
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> Result<usize, VfsError> {
+        if offset >= self.size {
+            return Ok(0);
+        }
+        let available = (self.size - offset) as usize;
+        let len = core::cmp::min(buf.len(), available);
+        self.data.lock().read_at(offset, &mut buf[..len])
     }
 
-    /// Writes data to the file (Unsupported for PST files).
-    ///
-    /// This method always returns a `PermissionDenied` error as writing to PST files is not supported.
-    // Assuming the VFileOps::write signature is &mut self
-    fn write(&mut self, _buf: &[u8], _offset: u64) -> Result<usize, FileSystemError> { // Return FileSystemError
-        Err(FileSystemError::PermissionDenied)
+    // write_at: varsayılan (ReadOnly), PST dosyalarına yazma desteklenmez.
+
+    /// Returns the metadata of the file.
+    fn metadata(&self) -> Result<Metadata, VfsError> {
+        Ok(pst_metadata(self.size))
+    }
+}
+
+fn pst_metadata(size: u64) -> Metadata {
+    let mut meta = Metadata::new(FileType::File, size);
+    meta.mode = 0o444;
+    meta.inode = 2;
+    meta
+}
+
+/// PST dosyasının düğümü.
+struct PstNode {
+    data: PstSource,
+    size: u64,
+}
+
+impl VfsNode for PstNode {
+    fn metadata(&self) -> Result<Metadata, VfsError> {
+        Ok(pst_metadata(self.size))
     }
 
-    /// Returns the size of the file.
-    fn size(&self) -> u64 {
-        self.size
+    fn open(&self, flags: u32) -> Result<Box<dyn VfsFile>, VfsError> {
+        if flags_allow_write(flags) {
+            return Err(VfsError::ReadOnly);
+        }
+        Ok(Box::new(PSTFile::new(self.data.clone(), self.size)))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
     }
 }
 
-/// `PSTFileSystem` structure representing a file system that can handle PST files.
-pub struct PSTFileSystem {}
+/// Tek bir PST dosyası içeren kök dizin.
+struct PstRoot {
+    name: String,
+    file: Arc<PstNode>,
+}
 
-impl PSTFileSystem {
-    /// Creates a new `PSTFileSystem` instance.
-    pub fn new() -> Self {
-        PSTFileSystem {}
+impl VfsNode for PstRoot {
+    fn metadata(&self) -> Result<Metadata, VfsError> {
+        let mut meta = Metadata::new(FileType::Directory, 0);
+        meta.mode = 0o555;
+        meta.inode = 1;
+        Ok(meta)
+    }
+
+    fn open(&self, _flags: u32) -> Result<Box<dyn VfsFile>, VfsError> {
+        Err(VfsError::IsDirectory)
+    }
+
+    fn lookup(&self, name: &str) -> Result<NodeRef, VfsError> {
+        if name == self.name {
+            Ok(self.file.clone())
+        } else {
+            Err(VfsError::NotFound)
+        }
+    }
+
+    fn read_dir(&self) -> Result<ReadDir, VfsError> {
+        Ok(ReadDir::new(vec![DirEntry { name: self.name.clone(), file_type: FileType::File, inode: 2 }])) // Requires alloc
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
     }
 }
 
-// Assuming FileSystem trait is defined like:
- pub trait FileSystem: Send + Sync { // Requires Send + Sync for Arc
-    fn open(&self, path: &str, data: Box<dyn core::io::Read + core::io::Seek + Send + Sync>, size: u64) -> Result<VFileRef, FileSystemError>;
-//    // Other filesystem operations like create, unlink, stat, etc.
- }
+/// `PSTFileSystem` structure representing a read-only file system that exposes a PST file.
+/// Mounted at a path, the root directory contains the PST file under its name.
+pub struct PSTFileSystem {
+    root: Arc<PstRoot>,
+}
 
-impl FileSystem for PSTFileSystem {
-    /// Opens a `VFile` instance for the given `path`.
-    ///
-    /// If the `path` ends with ".pst", a `PSTFile` wrapping the provided `data`
-    /// and `size` is created and returned within a `VFileRef`.
-    /// Otherwise, a `FileNotFound` error is returned.
+impl PSTFileSystem {
+    /// Creates a new `PSTFileSystem` instance.
     ///
     /// # Arguments
     ///
-    /// * `path`: The path of the file to open.
-    /// * `data`: The underlying file data source, must implement `core::io::Read + core::io::Seek + Send + Sync`.
+    /// * `name`: The file name shown in the root directory; must end with ".pst".
+    /// * `data`: The underlying file data source.
     /// * `size`: The size of the file.
     ///
     /// # Returns
     ///
-    /// On success, returns `Ok` with the `VFileRef` wrapping the `PSTFile`.
-    /// On failure, returns a `FileSystemError`.
-    // Assuming the FileSystem::open signature matches the trait definition assumed above
-    fn open(&self, path: &str, data: Box<dyn core::io::Read + core::io::Seek + Send + Sync>, size: u64) -> Result<VFileRef, FileSystemError> { // Return FileSystemError
-        // Check if the file path ends with ".pst".
-        if path.ends_with(".pst") {
-            // If it's a PST file, create a PSTFile and wrap it in a VFileRef.
-            let pst_file_ops: Box<dyn VFileOps> = Box::new(PSTFile::new(data, size));
-            // VFile::new typically wraps VFileOps in Arc<Mutex<...>> or similar for thread safety and shared ownership.
-            // Assuming VFile::new handles the Box<dyn VFileOps> and returns VFileRef (Arc<dyn VFileOps + Send + Sync>).
-            Ok(crate::fs::VFile::new(pst_file_ops)) // Assuming crate::fs::VFile::new takes Box<dyn VFileOps> and returns VFileRef
-        } else {
-            // Return FileSystemError::FileNotFound for unsupported file types or paths.
-            Err(FileSystemError::FileNotFound(format!("Unsupported file type for PSTFileSystem: {}", path))) // Use FileSystemError::FileNotFound
+    /// `VfsError::NotSupported` for non-PST names.
+    pub fn new(name: &str, data: Box<dyn VfsFile>, size: u64) -> Result<Self, VfsError> {
+        // Check if the file name ends with ".pst".
+        if !name.ends_with(".pst") || name.contains('/') {
+            return Err(VfsError::NotSupported);
         }
+        let file = Arc::new(PstNode { data: Arc::new(Mutex::new(data)), size });
+        Ok(PSTFileSystem { root: Arc::new(PstRoot { name: name.to_string(), file }) })
+    }
+}
+
+impl FileSystem for PSTFileSystem {
+    fn root(&self) -> NodeRef {
+        self.root.clone()
+    }
+
+    fn fs_type(&self) -> &str {
+        "pst"
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
     }
 }
 
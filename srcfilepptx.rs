@@ -21,12 +21,18 @@ use std::io::Cursor as StdCursor; // Use std Cursor for in-memory read in tests/
 
 
 // Gerekli Sahne64 modülleri ve yapılarını içeri aktar (assume these are defined elsewhere)
-use crate::{fs, resource, SahneError, FileSystemError, Handle}; // fs, resource, SahneError, FileSystemError, Handle
+use crate::{fs, resource, FileSystemError, Handle}; // fs, resource, FileSystemError, Handle
 
 // alloc crate for String, Vec, format!
 use alloc::string::String;
 use alloc::vec::Vec;
 use alloc::format;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+
+// Ortak VFS ve ZIP arşiv dosya sistemi
+use crate::vfs::{Vfs, VfsError, VfsFile};
+use crate::archivefs::{mount_archive, ArchiveFs};
 
 
 // core::result, core::option, core::fmt, core::cmp, core::ops::Drop, core::io
@@ -43,13 +49,6 @@ use core::io::{Read, Seek, SeekFrom, Error as CoreIOError, ErrorKind as CoreIOEr
 use crate::{println, eprintln}; // crate kökünden veya ortak modülünden import edildiği varsayılır
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -76,7 +75,7 @@ fn map_zip_error_to_fs_error(e: zip::Error) -> FileSystemError {
         zip::Error::InvalidArchive(msg) => FileSystemError::InvalidData(format!("Zip Invalid Archive: {}", msg)),
         zip::Error::UnsupportedArchive(msg) => FileSystemError::InvalidData(format!("Zip Unsupported Archive: {}", msg)),
         zip::Error::UnsupportedFeature(msg) => FileSystemError::InvalidData(format!("Zip Unsupported Feature: {}", msg)),
-        zip::Error::FileNotFound => FileSystemError::FileNotFound(format!("Zip entry not found")), // Map zip's FileNotFound
+        zip::Error::FileNotFound => FileSystemError::NotFound(format!("Zip entry not found")), // Map zip's FileNotFound
     }
 }
 
@@ -292,14 +291,13 @@ pub fn open_pptx_file<P: AsRef<Path>>(file_path: P) -> Result<PptxFile<BufReader
 #[cfg(not(feature = "std"))]
 pub fn open_pptx_file(file_path: &str) -> Result<PptxFile<SahneResourceReader>, FileSystemError> { // Return FileSystemError
     // Kaynağı edin
-    let handle = resource::acquire(file_path, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(file_path, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     // Dosyanın boyutunu al (SahneResourceReader için gerekli)
      let file_stat = fs::fstat(handle)
          .map_err(|e| {
               let _ = resource::release(handle); // Hata durumunda handle'ı serbest bırak
-              map_sahne_error_to_fs_error(e)
+              FileSystemError::from(e)
           })?;
      let file_size = file_stat.size as u64;
 
@@ -311,6 +309,16 @@ pub fn open_pptx_file(file_path: &str) -> Result<PptxFile<SahneResourceReader>,
 }
 
 
+/// Mounts a PPTX file at `mount_path` as a read-only directory tree
+/// (`ppt/slides/slide1.xml`, `ppt/media/...`). Works in both std and no_std
+/// builds, since entries are decompressed by the shared archive file system
+/// instead of the zip crate.
+/// Archives without `ppt/presentation.xml` are rejected with `VfsError::InvalidData`.
+pub fn mount_pptx(vfs: &Vfs, mount_path: &str, source: Box<dyn VfsFile>) -> Result<Arc<ArchiveFs>, VfsError> {
+    mount_archive(vfs, mount_path, source, Some("ppt/presentation.xml"))
+}
+
+
 // Example main function (no_std)
 #[cfg(feature = "example_pptx")] // Different feature flag
 #[cfg(not(feature = "std"))] // Only compile for no_std
@@ -504,7 +512,7 @@ mod tests {
            let result = pptx_file.read_entry_data("non_existent.txt");
            assert!(result.is_err());
            match result.unwrap_err() {
-               FileSystemError::FileNotFound(msg) => { // Mapped from zip::Error::FileNotFound
+               FileSystemError::NotFound(msg) => { // Mapped from zip::Error::FileNotFound
                    assert!(msg.contains("Zip entry not found"));
                },
                _ => panic!("Beklenenden farklı hata türü: {:?}", result.unwrap_err()),
@@ -2,16 +2,17 @@
  #![allow(dead_code)] // Keep if needed, but removing redundant one from top level is better
 
 // Necessary imports from the crate (assuming they are defined in lib.rs or other modules)
-use crate::vfs::{VfsNode, VfsNodeType, VFileOps}; // Assuming VFileOps is here and uses &self, u64, FileSystemError
-use crate::FileSystemError; // Assuming FileSystemError is defined
+use crate::vfs::{flags_allow_write, FileType, Metadata, SetMetadata, VfsError, VfsFile, VfsNode, O_ACCMODE, O_APPEND, O_WRONLY};
 
 // alloc crate for String, Vec
 extern crate alloc; // Ensure alloc is available
+use alloc::boxed::Box;
 use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 
-// core sync primitive
-use core::sync::Mutex; // Requires target_has_atomic = "cas" or similar
+// core any for VfsNode::as_any
+use core::any::Any;
 
 // core cmp for min
 use core::cmp;
@@ -19,81 +20,104 @@ use core::cmp;
 // core result type
 use core::result::Result;
 
+// spin Mutex (no_std uyumlu)
+use spin::Mutex;
+
 
 /// Represents an in-memory file within the VFS.
-/// Stores data in a Mutex-protected vector.
+/// Stores data in a Mutex-protected vector shared with its open handles.
 pub struct File {
     pub name: String,
-    data: Mutex<Vec<u8>>, // Mutex for thread-safe access to the data vector
-    pub node: VfsNode, // Associated VFS node
+    data: Arc<Mutex<Vec<u8>>>, // Shared with every open handle of this file
+    meta: Arc<Mutex<Metadata>>, // Mode, owner and timestamps (size is taken from data)
 }
 
-// Define a specific error type for in-memory file operations if needed,
-// or map directly to FileSystemError. Let's map directly for simplicity here.
- #[derive(Debug)]
- pub enum InMemoryFileError {
-     InvalidOffset,
-//     // Add other specific errors if necessary
- }
-//
- impl fmt::Display for InMemoryFileError { ... }
-//
- fn map_in_memory_file_error_to_fs_error(e: InMemoryFileError) -> FileSystemError { ... }
-
-
 impl File {
-    /// Creates a new in-memory file instance.
+    /// Creates a new, empty in-memory file instance.
     ///
     /// # Arguments
     ///
     /// * `name`: The name of the file.
-    /// * `node`: The associated VFS node.
-    pub fn new(name: String, node: VfsNode) -> Self {
+    /// * `inode`: The inode number reported in the metadata.
+    pub fn new(name: String, inode: u64) -> Self {
+        let mut meta = Metadata::new(FileType::File, 0);
+        meta.inode = inode;
         File {
             name,
-            data: Mutex::new(Vec::new()), // Initialize with an empty vector
-            node,
+            data: Arc::new(Mutex::new(Vec::new())), // Initialize with an empty vector
+            meta: Arc::new(Mutex::new(meta)),
+        }
+    }
+
+    /// Returns the size of the file in bytes.
+    pub fn size(&self) -> u64 {
+        self.data.lock().len() as u64
+    }
+}
+
+impl VfsNode for File {
+    fn metadata(&self) -> Result<Metadata, VfsError> {
+        let mut meta = self.meta.lock().clone();
+        meta.size = self.size();
+        Ok(meta)
+    }
+
+    fn set_metadata(&self, changes: &SetMetadata) -> Result<(), VfsError> {
+        if let Some(size) = changes.size {
+            self.data.lock().resize(size as usize, 0);
+        }
+        let mut meta = self.meta.lock();
+        if let Some(mode) = changes.mode {
+            meta.mode = mode & 0o7777;
+        }
+        if let Some(uid) = changes.uid {
+            meta.uid = uid;
         }
+        if let Some(gid) = changes.gid {
+            meta.gid = gid;
+        }
+        if let Some(atime) = changes.atime {
+            meta.atime = atime;
+        }
+        if let Some(mtime) = changes.mtime {
+            meta.mtime = mtime;
+        }
+        Ok(())
+    }
+
+    /// Opens the file; every handle shares the same data vector.
+    fn open(&self, flags: u32) -> Result<Box<dyn VfsFile>, VfsError> {
+        Ok(Box::new(OpenFile { data: self.data.clone(), meta: self.meta.clone(), flags }))
     }
 
-    // The read, write, size methods will now be part of the VFileOps implementation
-    // and will match the VFileOps trait signature.
-    // We keep them here as internal helpers if needed, but the primary interface
-    // should be through VFileOps. Let's remove the redundant implementations here
-    // and rely solely on the VFileOps impl below.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
-// Assuming VFileOps trait is defined like:
- pub trait VFileOps: Send + Sync { // Requires Send + Sync for Arc (if VFileRef is Arc)
-    fn read(&self, buf: &mut [u8], offset: u64) -> Result<usize, FileSystemError>; // &self if managing own position
-    fn write(&self, buf: &[u8], offset: u64) -> Result<usize, FileSystemError>; // &self if managing own position
-    fn size(&self) -> u64;
-//    // Potentially other methods like flush, truncate, etc.
- }
-//
-// Based on the original code's use of `&self` and explicit `offset`, we assume
-// VFileOps::read and VFileOps::write take `&self`. The offset type should be `u64`.
-
-// Add Send and Sync bounds if required by VFileOps and VfsNode (e.g., if VfsNode is Arc<Mutex<...>>)
- #[cfg(any(feature = "std", target_has_atomic = "ptr"))] // Sync requires atomics for no_std
- use core::marker::Sync;
- use core::marker::Send;
-
-impl VFileOps for File {
+/// An open handle to an in-memory `File`.
+struct OpenFile {
+    data: Arc<Mutex<Vec<u8>>>,
+    meta: Arc<Mutex<Metadata>>,
+    flags: u32,
+}
+
+impl VfsFile for OpenFile {
     /// Reads data from the file into `buf` starting at the given `offset`.
     ///
     /// # Arguments
     ///
-    /// * `buf`: The byte slice to read data into.
     /// * `offset`: The file offset (in bytes) to start reading from.
+    /// * `buf`: The byte slice to read data into.
     ///
     /// # Returns
     ///
-    /// On success, returns `Ok(usize)` number of bytes read.
-    /// On failure, returns a `FileSystemError`.
-    // Assuming the VFileOps::read signature is &self, u64 offset, Result<usize, FileSystemError>
-    fn read(&self, buf: &mut [u8], offset: u64) -> Result<usize, FileSystemError> { // Return FileSystemError
-        let data = self.data.lock().map_err(|_| FileSystemError::Other(String::from("Failed to lock mutex")))?; // Lock the mutex, map poisoning error
+    /// On success, returns `Ok(usize)` number of bytes read (0 at or past EOF).
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> Result<usize, VfsError> {
+        if self.flags & O_ACCMODE == O_WRONLY {
+            return Err(VfsError::InvalidDescriptor);
+        }
+        let data = self.data.lock();
 
         // Check if offset is out of bounds (u64 to usize conversion safety)
         if offset > usize::MAX as u64 || offset as usize >= data.len() {
@@ -101,86 +125,55 @@ impl VFileOps for File {
         }
         let offset_usize = offset as usize; // Safe conversion after check
 
-
-        let len = data.len();
-        let read_len = cmp::min(buffer.len(), len - offset_usize); // Calculate bytes to read
+        let read_len = cmp::min(buf.len(), data.len() - offset_usize); // Calculate bytes to read
 
         // Copy data from the vector to the buffer
-        buffer[..read_len].copy_from_slice(&data[offset_usize..offset_usize + read_len]);
+        buf[..read_len].copy_from_slice(&data[offset_usize..offset_usize + read_len]);
 
         Ok(read_len) // Return number of bytes read
     }
 
-    /// Writes data from `buffer` to the file starting at the given `offset`.
-    ///
-    /// # Arguments
-    ///
-    /// * `buf`: The byte slice containing data to write.
-    /// * `offset`: The file offset (in bytes) to start writing to.
-    ///
-    /// # Returns
-    ///
-    /// On success, returns `Ok(usize)` number of bytes written.
-    /// On failure, returns a `FileSystemError`.
-    // Assuming the VFileOps::write signature is &self, u64 offset, Result<usize, FileSystemError>
-    fn write(&self, buf: &[u8], offset: u64) -> Result<usize, FileSystemError> { // Return FileSystemError
-        let mut data = self.data.lock().map_err(|_| FileSystemError::Other(String::from("Failed to lock mutex")))?; // Lock the mutex mutably, map poisoning error
-
-         // Check if offset is out of bounds for writing (can write at or after end)
-        if offset > usize::MAX as u64 || (offset as usize) > data.len() { // Can write exactly at data.len()
-            return Err(FileSystemError::InvalidParameter(format!("Invalid write offset: {}", offset))); // Invalid offset for writing
+    /// Writes data from `buf` to the file starting at the given `offset`
+    /// (or at the end when opened with `O_APPEND`). Writing past the end
+    /// fills the gap with zeros.
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> Result<usize, VfsError> {
+        if !flags_allow_write(self.flags) {
+            return Err(VfsError::InvalidDescriptor);
         }
-        let offset_usize = offset as usize; // Safe conversion
-
-
-        let write_len = buf.len();
-        let current_len = data.len();
-
-        if offset_usize == current_len {
-            // Appending to the end
-            data.extend_from_slice(buf);
-        } else {
-            // Overwriting or extending within the vector
-            let required_len = offset_usize.checked_add(write_len).ok_or(FileSystemError::OutOfMemory)?; // Check for overflow
-             if required_len > current_len {
-                 data.resize(required_len, 0); // Resize if extending beyond current length, fill with zeros
-             }
-             // Copy data into the specified range
-            data[offset_usize..required_len].copy_from_slice(buf);
+        let mut data = self.data.lock();
+        let offset_usize = if self.flags & O_APPEND != 0 { data.len() } else { offset as usize };
+
+        let required_len = offset_usize.checked_add(buf.len()).ok_or(VfsError::NoSpace)?; // Check for overflow
+        if required_len > data.len() {
+            data.resize(required_len, 0); // Resize if extending beyond current length, fill with zeros
         }
+        // Copy data into the specified range
+        data[offset_usize..required_len].copy_from_slice(buf);
 
-        Ok(write_len) // Return number of bytes written
+        Ok(buf.len()) // Return number of bytes written
     }
 
-    /// Returns the size of the file in bytes.
-    fn size(&self) -> u64 { // Return u64 as per VFileOps assumption
-        // Acquire lock, get vector length, and return as u64
-        // Use unwrap() on lock() here is generally safe if lock poisoning is acceptable,
-        // or map the poisoning error to FileSystemError if VFileOps allowed returning error.
-        // Since size() returns u64, mapping to FileSystemError is not possible in return type.
-        // Assuming VFileOps::size() cannot fail, unwrap is used, implying panic on lock poisoning.
-        self.data.lock().unwrap().len() as u64 // Convert usize to u64
+    fn metadata(&self) -> Result<Metadata, VfsError> {
+        let mut meta = self.meta.lock().clone();
+        meta.size = self.data.lock().len() as u64;
+        Ok(meta)
     }
 
-    // Implement other VFileOps methods if required by the trait,
-    // such as flush, truncate, etc. For an in-memory file, flush might do nothing,
-    // truncate would resize the vector.
-     fn flush(&self) -> Result<(), FileSystemError> { Ok(()) }
-     fn truncate(&self, size: u64) -> Result<(), FileSystemError> {
-         let mut data = self.data.lock().map_err(|_| FileSystemError::Other(String::from("Failed to lock mutex")))?;
-          // Check for u64 to usize conversion safety
-          if size > usize::MAX as u64 {
-               return Err(FileSystemError::InvalidParameter(format!("Truncate size too large: {}", size)));
-          }
-          data.resize(size as usize, 0);
-          Ok(())
-     }
+    /// Truncates or zero-extends the file.
+    fn set_len(&mut self, size: u64) -> Result<(), VfsError> {
+        // Check for u64 to usize conversion safety
+        if size > usize::MAX as u64 {
+            return Err(VfsError::NoSpace);
+        }
+        self.data.lock().resize(size as usize, 0);
+        Ok(())
+    }
 }
 
 
 // This file defines an in-memory file type. Its usage within a filesystem
 // would be handled by a specific FileSystem implementation that manages
-// VfsNode structures and creates instances of this File struct.
+// the directory tree and hands out this File as a VfsNode.
 // No main function or tests are typically included in such a library component file.
 // Example usage and tests would be in other files that use this module.
 
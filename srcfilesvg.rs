@@ -17,7 +17,7 @@ use std::io::Cursor as StdCursor; // For std test/example in-memory reading
 
 
 // Gerekli Sahne64 modülleri ve yapılarını içeri aktar (assume these are defined elsewhere)
-use crate::{fs::{self, O_RDONLY}, resource, SahneError, FileSystemError, Handle}; // fs, O_RDONLY, resource, SahneError, FileSystemError, Handle
+use crate::{fs::{self, O_RDONLY}, resource, FileSystemError, Handle}; // fs, O_RDONLY, resource, FileSystemError, Handle
 
 // alloc crate for String, Vec, format!
 use alloc::string::{String, ToString}; // Import ToString trait for to_string()
@@ -61,13 +61,6 @@ use xml_parser::{EventReader, XmlEvent, XmlResult, XmlError};
 use crate::{println, eprintln}; // crate kökünden veya ortak modülünden import edildiği varsayılır
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -416,14 +409,13 @@ pub fn open_svg_file<P: AsRef<Path>>(file_path: P) -> Result<Svg, FileSystemErro
 #[cfg(not(feature = "std"))]
 pub fn open_svg_file(file_path: &str) -> Result<Svg, FileSystemError> { // Return FileSystemError
     // Kaynağı edin
-    let handle = resource::acquire(file_path, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(file_path, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     // Dosyanın boyutunu al (needed for SahneResourceReader and potential validation)
      let file_stat = fs::fstat(handle)
          .map_err(|e| {
               let _ = resource::release(handle); // Hata durumunda handle'ı serbest bırak
-              map_sahne_error_to_fs_error(e)
+              FileSystemError::from(e)
           })?;
      let file_size = file_stat.size as u64;
 
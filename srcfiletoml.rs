@@ -17,7 +17,7 @@ use std::io::Cursor as StdCursor; // For std test/example in-memory reading
 
 
 // Gerekli Sahne64 modülleri ve yapılarını içeri aktar (assume these are defined elsewhere)
-use crate::{fs, resource, SahneError, FileSystemError, Handle}; // fs, resource, SahneError, FileSystemError, Handle
+use crate::{fs, resource, FileSystemError, Handle}; // fs, resource, FileSystemError, Handle
 
 // alloc crate for String, Vec, format!
 use alloc::string::{String, ToString}; // Import ToString trait for to_string()
@@ -50,13 +50,6 @@ use toml::de::Error as TomlDeError;
 use crate::{println, eprintln}; // crate kökünden veya ortak modülünden import edildiği varsayılır
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -249,11 +242,31 @@ impl TomlFile {
         self.data.get(key).and_then(Value::as_integer)
     }
 
-    // Add other similar get methods for other TOML data types if needed.
-     pub fn get_boolean(&self, key: &str) -> Option<bool> { ... }
-     pub fn get_float(&self, key: &str) -> Option<f64> { ... }
-     pub fn get_array(&self, key: &str) -> Option<&toml::value::Array> { ... }
-     pub fn get_table(&self, key: &str) -> Option<&toml::value::Table> { ... }
+    /// Gets a boolean value from the parsed TOML data by key.
+    pub fn get_boolean(&self, key: &str) -> Option<bool> {
+        self.data.get(key).and_then(Value::as_bool)
+    }
+
+    /// Gets a float value from the parsed TOML data by key.
+    pub fn get_float(&self, key: &str) -> Option<f64> {
+        self.data.get(key).and_then(Value::as_float)
+    }
+
+    /// Gets an array value from the parsed TOML data by key.
+    pub fn get_array(&self, key: &str) -> Option<&toml::value::Array> {
+        self.data.get(key).and_then(Value::as_array)
+    }
+
+    /// Gets a table (section) from the parsed TOML data by key, e.g. "log" for `[log]`.
+    pub fn get_table(&self, key: &str) -> Option<&toml::value::Table> {
+        self.data.get(key).and_then(Value::as_table)
+    }
+
+    /// Parses TOML content that is already in memory (e.g. read through the VFS).
+    pub fn parse_str(contents: &str) -> Result<TomlFile, FileSystemError> {
+        let data: Value = toml::from_str(contents).map_err(map_toml_de_error_to_fs_error)?;
+        Ok(TomlFile { data })
+    }
 }
 
 
@@ -291,14 +304,13 @@ pub fn open_toml_file<P: AsRef<Path>>(file_path: P) -> Result<TomlFile, FileSyst
 #[cfg(not(feature = "std"))]
 pub fn open_toml_file(file_path: &str) -> Result<TomlFile, FileSystemError> { // Return FileSystemError
     // Kaynağı edin
-    let handle = resource::acquire(file_path, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(file_path, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     // Dosyanın boyutını al (needed for SahneResourceReader and potential validation)
      let file_stat = fs::fstat(handle)
          .map_err(|e| {
               let _ = resource::release(handle); // Hata durumunda handle'ı serbest bırak
-              map_sahne_error_to_fs_error(e)
+              FileSystemError::from(e)
           })?;
      let file_size = file_stat.size as u64;
 
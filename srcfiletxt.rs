@@ -17,7 +17,7 @@ use std::io::Cursor as StdCursor; // For std test/example in-memory reading
 
 
 // Gerekli Sahne64 modülleri ve yapılarını içeri aktar (assume these are defined elsewhere)
-use crate::{fs, resource, SahneError, FileSystemError, Handle}; // fs, resource, SahneError, FileSystemError, Handle
+use crate::{fs, resource, FileSystemError, Handle}; // fs, resource, FileSystemError, Handle
 use crate::fs::{O_RDONLY, O_WRONLY, O_CREAT, O_TRUNC, O_APPEND}; // Import necessary fs flags
 
 // alloc crate for String, Vec, format!
@@ -40,13 +40,6 @@ use core::io::{Read, Write, Seek, SeekFrom, Error as CoreIOError, ErrorKind as C
 use crate::{println, eprintln}; // crate kökünden veya ortak modülünden import edildiği varsayılır
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -240,10 +233,9 @@ impl TxtFile {
         #[cfg(feature = "std")]
         let file = File::open(&self.path).map_err(map_std_io_error_to_fs_error)?;
         #[cfg(not(feature = "std"))]
-         let handle = resource::acquire(&self.path, resource::MODE_READ)
-             .map_err(map_sahne_error_to_fs_error)?;
+         let handle = resource::acquire(&self.path, resource::MODE_READ)?;
          #[cfg(not(feature = "std"))]
-         let file_stat = fs::fstat(handle).map_err(|e| { let _ = resource::release(handle); map_sahne_error_to_fs_error(e) })?;
+         let file_stat = fs::fstat(handle).map_err(|e| { let _ = resource::release(handle); FileSystemError::from(e) })?;
          #[cfg(not(feature = "std"))]
          let reader_impl = SahneResourceReader::new(handle, file_stat.size as u64); // Implements Read + Seek + Drop
         #[cfg(feature = "std")]
@@ -332,8 +324,7 @@ impl TxtFile {
         #[cfg(feature = "std")]
         let file = File::open(&self.path).map_err(map_std_io_error_to_fs_error)?;
         #[cfg(not(feature = "std"))]
-         let handle = resource::acquire(&self.path, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE) // Use standardized modes
-             .map_err(map_sahne_error_to_fs_error)?;
+         let handle = resource::acquire(&self.path, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE) // Use standardized modes?;
          #[cfg(not(feature = "std"))]
          let mut writer = SahneResourceReader::new(handle, 0); // SahneResourceReader implements Write (using write_at implicitly)
 
@@ -388,8 +379,7 @@ impl TxtFile {
 
 
         #[cfg(not(feature = "std"))]
-         let handle = resource::acquire(&self.path, resource::MODE_APPEND | resource::MODE_CREATE) // Use standardized modes
-             .map_err(map_sahne_error_to_fs_error)?;
+         let handle = resource::acquire(&self.path, resource::MODE_APPEND | resource::MODE_CREATE) // Use standardized modes?;
          #[cfg(not(feature = "std"))]
          let mut writer = SahneResourceReader::new(handle, 0); // SahneResourceReader implements Write and should handle append due to MODE_APPEND
 
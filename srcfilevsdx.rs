@@ -8,8 +8,9 @@ extern crate alloc;
 
 // Gerekli Sahne64 modülleri ve yapılarını içeri aktar (assume these are defined elsewhere)
 // Use crate:: instead of super:: for consistency
-use crate::fs::{FileSystem, VfsNode};
-use crate::{resource, SahneError, FileSystemError, Handle}; // fs, resource, SahneError, FileSystemError, Handle
+use crate::vfs::{Vfs, VfsError, VfsFile};
+use crate::archivefs::{mount_archive, ArchiveFs}; // Ortak ZIP arşiv dosya sistemi
+use crate::{resource, FileSystemError, Handle}; // fs, resource, FileSystemError, Handle
 use crate::fs::O_RDONLY; // Import necessary fs flags
 
 
@@ -42,6 +43,8 @@ use zip::result::ZipError;
 // alloc crate for String, Vec, format! (used directly or by dependencies)
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
+use alloc::boxed::Box; // For Box<dyn VfsFile>
+use alloc::sync::Arc;
 use alloc::format;
 
 
@@ -50,13 +53,6 @@ use alloc::format;
 use crate::{println, eprintln}; // crate kökünden veya ortak modülünden import edildiği varsayılır
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -350,14 +346,13 @@ pub fn open_vsdx_file<P: AsRef<StdPath>>(file_path: P) -> Result<VsdxFile, FileS
 #[cfg(not(feature = "std"))]
 pub fn open_vsdx_file(file_path: &str) -> Result<VsdxFile, FileSystemError> { // Return FileSystemError
     // Kaynağı edin
-    let handle = resource::acquire(file_path, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(file_path, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     // Dosyanın boyutını al (needed for SahneResourceReadSeek)
      let file_stat = fs::fstat(handle)
          .map_err(|e| {
               let _ = resource::release(handle); // Hata durumunda handle'ı serbest bırak
-              map_sahne_error_to_fs_error(e)
+              FileSystemError::from(e)
           })?;
      let file_size = file_stat.size as u64;
 
@@ -372,90 +367,13 @@ pub fn open_vsdx_file(file_path: &str) -> Result<VsdxFile, FileSystemError> { //
 }
 
 
-/// Loads a VSDX file and adds it to the VFS as a composite node (directory).
-/// This function demonstrates how to integrate VSDX with the VFS by creating
-/// a parent node for the archive and child nodes for each file within.
-///
-/// # Arguments
-///
-/// * `fs`: A mutable reference to the Sahne64 FileSystem.
-/// * `vfs_path`: The path in the VFS tree where the VSDX archive should be mounted.
-/// * `vsdx_data_reader`: A reader providing access to the VSDX file content.
-///    The caller is responsible for managing the underlying resource of this reader.
-///    Ideally, open_vsdx_file is called first, and the reader is passed.
-///
-/// # Returns
-///
-/// A Result indicating success or a FileSystemError.
-#[cfg(feature = "std")] // This function relies on std Box<dyn ...> and FileSystem trait
-pub fn load_vsdx_into_vfs<R: StdRead + StdSeek + 'static>(
-     fs: &mut FileSystem,
-     vfs_path: &str,
-     vsdx_data_reader: R // Take the reader directly
- ) -> Result<(), FileSystemError> { // Return FileSystemError
-    // Create the VsdxFile instance from the reader
-    let vsdx_file = VsdxFile::from_reader(vsdx_data_reader)?; // This consumes the reader
-
-    // The VSDX archive itself is a container. We can represent it as a directory-like node in the VFS.
-    // The files within the archive will be child nodes under this parent node.
-
-    // Create a parent VfsNode for the VSDX archive (as a directory)
-    // This requires FileSystem::add_directory or similar VFS API
-    // Assuming a VfsNode implementation for a directory exists.
-    // For demonstration, let's create a simple placeholder node type or use a generic directory type.
-    // This requires more detail about the FileSystem/VfsNode API.
-
-    // For now, let's just add the VsdxFile instance itself as a node,
-    // acknowledging that its VfsNode impl (if it existed) would be limited.
-    // However, we removed the VfsNode impl from VsdxFile as it's not the right level.
-
-    // The proper way:
-    // 1. Create a VfsNode representing the VSDX directory. This node needs to hold
-    //    the VsdxFile instance (likely Arc<Mutex<VsdxFile>>) so child nodes can access it.
-    // 2. Iterate through `vsdx_file.archive.file_names()`.
-    // 3. For each filename, create a new VfsNode representing that file within the ZIP.
-    //    This child node needs a reference back to the shared VsdxFile archive.
-    //    The child node's `VfsNode::read` method would call `vsdx_file.get_file(filename)`.
-    //    This requires careful lifetime and sharing management (Arc, Mutex).
-    // 4. Add the parent VSDX directory node to the VFS at `vfs_path`.
-    // 5. Add each child file node under the parent directory node.
-
-
-    // Placeholder implementation: Just create a dummy VfsNode for the path.
-    // This is NOT a proper VSDX VFS integration.
-    // It just shows where the VSDX parsing fits before VFS node creation.
-    // A proper VFS node implementation for VSDX entries is complex.
-
-    eprintln!("WARN: load_vsdx_into_vfs is a placeholder. Proper VSDX VFS node implementation is needed.");
-
-    // Example of how to get file names from the parsed VSDX:
-     #[cfg(feature = "std")] // zip crate's file_names requires &self or &mut self depending on the reader
-     {
-          println!("Files within the VSDX archive:");
-          let mut archive_ref = StdBox::leak(vsdx_file.archive).deref_mut(); // Leaking for temporary access - NOT SAFE IN REAL CODE
-          for name in archive_ref.file_names() {
-              println!(" - {}", name);
-          }
-           // To avoid leak, pass &mut vsdx_file to a helper, or clone/re-open the archive (inefficient).
-     }
-      #[cfg(not(feature = "std"))]
-      {
-          // Accessing file_names in no_std from ZipArchive depends on its implementation.
-          // Assuming it works with &self or &mut self on the no_std reader.
-           println!("Files within the VSDX archive:");
-           for name in vsdx_file.archive.file_names() { // Assuming file_names exists and works in no_std
-               println!(" - {}", name);
-           }
-      }
-
-
-    // Actual VFS integration requires creating VfsNodes for directory and files.
-    // This requires knowledge of the FileSystem/VfsNode API beyond what's in this file.
-    // For now, we just acknowledge that vsdx_file is parsed.
-
-     fs.add_node(vfs_path, Box::new(vsdx_vfs_directory_node)); // Hypothetical VFS directory node creation
-
-    Ok(()) // Indicate success of parsing and potential file listing
+/// Mounts a VSDX file at `mount_path` as a read-only directory tree
+/// (`visio/document.xml`, `visio/pages/page1.xml`, `visio/media/...`), decompressing
+/// entries on demand through the shared archive file system. Like the other
+/// container formats, the mount point must already exist.
+/// Archives without `visio/document.xml` are rejected with `VfsError::InvalidData`.
+pub fn mount_vsdx(vfs: &Vfs, mount_path: &str, source: Box<dyn VfsFile>) -> Result<Arc<ArchiveFs>, VfsError> {
+    mount_archive(vfs, mount_path, source, Some("visio/document.xml"))
 }
 
 
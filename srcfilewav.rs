@@ -17,7 +17,7 @@ use std::io::Cursor as StdCursor; // For std test/example in-memory reading
 
 
 // Gerekli Sahne64 modülleri ve yapılarını içeri aktar (assume these are defined elsewhere)
-use crate::{fs, resource, SahneError, FileSystemError, Handle}; // fs, resource, SahneError, FileSystemError, Handle
+use crate::{fs, resource, FileSystemError, Handle}; // fs, resource, FileSystemError, Handle
 use crate::fs::{O_RDONLY, O_WRONLY, O_CREAT, O_TRUNC, O_RDWR}; // Import necessary fs flags
 
 
@@ -44,13 +44,6 @@ use core::io::{Read, Write, Seek, SeekFrom, Error as CoreIOError, ErrorKind as C
 use crate::{println, eprintln}; // crate kökünden or common module
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -494,14 +487,13 @@ pub fn open_wav_reader<P: AsRef<Path>>(file_path: P) -> Result<File, FileSystemE
 #[cfg(not(feature = "std"))]
 pub fn open_wav_reader(file_path: &str) -> Result<SahneResourceReadWriteSeek, FileSystemError> { // Return SahneResourceReadWriteSeek (implements Read+Seek+Drop)
     // Kaynağı edin
-    let handle = resource::acquire(file_path, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(file_path, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     // Dosyanın boyutını al (needed for SahneResourceReadWriteSeek)
      let file_stat = fs::fstat(handle)
          .map_err(|e| {
               let _ = resource::release(handle); // Hata durumunda handle'ı serbest bırak
-              map_sahne_error_to_fs_error(e)
+              FileSystemError::from(e)
           })?;
      let file_size = file_stat.size as u64;
 
@@ -546,14 +538,13 @@ pub fn open_wav_writer<P: AsRef<Path>>(file_path: P) -> Result<File, FileSystemE
 #[cfg(not(feature = "std"))]
 pub fn open_wav_writer(file_path: &str) -> Result<SahneResourceReadWriteSeek, FileSystemError> { // Return SahneResourceReadWriteSeek (implements Write+Seek+Drop)
     // Kaynağı edin for writing, create, and truncate
-    let handle = resource::acquire(file_path, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(file_path, resource::MODE_WRITE | resource::MODE_CREATE | resource::MODE_TRUNCATE)?; // SahneError -> FileSystemError
 
     // Get file size (initially 0 for a new/truncated file)
      let file_stat = fs::fstat(handle)
          .map_err(|e| {
               let _ = resource::release(handle); // Hata durumunda handle'ı serbest bırak
-              map_sahne_error_to_fs_error(e)
+              FileSystemError::from(e)
           })?;
      let file_size = file_stat.size as u64;
 
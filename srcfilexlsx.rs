@@ -6,18 +6,19 @@
 extern crate alloc;
 
 // Gerekli Sahne64 modülleri ve yapılarını içeri aktar (assume these are defined elsewhere)
-use crate::fs::vfs::{FileType, VfsNode}; // Correct VFS trait import
-use crate::{resource, SahneError, FileSystemError, Handle}; // fs, resource, SahneError, FileSystemError, Handle
+use crate::vfs::{flags_allow_write, FileType, Metadata, NodeRef, Vfs, VfsError, VfsFile, VfsNode}; // Ortak VFS trait'leri
+use crate::archivefs::{mount_archive, ArchiveFs}; // Ortak ZIP arşiv dosya sistemi
+use crate::{resource, FileSystemError, Handle}; // fs, resource, FileSystemError, Handle
 use crate::fs::O_RDONLY; // Import necessary fs flags
 
 
-use crate::sync::spinlock::Spinlock; // Spinlock for no_std synchronization
 use spin::Mutex; // Mutex for no_std synchronization (from spin crate)
 
 
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use alloc::sync::Arc; // For sharing data structures
+use alloc::boxed::Box; // For Box<dyn VfsFile>
 
 
 use core::result::Result;
@@ -43,13 +44,6 @@ use std::io::Cursor as StdCursor;
 use crate::{println, eprintln};
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -284,22 +278,47 @@ impl XlsxFile {
 
 // A separate struct to represent the raw XLSX file data as a VfsNode.
 /// Represents the raw bytes of an XLSX file as a VfsNode.
-/// Implements the VfsNode trait to provide read access to the raw data.
+/// Opening it yields a read-only `RawXlsxFile` over the same bytes.
 pub struct RawXlsxVfsNode {
-     data: Vec<u8>, // Raw bytes of the file content (Requires alloc)
+     data: Arc<Vec<u8>>, // Raw bytes of the file content, shared with open files (Requires alloc)
 }
 
 impl RawXlsxVfsNode {
      /// Creates a new `RawXlsxVfsNode` instance from raw file data.
      pub fn new(data: Vec<u8>) -> Self {
-         RawXlsxVfsNode { data }
+         RawXlsxVfsNode { data: Arc::new(data) }
      }
 }
 
 // Implement VfsNode for RawXlsxVfsNode to provide access to the raw bytes.
 impl VfsNode for RawXlsxVfsNode {
-    // Standard VfsNode read signature: &self, offset: u64, buffer: &mut [u8] -> Result<usize, FileSystemError>
-    fn read(&self, offset: u64, buffer: &mut [u8]) -> Result<usize, FileSystemError> { // Standardized signature
+    fn metadata(&self) -> Result<Metadata, VfsError> {
+        let mut meta = Metadata::new(FileType::File, self.data.len() as u64); // This node represents a file
+        meta.mode = 0o444;
+        Ok(meta)
+    }
+
+    fn open(&self, flags: u32) -> Result<Box<dyn VfsFile>, VfsError> {
+        // XLSX files (as raw bytes) are typically not modified directly in place in a VFS.
+        // Writing would imply modifying the ZIP/XML structure, which is complex.
+        if flags_allow_write(flags) {
+            return Err(VfsError::ReadOnly);
+        }
+        Ok(Box::new(RawXlsxFile { data: self.data.clone() }))
+    }
+
+    fn as_any(&self) -> &dyn core::any::Any {
+        self
+    }
+}
+
+/// An open, read-only view of a `RawXlsxVfsNode`.
+pub struct RawXlsxFile {
+     data: Arc<Vec<u8>>,
+}
+
+impl VfsFile for RawXlsxFile {
+    fn read_at(&mut self, offset: u64, buffer: &mut [u8]) -> Result<usize, VfsError> {
         let data_len = self.data.len() as u64;
 
         if offset >= data_len {
@@ -317,36 +336,13 @@ impl VfsNode for RawXlsxVfsNode {
         Ok(bytes_to_read) // Return number of bytes read
     }
 
-    // Standard VfsNode write signature: &self, offset: u64, buffer: &[u8] -> Result<usize, FileSystemError>
-    fn write(&self, _offset: u64, _buffer: &[u8]) -> Result<usize, FileSystemError> { // Standardized signature
-        // XLSX files (as raw bytes) are typically not modified directly in place in a VFS.
-        // Writing would imply modifying the ZIP/XML structure, which is complex.
-        // Returning Unsupported for simplicity.
-        Err(FileSystemError::NotSupported(String::from("Write operation is not supported for raw XLSX VFS nodes."))) // Requires alloc
-    }
+    // write_at: default (ReadOnly).
 
-    // Other VfsNode trait functions (e.g., metadata, file type, etc.) - implement as needed.
-     fn file_type(&self) -> FileType {
-         FileType::File // This node represents a file
-     }
-
-     fn size(&self) -> u64 {
-         self.data.len() as u64 // Return the size of the raw data
-     }
-
-     // Need to implement other VfsNode required methods.
-     // For a minimal VfsNode implementation for a file:
-     // - file_type()
-     // - size()
-     // - read()
-     // - write() (optional, can return NotSupported)
-     // - lookup() (for directories, not applicable here)
-     // - readdir() (for directories, not applicable here)
-     // - link_count() (default 1 for regular files)
-     // - permissions() (implement with default/relevant permissions)
-     // - user_id(), group_id() (implement with default/relevant IDs)
-     // - created_time(), modified_time(), accessed_time() (implement with relevant timestamps)
-     // - flags() (implement with default flags)
+    fn metadata(&self) -> Result<Metadata, VfsError> {
+        let mut meta = Metadata::new(FileType::File, self.data.len() as u64);
+        meta.mode = 0o444;
+        Ok(meta)
+    }
 }
 
 
@@ -375,14 +371,13 @@ pub fn load_xlsx_raw_data<P: AsRef<Path>>(file_path: P) -> Result<Vec<u8>, FileS
 #[cfg(not(feature = "std"))]
 pub fn load_xlsx_raw_data(file_path: &str) -> Result<Vec<u8>, FileSystemError> { // Return Vec<u8> or FileSystemError
     // Kaynağı edin
-    let handle = resource::acquire(file_path, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(file_path, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     // Dosyanın boyutını al (needed for SahneResourceReadSeek)
      let file_stat = fs::fstat(handle)
          .map_err(|e| {
               let _ = resource::release(handle); // Hata durumunda handle'ı serbest bırak
-              map_sahne_error_to_fs_error(e)
+              FileSystemError::from(e)
           })?;
      let file_size = file_stat.size as u64;
 
@@ -406,77 +401,23 @@ pub fn load_xlsx_raw_data(file_path: &str) -> Result<Vec<u8>, FileSystemError> {
 ///
 /// # Arguments
 ///
-/// * `name`: The name of the VFS node (e.g., the file name).
+/// * `name`: The name of the VFS node (e.g., the file name). The name is kept by
+///   the parent directory that links the node, not by the node itself.
 /// * `raw_data`: The raw bytes of the XLSX file content.
 ///
 /// # Returns
 ///
-/// An Arc<Spinlock<VfsNode>> representing the VFS node for the raw XLSX file.
-pub fn create_raw_xlsx_vfs_node(name: String, raw_data: Vec<u8>) -> Arc<Spinlock<VfsNode>> { // Return Arc<Spinlock<VfsNode>>
-    // Create a RawXlsxVfsNode instance to hold the raw data
-    let raw_node_data = Arc::new(RawXlsxVfsNode::new(raw_data)); // Requires alloc and Arc
-
-    // Create the VFS node:
-    // The node represents the file. Its data is the RawXlsxVfsNode instance.
-    // We use `Some(raw_node_data)` to embed the RawXlsxVfsNode within the VfsNode structure.
-    // The VfsNode implementation will need to cast/access this data.
-    // NOTE: The current VfsNode trait likely expects a trait object like `Box<dyn VfsFileData>`
-    // for the `data` field. This requires alignment with the actual VfsNode trait definition.
-    // Assuming the VfsNode trait has a mechanism to store arbitrary data that implements
-    // necessary file data traits (like Read, Write, size, etc. if not handled by VfsNode methods).
-    // Or, the VfsNode itself holds the Vec<u8> directly.
-
-    // Let's assume for now VfsNode can directly store the Arc<RawXlsxVfsNode>.
-    // The VfsNode methods would then access the raw data via this Arc.
-    // This requires VfsNode trait to have methods that take `&self` and access the internal data.
-
-    // Given the VfsNode signature we standardized to (`&self` read),
-    // the RawXlsxVfsNode implements the `read` method using `&self`.
-    // So, we can embed an `Arc<RawXlsxVfsNode>` in the VfsNode's data field
-    // if the VfsNode definition supports it.
-
-    // If VfsNode takes `Option<Arc<dyn VfsFileData>>` or similar, we need to implement VfsFileData.
-    // If VfsNode just takes `Option<Arc<Any>>` and requires downcasting, it's less safe.
-    // A simpler approach might be for VfsNode itself to contain the data Vec<u8> directly if it's for simple files.
-
-    // Let's revert to the original structure where the VfsNode has a data field
-    // that can hold something like `Option<Arc<dyn Any + Send + Sync>>` and requires downcasting
-    // or a dedicated trait `VfsFileData`. Assuming `VfsNode::new` takes `Option<Arc<dyn VfsFileData>>`.
-    // We need to define `trait VfsFileData` and implement it for `RawXlsxVfsNode`.
-
-     // Define a simple trait that VfsNode data might need to implement
-     // (This is a placeholder based on typical VFS data requirements)
-     // In the actual crate, this trait should be defined in the VFS module.
-    
-     #[cfg(not(feature = "std"))] // Assuming this trait is relevant in no_std
-     pub trait VfsFileData: Send + Sync {
-          fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize, FileSystemError>;
-          fn write_at(&self, offset: u64, buffer: &[u8]) -> Result<usize, FileSystemError>;
-          fn size(&self) -> u64;
-          // Add other file operation methods as needed by VfsNode's internal implementation
-     }
-     // Implement VfsFileData for RawXlsxVfsNode if needed by VfsNode definition
-     impl VfsFileData for RawXlsxVfsNode {
-          fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize, FileSystemError> { self.read(offset, buffer) }
-          fn write_at(&self, offset: u64, buffer: &[u8]) -> Result<usize, FileSystemError> { self.write(offset, buffer) }
-          fn size(&self) -> u64 { self.size() }
-     }
-
-    // Assuming VfsNode::new can take `Option<Arc<Spinlock<dyn VfsFileData>>>` or similar.
-    // Or, more simply, the VfsNode itself has a data field like `Arc<Mutex<Vec<u8>>>` for simple files.
-    // Given the original code structure and comments, it seems `VfsNode::new` is meant to take
-    // the `Arc<XlsxFile>` directly, which is inconsistent with VFS nodes handling raw bytes.
-
-    // Let's proceed with creating a VfsNode for the raw data,
-    // using `RawXlsxVfsNode` as the underlying data representation
-    // and assuming the VfsNode definition supports storing this.
-
-     // Create the VFS node
-     let node = VfsNode::new(name, FileType::File, Some(raw_node_data), None); // Assuming VfsNode::new takes Option<Arc<dyn Any + Send + Sync>> or similar and requires downcasting later.
-     // If VfsNode::new takes Option<Arc<dyn VfsFileData>>, then use Some(Arc::new(RawXlsxVfsNode::new(raw_data))).
+/// A `NodeRef` that can be linked into any directory of the VFS tree.
+pub fn create_raw_xlsx_vfs_node(_name: String, raw_data: Vec<u8>) -> NodeRef {
+    Arc::new(RawXlsxVfsNode::new(raw_data)) // Requires alloc and Arc
+}
 
-    // Wrap the VFS node in Spinlock and Arc for concurrent and shared access
-    Arc::new(Spinlock::new(node))
+/// Mounts an XLSX file at `mount_path` as a read-only directory tree
+/// (`xl/workbook.xml`, `xl/worksheets/sheet1.xml`, ...), in contrast to
+/// `create_raw_xlsx_vfs_node`, which exposes the container as a single file.
+/// Archives without `xl/workbook.xml` are rejected with `VfsError::InvalidData`.
+pub fn mount_xlsx(vfs: &Vfs, mount_path: &str, source: Box<dyn VfsFile>) -> Result<Arc<ArchiveFs>, VfsError> {
+    mount_archive(vfs, mount_path, source, Some("xl/workbook.xml"))
 }
 
 
@@ -587,10 +528,9 @@ fn main() -> io::Result<()> { // Return std::io::Result for std example
                // You can now interact with this node via the VFS interface:
                // Example: Simulate reading some bytes from the VFS node
                let mut buffer = [0u8; 20]; // Buffer to read into
-               let vfs_node_locked = raw_xlsx_vfs_node.lock(); // Lock the Spinlock
-
-               // Use the VfsNode::read method (implemented by RawXlsxVfsNode)
-               match vfs_node_locked.read(0, &mut buffer) { // Use read method on the locked node
+               // Open the node read-only and read through the VfsFile interface
+               let read_result = raw_xlsx_vfs_node.open(crate::vfs::O_RDONLY).and_then(|mut file| file.read_at(0, &mut buffer));
+               match read_result {
                    Ok(bytes_read) => {
                        println!("Read {} bytes from VFS node: {:?}", bytes_read, &buffer[..bytes_read]);
                         // Assert that the beginning of the content was read
@@ -598,18 +538,9 @@ fn main() -> io::Result<()> { // Return std::io::Result for std example
                    },
                    Err(e) => {
                        eprintln!("Error reading from VFS node: {}", e); // std error display
-                        // Map FileSystemError back to std::io::Error for std main
-                       match e {
-                           FileSystemError::IOError(msg) => return Err(io::Error::new(io::ErrorKind::Other, msg)),
-                           FileSystemError::InvalidData(msg) => return Err(io::Error::new(io::ErrorKind::InvalidData, msg)),
-                           FileSystemError::NotFound(msg) => return Err(io::Error::new(io::ErrorKind::NotFound, msg)),
-                           FileSystemError::PermissionDenied(msg) => return Err(io::Error::new(io::ErrorKind::PermissionDenied, msg)),
-                           FileSystemError::NotSupported(msg) => return Err(io::Error::new(io::ErrorKind::Unsupported, msg)),
-                           FileSystemError::Other(msg) => return Err(io::Error::new(io::ErrorKind::Other, msg)),
-                       }
+                       return Err(io::Error::new(io::ErrorKind::Other, format!("{}", e)));
                    }
                }
-                // The Spinlock is automatically unlocked when vfs_node_locked goes out of scope.
 
 
           },
@@ -693,8 +624,7 @@ fn main() -> Result<(), FileSystemError> { // Return FileSystemError for no_std
      //
      //          // Simulate reading from the VFS node
                 let mut buffer = [0u8; 20];
-                let vfs_node_locked = raw_xlsx_vfs_node.lock(); // Lock the Spinlock
-                match vfs_node_locked.read(0, &mut buffer) {
+                match raw_xlsx_vfs_node.open(crate::vfs::O_RDONLY).and_then(|mut file| file.read_at(0, &mut buffer)) {
                     Ok(bytes_read) => {
                         crate::println!("Read {} bytes from VFS node: {:?}", bytes_read, &buffer[..bytes_read]);
                     },
@@ -702,7 +632,6 @@ fn main() -> Result<(), FileSystemError> { // Return FileSystemError for no_std
                         crate::eprintln!("Error reading from VFS node: {:?}", e);
                     }
                 }
-     //           // Spinlock unlocked when vfs_node_locked goes out of scope.
      
           },
           Err(e) => crate::eprintln!("Error loading raw XLSX data: {:?}", e),
@@ -820,26 +749,28 @@ mod tests {
 
 
     #[test]
-    fn test_raw_xlsx_vfs_node_read_std_cursor() -> Result<(), FileSystemError> { // Return FileSystemError
+    fn test_raw_xlsx_vfs_node_read_std_cursor() -> Result<(), VfsError> { // Return VfsError
         let raw_data_bytes = create_dummy_xlsx_bytes("This is some raw data for the VFS node test.");
         let raw_data_len = raw_data_bytes.len();
 
         // Create a RawXlsxVfsNode instance
         let raw_node = RawXlsxVfsNode::new(raw_data_bytes.clone()); // Clone for creating the node
+        assert_eq!(raw_node.metadata()?.size, raw_data_len as u64);
+        let mut raw_file = raw_node.open(crate::vfs::O_RDONLY)?;
 
 
         // Test reading from the VFS node at different offsets
         let mut buffer = [0u8; 10];
 
         // Read from the beginning
-        let bytes_read_1 = raw_node.read(0, &mut buffer)?;
+        let bytes_read_1 = raw_file.read_at(0, &mut buffer)?;
         assert_eq!(bytes_read_1, 10);
         assert_eq!(&buffer[..bytes_read_1], &raw_data_bytes[..10]);
 
 
         // Read from an offset
         let offset = 5;
-        let bytes_read_2 = raw_node.read(offset as u64, &mut buffer)?;
+        let bytes_read_2 = raw_file.read_at(offset as u64, &mut buffer)?;
         let expected_bytes_read_2 = std::cmp::min(buffer.len(), raw_data_len - offset);
         assert_eq!(bytes_read_2, expected_bytes_read_2);
         assert_eq!(&buffer[..bytes_read_2], &raw_data_bytes[offset..offset + bytes_read_2]);
@@ -847,7 +778,7 @@ mod tests {
 
         // Read beyond the end
         let offset_beyond = raw_data_len + 10;
-        let bytes_read_3 = raw_node.read(offset_beyond as u64, &mut buffer)?;
+        let bytes_read_3 = raw_file.read_at(offset_beyond as u64, &mut buffer)?;
         assert_eq!(bytes_read_3, 0);
 
 
@@ -859,18 +790,16 @@ mod tests {
            let raw_data_bytes = create_dummy_xlsx_bytes("dummy data");
            let raw_node = RawXlsxVfsNode::new(raw_data_bytes);
 
-           let mut buffer = [0u8; 10];
-
-           // Attempt to write, expect a NotSupported error
-           let result = raw_node.write(0, &mut buffer);
+           let buffer = [0u8; 10];
 
-           assert!(result.is_err());
-           match result.unwrap_err() {
-               FileSystemError::NotSupported(msg) => {
-                   assert!(msg.contains("Write operation is not supported"));
-               },
-               _ => panic!("Beklenenden farklı hata türü: {:?}", result.unwrap_err()),
+           // Opening for write is refused, and a read-only file refuses writes
+           match raw_node.open(crate::vfs::O_RDWR) {
+               Err(VfsError::ReadOnly) => {},
+               other => panic!("Beklenenden farklı sonuç: {:?}", other.map(|_| ())),
            }
+           let mut raw_file = raw_node.open(crate::vfs::O_RDONLY).unwrap();
+           let result = raw_file.write_at(0, &buffer);
+           assert_eq!(result, Err(VfsError::ReadOnly));
       }
 
 
@@ -17,7 +17,7 @@ use std::io::Cursor as StdCursor; // For std test/example in-memory reading
 
 
 // Gerekli Sahne64 modülleri ve yapılarını içeri aktar (assume these are defined elsewhere)
-use crate::{fs, resource, SahneError, FileSystemError, Handle}; // fs, resource, SahneError, FileSystemError, Handle
+use crate::{fs, resource, FileSystemError, Handle}; // fs, resource, FileSystemError, Handle
 use crate::fs::O_RDONLY; // Import necessary fs flags
 
 
@@ -31,6 +31,13 @@ use zip::result::ZipError;
 use alloc::string::{String, ToString}; // Import ToString trait for to_string()
 use alloc::vec::Vec;
 use alloc::format;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+
+// Ortak VFS ve ZIP arşiv dosya sistemi (zip crate'ine bağlı değildir, no_std'de de çalışır)
+use crate::vfs::{Vfs, VfsError, VfsFile, O_CREAT, O_RDWR, O_TRUNC};
+use crate::archivefs::{map_archive_error_to_vfs_error, mount_archive, ArchiveFs};
+use crate::zipwriter::ZipWriter;
 
 
 // core::result, core::option, core::fmt, core::cmp, core::ops::Drop, core::io
@@ -47,13 +54,6 @@ use core::io::{Read, Seek, SeekFrom, Error as CoreIOError, ErrorKind as CoreIOEr
 use crate::{println, eprintln}; // crate kökünden or common module
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -200,14 +200,13 @@ pub fn open_zip_reader<P: AsRef<Path>>(file_path: P) -> Result<File, FileSystemE
 #[cfg(not(feature = "std"))]
 pub fn open_zip_reader(file_path: &str) -> Result<SahneResourceReadSeek, FileSystemError> { // Return SahneResourceReadSeek (implements Read+Seek+Drop)
     // Kaynağı edin
-    let handle = resource::acquire(file_path, resource::MODE_READ)
-        .map_err(map_sahne_error_to_fs_error)?; // SahneError -> FileSystemError
+    let handle = resource::acquire(file_path, resource::MODE_READ)?; // SahneError -> FileSystemError
 
     // Dosyanın boyutını al (needed for SahneResourceReadSeek)
      let file_stat = fs::fstat(handle)
          .map_err(|e| {
               let _ = resource::release(handle); // Hata durumunda handle'ı serbest bırak
-              map_sahne_error_to_fs_error(e)
+              FileSystemError::from(e)
           })?;
      let file_size = file_stat.size as u64;
 
@@ -263,6 +262,28 @@ pub fn list_zip_contents(zip_path: &str) -> Result<(), FileSystemError> { // Ret
 }
 
 
+/// Mounts a ZIP archive at `mount_path` as a read-only directory tree.
+/// Unlike `list_zip_contents`, this works in no_std builds as well: the
+/// central directory is parsed and entries are decompressed on demand by
+/// `crate::archivefs::ArchiveFs`.
+pub fn mount_zip(vfs: &Vfs, mount_path: &str, source: Box<dyn VfsFile>) -> Result<Arc<ArchiveFs>, VfsError> {
+    mount_archive(vfs, mount_path, source, None)
+}
+
+/// Creates (or truncates) a ZIP archive at `path` and returns a writer for it.
+/// Call `ZipWriter::finish` to write the central directory.
+pub fn create_zip(vfs: &Vfs, path: &str, mode: u32) -> Result<ZipWriter, VfsError> {
+    let sink = vfs.open(path, O_RDWR | O_CREAT | O_TRUNC, mode)?;
+    ZipWriter::new(sink).map_err(map_archive_error_to_vfs_error)
+}
+
+/// Opens an existing ZIP archive at `path` for in-place appending of new entries.
+pub fn append_zip(vfs: &Vfs, path: &str) -> Result<ZipWriter, VfsError> {
+    let sink = vfs.open(path, O_RDWR, 0)?;
+    ZipWriter::append(sink).map_err(map_archive_error_to_vfs_error)
+}
+
+
 // Example main function (std)
 #[cfg(feature = "example_zip")] // Different feature flag
 #[cfg(feature = "std")] // Only compile for std
@@ -0,0 +1,1068 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// Flash Translation Layer for raw NAND.
+//
+// NAND can only program erased pages, pages inside an erase block must be programmed in order,
+// and erasing works on whole blocks with a limited number of program/erase (P/E) cycles.
+// The FTL hides this behind a page-granular block device:
+// - Logical-to-physical (L2P) page mapping; every write goes to the next free page of the open
+//   erase block and the previous physical copy becomes invalid.
+// - Greedy garbage collection (fewest valid pages) when free erase blocks run low.
+// - Dynamic wear leveling (least-worn free block is opened next) and static wear leveling
+//   (cold blocks are recycled once the erase count spread exceeds a threshold).
+// - Bad-block management: factory bad blocks are skipped, blocks that fail program/erase are
+//   retired and their valid pages relocated.
+// - Power-loss safety: each page's spare area records (logical page, sequence number, erase
+//   count). A checkpoint of the mapping is written to a fresh erase block before the previous one
+//   is erased; mount loads the newest intact checkpoint and replays newer pages from the spare
+//   areas. A discard writes a checkpoint before returning, so trimmed pages are not resurrected
+//   by the replay.
+
+use crate::blockdevice::{BlockDevice, BlockDeviceError}; // Assuming these are in crate::blockdevice
+use crate::crypto::Crc32;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+
+use core::fmt;
+use core::result::Result;
+
+/// Bytes of the spare (OOB) area used by the FTL per page.
+pub const FTL_SPARE_SIZE: usize = 20;
+/// Default percentage of good erase blocks kept as spare capacity.
+pub const DEFAULT_OVERPROVISION_PERCENT: u32 = 7;
+/// Default erase count spread that triggers static wear leveling.
+pub const DEFAULT_WEAR_LEVEL_THRESHOLD: u32 = 32;
+
+const FTL_PAGE_MAGIC: [u8; 2] = *b"FT";
+const FTL_KIND_DATA: u8 = 1;
+const FTL_KIND_CHECKPOINT: u8 = 2;
+const FTL_CHECKPOINT_MAGIC: u32 = 0x5043_4653; // "SFCP"
+const FTL_CHECKPOINT_VERSION: u32 = 1;
+const FTL_CHECKPOINT_HEADER_SIZE: usize = 32;
+// Open data block, GC destination, current checkpoint and the next checkpoint
+const FTL_MIN_SPARE_BLOCKS: u32 = 4;
+const UNMAPPED: u32 = u32::MAX;
+
+/// Physical layout of a NAND device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NandGeometry {
+    pub page_size: usize, // Data bytes per page
+    pub spare_size: usize, // Spare (OOB) bytes per page
+    pub pages_per_block: u32,
+    pub block_count: u32, // Erase blocks
+}
+
+impl NandGeometry {
+    /// Total number of physical pages.
+    pub fn total_pages(&self) -> u64 {
+        self.pages_per_block as u64 * self.block_count as u64
+    }
+}
+
+/// Errors reported by a NAND device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NandError {
+    InvalidAddress, // Block/page out of range or wrong buffer size
+    NotErased, // Program of a page that is not in the erased state
+    OutOfOrderProgram, // Pages of a block must be programmed sequentially
+    ProgramFailed, // Program status failure: the block should be retired
+    EraseFailed, // Erase status failure: the block should be retired
+    UncorrectableRead, // ECC could not correct the page
+    BadBlock, // Operation on a block marked bad
+    PowerLoss, // The device lost power (simulation)
+}
+
+impl fmt::Display for NandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NandError::InvalidAddress => write!(f, "NAND invalid address"),
+            NandError::NotErased => write!(f, "NAND page is not erased"),
+            NandError::OutOfOrderProgram => write!(f, "NAND pages must be programmed in order"),
+            NandError::ProgramFailed => write!(f, "NAND program failed"),
+            NandError::EraseFailed => write!(f, "NAND erase failed"),
+            NandError::UncorrectableRead => write!(f, "NAND uncorrectable read"),
+            NandError::BadBlock => write!(f, "NAND bad block"),
+            NandError::PowerLoss => write!(f, "NAND power loss"),
+        }
+    }
+}
+
+/// Raw NAND access: page read/program with spare area, block erase and bad-block markers.
+pub trait NandFlash {
+    /// Returns the device geometry.
+    fn geometry(&self) -> NandGeometry;
+
+    /// Reads a page. `data` must be `page_size` bytes, `spare` at most `spare_size` bytes.
+    /// Erased pages read as 0xFF.
+    fn read_page(&mut self, block: u32, page: u32, data: &mut [u8], spare: &mut [u8]) -> Result<(), NandError>;
+
+    /// Reads only the spare area of a page.
+    fn read_spare(&mut self, block: u32, page: u32, spare: &mut [u8]) -> Result<(), NandError> {
+        let mut data = vec![0u8; self.geometry().page_size]; // Requires alloc
+        self.read_page(block, page, &mut data, spare)
+    }
+
+    /// Programs an erased page. Pages of a block must be programmed in increasing order.
+    fn program_page(&mut self, block: u32, page: u32, data: &[u8], spare: &[u8]) -> Result<(), NandError>;
+
+    /// Erases a block (all pages back to 0xFF), consuming one P/E cycle.
+    fn erase_block(&mut self, block: u32) -> Result<(), NandError>;
+
+    /// Returns true if the block carries a factory or runtime bad-block marker.
+    fn is_bad_block(&mut self, block: u32) -> Result<bool, NandError>;
+
+    /// Marks a block bad so it is never used again.
+    fn mark_bad_block(&mut self, block: u32) -> Result<(), NandError>;
+}
+
+/// FTL errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FtlError {
+    Nand(NandError),
+    NoSpace, // No erase block could be reclaimed
+    InvalidGeometry(String),
+    InvalidAddress(u64), // Logical page out of range
+    BufferSize(usize), // Buffer length does not match the page size
+    NotFormatted, // Mount found no intact checkpoint
+}
+
+impl fmt::Display for FtlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FtlError::Nand(e) => write!(f, "FTL NAND error: {}", e),
+            FtlError::NoSpace => write!(f, "FTL out of free erase blocks"),
+            FtlError::InvalidGeometry(msg) => write!(f, "FTL invalid geometry: {}", msg),
+            FtlError::InvalidAddress(lpn) => write!(f, "FTL logical page {} out of range", lpn),
+            FtlError::BufferSize(len) => write!(f, "FTL buffer size {} does not match the page size", len),
+            FtlError::NotFormatted => write!(f, "FTL checkpoint not found (device not formatted)"),
+        }
+    }
+}
+
+impl From<NandError> for FtlError {
+    fn from(e: NandError) -> Self {
+        FtlError::Nand(e)
+    }
+}
+
+/// Helper function to map FtlError to BlockDeviceError.
+fn map_ftl_error_to_block_device_error(e: FtlError) -> BlockDeviceError {
+    match e {
+        FtlError::InvalidAddress(_) => BlockDeviceError::InvalidParameter(e.to_string()),
+        FtlError::BufferSize(_) => BlockDeviceError::BlockSizeError(e.to_string()),
+        other => BlockDeviceError::DeviceError(other.to_string()),
+    }
+}
+
+/// FTL tuning parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FtlConfig {
+    /// Percentage of good erase blocks kept as spare (at least 4 blocks are always kept).
+    pub overprovision_percent: u32,
+    /// Garbage collection runs while fewer erase blocks than this are free.
+    pub gc_free_block_threshold: u32,
+    /// Erase count spread that triggers static wear leveling (0 disables it).
+    pub wear_level_threshold: u32,
+    /// Host page writes between automatic checkpoints (0: only explicit `checkpoint`/`flush`).
+    pub checkpoint_interval: u32,
+}
+
+impl Default for FtlConfig {
+    fn default() -> Self {
+        FtlConfig {
+            overprovision_percent: DEFAULT_OVERPROVISION_PERCENT,
+            gc_free_block_threshold: 2,
+            wear_level_threshold: DEFAULT_WEAR_LEVEL_THRESHOLD,
+            checkpoint_interval: 0,
+        }
+    }
+}
+
+/// Counters for write amplification and maintenance work.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FtlStats {
+    pub host_writes: u64, // Pages written by the host
+    pub pages_programmed: u64, // Pages programmed on NAND (host + GC + wear leveling)
+    pub gc_runs: u64, // Blocks reclaimed by garbage collection
+    pub wear_level_moves: u64, // Blocks recycled by static wear leveling
+    pub erases: u64,
+    pub retired_blocks: u64, // Blocks that failed at runtime
+    pub checkpoints: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockState {
+    Free,
+    Open, // Current write target
+    Full, // Written (possibly partially, after a power loss); GC candidate
+    Checkpoint, // Holds the current mapping checkpoint
+    Bad,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BlockInfo {
+    state: BlockState,
+    erase_count: u32,
+    valid_pages: u32,
+    write_ptr: u32, // Next page to program
+}
+
+// Spare area tag written with every page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PageTag {
+    kind: u8,
+    index: u32, // Logical page (data) or page index within the checkpoint
+    sequence: u64,
+    erase_count: u32, // Erase count of the block when the page was programmed
+}
+
+impl PageTag {
+    fn to_bytes(&self) -> [u8; FTL_SPARE_SIZE] {
+        let mut out = [0u8; FTL_SPARE_SIZE];
+        out[0..2].copy_from_slice(&FTL_PAGE_MAGIC);
+        out[2] = self.kind;
+        out[4..8].copy_from_slice(&self.index.to_le_bytes());
+        out[8..16].copy_from_slice(&self.sequence.to_le_bytes());
+        out[16..20].copy_from_slice(&self.erase_count.to_le_bytes());
+        out
+    }
+
+    fn parse(raw: &[u8]) -> Option<Self> {
+        if raw.len() < FTL_SPARE_SIZE || raw[0..2] != FTL_PAGE_MAGIC || raw[3] != 0 {
+            return None;
+        }
+        let kind = raw[2];
+        if kind != FTL_KIND_DATA && kind != FTL_KIND_CHECKPOINT {
+            return None;
+        }
+        Some(PageTag {
+            kind,
+            index: u32::from_le_bytes(raw[4..8].try_into().unwrap()),
+            sequence: u64::from_le_bytes(raw[8..16].try_into().unwrap()),
+            erase_count: u32::from_le_bytes(raw[16..20].try_into().unwrap()),
+        })
+    }
+}
+
+// Decoded checkpoint payload.
+struct Checkpoint {
+    sequence: u64,
+    logical_pages: u64,
+    l2p: Vec<u32>,
+    erase_counts: Vec<u32>,
+}
+
+impl Checkpoint {
+    fn size(logical_pages: u64, block_count: u32) -> usize {
+        FTL_CHECKPOINT_HEADER_SIZE + 4 * logical_pages as usize + 4 * block_count as usize + 4
+    }
+
+    fn parse(raw: &[u8], block_count: u32) -> Option<Self> {
+        if raw.len() < FTL_CHECKPOINT_HEADER_SIZE {
+            return None;
+        }
+        let magic = u32::from_le_bytes(raw[0..4].try_into().unwrap());
+        let version = u32::from_le_bytes(raw[4..8].try_into().unwrap());
+        let sequence = u64::from_le_bytes(raw[8..16].try_into().unwrap());
+        let logical_pages = u64::from_le_bytes(raw[16..24].try_into().unwrap());
+        let stored_blocks = u32::from_le_bytes(raw[24..28].try_into().unwrap());
+        if magic != FTL_CHECKPOINT_MAGIC || version != FTL_CHECKPOINT_VERSION || stored_blocks != block_count {
+            return None;
+        }
+        let size = Checkpoint::size(logical_pages, block_count);
+        if raw.len() < size {
+            return None;
+        }
+        let stored_crc = u32::from_le_bytes(raw[size - 4..size].try_into().unwrap());
+        if Crc32::checksum(&raw[..size - 4]) != stored_crc {
+            return None; // Torn or stale checkpoint
+        }
+        let words = |start: usize, count: usize| -> Vec<u32> {
+            raw[start..start + count * 4].chunks(4).map(|w| u32::from_le_bytes(w.try_into().unwrap())).collect()
+        };
+        let l2p = words(FTL_CHECKPOINT_HEADER_SIZE, logical_pages as usize);
+        let erase_counts = words(FTL_CHECKPOINT_HEADER_SIZE + 4 * logical_pages as usize, block_count as usize);
+        Some(Checkpoint { sequence, logical_pages, l2p, erase_counts })
+    }
+}
+
+/// Page-mapped FTL over a raw NAND device. Exposes `page_size` logical blocks.
+pub struct Ftl<N: NandFlash> {
+    nand: N,
+    geometry: NandGeometry,
+    config: FtlConfig,
+    logical_pages: u64,
+    l2p: Vec<u32>, // Logical page -> physical page (UNMAPPED if never written or discarded)
+    p2l: Vec<u32>, // Physical page -> logical page (UNMAPPED if invalid)
+    blocks: Vec<BlockInfo>,
+    open_block: Option<u32>,
+    checkpoint_block: Option<u32>,
+    sequence: u64, // Next sequence number
+    writes_since_checkpoint: u32,
+    stats: FtlStats,
+}
+
+impl<N: NandFlash> Ftl<N> {
+    /// Erases every good block, skips (and marks) bad ones and writes an empty checkpoint.
+    ///
+    /// # Returns
+    ///
+    /// The formatted FTL or FtlError (InvalidGeometry if too few good blocks remain).
+    pub fn format(mut nand: N, config: FtlConfig) -> Result<Self, FtlError> {
+        let geometry = nand.geometry();
+        Self::validate_geometry(&geometry)?;
+        let mut blocks = Vec::with_capacity(geometry.block_count as usize); // Requires alloc
+        for block in 0..geometry.block_count {
+            let mut info = BlockInfo { state: BlockState::Free, erase_count: 0, valid_pages: 0, write_ptr: 0 };
+            if nand.is_bad_block(block)? {
+                info.state = BlockState::Bad;
+            } else {
+                match nand.erase_block(block) {
+                    Ok(()) => {}
+                    Err(NandError::EraseFailed) => {
+                        nand.mark_bad_block(block)?;
+                        info.state = BlockState::Bad;
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            blocks.push(info);
+        }
+
+        let logical_pages = Self::logical_capacity(&geometry, &blocks, &config)?;
+        let mut ftl = Ftl::with_state(nand, geometry, config, logical_pages, blocks);
+        ftl.checkpoint()?;
+        Ok(ftl)
+    }
+
+    /// Rebuilds the FTL state from NAND: newest intact checkpoint plus newer pages found in
+    /// the spare areas (writes acknowledged after the last checkpoint survive a power loss).
+    pub fn mount(mut nand: N, config: FtlConfig) -> Result<Self, FtlError> {
+        let geometry = nand.geometry();
+        Self::validate_geometry(&geometry)?;
+        let ppb = geometry.pages_per_block;
+        let mut tags: Vec<Option<PageTag>> = vec![None; geometry.total_pages() as usize]; // Requires alloc
+        let mut blocks = Vec::with_capacity(geometry.block_count as usize);
+        let mut max_sequence = 0u64;
+        let mut spare = [0u8; FTL_SPARE_SIZE];
+
+        // 1. Scan spare areas
+        for block in 0..geometry.block_count {
+            let mut info = BlockInfo { state: BlockState::Full, erase_count: 0, valid_pages: 0, write_ptr: 0 };
+            if nand.is_bad_block(block)? {
+                info.state = BlockState::Bad;
+                blocks.push(info);
+                continue;
+            }
+            for page in 0..ppb {
+                match nand.read_spare(block, page, &mut spare) {
+                    Ok(()) if spare.iter().all(|b| *b == 0xFF) => continue, // Erased
+                    Ok(()) => {
+                        info.write_ptr = page + 1;
+                        if let Some(tag) = PageTag::parse(&spare) {
+                            info.erase_count = core::cmp::max(info.erase_count, tag.erase_count);
+                            max_sequence = core::cmp::max(max_sequence, tag.sequence);
+                            tags[(block * ppb + page) as usize] = Some(tag);
+                        }
+                    }
+                    Err(NandError::UncorrectableRead) => info.write_ptr = page + 1, // Torn page: garbage
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            if info.write_ptr == 0 {
+                info.state = BlockState::Free;
+            }
+            blocks.push(info);
+        }
+
+        // 2. Newest intact checkpoint
+        let mut best: Option<(u32, Checkpoint)> = None;
+        for block in 0..geometry.block_count {
+            let first = match tags[(block * ppb) as usize] {
+                Some(tag) if tag.kind == FTL_KIND_CHECKPOINT && tag.index == 0 => tag,
+                _ => continue,
+            };
+            if best.as_ref().map_or(false, |(_, cp)| cp.sequence >= first.sequence) {
+                continue;
+            }
+            let mut raw = Vec::new(); // Requires alloc
+            let mut data = vec![0u8; geometry.page_size];
+            for page in 0..ppb {
+                match tags[(block * ppb + page) as usize] {
+                    Some(tag) if tag.kind == FTL_KIND_CHECKPOINT && tag.index == page && tag.sequence == first.sequence => {
+                        nand.read_page(block, page, &mut data, &mut spare)?;
+                        raw.extend_from_slice(&data);
+                    }
+                    _ => break,
+                }
+            }
+            if let Some(cp) = Checkpoint::parse(&raw, geometry.block_count) {
+                if cp.sequence == first.sequence {
+                    best = Some((block, cp));
+                }
+            }
+        }
+        let (checkpoint_block, cp) = best.ok_or(FtlError::NotFormatted)?;
+
+        // 3. Mapping: checkpoint entries that still point at their page, then newer pages
+        let logical_pages = cp.logical_pages;
+        let mut l2p = vec![UNMAPPED; logical_pages as usize];
+        let mut lpn_sequence = vec![0u64; logical_pages as usize];
+        for (lpn, &ppn) in cp.l2p.iter().enumerate() {
+            if let Some(Some(tag)) = tags.get(ppn as usize) {
+                if tag.kind == FTL_KIND_DATA && tag.index as usize == lpn {
+                    l2p[lpn] = ppn;
+                    lpn_sequence[lpn] = tag.sequence;
+                }
+            }
+        }
+        for (ppn, tag) in tags.iter().enumerate() {
+            if let Some(tag) = tag {
+                let lpn = tag.index as usize;
+                if tag.kind == FTL_KIND_DATA && tag.sequence > cp.sequence && lpn < l2p.len() && tag.sequence > lpn_sequence[lpn] {
+                    l2p[lpn] = ppn as u32;
+                    lpn_sequence[lpn] = tag.sequence;
+                }
+            }
+        }
+
+        for (block, info) in blocks.iter_mut().enumerate() {
+            info.erase_count = core::cmp::max(info.erase_count, cp.erase_counts[block]);
+        }
+        blocks[checkpoint_block as usize].state = BlockState::Checkpoint;
+
+        let mut ftl = Ftl::with_state(nand, geometry, config, logical_pages, blocks);
+        ftl.checkpoint_block = Some(checkpoint_block);
+        ftl.sequence = max_sequence + 1;
+        for (lpn, &ppn) in l2p.iter().enumerate() {
+            if ppn != UNMAPPED {
+                ftl.map(lpn as u32, ppn);
+            }
+        }
+        Ok(ftl)
+    }
+
+    fn with_state(nand: N, geometry: NandGeometry, config: FtlConfig, logical_pages: u64, blocks: Vec<BlockInfo>) -> Self {
+        Ftl {
+            nand,
+            geometry,
+            config,
+            logical_pages,
+            l2p: vec![UNMAPPED; logical_pages as usize],
+            p2l: vec![UNMAPPED; geometry.total_pages() as usize],
+            blocks,
+            open_block: None,
+            checkpoint_block: None,
+            sequence: 1,
+            writes_since_checkpoint: 0,
+            stats: FtlStats::default(),
+        }
+    }
+
+    fn validate_geometry(geometry: &NandGeometry) -> Result<(), FtlError> {
+        if geometry.page_size == 0 || geometry.pages_per_block == 0 || geometry.block_count == 0 {
+            return Err(FtlError::InvalidGeometry(String::from("page size, pages per block and block count must be non-zero")));
+        }
+        if geometry.spare_size < FTL_SPARE_SIZE {
+            return Err(FtlError::InvalidGeometry(format!("spare area {} bytes, need {}", geometry.spare_size, FTL_SPARE_SIZE)));
+        }
+        Ok(())
+    }
+
+    fn logical_capacity(geometry: &NandGeometry, blocks: &[BlockInfo], config: &FtlConfig) -> Result<u64, FtlError> {
+        let good = blocks.iter().filter(|b| b.state != BlockState::Bad).count() as u32;
+        let reserve = core::cmp::max(FTL_MIN_SPARE_BLOCKS, good * config.overprovision_percent / 100);
+        if good <= reserve {
+            return Err(FtlError::InvalidGeometry(format!("{} good blocks, {} reserved", good, reserve)));
+        }
+        let logical_pages = (good - reserve) as u64 * geometry.pages_per_block as u64;
+        let block_bytes = geometry.page_size * geometry.pages_per_block as usize;
+        if Checkpoint::size(logical_pages, geometry.block_count) > block_bytes {
+            return Err(FtlError::InvalidGeometry(String::from("mapping checkpoint does not fit in one erase block")));
+        }
+        Ok(logical_pages)
+    }
+
+    /// Number of logical pages exposed to the host.
+    pub fn logical_page_count(&self) -> u64 {
+        self.logical_pages
+    }
+
+    /// Page size (the logical block size).
+    pub fn page_size(&self) -> usize {
+        self.geometry.page_size
+    }
+
+    /// Write amplification and maintenance counters.
+    pub fn stats(&self) -> FtlStats {
+        self.stats
+    }
+
+    /// Erase count of every erase block (bad blocks included).
+    pub fn erase_counts(&self) -> Vec<u32> {
+        self.blocks.iter().map(|b| b.erase_count).collect()
+    }
+
+    /// Number of blocks that are bad (factory or retired).
+    pub fn bad_block_count(&self) -> usize {
+        self.blocks.iter().filter(|b| b.state == BlockState::Bad).count()
+    }
+
+    /// Gives access to the NAND device.
+    pub fn nand(&self) -> &N {
+        &self.nand
+    }
+
+    /// Mutable access to the NAND device (e.g. to inject faults in tests).
+    pub fn nand_mut(&mut self) -> &mut N {
+        &mut self.nand
+    }
+
+    /// Releases the NAND device (e.g. to mount it again after a simulated power loss).
+    pub fn into_nand(self) -> N {
+        self.nand
+    }
+
+    /// Reads a logical page. Unwritten or discarded pages read as zeros.
+    pub fn read_page(&mut self, lpn: u64, buf: &mut [u8]) -> Result<(), FtlError> {
+        self.check_access(lpn, buf.len())?;
+        let ppn = self.l2p[lpn as usize];
+        if ppn == UNMAPPED {
+            buf.fill(0);
+            return Ok(());
+        }
+        let (block, page) = self.split(ppn);
+        let mut spare = [0u8; FTL_SPARE_SIZE];
+        self.nand.read_page(block, page, buf, &mut spare)?;
+        Ok(())
+    }
+
+    /// Writes a logical page out of place.
+    pub fn write_page(&mut self, lpn: u64, data: &[u8]) -> Result<(), FtlError> {
+        self.check_access(lpn, data.len())?;
+        self.program_logical(lpn as u32, data, true)?;
+        self.stats.host_writes += 1;
+        self.static_wear_level()?;
+        self.writes_since_checkpoint += 1;
+        if self.config.checkpoint_interval != 0 && self.writes_since_checkpoint >= self.config.checkpoint_interval {
+            self.checkpoint()?;
+        }
+        Ok(())
+    }
+
+    /// Discards logical pages: they read as zeros and their physical pages become reclaimable.
+    ///
+    /// The old copies stay on NAND with valid spare tags until they are erased, so the unmapped
+    /// state is persisted with a checkpoint before returning; otherwise the mount replay would
+    /// bring them back after a power loss.
+    pub fn trim(&mut self, lpn: u64, count: u64) -> Result<(), FtlError> {
+        let end = lpn.checked_add(count).filter(|end| *end <= self.logical_pages).ok_or(FtlError::InvalidAddress(lpn))?;
+        let mut changed = false;
+        for page in lpn..end {
+            changed |= self.l2p[page as usize] != UNMAPPED;
+            self.unmap(page as u32);
+        }
+        if changed {
+            self.checkpoint()?;
+        }
+        Ok(())
+    }
+
+    /// Writes a new mapping checkpoint to a fresh erase block, then erases the previous one.
+    pub fn checkpoint(&mut self) -> Result<(), FtlError> {
+        loop {
+            self.garbage_collect()?;
+            let block = self.take_free_block()?;
+            self.blocks[block as usize].state = BlockState::Checkpoint;
+            let sequence = self.next_sequence();
+            let payload = self.serialize_checkpoint(sequence);
+
+            let mut failed = false;
+            for (page, chunk) in payload.chunks(self.geometry.page_size).enumerate() {
+                let mut data = vec![0xFFu8; self.geometry.page_size]; // Requires alloc
+                data[..chunk.len()].copy_from_slice(chunk);
+                let tag = PageTag {
+                    kind: FTL_KIND_CHECKPOINT,
+                    index: page as u32,
+                    sequence,
+                    erase_count: self.blocks[block as usize].erase_count,
+                };
+                self.blocks[block as usize].write_ptr = page as u32 + 1;
+                match self.nand.program_page(block, page as u32, &data, &tag.to_bytes()) {
+                    Ok(()) => self.stats.pages_programmed += 1,
+                    Err(NandError::ProgramFailed) => {
+                        failed = true;
+                        break;
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            if failed {
+                self.retire_block(block)?; // Try again on another block
+                continue;
+            }
+
+            if let Some(previous) = self.checkpoint_block.replace(block) {
+                self.erase_and_free(previous)?; // Only after the new checkpoint is complete
+            }
+            self.stats.checkpoints += 1;
+            self.writes_since_checkpoint = 0;
+            return Ok(());
+        }
+    }
+
+    fn serialize_checkpoint(&self, sequence: u64) -> Vec<u8> {
+        let size = Checkpoint::size(self.logical_pages, self.geometry.block_count);
+        let mut out = Vec::with_capacity(size); // Requires alloc
+        out.extend_from_slice(&FTL_CHECKPOINT_MAGIC.to_le_bytes());
+        out.extend_from_slice(&FTL_CHECKPOINT_VERSION.to_le_bytes());
+        out.extend_from_slice(&sequence.to_le_bytes());
+        out.extend_from_slice(&self.logical_pages.to_le_bytes());
+        out.extend_from_slice(&self.geometry.block_count.to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes()); // Reserved
+        for ppn in &self.l2p {
+            out.extend_from_slice(&ppn.to_le_bytes());
+        }
+        for info in &self.blocks {
+            out.extend_from_slice(&info.erase_count.to_le_bytes());
+        }
+        let crc = Crc32::checksum(&out);
+        out.extend_from_slice(&crc.to_le_bytes());
+        out
+    }
+
+    fn check_access(&self, lpn: u64, len: usize) -> Result<(), FtlError> {
+        if lpn >= self.logical_pages {
+            return Err(FtlError::InvalidAddress(lpn));
+        }
+        if len != self.geometry.page_size {
+            return Err(FtlError::BufferSize(len));
+        }
+        Ok(())
+    }
+
+    fn split(&self, ppn: u32) -> (u32, u32) {
+        (ppn / self.geometry.pages_per_block, ppn % self.geometry.pages_per_block)
+    }
+
+    fn next_sequence(&mut self) -> u64 {
+        let sequence = self.sequence;
+        self.sequence += 1;
+        sequence
+    }
+
+    fn free_block_count(&self) -> u32 {
+        self.blocks.iter().filter(|b| b.state == BlockState::Free).count() as u32
+    }
+
+    fn map(&mut self, lpn: u32, ppn: u32) {
+        self.unmap(lpn);
+        self.l2p[lpn as usize] = ppn;
+        self.p2l[ppn as usize] = lpn;
+        self.blocks[(ppn / self.geometry.pages_per_block) as usize].valid_pages += 1;
+    }
+
+    fn unmap(&mut self, lpn: u32) {
+        let old = self.l2p[lpn as usize];
+        if old != UNMAPPED {
+            self.p2l[old as usize] = UNMAPPED;
+            self.blocks[(old / self.geometry.pages_per_block) as usize].valid_pages -= 1;
+            self.l2p[lpn as usize] = UNMAPPED;
+        }
+    }
+
+    // Dynamic wear leveling: the least-worn free block is used next.
+    fn take_free_block(&mut self) -> Result<u32, FtlError> {
+        let block = self
+            .blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.state == BlockState::Free)
+            .min_by_key(|(_, b)| b.erase_count)
+            .map(|(i, _)| i as u32)
+            .ok_or(FtlError::NoSpace)?;
+        let info = &mut self.blocks[block as usize];
+        info.state = BlockState::Open;
+        info.write_ptr = 0;
+        info.valid_pages = 0;
+        Ok(block)
+    }
+
+    // Next physical page of the open block; opens a new block (after GC if allowed) when full.
+    fn allocate_page(&mut self, allow_gc: bool) -> Result<(u32, u32), FtlError> {
+        if let Some(block) = self.open_block {
+            let info = &mut self.blocks[block as usize];
+            if info.write_ptr < self.geometry.pages_per_block {
+                let page = info.write_ptr;
+                info.write_ptr += 1;
+                return Ok((block, page));
+            }
+            info.state = BlockState::Full;
+            self.open_block = None;
+        }
+        if allow_gc {
+            self.garbage_collect()?;
+            if let Some(block) = self.open_block {
+                // GC relocations opened a block that still has room
+                if self.blocks[block as usize].write_ptr < self.geometry.pages_per_block {
+                    return self.allocate_page(false);
+                }
+            }
+        }
+        let block = self.take_free_block()?;
+        self.open_block = Some(block);
+        self.blocks[block as usize].write_ptr = 1;
+        Ok((block, 0))
+    }
+
+    // Programs `data` for `lpn` at a new location, retiring blocks that fail to program.
+    fn program_logical(&mut self, lpn: u32, data: &[u8], allow_gc: bool) -> Result<(), FtlError> {
+        loop {
+            let (block, page) = self.allocate_page(allow_gc)?;
+            let tag = PageTag {
+                kind: FTL_KIND_DATA,
+                index: lpn,
+                sequence: self.next_sequence(),
+                erase_count: self.blocks[block as usize].erase_count,
+            };
+            match self.nand.program_page(block, page, data, &tag.to_bytes()) {
+                Ok(()) => {
+                    self.stats.pages_programmed += 1;
+                    self.map(lpn, block * self.geometry.pages_per_block + page);
+                    return Ok(());
+                }
+                Err(NandError::ProgramFailed) => self.retire_block(block)?,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    // Relocates the valid pages of a failing block and marks it bad.
+    fn retire_block(&mut self, block: u32) -> Result<(), FtlError> {
+        if self.open_block == Some(block) {
+            self.open_block = None;
+        }
+        self.blocks[block as usize].state = BlockState::Bad;
+        self.relocate_valid_pages(block)?;
+        self.nand.mark_bad_block(block)?;
+        self.stats.retired_blocks += 1;
+        Ok(())
+    }
+
+    fn relocate_valid_pages(&mut self, block: u32) -> Result<(), FtlError> {
+        let ppb = self.geometry.pages_per_block;
+        let mut data = vec![0u8; self.geometry.page_size]; // Requires alloc
+        let mut spare = [0u8; FTL_SPARE_SIZE];
+        for page in 0..ppb {
+            let lpn = self.p2l[(block * ppb + page) as usize];
+            if lpn == UNMAPPED {
+                continue;
+            }
+            self.nand.read_page(block, page, &mut data, &mut spare)?;
+            self.program_logical(lpn, &data, false)?;
+        }
+        Ok(())
+    }
+
+    fn erase_and_free(&mut self, block: u32) -> Result<(), FtlError> {
+        let info = &mut self.blocks[block as usize];
+        info.valid_pages = 0;
+        info.write_ptr = 0;
+        match self.nand.erase_block(block) {
+            Ok(()) => {
+                info.erase_count += 1;
+                info.state = BlockState::Free;
+                self.stats.erases += 1;
+                Ok(())
+            }
+            Err(NandError::EraseFailed) => {
+                info.state = BlockState::Bad;
+                self.nand.mark_bad_block(block)?;
+                self.stats.retired_blocks += 1;
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // Moves the valid pages of `block` elsewhere and erases it.
+    fn collect_block(&mut self, block: u32) -> Result<(), FtlError> {
+        self.relocate_valid_pages(block)?;
+        self.erase_and_free(block)
+    }
+
+    // Greedy GC: reclaim the full blocks with the fewest valid pages until enough blocks are free.
+    fn garbage_collect(&mut self) -> Result<(), FtlError> {
+        let ppb = self.geometry.pages_per_block;
+        for _ in 0..self.geometry.block_count {
+            if self.free_block_count() >= self.config.gc_free_block_threshold {
+                break;
+            }
+            let victim = self
+                .blocks
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| b.state == BlockState::Full && b.valid_pages < ppb)
+                .min_by_key(|(_, b)| (b.valid_pages, b.erase_count))
+                .map(|(i, _)| i as u32);
+            match victim {
+                Some(block) => {
+                    self.collect_block(block)?;
+                    self.stats.gc_runs += 1;
+                }
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    // Static wear leveling: recycle the coldest written block once the erase count spread is too large.
+    fn static_wear_level(&mut self) -> Result<(), FtlError> {
+        if self.config.wear_level_threshold == 0 || self.free_block_count() == 0 {
+            return Ok(());
+        }
+        let max_erase = self.blocks.iter().filter(|b| b.state != BlockState::Bad).map(|b| b.erase_count).max().unwrap_or(0);
+        let coldest = self
+            .blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.state == BlockState::Full)
+            .min_by_key(|(_, b)| b.erase_count)
+            .map(|(i, b)| (i as u32, b.erase_count));
+        if let Some((block, erase_count)) = coldest {
+            if max_erase - erase_count > self.config.wear_level_threshold {
+                self.collect_block(block)?;
+                self.stats.wear_level_moves += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<N: NandFlash> BlockDevice for Ftl<N> {
+    /// Reads one logical page.
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> Result<(), BlockDeviceError> {
+        self.read_page(block_id, buf).map_err(map_ftl_error_to_block_device_error)
+    }
+
+    /// Writes one logical page.
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> Result<(), BlockDeviceError> {
+        self.write_page(block_id, buf).map_err(map_ftl_error_to_block_device_error)
+    }
+
+    /// Returns the NAND page size.
+    fn block_size(&self) -> usize {
+        self.geometry.page_size
+    }
+
+    /// Returns the number of logical pages.
+    fn block_count(&self) -> u64 {
+        self.logical_pages
+    }
+
+    /// Unmaps the pages so garbage collection can reclaim them.
+    fn discard_blocks(&mut self, start_block: u64, block_count: u64) -> Result<(), BlockDeviceError> {
+        self.trim(start_block, block_count).map_err(map_ftl_error_to_block_device_error)
+    }
+
+    /// Persists the mapping (checkpoint).
+    fn flush(&mut self) -> Result<(), BlockDeviceError> {
+        self.checkpoint().map_err(map_ftl_error_to_block_device_error)
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+    use crate::ssd::NandSimulator;
+
+    fn geometry() -> NandGeometry {
+        NandGeometry { page_size: 256, spare_size: 32, pages_per_block: 16, block_count: 40 }
+    }
+
+    fn page(lpn: u64, version: u32) -> Vec<u8> {
+        let mut data = vec![0u8; 256];
+        data[0..8].copy_from_slice(&lpn.to_le_bytes());
+        data[8..12].copy_from_slice(&version.to_le_bytes());
+        for (i, byte) in data[12..].iter_mut().enumerate() {
+            *byte = (i as u8).wrapping_add(lpn as u8).wrapping_mul(version as u8 | 1);
+        }
+        data
+    }
+
+    fn verify(ftl: &mut Ftl<NandSimulator>, expected: &[Option<u32>]) {
+        let mut buf = vec![0u8; 256];
+        for (lpn, version) in expected.iter().enumerate() {
+            ftl.read_page(lpn as u64, &mut buf).unwrap();
+            match version {
+                Some(v) => assert_eq!(buf, page(lpn as u64, *v), "logical page {}", lpn),
+                None => assert_eq!(buf, vec![0u8; 256], "logical page {}", lpn),
+            }
+        }
+    }
+
+    #[test]
+    fn test_overwrite_gc_and_capacity() -> Result<(), FtlError> {
+        let mut ftl = Ftl::format(NandSimulator::new(geometry()), FtlConfig::default())?;
+        // 40 blocks, 4 reserved -> 36 * 16 logical pages
+        assert_eq!(ftl.logical_page_count(), 36 * 16);
+        let pages = ftl.logical_page_count();
+        let mut expected = vec![None; pages as usize];
+
+        // Write the whole device three times in a scrambled order: forces garbage collection
+        for round in 1..=3u32 {
+            for i in 0..pages {
+                let lpn = (i * 7 + round as u64) % pages;
+                ftl.write_page(lpn, &page(lpn, round))?;
+                expected[lpn as usize] = Some(round);
+            }
+        }
+        verify(&mut ftl, &expected);
+
+        let stats = ftl.stats();
+        assert_eq!(stats.host_writes, 3 * pages);
+        assert!(stats.gc_runs > 0);
+        assert!(stats.pages_programmed >= stats.host_writes);
+        // The simulator enforced erase-before-write: every erase is accounted for
+        assert_eq!(ftl.nand().total_erases(), stats.erases + 40); // + one erase per block during format
+
+        assert!(matches!(ftl.write_page(pages, &page(0, 1)), Err(FtlError::InvalidAddress(_))));
+        assert!(matches!(ftl.write_page(0, &[0u8; 10]), Err(FtlError::BufferSize(10))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_static_wear_leveling() -> Result<(), FtlError> {
+        let config = FtlConfig { wear_level_threshold: 4, ..FtlConfig::default() };
+        let mut ftl = Ftl::format(NandSimulator::new(geometry()), config)?;
+        let pages = ftl.logical_page_count();
+
+        // Cold data fills most of the device once, then a few hot pages are rewritten many times
+        for lpn in 0..pages - 32 {
+            ftl.write_page(lpn, &page(lpn, 1))?;
+        }
+        for round in 0..400u32 {
+            let lpn = pages - 1 - (round as u64 % 8);
+            ftl.write_page(lpn, &page(lpn, round))?;
+        }
+
+        assert!(ftl.stats().wear_level_moves > 0);
+        let counts = ftl.erase_counts();
+        let spread = counts.iter().max().unwrap() - counts.iter().min().unwrap();
+        assert!(spread <= 4 + 2, "erase count spread {} ({:?})", spread, counts);
+        let mut buf = vec![0u8; 256];
+        ftl.read_page(3, &mut buf)?;
+        assert_eq!(buf, page(3, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bad_block_management() -> Result<(), FtlError> {
+        let mut nand = NandSimulator::new(geometry());
+        nand.set_factory_bad_block(0);
+        nand.set_factory_bad_block(17);
+        let mut ftl = Ftl::format(nand, FtlConfig::default())?;
+        assert_eq!(ftl.bad_block_count(), 2);
+        assert_eq!(ftl.logical_page_count(), 34 * 16);
+
+        let mut expected = vec![None; ftl.logical_page_count() as usize];
+        for lpn in 0..40u64 {
+            ftl.write_page(lpn, &page(lpn, 1))?;
+            expected[lpn as usize] = Some(1);
+        }
+        // Next program in the open block fails: its valid pages must be moved and the block retired
+        let open = ftl.open_block.unwrap();
+        let next = ftl.blocks[open as usize].write_ptr;
+        ftl.nand_mut().fail_program(open, next);
+        for lpn in 40..60u64 {
+            ftl.write_page(lpn, &page(lpn, 1))?;
+            expected[lpn as usize] = Some(1);
+        }
+        assert_eq!(ftl.stats().retired_blocks, 1);
+        assert_eq!(ftl.bad_block_count(), 3);
+        assert!(ftl.nand_mut().is_bad_block(open).unwrap());
+        verify(&mut ftl, &expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_power_loss_recovery() -> Result<(), FtlError> {
+        let mut ftl = Ftl::format(NandSimulator::new(geometry()), FtlConfig::default())?;
+        let pages = ftl.logical_page_count();
+        let mut expected = vec![None; pages as usize];
+        for lpn in 0..100u64 {
+            ftl.write_page(lpn, &page(lpn, 1))?;
+            expected[lpn as usize] = Some(1);
+        }
+        ftl.trim(10, 5)?;
+        for lpn in 10..15 {
+            expected[lpn] = None;
+        }
+        let checkpoints = ftl.stats().checkpoints;
+        ftl.trim(10, 5)?; // Already unmapped: no extra checkpoint
+        assert_eq!(ftl.stats().checkpoints, checkpoints);
+
+        // Acknowledged after the trim's checkpoint: must survive through the spare-area replay
+        for lpn in 50..80u64 {
+            ftl.write_page(lpn, &page(lpn, 2))?;
+            expected[lpn as usize] = Some(2);
+        }
+        // Power is cut during the next write: that write is lost, nothing else
+        ftl.nand_mut().cut_power_after(0);
+        assert_eq!(ftl.write_page(90, &page(90, 3)), Err(FtlError::Nand(NandError::PowerLoss)));
+
+        let mut nand = ftl.into_nand();
+        nand.power_on();
+        let mut ftl = Ftl::mount(nand, FtlConfig::default())?;
+        verify(&mut ftl, &expected);
+
+        // Power cut in the middle of writing a checkpoint: the previous checkpoint is used
+        ftl.write_page(200, &page(200, 4))?;
+        expected[200] = Some(4);
+        ftl.nand_mut().cut_power_after(2);
+        assert!(ftl.checkpoint().is_err());
+        let mut nand = ftl.into_nand();
+        nand.power_on();
+        let mut ftl = Ftl::mount(nand, FtlConfig::default())?;
+        verify(&mut ftl, &expected);
+
+        // The remounted FTL keeps working and its GC still respects erase semantics
+        for round in 5..8u32 {
+            for lpn in 0..pages {
+                ftl.write_page(lpn, &page(lpn, round))?;
+            }
+        }
+        ftl.read_page(pages - 1, &mut vec![0u8; 256])?;
+        assert!(Ftl::mount(NandSimulator::new(geometry()), FtlConfig::default()).err() == Some(FtlError::NotFormatted));
+        Ok(())
+    }
+
+    #[test]
+    fn test_block_device_interface() -> Result<(), BlockDeviceError> {
+        let mut ftl = Ftl::format(NandSimulator::new(geometry()), FtlConfig { checkpoint_interval: 16, ..FtlConfig::default() })
+            .map_err(map_ftl_error_to_block_device_error)?;
+        assert_eq!(ftl.block_size(), 256);
+        ftl.write_block(5, &page(5, 1))?;
+        ftl.discard_blocks(5, 1)?;
+        let mut buf = vec![1u8; 256];
+        ftl.read_block(5, &mut buf)?;
+        assert_eq!(buf, vec![0u8; 256]);
+        for lpn in 0..20 {
+            ftl.write_block(lpn, &page(lpn, 1))?;
+        }
+        assert_eq!(ftl.stats().checkpoints, 3); // Format + discard + automatic checkpoint after 16 writes
+        ftl.flush()?;
+        assert_eq!(ftl.stats().checkpoints, 4);
+        assert!(matches!(ftl.read_block(ftl.block_count(), &mut buf), Err(BlockDeviceError::InvalidParameter(_))));
+        Ok(())
+    }
+}
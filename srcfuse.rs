@@ -0,0 +1,887 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// SADAK imajlarını Linux geliştirme makinelerinde FUSE ile bağlamak için bağdaştırıcı.
+//
+// `FuseAdapter` FUSE işlemlerini (lookup, getattr, readdir, read, write, create, mkdir, unlink,
+// rename, setattr, xattr...) bir `Vfs` üzerindeki yol tabanlı işlemlere çevirir ve hataları
+// errno olarak döndürür. FUSE inode numaraları bağdaştırıcı tarafından yollara atanır
+// (kök 1); dosya sistemi inode numarası `Metadata::inode` yalnızca bilgi amaçlıdır.
+//
+// `fuser` crate'ine bağlanan kısım ve `main` yalnızca `std` ve `fuse` özellikleriyle derlenir:
+//
+//   sadak-fuse <imaj> <bağlama noktası> [--ro]
+
+use crate::error::errno;
+use crate::sadakfs::SadakStats;
+use crate::vfs::{
+    map_vfs_error_to_fs_error, FileType, Metadata, SetMetadata, Vfs, VfsError, VfsFile, O_ACCMODE, O_APPEND, O_CREAT,
+    O_EXCL, O_TRUNC,
+};
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use alloc::format;
+
+use core::result::Result;
+
+/// FUSE kök dizininin inode numarası.
+pub const ROOT_INO: u64 = 1;
+
+/// `statfs` için bildirilen en uzun ad.
+pub const NAME_MAX: u32 = 255;
+
+// FUSE'dan gelen açma bayraklarından VFS'nin anladıkları (değerler Linux ile aynı)
+const OPEN_FLAGS_MASK: u32 = O_ACCMODE | O_CREAT | O_EXCL | O_TRUNC | O_APPEND;
+
+/// VFS hatasını FUSE yanıtı için errno'ya çevirir.
+pub fn vfs_errno(e: VfsError) -> i32 {
+    match e {
+        VfsError::NoAttribute => errno::ENODATA,
+        e => map_vfs_error_to_fs_error(e).errno(),
+    }
+}
+
+/// FUSE'a bildirilen dosya öznitelikleri (`fuser::FileAttr`'dan bağımsız).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FuseAttr {
+    pub ino: u64,
+    pub size: u64,
+    pub blocks: u64, // 512 baytlık birimler
+    pub atime: u64,
+    pub mtime: u64,
+    pub ctime: u64,
+    pub kind: FileType,
+    pub perm: u16,
+    pub nlink: u32,
+    pub uid: u32,
+    pub gid: u32,
+    pub blksize: u32,
+}
+
+/// Dizin okumasında tek girdi: inode, sonraki girdinin ofseti, tür ve ad.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuseDirEntry {
+    pub ino: u64,
+    pub offset: i64,
+    pub kind: FileType,
+    pub name: String,
+}
+
+fn child_path(parent: &str, name: &str) -> Result<String, i32> {
+    if name.is_empty() || name == "." || name == ".." || name.contains('/') {
+        return Err(errno::EINVAL);
+    }
+    if parent == "/" {
+        Ok(format!("/{}", name))
+    } else {
+        Ok(format!("{}/{}", parent, name))
+    }
+}
+
+fn parent_path(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(0) | None => "/",
+        Some(i) => &path[..i],
+    }
+}
+
+fn in_subtree(path: &str, root: &str) -> bool {
+    path == root || (path.starts_with(root) && path.as_bytes().get(root.len()) == Some(&b'/'))
+}
+
+/// FUSE işlemlerini bir `Vfs` üzerinde yürüten bağdaştırıcı.
+pub struct FuseAdapter {
+    vfs: Arc<Vfs>,
+    paths: BTreeMap<u64, String>, // FUSE inode -> yol
+    inodes: BTreeMap<String, u64>, // yol -> FUSE inode
+    lookups: BTreeMap<u64, u64>, // FUSE inode -> çekirdeğin tuttuğu başvuru sayısı
+    next_ino: u64,
+    handles: BTreeMap<u64, Box<dyn VfsFile>>,
+    next_fh: u64,
+    block_size: u32,
+    stats: Option<Box<dyn Fn() -> SadakStats + Send>>,
+}
+
+impl FuseAdapter {
+    pub fn new(vfs: Arc<Vfs>) -> Self {
+        let mut adapter = FuseAdapter {
+            vfs,
+            paths: BTreeMap::new(),
+            inodes: BTreeMap::new(),
+            lookups: BTreeMap::new(),
+            next_ino: ROOT_INO + 1,
+            handles: BTreeMap::new(),
+            next_fh: 1,
+            block_size: 4096,
+            stats: None,
+        };
+        adapter.paths.insert(ROOT_INO, String::from("/"));
+        adapter.inodes.insert(String::from("/"), ROOT_INO);
+        adapter
+    }
+
+    /// `statfs` yanıtları için kapasite kaynağı (genellikle `SadakFs::stats`).
+    pub fn with_stats(mut self, stats: Box<dyn Fn() -> SadakStats + Send>) -> Self {
+        self.block_size = stats().block_size;
+        self.stats = Some(stats);
+        self
+    }
+
+    pub fn vfs(&self) -> &Arc<Vfs> {
+        &self.vfs
+    }
+
+    fn path(&self, ino: u64) -> Result<String, i32> {
+        self.paths.get(&ino).cloned().ok_or(errno::ENOENT)
+    }
+
+    fn ino_for(&mut self, path: &str) -> u64 {
+        if let Some(&ino) = self.inodes.get(path) {
+            return ino;
+        }
+        let ino = self.next_ino;
+        self.next_ino += 1;
+        self.paths.insert(ino, path.to_string());
+        self.inodes.insert(path.to_string(), ino);
+        ino
+    }
+
+    // Yolu ve altındaki tüm yolları eşlemeden çıkarır.
+    fn drop_paths(&mut self, path: &str) {
+        let gone: Vec<String> = self.inodes.keys().filter(|p| in_subtree(p, path)).cloned().collect();
+        for p in gone {
+            if let Some(ino) = self.inodes.remove(&p) {
+                self.paths.remove(&ino);
+                self.lookups.remove(&ino);
+            }
+        }
+    }
+
+    fn attr(&self, ino: u64, meta: &Metadata) -> FuseAttr {
+        FuseAttr {
+            ino,
+            size: meta.size,
+            blocks: meta.size.div_ceil(512),
+            atime: meta.atime,
+            mtime: meta.mtime,
+            ctime: meta.ctime,
+            kind: meta.file_type,
+            perm: (meta.mode & 0o7777) as u16,
+            nlink: meta.nlink,
+            uid: meta.uid,
+            gid: meta.gid,
+            blksize: self.block_size,
+        }
+    }
+
+    // Çekirdeğe verilen girdi; her yanıt başvuru sayısını bir artırır (bkz. `forget`).
+    fn entry(&mut self, path: &str) -> Result<FuseAttr, i32> {
+        let meta = self.vfs.metadata(path).map_err(vfs_errno)?;
+        let ino = self.ino_for(path);
+        *self.lookups.entry(ino).or_insert(0) += 1;
+        Ok(self.attr(ino, &meta))
+    }
+
+    // Yeni düğümü isteği yapan kullanıcıya verir.
+    fn chown_new(&mut self, path: &str, uid: u32, gid: u32) -> Result<FuseAttr, i32> {
+        let owner = SetMetadata { uid: Some(uid), gid: Some(gid), ..SetMetadata::default() };
+        self.vfs.set_metadata(path, &owner).map_err(vfs_errno)?;
+        self.entry(path)
+    }
+
+    pub fn lookup(&mut self, parent: u64, name: &str) -> Result<FuseAttr, i32> {
+        let path = child_path(&self.path(parent)?, name)?;
+        self.entry(&path)
+    }
+
+    /// Çekirdek `nlookup` başvuruyu bıraktı; sayı sıfıra inince inode eşlemeden çıkarılır.
+    /// Kök hiçbir zaman çıkarılmaz.
+    pub fn forget(&mut self, ino: u64, nlookup: u64) {
+        let Some(count) = self.lookups.get_mut(&ino) else { return };
+        *count = count.saturating_sub(nlookup);
+        if *count == 0 {
+            self.lookups.remove(&ino);
+            if ino != ROOT_INO {
+                if let Some(path) = self.paths.remove(&ino) {
+                    self.inodes.remove(&path);
+                }
+            }
+        }
+    }
+
+    pub fn getattr(&mut self, ino: u64) -> Result<FuseAttr, i32> {
+        let path = self.path(ino)?;
+        let meta = self.vfs.metadata(&path).map_err(vfs_errno)?;
+        Ok(self.attr(ino, &meta))
+    }
+
+    /// Boyut değişikliği açık tanıtıcı verilmişse onun üzerinden yapılır.
+    pub fn setattr(&mut self, ino: u64, fh: Option<u64>, changes: &SetMetadata) -> Result<FuseAttr, i32> {
+        let path = self.path(ino)?;
+        let mut changes = changes.clone();
+        if let (Some(size), Some(file)) = (changes.size, fh.and_then(|fh| self.handles.get_mut(&fh))) {
+            file.set_len(size).map_err(vfs_errno)?;
+            changes.size = None;
+        }
+        if changes != SetMetadata::default() {
+            self.vfs.set_metadata(&path, &changes).map_err(vfs_errno)?;
+        }
+        self.getattr(ino)
+    }
+
+    /// `offset` önceki yanıttaki son girdinin ofsetidir (ilk çağrıda 0).
+    pub fn readdir(&mut self, ino: u64, offset: i64) -> Result<Vec<FuseDirEntry>, i32> {
+        let path = self.path(ino)?;
+        let children = self.vfs.read_dir(&path).map_err(vfs_errno)?;
+        let parent = parent_path(&path).to_string();
+        let mut entries = vec![
+            FuseDirEntry { ino, offset: 1, kind: FileType::Directory, name: String::from(".") },
+            FuseDirEntry { ino: self.ino_for(&parent), offset: 2, kind: FileType::Directory, name: String::from("..") },
+        ];
+        for (i, child) in children.enumerate() {
+            let child_ino = self.ino_for(&child_path(&path, &child.name)?);
+            entries.push(FuseDirEntry { ino: child_ino, offset: i as i64 + 3, kind: child.file_type, name: child.name });
+        }
+        Ok(entries.into_iter().skip(offset.max(0) as usize).collect())
+    }
+
+    pub fn open(&mut self, ino: u64, flags: u32) -> Result<u64, i32> {
+        let path = self.path(ino)?;
+        let file = self.vfs.open(&path, flags & OPEN_FLAGS_MASK & !(O_CREAT | O_EXCL), 0).map_err(vfs_errno)?;
+        let fh = self.next_fh;
+        self.next_fh += 1;
+        self.handles.insert(fh, file);
+        Ok(fh)
+    }
+
+    pub fn create(&mut self, parent: u64, name: &str, mode: u32, flags: u32, uid: u32, gid: u32) -> Result<(FuseAttr, u64), i32> {
+        let path = child_path(&self.path(parent)?, name)?;
+        let file = self.vfs.open(&path, (flags & OPEN_FLAGS_MASK) | O_CREAT, mode & 0o7777).map_err(vfs_errno)?;
+        let fh = self.next_fh;
+        self.next_fh += 1;
+        self.handles.insert(fh, file);
+        let attr = self.chown_new(&path, uid, gid)?;
+        Ok((attr, fh))
+    }
+
+    pub fn read(&mut self, fh: u64, offset: u64, size: u32) -> Result<Vec<u8>, i32> {
+        let file = self.handles.get_mut(&fh).ok_or(errno::EINVAL)?;
+        let mut buf = vec![0u8; size as usize];
+        // Kısa okuma çekirdeğe dosya sonu demektir; tampon dolana veya dosya bitene kadar okunur
+        let mut done = 0;
+        while done < buf.len() {
+            let count = file.read_at(offset + done as u64, &mut buf[done..]).map_err(vfs_errno)?;
+            if count == 0 {
+                break;
+            }
+            done += count;
+        }
+        buf.truncate(done);
+        Ok(buf)
+    }
+
+    pub fn write(&mut self, fh: u64, offset: u64, data: &[u8]) -> Result<u32, i32> {
+        let file = self.handles.get_mut(&fh).ok_or(errno::EINVAL)?;
+        file.write_at(offset, data).map(|n| n as u32).map_err(vfs_errno)
+    }
+
+    pub fn fsync(&mut self, fh: u64) -> Result<(), i32> {
+        let file = self.handles.get_mut(&fh).ok_or(errno::EINVAL)?;
+        file.sync().map_err(vfs_errno)
+    }
+
+    pub fn release(&mut self, fh: u64) -> Result<(), i32> {
+        self.handles.remove(&fh).map(|_| ()).ok_or(errno::EINVAL)
+    }
+
+    pub fn mkdir(&mut self, parent: u64, name: &str, mode: u32, uid: u32, gid: u32) -> Result<FuseAttr, i32> {
+        let path = child_path(&self.path(parent)?, name)?;
+        self.vfs.mkdir(&path, mode & 0o7777).map_err(vfs_errno)?;
+        self.chown_new(&path, uid, gid)
+    }
+
+    pub fn symlink(&mut self, parent: u64, name: &str, target: &str, uid: u32, gid: u32) -> Result<FuseAttr, i32> {
+        let path = child_path(&self.path(parent)?, name)?;
+        self.vfs.symlink(target, &path).map_err(vfs_errno)?;
+        self.chown_new(&path, uid, gid)
+    }
+
+    pub fn readlink(&mut self, ino: u64) -> Result<String, i32> {
+        let path = self.path(ino)?;
+        self.vfs.readlink(&path).map_err(vfs_errno)
+    }
+
+    /// unlink(2): dizinler için EISDIR.
+    pub fn unlink(&mut self, parent: u64, name: &str) -> Result<(), i32> {
+        let path = child_path(&self.path(parent)?, name)?;
+        if self.vfs.metadata(&path).map_err(vfs_errno)?.file_type == FileType::Directory {
+            return Err(errno::EISDIR);
+        }
+        self.vfs.unlink(&path).map_err(vfs_errno)?;
+        self.drop_paths(&path);
+        Ok(())
+    }
+
+    /// rmdir(2): dizin olmayanlar için ENOTDIR.
+    pub fn rmdir(&mut self, parent: u64, name: &str) -> Result<(), i32> {
+        let path = child_path(&self.path(parent)?, name)?;
+        if self.vfs.metadata(&path).map_err(vfs_errno)?.file_type != FileType::Directory {
+            return Err(errno::ENOTDIR);
+        }
+        self.vfs.unlink(&path).map_err(vfs_errno)?;
+        self.drop_paths(&path);
+        Ok(())
+    }
+
+    /// Taşınan alt ağacın FUSE inode numaraları korunur.
+    pub fn rename(&mut self, parent: u64, name: &str, new_parent: u64, new_name: &str) -> Result<(), i32> {
+        let from = child_path(&self.path(parent)?, name)?;
+        let to = child_path(&self.path(new_parent)?, new_name)?;
+        self.vfs.rename(&from, &to).map_err(vfs_errno)?;
+        if from == to {
+            return Ok(());
+        }
+        self.drop_paths(&to);
+        let moved: Vec<(String, u64)> = self.inodes.iter().filter(|(p, _)| in_subtree(p, &from)).map(|(p, &i)| (p.clone(), i)).collect();
+        for (old, ino) in moved {
+            let new = format!("{}{}", to, &old[from.len()..]);
+            self.inodes.remove(&old);
+            self.inodes.insert(new.clone(), ino);
+            self.paths.insert(ino, new);
+        }
+        Ok(())
+    }
+
+    pub fn setxattr(&mut self, ino: u64, name: &str, value: &[u8], flags: u32) -> Result<(), i32> {
+        let path = self.path(ino)?;
+        self.vfs.set_xattr(&path, name, value, flags).map_err(vfs_errno)
+    }
+
+    pub fn getxattr(&mut self, ino: u64, name: &str) -> Result<Vec<u8>, i32> {
+        let path = self.path(ino)?;
+        self.vfs.get_xattr(&path, name).map_err(vfs_errno)
+    }
+
+    /// Adlar listxattr(2) biçiminde NUL ile ayrılmış olarak döner.
+    pub fn listxattr(&mut self, ino: u64) -> Result<Vec<u8>, i32> {
+        let path = self.path(ino)?;
+        let mut list = Vec::new();
+        for name in self.vfs.list_xattr(&path).map_err(vfs_errno)? {
+            list.extend_from_slice(name.as_bytes());
+            list.push(0);
+        }
+        Ok(list)
+    }
+
+    pub fn removexattr(&mut self, ino: u64, name: &str) -> Result<(), i32> {
+        let path = self.path(ino)?;
+        self.vfs.remove_xattr(&path, name).map_err(vfs_errno)
+    }
+
+    pub fn statfs(&self) -> SadakStats {
+        match self.stats {
+            Some(ref stats) => stats(),
+            None => SadakStats { block_size: self.block_size, blocks: 0, free_blocks: 0, inodes: 0, free_inodes: 0 },
+        }
+    }
+
+    /// Bağlama kaldırılırken açık dosyaları kapatır ve dosya sistemini eşitler.
+    pub fn destroy(&mut self) -> Result<(), i32> {
+        self.handles.clear();
+        self.vfs.sync().map_err(vfs_errno)
+    }
+}
+
+/// Komut satırı: `<imaj> <bağlama noktası> [--ro]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountArgs {
+    pub image: String,
+    pub mountpoint: String,
+    pub read_only: bool,
+}
+
+pub fn parse_args(args: &[String]) -> Result<MountArgs, String> {
+    let mut positional = Vec::new();
+    let mut read_only = false;
+    for arg in args {
+        match arg.as_str() {
+            "--ro" | "-r" => read_only = true,
+            flag if flag.starts_with('-') => return Err(format!("unknown option: {}", flag)),
+            value => positional.push(value.to_string()),
+        }
+    }
+    if positional.len() != 2 {
+        return Err(String::from("usage: sadak-fuse <image> <mountpoint> [--ro]"));
+    }
+    let mountpoint = positional.pop().unwrap();
+    let image = positional.pop().unwrap();
+    Ok(MountArgs { image, mountpoint, read_only })
+}
+
+// fuser crate'ine bağlantı
+#[cfg(all(feature = "std", feature = "fuse"))]
+mod glue {
+    use super::*;
+    use fuser::{
+        FileAttr, KernelConfig, ReplyAttr, ReplyCreate, ReplyData, ReplyDirectory, ReplyEmpty, ReplyEntry, ReplyOpen,
+        ReplyStatfs, ReplyWrite, ReplyXattr, Request, TimeOrNow,
+    };
+    use std::ffi::OsStr;
+    use std::path::Path;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    const TTL: Duration = Duration::from_secs(1);
+
+    fn to_system_time(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn to_secs(time: TimeOrNow) -> u64 {
+        let time = match time {
+            TimeOrNow::SpecificTime(time) => time,
+            TimeOrNow::Now => SystemTime::now(),
+        };
+        time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+    }
+
+    fn fuse_kind(kind: FileType) -> fuser::FileType {
+        match kind {
+            FileType::File => fuser::FileType::RegularFile,
+            FileType::Directory => fuser::FileType::Directory,
+            FileType::Symlink => fuser::FileType::Symlink,
+        }
+    }
+
+    fn file_attr(attr: FuseAttr) -> FileAttr {
+        FileAttr {
+            ino: attr.ino,
+            size: attr.size,
+            blocks: attr.blocks,
+            atime: to_system_time(attr.atime),
+            mtime: to_system_time(attr.mtime),
+            ctime: to_system_time(attr.ctime),
+            crtime: to_system_time(attr.ctime),
+            kind: fuse_kind(attr.kind),
+            perm: attr.perm,
+            nlink: attr.nlink,
+            uid: attr.uid,
+            gid: attr.gid,
+            rdev: 0,
+            blksize: attr.blksize,
+            flags: 0,
+        }
+    }
+
+    fn name_str(name: &OsStr) -> Result<&str, i32> {
+        name.to_str().ok_or(errno::EINVAL)
+    }
+
+    /// `fuser` oturumuna verilen dosya sistemi.
+    pub struct SadakFuse(pub FuseAdapter);
+
+    impl SadakFuse {
+        // Değişikliklerin zaman damgası için duvar saati
+        fn touch(&self) {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+            self.0.vfs().set_time(now);
+        }
+    }
+
+    impl fuser::Filesystem for SadakFuse {
+        fn init(&mut self, _req: &Request<'_>, _config: &mut KernelConfig) -> Result<(), i32> {
+            Ok(())
+        }
+
+        fn destroy(&mut self) {
+            if let Err(e) = self.0.destroy() {
+                eprintln!("sadak-fuse: sync on unmount failed: errno {}", e);
+            }
+        }
+
+        fn lookup(&mut self, _req: &Request<'_>, parent: u64, name: &OsStr, reply: ReplyEntry) {
+            match name_str(name).and_then(|name| self.0.lookup(parent, name)) {
+                Ok(attr) => reply.entry(&TTL, &file_attr(attr), 0),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn forget(&mut self, _req: &Request<'_>, ino: u64, nlookup: u64) {
+            self.0.forget(ino, nlookup);
+        }
+
+        fn getattr(&mut self, _req: &Request<'_>, ino: u64, reply: ReplyAttr) {
+            match self.0.getattr(ino) {
+                Ok(attr) => reply.attr(&TTL, &file_attr(attr)),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn setattr(
+            &mut self,
+            _req: &Request<'_>,
+            ino: u64,
+            mode: Option<u32>,
+            uid: Option<u32>,
+            gid: Option<u32>,
+            size: Option<u64>,
+            atime: Option<TimeOrNow>,
+            mtime: Option<TimeOrNow>,
+            _ctime: Option<SystemTime>,
+            fh: Option<u64>,
+            _crtime: Option<SystemTime>,
+            _chgtime: Option<SystemTime>,
+            _bkuptime: Option<SystemTime>,
+            _flags: Option<u32>,
+            reply: ReplyAttr,
+        ) {
+            self.touch();
+            let changes = SetMetadata { mode, uid, gid, size, atime: atime.map(to_secs), mtime: mtime.map(to_secs) };
+            match self.0.setattr(ino, fh, &changes) {
+                Ok(attr) => reply.attr(&TTL, &file_attr(attr)),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn readlink(&mut self, _req: &Request<'_>, ino: u64, reply: ReplyData) {
+            match self.0.readlink(ino) {
+                Ok(target) => reply.data(target.as_bytes()),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn mkdir(&mut self, req: &Request<'_>, parent: u64, name: &OsStr, mode: u32, umask: u32, reply: ReplyEntry) {
+            self.touch();
+            match name_str(name).and_then(|name| self.0.mkdir(parent, name, mode & !umask, req.uid(), req.gid())) {
+                Ok(attr) => reply.entry(&TTL, &file_attr(attr), 0),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn unlink(&mut self, _req: &Request<'_>, parent: u64, name: &OsStr, reply: ReplyEmpty) {
+            self.touch();
+            match name_str(name).and_then(|name| self.0.unlink(parent, name)) {
+                Ok(()) => reply.ok(),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn rmdir(&mut self, _req: &Request<'_>, parent: u64, name: &OsStr, reply: ReplyEmpty) {
+            self.touch();
+            match name_str(name).and_then(|name| self.0.rmdir(parent, name)) {
+                Ok(()) => reply.ok(),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn symlink(&mut self, req: &Request<'_>, parent: u64, name: &OsStr, link: &Path, reply: ReplyEntry) {
+            self.touch();
+            let target = link.to_str().ok_or(errno::EINVAL);
+            match name_str(name).and_then(|name| self.0.symlink(parent, name, target?, req.uid(), req.gid())) {
+                Ok(attr) => reply.entry(&TTL, &file_attr(attr), 0),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn rename(&mut self, _req: &Request<'_>, parent: u64, name: &OsStr, newparent: u64, newname: &OsStr, flags: u32, reply: ReplyEmpty) {
+            if flags != 0 {
+                // RENAME_NOREPLACE / RENAME_EXCHANGE desteklenmiyor
+                reply.error(errno::EINVAL);
+                return;
+            }
+            self.touch();
+            match name_str(name).and_then(|name| Ok((name, name_str(newname)?))).and_then(|(name, newname)| self.0.rename(parent, name, newparent, newname)) {
+                Ok(()) => reply.ok(),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn open(&mut self, _req: &Request<'_>, ino: u64, flags: i32, reply: ReplyOpen) {
+            if flags as u32 & O_TRUNC != 0 {
+                self.touch();
+            }
+            match self.0.open(ino, flags as u32) {
+                Ok(fh) => reply.opened(fh, 0),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn read(&mut self, _req: &Request<'_>, _ino: u64, fh: u64, offset: i64, size: u32, _flags: i32, _lock_owner: Option<u64>, reply: ReplyData) {
+            match self.0.read(fh, offset.max(0) as u64, size) {
+                Ok(data) => reply.data(&data),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn write(
+            &mut self,
+            _req: &Request<'_>,
+            _ino: u64,
+            fh: u64,
+            offset: i64,
+            data: &[u8],
+            _write_flags: u32,
+            _flags: i32,
+            _lock_owner: Option<u64>,
+            reply: ReplyWrite,
+        ) {
+            self.touch();
+            match self.0.write(fh, offset.max(0) as u64, data) {
+                Ok(written) => reply.written(written),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn flush(&mut self, _req: &Request<'_>, _ino: u64, _fh: u64, _lock_owner: u64, reply: ReplyEmpty) {
+            reply.ok();
+        }
+
+        fn release(&mut self, _req: &Request<'_>, _ino: u64, fh: u64, _flags: i32, _lock_owner: Option<u64>, _flush: bool, reply: ReplyEmpty) {
+            match self.0.release(fh) {
+                Ok(()) => reply.ok(),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn fsync(&mut self, _req: &Request<'_>, _ino: u64, fh: u64, _datasync: bool, reply: ReplyEmpty) {
+            match self.0.fsync(fh) {
+                Ok(()) => reply.ok(),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn readdir(&mut self, _req: &Request<'_>, ino: u64, _fh: u64, offset: i64, mut reply: ReplyDirectory) {
+            match self.0.readdir(ino, offset) {
+                Ok(entries) => {
+                    for entry in entries {
+                        if reply.add(entry.ino, entry.offset, fuse_kind(entry.kind), &entry.name) {
+                            break; // Tampon doldu
+                        }
+                    }
+                    reply.ok();
+                }
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn statfs(&mut self, _req: &Request<'_>, _ino: u64, reply: ReplyStatfs) {
+            let stats = self.0.statfs();
+            reply.statfs(stats.blocks, stats.free_blocks, stats.free_blocks, stats.inodes, stats.free_inodes, stats.block_size, NAME_MAX, stats.block_size);
+        }
+
+        fn setxattr(&mut self, _req: &Request<'_>, ino: u64, name: &OsStr, value: &[u8], flags: i32, _position: u32, reply: ReplyEmpty) {
+            self.touch();
+            match name_str(name).and_then(|name| self.0.setxattr(ino, name, value, flags as u32)) {
+                Ok(()) => reply.ok(),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn getxattr(&mut self, _req: &Request<'_>, ino: u64, name: &OsStr, size: u32, reply: ReplyXattr) {
+            match name_str(name).and_then(|name| self.0.getxattr(ino, name)) {
+                Ok(value) => reply_xattr(reply, size, &value),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn listxattr(&mut self, _req: &Request<'_>, ino: u64, size: u32, reply: ReplyXattr) {
+            match self.0.listxattr(ino) {
+                Ok(list) => reply_xattr(reply, size, &list),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn removexattr(&mut self, _req: &Request<'_>, ino: u64, name: &OsStr, reply: ReplyEmpty) {
+            self.touch();
+            match name_str(name).and_then(|name| self.0.removexattr(ino, name)) {
+                Ok(()) => reply.ok(),
+                Err(e) => reply.error(e),
+            }
+        }
+
+        fn create(&mut self, req: &Request<'_>, parent: u64, name: &OsStr, mode: u32, umask: u32, flags: i32, reply: ReplyCreate) {
+            self.touch();
+            match name_str(name).and_then(|name| self.0.create(parent, name, mode & !umask, flags as u32, req.uid(), req.gid())) {
+                Ok((attr, fh)) => reply.created(&TTL, &file_attr(attr), 0, fh, 0),
+                Err(e) => reply.error(e),
+            }
+        }
+    }
+
+    // Boyut 0 ise gereken boyut, yetmiyorsa ERANGE döner (getxattr(2)/listxattr(2)).
+    fn reply_xattr(reply: ReplyXattr, size: u32, data: &[u8]) {
+        if size == 0 {
+            reply.size(data.len() as u32);
+        } else if data.len() > size as usize {
+            reply.error(errno::ERANGE);
+        } else {
+            reply.data(data);
+        }
+    }
+}
+
+#[cfg(all(feature = "std", feature = "fuse"))]
+pub use glue::SadakFuse;
+
+#[cfg(all(feature = "std", feature = "fuse"))]
+fn main() {
+    use crate::sadakfs::{open_image, SadakFs};
+    use fuser::MountOption;
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args = match parse_args(&args) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+    let fs = match open_image(&args.image, args.read_only).and_then(|image| SadakFs::mount(image, args.read_only)) {
+        Ok(fs) => Arc::new(fs),
+        Err(e) => {
+            eprintln!("sadak-fuse: cannot mount {}: {}", args.image, e);
+            std::process::exit(1);
+        }
+    };
+    let vfs = Arc::new(Vfs::new());
+    if let Err(e) = vfs.mount("/", fs.clone()) {
+        eprintln!("sadak-fuse: {}", e);
+        std::process::exit(1);
+    }
+    let stats = fs.clone();
+    let adapter = FuseAdapter::new(vfs).with_stats(Box::new(move || stats.stats()));
+    let mut options = vec![MountOption::FSName(args.image.clone()), MountOption::Subtype(String::from("sadak")), MountOption::AutoUnmount];
+    options.push(if args.read_only { MountOption::RO } else { MountOption::RW });
+    if let Err(e) = fuser::mount2(SadakFuse(adapter), &args.mountpoint, &options) {
+        eprintln!("sadak-fuse: mount failed: {}", e);
+        std::process::exit(1);
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+    use crate::vfs::{MemFs, O_RDWR, O_WRONLY, XATTR_CREATE};
+
+    fn adapter() -> FuseAdapter {
+        let vfs = Arc::new(Vfs::new());
+        vfs.mount("/", Arc::new(MemFs::new())).unwrap();
+        FuseAdapter::new(vfs)
+    }
+
+    #[test]
+    fn test_file_lifecycle_through_inodes() -> Result<(), i32> {
+        let mut fs = adapter();
+        let dir = fs.mkdir(ROOT_INO, "etc", 0o755, 1000, 100)?;
+        assert_eq!((dir.kind, dir.perm, dir.uid, dir.gid), (FileType::Directory, 0o755, 1000, 100));
+        let (file, fh) = fs.create(dir.ino, "motd", 0o640, O_WRONLY, 1000, 100)?;
+        assert_eq!(fs.write(fh, 0, b"merhaba")?, 7);
+        fs.fsync(fh)?;
+        fs.release(fh)?;
+        assert_eq!(fs.release(fh), Err(errno::EINVAL));
+        assert_eq!(fs.lookup(dir.ino, "motd")?.ino, file.ino);
+        assert_eq!(fs.getattr(file.ino)?.size, 7);
+
+        let fh = fs.open(file.ino, O_RDWR)?;
+        assert_eq!(fs.read(fh, 3, 100)?, b"haba");
+        let attr = fs.setattr(file.ino, Some(fh), &SetMetadata { size: Some(3), mode: Some(0o600), ..SetMetadata::default() })?;
+        assert_eq!((attr.size, attr.perm), (3, 0o600));
+        fs.release(fh)?;
+
+        let names: Vec<String> = fs.readdir(ROOT_INO, 0)?.into_iter().map(|e| e.name).collect();
+        assert_eq!(names, vec![".", "..", "etc"]);
+        let entries = fs.readdir(dir.ino, 2)?;
+        assert_eq!((entries.len(), entries[0].ino, entries[0].offset), (1, file.ino, 3));
+
+        // Yeniden adlandırma inode numarasını korur, alt ağaç yolları güncellenir
+        fs.rename(ROOT_INO, "etc", ROOT_INO, "conf")?;
+        assert_eq!(fs.getattr(file.ino)?.size, 3);
+        assert_eq!(fs.lookup(ROOT_INO, "conf")?.ino, dir.ino);
+        assert_eq!(fs.lookup(ROOT_INO, "etc"), Err(errno::ENOENT));
+
+        let link = fs.symlink(ROOT_INO, "motd", "conf/motd", 0, 0)?;
+        assert_eq!(fs.readlink(link.ino)?, "conf/motd");
+        assert_eq!(fs.rmdir(dir.ino, "motd"), Err(errno::ENOTDIR));
+        assert_eq!(fs.unlink(ROOT_INO, "conf"), Err(errno::EISDIR));
+        assert_eq!(fs.rmdir(ROOT_INO, "conf"), Err(errno::ENOTEMPTY));
+        fs.unlink(dir.ino, "motd")?;
+        fs.rmdir(ROOT_INO, "conf")?;
+        assert_eq!(fs.getattr(dir.ino), Err(errno::ENOENT));
+        assert_eq!(fs.create(ROOT_INO, "a/b", 0o644, O_WRONLY, 0, 0).map(|_| ()), Err(errno::EINVAL));
+
+        // Çekirdek tüm başvuruları bırakınca inode eşlemeden çıkar; kök kalır
+        let (note, fh) = fs.create(ROOT_INO, "not", 0o644, O_WRONLY, 0, 0)?;
+        fs.release(fh)?;
+        assert_eq!(fs.lookup(ROOT_INO, "not")?.ino, note.ino);
+        fs.forget(note.ino, 1);
+        assert_eq!(fs.getattr(note.ino)?.ino, note.ino);
+        fs.forget(note.ino, 1);
+        assert_eq!(fs.getattr(note.ino), Err(errno::ENOENT));
+        assert_ne!(fs.lookup(ROOT_INO, "not")?.ino, note.ino);
+        fs.forget(ROOT_INO, 1);
+        assert_eq!(fs.getattr(ROOT_INO)?.ino, ROOT_INO);
+        fs.destroy()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_sadak_image_xattrs_and_args() -> Result<(), i32> {
+        use crate::config::DeviceConfig;
+        use crate::sadakfs::{create_image, SadakFs};
+        use crate::superblock::DeviceType;
+
+        let path = std::env::temp_dir().join(format!("fuse-test-{}.img", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut config = DeviceConfig::new("img");
+        config.block_size = 1024;
+        config.total_blocks = 1024;
+        config.inodes_count = 32;
+        let image = create_image(path, 1024, 1024).map_err(|e| e.errno())?;
+        let sadak = Arc::new(SadakFs::format(image, &config, DeviceType::Other, 0, 0).map_err(|e| e.errno())?);
+        let vfs = Arc::new(Vfs::new());
+        vfs.mount("/", sadak.clone()).map_err(vfs_errno)?;
+        let stats = sadak.clone();
+        let mut fs = FuseAdapter::new(vfs).with_stats(Box::new(move || stats.stats()));
+        let before = fs.statfs();
+        assert_eq!((before.block_size, before.inodes, before.free_inodes), (1024, 32, 31));
+        let (file, fh) = fs.create(ROOT_INO, "veri", 0o644, O_RDWR, 1000, 1000)?;
+        fs.write(fh, 5000, b"son")?;
+        assert_eq!(fs.read(fh, 4998, 10)?, b"\0\0son");
+        fs.release(fh)?;
+        assert_eq!((fs.getattr(file.ino)?.size, fs.statfs().free_inodes), (5003, 30));
+
+        fs.setxattr(ROOT_INO, "user.b", b"2", 0)?;
+        fs.setxattr(ROOT_INO, "user.a", b"1", XATTR_CREATE)?;
+        assert_eq!(fs.setxattr(ROOT_INO, "user.a", b"1", XATTR_CREATE), Err(errno::EEXIST));
+        assert_eq!(fs.listxattr(ROOT_INO)?, b"user.a\0user.b\0");
+        assert_eq!(fs.getxattr(ROOT_INO, "user.b")?, b"2");
+        fs.removexattr(ROOT_INO, "user.b")?;
+        assert_eq!(fs.getxattr(ROOT_INO, "user.b"), Err(errno::ENODATA));
+        assert_eq!(fs.removexattr(ROOT_INO, "user.b"), Err(errno::ENODATA));
+
+        let args = |list: &[&str]| parse_args(&list.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+        assert_eq!(
+            args(&["disk.img", "/mnt/sadak", "--ro"]),
+            Ok(MountArgs { image: String::from("disk.img"), mountpoint: String::from("/mnt/sadak"), read_only: true })
+        );
+        assert!(args(&["disk.img"]).is_err());
+        assert!(args(&["disk.img", "/mnt", "--rw"]).is_err());
+        fs.destroy()?;
+        std::fs::remove_file(path).unwrap();
+        Ok(())
+    }
+}
+
+// Removed redundant print module and panic handler boilerplate.
+// The empty lib module configuration is kept.
+#[cfg(not(any(feature = "std", test)))] // Only when not building std or test
+pub mod lib {} // Keep the empty lib module if needed for the crate structure
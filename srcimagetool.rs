@@ -0,0 +1,889 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// SADAK imaj aracı: ana makinedeki bir dizin ağacından SADAK imajı oluşturur (pack), imajdaki
+// dosyaları listeler (list) ve ana makineye çıkarır (extract). CI'da aygıt imajı üretmek içindir.
+//
+//   sadak-image pack <dizin> <imaj> [--block-size N] [--blocks N] [--inodes N]
+//                    [--config dosya --device ad] [--timestamp saniye] [--owner uid:gid] [--no-xattrs]
+//   sadak-image list <imaj>
+//   sadak-image extract <imaj> <dizin> [--same-owner]
+//
+// Paketleme `SadakFs::format` ile biçimlendirir ve içeriği VFS dosya API'leriyle yazar; izinler,
+// sahiplik, zaman damgaları, sembolik bağlar ve genişletilmiş öznitelikler korunur. Girdiler ada
+// göre sıralı eklendiğinden aynı ağaç, sabit zaman damgası (`--timestamp` veya SOURCE_DATE_EPOCH)
+// ile her seferinde bayt bayt aynı imajı üretir: değişiklik zamanı sabitlenir, daha yeni
+// değişiklik zamanları ona indirilir. Sabit bağlar ayrı kopyalar olarak paketlenir; aygıt
+// dosyaları, FIFO'lar ve soketler atlanır.
+
+use crate::vfs::FileType;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+
+use core::result::Result;
+
+#[cfg(feature = "std")]
+use crate::config::{map_config_error_to_fs_error, DeviceConfig, SadakConfig};
+#[cfg(feature = "std")]
+use crate::inodetable::Inode;
+#[cfg(feature = "std")]
+use crate::sadakfs::{create_image, open_image, SadakFs, MAX_NAME_LEN};
+#[cfg(feature = "std")]
+use crate::superblock::DeviceType;
+#[cfg(feature = "std")]
+use crate::vfs::{map_vfs_error_to_fs_error, symlink_stays_inside, Metadata, SetMetadata, Vfs, O_CREAT, O_EXCL, O_RDONLY, O_WRONLY};
+#[cfg(feature = "std")]
+use crate::FileSystemError;
+#[cfg(feature = "std")]
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+
+/// Varsayılan blok boyutu (bayt).
+pub const DEFAULT_IMAGE_BLOCK_SIZE: u32 = 4096;
+
+// Dosya kopyalarken kullanılan parça boyutu
+const COPY_CHUNK: usize = 64 * 1024;
+
+// Otomatik boyutlandırmada sonradan değişiklik (ör. FUSE ile) için bırakılan en az boş alan
+const MIN_SLACK_BLOCKS: u64 = 64;
+const SLACK_INODES: u64 = 64;
+
+/// `pack` seçenekleri.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackOptions {
+    pub block_size: u32,
+    /// Verilmezse içerikten hesaplanır.
+    pub total_blocks: Option<u64>,
+    pub inodes: Option<u32>,
+    /// Yapılandırmadaki aygıt; geometri (ve yerleşim) olduğu gibi kullanılır.
+    pub device: Option<(String, String)>, // (yapılandırma dosyası, aygıt adı)
+    /// Sabit zaman damgası (Unix saniyesi): yeniden üretilebilir imaj için.
+    pub timestamp: Option<u64>,
+    /// Tüm girdilerin sahibi bu olur.
+    pub owner: Option<(u32, u32)>,
+    pub xattrs: bool,
+}
+
+impl Default for PackOptions {
+    fn default() -> Self {
+        PackOptions { block_size: DEFAULT_IMAGE_BLOCK_SIZE, total_blocks: None, inodes: None, device: None, timestamp: None, owner: None, xattrs: true }
+    }
+}
+
+/// Komut satırı komutları.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Pack { source: String, image: String, options: PackOptions },
+    List { image: String },
+    Extract { image: String, dest: String, same_owner: bool },
+}
+
+pub const USAGE: &str = "usage: sadak-image pack <dir> <image> [--block-size N] [--blocks N] [--inodes N] \
+[--config FILE --device NAME] [--timestamp SECS] [--owner UID:GID] [--no-xattrs]\n\
+       sadak-image list <image>\n\
+       sadak-image extract <image> <dir> [--same-owner]";
+
+fn parse_number<T: core::str::FromStr>(option: &str, value: Option<&String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} needs a value", option))?;
+    value.parse().map_err(|_| format!("{}: invalid number '{}'", option, value))
+}
+
+/// Komut satırını çözümler (program adı hariç).
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    let (command, rest) = args.split_first().ok_or_else(|| USAGE.to_string())?;
+    let mut positional = Vec::new();
+    let mut options = PackOptions::default();
+    let (mut config, mut device) = (None, None);
+    let mut same_owner = false;
+    let mut iter = rest.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--block-size" => options.block_size = parse_number(arg, iter.next())?,
+            "--blocks" => options.total_blocks = Some(parse_number(arg, iter.next())?),
+            "--inodes" => options.inodes = Some(parse_number(arg, iter.next())?),
+            "--timestamp" => options.timestamp = Some(parse_number(arg, iter.next())?),
+            "--config" => config = Some(iter.next().ok_or("--config needs a value")?.clone()),
+            "--device" => device = Some(iter.next().ok_or("--device needs a value")?.clone()),
+            "--owner" => {
+                let value = iter.next().ok_or("--owner needs a value")?;
+                let (uid, gid) = value.split_once(':').ok_or_else(|| format!("--owner: expected UID:GID, got '{}'", value))?;
+                options.owner = Some((parse_number("--owner", Some(&uid.to_string()))?, parse_number("--owner", Some(&gid.to_string()))?));
+            }
+            "--no-xattrs" => options.xattrs = false,
+            "--same-owner" => same_owner = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown option: {}", flag)),
+            value => positional.push(value.to_string()),
+        }
+    }
+    match (config, device) {
+        (Some(config), Some(device)) => options.device = Some((config, device)),
+        (None, None) => {}
+        _ => return Err(String::from("--config and --device must be given together")),
+    }
+    let pack_only = options != PackOptions::default();
+    match (command.as_str(), positional.len()) {
+        ("pack", 2) if !same_owner => {
+            let image = positional.pop().unwrap();
+            let source = positional.pop().unwrap();
+            Ok(Command::Pack { source, image, options })
+        }
+        ("list", 1) if !pack_only && !same_owner => Ok(Command::List { image: positional.pop().unwrap() }),
+        ("extract", 2) if !pack_only => {
+            let dest = positional.pop().unwrap();
+            let image = positional.pop().unwrap();
+            Ok(Command::Extract { image, dest, same_owner })
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+/// `ls -l` biçiminde izin dizgesi ("drwxr-xr-x").
+pub fn mode_string(file_type: FileType, mode: u32) -> String {
+    let mut out = String::with_capacity(10);
+    out.push(match file_type {
+        FileType::File => '-',
+        FileType::Directory => 'd',
+        FileType::Symlink => 'l',
+    });
+    let special = [(0o4000, 's', 'S'), (0o2000, 's', 'S'), (0o1000, 't', 'T')];
+    for (i, &(bit, set, unset)) in special.iter().enumerate() {
+        let shift = 6 - 3 * i;
+        out.push(if mode & (0o4 << shift) != 0 { 'r' } else { '-' });
+        out.push(if mode & (0o2 << shift) != 0 { 'w' } else { '-' });
+        let exec = mode & (0o1 << shift) != 0;
+        out.push(match (mode & bit != 0, exec) {
+            (true, true) => set,
+            (true, false) => unset,
+            (false, true) => 'x',
+            (false, false) => '-',
+        });
+    }
+    out
+}
+
+/// İşlem özeti.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub files: u64,
+    pub directories: u64,
+    pub symlinks: u64,
+    pub bytes: u64,
+    /// Atlanan girdiler ve nedenleri.
+    pub skipped: Vec<String>,
+}
+
+impl Summary {
+    fn count(&mut self, file_type: FileType, size: u64) {
+        match file_type {
+            FileType::File => {
+                self.files += 1;
+                self.bytes += size;
+            }
+            FileType::Directory => self.directories += 1,
+            FileType::Symlink => self.symlinks += 1,
+        }
+    }
+}
+
+/// İmajdaki bir girdi (`list` çıktısı).
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageEntry {
+    pub path: String,
+    pub metadata: Metadata,
+    pub target: Option<String>,
+    pub xattrs: Vec<String>,
+}
+
+#[cfg(feature = "std")]
+impl ImageEntry {
+    /// `mode uid gid boyut mtime yol [-> hedef]`
+    pub fn format(&self) -> String {
+        let mut line = format!(
+            "{} {:>5} {:>5} {:>10} {:>10} {}",
+            mode_string(self.metadata.file_type, self.metadata.mode),
+            self.metadata.uid,
+            self.metadata.gid,
+            self.metadata.size,
+            self.metadata.mtime,
+            self.path
+        );
+        if let Some(target) = &self.target {
+            line.push_str(" -> ");
+            line.push_str(target);
+        }
+        line
+    }
+}
+
+#[cfg(feature = "std")]
+fn map_host_error(path: &Path, e: std::io::Error) -> FileSystemError {
+    FileSystemError::IOError(format!("{}: {}", path.display(), e))
+}
+
+// Ana makine genişletilmiş öznitelikleri (Linux l*xattr çağrıları; sembolik bağı izlemez)
+#[cfg(all(feature = "std", target_os = "linux"))]
+mod host_xattr {
+    use std::ffi::{c_char, c_int, c_void, CString};
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+    use std::ptr;
+
+    const EOPNOTSUPP: i32 = 95;
+
+    extern "C" {
+        fn llistxattr(path: *const c_char, list: *mut c_char, size: usize) -> isize;
+        fn lgetxattr(path: *const c_char, name: *const c_char, value: *mut c_void, size: usize) -> isize;
+        fn lsetxattr(path: *const c_char, name: *const c_char, value: *const c_void, size: usize, flags: c_int) -> c_int;
+    }
+
+    fn c_string(bytes: &[u8]) -> io::Result<CString> {
+        CString::new(bytes).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))
+    }
+
+    /// Ada göre sıralı öznitelikler; dosya sistemi desteklemiyorsa boş.
+    pub fn list(path: &Path) -> io::Result<Vec<(String, Vec<u8>)>> {
+        let c_path = c_string(path.as_os_str().as_bytes())?;
+        // SAFETY: c_path NUL ile biter; boyut 0 ile yalnızca gereken uzunluk sorulur
+        let size = unsafe { llistxattr(c_path.as_ptr(), ptr::null_mut(), 0) };
+        if size < 0 {
+            let e = io::Error::last_os_error();
+            return if e.raw_os_error() == Some(EOPNOTSUPP) { Ok(Vec::new()) } else { Err(e) };
+        }
+        let mut names = vec![0u8; size as usize];
+        // SAFETY: tampon names.len() bayt yazılabilir
+        let size = unsafe { llistxattr(c_path.as_ptr(), names.as_mut_ptr() as *mut c_char, names.len()) };
+        if size < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        names.truncate(size as usize);
+        let mut xattrs = Vec::new();
+        for name in names.split(|&b| b == 0).filter(|name| !name.is_empty()) {
+            let c_name = c_string(name)?;
+            // SAFETY: yukarıdaki gibi, önce uzunluk sonra değer
+            let len = unsafe { lgetxattr(c_path.as_ptr(), c_name.as_ptr(), ptr::null_mut(), 0) };
+            if len < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let mut value = vec![0u8; len as usize];
+            let len = unsafe { lgetxattr(c_path.as_ptr(), c_name.as_ptr(), value.as_mut_ptr() as *mut c_void, value.len()) };
+            if len < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            value.truncate(len as usize);
+            let name = String::from_utf8(name.to_vec()).map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+            xattrs.push((name, value));
+        }
+        xattrs.sort();
+        Ok(xattrs)
+    }
+
+    pub fn set(path: &Path, name: &str, value: &[u8]) -> io::Result<()> {
+        let c_path = c_string(path.as_os_str().as_bytes())?;
+        let c_name = c_string(name.as_bytes())?;
+        // SAFETY: işaretçiler çağrı süresince geçerli
+        let result = unsafe { lsetxattr(c_path.as_ptr(), c_name.as_ptr(), value.as_ptr() as *const c_void, value.len(), 0) };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "std", not(target_os = "linux")))]
+mod host_xattr {
+    use std::io;
+    use std::path::Path;
+
+    pub fn list(_path: &Path) -> io::Result<Vec<(String, Vec<u8>)>> {
+        Ok(Vec::new())
+    }
+
+    pub fn set(_path: &Path, _name: &str, _value: &[u8]) -> io::Result<()> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+}
+
+// Paketlenecek ana makine girdisi
+#[cfg(feature = "std")]
+struct HostEntry {
+    path: String, // İmajdaki yol ("/etc/hostname")
+    host: PathBuf,
+    file_type: FileType,
+    size: u64,
+    mode: u32,
+    uid: u32,
+    gid: u32,
+    atime: u64,
+    mtime: u64,
+    target: Option<String>,
+    xattrs: Vec<(String, Vec<u8>)>,
+}
+
+#[cfg(feature = "std")]
+fn host_entry(host: &Path, path: String, options: &PackOptions) -> Result<Option<HostEntry>, FileSystemError> {
+    use std::os::unix::fs::MetadataExt;
+    let meta = std::fs::symlink_metadata(host).map_err(|e| map_host_error(host, e))?;
+    let file_type = if meta.is_dir() {
+        FileType::Directory
+    } else if meta.is_file() {
+        FileType::File
+    } else if meta.file_type().is_symlink() {
+        FileType::Symlink
+    } else {
+        return Ok(None);
+    };
+    let target = match file_type {
+        FileType::Symlink => {
+            let target = std::fs::read_link(host).map_err(|e| map_host_error(host, e))?;
+            Some(target.to_str().ok_or_else(|| FileSystemError::InvalidData(format!("{}: symlink target is not UTF-8", host.display())))?.to_string())
+        }
+        _ => None,
+    };
+    // Sabit zaman damgasıyla daha yeni zamanlar ona indirilir
+    let clamp = |secs: i64| {
+        let secs = secs.max(0) as u64;
+        options.timestamp.map_or(secs, |ts| secs.min(ts))
+    };
+    let (uid, gid) = options.owner.unwrap_or((meta.uid(), meta.gid()));
+    let xattrs = if options.xattrs { host_xattr::list(host).map_err(|e| map_host_error(host, e))? } else { Vec::new() };
+    Ok(Some(HostEntry {
+        path,
+        host: host.to_path_buf(),
+        file_type,
+        size: if file_type == FileType::Symlink { target.as_ref().map_or(0, |t| t.len() as u64) } else { meta.len() },
+        mode: meta.mode() & 0o7777,
+        uid,
+        gid,
+        atime: clamp(meta.atime()),
+        mtime: clamp(meta.mtime()),
+        target,
+        xattrs,
+    }))
+}
+
+// Ağacı ada göre sıralı, önce-kök sırasıyla toplar.
+#[cfg(feature = "std")]
+fn scan(dir: &Path, path: &str, options: &PackOptions, entries: &mut Vec<HostEntry>, summary: &mut Summary) -> Result<(), FileSystemError> {
+    let mut children = Vec::new();
+    for child in std::fs::read_dir(dir).map_err(|e| map_host_error(dir, e))? {
+        children.push(child.map_err(|e| map_host_error(dir, e))?.path());
+    }
+    children.sort();
+    for host in children {
+        let name = match host.file_name().and_then(|n| n.to_str()) {
+            Some(name) if name.len() <= MAX_NAME_LEN => name.to_string(),
+            _ => {
+                summary.skipped.push(format!("{}: name is not UTF-8 or too long", host.display()));
+                continue;
+            }
+        };
+        let child_path = if path == "/" { format!("/{}", name) } else { format!("{}/{}", path, name) };
+        match host_entry(&host, child_path, options)? {
+            Some(entry) => {
+                let is_dir = entry.file_type == FileType::Directory;
+                let child_path = entry.path.clone();
+                entries.push(entry);
+                if is_dir {
+                    scan(&host, &child_path, options, entries, summary)?;
+                }
+            }
+            None => summary.skipped.push(format!("{}: unsupported file type", host.display())),
+        }
+    }
+    Ok(())
+}
+
+// `size` baytlık veri için gereken veri ve işaretçi blokları
+#[cfg(feature = "std")]
+fn blocks_for(size: u64, block_size: u64) -> u64 {
+    let data = size.div_ceil(block_size);
+    let per_block = block_size / 8;
+    let mut total = data;
+    if data > 12 {
+        total += 1;
+    }
+    if data > 12 + per_block {
+        total += 1 + (data - 12 - per_block).div_ceil(per_block);
+    }
+    total
+}
+
+// İçeriğe göre geometri: veri için gereken bloklar, %10 (en az MIN_SLACK_BLOCKS) boş alan
+#[cfg(feature = "std")]
+fn auto_config(root: &HostEntry, entries: &[HostEntry], options: &PackOptions) -> Result<DeviceConfig, FileSystemError> {
+    let block_size = options.block_size as u64;
+    let mut dir_bytes: BTreeMap<&str, u64> = BTreeMap::new();
+    let mut data = 0;
+    for entry in entries.iter() {
+        let (parent, name) = entry.path.rsplit_once('/').unwrap_or(("", &entry.path));
+        *dir_bytes.entry(if parent.is_empty() { "/" } else { parent }).or_insert(0) += 10 + name.len() as u64;
+        if entry.file_type != FileType::Directory {
+            data += blocks_for(entry.size, block_size);
+        }
+    }
+    data += dir_bytes.values().map(|&bytes| blocks_for(bytes, block_size)).sum::<u64>();
+    data += core::iter::once(root).chain(entries.iter()).filter(|e| !e.xattrs.is_empty()).count() as u64;
+    let data = data + (data / 10).max(MIN_SLACK_BLOCKS);
+
+    let mut config = DeviceConfig::new("image");
+    config.block_size = options.block_size;
+    config.inodes_count = match options.inodes {
+        Some(inodes) => inodes,
+        None => u32::try_from(entries.len() as u64 + 1 + SLACK_INODES)
+            .map_err(|_| FileSystemError::InvalidParameter(String::from("Too many files for one image")))?,
+    };
+    config.total_blocks = match options.total_blocks {
+        Some(blocks) => blocks,
+        None => {
+            let inode_blocks = (config.inodes_count as u64 * Inode::size() as u64).div_ceil(block_size);
+            let fixed = 1 + inode_blocks + data;
+            fixed + (fixed + 1).div_ceil(block_size * 8)
+        }
+    };
+    config.validate().map_err(map_config_error_to_fs_error)?;
+    Ok(config)
+}
+
+#[cfg(feature = "std")]
+fn copy_into(vfs: &Vfs, entry: &HostEntry) -> Result<(), FileSystemError> {
+    use std::io::Read;
+    let mut source = std::fs::File::open(&entry.host).map_err(|e| map_host_error(&entry.host, e))?;
+    let mut file = vfs.open(&entry.path, O_WRONLY | O_CREAT | O_EXCL, entry.mode).map_err(map_vfs_error_to_fs_error)?;
+    let mut buf = vec![0u8; COPY_CHUNK];
+    let mut offset = 0u64;
+    loop {
+        let count = source.read(&mut buf).map_err(|e| map_host_error(&entry.host, e))?;
+        if count == 0 {
+            break;
+        }
+        // Tamamen sıfır parçalar yazılmaz: seyrek dosyalar seyrek kalır
+        if buf[..count].iter().any(|&b| b != 0) {
+            let written = file.write_at(offset, &buf[..count]).map_err(map_vfs_error_to_fs_error)?;
+            if written < count {
+                return Err(FileSystemError::NoSpace(format!("{}: image is full", entry.path)));
+            }
+        }
+        offset += count as u64;
+    }
+    file.set_len(offset).map_err(map_vfs_error_to_fs_error)
+}
+
+/// Ana makine dizinini yeni bir SADAK imajına paketler (imaj dosyasının üzerine yazar).
+#[cfg(feature = "std")]
+pub fn pack(source: &Path, image: &str, options: &PackOptions) -> Result<Summary, FileSystemError> {
+    let mut summary = Summary::default();
+    let root = host_entry(source, String::from("/"), options)?
+        .filter(|root| root.file_type == FileType::Directory)
+        .ok_or_else(|| FileSystemError::InvalidParameter(format!("{}: not a directory", source.display())))?;
+    let mut entries = Vec::new();
+    scan(source, "/", options, &mut entries, &mut summary)?;
+
+    let config = match &options.device {
+        Some((file, name)) => {
+            let config = SadakConfig::load(file)?;
+            config.device(name).cloned().ok_or_else(|| FileSystemError::NotFound(format!("{}: no device '{}'", file, name)))?
+        }
+        None => auto_config(&root, &entries, options)?,
+    };
+    let now = options.timestamp.unwrap_or_else(|| {
+        std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+    });
+
+    let device = create_image(image, config.block_size as usize, config.total_blocks)?;
+    let fs = Arc::new(SadakFs::format(device, &config, DeviceType::Other, 0, now)?);
+    let vfs = Vfs::new();
+    vfs.mount("/", fs.clone()).map_err(map_vfs_error_to_fs_error)?;
+
+    for entry in entries.iter() {
+        match entry.file_type {
+            FileType::Directory => vfs.mkdir(&entry.path, entry.mode).map(|_| ()),
+            FileType::Symlink => vfs.symlink(entry.target.as_deref().unwrap_or_default(), &entry.path).map(|_| ()),
+            FileType::File => {
+                copy_into(&vfs, entry)?;
+                Ok(())
+            }
+        }
+        .map_err(map_vfs_error_to_fs_error)?;
+        summary.count(entry.file_type, entry.size);
+    }
+    // Öznitelikler ve sahiplik/zamanlar en son: alt girdilerin eklenmesi dizin zamanlarını değiştirir
+    for entry in core::iter::once(&root).chain(entries.iter()) {
+        for (name, value) in entry.xattrs.iter() {
+            vfs.set_xattr(&entry.path, name, value, 0).map_err(map_vfs_error_to_fs_error)?;
+        }
+        let changes = SetMetadata {
+            mode: Some(entry.mode),
+            uid: Some(entry.uid),
+            gid: Some(entry.gid),
+            atime: Some(entry.atime),
+            mtime: Some(entry.mtime),
+            ..SetMetadata::default()
+        };
+        vfs.set_metadata(&entry.path, &changes).map_err(map_vfs_error_to_fs_error)?;
+    }
+    vfs.sync().map_err(map_vfs_error_to_fs_error)?;
+    Ok(summary)
+}
+
+#[cfg(feature = "std")]
+fn mount_image(image: &str) -> Result<Vfs, FileSystemError> {
+    let fs = SadakFs::mount(open_image(image, true)?, true)?;
+    let vfs = Vfs::new();
+    vfs.mount("/", Arc::new(fs)).map_err(map_vfs_error_to_fs_error)?;
+    Ok(vfs)
+}
+
+// İmaj güvenilmez girdidir: ad tek bir yol bileşeni olmalıdır
+#[cfg(feature = "std")]
+fn check_entry_name(dir: &str, name: &str) -> Result<(), FileSystemError> {
+    if name.is_empty() || name == "." || name == ".." || name.contains('/') || name.contains('\0') {
+        return Err(FileSystemError::InvalidData(format!("{}: invalid entry name {:?}", dir, name)));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+fn image_entry(vfs: &Vfs, path: String) -> Result<ImageEntry, FileSystemError> {
+    let metadata = vfs.metadata(&path).map_err(map_vfs_error_to_fs_error)?;
+    let target = match metadata.file_type {
+        FileType::Symlink => Some(vfs.readlink(&path).map_err(map_vfs_error_to_fs_error)?),
+        _ => None,
+    };
+    let xattrs = vfs.list_xattr(&path).map_err(map_vfs_error_to_fs_error)?;
+    Ok(ImageEntry { path, metadata, target, xattrs })
+}
+
+#[cfg(feature = "std")]
+fn walk(vfs: &Vfs, path: &str, entries: &mut Vec<ImageEntry>) -> Result<(), FileSystemError> {
+    for child in vfs.read_dir(path).map_err(map_vfs_error_to_fs_error)? {
+        check_entry_name(path, &child.name)?;
+        let child_path = if path == "/" { format!("/{}", child.name) } else { format!("{}/{}", path, child.name) };
+        let entry = image_entry(vfs, child_path.clone())?;
+        let is_dir = entry.metadata.file_type == FileType::Directory;
+        entries.push(entry);
+        if is_dir {
+            walk(vfs, &child_path, entries)?;
+        }
+    }
+    Ok(())
+}
+
+/// İmajdaki girdileri ada göre sıralı, önce-kök sırasıyla listeler (kök hariç).
+#[cfg(feature = "std")]
+pub fn list(image: &str) -> Result<Vec<ImageEntry>, FileSystemError> {
+    let vfs = mount_image(image)?;
+    let mut entries = Vec::new();
+    walk(&vfs, "/", &mut entries)?;
+    Ok(entries)
+}
+
+#[cfg(feature = "std")]
+fn set_host_times(host: &Path, metadata: &Metadata) -> Result<(), FileSystemError> {
+    use std::time::{Duration, UNIX_EPOCH};
+    let times = std::fs::FileTimes::new()
+        .set_accessed(UNIX_EPOCH + Duration::from_secs(metadata.atime))
+        .set_modified(UNIX_EPOCH + Duration::from_secs(metadata.mtime));
+    std::fs::File::open(host).and_then(|f| f.set_times(times)).map_err(|e| map_host_error(host, e))
+}
+
+// Girdinin özniteliklerini ve (istenirse) sahipliğini ana makinedeki karşılığına uygular.
+#[cfg(feature = "std")]
+fn apply_xattrs_and_owner(vfs: &Vfs, entry: &ImageEntry, host: &Path, same_owner: bool, summary: &mut Summary) -> Result<(), FileSystemError> {
+    for name in entry.xattrs.iter() {
+        let value = vfs.get_xattr(&entry.path, name).map_err(map_vfs_error_to_fs_error)?;
+        if let Err(e) = host_xattr::set(host, name, &value) {
+            summary.skipped.push(format!("{}: xattr {}: {}", host.display(), name, e));
+        }
+    }
+    if same_owner {
+        std::os::unix::fs::lchown(host, Some(entry.metadata.uid), Some(entry.metadata.gid)).map_err(|e| map_host_error(host, e))?;
+    }
+    Ok(())
+}
+
+/// İmajı `dest` dizinine çıkarır. `same_owner` ile sahiplik de uygulanır (genellikle root gerekir).
+/// Kökün kipi, zamanları ve öznitelikleri `dest`e uygulanır. Var olan dosyaların üzerine yazılmaz
+/// ve var olan sembolik bağlar izlenmez; hedefi `dest` dışına çıkan bağlar atlanır.
+#[cfg(feature = "std")]
+pub fn extract(image: &str, dest: &Path, same_owner: bool) -> Result<Summary, FileSystemError> {
+    use std::os::unix::fs::PermissionsExt;
+    let vfs = mount_image(image)?;
+    let root = image_entry(&vfs, String::from("/"))?;
+    let mut entries = Vec::new();
+    walk(&vfs, "/", &mut entries)?;
+    std::fs::create_dir_all(dest).map_err(|e| map_host_error(dest, e))?;
+
+    let mut summary = Summary::default();
+    for entry in entries.iter() {
+        let host = dest.join(&entry.path[1..]);
+        match entry.metadata.file_type {
+            FileType::Directory => match std::fs::create_dir(&host) {
+                // Yalnızca gerçek bir dizin kabul edilir; dizine giden bağ izlenmez
+                Err(e) if !(e.kind() == std::io::ErrorKind::AlreadyExists && std::fs::symlink_metadata(&host).is_ok_and(|m| m.is_dir())) => {
+                    return Err(map_host_error(&host, e))
+                }
+                _ => {}
+            },
+            FileType::Symlink => {
+                let target = entry.target.as_deref().unwrap_or_default();
+                let components: Vec<String> = entry.path[1..].split('/').map(String::from).collect();
+                if !symlink_stays_inside(&components, target) {
+                    summary.skipped.push(format!("{}: link target {} leaves the destination", host.display(), target));
+                    continue;
+                }
+                std::os::unix::fs::symlink(target, &host).map_err(|e| map_host_error(&host, e))?;
+            }
+            FileType::File => {
+                use std::io::Write;
+                let mut source = vfs.open(&entry.path, O_RDONLY, 0).map_err(map_vfs_error_to_fs_error)?;
+                let mut out = std::fs::OpenOptions::new().write(true).create_new(true).open(&host).map_err(|e| map_host_error(&host, e))?;
+                let mut buf = vec![0u8; COPY_CHUNK];
+                let mut offset = 0u64;
+                while offset < entry.metadata.size {
+                    let count = source.read_at(offset, &mut buf).map_err(map_vfs_error_to_fs_error)?;
+                    if count == 0 {
+                        break;
+                    }
+                    out.write_all(&buf[..count]).map_err(|e| map_host_error(&host, e))?;
+                    offset += count as u64;
+                }
+            }
+        }
+        apply_xattrs_and_owner(&vfs, entry, &host, same_owner, &mut summary)?;
+        if entry.metadata.file_type == FileType::File {
+            std::fs::set_permissions(&host, std::fs::Permissions::from_mode(entry.metadata.mode)).map_err(|e| map_host_error(&host, e))?;
+            set_host_times(&host, &entry.metadata)?;
+        }
+        summary.count(entry.metadata.file_type, entry.metadata.size);
+    }
+    apply_xattrs_and_owner(&vfs, &root, dest, same_owner, &mut summary)?;
+    // Dizin izinleri ve zamanları en son, alttan üste, kök (`dest`) en son
+    // (salt okunur dizinlere yazılabilsin diye)
+    for entry in entries.iter().rev().filter(|e| e.metadata.file_type == FileType::Directory).chain(core::iter::once(&root)) {
+        let host = dest.join(&entry.path[1..]);
+        set_host_times(&host, &entry.metadata)?;
+        std::fs::set_permissions(&host, std::fs::Permissions::from_mode(entry.metadata.mode)).map_err(|e| map_host_error(&host, e))?;
+    }
+    Ok(summary)
+}
+
+/// Komutu çalıştırır ve sonucu standart çıktıya yazar.
+#[cfg(feature = "std")]
+pub fn run(command: Command) -> Result<(), FileSystemError> {
+    let report = |summary: &Summary| {
+        for skipped in summary.skipped.iter() {
+            eprintln!("sadak-image: skipped {}", skipped);
+        }
+        println!("{} files ({} bytes), {} directories, {} symlinks", summary.files, summary.bytes, summary.directories, summary.symlinks);
+    };
+    match command {
+        Command::Pack { source, image, mut options } => {
+            if options.timestamp.is_none() {
+                if let Ok(epoch) = std::env::var("SOURCE_DATE_EPOCH") {
+                    options.timestamp = Some(epoch.parse().map_err(|_| FileSystemError::InvalidParameter(format!("SOURCE_DATE_EPOCH: invalid value '{}'", epoch)))?);
+                }
+            }
+            report(&pack(Path::new(&source), &image, &options)?);
+        }
+        Command::List { image } => {
+            for entry in list(&image)? {
+                println!("{}", entry.format());
+            }
+        }
+        Command::Extract { image, dest, same_owner } => report(&extract(&image, Path::new(&dest), same_owner)?),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = match parse_args(&args) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+    if let Err(e) = run(command) {
+        eprintln!("sadak-image: {}", e);
+        std::process::exit(1);
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sadak-image-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn set_mtime(path: &Path, secs: u64) {
+        let times = std::fs::FileTimes::new().set_modified(UNIX_EPOCH + Duration::from_secs(secs));
+        std::fs::File::open(path).unwrap().set_times(times).unwrap();
+    }
+
+    // Örnek ağaç: /bin/tool (0755, blokları aşan), /etc/motd, /etc/empty/, /link -> etc/motd
+    fn sample_tree(root: &Path) -> Vec<u8> {
+        let tool: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::create_dir_all(root.join("bin")).unwrap();
+        std::fs::create_dir_all(root.join("etc/empty")).unwrap();
+        std::fs::write(root.join("bin/tool"), &tool).unwrap();
+        std::fs::set_permissions(root.join("bin/tool"), std::fs::Permissions::from_mode(0o4755)).unwrap();
+        std::fs::write(root.join("etc/motd"), b"Sahne64\n").unwrap();
+        std::os::unix::fs::symlink("etc/motd", root.join("link")).unwrap();
+        for path in ["bin/tool", "etc/motd", "etc/empty", "etc", "bin", ""] {
+            set_mtime(&root.join(path), 1_600_000_000);
+        }
+        tool
+    }
+
+    #[test]
+    fn test_pack_list_and_extract() -> Result<(), FileSystemError> {
+        let dir = scratch("roundtrip");
+        let tool = sample_tree(&dir.join("src"));
+        // Dosya sistemi kullanıcı özniteliklerini destekliyorsa bunlar da taşınır
+        let with_xattr = host_xattr::set(&dir.join("src/etc/motd"), "user.sahne", b"1").is_ok();
+        let image = dir.join("disk.img");
+        let image = image.to_str().unwrap();
+        let options = PackOptions { timestamp: Some(1_700_000_000), owner: Some((0, 0)), ..PackOptions::default() };
+
+        let summary = pack(&dir.join("src"), image, &options)?;
+        assert_eq!((summary.files, summary.directories, summary.symlinks, summary.bytes), (2, 3, 1, 20_008));
+        assert!(summary.skipped.is_empty());
+
+        let entries = list(image)?;
+        let paths: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["/bin", "/bin/tool", "/etc", "/etc/empty", "/etc/motd", "/link"]);
+        let tool_entry = &entries[1];
+        assert_eq!((tool_entry.metadata.mode, tool_entry.metadata.size, tool_entry.metadata.mtime), (0o4755, 20_000, 1_600_000_000));
+        assert_eq!(tool_entry.format(), "-rwsr-xr-x     0     0      20000 1600000000 /bin/tool");
+        // Bağın kendi zamanı ayarlanmadı (şimdi): sabit zaman damgasına indirildi
+        assert_eq!(entries[5].format(), "lrwxrwxrwx     0     0          8 1700000000 /link -> etc/motd");
+        assert_eq!(entries[4].xattrs.is_empty(), !with_xattr);
+
+        let out = dir.join("out");
+        let summary = extract(image, &out, false)?;
+        assert!(summary.skipped.is_empty(), "{:?}", summary.skipped);
+        assert_eq!(std::fs::read(out.join("bin/tool")).unwrap(), tool);
+        assert_eq!(std::fs::read_link(out.join("link")).unwrap(), Path::new("etc/motd"));
+        let meta = std::fs::metadata(out.join("bin/tool")).unwrap();
+        assert_eq!(meta.permissions().mode() & 0o7777, 0o4755);
+        assert_eq!(meta.modified().unwrap(), UNIX_EPOCH + Duration::from_secs(1_600_000_000));
+        assert_eq!(std::fs::metadata(out.join("etc")).unwrap().modified().unwrap(), UNIX_EPOCH + Duration::from_secs(1_600_000_000));
+        if with_xattr {
+            assert_eq!(host_xattr::list(&out.join("etc/motd")).unwrap(), vec![(String::from("user.sahne"), b"1".to_vec())]);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn test_reproducible_images_and_geometry() -> Result<(), FileSystemError> {
+        let dir = scratch("repro");
+        sample_tree(&dir.join("src"));
+        let options = PackOptions { timestamp: Some(1_500_000_000), owner: Some((0, 0)), xattrs: false, ..PackOptions::default() };
+        let (a, b) = (dir.join("a.img"), dir.join("b.img"));
+        pack(&dir.join("src"), a.to_str().unwrap(), &options)?;
+        // Ana makinedeki zamanlar değişse de sabit zaman damgasından yenileri ona indirilir
+        set_mtime(&dir.join("src/etc/motd"), 1_650_000_000);
+        pack(&dir.join("src"), b.to_str().unwrap(), &options)?;
+        assert_eq!(std::fs::read(&a).unwrap(), std::fs::read(&b).unwrap());
+        let motd = list(a.to_str().unwrap())?.into_iter().find(|e| e.path == "/etc/motd").unwrap();
+        assert_eq!((motd.metadata.mtime, motd.metadata.ctime), (1_500_000_000, 1_500_000_000));
+
+        // Açık geometri: yetmezse NoSpace
+        let tight = PackOptions { block_size: 1024, total_blocks: Some(28), inodes: Some(16), ..options.clone() };
+        assert!(matches!(pack(&dir.join("src"), a.to_str().unwrap(), &tight), Err(FileSystemError::NoSpace(_))));
+        let roomy = PackOptions { total_blocks: Some(256), ..tight };
+        pack(&dir.join("src"), a.to_str().unwrap(), &roomy)?;
+        assert_eq!(std::fs::metadata(&a).unwrap().len(), 256 * 1024);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let args = |list: &[&str]| parse_args(&list.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+        assert_eq!(
+            args(&["pack", "root", "out.img", "--timestamp", "0", "--owner", "0:0", "--no-xattrs"]),
+            Ok(Command::Pack {
+                source: String::from("root"),
+                image: String::from("out.img"),
+                options: PackOptions { timestamp: Some(0), owner: Some((0, 0)), xattrs: false, ..PackOptions::default() },
+            })
+        );
+        assert_eq!(args(&["extract", "a.img", "out", "--same-owner"]), Ok(Command::Extract { image: String::from("a.img"), dest: String::from("out"), same_owner: true }));
+        assert!(args(&["list", "a.img", "--blocks", "10"]).is_err());
+        assert!(args(&["pack", "root", "out.img", "--config", "sadak.toml"]).is_err());
+        assert!(args(&["pack", "root", "out.img", "--owner", "root"]).is_err());
+        assert_eq!(mode_string(FileType::Directory, 0o1777), "drwxrwxrwt");
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_stays_inside_destination() -> Result<(), FileSystemError> {
+        let dir = scratch("confine");
+        let src = dir.join("src");
+        std::fs::create_dir_all(src.join("etc")).unwrap();
+        std::fs::write(src.join("etc/motd"), b"Sahne64\n").unwrap();
+        std::os::unix::fs::symlink("../../outside", src.join("etc/escape")).unwrap();
+        std::os::unix::fs::symlink("/etc/passwd", src.join("absolute")).unwrap();
+        std::os::unix::fs::symlink("../etc/motd", src.join("etc/inside")).unwrap();
+        std::fs::set_permissions(&src, std::fs::Permissions::from_mode(0o750)).unwrap();
+        set_mtime(&src, 1_600_000_000);
+        let image = dir.join("disk.img");
+        let image = image.to_str().unwrap();
+        pack(&src, image, &PackOptions { owner: Some((0, 0)), xattrs: false, ..PackOptions::default() })?;
+
+        // Dışarı çıkan bağlar atlanır, içeride kalanlar kurulur; kökün kipi ve zamanı hedefe uygulanır
+        let out = dir.join("out");
+        let summary = extract(image, &out, false)?;
+        assert_eq!(summary.skipped.len(), 2, "{:?}", summary.skipped);
+        assert!(std::fs::symlink_metadata(out.join("etc/escape")).is_err());
+        assert!(std::fs::symlink_metadata(out.join("absolute")).is_err());
+        assert_eq!(std::fs::read_link(out.join("etc/inside")).unwrap(), Path::new("../etc/motd"));
+        let meta = std::fs::metadata(&out).unwrap();
+        assert_eq!(meta.permissions().mode() & 0o7777, 0o750);
+        assert_eq!(meta.modified().unwrap(), UNIX_EPOCH + Duration::from_secs(1_600_000_000));
+
+        // Hedefte dizin adında bir bağ izlenmez, var olan dosyanın üzerine yazılmaz
+        let elsewhere = dir.join("elsewhere");
+        std::fs::create_dir_all(&elsewhere).unwrap();
+        let linked = dir.join("linked");
+        std::fs::create_dir_all(&linked).unwrap();
+        std::os::unix::fs::symlink(&elsewhere, linked.join("etc")).unwrap();
+        assert!(extract(image, &linked, false).is_err());
+        assert!(std::fs::read_dir(&elsewhere).unwrap().next().is_none());
+        std::fs::set_permissions(&out, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(extract(image, &out, false).is_err());
+        assert_eq!(std::fs::read(out.join("etc/motd")).unwrap(), b"Sahne64\n");
+
+        assert!(check_entry_name("/", "motd").is_ok());
+        for name in ["", ".", "..", "a/b", "a\0b"] {
+            assert!(matches!(check_entry_name("/", name), Err(FileSystemError::InvalidData(_))));
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+        Ok(())
+    }
+}
+
+// Removed redundant print module and panic handler boilerplate.
+// The empty lib module configuration is kept.
+#[cfg(not(any(feature = "std", test)))] // Only when not building std or test
+pub mod lib {} // Keep the empty lib module if needed for the crate structure
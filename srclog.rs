@@ -1,458 +1,1003 @@
 #![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
-#![cfg_attr(not(feature = "std")), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
 
 // no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
-#[cfg_attr(not(feature = "std")), macro_use]
+#[cfg_attr(not(feature = "std"), macro_use)]
 extern crate alloc;
 
-// Eğer std özelliği aktifse, standart kütüphaneyi kullan.
-#[cfg(feature = "std")]
-use std::fs::{File, OpenOptions};
-#[cfg(feature = "std")]
-use std::io::{self, Write as StdWrite, Error as StdIOError, ErrorKind as StdIOErrorKind, WriteExt as StdWriteExt}; // Added WriteExt
-#[cfg(feature = "std")]
-use std::sync::Mutex as StdMutex; // Use std Mutex in std
-#[cfg(feature = "std")]
-use std::path::Path; // For std file paths
+// Structured logger for SADAK.
+//
+// - A record carries a level, the module it comes from, a message and key-value fields
+//   (well-known keys: device, inode, block, op). Records are written as logfmt lines:
+//   `time=2024-05-17T12:30:20Z level=warning module=journal msg="replay cut short" device=nvme0 block=88`
+// - Filters are per module (longest `::` prefix wins, e.g. "info,vfs=debug,archivefs=off") and
+//   can be changed while the logger is in use.
+// - Sinks: a rotating log file (size and record-count triggers, a fixed number of rotated files
+//   kept) on a SADAK VFS path or, with `std`, on the host; an in-memory sink.
+// - Every record that reaches the ring buffer level is also kept in memory so the last records
+//   can be dumped after a crash, even when the sinks fail.
+// - Write errors are returned from `log` and counted; they are never silently dropped.
+
 #[cfg(feature = "std")]
-use std::error::Error as StdError; // For std Error trait
+use std::io::Write as StdWrite;
 #[cfg(feature = "std")]
-use chrono::Local; // For std timestamp
-
-// Gerekli Sahne64 modülleri ve yapılarını içeri aktar (assume these are defined elsewhere)
-use crate::{fs, resource, SahneError, FileSystemError, Handle}; // fs, resource, SahneError, FileSystemError, Handle
-use crate::fs::{O_CREAT, O_APPEND, O_WRONLY}; // Import necessary fs flags
+use std::path::PathBuf;
 
+use crate::config::LogConfig;
+use crate::error::DriveType;
+use crate::vfs::{map_vfs_error_to_fs_error, Vfs, VfsError, VfsFile, O_APPEND, O_CREAT, O_WRONLY};
+use crate::FileSystemError;
 
 use spin::Mutex; // Use spin Mutex for no_std consistency
 
-
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
 use alloc::string::{String, ToString}; // Requires alloc
+use alloc::sync::Arc;
 use alloc::vec::Vec; // Requires alloc
 use alloc::format; // Requires alloc
 
-
-// core::fmt, core::result, core::ops::Drop, core::io
+// core::fmt, core::result, core::sync::atomic
 use core::fmt;
+use core::fmt::Write as FmtWrite;
 use core::result::Result;
-use core::ops::Drop; // For Drop trait
-use core::io::{Write, Error as CoreIOError, ErrorKind as CoreIOErrorKind, WriteExt as CoreWriteExt}; // core::io
+use core::sync::atomic::{AtomicU64, Ordering};
 
 
-// Removed redundant imports from core, cfg(not(feature="std")), etc.
-// Removed redundant panic handler boilerplate.
+// Well-known field keys
+pub const FIELD_DEVICE: &str = "device";
+pub const FIELD_INODE: &str = "inode";
+pub const FIELD_BLOCK: &str = "block";
+pub const FIELD_OP: &str = "op";
 
+/// Default number of records kept in the ring buffer.
+pub const DEFAULT_RING_CAPACITY: usize = 256;
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
+
+// Günlük seviyeleri
+/// Log levels, from the most verbose to the most important.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warning,
+    Error,
 }
 
-// Helper function to map std::io::Error to FileSystemError (copied from other files)
-#[cfg(feature = "std")]
-fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
-    FileSystemError::IOError(format!("IO Error: {}", e))
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warning => "warning",
+            LogLevel::Error => "error",
+        }
+    }
+
+    /// Parses a level name ("debug", "info", "warn"/"warning", "error"; case-insensitive).
+    pub fn parse(name: &str) -> Option<LogLevel> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "debug" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warn" | "warning" => Some(LogLevel::Warning),
+            "error" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
 }
 
-// Helper function to map CoreIOError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_core_io_error_to_fs_error(e: CoreIOError) -> FileSystemError {
-     FileSystemError::IOError(format!("CoreIOError: {:?}", e))
-     // TODO: Implement a proper mapping based on CoreIOErrorKind
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
+/// Parses a filter level: a level name or "off" (`None`).
+fn parse_filter_level(name: &str) -> Result<Option<LogLevel>, FileSystemError> {
+    if name.trim().eq_ignore_ascii_case("off") {
+        return Ok(None);
+    }
+    LogLevel::parse(name).map(Some).ok_or_else(|| FileSystemError::InvalidParameter(format!("Invalid log level: {}", name.trim())))
+}
 
-// Sahne64 Handle'ı için core::io::Write implementasyonu (simplified from SahneResourceReadWriteSeek)
-// This requires fs::write_at. Append mode might need explicit seeking to end before writing.
-#[cfg(not(feature = "std"))]
-pub struct SahneResourceWrite { // Write-only resource wrapper
-    handle: Handle,
-    // Append mode often implies writing at the end, explicit position tracking might be complex.
-    // Relying on fs::write_at at offset 0 or implicitly at the end for append mode.
-    // Let's assume fs::write_at with offset 0 for simplicity here, simulating append.
-    // A true append mode might require seeking to the end before each write or a dedicated fs::append_at syscall.
-    // For this logger, append is crucial. Let's assume `fs::write` *always* appends when opened with O_APPEND.
-    // If not, we need Seek and seek to End before each write.
-    // Let's assume fs::write with O_APPEND works as expected without explicit seek to end.
+/// Per-module level filter. A module's records pass when their level is at least the level of
+/// the longest matching module prefix (or the default level); `None` turns logging off.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFilter {
+    default: Option<LogLevel>,
+    modules: Vec<(String, Option<LogLevel>)>,
 }
 
-#[cfg(not(feature = "std"))]
-impl SahneResourceWrite {
-    pub fn new(handle: Handle) -> Self {
-        SahneResourceWrite { handle }
+impl LogFilter {
+    pub fn new(default: Option<LogLevel>) -> Self {
+        LogFilter { default, modules: Vec::new() }
+    }
+
+    /// Parses a filter spec such as "warning,vfs=debug,raid::rebuild=info,archivefs=off".
+    /// An entry without '=' sets the default level.
+    pub fn parse(spec: &str) -> Result<Self, FileSystemError> {
+        let mut filter = LogFilter::new(Some(LogLevel::Info));
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match part.split_once('=') {
+                Some((module, level)) => filter.set_module_level(module.trim(), parse_filter_level(level)?),
+                None => filter.default = parse_filter_level(part)?,
+            }
+        }
+        Ok(filter)
+    }
+
+    pub fn default_level(&self) -> Option<LogLevel> {
+        self.default
+    }
+
+    pub fn set_default_level(&mut self, level: Option<LogLevel>) {
+        self.default = level;
+    }
+
+    /// Sets (or replaces) the level of a module and its submodules.
+    pub fn set_module_level(&mut self, module: &str, level: Option<LogLevel>) {
+        match self.modules.iter_mut().find(|(m, _)| m == module) {
+            Some(entry) => entry.1 = level,
+            None => self.modules.push((module.to_string(), level)),
+        }
+    }
+
+    /// Removes a module's own level; it falls back to its parent or the default.
+    pub fn clear_module_level(&mut self, module: &str) {
+        self.modules.retain(|(m, _)| m != module);
+    }
+
+    /// Effective level of `module`.
+    pub fn level_for(&self, module: &str) -> Option<LogLevel> {
+        self.modules
+            .iter()
+            .filter(|(prefix, _)| {
+                module == prefix || (module.starts_with(prefix.as_str()) && module[prefix.len()..].starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    pub fn enabled(&self, level: LogLevel, module: &str) -> bool {
+        self.level_for(module).map_or(false, |min| level >= min)
     }
 }
 
-#[cfg(not(feature = "std"))]
-impl core::io::Write for SahneResourceWrite { // Use core::io::Write trait
-    fn write(&mut self, buf: &[u8]) -> Result<usize, core::io::Error> { // Return core::io::Error
-         // Assuming fs::write(handle, buf) writes at the current position or appends with O_APPEND.
-         // Let's assume it appends for O_APPEND.
-         let bytes_to_write = buf.len();
-         if bytes_to_write == 0 { return Ok(0); }
+impl fmt::Display for LogFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.default.map_or("off", |l| l.as_str()))?;
+        for (module, level) in &self.modules {
+            write!(f, ",{}={}", module, level.map_or("off", |l| l.as_str()))?;
+        }
+        Ok(())
+    }
+}
 
-         // Assuming fs::write(handle, buf) Result<usize, SahneError>
-         let bytes_written = fs::write(self.handle, buf) // Use the basic fs::write
-             .map_err(|e| core::io::Error::new(core::io::ErrorKind::Other, format!("fs::write error: {:?}", e)))?; // Map SahneError to core::io::Error
+/// Value of a record field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldValue {
+    Str(String),
+    U64(u64),
+    I64(i64),
+    Bool(bool),
+}
 
-         Ok(bytes_written) // Return bytes written
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Str(s) => f.write_str(s),
+            FieldValue::U64(v) => write!(f, "{}", v),
+            FieldValue::I64(v) => write!(f, "{}", v),
+            FieldValue::Bool(v) => write!(f, "{}", v),
+        }
     }
+}
 
-     fn flush(&mut self) -> Result<(), core::io::Error> {
-         // Assuming fs::flush(handle) or sync() is available for durability.
-         // If not, this is a no-op or needs a different syscall.
-         // For logging, flushing is important for durability. Assume fs::flush is available.
-         #[cfg(not(feature = "no_fs_flush"))] // Assume fs::flush is available unless this feature is set
-         fs::flush(self.handle).map_err(|e| core::io::Error::new(core::io::ErrorKind::Other, format!("fs::flush error: {:?}", e)))?;
-         #[cfg(feature = "no_fs_flush")] // If fs::flush is not available
-         { /* No-op or alternative sync method */ }
-         Ok(())
-     }
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        FieldValue::Str(value.to_string())
+    }
 }
 
-#[cfg(not(feature = "std"))]
-impl Drop for SahneResourceWrite {
-     fn drop(&mut self) {
-         // Release the resource Handle when the SahneResourceWrite is dropped
-         if let Some(handle) = self.handle.take() { // Use take() to avoid double free if drop is called multiple times
-              // Attempt to flush before closing for better durability, ignore errors in drop
-              #[cfg(not(feature = "no_fs_flush"))]
-              let _ = fs::flush(handle);
-              #[cfg(feature = "no_fs_flush")]
-              { /* No-op */ }
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        FieldValue::Str(value)
+    }
+}
 
-              if let Err(e) = resource::release(handle) {
-                  // Log the error as drop should not panic
-                  eprintln!("WARN: SahneResourceWrite drop sırasında Sahne64 kaynak serbest bırakma hatası: {:?}", e); // no_std print
-              }
-         }
-     }
+impl From<&DriveType> for FieldValue {
+    fn from(value: &DriveType) -> Self {
+        FieldValue::Str(value.to_string())
+    }
 }
 
+impl From<u64> for FieldValue {
+    fn from(value: u64) -> Self {
+        FieldValue::U64(value)
+    }
+}
 
-// Günlük seviyeleri
-#[derive(Debug, PartialEq, PartialOrd, Copy, Clone)]
-pub enum LogLevel {
-    Debug,
-    Info,
-    Warning,
-    Error,
-}
-
-// Sürücü tipleri
-#[derive(Debug, Clone, Copy)] // Add Clone, Copy for easier handling
-pub enum DriveType {
-    HDD,
-    SSD,
-    SATA,
-    SAS,
-    NVMe,
-    UFS,
-    eMMC,
-    USB,
-    Unknown,
-}
-
-/// File-based logger for Sahne66.
-/// Writes log messages to a file with timestamps, levels, and drive type.
-pub struct Logger<W: Write + Drop> { // Generic over the underlying writer
-     // The underlying file/resource writer, protected by a Mutex for thread safety.
-    file: Mutex<W>, // Use spin Mutex for both std and no_std consistency
-    level: LogLevel, // Minimum level to log
-    drive_type: DriveType, // Associated drive type
-    // Add persistence mechanism if logger configuration needs to be saved.
-}
-
-impl<W: Write + Drop> Logger<W> {
-    /// Creates a new Logger instance with the given writer.
-    ///
-    /// # Arguments
-    ///
-    /// * `writer`: The underlying writer for the log output (implements Write + Drop).
-    /// * `level`: The minimum log level for this logger.
-    /// * `drive_type`: The associated drive type.
-    ///
-    /// # Returns
-    ///
-    /// A new Logger instance.
-    pub fn new(writer: W, level: LogLevel, drive_type: DriveType) -> Self {
-        Logger {
-            file: Mutex::new(writer), // Wrap writer in Mutex (Requires Mutex)
-            level,
-            drive_type,
-        }
-    }
-
-    /// Opens a file/resource and creates a Logger instance over it.
-    /// Convenience constructor for file-based loggers.
-    ///
-    /// # Arguments
-    ///
-    /// * `filename`: The path to the log file/resource.
-    /// * `level`: The minimum log level for this logger.
-    /// * `drive_type`: The associated drive type.
-    ///
-    /// # Returns
-    ///
-    /// A Result containing the Logger instance or a FileSystemError.
-    #[cfg(feature = "std")]
-    pub fn open_file(filename: &str, level: LogLevel, drive_type: DriveType) -> Result<Logger<File>, FileSystemError> { // Return FileSystemError
-        let file = OpenOptions::new()
-            .create(true) // Create if doesn't exist
-            .append(true) // Append to the end
-            .write(true) // Enable writing
-            .open(filename).map_err(|e| map_std_io_error_to_fs_error(e))?; // Map std::io::Error
-
-
-        // In std, File implements Write + Drop.
-        Ok(Logger::new(file, level, drive_type))
-    }
-
-    /// Opens a resource using Sahne64 fs calls and creates a Logger instance over it.
-    /// Convenience constructor for Sahne64 resource-based loggers.
-    ///
-    /// # Arguments
-    ///
-    /// * `filename`: The path/ID of the resource.
-    /// * `level`: The minimum log level for this logger.
-    /// * `drive_type`: The associated drive type.
-    ///
-    /// # Returns
-    ///
-    /// A Result containing the Logger instance or a FileSystemError.
-    #[cfg(not(feature = "std"))]
-    pub fn open_resource(filename: &str, level: LogLevel, drive_type: DriveType) -> Result<Logger<SahneResourceWrite>, FileSystemError> { // Return FileSystemError
-        let flags = fs::O_CREAT | fs::O_APPEND | fs::O_WRONLY;
-        let handle = fs::open(filename, flags).map_err(|e| map_sahne_error_to_fs_error(e))?; // Map SahneError
-
-        // SahneResourceWrite implements Write + Drop.
-        let resource_writer = SahneResourceWrite::new(handle);
-
-
-        Ok(Logger::new(resource_writer, level, drive_type))
-    }
-
-
-    /// Writes a log message if its level is sufficient.
-    /// Formats the message with timestamp, level, and drive type.
-    ///
-    /// # Arguments
-    ///
-    /// * `level`: The level of the log message.
-    /// * `message`: The log message string.
-    ///
-    /// Note: Write errors are currently ignored.
-    pub fn log(&self, level: LogLevel, message: &str) { // Does not return Result, errors are ignored
-        if self.should_log(&level) {
-            let timestamp = self.get_timestamp(); // Requires alloc and timestamp logic
-            let level_str = format!("{:?}", level).to_uppercase(); // Requires alloc
-            let drive_type_str = format!("{:?}", self.drive_type).to_uppercase(); // Requires alloc
-            // Format the log message string (Requires alloc)
-            let log_message = format!("[{}] {} ({}) {}\n", timestamp, level_str, drive_type_str, message);
-
-            // Acquire the lock on the file writer
-            let mut file = self.file.lock(); // Use spin Mutex lock
-            // Check if lock acquisition was successful in case of panic (though spin Mutex doesn't panic on acquisition)
-             #[cfg(feature = "std")] // std Mutex::lock can panic
-             let mut file = file.expect("Logger file mutex poisoned"); // Handle poisoned mutex in std
-
-
-            // Write the message to the file. Ignore errors for basic logging.
-            // In a robust system, write errors should be handled (e.g., retry, fallback to console).
-            let _ = file.write_all(log_message.as_bytes()); // Requires WriteExt
-            let _ = file.flush(); // Flush for durability (Requires WriteExt)
-
-            // Lock is released when 'file' goes out of scope.
-        }
-    }
-
-    /// Checks if a log message with the given level should be logged by this logger.
-    ///
-    /// # Arguments
-    ///
-    /// * `level`: The level of the log message.
-    ///
-    /// # Returns
-    ///
-    /// True if the message should be logged, false otherwise.
-    fn should_log(&self, level: &LogLevel) -> bool { // Takes reference to LogLevel
-        level >= &self.level // Comparison uses PartialOrd derive
-    }
-
-    /// Gets the current timestamp string.
-    ///
-    /// # Returns
-    ///
-    /// A String containing the formatted timestamp.
-    #[cfg(feature = "std")]
-    fn get_timestamp(&self) -> String { // Returns String (Requires alloc)
-        Local::now().format("%Y-%m-%d %H:%M:%S").to_string() // Uses chrono in std
-    }
-
-    #[cfg(not(feature = "std"))]
-    fn get_timestamp(&self) -> String { // Returns String (Requires alloc)
-        // Sahne64'te gerçek zamanı almak için bir sistem çağrısı gerekebilir.
-        // Şimdilik basit bir placeholder kullanıyoruz ve alloc gerektiriyor.
-        String::from("YYYY-MM-DD HH:MM:SS") // Requires alloc
-    }
-}
-
-
-// Removed example main functions - they are typically outside the library crate file.
+impl From<u32> for FieldValue {
+    fn from(value: u32) -> Self {
+        FieldValue::U64(value as u64)
+    }
+}
 
-#[cfg(test)]
-#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
-mod tests {
-    use super::*;
-    use std::io::{Read, Write, Seek, Cursor}; // For File/Cursor traits
-    use std::fs::{remove_file, OpenOptions}; // For creating/managing test files
-    use std::path::Path;
-    use alloc::string::ToString; // For to_string()
-    use alloc::vec::Vec; // For Vec
-    use core::io::WriteExt; // For flush() and write_all() on Cursor
+impl From<usize> for FieldValue {
+    fn from(value: usize) -> Self {
+        FieldValue::U64(value as u64)
+    }
+}
+
+impl From<i64> for FieldValue {
+    fn from(value: i64) -> Self {
+        FieldValue::I64(value)
+    }
+}
 
-    // Helper function to map std::io::Error to FileSystemError in tests
-    fn map_std_io_error_to_fs_error_test(e: std::io::Error) -> FileSystemError {
-        FileSystemError::IOError(format!("IO Error in test: {}", e))
+impl From<bool> for FieldValue {
+    fn from(value: bool) -> Self {
+        FieldValue::Bool(value)
     }
+}
+
+/// A log record. Built with `Record::new(...).device(..).block(..)` and passed to `Logger::log`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub level: LogLevel,
+    /// Module path, e.g. "vfs" or "raid::rebuild".
+    pub module: String,
+    pub message: String,
+    pub fields: Vec<(String, FieldValue)>,
+}
 
+impl Record {
+    pub fn new(level: LogLevel, module: &str, message: &str) -> Self {
+        Record { level, module: module.to_string(), message: message.to_string(), fields: Vec::new() }
+    }
 
-    // Mock SahneResourceWrite for testing no_std path with in-memory buffer (Cursor)
-    struct MockSahneResourceWrite {
-        cursor: Cursor<Vec<u8>>, // In-memory buffer using std::io::Cursor
-        // No Handle needed for in-memory mock
+    /// Adds a key-value field (keys are reduced to `[A-Za-z0-9_.-]`).
+    pub fn field(mut self, key: &str, value: impl Into<FieldValue>) -> Self {
+        let key: String = key.chars().map(|c| if c.is_ascii_alphanumeric() || "_.-".contains(c) { c } else { '_' }).collect();
+        self.fields.push((key, value.into()));
+        self
     }
-    impl MockSahneResourceWrite {
-        fn new() -> Self { MockSahneResourceWrite { cursor: Cursor::new(Vec::new()) } } // Requires alloc
-        fn get_content(&self) -> &[u8] { self.cursor.get_ref().as_slice() } // Get the underlying data
+
+    pub fn device(self, device: impl Into<FieldValue>) -> Self {
+        self.field(FIELD_DEVICE, device)
+    }
+
+    pub fn inode(self, inode: u64) -> Self {
+        self.field(FIELD_INODE, inode)
+    }
+
+    pub fn block(self, block: u64) -> Self {
+        self.field(FIELD_BLOCK, block)
+    }
+
+    pub fn op(self, op: &str) -> Self {
+        self.field(FIELD_OP, op)
     }
-    // Implement core::io::Write for the Mock
-    impl Write for MockSahneResourceWrite {
-        fn write(&mut self, buf: &[u8]) -> Result<usize, core::io::Error> {
-            // Write to the inner Cursor
-             self.cursor.write(buf)
-                 .map_err(|e| core::io::Error::new(core::io::ErrorKind::Other, format!("Mock write error: {}", e))) // Map std io error to core io error
+
+    /// Field value by key (first occurrence).
+    pub fn get(&self, key: &str) -> Option<&FieldValue> {
+        self.fields.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Formats the record as one logfmt line (without the line break).
+    pub fn to_logfmt(&self, time: u64) -> String {
+        let mut line = String::with_capacity(64 + self.message.len());
+        let _ = write!(line, "time={} level={} module=", format_timestamp(time), self.level);
+        push_logfmt_value(&mut line, &self.module);
+        line.push_str(" msg=");
+        push_logfmt_value(&mut line, &self.message);
+        for (key, value) in &self.fields {
+            line.push(' ');
+            line.push_str(key);
+            line.push('=');
+            match value {
+                FieldValue::Str(s) => push_logfmt_value(&mut line, s),
+                other => {
+                    let _ = write!(line, "{}", other);
+                }
+            }
         }
-         fn flush(&mut self) -> Result<(), core::io::Error> {
-             // Flush the inner Cursor (mostly a no-op for Cursor)
-             self.cursor.flush()
-                 .map_err(|e| core::io::Error::new(core::io::ErrorKind::Other, format!("Mock flush error: {}", e))) // Map std io error to core io error
-         }
+        line
     }
-    // Implement core::ops::Drop for the Mock
-    impl Drop for MockSahneResourceWrite {
-        fn drop(&mut self) {
-            println!("MockSahneResourceWrite dropped."); // Indicate drop occurred
+}
+
+// Appends a logfmt value, quoted and escaped when needed.
+fn push_logfmt_value(line: &mut String, value: &str) {
+    let needs_quotes = value.is_empty() || value.chars().any(|c| c == ' ' || c == '=' || c == '"' || c == '\\' || c.is_control());
+    if !needs_quotes {
+        line.push_str(value);
+        return;
+    }
+    line.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => line.push_str("\\\""),
+            '\\' => line.push_str("\\\\"),
+            '\n' => line.push_str("\\n"),
+            '\r' => line.push_str("\\r"),
+            '\t' => line.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(line, "\\u{{{:x}}}", c as u32);
+            }
+            c => line.push(c),
         }
     }
+    line.push('"');
+}
 
+/// Formats Unix time as an RFC 3339 UTC timestamp (e.g. "2024-05-17T12:30:20Z").
+pub fn format_timestamp(unix: u64) -> String {
+    let days = (unix / 86400) as i64;
+    let secs = unix % 86400;
+    // civil_from_days
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, secs / 3600, (secs / 60) % 60, secs % 60)
+}
 
-    #[test]
-    fn test_logger_std_file() -> Result<(), FileSystemError> { // Return FileSystemError
-        let test_file_path = Path::new("test_logger.log");
-        let level = LogLevel::Info;
-        let drive_type = DriveType::SSD;
 
-        // Create logger using the file constructor
-        let logger = Logger::open_file(test_file_path.to_str().unwrap(), level, drive_type)?; // Uses Logger::open_file
+/// Destination of formatted log lines.
+pub trait LogSink: Send {
+    /// Writes one line (without the line break).
+    fn write_line(&mut self, line: &str) -> Result<(), FileSystemError>;
 
+    fn flush(&mut self) -> Result<(), FileSystemError> {
+        Ok(())
+    }
+}
 
-        // Log messages at different levels
-        logger.log(LogLevel::Debug, "This is a debug message (should NOT appear)."); // Below logger level
-        logger.log(LogLevel::Info, "This is an info message (should appear)."); // At logger level
-        logger.log(LogLevel::Warning, "This is a warning message (should appear)."); // Above logger level
-        logger.log(LogLevel::Error, "This is an error message (should appear)."); // Above logger level
+/// Keeps lines in memory; clones share the same buffer.
+#[derive(Clone)]
+pub struct MemorySink {
+    lines: Arc<Mutex<Vec<String>>>,
+}
 
+impl MemorySink {
+    pub fn new() -> Self {
+        MemorySink { lines: Arc::new(Mutex::new(Vec::new())) }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.lock().clone()
+    }
+}
 
-         // The logger should be dropped here, closing the file.
-         drop(logger); // Explicitly drop to ensure file is closed before reading it.
+impl LogSink for MemorySink {
+    fn write_line(&mut self, line: &str) -> Result<(), FileSystemError> {
+        self.lines.lock().push(line.to_string());
+        Ok(())
+    }
+}
 
+/// File operations needed by `RotatingFile`; names are relative to the storage's directory.
+pub trait LogStorage: Send {
+    fn append(&mut self, name: &str, data: &[u8]) -> Result<(), FileSystemError>;
+    /// Current size, `None` if the file does not exist.
+    fn size(&mut self, name: &str) -> Result<Option<u64>, FileSystemError>;
+    /// Renames `from` to `to`, replacing `to`.
+    fn rename(&mut self, from: &str, to: &str) -> Result<(), FileSystemError>;
+    /// Removes `name`; a missing file is not an error.
+    fn remove(&mut self, name: &str) -> Result<(), FileSystemError>;
+    fn sync(&mut self) -> Result<(), FileSystemError>;
+}
 
-        // Read the content of the log file
-         let mut file = OpenOptions::new().read(true).open(test_file_path).map_err(|e| map_std_io_error_to_fs_error_test(e))?;
-         let mut contents = String::new(); // Requires alloc
-         file.read_to_string(&mut contents).map_err(|e| map_std_io_error_to_fs_error_test(e))?;
+/// Log files in a directory of the SADAK VFS namespace.
+pub struct VfsLogStorage {
+    vfs: Arc<Vfs>,
+    dir: String,
+    // Open handle of the file being appended to
+    open: Option<(String, Box<dyn VfsFile>)>,
+}
 
+impl VfsLogStorage {
+    pub fn new(vfs: Arc<Vfs>, dir: &str) -> Self {
+        VfsLogStorage { vfs, dir: dir.trim_end_matches('/').to_string(), open: None }
+    }
 
-        // Assert the content of the log file
-        println!("Log file contents:\n{}", contents); // Print for inspection
+    fn path(&self, name: &str) -> String {
+        format!("{}/{}", self.dir, name)
+    }
+}
 
+impl LogStorage for VfsLogStorage {
+    fn append(&mut self, name: &str, data: &[u8]) -> Result<(), FileSystemError> {
+        if self.open.as_ref().map_or(true, |(open_name, _)| open_name != name) {
+            let file = self.vfs.open(&self.path(name), O_WRONLY | O_CREAT | O_APPEND, 0o640).map_err(map_vfs_error_to_fs_error)?;
+            self.open = Some((name.to_string(), file));
+        }
+        let (_, file) = self.open.as_mut().ok_or_else(|| FileSystemError::IOError(String::from("Log file is not open")))?;
+        let mut done = 0;
+        while done < data.len() {
+            // O_APPEND: the offset is ignored
+            let count = file.write_at(0, &data[done..]).map_err(map_vfs_error_to_fs_error)?;
+            if count == 0 {
+                return Err(map_vfs_error_to_fs_error(VfsError::NoSpace));
+            }
+            done += count;
+        }
+        Ok(())
+    }
 
-        // Check for the presence of expected messages
-        assert!(!contents.contains("This is a debug message")); // Debug message should be filtered out
-        assert!(contents.contains("INFO (SSD) This is an info message."));
-        assert!(contents.contains("WARNING (SSD) This is a warning message."));
-        assert!(contents.contains("ERROR (SSD) This is an error message."));
+    fn size(&mut self, name: &str) -> Result<Option<u64>, FileSystemError> {
+        match self.vfs.metadata(&self.path(name)) {
+            Ok(meta) => Ok(Some(meta.size)),
+            Err(VfsError::NotFound) => Ok(None),
+            Err(e) => Err(map_vfs_error_to_fs_error(e)),
+        }
+    }
 
-         // Check timestamp format (basic check for the placeholder format in std)
-          assert!(contents.contains("YYYY-MM-DD HH:MM:SS") || contents.contains(Local::now().format("%Y-%m-%d %H:%M:%S").to_string().split(" ").next().unwrap())); // Check for either placeholder or actual date start
+    fn rename(&mut self, from: &str, to: &str) -> Result<(), FileSystemError> {
+        self.open = None;
+        self.vfs.rename(&self.path(from), &self.path(to)).map_err(map_vfs_error_to_fs_error)
+    }
 
+    fn remove(&mut self, name: &str) -> Result<(), FileSystemError> {
+        if self.open.as_ref().map_or(false, |(open_name, _)| open_name == name) {
+            self.open = None;
+        }
+        match self.vfs.unlink(&self.path(name)) {
+            Ok(()) | Err(VfsError::NotFound) => Ok(()),
+            Err(e) => Err(map_vfs_error_to_fs_error(e)),
+        }
+    }
 
-        // Clean up the test file
-        remove_file(test_file_path).expect("Test dosyası silinemedi");
+    fn sync(&mut self) -> Result<(), FileSystemError> {
+        match self.open.as_mut() {
+            Some((_, file)) => file.sync().map_err(map_vfs_error_to_fs_error),
+            None => Ok(()),
+        }
+    }
+}
 
+// Helper function to map std::io::Error to FileSystemError
+#[cfg(feature = "std")]
+fn map_std_io_error_to_fs_error(e: std::io::Error) -> FileSystemError {
+    FileSystemError::IOError(format!("IO Error: {}", e))
+}
 
-        Ok(()) // Return Ok from test function
-    }
+/// Log files in a host directory.
+#[cfg(feature = "std")]
+pub struct HostLogStorage {
+    dir: PathBuf,
+    open: Option<(String, std::fs::File)>,
+}
 
-    #[test]
-     fn test_logger_no_std_mock_resource() {
-          let level = LogLevel::Warning;
-          let drive_type = DriveType::HDD;
+#[cfg(feature = "std")]
+impl HostLogStorage {
+    /// Uses `dir`, creating it if needed.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, FileSystemError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(map_std_io_error_to_fs_error)?;
+        Ok(HostLogStorage { dir, open: None })
+    }
+}
 
-          // Create a mock SahneResourceWrite (in-memory)
-          let mock_writer = MockSahneResourceWrite::new(); // Requires alloc
+#[cfg(feature = "std")]
+impl LogStorage for HostLogStorage {
+    fn append(&mut self, name: &str, data: &[u8]) -> Result<(), FileSystemError> {
+        if self.open.as_ref().map_or(true, |(open_name, _)| open_name != name) {
+            let file = std::fs::OpenOptions::new().create(true).append(true).open(self.dir.join(name)).map_err(map_std_io_error_to_fs_error)?;
+            self.open = Some((name.to_string(), file));
+        }
+        match self.open.as_mut() {
+            Some((_, file)) => file.write_all(data).map_err(map_std_io_error_to_fs_error),
+            None => Err(FileSystemError::IOError(String::from("Log file is not open"))),
+        }
+    }
+
+    fn size(&mut self, name: &str) -> Result<Option<u64>, FileSystemError> {
+        match std::fs::metadata(self.dir.join(name)) {
+            Ok(meta) => Ok(Some(meta.len())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(map_std_io_error_to_fs_error(e)),
+        }
+    }
+
+    fn rename(&mut self, from: &str, to: &str) -> Result<(), FileSystemError> {
+        self.open = None;
+        std::fs::rename(self.dir.join(from), self.dir.join(to)).map_err(map_std_io_error_to_fs_error)
+    }
+
+    fn remove(&mut self, name: &str) -> Result<(), FileSystemError> {
+        if self.open.as_ref().map_or(false, |(open_name, _)| open_name == name) {
+            self.open = None;
+        }
+        match std::fs::remove_file(self.dir.join(name)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(map_std_io_error_to_fs_error(e)),
+            _ => Ok(()),
+        }
+    }
+
+    fn sync(&mut self) -> Result<(), FileSystemError> {
+        match self.open.as_mut() {
+            Some((_, file)) => file.sync_data().map_err(map_std_io_error_to_fs_error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// When the active log file is rotated and how many old files are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RotationPolicy {
+    /// Rotate before a line would make the file larger than this.
+    pub max_bytes: Option<u64>,
+    /// Rotate after this many lines in the file.
+    pub max_records: Option<u64>,
+    /// Rotated files kept (`name.1` is the newest); 0 discards the old file.
+    pub keep: usize,
+}
+
+impl Default for RotationPolicy {
+    fn default() -> Self {
+        RotationPolicy { max_bytes: Some(1024 * 1024), max_records: None, keep: 4 }
+    }
+}
+
+/// Log file rotated as `name` -> `name.1` -> ... -> `name.<keep>`.
+pub struct RotatingFile<S: LogStorage> {
+    storage: S,
+    name: String,
+    policy: RotationPolicy,
+    // Size and line count of the active file (None: not looked up yet)
+    size: Option<u64>,
+    records: u64,
+    rotations: u64,
+}
+
+impl<S: LogStorage> RotatingFile<S> {
+    pub fn new(storage: S, name: &str, policy: RotationPolicy) -> Self {
+        RotatingFile { storage, name: name.to_string(), policy, size: None, records: 0, rotations: 0 }
+    }
+
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Number of rotations done by this sink.
+    pub fn rotations(&self) -> u64 {
+        self.rotations
+    }
+
+    fn rotated_name(&self, index: usize) -> String {
+        format!("{}.{}", self.name, index)
+    }
+
+    /// Rotates now, even if the limits are not reached.
+    pub fn rotate(&mut self) -> Result<(), FileSystemError> {
+        self.storage.sync()?;
+        if self.policy.keep == 0 {
+            self.storage.remove(&self.name)?;
+        } else {
+            self.storage.remove(&self.rotated_name(self.policy.keep))?;
+            for index in (1..self.policy.keep).rev() {
+                if self.storage.size(&self.rotated_name(index))?.is_some() {
+                    self.storage.rename(&self.rotated_name(index), &self.rotated_name(index + 1))?;
+                }
+            }
+            if self.storage.size(&self.name)?.is_some() {
+                self.storage.rename(&self.name, &self.rotated_name(1))?;
+            }
+        }
+        self.size = Some(0);
+        self.records = 0;
+        self.rotations += 1;
+        Ok(())
+    }
+}
+
+impl<S: LogStorage> LogSink for RotatingFile<S> {
+    fn write_line(&mut self, line: &str) -> Result<(), FileSystemError> {
+        let mut size = match self.size {
+            Some(size) => size,
+            None => {
+                // An existing file counts towards the size limit; its lines are not counted
+                let size = self.storage.size(&self.name)?.unwrap_or(0);
+                self.size = Some(size);
+                size
+            }
+        };
+        let len = line.len() as u64 + 1;
+        let too_big = self.policy.max_bytes.map_or(false, |max| size > 0 && size + len > max);
+        let too_many = self.policy.max_records.map_or(false, |max| self.records >= max);
+        if too_big || too_many {
+            self.rotate()?;
+            size = 0;
+        }
+        let mut data = Vec::with_capacity(line.len() + 1);
+        data.extend_from_slice(line.as_bytes());
+        data.push(b'\n');
+        // On failure the size is looked up again before the next line
+        self.size = None;
+        self.storage.append(&self.name, &data)?;
+        self.size = Some(size + len);
+        self.records += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), FileSystemError> {
+        self.storage.sync()
+    }
+}
+
+// Last records kept in memory for crash dumps.
+struct RingBuffer {
+    lines: VecDeque<String>,
+    capacity: usize,
+    level: Option<LogLevel>,
+}
+
+impl RingBuffer {
+    fn push(&mut self, line: String) -> bool {
+        if self.capacity == 0 {
+            return false;
+        }
+        let overwritten = self.lines.len() == self.capacity;
+        if overwritten {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+        overwritten
+    }
+}
+
+/// Logger counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LogStats {
+    /// Records that passed the module filter.
+    pub records: u64,
+    /// Records rejected by the module filter.
+    pub filtered: u64,
+    /// Failed sink writes (each sink counted separately).
+    pub write_errors: u64,
+    /// Ring buffer records overwritten by newer ones.
+    pub ring_overwritten: u64,
+}
+
+/// Structured logger with runtime module filters, any number of sinks and a ring buffer.
+pub struct Logger {
+    filter: Mutex<LogFilter>,
+    sinks: Mutex<Vec<Box<dyn LogSink>>>,
+    ring: Mutex<RingBuffer>,
+    clock: AtomicU64,
+    records: AtomicU64,
+    filtered: AtomicU64,
+    write_errors: AtomicU64,
+    ring_overwritten: AtomicU64,
+}
+
+impl Logger {
+    /// Creates a logger without sinks; the ring buffer keeps the last
+    /// `DEFAULT_RING_CAPACITY` records of level Debug and above.
+    pub fn new(filter: LogFilter) -> Self {
+        Logger {
+            filter: Mutex::new(filter),
+            sinks: Mutex::new(Vec::new()),
+            ring: Mutex::new(RingBuffer { lines: VecDeque::new(), capacity: DEFAULT_RING_CAPACITY, level: Some(LogLevel::Debug) }),
+            clock: AtomicU64::new(0),
+            records: AtomicU64::new(0),
+            filtered: AtomicU64::new(0),
+            write_errors: AtomicU64::new(0),
+            ring_overwritten: AtomicU64::new(0),
+        }
+    }
+
+    /// Builds a logger from the `[log]` configuration section: module filter, ring buffer and,
+    /// when `file` is set, a rotating log file in `vfs` (its directory must exist).
+    pub fn from_config(config: &LogConfig, vfs: &Arc<Vfs>) -> Result<Self, FileSystemError> {
+        let logger = Logger::new(config.filter.clone());
+        logger.set_ring_buffer(config.ring_capacity, config.ring_level);
+        if let Some(file) = &config.file {
+            let (dir, name) = config
+                .file_location()
+                .ok_or_else(|| FileSystemError::InvalidData(format!("Invalid log file path: {}", file)))?;
+            let storage = VfsLogStorage::new(vfs.clone(), &dir);
+            logger.add_sink(Box::new(RotatingFile::new(storage, &name, config.rotation)));
+        }
+        Ok(logger)
+    }
+
+    pub fn add_sink(&self, sink: Box<dyn LogSink>) {
+        self.sinks.lock().push(sink);
+    }
 
-          // Create logger using the generic constructor with the mock writer
-          let logger = Logger::new(mock_writer, level, drive_type);
+    /// Sets the ring buffer size (0 disables it) and the lowest level it keeps. The ring buffer
+    /// does not use the module filter, so it can keep debug records that are not written.
+    pub fn set_ring_buffer(&self, capacity: usize, level: Option<LogLevel>) {
+        let mut ring = self.ring.lock();
+        while ring.lines.len() > capacity {
+            ring.lines.pop_front();
+        }
+        ring.capacity = capacity;
+        ring.level = level;
+    }
+
+    /// Sets the time (Unix seconds) put on records. With `std` the system clock is used
+    /// until a time is set.
+    pub fn set_time(&self, now: u64) {
+        self.clock.store(now, Ordering::Relaxed);
+    }
+
+    fn now(&self) -> u64 {
+        let now = self.clock.load(Ordering::Relaxed);
+        #[cfg(feature = "std")]
+        if now == 0 {
+            return std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        }
+        now
+    }
+
+    pub fn filter(&self) -> LogFilter {
+        self.filter.lock().clone()
+    }
+
+    pub fn set_filter(&self, filter: LogFilter) {
+        *self.filter.lock() = filter;
+    }
 
-          // Log messages at different levels
-          logger.log(LogLevel::Info, "This is an info message (should NOT appear)."); // Below logger level
-          logger.log(LogLevel::Warning, "This is a warning message (should appear)."); // At logger level
-          logger.log(LogLevel::Error, "This is an error message (should appear)."); // Above logger level
+    /// Changes one module's level while the logger is running.
+    pub fn set_module_level(&self, module: &str, level: Option<LogLevel>) {
+        self.filter.lock().set_module_level(module, level);
+    }
+
+    pub fn set_default_level(&self, level: Option<LogLevel>) {
+        self.filter.lock().set_default_level(level);
+    }
+
+    /// Would a record of this level and module be written or kept?
+    pub fn enabled(&self, level: LogLevel, module: &str) -> bool {
+        self.ring.lock().level.map_or(false, |min| level >= min) || self.filter.lock().enabled(level, module)
+    }
+
+    /// Logs a record. It is kept in the ring buffer even if writing fails; the first sink
+    /// error is returned after all sinks have been tried.
+    pub fn log(&self, record: &Record) -> Result<(), FileSystemError> {
+        let to_sinks = self.filter.lock().enabled(record.level, &record.module);
+        let to_ring = self.ring.lock().level.map_or(false, |min| record.level >= min);
+        if !to_sinks {
+            self.filtered.fetch_add(1, Ordering::Relaxed);
+        }
+        if !to_sinks && !to_ring {
+            return Ok(());
+        }
+        let line = record.to_logfmt(self.now());
+        if to_ring && self.ring.lock().push(line.clone()) {
+            self.ring_overwritten.fetch_add(1, Ordering::Relaxed);
+        }
+        if !to_sinks {
+            return Ok(());
+        }
+        self.records.fetch_add(1, Ordering::Relaxed);
+        let mut first_error = None;
+        for sink in self.sinks.lock().iter_mut() {
+            if let Err(e) = sink.write_line(&line) {
+                self.write_errors.fetch_add(1, Ordering::Relaxed);
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    pub fn debug(&self, module: &str, message: &str) -> Result<(), FileSystemError> {
+        self.log(&Record::new(LogLevel::Debug, module, message))
+    }
 
+    pub fn info(&self, module: &str, message: &str) -> Result<(), FileSystemError> {
+        self.log(&Record::new(LogLevel::Info, module, message))
+    }
 
-          // Get the content from the mock writer after logging
-          // We need to access the inner MockSahneResourceWrite from the Logger.
-          // This requires the Mock to be accessible or the Logger to provide a debug method.
-          // Let's capture the MockSahneResourceWrite before passing it, or make the Mutex public for testing.
-          // Making the Mutex public is acceptable for testing.
+    pub fn warning(&self, module: &str, message: &str) -> Result<(), FileSystemError> {
+        self.log(&Record::new(LogLevel::Warning, module, message))
+    }
 
-           // Re-create logger and access the mock writer content
-           let mock_writer_captured = MockSahneResourceWrite::new(); // Requires alloc
-           let logger_test = Logger::new(mock_writer_captured, level, drive_type);
+    pub fn error(&self, module: &str, message: &str) -> Result<(), FileSystemError> {
+        self.log(&Record::new(LogLevel::Error, module, message))
+    }
 
-           logger_test.log(LogLevel::Info, "This is an info message (should NOT appear)."); // Below logger level
-           logger_test.log(LogLevel::Warning, "This is a warning message (should appear)."); // At logger level
-           logger_test.log(LogLevel::Error, "This is an error message (should appear)."); // Above logger level
+    /// Flushes every sink; returns the first error.
+    pub fn flush(&self) -> Result<(), FileSystemError> {
+        let mut first_error = None;
+        for sink in self.sinks.lock().iter_mut() {
+            if let Err(e) = sink.flush() {
+                self.write_errors.fetch_add(1, Ordering::Relaxed);
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
 
-           // Get the content from the mock writer via the logger's mutex
-           let mut file_lock = logger_test.file.lock();
-           let mock_writer_content = file_lock.get_content(); // Access helper on the mock writer
+    pub fn stats(&self) -> LogStats {
+        LogStats {
+            records: self.records.load(Ordering::Relaxed),
+            filtered: self.filtered.load(Ordering::Relaxed),
+            write_errors: self.write_errors.load(Ordering::Relaxed),
+            ring_overwritten: self.ring_overwritten.load(Ordering::Relaxed),
+        }
+    }
 
-           let contents = String::from_utf8_lossy(mock_writer_content); // Convert bytes to String for assertion
+    /// Ring buffer contents, oldest first.
+    pub fn recent(&self) -> Vec<String> {
+        self.ring.lock().lines.iter().cloned().collect()
+    }
 
-           println!("Mock log content:\n{}", contents); // Print for inspection
+    /// Ring buffer contents as text for a crash dump.
+    pub fn crash_dump(&self) -> String {
+        let ring = self.ring.lock();
+        let mut dump = String::new();
+        for line in ring.lines.iter() {
+            dump.push_str(line);
+            dump.push('\n');
+        }
+        dump
+    }
 
+    /// Writes the ring buffer to `sink` (e.g. a dump file opened after a failure).
+    pub fn dump_ring(&self, sink: &mut dyn LogSink) -> Result<usize, FileSystemError> {
+        let lines = self.recent();
+        for line in &lines {
+            sink.write_line(line)?;
+        }
+        sink.flush()?;
+        Ok(lines.len())
+    }
+}
 
-           // Assert the content of the mock log
-           assert!(!contents.contains("This is an info message")); // Info message should be filtered out
-           assert!(contents.contains("WARNING (HDD) This is a warning message."));
-           assert!(contents.contains("ERROR (HDD) This is an error message."));
 
-           // Check timestamp placeholder in no_std test
-            assert!(contents.contains("YYYY-MM-DD HH:MM:SS"));
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+    use crate::vfs::MemFs;
 
+    #[test]
+    fn test_filter_spec_and_module_levels() -> Result<(), FileSystemError> {
+        let filter = LogFilter::parse("warn, vfs=debug, raid::rebuild=info, archivefs=off")?;
+        assert_eq!(filter.level_for("journal"), Some(LogLevel::Warning));
+        assert_eq!(filter.level_for("vfs::memfs"), Some(LogLevel::Debug));
+        assert_eq!(filter.level_for("vfsx"), Some(LogLevel::Warning)); // Not a submodule of "vfs"
+        assert_eq!(filter.level_for("raid::rebuild::stripe"), Some(LogLevel::Info));
+        assert!(filter.enabled(LogLevel::Info, "raid::rebuild"));
+        assert!(!filter.enabled(LogLevel::Info, "raid"));
+        assert!(!filter.enabled(LogLevel::Error, "archivefs"));
+        assert_eq!(filter.to_string(), "warning,vfs=debug,raid::rebuild=info,archivefs=off");
+        assert_eq!(LogFilter::parse(&filter.to_string())?, filter);
+        assert!(LogFilter::parse("vfs=loud").is_err());
+
+        let sink = MemorySink::new();
+        let logger = Logger::new(filter);
+        logger.add_sink(Box::new(sink.clone()));
+        logger.set_ring_buffer(0, None);
+        logger.info("raid", "resync started")?;
+        logger.set_module_level("raid", Some(LogLevel::Debug));
+        logger.info("raid", "resync finished")?;
+        logger.set_default_level(None);
+        logger.error("journal", "dropped")?;
+        assert_eq!(sink.lines().len(), 1);
+        assert!(sink.lines()[0].ends_with("module=raid msg=\"resync finished\""));
+        assert_eq!((logger.stats().records, logger.stats().filtered), (1, 2));
+        Ok(())
+    }
 
-          // Drop the logger explicitly
-          drop(logger_test);
-     }
+    #[test]
+    fn test_structured_fields_and_ring_buffer() -> Result<(), FileSystemError> {
+        let logger = Logger::new(LogFilter::new(Some(LogLevel::Info)));
+        let sink = MemorySink::new();
+        logger.add_sink(Box::new(sink.clone()));
+        logger.set_time(1715949020);
+        logger.set_ring_buffer(3, Some(LogLevel::Debug));
+
+        let record = Record::new(LogLevel::Warning, "journal", "replay cut short")
+            .device(&DriveType::NVMe)
+            .inode(12)
+            .block(88)
+            .op("write")
+            .field("path", "/ev/not \"1\".txt")
+            .field("retry ok", true);
+        assert_eq!(record.get(FIELD_BLOCK), Some(&FieldValue::U64(88)));
+        logger.log(&record)?;
+        assert_eq!(
+            sink.lines(),
+            vec![String::from(
+                "time=2024-05-17T12:30:20Z level=warning module=journal msg=\"replay cut short\" device=NVMe inode=12 block=88 op=write path=\"/ev/not \\\"1\\\".txt\" retry_ok=true"
+            )]
+        );
+
+        // Debug records are not written but kept for a crash dump
+        for i in 0..3u64 {
+            logger.log(&Record::new(LogLevel::Debug, "alloc", "extent").block(i))?;
+        }
+        assert_eq!(sink.lines().len(), 1);
+        let recent = logger.recent();
+        assert_eq!(recent.len(), 3);
+        assert!(recent[0].ends_with("block=0") && recent[2].ends_with("block=2"));
+        assert_eq!(logger.stats().ring_overwritten, 1);
+        assert_eq!(logger.crash_dump().lines().count(), 3);
+
+        let mut dump = MemorySink::new();
+        assert_eq!(logger.dump_ring(&mut dump)?, 3);
+        assert_eq!(dump.lines(), recent);
+        assert_eq!(format_timestamp(951782400), "2000-02-29T00:00:00Z");
+        Ok(())
+    }
 
+    #[test]
+    fn test_rotation_on_vfs_and_write_errors() -> Result<(), FileSystemError> {
+        let vfs = Arc::new(Vfs::new());
+        let fs = Arc::new(MemFs::new());
+        vfs.mount("/", fs.clone()).map_err(map_vfs_error_to_fs_error)?;
+        vfs.mkdir("/log", 0o755).map_err(map_vfs_error_to_fs_error)?;
+
+        let logger = Logger::new(LogFilter::parse("info")?);
+        logger.set_time(1715949020);
+        let policy = RotationPolicy { max_bytes: Some(200), max_records: None, keep: 2 };
+        logger.add_sink(Box::new(RotatingFile::new(VfsLogStorage::new(vfs.clone(), "/log"), "sadak.log", policy)));
+        for i in 0..12u64 {
+            logger.log(&Record::new(LogLevel::Info, "mount", "mounted").device("disk0").inode(i))?;
+        }
+        logger.flush()?;
+        let names: Vec<String> = vfs.read_dir("/log").map_err(map_vfs_error_to_fs_error)?.map(|e| e.name).collect();
+        assert_eq!(names, ["sadak.log", "sadak.log.1", "sadak.log.2"]);
+        for name in &names {
+            let size = vfs.metadata(&format!("/log/{}", name)).map_err(map_vfs_error_to_fs_error)?.size;
+            assert!(size > 0 && size <= 200);
+        }
+        let mut last = vec![0u8; 200];
+        let mut file = vfs.open("/log/sadak.log", 0, 0).map_err(map_vfs_error_to_fs_error)?;
+        let len = file.read_at(0, &mut last).map_err(map_vfs_error_to_fs_error)?;
+        let text = String::from_utf8_lossy(&last[..len]).into_owned();
+        assert!(text.ends_with("device=disk0 inode=11\n"));
+
+        // Count-based rotation with no old files kept
+        let mut counted = RotatingFile::new(
+            VfsLogStorage::new(vfs.clone(), "/log"),
+            "sayac.log",
+            RotationPolicy { max_bytes: None, max_records: Some(2), keep: 0 },
+        );
+        for line in ["bir", "iki", "uc", "dort", "bes"] {
+            counted.write_line(line)?;
+        }
+        assert_eq!(counted.rotations(), 2);
+        assert_eq!(vfs.metadata("/log/sayac.log").map_err(map_vfs_error_to_fs_error)?.size, 4);
+        assert!(vfs.metadata("/log/sayac.log.1").is_err());
+
+        // Write errors are reported, counted and the record stays in the ring buffer
+        fs.set_read_only(true);
+        let logger = Logger::new(LogFilter::parse("info")?);
+        logger.add_sink(Box::new(RotatingFile::new(VfsLogStorage::new(vfs.clone(), "/log"), "yeni.log", RotationPolicy::default())));
+        assert!(logger.error("mount", "read-only").is_err());
+        assert_eq!(logger.stats().write_errors, 1);
+        assert_eq!(logger.recent().len(), 1);
+        Ok(())
+    }
 
-    // TODO: Add tests for error handling in open_file/open_resource.
-    // TODO: Add test for concurrent logging (requires multiple threads).
+    #[test]
+    fn test_host_file_rotation() -> Result<(), FileSystemError> {
+        let dir = std::env::temp_dir().join(format!("sadak-log-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let policy = RotationPolicy { max_bytes: Some(64), max_records: None, keep: 1 };
+        let mut file = RotatingFile::new(HostLogStorage::new(&dir)?, "host.log", policy);
+        for i in 0..6 {
+            file.write_line(&format!("satir {} {}", i, "x".repeat(20)))?;
+        }
+        file.flush()?;
+        let current = std::fs::read_to_string(dir.join("host.log")).map_err(map_std_io_error_to_fs_error)?;
+        let rotated = std::fs::read_to_string(dir.join("host.log.1")).map_err(map_std_io_error_to_fs_error)?;
+        assert!(current.starts_with("satir 4") && rotated.starts_with("satir 2"));
+        assert!(!dir.join("host.log.2").exists());
+        std::fs::remove_dir_all(&dir).map_err(map_std_io_error_to_fs_error)?;
+        Ok(())
+    }
 }
 
 
@@ -0,0 +1,945 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// Bölüm tabloları: GPT ve eski (legacy) MBR.
+//
+// - GPT: LBA 0'da koruyucu (protective) MBR, LBA 1'de birincil başlık ve ardından 128 girişlik
+//   bölüm dizisi; diskin sonunda yedek dizi ve yedek başlık. Başlık ve dizi CRC32 ile korunur;
+//   birincil kopya bozuksa yedekten okunur ve `Gpt::write` ile iki kopya da onarılır.
+// - MBR: dört birincil giriş; genişletilmiş (extended) bölümdeki mantıksal bölümler EBR
+//   zinciri izlenerek bulunur.
+// - `PartitionBlockDevice` bir bölümü, blok 0'ı bölümün ilk bloğu olan ayrı bir blok aygıtı
+//   olarak sunar; Superblock::load_from_device gibi blok 0 varsayan kod onun üzerinde çalışır.
+// - `create_sadak_partition` biçimlendirici için SADAK tür GUID'li bir bölüm oluşturur.
+
+use crate::FileSystemError; // Assuming FileSystemError is in crate
+use crate::blockdevice::{BlockDevice, BlockDeviceError}; // Assuming these are in crate::blockdevice
+use crate::crypto::Crc32;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::format;
+
+use core::fmt;
+use core::result::Result;
+
+/// MBR imzası (bayt 510-511).
+pub const MBR_SIGNATURE: [u8; 2] = [0x55, 0xAA];
+pub const MBR_SIZE: usize = 512;
+/// Koruyucu MBR bölüm türü (GPT diski).
+pub const MBR_TYPE_GPT_PROTECTIVE: u8 = 0xEE;
+/// SADAK bölümleri için MBR bölüm türü.
+pub const MBR_TYPE_SADAK: u8 = 0x5A;
+const MBR_EXTENDED_TYPES: [u8; 3] = [0x05, 0x0F, 0x85];
+// Bozuk/döngüsel EBR zincirlerine karşı üst sınır
+const MBR_MAX_LOGICAL_PARTITIONS: usize = 128;
+
+pub const GPT_SIGNATURE: &[u8; 8] = b"EFI PART";
+pub const GPT_REVISION: u32 = 0x0001_0000;
+pub const GPT_HEADER_SIZE: usize = 92;
+pub const GPT_ENTRY_SIZE: usize = 128;
+pub const GPT_ENTRY_COUNT: usize = 128;
+// Diskten okunan başlıkta kabul edilen sınırlar (dizi en fazla 4 MiB)
+const GPT_MAX_ENTRY_COUNT: u32 = 1024;
+const GPT_MAX_ENTRY_SIZE: u32 = 4096;
+const GPT_NAME_UNITS: usize = 36; // UTF-16LE karakter sayısı
+/// Yeni bölümlerin hizalandığı sınır (bayt).
+pub const PARTITION_ALIGNMENT_BYTES: u64 = 1024 * 1024;
+
+/// 128 bitlik GUID; disk üzerinde karışık endian (ilk üç alan little-endian) tutulur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Guid(pub [u8; 16]);
+
+impl Guid {
+    pub const NIL: Guid = Guid([0u8; 16]);
+
+    /// "XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX" biçiminden GUID üretir.
+    pub fn parse(text: &str) -> Option<Guid> {
+        let parts: Vec<&str> = text.split('-').collect();
+        if parts.len() != 5 || parts.iter().map(|p| p.len()).ne([8, 4, 4, 4, 12].iter().copied()) {
+            return None;
+        }
+        let mut bytes = [0u8; 16];
+        let hex: String = parts.concat();
+        for i in 0..16 {
+            bytes[i] = u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+        }
+        // İlk üç alan disk üzerinde little-endian
+        bytes[0..4].reverse();
+        bytes[4..6].reverse();
+        bytes[6..8].reverse();
+        Some(Guid(bytes))
+    }
+
+    pub fn is_nil(&self) -> bool {
+        self.0 == [0u8; 16]
+    }
+}
+
+impl fmt::Display for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let b = &self.0;
+        write!(
+            f,
+            "{:02X}{:02X}{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
+            b[3], b[2], b[1], b[0], b[5], b[4], b[7], b[6], b[8], b[9], b[10], b[11], b[12], b[13], b[14], b[15]
+        )
+    }
+}
+
+/// SADAK dosya sistemi bölüm türü: 5ADA4B64-4B61-726E-616C-534144414B46.
+pub const GUID_SADAK: Guid = Guid([
+    0x64, 0x4B, 0xDA, 0x5A, 0x61, 0x4B, 0x6E, 0x72, 0x61, 0x6C, 0x53, 0x41, 0x44, 0x41, 0x4B, 0x46,
+]);
+/// EFI sistem bölümü: C12A7328-F81F-11D2-BA4B-00A0C93EC93B.
+pub const GUID_EFI_SYSTEM: Guid = Guid([
+    0x28, 0x73, 0x2A, 0xC1, 0x1F, 0xF8, 0xD2, 0x11, 0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E, 0xC9, 0x3B,
+]);
+
+/// Bölüm tablosu hataları.
+#[derive(Debug)]
+pub enum PartitionError {
+    /// Alttaki aygıt hatası.
+    Device(BlockDeviceError),
+    /// LBA 0'da geçerli bir MBR yok.
+    NoPartitionTable,
+    /// GPT'nin iki kopyası da geçersiz.
+    InvalidGpt(String),
+    /// Yeni bölüm için yeterli bitişik boş alan yok.
+    NoSpace,
+    /// Bölüm dizisinde boş giriş kalmadı.
+    TableFull,
+    /// İstenen bölüm bulunamadı.
+    NotFound,
+    /// Geçersiz argüman (blok boyutu, aralık...).
+    InvalidParameter(String),
+}
+
+impl fmt::Display for PartitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PartitionError::Device(e) => write!(f, "Bölüm tablosu aygıt hatası: {:?}", e),
+            PartitionError::NoPartitionTable => write!(f, "Geçerli bir bölüm tablosu bulunamadı"),
+            PartitionError::InvalidGpt(msg) => write!(f, "Geçersiz GPT: {}", msg),
+            PartitionError::NoSpace => write!(f, "Yeni bölüm için yeterli boş alan yok"),
+            PartitionError::TableFull => write!(f, "Bölüm tablosu dolu"),
+            PartitionError::NotFound => write!(f, "Bölüm bulunamadı"),
+            PartitionError::InvalidParameter(msg) => write!(f, "Geçersiz parametre: {}", msg),
+        }
+    }
+}
+
+impl From<BlockDeviceError> for PartitionError {
+    fn from(e: BlockDeviceError) -> Self {
+        PartitionError::Device(e)
+    }
+}
+
+/// PartitionError'ı FileSystemError'a çevirir.
+pub fn map_partition_error_to_fs_error(e: PartitionError) -> FileSystemError {
+    match e {
+        PartitionError::Device(e) => FileSystemError::IOError(format!("Block device error: {:?}", e)),
+        other => FileSystemError::SuperblockError(format!("{}", other)),
+    }
+}
+
+/// Bir MBR bölüm girişi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MbrEntry {
+    pub bootable: bool,
+    pub partition_type: u8,
+    pub start_lba: u32,
+    pub sector_count: u32,
+}
+
+impl MbrEntry {
+    fn parse(raw: &[u8]) -> Self {
+        MbrEntry {
+            bootable: raw[0] == 0x80,
+            partition_type: raw[4],
+            start_lba: u32::from_le_bytes([raw[8], raw[9], raw[10], raw[11]]),
+            sector_count: u32::from_le_bytes([raw[12], raw[13], raw[14], raw[15]]),
+        }
+    }
+
+    fn write(&self, raw: &mut [u8]) {
+        raw[0] = if self.bootable { 0x80 } else { 0x00 };
+        // CHS alanları LBA kullanan sistemlerde anlamsız; "LBA'ya bak" değerleri yazılır
+        raw[1..4].copy_from_slice(&[0xFE, 0xFF, 0xFF]);
+        raw[4] = self.partition_type;
+        raw[5..8].copy_from_slice(&[0xFE, 0xFF, 0xFF]);
+        raw[8..12].copy_from_slice(&self.start_lba.to_le_bytes());
+        raw[12..16].copy_from_slice(&self.sector_count.to_le_bytes());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.partition_type == 0 || self.sector_count == 0
+    }
+
+    pub fn is_extended(&self) -> bool {
+        MBR_EXTENDED_TYPES.contains(&self.partition_type)
+    }
+}
+
+/// Ana Önyükleme Kaydı (LBA 0).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mbr {
+    pub disk_signature: u32,
+    pub entries: [MbrEntry; 4],
+}
+
+impl Mbr {
+    /// 512 baytlık MBR'yi çözer; 0x55AA imzası yoksa None döner.
+    pub fn parse(raw: &[u8]) -> Option<Self> {
+        if raw.len() < MBR_SIZE || raw[510..512] != MBR_SIGNATURE {
+            return None;
+        }
+        let mut entries = [MbrEntry::default(); 4];
+        for (i, entry) in entries.iter_mut().enumerate() {
+            *entry = MbrEntry::parse(&raw[446 + i * 16..462 + i * 16]);
+        }
+        Some(Mbr { disk_signature: u32::from_le_bytes([raw[440], raw[441], raw[442], raw[443]]), entries })
+    }
+
+    /// MBR'yi 512 baytlık bir tampona yazar (önyükleme kodu alanı sıfır kalır).
+    pub fn to_bytes(&self) -> [u8; MBR_SIZE] {
+        let mut raw = [0u8; MBR_SIZE];
+        raw[440..444].copy_from_slice(&self.disk_signature.to_le_bytes());
+        for (i, entry) in self.entries.iter().enumerate() {
+            entry.write(&mut raw[446 + i * 16..462 + i * 16]);
+        }
+        raw[510..512].copy_from_slice(&MBR_SIGNATURE);
+        raw
+    }
+
+    /// GPT diskleri için tüm diski kaplayan tek 0xEE girişli koruyucu MBR.
+    pub fn protective(block_count: u64) -> Self {
+        let mut entries = [MbrEntry::default(); 4];
+        entries[0] = MbrEntry {
+            bootable: false,
+            partition_type: MBR_TYPE_GPT_PROTECTIVE,
+            start_lba: 1,
+            sector_count: core::cmp::min(block_count - 1, u32::MAX as u64) as u32,
+        };
+        Mbr { disk_signature: 0, entries }
+    }
+
+    /// Diskin GPT kullandığını belirten koruyucu girişi var mı?
+    pub fn is_protective(&self) -> bool {
+        self.entries.iter().any(|e| e.partition_type == MBR_TYPE_GPT_PROTECTIVE)
+    }
+}
+
+/// Bir GPT bölüm girişi.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GptEntry {
+    pub type_guid: Guid,
+    pub unique_guid: Guid,
+    pub first_lba: u64,
+    pub last_lba: u64, // Dahil
+    pub attributes: u64,
+    pub name: String,
+}
+
+impl GptEntry {
+    fn empty() -> Self {
+        GptEntry { type_guid: Guid::NIL, unique_guid: Guid::NIL, first_lba: 0, last_lba: 0, attributes: 0, name: String::new() }
+    }
+
+    pub fn is_used(&self) -> bool {
+        !self.type_guid.is_nil()
+    }
+
+    pub fn block_count(&self) -> u64 {
+        self.last_lba - self.first_lba + 1
+    }
+
+    fn parse(raw: &[u8]) -> Self {
+        let mut type_guid = [0u8; 16];
+        type_guid.copy_from_slice(&raw[0..16]);
+        let mut unique_guid = [0u8; 16];
+        unique_guid.copy_from_slice(&raw[16..32]);
+        let le64 = |offset: usize| {
+            let mut v = [0u8; 8];
+            v.copy_from_slice(&raw[offset..offset + 8]);
+            u64::from_le_bytes(v)
+        };
+        let units: Vec<u16> = raw[56..56 + GPT_NAME_UNITS * 2]
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|u| *u != 0)
+            .collect();
+        GptEntry {
+            type_guid: Guid(type_guid),
+            unique_guid: Guid(unique_guid),
+            first_lba: le64(32),
+            last_lba: le64(40),
+            attributes: le64(48),
+            name: String::from_utf16_lossy(&units),
+        }
+    }
+
+    fn write(&self, raw: &mut [u8]) {
+        raw[0..16].copy_from_slice(&self.type_guid.0);
+        raw[16..32].copy_from_slice(&self.unique_guid.0);
+        raw[32..40].copy_from_slice(&self.first_lba.to_le_bytes());
+        raw[40..48].copy_from_slice(&self.last_lba.to_le_bytes());
+        raw[48..56].copy_from_slice(&self.attributes.to_le_bytes());
+        for (i, unit) in self.name.encode_utf16().take(GPT_NAME_UNITS).enumerate() {
+            raw[56 + i * 2..58 + i * 2].copy_from_slice(&unit.to_le_bytes());
+        }
+    }
+}
+
+// Disk üzerindeki GPT başlığı.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GptHeader {
+    my_lba: u64,
+    alternate_lba: u64,
+    first_usable_lba: u64,
+    last_usable_lba: u64,
+    disk_guid: Guid,
+    entries_lba: u64,
+    entry_count: u32,
+    entry_size: u32,
+    entries_crc: u32,
+}
+
+impl GptHeader {
+    fn parse(raw: &[u8]) -> Result<Self, String> {
+        if raw.len() < GPT_HEADER_SIZE || &raw[0..8] != GPT_SIGNATURE {
+            return Err(String::from("imza yok"));
+        }
+        let header_size = u32::from_le_bytes([raw[12], raw[13], raw[14], raw[15]]) as usize;
+        if header_size < GPT_HEADER_SIZE || header_size > raw.len() {
+            return Err(format!("başlık boyutu {}", header_size));
+        }
+        let stored_crc = u32::from_le_bytes([raw[16], raw[17], raw[18], raw[19]]);
+        let mut copy = raw[..header_size].to_vec();
+        copy[16..20].fill(0);
+        if Crc32::checksum(&copy) != stored_crc {
+            return Err(String::from("başlık CRC32 uyuşmuyor"));
+        }
+        let le64 = |offset: usize| {
+            let mut v = [0u8; 8];
+            v.copy_from_slice(&raw[offset..offset + 8]);
+            u64::from_le_bytes(v)
+        };
+        let mut disk_guid = [0u8; 16];
+        disk_guid.copy_from_slice(&raw[56..72]);
+        let header = GptHeader {
+            my_lba: le64(24),
+            alternate_lba: le64(32),
+            first_usable_lba: le64(40),
+            last_usable_lba: le64(48),
+            disk_guid: Guid(disk_guid),
+            entries_lba: le64(72),
+            entry_count: u32::from_le_bytes([raw[80], raw[81], raw[82], raw[83]]),
+            entry_size: u32::from_le_bytes([raw[84], raw[85], raw[86], raw[87]]),
+            entries_crc: u32::from_le_bytes([raw[88], raw[89], raw[90], raw[91]]),
+        };
+        // UEFI: giriş boyutu 128 * 2^n
+        let size_ok = header.entry_size >= GPT_ENTRY_SIZE as u32 && header.entry_size <= GPT_MAX_ENTRY_SIZE && header.entry_size.is_power_of_two();
+        if !size_ok || header.entry_count == 0 || header.entry_count > GPT_MAX_ENTRY_COUNT {
+            return Err(format!("giriş boyutu/sayısı {}/{}", header.entry_size, header.entry_count));
+        }
+        if header.first_usable_lba > header.last_usable_lba {
+            return Err(String::from("kullanılabilir alan boş"));
+        }
+        Ok(header)
+    }
+
+    fn to_bytes(&self, block_size: usize) -> Vec<u8> {
+        let mut raw = vec![0u8; block_size]; // Requires alloc
+        raw[0..8].copy_from_slice(GPT_SIGNATURE);
+        raw[8..12].copy_from_slice(&GPT_REVISION.to_le_bytes());
+        raw[12..16].copy_from_slice(&(GPT_HEADER_SIZE as u32).to_le_bytes());
+        raw[24..32].copy_from_slice(&self.my_lba.to_le_bytes());
+        raw[32..40].copy_from_slice(&self.alternate_lba.to_le_bytes());
+        raw[40..48].copy_from_slice(&self.first_usable_lba.to_le_bytes());
+        raw[48..56].copy_from_slice(&self.last_usable_lba.to_le_bytes());
+        raw[56..72].copy_from_slice(&self.disk_guid.0);
+        raw[72..80].copy_from_slice(&self.entries_lba.to_le_bytes());
+        raw[80..84].copy_from_slice(&self.entry_count.to_le_bytes());
+        raw[84..88].copy_from_slice(&self.entry_size.to_le_bytes());
+        raw[88..92].copy_from_slice(&self.entries_crc.to_le_bytes());
+        let crc = Crc32::checksum(&raw[..GPT_HEADER_SIZE]);
+        raw[16..20].copy_from_slice(&crc.to_le_bytes());
+        raw
+    }
+}
+
+/// GUID Bölüm Tablosu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gpt {
+    pub disk_guid: Guid,
+    pub first_usable_lba: u64,
+    pub last_usable_lba: u64,
+    /// Tüm giriş yuvaları (kullanılmayanların tür GUID'i sıfır).
+    pub entries: Vec<GptEntry>,
+    /// Okuma sırasında birincil kopya geçerli miydi?
+    pub primary_valid: bool,
+    /// Okuma sırasında yedek kopya geçerli miydi?
+    pub backup_valid: bool,
+}
+
+// Bölüm dizisinin kapladığı blok sayısı
+fn entry_array_blocks(entry_count: usize, entry_size: usize, block_size: usize) -> u64 {
+    ((entry_count * entry_size + block_size - 1) / block_size) as u64
+}
+
+fn read_blocks(device: &mut impl BlockDevice, lba: u64, count: u64) -> Result<Vec<u8>, PartitionError> {
+    let block_size = device.block_size();
+    let mut data = vec![0u8; block_size * count as usize]; // Requires alloc
+    for (i, chunk) in data.chunks_mut(block_size).enumerate() {
+        device.read_block(lba + i as u64, chunk)?;
+    }
+    Ok(data)
+}
+
+fn write_blocks(device: &mut impl BlockDevice, lba: u64, data: &[u8]) -> Result<(), PartitionError> {
+    let block_size = device.block_size();
+    for (i, chunk) in data.chunks(block_size).enumerate() {
+        device.write_block(lba + i as u64, chunk)?;
+    }
+    Ok(())
+}
+
+fn check_block_size(device: &impl BlockDevice) -> Result<(), PartitionError> {
+    let block_size = device.block_size();
+    if block_size < MBR_SIZE || !block_size.is_power_of_two() {
+        return Err(PartitionError::InvalidParameter(format!("Blok boyutu {} bölüm tablosu için uygun değil", block_size)));
+    }
+    Ok(())
+}
+
+impl Gpt {
+    /// Aygıt için boş bir GPT hazırlar (henüz yazmaz).
+    pub fn new(device: &impl BlockDevice, disk_guid: Guid) -> Result<Self, PartitionError> {
+        check_block_size(device)?;
+        let array_blocks = entry_array_blocks(GPT_ENTRY_COUNT, GPT_ENTRY_SIZE, device.block_size());
+        let block_count = device.block_count();
+        // MBR + başlık + dizi, sonda dizi + başlık ve en az bir kullanılabilir blok
+        if block_count < 2 * (array_blocks + 1) + 2 {
+            return Err(PartitionError::InvalidParameter(format!("{} bloklu aygıt GPT için çok küçük", block_count)));
+        }
+        Ok(Gpt {
+            disk_guid,
+            first_usable_lba: 2 + array_blocks,
+            last_usable_lba: block_count - 2 - array_blocks,
+            entries: (0..GPT_ENTRY_COUNT).map(|_| GptEntry::empty()).collect(),
+            primary_valid: true,
+            backup_valid: true,
+        })
+    }
+
+    /// GPT'yi okur: önce birincil başlık (LBA 1), geçersizse diskin son bloğundaki yedek.
+    /// Hangi kopyanın geçerli olduğu `primary_valid` / `backup_valid` alanlarında bildirilir.
+    ///
+    /// # Returns
+    ///
+    /// Okunan GPT veya iki kopya da geçersizse PartitionError::InvalidGpt.
+    pub fn read(device: &mut impl BlockDevice) -> Result<Self, PartitionError> {
+        check_block_size(device)?;
+        let last_lba = device.block_count().checked_sub(1).ok_or(PartitionError::NoPartitionTable)?;
+        let primary = Gpt::read_copy(device, 1);
+        let backup = Gpt::read_copy(device, last_lba);
+        match (primary, backup) {
+            (Ok(mut gpt), backup) => {
+                gpt.backup_valid = backup.is_ok();
+                Ok(gpt)
+            }
+            (Err(_), Ok(mut gpt)) => {
+                gpt.primary_valid = false;
+                Ok(gpt)
+            }
+            (Err(primary), Err(backup)) => {
+                Err(PartitionError::InvalidGpt(format!("birincil: {}; yedek: {}", primary, backup)))
+            }
+        }
+    }
+
+    fn read_copy(device: &mut impl BlockDevice, lba: u64) -> Result<Gpt, String> {
+        let raw = read_blocks(device, lba, 1).map_err(|e| format!("{}", e))?;
+        let header = GptHeader::parse(&raw)?;
+        if header.my_lba != lba {
+            return Err(format!("başlık LBA {} != {}", header.my_lba, lba));
+        }
+        let block_size = device.block_size();
+        let (count, size) = (header.entry_count as usize, header.entry_size as usize);
+        let array_blocks = entry_array_blocks(count, size, block_size);
+        if header.entries_lba.checked_add(array_blocks).map_or(true, |end| end > device.block_count()) {
+            return Err(format!("bölüm dizisi ({} + {} blok) aygıtın dışında", header.entries_lba, array_blocks));
+        }
+        let array = read_blocks(device, header.entries_lba, array_blocks).map_err(|e| format!("{}", e))?;
+        if Crc32::checksum(&array[..count * size]) != header.entries_crc {
+            return Err(String::from("bölüm dizisi CRC32 uyuşmuyor"));
+        }
+        Ok(Gpt {
+            disk_guid: header.disk_guid,
+            first_usable_lba: header.first_usable_lba,
+            last_usable_lba: header.last_usable_lba,
+            entries: array[..count * size].chunks_exact(size).map(GptEntry::parse).collect(),
+            primary_valid: true,
+            backup_valid: true,
+        })
+    }
+
+    /// Koruyucu MBR'yi, birincil başlık ve diziyi, yedek dizi ve başlığı yazar. Okuma sırasında
+    /// bozuk bulunan kopya böylece onarılır.
+    pub fn write(&mut self, device: &mut impl BlockDevice) -> Result<(), PartitionError> {
+        check_block_size(device)?;
+        let block_size = device.block_size();
+        let block_count = device.block_count();
+        let array_blocks = entry_array_blocks(self.entries.len(), GPT_ENTRY_SIZE, block_size);
+        if block_count < 2 * (array_blocks + 1) + 2 {
+            return Err(PartitionError::InvalidParameter(format!("{} bloklu aygıt GPT için çok küçük", block_count)));
+        }
+        let last_lba = block_count - 1;
+
+        let mut array = vec![0u8; array_blocks as usize * block_size]; // Requires alloc
+        for (i, entry) in self.entries.iter().enumerate() {
+            entry.write(&mut array[i * GPT_ENTRY_SIZE..(i + 1) * GPT_ENTRY_SIZE]);
+        }
+        let entries_crc = Crc32::checksum(&array[..self.entries.len() * GPT_ENTRY_SIZE]);
+
+        let mut header = GptHeader {
+            my_lba: 1,
+            alternate_lba: last_lba,
+            first_usable_lba: self.first_usable_lba,
+            last_usable_lba: self.last_usable_lba,
+            disk_guid: self.disk_guid,
+            entries_lba: 2,
+            entry_count: self.entries.len() as u32,
+            entry_size: GPT_ENTRY_SIZE as u32,
+            entries_crc,
+        };
+
+        let mut mbr_block = vec![0u8; block_size];
+        mbr_block[..MBR_SIZE].copy_from_slice(&Mbr::protective(block_count).to_bytes());
+        write_blocks(device, 0, &mbr_block)?;
+        write_blocks(device, 2, &array)?;
+        write_blocks(device, 1, &header.to_bytes(block_size))?;
+
+        // Yedek: dizi son başlığın hemen önünde
+        header.my_lba = last_lba;
+        header.alternate_lba = 1;
+        header.entries_lba = last_lba - array_blocks;
+        write_blocks(device, header.entries_lba, &array)?;
+        write_blocks(device, last_lba, &header.to_bytes(block_size))?;
+        device.flush()?;
+
+        self.primary_valid = true;
+        self.backup_valid = true;
+        Ok(())
+    }
+
+    /// Kullanılan girişler (yuva numarasıyla).
+    pub fn used_entries(&self) -> impl Iterator<Item = (usize, &GptEntry)> {
+        self.entries.iter().enumerate().filter(|(_, e)| e.is_used())
+    }
+
+    /// Hizalanmış ilk uygun boşluğa yeni bir bölüm ekler (yazmaz; ardından `write` çağrılmalı).
+    ///
+    /// # Arguments
+    ///
+    /// * `block_size`: Aygıtın blok boyutu (hizalama için).
+    /// * `blocks`: Bölüm boyutu (blok); None ise bulunan boşluğun tamamı kullanılır.
+    ///
+    /// # Returns
+    ///
+    /// Yeni girişin yuva numarası.
+    pub fn add_partition(&mut self, block_size: usize, type_guid: Guid, unique_guid: Guid, name: &str, blocks: Option<u64>) -> Result<usize, PartitionError> {
+        if type_guid.is_nil() {
+            return Err(PartitionError::InvalidParameter(String::from("Tür GUID'i boş olamaz")));
+        }
+        if blocks == Some(0) {
+            return Err(PartitionError::InvalidParameter(String::from("Bölüm boyutu sıfır olamaz")));
+        }
+        let slot = self.entries.iter().position(|e| !e.is_used()).ok_or(PartitionError::TableFull)?;
+        let alignment = core::cmp::max(1, PARTITION_ALIGNMENT_BYTES / block_size as u64);
+
+        let mut used: Vec<(u64, u64)> = self.used_entries().map(|(_, e)| (e.first_lba, e.last_lba)).collect();
+        used.sort();
+        let mut candidate = self.first_usable_lba;
+        for (first, last) in used.iter().copied().chain(core::iter::once((self.last_usable_lba + 1, self.last_usable_lba + 1))) {
+            let start = (candidate + alignment - 1) / alignment * alignment;
+            if start < first {
+                let gap = first - start;
+                let size = blocks.unwrap_or(gap);
+                if size <= gap {
+                    self.entries[slot] = GptEntry {
+                        type_guid,
+                        unique_guid,
+                        first_lba: start,
+                        last_lba: start + size - 1,
+                        attributes: 0,
+                        name: String::from(name),
+                    };
+                    return Ok(slot);
+                }
+            }
+            candidate = core::cmp::max(candidate, last + 1);
+        }
+        Err(PartitionError::NoSpace)
+    }
+}
+
+/// Bölüm tablosu türünden bağımsız bir bölüm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Partition {
+    /// 1'den başlayan bölüm numarası (GPT yuvası + 1; MBR'de 1-4 birincil, 5+ mantıksal).
+    pub number: u32,
+    pub start_lba: u64,
+    pub block_count: u64,
+    pub kind: PartitionKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartitionKind {
+    Gpt { type_guid: Guid, unique_guid: Guid, name: String, attributes: u64 },
+    Mbr { partition_type: u8, bootable: bool },
+}
+
+impl Partition {
+    /// SADAK dosya sistemi bölümü mü?
+    pub fn is_sadak(&self) -> bool {
+        match &self.kind {
+            PartitionKind::Gpt { type_guid, .. } => *type_guid == GUID_SADAK,
+            PartitionKind::Mbr { partition_type, .. } => *partition_type == MBR_TYPE_SADAK,
+        }
+    }
+}
+
+/// Okunan bölüm tablosu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartitionTable {
+    Gpt(Gpt),
+    Mbr(Mbr),
+}
+
+impl PartitionTable {
+    /// LBA 0'daki MBR'ye bakar: koruyucu MBR ise GPT'yi, değilse MBR'yi döndürür.
+    pub fn read(device: &mut impl BlockDevice) -> Result<Self, PartitionError> {
+        check_block_size(device)?;
+        let raw = read_blocks(device, 0, 1)?;
+        let mbr = Mbr::parse(&raw).ok_or(PartitionError::NoPartitionTable)?;
+        if mbr.is_protective() {
+            return Ok(PartitionTable::Gpt(Gpt::read(device)?));
+        }
+        Ok(PartitionTable::Mbr(mbr))
+    }
+
+    /// Tablodaki bölümler; MBR'de genişletilmiş bölümün EBR zinciri de izlenir.
+    pub fn partitions(&self, device: &mut impl BlockDevice) -> Result<Vec<Partition>, PartitionError> {
+        let mut partitions = Vec::new(); // Requires alloc
+        match self {
+            PartitionTable::Gpt(gpt) => {
+                for (slot, entry) in gpt.used_entries() {
+                    partitions.push(Partition {
+                        number: slot as u32 + 1,
+                        start_lba: entry.first_lba,
+                        block_count: entry.block_count(),
+                        kind: PartitionKind::Gpt {
+                            type_guid: entry.type_guid,
+                            unique_guid: entry.unique_guid,
+                            name: entry.name.clone(),
+                            attributes: entry.attributes,
+                        },
+                    });
+                }
+            }
+            PartitionTable::Mbr(mbr) => {
+                let mut next_logical = 5;
+                for (i, entry) in mbr.entries.iter().enumerate() {
+                    if entry.is_empty() {
+                        continue;
+                    }
+                    if entry.is_extended() {
+                        read_logical_partitions(device, entry.start_lba as u64, &mut next_logical, &mut partitions)?;
+                        continue;
+                    }
+                    partitions.push(mbr_partition(i as u32 + 1, 0, entry));
+                }
+            }
+        }
+        Ok(partitions)
+    }
+}
+
+fn mbr_partition(number: u32, base_lba: u64, entry: &MbrEntry) -> Partition {
+    Partition {
+        number,
+        start_lba: base_lba + entry.start_lba as u64,
+        block_count: entry.sector_count as u64,
+        kind: PartitionKind::Mbr { partition_type: entry.partition_type, bootable: entry.bootable },
+    }
+}
+
+// Genişletilmiş bölümdeki EBR zinciri: her EBR'nin ilk girişi kendine göre, ikinci girişi
+// genişletilmiş bölümün başına göre bir sonraki EBR'yi gösterir.
+fn read_logical_partitions(device: &mut impl BlockDevice, extended_start: u64, next_number: &mut u32, out: &mut Vec<Partition>) -> Result<(), PartitionError> {
+    let mut ebr_lba = extended_start;
+    for _ in 0..MBR_MAX_LOGICAL_PARTITIONS {
+        if ebr_lba >= device.block_count() {
+            break;
+        }
+        let raw = read_blocks(device, ebr_lba, 1)?;
+        let ebr = match Mbr::parse(&raw) {
+            Some(ebr) => ebr,
+            None => break,
+        };
+        if !ebr.entries[0].is_empty() {
+            out.push(mbr_partition(*next_number, ebr_lba, &ebr.entries[0]));
+            *next_number += 1;
+        }
+        if ebr.entries[1].is_empty() {
+            break;
+        }
+        ebr_lba = extended_start + ebr.entries[1].start_lba as u64;
+    }
+    Ok(())
+}
+
+/// Bir bölümü, blok 0'ı bölümün ilk bloğu olan ayrı bir blok aygıtı olarak sunar.
+pub struct PartitionBlockDevice<D: BlockDevice> {
+    device: D,
+    start_lba: u64,
+    block_count: u64,
+}
+
+impl<D: BlockDevice> PartitionBlockDevice<D> {
+    /// `start_lba`'dan başlayan `block_count` bloğu kaplayan pencere.
+    pub fn new(device: D, start_lba: u64, block_count: u64) -> Result<Self, PartitionError> {
+        if block_count == 0 || start_lba.checked_add(block_count).map_or(true, |end| end > device.block_count()) {
+            return Err(PartitionError::InvalidParameter(format!(
+                "Bölüm {}+{} aygıtın dışında (toplam {} blok)",
+                start_lba, block_count, device.block_count()
+            )));
+        }
+        Ok(PartitionBlockDevice { device, start_lba, block_count })
+    }
+
+    /// Okunan bir bölüm için pencere.
+    pub fn open(device: D, partition: &Partition) -> Result<Self, PartitionError> {
+        PartitionBlockDevice::new(device, partition.start_lba, partition.block_count)
+    }
+
+    pub fn start_lba(&self) -> u64 {
+        self.start_lba
+    }
+
+    /// Alttaki aygıtı geri verir.
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+
+    fn translate(&self, block_id: u64, count: u64) -> Result<u64, BlockDeviceError> {
+        if block_id.checked_add(count).map_or(true, |end| end > self.block_count) {
+            return Err(BlockDeviceError::InvalidParameter(format!(
+                "Block range {}+{} is out of bounds. Total blocks: {}",
+                block_id, count, self.block_count
+            )));
+        }
+        Ok(self.start_lba + block_id)
+    }
+}
+
+impl<D: BlockDevice> BlockDevice for PartitionBlockDevice<D> {
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> Result<(), BlockDeviceError> {
+        let lba = self.translate(block_id, 1)?;
+        self.device.read_block(lba, buf)
+    }
+
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> Result<(), BlockDeviceError> {
+        let lba = self.translate(block_id, 1)?;
+        self.device.write_block(lba, buf)
+    }
+
+    fn block_size(&self) -> usize {
+        self.device.block_size()
+    }
+
+    fn block_count(&self) -> u64 {
+        self.block_count
+    }
+
+    fn discard_blocks(&mut self, start_block: u64, block_count: u64) -> Result<(), BlockDeviceError> {
+        let lba = self.translate(start_block, block_count)?;
+        self.device.discard_blocks(lba, block_count)
+    }
+
+    fn flush(&mut self) -> Result<(), BlockDeviceError> {
+        self.device.flush()
+    }
+}
+
+/// Biçimlendirici için SADAK bölümü oluşturur: aygıtta GPT varsa ona eklenir, yoksa yeni bir
+/// GPT yazılır (MBR'li disklere dokunulmaz).
+///
+/// # Arguments
+///
+/// * `disk_guid`: Yeni GPT oluşturulursa kullanılacak disk GUID'i.
+/// * `unique_guid`: Bölümün benzersiz GUID'i.
+/// * `blocks`: Bölüm boyutu (blok); None ise ilk boşluğun tamamı.
+///
+/// # Returns
+///
+/// Oluşturulan bölüm.
+pub fn create_sadak_partition(device: &mut impl BlockDevice, disk_guid: Guid, unique_guid: Guid, name: &str, blocks: Option<u64>) -> Result<Partition, PartitionError> {
+    let mut gpt = match PartitionTable::read(device) {
+        Ok(PartitionTable::Gpt(gpt)) => gpt,
+        Ok(PartitionTable::Mbr(mbr)) if mbr.entries.iter().any(|e| !e.is_empty()) => {
+            return Err(PartitionError::InvalidParameter(String::from("Aygıtta MBR bölümleri var; GPT oluşturulmadı")));
+        }
+        Ok(PartitionTable::Mbr(_)) | Err(PartitionError::NoPartitionTable) => Gpt::new(device, disk_guid)?,
+        Err(e) => return Err(e),
+    };
+    let slot = gpt.add_partition(device.block_size(), GUID_SADAK, unique_guid, name, blocks)?;
+    gpt.write(device)?;
+    let entry = &gpt.entries[slot];
+    Ok(Partition {
+        number: slot as u32 + 1,
+        start_lba: entry.first_lba,
+        block_count: entry.block_count(),
+        kind: PartitionKind::Gpt { type_guid: entry.type_guid, unique_guid: entry.unique_guid, name: entry.name.clone(), attributes: 0 },
+    })
+}
+
+/// Aygıttaki ilk SADAK bölümünü bulup blok aygıtı olarak açar.
+pub fn open_sadak_partition<D: BlockDevice>(mut device: D) -> Result<PartitionBlockDevice<D>, PartitionError> {
+    let table = PartitionTable::read(&mut device)?;
+    let partition = table.partitions(&mut device)?.into_iter().find(|p| p.is_sadak()).ok_or(PartitionError::NotFound)?;
+    PartitionBlockDevice::open(device, &partition)
+}
+
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+    use crate::testdevice::MemDevice;
+
+    #[test]
+    fn test_gpt_create_read_and_backup_recovery() -> Result<(), PartitionError> {
+        assert_eq!(GUID_SADAK.to_string(), "5ADA4B64-4B61-726E-616C-534144414B46");
+        assert_eq!(Guid::parse("C12A7328-F81F-11D2-BA4B-00A0C93EC93B"), Some(GUID_EFI_SYSTEM));
+        assert_eq!(Guid::parse("not-a-guid"), None);
+
+        let mut disk = MemDevice::new(16384, 512); // 8 MiB
+        let disk_guid = Guid::parse("11111111-2222-3333-4444-555555555555").unwrap();
+        let efi_guid = Guid::parse("AAAAAAAA-0000-0000-0000-000000000001").unwrap();
+        let mut gpt = Gpt::new(&disk, disk_guid)?;
+        gpt.add_partition(512, GUID_EFI_SYSTEM, efi_guid, "EFI", Some(2048))?;
+        gpt.write(&mut disk)?;
+
+        let sadak_guid = Guid::parse("AAAAAAAA-0000-0000-0000-000000000002").unwrap();
+        let sadak = create_sadak_partition(&mut disk, Guid::NIL, sadak_guid, "SADAK kök", None)?;
+        assert_eq!(sadak.number, 2);
+        assert_eq!(sadak.start_lba, 4096); // EFI 2048..4095, 1 MiB hizalı
+        assert!(sadak.is_sadak());
+
+        let table = PartitionTable::read(&mut disk)?;
+        let parts = table.partitions(&mut disk)?;
+        assert_eq!(parts.len(), 2);
+        assert_eq!((parts[0].start_lba, parts[0].block_count), (2048, 2048));
+        match &parts[1].kind {
+            PartitionKind::Gpt { name, unique_guid, .. } => assert_eq!((name.as_str(), *unique_guid), ("SADAK kök", sadak_guid)),
+            other => panic!("Beklenenden farklı sonuç: {:?}", other),
+        }
+        // Son kullanılabilir bloğa kadar uzanır: 16384 - 1 - 32 - 4096
+        assert_eq!(parts[1].block_count, 16384 - 33 - 4096);
+
+        // Birincil başlık bozulursa yedekten okunur, yazınca iki kopya onarılır
+        disk.blocks[1][60] ^= 0xFF;
+        let mut gpt = Gpt::read(&mut disk)?;
+        assert!(!gpt.primary_valid && gpt.backup_valid);
+        assert_eq!(gpt.used_entries().count(), 2);
+        gpt.write(&mut disk)?;
+        let gpt = Gpt::read(&mut disk)?;
+        assert!(gpt.primary_valid && gpt.backup_valid);
+
+        // Dizi CRC'si: yedek dizide tek bayt değişikliği fark edilir
+        let backup_array = 16383 - 32;
+        disk.blocks[backup_array][0] ^= 1;
+        assert!(!Gpt::read(&mut disk)?.backup_valid);
+        disk.blocks[1][60] ^= 0xFF;
+        assert!(matches!(Gpt::read(&mut disk), Err(PartitionError::InvalidGpt(_))));
+
+        // Diskten gelen giriş boyutu sınırlanır (CRC'si geçerli olsa bile)
+        let mut raw = disk.blocks[1].clone();
+        raw[84..88].copy_from_slice(&0x8000_0000u32.to_le_bytes());
+        raw[16..20].fill(0);
+        let crc = Crc32::checksum(&raw[..GPT_HEADER_SIZE]);
+        raw[16..20].copy_from_slice(&crc.to_le_bytes());
+        assert!(GptHeader::parse(&raw).is_err());
+
+        // Boş aygıt taşma yerine hata verir
+        let mut empty = MemDevice::new(0, 512);
+        assert!(matches!(Gpt::read(&mut empty), Err(PartitionError::NoPartitionTable)));
+        assert!(matches!(gpt.clone().write(&mut empty), Err(PartitionError::InvalidParameter(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_partition_block_device_window() -> Result<(), BlockDeviceError> {
+        let mut disk = MemDevice::new(8192, 512);
+        create_sadak_partition(&mut disk, Guid::NIL, Guid([7u8; 16]), "SADAK", Some(1000)).unwrap();
+
+        let mut part = open_sadak_partition(disk).unwrap();
+        assert_eq!((part.start_lba(), part.block_count(), part.block_size()), (2048, 1000, 512));
+        part.write_block(0, &[0xAB; 512])?;
+        part.write_block(999, &[0xCD; 512])?;
+        match part.write_block(1000, &[0u8; 512]) {
+            Err(BlockDeviceError::InvalidParameter(_)) => {}
+            other => panic!("Beklenenden farklı sonuç: {:?}", other),
+        }
+        let disk = part.into_inner();
+        assert_eq!(disk.blocks[2048], vec![0xAB; 512]);
+        assert_eq!(disk.blocks[3047], vec![0xCD; 512]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_mbr_with_logical_partitions() -> Result<(), PartitionError> {
+        let mut disk = MemDevice::new(4096, 512);
+        let mut mbr = Mbr { disk_signature: 0xCAFE, entries: [MbrEntry::default(); 4] };
+        mbr.entries[0] = MbrEntry { bootable: true, partition_type: 0x83, start_lba: 63, sector_count: 937 };
+        mbr.entries[1] = MbrEntry { bootable: false, partition_type: 0x05, start_lba: 1000, sector_count: 3000 };
+        disk.blocks[0].copy_from_slice(&mbr.to_bytes());
+        // EBR zinciri: 1000'de mantıksal bölüm (1063, 437) ve sonraki EBR 1500'de (SADAK, 1563, 500)
+        let mut ebr = Mbr { disk_signature: 0, entries: [MbrEntry::default(); 4] };
+        ebr.entries[0] = MbrEntry { bootable: false, partition_type: 0x83, start_lba: 63, sector_count: 437 };
+        ebr.entries[1] = MbrEntry { bootable: false, partition_type: 0x05, start_lba: 500, sector_count: 1000 };
+        disk.blocks[1000].copy_from_slice(&ebr.to_bytes());
+        let mut ebr = Mbr { disk_signature: 0, entries: [MbrEntry::default(); 4] };
+        ebr.entries[0] = MbrEntry { bootable: false, partition_type: MBR_TYPE_SADAK, start_lba: 63, sector_count: 500 };
+        disk.blocks[1500].copy_from_slice(&ebr.to_bytes());
+
+        let table = PartitionTable::read(&mut disk)?;
+        assert!(matches!(&table, PartitionTable::Mbr(m) if m.disk_signature == 0xCAFE));
+        let parts = table.partitions(&mut disk)?;
+        let summary: Vec<(u32, u64, u64)> = parts.iter().map(|p| (p.number, p.start_lba, p.block_count)).collect();
+        assert_eq!(summary, vec![(1, 63, 937), (5, 1063, 437), (6, 1563, 500)]);
+        assert!(parts[2].is_sadak());
+
+        // MBR bölümleri olan diske GPT yazılmaz
+        assert!(matches!(
+            create_sadak_partition(&mut disk, Guid::NIL, Guid([1u8; 16]), "SADAK", None),
+            Err(PartitionError::InvalidParameter(_))
+        ));
+        // Boş diskte tablo yok
+        assert!(matches!(PartitionTable::read(&mut MemDevice::new(64, 512)), Err(PartitionError::NoPartitionTable)));
+        Ok(())
+    }
+}
+
+
+// Removed redundant print module and panic handler boilerplate.
+// The empty lib module configuration is kept.
+#[cfg(not(any(feature = "std", test)))] // Only when not building std or test
+pub mod lib {} // Keep the empty lib module if needed for the crate structure
@@ -0,0 +1,564 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// SADAK disk kotaları: kullanıcı (uid), grup (gid) ve proje kimliği başına
+// blok ve inode kullanım sınırları.
+//
+// - Her sınır için yumuşak (soft) ve sert (hard) limit vardır; 0 "sınırsız" demektir.
+// - Sert limit hiçbir zaman aşılamaz.
+// - Yumuşak limit aşıldığında bir ödemesüresi (grace period) başlar; süre dolduktan sonra
+//   kullanım yumuşak limitin altına inene kadar yeni tahsis reddedilir.
+// - Kullanım muhasebesi FreeSpaceManager::allocate_block_charged / release_block_charged ve
+//   InodeTable::allocate_inode / free_inode üzerinden her tahsiste güncellenir.
+// - Durum kalıcı bir kota dosyasına (`to_bytes` / `load_from_data`) yazılır.
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(not(feature = "std"))]
+use hashbrown::HashMap;
+
+use crate::FileSystemError; // Assuming FileSystemError is in crate
+use crate::inodetable::Inode;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::format;
+
+use core::result::Result;
+use core::fmt;
+
+/// Kota dosyasının sihirli sayısı ("SDKQ").
+pub const QUOTA_FILE_MAGIC: [u8; 4] = *b"SDKQ";
+/// Kota dosyası biçim sürümü.
+pub const QUOTA_FILE_VERSION: u32 = 1;
+/// Varsayılan ödemesüresi: 7 gün (saniye).
+pub const DEFAULT_GRACE_PERIOD: u64 = 7 * 24 * 60 * 60;
+
+const QUOTA_HEADER_SIZE: usize = 4 + 4 + 8 + 8 + 4;
+const QUOTA_RECORD_SIZE: usize = 8 + 4 * 8 + 2 * 8 + 2 * 8;
+
+/// Kotanın uygulandığı kimlik türü.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum QuotaType {
+    User,
+    Group,
+    Project,
+}
+
+impl QuotaType {
+    fn to_u8(self) -> u8 {
+        match self {
+            QuotaType::User => 0,
+            QuotaType::Group => 1,
+            QuotaType::Project => 2,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(QuotaType::User),
+            1 => Some(QuotaType::Group),
+            2 => Some(QuotaType::Project),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for QuotaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotaType::User => write!(f, "user"),
+            QuotaType::Group => write!(f, "group"),
+            QuotaType::Project => write!(f, "project"),
+        }
+    }
+}
+
+/// Bir kaynağın (blok veya inode) sahibini oluşturan üç kimlik.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaOwner {
+    pub uid: u32,
+    pub gid: u32,
+    pub project_id: u32,
+}
+
+impl QuotaOwner {
+    /// Bir inode'un sahiplik bilgilerinden QuotaOwner oluşturur.
+    pub fn of(inode: &Inode) -> Self {
+        QuotaOwner { uid: inode.uid, gid: inode.gid, project_id: inode.project_id }
+    }
+
+    // Proje 0 "proje yok" anlamına gelir ve muhasebeye katılmaz.
+    fn ids(&self) -> Vec<(QuotaType, u32)> {
+        let mut ids = vec![(QuotaType::User, self.uid), (QuotaType::Group, self.gid)];
+        if self.project_id != 0 {
+            ids.push((QuotaType::Project, self.project_id));
+        }
+        ids
+    }
+}
+
+/// Blok ve inode için yumuşak/sert limitler. 0 sınırsız anlamına gelir.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuotaLimits {
+    pub block_soft: u64,
+    pub block_hard: u64,
+    pub inode_soft: u64,
+    pub inode_hard: u64,
+}
+
+/// Bir kimliğin kota kaydı: limitler, kullanım ve varsa ödemesüresi bitiş zamanları.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuotaEntry {
+    pub limits: QuotaLimits,
+    pub blocks_used: u64,
+    pub inodes_used: u64,
+    pub block_grace_expires: Option<u64>, // Unix zaman damgası (saniye)
+    pub inode_grace_expires: Option<u64>,
+}
+
+/// `report` tarafından döndürülen tek satır.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaReportEntry {
+    pub quota_type: QuotaType,
+    pub id: u32,
+    pub entry: QuotaEntry,
+    pub over_block_soft: bool,
+    pub over_inode_soft: bool,
+}
+
+// Tek bir kaynak türü için (blok veya inode) limit kontrolü.
+// Başarılıysa yeni ödemesüresi bitiş zamanını döndürür.
+fn check_limit(
+    what: &str,
+    quota_type: QuotaType,
+    id: u32,
+    used: u64,
+    amount: u64,
+    soft: u64,
+    hard: u64,
+    grace_expires: Option<u64>,
+    grace_period: u64,
+    now: u64,
+) -> Result<Option<u64>, FileSystemError> {
+    let new_used = used.saturating_add(amount);
+    if hard != 0 && new_used > hard {
+        return Err(FileSystemError::QuotaExceeded(format!(
+            "{} hard limit exceeded for {} {} ({} + {} > {})",
+            what, quota_type, id, used, amount, hard
+        )));
+    }
+    if soft != 0 && new_used > soft {
+        match grace_expires {
+            Some(expires) if now >= expires => {
+                return Err(FileSystemError::QuotaExceeded(format!(
+                    "{} soft limit exceeded for {} {} and grace period expired",
+                    what, quota_type, id
+                )));
+            }
+            Some(expires) => return Ok(Some(expires)),
+            None => return Ok(Some(now.saturating_add(grace_period))),
+        }
+    }
+    Ok(None)
+}
+
+/// Tüm kota kayıtlarını tutan ve tahsisleri denetleyen yönetici.
+pub struct QuotaManager {
+    entries: HashMap<(QuotaType, u32), QuotaEntry>, // Requires alloc
+    block_grace_period: u64,
+    inode_grace_period: u64,
+    enforcing: bool, // false ise yalnızca muhasebe yapılır, limitler uygulanmaz
+}
+
+impl QuotaManager {
+    /// Varsayılan ödemesüreleriyle, limitleri uygulayan yeni bir yönetici oluşturur.
+    pub fn new() -> Self {
+        QuotaManager {
+            entries: HashMap::new(),
+            block_grace_period: DEFAULT_GRACE_PERIOD,
+            inode_grace_period: DEFAULT_GRACE_PERIOD,
+            enforcing: true,
+        }
+    }
+
+    /// Blok ve inode ödemesürelerini (saniye) ayarlar.
+    pub fn set_grace_periods(&mut self, block_grace: u64, inode_grace: u64) {
+        self.block_grace_period = block_grace;
+        self.inode_grace_period = inode_grace;
+    }
+
+    /// Limit uygulamasını açar/kapatır. Kapalıyken kullanım yine de sayılır.
+    pub fn set_enforcing(&mut self, enforcing: bool) {
+        self.enforcing = enforcing;
+    }
+
+    /// Bir kimlik için limitleri ayarlar. Kullanım yeni yumuşak limitin altındaysa
+    /// ödemesüresi sıfırlanır.
+    pub fn set_limits(&mut self, quota_type: QuotaType, id: u32, limits: QuotaLimits) {
+        let entry = self.entries.entry((quota_type, id)).or_default();
+        entry.limits = limits;
+        if limits.block_soft == 0 || entry.blocks_used <= limits.block_soft {
+            entry.block_grace_expires = None;
+        }
+        if limits.inode_soft == 0 || entry.inodes_used <= limits.inode_soft {
+            entry.inode_grace_expires = None;
+        }
+    }
+
+    /// Bir kimliğin kota kaydını döndürür.
+    pub fn get(&self, quota_type: QuotaType, id: u32) -> Option<&QuotaEntry> {
+        self.entries.get(&(quota_type, id))
+    }
+
+    /// Sahibe `count` blok yükler. Herhangi bir kimlikte limit aşılıyorsa hiçbir kayıt
+    /// değiştirilmez ve FileSystemError::QuotaExceeded döner.
+    pub fn charge_blocks(&mut self, owner: &QuotaOwner, count: u64, now: u64) -> Result<(), FileSystemError> {
+        let mut grace = [None; 3];
+        if self.enforcing {
+            for (i, (quota_type, id)) in owner.ids().iter().enumerate() {
+                if let Some(entry) = self.entries.get(&(*quota_type, *id)) {
+                    grace[i] = check_limit(
+                        "block", *quota_type, *id, entry.blocks_used, count,
+                        entry.limits.block_soft, entry.limits.block_hard,
+                        entry.block_grace_expires, self.block_grace_period, now,
+                    )?;
+                }
+            }
+        }
+        for (i, key) in owner.ids().iter().enumerate() {
+            let entry = self.entries.entry(*key).or_default();
+            entry.blocks_used = entry.blocks_used.saturating_add(count);
+            if self.enforcing {
+                entry.block_grace_expires = grace[i];
+            }
+        }
+        Ok(())
+    }
+
+    /// Sahibin blok kullanımını `count` kadar azaltır.
+    pub fn release_blocks(&mut self, owner: &QuotaOwner, count: u64) {
+        for key in owner.ids().iter() {
+            if let Some(entry) = self.entries.get_mut(key) {
+                entry.blocks_used = entry.blocks_used.saturating_sub(count);
+                if entry.limits.block_soft == 0 || entry.blocks_used <= entry.limits.block_soft {
+                    entry.block_grace_expires = None;
+                }
+            }
+        }
+    }
+
+    /// Sahibe bir inode yükler (charge_blocks ile aynı kurallar).
+    pub fn charge_inode(&mut self, owner: &QuotaOwner, now: u64) -> Result<(), FileSystemError> {
+        let mut grace = [None; 3];
+        if self.enforcing {
+            for (i, (quota_type, id)) in owner.ids().iter().enumerate() {
+                if let Some(entry) = self.entries.get(&(*quota_type, *id)) {
+                    grace[i] = check_limit(
+                        "inode", *quota_type, *id, entry.inodes_used, 1,
+                        entry.limits.inode_soft, entry.limits.inode_hard,
+                        entry.inode_grace_expires, self.inode_grace_period, now,
+                    )?;
+                }
+            }
+        }
+        for (i, key) in owner.ids().iter().enumerate() {
+            let entry = self.entries.entry(*key).or_default();
+            entry.inodes_used = entry.inodes_used.saturating_add(1);
+            if self.enforcing {
+                entry.inode_grace_expires = grace[i];
+            }
+        }
+        Ok(())
+    }
+
+    /// Sahibin inode kullanımını bir azaltır.
+    pub fn release_inode(&mut self, owner: &QuotaOwner) {
+        for key in owner.ids().iter() {
+            if let Some(entry) = self.entries.get_mut(key) {
+                entry.inodes_used = entry.inodes_used.saturating_sub(1);
+                if entry.limits.inode_soft == 0 || entry.inodes_used <= entry.limits.inode_soft {
+                    entry.inode_grace_expires = None;
+                }
+            }
+        }
+    }
+
+    /// Bir kaynağın sahipliği değiştiğinde (chown/chgrp/proje değişimi) kullanımı taşır.
+    /// Yalnızca değişen kimlikler etkilenir ve yeni kimlikler için limitler uygulanır.
+    /// Herhangi bir kimlikte limit aşılıyorsa hiçbir kayıt değiştirilmez.
+    pub fn transfer(
+        &mut self,
+        from: &QuotaOwner,
+        to: &QuotaOwner,
+        blocks: u64,
+        inodes: u64,
+        now: u64,
+    ) -> Result<(), FileSystemError> {
+        let from_ids = from.ids();
+        let to_ids = to.ids();
+
+        // Önce tüm yeni kimlikler denetlenir, ardından değişiklikler tek seferde uygulanır.
+        let mut gained = Vec::new();
+        for key in to_ids.iter().filter(|key| !from_ids.contains(key)) {
+            let entry = self.entries.get(key).copied().unwrap_or_default();
+            let (mut block_grace, mut inode_grace) = (entry.block_grace_expires, entry.inode_grace_expires);
+            if self.enforcing {
+                block_grace = check_limit(
+                    "block", key.0, key.1, entry.blocks_used, blocks,
+                    entry.limits.block_soft, entry.limits.block_hard,
+                    entry.block_grace_expires, self.block_grace_period, now,
+                )?;
+                inode_grace = check_limit(
+                    "inode", key.0, key.1, entry.inodes_used, inodes,
+                    entry.limits.inode_soft, entry.limits.inode_hard,
+                    entry.inode_grace_expires, self.inode_grace_period, now,
+                )?;
+            }
+            gained.push((*key, block_grace, inode_grace));
+        }
+
+        for (key, block_grace, inode_grace) in gained {
+            let entry = self.entries.entry(key).or_default();
+            entry.blocks_used = entry.blocks_used.saturating_add(blocks);
+            entry.inodes_used = entry.inodes_used.saturating_add(inodes);
+            entry.block_grace_expires = block_grace;
+            entry.inode_grace_expires = inode_grace;
+        }
+        for key in from_ids.iter().filter(|key| !to_ids.contains(key)) {
+            if let Some(entry) = self.entries.get_mut(key) {
+                entry.blocks_used = entry.blocks_used.saturating_sub(blocks);
+                entry.inodes_used = entry.inodes_used.saturating_sub(inodes);
+                if entry.limits.block_soft == 0 || entry.blocks_used <= entry.limits.block_soft {
+                    entry.block_grace_expires = None;
+                }
+                if entry.limits.inode_soft == 0 || entry.inodes_used <= entry.limits.inode_soft {
+                    entry.inode_grace_expires = None;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Belirtilen türdeki tüm kayıtları kimliğe göre sıralı olarak raporlar.
+    pub fn report(&self, quota_type: QuotaType) -> Vec<QuotaReportEntry> {
+        let mut rows: Vec<QuotaReportEntry> = self
+            .entries
+            .iter()
+            .filter(|((t, _), _)| *t == quota_type)
+            .map(|((t, id), entry)| QuotaReportEntry {
+                quota_type: *t,
+                id: *id,
+                entry: *entry,
+                over_block_soft: entry.limits.block_soft != 0 && entry.blocks_used > entry.limits.block_soft,
+                over_inode_soft: entry.limits.inode_soft != 0 && entry.inodes_used > entry.limits.inode_soft,
+            })
+            .collect();
+        rows.sort_by_key(|row| row.id);
+        rows
+    }
+
+    /// Kota durumunu kalıcı kota dosyası biçimine dönüştürür.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut keys: Vec<&(QuotaType, u32)> = self.entries.keys().collect();
+        keys.sort();
+
+        let mut out = Vec::with_capacity(QUOTA_HEADER_SIZE + keys.len() * QUOTA_RECORD_SIZE); // Requires alloc
+        out.extend_from_slice(&QUOTA_FILE_MAGIC);
+        out.extend_from_slice(&QUOTA_FILE_VERSION.to_le_bytes());
+        out.extend_from_slice(&self.block_grace_period.to_le_bytes());
+        out.extend_from_slice(&self.inode_grace_period.to_le_bytes());
+        out.extend_from_slice(&(keys.len() as u32).to_le_bytes());
+
+        for key in keys {
+            let entry = &self.entries[key];
+            out.push(key.0.to_u8());
+            out.extend_from_slice(&[0u8; 3]); // Hizalama
+            out.extend_from_slice(&key.1.to_le_bytes());
+            for value in [
+                entry.limits.block_soft,
+                entry.limits.block_hard,
+                entry.limits.inode_soft,
+                entry.limits.inode_hard,
+                entry.blocks_used,
+                entry.inodes_used,
+                entry.block_grace_expires.unwrap_or(0),
+                entry.inode_grace_expires.unwrap_or(0),
+            ] {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        out
+    }
+
+    /// Kalıcı kota dosyasından bir yönetici yükler.
+    pub fn load_from_data(data: &[u8]) -> Result<Self, FileSystemError> {
+        if data.len() < QUOTA_HEADER_SIZE || data[0..4] != QUOTA_FILE_MAGIC {
+            return Err(FileSystemError::InvalidData(String::from("Quota file has an invalid header.")));
+        }
+        let read_u32 = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+        let read_u64 = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+        let version = read_u32(4);
+        if version != QUOTA_FILE_VERSION {
+            return Err(FileSystemError::InvalidData(format!("Unsupported quota file version {}.", version)));
+        }
+        let count = read_u32(24) as usize;
+        if data.len() != QUOTA_HEADER_SIZE + count * QUOTA_RECORD_SIZE {
+            return Err(FileSystemError::InvalidData(format!(
+                "Quota file size mismatch for {} records (found {} bytes).",
+                count,
+                data.len()
+            )));
+        }
+
+        let mut manager = QuotaManager::new();
+        manager.block_grace_period = read_u64(8);
+        manager.inode_grace_period = read_u64(16);
+
+        for i in 0..count {
+            let base = QUOTA_HEADER_SIZE + i * QUOTA_RECORD_SIZE;
+            let quota_type = QuotaType::from_u8(data[base])
+                .ok_or_else(|| FileSystemError::InvalidData(format!("Unknown quota type {} in record {}.", data[base], i)))?;
+            let id = read_u32(base + 4);
+            let field = |n: usize| read_u64(base + 8 + n * 8);
+            let non_zero = |v: u64| if v == 0 { None } else { Some(v) };
+            manager.entries.insert(
+                (quota_type, id),
+                QuotaEntry {
+                    limits: QuotaLimits {
+                        block_soft: field(0),
+                        block_hard: field(1),
+                        inode_soft: field(2),
+                        inode_hard: field(3),
+                    },
+                    blocks_used: field(4),
+                    inodes_used: field(5),
+                    block_grace_expires: non_zero(field(6)),
+                    inode_grace_expires: non_zero(field(7)),
+                },
+            );
+        }
+        Ok(manager)
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+
+    const ALICE: QuotaOwner = QuotaOwner { uid: 1000, gid: 100, project_id: 7 };
+    const BOB: QuotaOwner = QuotaOwner { uid: 1001, gid: 100, project_id: 0 };
+
+    #[test]
+    fn test_hard_limit_and_accounting() -> Result<(), FileSystemError> {
+        let mut quota = QuotaManager::new();
+        quota.set_limits(QuotaType::User, 1000, QuotaLimits { block_hard: 10, ..Default::default() });
+
+        quota.charge_blocks(&ALICE, 8, 0)?;
+        match quota.charge_blocks(&ALICE, 3, 0) {
+            Err(FileSystemError::QuotaExceeded(msg)) => assert!(msg.contains("hard limit") && msg.contains("user 1000")),
+            other => panic!("Beklenenden farklı sonuç: {:?}", other),
+        }
+        // Reddedilen tahsis hiçbir kaydı değiştirmemeli
+        assert_eq!(quota.get(QuotaType::User, 1000).unwrap().blocks_used, 8);
+        assert_eq!(quota.get(QuotaType::Group, 100).unwrap().blocks_used, 8);
+        assert_eq!(quota.get(QuotaType::Project, 7).unwrap().blocks_used, 8);
+
+        // Aynı gruptaki başka kullanıcı kullanıcı limitinden etkilenmez, grup muhasebesi toplanır
+        quota.charge_blocks(&BOB, 5, 0)?;
+        assert_eq!(quota.get(QuotaType::Group, 100).unwrap().blocks_used, 13);
+
+        quota.release_blocks(&ALICE, 8);
+        quota.charge_blocks(&ALICE, 10, 0)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_soft_limit_grace_period() -> Result<(), FileSystemError> {
+        let mut quota = QuotaManager::new();
+        quota.set_grace_periods(100, 50);
+        quota.set_limits(QuotaType::Group, 100, QuotaLimits { inode_soft: 2, inode_hard: 5, ..Default::default() });
+
+        quota.charge_inode(&ALICE, 1_000)?;
+        quota.charge_inode(&ALICE, 1_000)?;
+        quota.charge_inode(&ALICE, 1_010)?; // Yumuşak limit aşıldı, ödemesüresi başlar
+        let entry = quota.get(QuotaType::Group, 100).unwrap();
+        assert_eq!(entry.inode_grace_expires, Some(1_060));
+
+        quota.charge_inode(&BOB, 1_059)?; // Süre dolmadı
+        assert!(matches!(quota.charge_inode(&BOB, 1_060), Err(FileSystemError::QuotaExceeded(_))));
+
+        // Kullanım yumuşak limitin altına inince ödemesüresi sıfırlanır
+        quota.release_inode(&ALICE);
+        quota.release_inode(&BOB);
+        assert_eq!(quota.get(QuotaType::Group, 100).unwrap().inode_grace_expires, None);
+        quota.charge_inode(&BOB, 5_000)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_and_persistence() -> Result<(), FileSystemError> {
+        let mut quota = QuotaManager::new();
+        quota.set_grace_periods(3600, 7200);
+        quota.set_limits(QuotaType::User, 1001, QuotaLimits { block_soft: 1, block_hard: 100, inode_soft: 0, inode_hard: 0 });
+        quota.charge_blocks(&ALICE, 4, 10)?;
+        quota.charge_blocks(&BOB, 2, 10)?;
+        quota.charge_inode(&BOB, 10)?;
+
+        let users = quota.report(QuotaType::User);
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0].id, 1000);
+        assert_eq!(users[1].id, 1001);
+        assert!(users[1].over_block_soft);
+        assert_eq!(users[1].entry.block_grace_expires, Some(3610));
+        assert_eq!(quota.report(QuotaType::Project).len(), 1); // Bob proje 0: izlenmez
+
+        let raw = quota.to_bytes();
+        let loaded = QuotaManager::load_from_data(&raw)?;
+        assert_eq!(loaded.report(QuotaType::User), users);
+        assert_eq!(loaded.to_bytes(), raw);
+
+        let mut corrupted = raw.clone();
+        corrupted[0] = b'X';
+        assert!(QuotaManager::load_from_data(&corrupted).is_err());
+        assert!(QuotaManager::load_from_data(&raw[..raw.len() - 1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_transfer_on_chown() -> Result<(), FileSystemError> {
+        let mut quota = QuotaManager::new();
+        quota.charge_blocks(&ALICE, 6, 0)?;
+        quota.charge_inode(&ALICE, 0)?;
+        quota.set_limits(QuotaType::User, 1001, QuotaLimits { block_hard: 5, ..Default::default() });
+
+        // Bob'un limiti 6 bloğu kabul etmez; sahiplik değişimi reddedilir ve Alice'te kalır
+        assert!(quota.transfer(&ALICE, &BOB, 6, 1, 0).is_err());
+        assert_eq!(quota.get(QuotaType::User, 1000).unwrap().blocks_used, 6);
+        assert_eq!(quota.get(QuotaType::User, 1001).map(|e| e.blocks_used).unwrap_or(0), 0);
+
+        // İnode limiti aşılırsa daha önce denetlenen blok yükü de uygulanmamalı
+        quota.set_limits(QuotaType::User, 1001, QuotaLimits { inode_hard: 1, ..Default::default() });
+        assert!(quota.transfer(&ALICE, &BOB, 6, 2, 0).is_err());
+        assert_eq!(quota.get(QuotaType::User, 1001).unwrap().blocks_used, 0);
+        assert_eq!(quota.get(QuotaType::User, 1001).unwrap().inodes_used, 0);
+
+        quota.set_limits(QuotaType::User, 1001, QuotaLimits::default());
+        quota.transfer(&ALICE, &BOB, 6, 1, 0)?;
+        assert_eq!(quota.get(QuotaType::User, 1000).unwrap().blocks_used, 0);
+        assert_eq!(quota.get(QuotaType::User, 1001).unwrap().inodes_used, 1);
+        // Ortak grup değişmediği için kullanımı korunur; proje 0 hiç izlenmez
+        assert_eq!(quota.get(QuotaType::Group, 100).unwrap().blocks_used, 6);
+        assert_eq!(quota.get(QuotaType::Project, 7).unwrap().blocks_used, 0);
+        assert!(quota.get(QuotaType::Project, 0).is_none());
+        Ok(())
+    }
+}
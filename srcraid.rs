@@ -0,0 +1,1003 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// Yazılımsal RAID: birden çok blok aygıtını SADAK'a tek bir blok aygıtı olarak sunar.
+//
+// - RAID0: `chunk_blocks` bloklık parçalar üyelere sırayla dağıtılır (yedeklilik yok).
+// - RAID1: her blok tüm üyelere yazılır; okumalar kafası isteğe en yakın üyeden yapılır.
+// - RAID5: sol-simetrik yerleşim; her satırda bir parite parçası. Bir üye eksikken okumalar
+//   diğer üyelerin XOR'u ile yeniden oluşturulur.
+//
+// Her üyenin son bloğunda bir RAID süper bloğu tutulur (dizi UUID'si, üye sırası, olay sayacı,
+// temiz kapanma bayrağı). `assemble` üyeleri herhangi bir sırada kabul eder; olay sayacı geride
+// kalan üye yeniden inşa edilir, temiz kapanmamış dizi yeniden eşitlenir (resync). Yeniden inşa
+// ve eşitleme `rebuild_step` / `resync_step` ile arka planda parça parça ilerletilir.
+
+use crate::blockdevice::{BlockDevice, BlockDeviceError}; // Assuming these are in crate::blockdevice
+use crate::crypto::Crc32;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::format;
+
+use core::fmt;
+use core::result::Result;
+
+pub const RAID_SUPERBLOCK_MAGIC: &[u8; 8] = b"SDKRAID\0";
+pub const RAID_SUPERBLOCK_VERSION: u32 = 1;
+const RAID_SUPERBLOCK_SIZE: usize = 72;
+
+/// RAID seviyesi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RaidLevel {
+    Raid0,
+    Raid1,
+    Raid5,
+}
+
+impl RaidLevel {
+    fn to_u32(self) -> u32 {
+        match self {
+            RaidLevel::Raid0 => 0,
+            RaidLevel::Raid1 => 1,
+            RaidLevel::Raid5 => 5,
+        }
+    }
+
+    fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(RaidLevel::Raid0),
+            1 => Some(RaidLevel::Raid1),
+            5 => Some(RaidLevel::Raid5),
+            _ => None,
+        }
+    }
+
+    /// Seviye için gereken en az üye sayısı.
+    pub fn min_members(self) -> usize {
+        match self {
+            RaidLevel::Raid0 | RaidLevel::Raid1 => 2,
+            RaidLevel::Raid5 => 3,
+        }
+    }
+
+    /// `members` üyeli dizinin veri kaybetmeden kaldırabileceği eksik üye sayısı.
+    pub fn tolerated_failures(self, members: usize) -> usize {
+        match self {
+            RaidLevel::Raid0 => 0,
+            RaidLevel::Raid1 => members - 1,
+            RaidLevel::Raid5 => 1,
+        }
+    }
+}
+
+/// Bir üyenin durumu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberState {
+    /// Eşit ve kullanımda.
+    Active,
+    /// Yeniden inşa ediliyor; `next_block` öncesindeki üye blokları güncel.
+    Rebuilding { next_block: u64 },
+    /// Arızalı veya eksik.
+    Failed,
+}
+
+/// RAID hataları.
+#[derive(Debug)]
+pub enum RaidError {
+    /// Bir üyeden gelen aygıt hatası.
+    Device { member: usize, error: BlockDeviceError },
+    /// Seviye için yetersiz üye.
+    TooFewMembers { level: RaidLevel, count: usize },
+    /// Üyeler birbiriyle uyuşmuyor (blok boyutu, dizi UUID'si, üye sırası...).
+    Mismatch(String),
+    /// Üyede geçerli bir RAID süper bloğu yok (`member` verilen aygıt listesindeki sıradır).
+    InvalidSuperblock { member: usize },
+    /// Eksik üye sayısı seviyenin tolere edebileceğinden fazla.
+    TooManyFailures,
+    /// Geçersiz argüman.
+    InvalidParameter(String),
+}
+
+impl fmt::Display for RaidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RaidError::Device { member, error } => write!(f, "RAID üyesi {} aygıt hatası: {:?}", member, error),
+            RaidError::TooFewMembers { level, count } => write!(f, "{:?} için {} üye yetersiz", level, count),
+            RaidError::Mismatch(msg) => write!(f, "RAID üyeleri uyuşmuyor: {}", msg),
+            RaidError::InvalidSuperblock { member } => write!(f, "Aygıt {} üzerinde geçerli RAID süper bloğu yok", member),
+            RaidError::TooManyFailures => write!(f, "RAID dizisinde çok fazla eksik üye var"),
+            RaidError::InvalidParameter(msg) => write!(f, "Geçersiz parametre: {}", msg),
+        }
+    }
+}
+
+/// RaidError'ı, RAID hacmini kullanan katman için BlockDeviceError'a çevirir.
+pub fn map_raid_error_to_block_device_error(e: RaidError) -> BlockDeviceError {
+    match e {
+        RaidError::Device { error, .. } => error,
+        RaidError::InvalidParameter(msg) => BlockDeviceError::InvalidParameter(msg),
+        other => BlockDeviceError::DeviceError(format!("{}", other)),
+    }
+}
+
+/// Her üyenin son bloğunda tutulan RAID süper bloğu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaidSuperblock {
+    pub level: RaidLevel,
+    pub array_uuid: [u8; 16],
+    pub member_index: u32,
+    pub member_count: u32,
+    pub chunk_blocks: u64,
+    /// Üye başına kullanılan veri bloğu sayısı.
+    pub data_blocks: u64,
+    /// Dizi durumu her değiştiğinde artan sayaç; geride kalan üye eski veri taşır.
+    pub events: u64,
+    /// Dizi temiz kapatıldı mı (kapatılmadıysa açılışta yeniden eşitlenir)?
+    pub clean: bool,
+}
+
+impl RaidSuperblock {
+    fn to_bytes(&self, block_size: usize) -> Vec<u8> {
+        let mut raw = vec![0u8; block_size]; // Requires alloc
+        raw[0..8].copy_from_slice(RAID_SUPERBLOCK_MAGIC);
+        raw[8..12].copy_from_slice(&RAID_SUPERBLOCK_VERSION.to_le_bytes());
+        raw[12..16].copy_from_slice(&self.level.to_u32().to_le_bytes());
+        raw[16..32].copy_from_slice(&self.array_uuid);
+        raw[32..36].copy_from_slice(&self.member_index.to_le_bytes());
+        raw[36..40].copy_from_slice(&self.member_count.to_le_bytes());
+        raw[40..48].copy_from_slice(&self.chunk_blocks.to_le_bytes());
+        raw[48..56].copy_from_slice(&self.data_blocks.to_le_bytes());
+        raw[56..64].copy_from_slice(&self.events.to_le_bytes());
+        raw[64] = self.clean as u8;
+        let crc = Crc32::checksum(&raw[..68]);
+        raw[68..72].copy_from_slice(&crc.to_le_bytes());
+        raw
+    }
+
+    fn parse(raw: &[u8]) -> Option<Self> {
+        if raw.len() < RAID_SUPERBLOCK_SIZE || &raw[0..8] != RAID_SUPERBLOCK_MAGIC {
+            return None;
+        }
+        let le32 = |offset: usize| u32::from_le_bytes([raw[offset], raw[offset + 1], raw[offset + 2], raw[offset + 3]]);
+        let le64 = |offset: usize| {
+            let mut v = [0u8; 8];
+            v.copy_from_slice(&raw[offset..offset + 8]);
+            u64::from_le_bytes(v)
+        };
+        if le32(8) != RAID_SUPERBLOCK_VERSION || Crc32::checksum(&raw[..68]) != le32(68) || le64(40) == 0 {
+            return None;
+        }
+        let mut array_uuid = [0u8; 16];
+        array_uuid.copy_from_slice(&raw[16..32]);
+        Some(RaidSuperblock {
+            level: RaidLevel::from_u32(le32(12))?,
+            array_uuid,
+            member_index: le32(32),
+            member_count: le32(36),
+            chunk_blocks: le64(40),
+            data_blocks: le64(48),
+            events: le64(56),
+            clean: raw[64] != 0,
+        })
+    }
+}
+
+/// Birden çok üyeden oluşan RAID hacmi; kendisi de bir BlockDevice'tır.
+pub struct RaidVolume<D: BlockDevice> {
+    level: RaidLevel,
+    array_uuid: [u8; 16],
+    chunk_blocks: u64,
+    data_blocks: u64, // Üye başına
+    block_size: usize,
+    members: Vec<Option<D>>,
+    states: Vec<MemberState>,
+    events: u64,
+    clean: bool,
+    resync_next: Option<u64>,
+    heads: Vec<u64>,   // Her üyede son erişilen blok (RAID1 okuma dengeleme)
+    next_mirror: usize, // Eşit uzaklıkta sıradaki ayna
+}
+
+impl<D: BlockDevice> RaidVolume<D> {
+    /// Yeni bir dizi oluşturur ve üyelere süper bloklarını yazar. RAID1/RAID5 dizileri
+    /// eşitlenmemiş başlar; `resync_step` ile eşitlenmeleri gerekir.
+    ///
+    /// # Arguments
+    ///
+    /// * `members`: Üye aygıtlar (sırası dizideki sıradır).
+    /// * `chunk_blocks`: Parça (chunk) boyutu, blok cinsinden.
+    /// * `array_uuid`: Diziyi tanımlayan UUID.
+    pub fn create(level: RaidLevel, members: Vec<D>, chunk_blocks: u64, array_uuid: [u8; 16]) -> Result<Self, RaidError> {
+        if members.len() < level.min_members() {
+            return Err(RaidError::TooFewMembers { level, count: members.len() });
+        }
+        if chunk_blocks == 0 {
+            return Err(RaidError::InvalidParameter(String::from("Parça boyutu sıfır olamaz")));
+        }
+        let block_size = check_block_sizes(members.iter())?;
+        // Son blok süper bloğa ayrılır; veri alanı parça boyutunun katına yuvarlanır
+        let smallest = members.iter().map(|m| m.block_count()).min().unwrap_or(0);
+        let data_blocks = smallest.saturating_sub(1) / chunk_blocks * chunk_blocks;
+        if data_blocks == 0 {
+            return Err(RaidError::InvalidParameter(format!("{} bloklu üyeler için parça boyutu {} çok büyük", smallest, chunk_blocks)));
+        }
+
+        let count = members.len();
+        let mut volume = RaidVolume {
+            level,
+            array_uuid,
+            chunk_blocks,
+            data_blocks,
+            block_size,
+            members: members.into_iter().map(Some).collect(),
+            states: vec![MemberState::Active; count],
+            events: 0,
+            clean: true,
+            resync_next: if level == RaidLevel::Raid0 { None } else { Some(0) },
+            heads: vec![0; count],
+            next_mirror: 0,
+        };
+        volume.write_superblocks()?;
+        Ok(volume)
+    }
+
+    /// Süper bloklarından mevcut bir diziyi kurar. Üyeler herhangi bir sırada verilebilir;
+    /// eksik üyeler arızalı, olay sayacı geride kalanlar yeniden inşa edilecek kabul edilir.
+    pub fn assemble(devices: Vec<D>) -> Result<Self, RaidError> {
+        if devices.is_empty() {
+            return Err(RaidError::InvalidParameter(String::from("Üye verilmedi")));
+        }
+        let block_size = check_block_sizes(devices.iter())?;
+        let mut found = Vec::with_capacity(devices.len()); // Requires alloc
+        for (i, mut device) in devices.into_iter().enumerate() {
+            let mut raw = vec![0u8; block_size]; // Requires alloc
+            let last = device.block_count().saturating_sub(1);
+            device.read_block(last, &mut raw).map_err(|error| RaidError::Device { member: i, error })?;
+            let sb = RaidSuperblock::parse(&raw).ok_or(RaidError::InvalidSuperblock { member: i })?;
+            found.push((sb, device));
+        }
+
+        let first = found[0].0.clone();
+        let count = first.member_count as usize;
+        if count < first.level.min_members() {
+            return Err(RaidError::TooFewMembers { level: first.level, count });
+        }
+        let mut members: Vec<Option<D>> = (0..count).map(|_| None).collect();
+        let mut member_events = vec![0u64; count];
+        for (position, (sb, _)) in found.iter().enumerate() {
+            if sb.array_uuid != first.array_uuid || sb.level != first.level || sb.member_count != first.member_count
+                || sb.chunk_blocks != first.chunk_blocks || sb.data_blocks != first.data_blocks
+            {
+                return Err(RaidError::Mismatch(format!("aygıt {} başka bir diziye ait", position)));
+            }
+            if sb.member_index as usize >= count {
+                return Err(RaidError::Mismatch(format!("aygıt {} geçersiz üye sırası {}", position, sb.member_index)));
+            }
+        }
+        let max_events = found.iter().map(|(sb, _)| sb.events).max().unwrap_or(0);
+        let mut clean = true;
+        for (sb, device) in found {
+            let index = sb.member_index as usize;
+            if members[index].is_some() {
+                return Err(RaidError::Mismatch(format!("üye {} iki kez verildi", index)));
+            }
+            if device.block_count() <= sb.data_blocks {
+                return Err(RaidError::Mismatch(format!("üye {} veri alanından küçük", index)));
+            }
+            if sb.events == max_events {
+                clean &= sb.clean;
+            }
+            member_events[index] = sb.events;
+            members[index] = Some(device);
+        }
+
+        let states = members
+            .iter()
+            .zip(member_events.iter())
+            .map(|(device, events)| match device {
+                None => MemberState::Failed,
+                Some(_) if *events == max_events => MemberState::Active,
+                Some(_) => MemberState::Rebuilding { next_block: 0 },
+            })
+            .collect();
+        let mut volume = RaidVolume {
+            level: first.level,
+            array_uuid: first.array_uuid,
+            chunk_blocks: first.chunk_blocks,
+            data_blocks: first.data_blocks,
+            block_size,
+            members,
+            states,
+            events: max_events,
+            clean,
+            resync_next: if !clean && first.level != RaidLevel::Raid0 { Some(0) } else { None },
+            heads: vec![0; count],
+            next_mirror: 0,
+        };
+        volume.write_superblocks()?;
+        Ok(volume)
+    }
+
+    pub fn level(&self) -> RaidLevel {
+        self.level
+    }
+
+    pub fn array_uuid(&self) -> [u8; 16] {
+        self.array_uuid
+    }
+
+    pub fn member_count(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn member_state(&self, index: usize) -> Option<MemberState> {
+        self.states.get(index).copied()
+    }
+
+    /// Üye aygıta erişim (ör. SMART sorgusu için).
+    pub fn member_mut(&mut self, index: usize) -> Option<&mut D> {
+        self.members.get_mut(index).and_then(|m| m.as_mut())
+    }
+
+    /// En az bir üye etkin değilse true.
+    pub fn is_degraded(&self) -> bool {
+        self.states.iter().any(|s| *s != MemberState::Active)
+    }
+
+    /// Yeniden eşitleme bekleniyor mu?
+    pub fn needs_resync(&self) -> bool {
+        self.resync_next.is_some()
+    }
+
+    /// Bir üyeyi arızalı olarak işaretler (yönetici isteği veya dışarıdan algılanan hata).
+    pub fn fail_member(&mut self, index: usize) -> Result<(), RaidError> {
+        if index >= self.members.len() {
+            return Err(RaidError::InvalidParameter(format!("Üye {} yok", index)));
+        }
+        self.states[index] = MemberState::Failed;
+        self.write_superblocks()
+    }
+
+    /// Arızalı bir üyenin yerine yeni aygıt koyar ve yeniden inşayı başlatır.
+    ///
+    /// # Returns
+    ///
+    /// Çıkarılan eski aygıt (varsa).
+    pub fn replace_member(&mut self, index: usize, mut device: D) -> Result<Option<D>, RaidError> {
+        if self.level == RaidLevel::Raid0 {
+            return Err(RaidError::InvalidParameter(String::from("RAID0 üyesi yeniden inşa edilemez")));
+        }
+        if self.states.get(index) != Some(&MemberState::Failed) {
+            return Err(RaidError::InvalidParameter(format!("Üye {} arızalı değil", index)));
+        }
+        if device.block_size() != self.block_size || device.block_count() <= self.data_blocks {
+            return Err(RaidError::Mismatch(format!("yeni üye {} dizi için uygun değil", index)));
+        }
+        // Olay sayacı 0: inşa bitmeden kurulan dizide üye yine geride kalmış sayılır
+        let mut sb = self.superblock(index);
+        sb.events = 0;
+        sb.clean = false;
+        let last = device.block_count() - 1;
+        device.write_block(last, &sb.to_bytes(self.block_size)).map_err(|error| RaidError::Device { member: index, error })?;
+        let old = self.members[index].replace(device);
+        self.states[index] = MemberState::Rebuilding { next_block: 0 };
+        self.heads[index] = 0;
+        Ok(old)
+    }
+
+    /// Yeniden inşa edilen ilk üyenin en fazla `max_blocks` bloğunu oluşturur.
+    ///
+    /// # Returns
+    ///
+    /// Yeniden inşa edilecek üye kalmadıysa true.
+    pub fn rebuild_step(&mut self, max_blocks: u64) -> Result<bool, RaidError> {
+        let (index, start) = match self.states.iter().enumerate().find_map(|(i, s)| match s {
+            MemberState::Rebuilding { next_block } => Some((i, *next_block)),
+            _ => None,
+        }) {
+            Some(found) => found,
+            None => return Ok(true),
+        };
+        let end = core::cmp::min(start.saturating_add(max_blocks), self.data_blocks);
+        let mut buf = vec![0u8; self.block_size]; // Requires alloc
+        for lba in start..end {
+            match self.level {
+                RaidLevel::Raid1 => {
+                    let source = (0..self.members.len())
+                        .find(|&i| i != index && self.states[i] == MemberState::Active)
+                        .ok_or(RaidError::TooManyFailures)?;
+                    self.read_redundant(source, lba, &mut buf)?;
+                }
+                RaidLevel::Raid5 => self.reconstruct(index, lba, &mut buf)?,
+                RaidLevel::Raid0 => return Err(RaidError::TooManyFailures),
+            }
+            self.write_redundant(index, lba, &buf)?;
+            self.states[index] = MemberState::Rebuilding { next_block: lba + 1 };
+        }
+        if end == self.data_blocks {
+            self.states[index] = MemberState::Active;
+            self.write_superblocks()?;
+        }
+        Ok(!self.states.iter().any(|s| matches!(s, MemberState::Rebuilding { .. })))
+    }
+
+    /// Temiz kapanmamış diziyi en fazla `max_blocks` satır ilerletir: RAID1'de ilk etkin
+    /// üye diğerlerine kopyalanır, RAID5'te parite yeniden hesaplanır.
+    ///
+    /// # Returns
+    ///
+    /// Eşitleme tamamlandıysa true.
+    pub fn resync_step(&mut self, max_blocks: u64) -> Result<bool, RaidError> {
+        let start = match self.resync_next {
+            Some(start) => start,
+            None => return Ok(true),
+        };
+        // Eksik üyeli RAID5'te parite doğrulanamaz; yeniden inşa satırları zaten yeniden yazar
+        if self.level == RaidLevel::Raid5 && self.is_degraded() {
+            self.resync_next = None;
+            self.write_superblocks()?;
+            return Ok(true);
+        }
+        let end = core::cmp::min(start.saturating_add(max_blocks), self.data_blocks);
+        let mut buf = vec![0u8; self.block_size]; // Requires alloc
+        for lba in start..end {
+            match self.level {
+                RaidLevel::Raid1 => {
+                    let source = self.states.iter().position(|s| *s == MemberState::Active).ok_or(RaidError::TooManyFailures)?;
+                    self.read_redundant(source, lba, &mut buf)?;
+                    for i in 0..self.members.len() {
+                        if i != source && self.states[i] == MemberState::Active {
+                            self.write_redundant(i, lba, &buf)?;
+                        }
+                    }
+                }
+                RaidLevel::Raid5 => {
+                    let parity = self.parity_member(lba);
+                    self.reconstruct(parity, lba, &mut buf)?;
+                    self.write_redundant(parity, lba, &buf)?;
+                }
+                RaidLevel::Raid0 => {}
+            }
+            self.resync_next = Some(lba + 1);
+        }
+        if end == self.data_blocks {
+            self.resync_next = None;
+            self.write_superblocks()?;
+        }
+        Ok(self.resync_next.is_none())
+    }
+
+    fn superblock(&self, index: usize) -> RaidSuperblock {
+        RaidSuperblock {
+            level: self.level,
+            array_uuid: self.array_uuid,
+            member_index: index as u32,
+            member_count: self.members.len() as u32,
+            chunk_blocks: self.chunk_blocks,
+            data_blocks: self.data_blocks,
+            events: self.events,
+            clean: self.clean && self.resync_next.is_none(),
+        }
+    }
+
+    // Olay sayacını artırıp etkin üyelerin süper bloklarını yazar. Yazılamayan üye arızalı olur.
+    fn write_superblocks(&mut self) -> Result<(), RaidError> {
+        self.events += 1;
+        for i in 0..self.members.len() {
+            if self.states[i] != MemberState::Active {
+                continue;
+            }
+            let raw = self.superblock(i).to_bytes(self.block_size);
+            if let Some(device) = self.members[i].as_mut() {
+                let last = device.block_count() - 1;
+                if device.write_block(last, &raw).is_err() {
+                    self.states[i] = MemberState::Failed;
+                }
+            }
+        }
+        self.check_failures()
+    }
+
+    fn check_failures(&self) -> Result<(), RaidError> {
+        let missing = self.states.iter().filter(|s| **s != MemberState::Active).count();
+        if missing > self.level.tolerated_failures(self.members.len()) {
+            return Err(RaidError::TooManyFailures);
+        }
+        Ok(())
+    }
+
+    // Üyenin `lba` bloğu güncel veri içeriyor mu?
+    fn available(&self, index: usize, lba: u64) -> bool {
+        self.members[index].is_some()
+            && match self.states[index] {
+                MemberState::Active => true,
+                MemberState::Rebuilding { next_block } => lba < next_block,
+                MemberState::Failed => false,
+            }
+    }
+
+    fn writable(&self, index: usize) -> bool {
+        self.members[index].is_some() && self.states[index] != MemberState::Failed
+    }
+
+    // Yedekli seviyelerde hata veren üye arızalı işaretlenir; çağıran diğer üyelerle devam eder.
+    fn mark_failed(&mut self, index: usize) {
+        self.states[index] = MemberState::Failed;
+        let _ = self.write_superblocks();
+    }
+
+    fn read_member(&mut self, index: usize, lba: u64, buf: &mut [u8]) -> Result<(), RaidError> {
+        let device = self.members[index].as_mut().ok_or(RaidError::TooManyFailures)?;
+        device.read_block(lba, buf).map_err(|error| RaidError::Device { member: index, error })?;
+        self.heads[index] = lba;
+        Ok(())
+    }
+
+    fn write_member(&mut self, index: usize, lba: u64, buf: &[u8]) -> Result<(), RaidError> {
+        let device = self.members[index].as_mut().ok_or(RaidError::TooManyFailures)?;
+        device.write_block(lba, buf).map_err(|error| RaidError::Device { member: index, error })?;
+        self.heads[index] = lba;
+        Ok(())
+    }
+
+    fn read_redundant(&mut self, index: usize, lba: u64, buf: &mut [u8]) -> Result<(), RaidError> {
+        let result = self.read_member(index, lba, buf);
+        if result.is_err() {
+            self.mark_failed(index);
+        }
+        result
+    }
+
+    fn write_redundant(&mut self, index: usize, lba: u64, buf: &[u8]) -> Result<(), RaidError> {
+        let result = self.write_member(index, lba, buf);
+        if result.is_err() {
+            self.mark_failed(index);
+        }
+        result
+    }
+
+    // RAID5 satırındaki parite üyesi (sol-simetrik: her şeritte bir sola kayar).
+    fn parity_member(&self, member_lba: u64) -> usize {
+        let n = self.members.len() as u64;
+        let stripe = member_lba / self.chunk_blocks;
+        ((n - 1) - stripe % n) as usize
+    }
+
+    // Mantıksal bloğu (üye, üye bloğu) çiftine çevirir (RAID0/RAID5).
+    fn locate(&self, block: u64) -> (usize, u64) {
+        let n = self.members.len() as u64;
+        let chunk = block / self.chunk_blocks;
+        let offset = block % self.chunk_blocks;
+        match self.level {
+            RaidLevel::Raid0 => ((chunk % n) as usize, (chunk / n) * self.chunk_blocks + offset),
+            RaidLevel::Raid1 => (0, block),
+            RaidLevel::Raid5 => {
+                let stripe = chunk / (n - 1);
+                let member_lba = stripe * self.chunk_blocks + offset;
+                let parity = self.parity_member(member_lba) as u64;
+                (((parity + 1 + chunk % (n - 1)) % n) as usize, member_lba)
+            }
+        }
+    }
+
+    // `index` üyesinin `lba` bloğunu diğer üyelerin XOR'u ile oluşturur.
+    fn reconstruct(&mut self, index: usize, lba: u64, buf: &mut [u8]) -> Result<(), RaidError> {
+        buf.fill(0);
+        let mut other = vec![0u8; self.block_size]; // Requires alloc
+        for i in 0..self.members.len() {
+            if i == index {
+                continue;
+            }
+            if !self.available(i, lba) {
+                return Err(RaidError::TooManyFailures);
+            }
+            self.read_redundant(i, lba, &mut other)?;
+            xor_into(buf, &other);
+        }
+        Ok(())
+    }
+
+    // RAID1: kafası isteğe en yakın güncel ayna; eşitlikte sıradaki ayna seçilir. Eşitleme
+    // sürerken henüz eşitlenmemiş bloklar yalnızca kaynak (ilk etkin) üyeden okunur.
+    fn pick_mirror(&mut self, block: u64) -> Option<usize> {
+        let n = self.members.len();
+        if self.resync_next.map_or(false, |next| block >= next) {
+            return self.states.iter().position(|s| *s == MemberState::Active);
+        }
+        let mut best: Option<(usize, u64)> = None;
+        for k in 0..n {
+            let i = (self.next_mirror + k) % n;
+            if !self.available(i, block) {
+                continue;
+            }
+            let distance = if self.heads[i] > block { self.heads[i] - block } else { block - self.heads[i] };
+            if best.map_or(true, |(_, d)| distance < d) {
+                best = Some((i, distance));
+            }
+        }
+        let (chosen, _) = best?;
+        self.next_mirror = (chosen + 1) % n;
+        Some(chosen)
+    }
+
+    fn read_logical(&mut self, block: u64, buf: &mut [u8]) -> Result<(), RaidError> {
+        self.check_request(block, buf.len())?;
+        match self.level {
+            RaidLevel::Raid0 => {
+                let (member, lba) = self.locate(block);
+                self.read_member(member, lba, buf)
+            }
+            RaidLevel::Raid1 => loop {
+                let member = self.pick_mirror(block).ok_or(RaidError::TooManyFailures)?;
+                if self.read_redundant(member, block, buf).is_ok() {
+                    return Ok(());
+                }
+            },
+            RaidLevel::Raid5 => {
+                let (member, lba) = self.locate(block);
+                if self.available(member, lba) && self.read_redundant(member, lba, buf).is_ok() {
+                    return Ok(());
+                }
+                self.reconstruct(member, lba, buf)
+            }
+        }
+    }
+
+    fn write_logical(&mut self, block: u64, buf: &[u8]) -> Result<(), RaidError> {
+        self.check_request(block, buf.len())?;
+        if self.clean {
+            // İlk yazmadan önce dizi kirli işaretlenir; temiz kapanma flush ile kaydedilir
+            self.clean = false;
+            self.write_superblocks()?;
+        }
+        match self.level {
+            RaidLevel::Raid0 => {
+                let (member, lba) = self.locate(block);
+                self.write_member(member, lba, buf)
+            }
+            RaidLevel::Raid1 => {
+                let mut written = 0;
+                for i in 0..self.members.len() {
+                    if self.writable(i) && self.write_redundant(i, block, buf).is_ok() {
+                        written += 1;
+                    }
+                }
+                if written == 0 {
+                    return Err(RaidError::TooManyFailures);
+                }
+                Ok(())
+            }
+            RaidLevel::Raid5 => {
+                let (member, lba) = self.locate(block);
+                self.write_raid5(member, lba, buf)
+            }
+        }
+    }
+
+    fn write_raid5(&mut self, member: usize, lba: u64, data: &[u8]) -> Result<(), RaidError> {
+        let parity_member = self.parity_member(lba);
+        let parity = if self.available(member, lba) && self.available(parity_member, lba) {
+            // Oku-değiştir-yaz: P' = P ^ D ^ D'
+            let mut old = vec![0u8; self.block_size]; // Requires alloc
+            let mut parity = vec![0u8; self.block_size]; // Requires alloc
+            if self.read_redundant(member, lba, &mut old).is_err() || self.read_redundant(parity_member, lba, &mut parity).is_err() {
+                // Durum değişti; eksik üyeye göre yeniden dene
+                return self.write_raid5(member, lba, data);
+            }
+            xor_into(&mut parity, &old);
+            xor_into(&mut parity, data);
+            Some(parity)
+        } else if self.available(parity_member, lba) {
+            // Veri üyesi eksik: parite diğer veri parçalarından yeniden hesaplanır
+            let mut parity = data.to_vec();
+            let mut other = vec![0u8; self.block_size]; // Requires alloc
+            for i in 0..self.members.len() {
+                if i == member || i == parity_member {
+                    continue;
+                }
+                if !self.available(i, lba) {
+                    return Err(RaidError::TooManyFailures);
+                }
+                self.read_redundant(i, lba, &mut other)?;
+                xor_into(&mut parity, &other);
+            }
+            Some(parity)
+        } else {
+            // Parite üyesi eksik: yalnızca veri yazılır
+            None
+        };
+
+        if self.writable(member) {
+            let _ = self.write_redundant(member, lba, data);
+        }
+        if let Some(parity) = parity {
+            if self.writable(parity_member) {
+                let _ = self.write_redundant(parity_member, lba, &parity);
+            }
+        }
+        self.check_failures()
+    }
+
+    fn check_request(&self, block: u64, len: usize) -> Result<(), RaidError> {
+        if len != self.block_size {
+            return Err(RaidError::InvalidParameter(format!("Buffer size {} does not match block size {}", len, self.block_size)));
+        }
+        if block >= self.volume_blocks() {
+            return Err(RaidError::InvalidParameter(format!("Block ID {} is out of bounds. Total blocks: {}", block, self.volume_blocks())));
+        }
+        Ok(())
+    }
+
+    fn volume_blocks(&self) -> u64 {
+        let n = self.members.len() as u64;
+        match self.level {
+            RaidLevel::Raid0 => self.data_blocks * n,
+            RaidLevel::Raid1 => self.data_blocks,
+            RaidLevel::Raid5 => self.data_blocks * (n - 1),
+        }
+    }
+
+    fn flush_members(&mut self) -> Result<(), RaidError> {
+        for i in 0..self.members.len() {
+            if !self.writable(i) {
+                continue;
+            }
+            let result = match self.members[i].as_mut() {
+                Some(device) => device.flush().map_err(|error| RaidError::Device { member: i, error }),
+                None => Ok(()),
+            };
+            if let Err(e) = result {
+                if self.level == RaidLevel::Raid0 {
+                    return Err(e);
+                }
+                self.mark_failed(i);
+            }
+        }
+        if !self.clean {
+            self.clean = true;
+            self.write_superblocks()?;
+        }
+        self.check_failures()
+    }
+}
+
+fn check_block_sizes<'a, D: BlockDevice + 'a>(mut members: impl Iterator<Item = &'a D>) -> Result<usize, RaidError> {
+    let block_size = members.next().map(|m| m.block_size()).unwrap_or(0);
+    if block_size < RAID_SUPERBLOCK_SIZE {
+        return Err(RaidError::InvalidParameter(format!("Blok boyutu {} RAID için çok küçük", block_size)));
+    }
+    if members.any(|m| m.block_size() != block_size) {
+        return Err(RaidError::Mismatch(String::from("üyelerin blok boyutları farklı")));
+    }
+    Ok(block_size)
+}
+
+fn xor_into(target: &mut [u8], source: &[u8]) {
+    for (t, s) in target.iter_mut().zip(source.iter()) {
+        *t ^= s;
+    }
+}
+
+impl<D: BlockDevice> BlockDevice for RaidVolume<D> {
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> Result<(), BlockDeviceError> {
+        self.read_logical(block_id, buf).map_err(map_raid_error_to_block_device_error)
+    }
+
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> Result<(), BlockDeviceError> {
+        self.write_logical(block_id, buf).map_err(map_raid_error_to_block_device_error)
+    }
+
+    fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    fn block_count(&self) -> u64 {
+        self.volume_blocks()
+    }
+
+    fn discard_blocks(&mut self, start_block: u64, block_count: u64) -> Result<(), BlockDeviceError> {
+        if start_block.checked_add(block_count).map_or(true, |end| end > self.volume_blocks()) {
+            return Err(BlockDeviceError::InvalidParameter(format!(
+                "Block range {}+{} is out of bounds. Total blocks: {}",
+                start_block, block_count, self.volume_blocks()
+            )));
+        }
+        match self.level {
+            RaidLevel::Raid0 => {
+                // Aralık parça sınırlarında bölünür
+                let mut block = start_block;
+                let end = start_block + block_count;
+                while block < end {
+                    let run = core::cmp::min(self.chunk_blocks - block % self.chunk_blocks, end - block);
+                    let (member, lba) = self.locate(block);
+                    if let Some(device) = self.members[member].as_mut() {
+                        device.discard_blocks(lba, run)?;
+                    }
+                    block += run;
+                }
+                Ok(())
+            }
+            RaidLevel::Raid1 => {
+                for i in 0..self.members.len() {
+                    if self.writable(i) {
+                        if let Some(device) = self.members[i].as_mut() {
+                            device.discard_blocks(start_block, block_count)?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            // Discard edilen blokların içeriği aygıta bağlıdır; parite tutarlılığı için RAID5'te
+            // discard üyelere iletilmez.
+            RaidLevel::Raid5 => Ok(()),
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), BlockDeviceError> {
+        self.flush_members().map_err(map_raid_error_to_block_device_error)
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+    use crate::testdevice::MemDevice;
+
+    fn pattern(block: u64) -> Vec<u8> {
+        (0..512).map(|i| (block as usize * 7 + i) as u8).collect()
+    }
+
+    fn fill(volume: &mut RaidVolume<MemDevice>) -> Result<(), BlockDeviceError> {
+        for block in 0..volume.block_count() {
+            volume.write_block(block, &pattern(block))?;
+        }
+        Ok(())
+    }
+
+    fn verify(volume: &mut RaidVolume<MemDevice>) -> Result<(), BlockDeviceError> {
+        let mut buf = vec![0u8; 512];
+        for block in 0..volume.block_count() {
+            volume.read_block(block, &mut buf)?;
+            assert_eq!(buf, pattern(block), "blok {}", block);
+        }
+        Ok(())
+    }
+
+    fn members(count: usize, blocks: usize) -> Vec<MemDevice> {
+        (0..count).map(|_| MemDevice::new(blocks, 512)).collect()
+    }
+
+    #[test]
+    fn test_raid0_striping_and_assembly() -> Result<(), BlockDeviceError> {
+        let mut volume = RaidVolume::create(RaidLevel::Raid0, members(2, 65), 4, [1u8; 16]).unwrap();
+        assert_eq!(volume.block_count(), 128);
+        assert!(!volume.needs_resync());
+        fill(&mut volume)?;
+        // Parça 0 -> üye 0, parça 1 -> üye 1, parça 2 -> üye 0 (blok 4)
+        assert_eq!(volume.member_mut(0).unwrap().blocks[1], pattern(1));
+        assert_eq!(volume.member_mut(1).unwrap().blocks[0], pattern(4));
+        assert_eq!(volume.member_mut(0).unwrap().blocks[4], pattern(8));
+        volume.flush()?;
+
+        let mut devices: Vec<MemDevice> = (0..2).map(|i| core::mem::replace(volume.member_mut(i).unwrap(), MemDevice::new(1, 512))).collect();
+        devices.reverse();
+        let mut volume = RaidVolume::assemble(devices).unwrap();
+        assert!(!volume.needs_resync() && !volume.is_degraded());
+        verify(&mut volume)?;
+
+        match RaidVolume::create(RaidLevel::Raid0, members(1, 65), 4, [1u8; 16]) {
+            Err(RaidError::TooFewMembers { count: 1, .. }) => {}
+            other => panic!("Beklenenden farklı sonuç: {:?}", other.map(|_| ())),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_raid1_balancing_failure_and_rebuild() -> Result<(), BlockDeviceError> {
+        let mut volume = RaidVolume::create(RaidLevel::Raid1, members(2, 129), 8, [2u8; 16]).unwrap();
+        assert!(volume.needs_resync());
+        while !volume.resync_step(32).unwrap() {}
+        fill(&mut volume)?;
+        volume.flush()?;
+
+        // İki sıralı okuma akışı farklı aynalara dağılır
+        volume.member_mut(0).unwrap().reads = 0;
+        volume.member_mut(1).unwrap().reads = 0;
+        let mut buf = vec![0u8; 512];
+        for i in 0..16 {
+            volume.read_block(i, &mut buf)?;
+            volume.read_block(100 + i, &mut buf)?;
+        }
+        assert_eq!((volume.member_mut(0).unwrap().reads, volume.member_mut(1).unwrap().reads), (16, 16));
+
+        // Üye 1 arızalanır: okumalar devam eder, üye arızalı işaretlenir
+        volume.member_mut(1).unwrap().failed = true;
+        verify(&mut volume)?;
+        assert_eq!(volume.member_state(1), Some(MemberState::Failed));
+        volume.write_block(5, &pattern(500))?;
+
+        volume.replace_member(1, MemDevice::new(129, 512)).unwrap();
+        assert_eq!(volume.member_state(1), Some(MemberState::Rebuilding { next_block: 0 }));
+        while !volume.rebuild_step(50).unwrap() {}
+        assert!(!volume.is_degraded());
+        assert_eq!(volume.member_mut(1).unwrap().blocks[5], pattern(500));
+        volume.write_block(5, &pattern(5))?;
+        volume.flush()?;
+
+        // Kirli kapanma sonrası kurulum eşitleme ister; geride kalan üye yeniden inşa edilir
+        volume.write_block(7, &pattern(7))?;
+        let mut stale = core::mem::replace(volume.member_mut(0).unwrap(), MemDevice::new(1, 512));
+        let fresh = core::mem::replace(volume.member_mut(1).unwrap(), MemDevice::new(1, 512));
+        let last = stale.blocks.len() - 1;
+        let mut sb = RaidSuperblock::parse(&stale.blocks[last]).unwrap();
+        sb.events -= 1;
+        stale.blocks[last] = sb.to_bytes(512);
+        let mut volume = RaidVolume::assemble(vec![fresh, stale]).unwrap();
+        assert!(volume.needs_resync());
+        assert_eq!(volume.member_state(0), Some(MemberState::Rebuilding { next_block: 0 }));
+        while !volume.rebuild_step(64).unwrap() {}
+        while !volume.resync_step(64).unwrap() {}
+        verify(&mut volume)
+    }
+
+    #[test]
+    fn test_raid5_degraded_read_write_and_rebuild() -> Result<(), BlockDeviceError> {
+        let mut volume = RaidVolume::create(RaidLevel::Raid5, members(3, 65), 4, [5u8; 16]).unwrap();
+        assert_eq!(volume.block_count(), 128);
+        while !volume.resync_step(16).unwrap() {}
+        fill(&mut volume)?;
+
+        // Her satırda üç üyenin XOR'u sıfırdır; parite her şeritte kayar
+        for lba in 0..64 {
+            let mut row = vec![0u8; 512];
+            for i in 0..3 {
+                xor_into(&mut row, &volume.member_mut(i).unwrap().blocks[lba]);
+            }
+            assert!(row.iter().all(|b| *b == 0), "satır {}", lba);
+        }
+        assert_eq!((volume.parity_member(0), volume.parity_member(4), volume.parity_member(8)), (2, 1, 0));
+
+        // Eksik üyeyle okuma ve yazma
+        volume.member_mut(1).unwrap().failed = true;
+        verify(&mut volume)?;
+        assert!(volume.is_degraded());
+        for block in (0..128).step_by(3) {
+            volume.write_block(block, &pattern(block + 1000))?;
+        }
+        let mut buf = vec![0u8; 512];
+        for block in (0..128).step_by(3) {
+            volume.read_block(block, &mut buf)?;
+            assert_eq!(buf, pattern(block + 1000));
+        }
+
+        volume.replace_member(1, MemDevice::new(65, 512)).unwrap();
+        while !volume.rebuild_step(10).unwrap() {}
+        volume.flush()?;
+        for block in (0..128).step_by(3) {
+            volume.write_block(block, &pattern(block))?;
+        }
+        volume.flush()?;
+
+        // Üye 0 olmadan kurulan dizi eksik çalışır ve veriyi yeniden oluşturur
+        let devices: Vec<MemDevice> = (1..3).map(|i| core::mem::replace(volume.member_mut(i).unwrap(), MemDevice::new(1, 512))).collect();
+        let mut volume = RaidVolume::assemble(devices).unwrap();
+        assert_eq!(volume.member_state(0), Some(MemberState::Failed));
+        assert!(!volume.needs_resync());
+        verify(&mut volume)?;
+        assert!(matches!(volume.fail_member(1), Err(RaidError::TooManyFailures)));
+        Ok(())
+    }
+}
+
+
+// Removed redundant print module and panic handler boilerplate.
+// The empty lib module configuration is kept.
+#[cfg(not(any(feature = "std", test)))] // Only when not building std or test
+pub mod lib {} // Keep the empty lib module if needed for the crate structure
@@ -0,0 +1,1761 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// SADAK disk üstü dosya sistemi: bir blok aygıtındaki SADAK birimini VFS trait'leriyle sunar.
+//
+// Yerleşim `DeviceConfig::layout` ile aynıdır:
+//
+//   süperblok | boş alan haritası (blok bitmap'i) | inode tablosu | veri blokları
+//
+// - Süperblok `crate::superblock::Superblock`, inode'lar `crate::inodetable::Inode` biçimindedir.
+//   Inode numarası inode tablosundaki sıradır; `mode == 0` olan inode boştur.
+// - `Inode::mode` tür bitlerini de taşır (S_IFREG, S_IFDIR, S_IFLNK).
+// - Veri blokları 12 doğrudan, bir dolaylı ve bir çift dolaylı işaretçiyle adreslenir.
+//   İşaretçi 0 ise blok ayrılmamıştır (seyrek dosya, okunduğunda sıfır).
+// - Dizin verisi kayıtlardan oluşur: inode u64, tür u8, ad uzunluğu u8, ad (UTF-8).
+// - Sembolik bağın hedefi dosya verisi olarak saklanır.
+// - Genişletilmiş öznitelikler `Inode::xattr_block` ile gösterilen tek bir blokta tutulur:
+//   sayı u16, her öznitelik için ad uzunluğu u8, değer uzunluğu u16, ad, değer.
+//
+// Inode, veri ve blok bitmap'i yazmaları doğrudan aygıta gider; bir ayırma, bloğu kullanan
+// işaretçiden önce bitmap'e yazılır. Süperbloktaki boş sayaçlar bellekte tutulur ve `sync` ile
+// yazılır (bağlanırken boş blok sayısı bitmap'ten, boş inode sayısı inode tablosundan hesaplanır).
+// - Açık tanıtıcısı olan dosya silinince inode'u hemen boşaltılmaz: `links == 0` ile yetim
+//   olarak kalır ve son tanıtıcı kapanınca boşaltılır. Çökmeden kalan yetimler bağlanırken boşaltılır.
+// - Süperbloktaki `version` yerleşim sürümüdür; `SUPERBLOCK_VERSION` dışındaki birimler bağlanmaz.
+// - Şifreleme (`SadakFs::enable_encryption`): inode'un şifreleme bağlamı `ENCRYPTION_XATTR`
+//   özniteliğinde saklanır ve inode'a ilk erişimde yüklenir. Şifreli dosya ve sembolik bağ içerikleri
+//   blok blok (mantıksal blok numarası tweak olarak) şifrelenir; şifreli dizinlerde yalnızca girdi
+//   adları şifrelenir. Ayrılmamış bloklar şifresiz sıfır olarak okunur.
+
+use crate::blockdevice::{BlockDevice, BlockDeviceError}; // Assuming these are in crate::blockdevice
+use crate::config::{map_config_error_to_fs_error, DeviceConfig};
+use crate::encryption::{EncryptionManager, EncryptionPolicy, Keyring, FILE_NONCE_SIZE};
+use crate::freespacemanagement::FreeSpaceManager;
+#[cfg(feature = "std")]
+use crate::hdd::HostFileBlockDevice;
+use crate::inodetable::Inode;
+use crate::superblock::{DeviceType, Superblock, SUPERBLOCK_VERSION};
+use crate::vfs::{
+    flags_allow_write, map_fs_error_to_vfs_error, map_vfs_error_to_fs_error, DirEntry, FileSystem, FileType, Metadata, NodeRef, ReadDir,
+    SetMetadata, VfsError, VfsFile, VfsNode, O_ACCMODE, O_APPEND, O_TRUNC, O_WRONLY, XATTR_CREATE, XATTR_REPLACE,
+};
+use crate::FileSystemError;
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use alloc::format;
+
+use core::any::Any;
+use core::cmp;
+use core::result::Result;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use spin::Mutex;
+
+// Inode::mode tür bitleri
+pub const S_IFMT: u16 = 0o170000;
+pub const S_IFREG: u16 = 0o100000;
+pub const S_IFDIR: u16 = 0o040000;
+pub const S_IFLNK: u16 = 0o120000;
+
+/// Dizin girdisi adının en fazla uzunluğu (bayt).
+pub const MAX_NAME_LEN: usize = 255;
+
+/// Inode'un şifreleme bağlamını taşıyan öznitelik; VFS üzerinden görünmez ve değiştirilemez.
+pub const ENCRYPTION_XATTR: &str = "system.sadak.encryption";
+
+const DIRECT_BLOCKS: u64 = 12;
+
+// Helper function to map BlockDeviceError to FileSystemError
+fn map_block_device_error_to_fs_error(e: BlockDeviceError) -> FileSystemError {
+    FileSystemError::IOError(format!("Block device error: {:?}", e)) // Using Debug format for BlockDeviceError
+}
+
+fn map_block_device_error_to_vfs_error(e: BlockDeviceError) -> VfsError {
+    VfsError::IOError(format!("Block device error: {:?}", e))
+}
+
+// Süperbloktaki bölgeler hem birimin hem aygıtın içinde olmalıdır; bitmap blok sayısını döndürür.
+fn check_geometry(superblock: &Superblock, block_size: usize, device_blocks: u64) -> Result<u64, FileSystemError> {
+    let corrupt = |message: String| FileSystemError::Corrupted { block: 0, message };
+    if superblock.blocks_count > device_blocks {
+        return Err(corrupt(format!("Superblock has {} blocks but the device only {}", superblock.blocks_count, device_blocks)));
+    }
+    let bitmap_blocks = superblock.blocks_count.div_ceil(block_size as u64 * 8);
+    let inode_table_blocks = superblock
+        .inodes_count
+        .checked_mul(superblock.inode_size as u64)
+        .map(|bytes| bytes.div_ceil(block_size as u64))
+        .ok_or_else(|| corrupt(String::from("Inode table size overflows")))?;
+    let regions = [
+        ("block bitmap", superblock.block_bitmap_start, bitmap_blocks),
+        ("inode table", superblock.inode_table_start, inode_table_blocks),
+        ("data blocks", superblock.data_blocks_start, 0),
+    ];
+    for &(name, start, count) in regions.iter() {
+        if start.checked_add(count).is_none_or(|end| end > superblock.blocks_count) {
+            return Err(corrupt(format!("{} at {} (+{}) is outside the volume", name, start, count)));
+        }
+    }
+    Ok(bitmap_blocks)
+}
+
+fn file_type_of(mode: u16) -> Option<FileType> {
+    match mode & S_IFMT {
+        S_IFREG => Some(FileType::File),
+        S_IFDIR => Some(FileType::Directory),
+        S_IFLNK => Some(FileType::Symlink),
+        _ => None,
+    }
+}
+
+fn type_bits(file_type: FileType) -> u16 {
+    match file_type {
+        FileType::File => S_IFREG,
+        FileType::Directory => S_IFDIR,
+        FileType::Symlink => S_IFLNK,
+    }
+}
+
+fn type_code(file_type: FileType) -> u8 {
+    match file_type {
+        FileType::File => 1,
+        FileType::Directory => 2,
+        FileType::Symlink => 3,
+    }
+}
+
+fn check_name(name: &str) -> Result<(), VfsError> {
+    if name.is_empty() || name == "." || name == ".." || name.contains('/') || name.contains('\0') || name.len() > MAX_NAME_LEN {
+        return Err(VfsError::InvalidPath(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Kapasite ve boş alan bilgisi (statfs).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SadakStats {
+    pub block_size: u32,
+    pub blocks: u64,
+    pub free_blocks: u64,
+    pub inodes: u64,
+    pub free_inodes: u64,
+}
+
+// Aygıt ve bellekteki ayırma durumu; tek kilitle korunur.
+struct SadakState<D: BlockDevice> {
+    device: D,
+    superblock: Superblock,
+    free_space: FreeSpaceManager,
+    block_size: usize,
+    dirty: bool,
+    open_files: BTreeMap<u64, u32>, // Inode başına açık tanıtıcı sayısı
+    encryption: Option<SadakEncryption>,
+}
+
+// Şifreleme bağlamları ve yeni şifreli inode'lar için nonce kaynağı.
+struct SadakEncryption {
+    manager: EncryptionManager,
+    nonce_source: Box<dyn FnMut() -> [u8; FILE_NONCE_SIZE] + Send>,
+}
+
+struct SadakShared<D: BlockDevice> {
+    state: Mutex<SadakState<D>>,
+    clock: AtomicU64,
+    read_only: bool,
+}
+
+/// Bir blok aygıtına bağlanmış SADAK dosya sistemi.
+pub struct SadakFs<D: BlockDevice + Send + 'static> {
+    shared: Arc<SadakShared<D>>,
+}
+
+impl<D: BlockDevice + Send + 'static> SadakFs<D> {
+    /// Aygıtı yapılandırılmış geometriyle biçimlendirir (mkfs): süperblok, boş alan haritası,
+    /// boş inode tablosu ve `now` zamanlı kök dizin yazılır. Yeni birim yazılabilir olarak bağlı döner.
+    pub fn format(mut device: D, config: &DeviceConfig, device_type: DeviceType, device_id: u64, now: u64) -> Result<Self, FileSystemError> {
+        let mut superblock = Superblock::from_config(config, device_type, device_id)?;
+        let layout = config.layout().map_err(map_config_error_to_fs_error)?;
+        let block_size = device.block_size();
+        if block_size != config.block_size as usize {
+            return Err(FileSystemError::InvalidParameter(format!(
+                "{}: device block size {} does not match configured block size {}",
+                config.name, block_size, config.block_size
+            )));
+        }
+        if device.block_count() < config.total_blocks {
+            return Err(FileSystemError::NoSpace(format!(
+                "{}: device has {} blocks, configuration needs {}",
+                config.name,
+                device.block_count(),
+                config.total_blocks
+            )));
+        }
+        if superblock.root_inode >= superblock.inodes_count {
+            return Err(FileSystemError::InvalidParameter(format!("{}: root inode {} is out of range", config.name, superblock.root_inode)));
+        }
+
+        // Meta veri alanları bitmap'te dolu işaretlenir
+        let mut bitmap = vec![0u8; (config.total_blocks as usize).div_ceil(8)];
+        let regions = [
+            (layout.superblock, 1),
+            (layout.free_space_map, layout.free_space_map_blocks),
+            (layout.inode_table, layout.inode_table_blocks),
+        ];
+        for &(start, count) in regions.iter() {
+            for block in start..start + count {
+                bitmap[block as usize / 8] |= 1 << (block % 8);
+            }
+        }
+        let free_space = FreeSpaceManager::load_from_data(bitmap, config.total_blocks as usize, block_size)?;
+
+        let zero = vec![0u8; block_size];
+        for block in layout.inode_table..layout.inode_table + layout.inode_table_blocks {
+            device.write_block(block, &zero).map_err(map_block_device_error_to_fs_error)?;
+        }
+
+        superblock.free_inodes_count = superblock.inodes_count - 1;
+        let mut state = SadakState {
+            device,
+            superblock,
+            free_space,
+            block_size,
+            dirty: true,
+            open_files: BTreeMap::new(),
+            encryption: None,
+        };
+        for index in 0..layout.free_space_map_blocks {
+            state.write_bitmap_block(index).map_err(map_vfs_error_to_fs_error)?;
+        }
+        let mut root = Inode::new(S_IFDIR | 0o755, 0, 0);
+        root.links = 2;
+        root.atime = now;
+        root.mtime = now;
+        root.ctime = now;
+        state.write_inode(superblock.root_inode, &root).map_err(map_vfs_error_to_fs_error)?;
+        state.flush().map_err(map_vfs_error_to_fs_error)?;
+        Ok(SadakFs { shared: Arc::new(SadakShared { state: Mutex::new(state), clock: AtomicU64::new(now), read_only: false }) })
+    }
+
+    /// Biçimlendirilmiş aygıtı bağlar.
+    pub fn mount(mut device: D, read_only: bool) -> Result<Self, FileSystemError> {
+        let superblock = Superblock::load_from_device(&mut device)?;
+        if superblock.version != SUPERBLOCK_VERSION {
+            return Err(FileSystemError::NotSupported(format!(
+                "SADAK layout version {} (supported: {})",
+                superblock.version, SUPERBLOCK_VERSION
+            )));
+        }
+        let block_size = device.block_size();
+        if superblock.block_size as usize != block_size {
+            return Err(FileSystemError::InvalidData(format!(
+                "Superblock block size {} does not match device block size {}",
+                superblock.block_size, block_size
+            )));
+        }
+        if (superblock.inode_size as usize) < Inode::size() || superblock.root_inode >= superblock.inodes_count {
+            return Err(FileSystemError::Corrupted { block: 0, message: String::from("Invalid inode geometry in superblock") });
+        }
+        let bitmap_blocks = check_geometry(&superblock, block_size, device.block_count())?;
+
+        let mut bitmap = vec![0u8; (bitmap_blocks as usize) * block_size];
+        for (i, chunk) in bitmap.chunks_mut(block_size).enumerate() {
+            device.read_block(superblock.block_bitmap_start + i as u64, chunk).map_err(map_block_device_error_to_fs_error)?;
+        }
+        bitmap.truncate((superblock.blocks_count as usize).div_ceil(8));
+        let free_space = FreeSpaceManager::load_from_data(bitmap, superblock.blocks_count as usize, block_size)?;
+
+        let mut state = SadakState { device, superblock, free_space, block_size, dirty: false, open_files: BTreeMap::new(), encryption: None };
+        let root = state.read_inode(superblock.root_inode).map_err(map_vfs_error_to_fs_error)?;
+        if file_type_of(root.mode) != Some(FileType::Directory) {
+            return Err(FileSystemError::Corrupted { block: superblock.inode_table_start, message: String::from("Root inode is not a directory") });
+        }
+        state.scan_inodes(!read_only).map_err(map_vfs_error_to_fs_error)?;
+        Ok(SadakFs { shared: Arc::new(SadakShared { state: Mutex::new(state), clock: AtomicU64::new(0), read_only }) })
+    }
+
+    /// Yapılandırılmış aygıtı bağlar: disk üstü geometri yapılandırmayla eşleşmelidir,
+    /// `read_only` yapılandırmadan alınır.
+    pub fn mount_with_config(mut device: D, config: &DeviceConfig) -> Result<Self, FileSystemError> {
+        Superblock::load_from_device(&mut device)?.check_config(config)?;
+        Self::mount(device, config.read_only)
+    }
+
+    /// Yeni düğümlerin ve değişikliklerin zaman damgası için kullanılan zamanı ayarlar.
+    pub fn set_time(&self, now: u64) {
+        self.shared.clock.store(now, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> SadakStats {
+        let state = self.shared.state.lock();
+        SadakStats {
+            block_size: state.superblock.block_size,
+            blocks: state.superblock.blocks_count,
+            free_blocks: state.count_free_blocks(),
+            inodes: state.superblock.inodes_count,
+            free_inodes: state.superblock.free_inodes_count,
+        }
+    }
+
+    pub fn superblock(&self) -> Superblock {
+        self.shared.state.lock().superblock
+    }
+
+    /// Şifrelemeyi etkinleştirir: ana anahtarlar `keyring`den alınır, `nonce_source` her yeni
+    /// şifreli inode için rastgele bir nonce vermelidir. Etkinleştirilmeden şifreli inode'lara
+    /// erişim PermissionDenied ile reddedilir.
+    pub fn enable_encryption(&self, keyring: Arc<Mutex<Keyring>>, nonce_source: Box<dyn FnMut() -> [u8; FILE_NONCE_SIZE] + Send>) {
+        self.shared.state.lock().encryption = Some(SadakEncryption { manager: EncryptionManager::new(keyring), nonce_source });
+    }
+
+    /// Boş bir dizine şifreleme politikası atar; bağlam dizinin `ENCRYPTION_XATTR` özniteliğine yazılır
+    /// ve dizinde oluşturulan inode'lara miras kalır.
+    pub fn set_encryption_policy(&self, dir_ino: u64, policy: EncryptionPolicy) -> Result<(), VfsError> {
+        if self.shared.read_only {
+            return Err(VfsError::ReadOnly);
+        }
+        let mut state = self.shared.state.lock();
+        let mut dir = state.live_inode(dir_ino)?;
+        if file_type_of(dir.mode) != Some(FileType::Directory) {
+            return Err(VfsError::NotDirectory);
+        }
+        let is_empty = state.read_dir_entries(dir_ino, &mut dir)?.is_empty();
+        let Some(encryption) = state.encryption.as_mut() else { return Err(VfsError::NotSupported) };
+        let nonce = (encryption.nonce_source)();
+        encryption.manager.set_directory_policy(dir_ino, policy, is_empty, nonce).map_err(map_fs_error_to_vfs_error)?;
+        state.store_encryption_context(dir_ino, &mut dir)?;
+        state.write_inode(dir_ino, &dir)
+    }
+}
+
+impl<D: BlockDevice + Send + 'static> FileSystem for SadakFs<D> {
+    fn root(&self) -> NodeRef {
+        let ino = self.shared.state.lock().superblock.root_inode;
+        Arc::new(SadakNode { shared: self.shared.clone(), ino })
+    }
+
+    fn fs_type(&self) -> &str {
+        "sadak"
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.shared.read_only
+    }
+
+    fn sync(&self) -> Result<(), VfsError> {
+        self.shared.state.lock().flush()
+    }
+
+    fn set_time(&self, now: u64) {
+        SadakFs::set_time(self, now);
+    }
+}
+
+impl<D: BlockDevice> SadakState<D> {
+    fn read_block(&mut self, block: u64, buf: &mut [u8]) -> Result<(), VfsError> {
+        self.device.read_block(block, buf).map_err(map_block_device_error_to_vfs_error)
+    }
+
+    fn write_block(&mut self, block: u64, buf: &[u8]) -> Result<(), VfsError> {
+        self.device.write_block(block, buf).map_err(map_block_device_error_to_vfs_error)
+    }
+
+    // `block` bloğunun `offset` baytından başlayan, blok sınırlarını aşabilen aralık
+    fn read_bytes(&mut self, mut block: u64, mut offset: usize, buf: &mut [u8]) -> Result<(), VfsError> {
+        let mut scratch = vec![0u8; self.block_size];
+        let mut done = 0;
+        while done < buf.len() {
+            self.read_block(block, &mut scratch)?;
+            let count = cmp::min(buf.len() - done, self.block_size - offset);
+            buf[done..done + count].copy_from_slice(&scratch[offset..offset + count]);
+            done += count;
+            block += 1;
+            offset = 0;
+        }
+        Ok(())
+    }
+
+    fn write_bytes(&mut self, mut block: u64, mut offset: usize, data: &[u8]) -> Result<(), VfsError> {
+        let mut scratch = vec![0u8; self.block_size];
+        let mut done = 0;
+        while done < data.len() {
+            self.read_block(block, &mut scratch)?;
+            let count = cmp::min(data.len() - done, self.block_size - offset);
+            scratch[offset..offset + count].copy_from_slice(&data[done..done + count]);
+            self.write_block(block, &scratch)?;
+            done += count;
+            block += 1;
+            offset = 0;
+        }
+        Ok(())
+    }
+
+    fn inode_location(&self, ino: u64) -> Result<(u64, usize), VfsError> {
+        if ino >= self.superblock.inodes_count {
+            return Err(VfsError::InvalidData(format!("Inode {} is out of range", ino)));
+        }
+        let position = ino * self.superblock.inode_size as u64;
+        Ok((self.superblock.inode_table_start + position / self.block_size as u64, (position % self.block_size as u64) as usize))
+    }
+
+    fn read_inode(&mut self, ino: u64) -> Result<Inode, VfsError> {
+        let (block, offset) = self.inode_location(ino)?;
+        let mut raw = vec![0u8; Inode::size()];
+        self.read_bytes(block, offset, &mut raw)?;
+        Inode::deserialize_from_buffer(&raw).map_err(map_fs_error_to_vfs_error)
+    }
+
+    fn write_inode(&mut self, ino: u64, inode: &Inode) -> Result<(), VfsError> {
+        let (block, offset) = self.inode_location(ino)?;
+        let mut raw = vec![0u8; Inode::size()];
+        Inode::serialize_into_buffer(inode, &mut raw).map_err(map_fs_error_to_vfs_error)?;
+        self.write_bytes(block, offset, &raw)
+    }
+
+    // Kullanımdaki inode; boşsa NotFound (ör. silinmiş dosyanın açık tanıtıcısı)
+    fn live_inode(&mut self, ino: u64) -> Result<Inode, VfsError> {
+        let inode = self.read_inode(ino)?;
+        if inode.mode == 0 {
+            return Err(VfsError::NotFound);
+        }
+        Ok(inode)
+    }
+
+    // Boş inode sayısını tablodan yeniden hesaplar (süperbloktaki sayaca güvenilmez) ve
+    // `repair` ile önceki oturumdan kalan yetimleri boşaltır.
+    fn scan_inodes(&mut self, repair: bool) -> Result<(), VfsError> {
+        let mut free = 0;
+        for ino in 0..self.superblock.inodes_count {
+            let inode = self.read_inode(ino)?;
+            if inode.mode == 0 {
+                free += 1;
+            } else if inode.links == 0 && repair {
+                self.free_inode(ino, inode)?;
+                free += 1;
+            }
+        }
+        if free != self.superblock.free_inodes_count {
+            self.superblock.free_inodes_count = free;
+            self.dirty = repair; // Salt okunur bağlamada yalnızca bellekte düzeltilir
+        }
+        Ok(())
+    }
+
+    fn alloc_inode(&mut self, inode: &Inode) -> Result<u64, VfsError> {
+        if self.superblock.free_inodes_count == 0 {
+            return Err(VfsError::NoSpace);
+        }
+        for ino in 0..self.superblock.inodes_count {
+            if self.read_inode(ino)?.mode == 0 {
+                self.write_inode(ino, inode)?;
+                self.superblock.free_inodes_count -= 1;
+                self.dirty = true;
+                return Ok(ino);
+            }
+        }
+        Err(VfsError::NoSpace)
+    }
+
+    // Inode'un bloklarını ve öznitelik bloğunu bırakıp inode'u boşaltır.
+    fn free_inode(&mut self, ino: u64, mut inode: Inode) -> Result<(), VfsError> {
+        self.truncate(ino, &mut inode, 0)?;
+        if inode.xattr_block != 0 {
+            self.free_block(inode.xattr_block)?;
+        }
+        if let Some(encryption) = self.encryption.as_mut() {
+            encryption.manager.remove_context(ino);
+        }
+        let mut empty = Inode::new(0, 0, 0);
+        empty.links = 0;
+        self.write_inode(ino, &empty)?;
+        self.superblock.free_inodes_count += 1;
+        self.dirty = true;
+        Ok(())
+    }
+
+    // Dizinden çıkarılan inode'u boşaltır; açık tanıtıcısı varsa yetim bırakır.
+    fn release_inode(&mut self, ino: u64, mut inode: Inode) -> Result<(), VfsError> {
+        if self.open_files.contains_key(&ino) {
+            inode.links = 0;
+            return self.write_inode(ino, &inode);
+        }
+        self.free_inode(ino, inode)
+    }
+
+    // Inode'un şifreleme bağlamını öznitelik bloğundan yöneticiye yükler. Şifreleme
+    // etkinleştirilmemişse şifreli inode'a erişim PermissionDenied ile reddedilir.
+    fn load_encryption_context(&mut self, ino: u64, inode: &Inode) -> Result<(), VfsError> {
+        if inode.xattr_block == 0 || self.encryption.as_ref().is_some_and(|e| e.manager.is_encrypted(ino)) {
+            return Ok(());
+        }
+        let Some(raw) = self.read_xattrs(inode)?.remove(ENCRYPTION_XATTR) else { return Ok(()) };
+        let Some(encryption) = self.encryption.as_mut() else { return Err(VfsError::PermissionDenied) };
+        encryption.manager.load_context(ino, &raw).map_err(map_fs_error_to_vfs_error)
+    }
+
+    // Yöneticideki bağlamı inode'un özniteliğine yazar; inode'u çağıran yazar.
+    fn store_encryption_context(&mut self, ino: u64, inode: &mut Inode) -> Result<(), VfsError> {
+        let Some(raw) = self.encryption.as_ref().and_then(|e| e.manager.get_context(ino)).map(|c| c.to_bytes()) else {
+            return Ok(());
+        };
+        let mut xattrs = self.read_xattrs(inode)?;
+        xattrs.insert(ENCRYPTION_XATTR.to_string(), raw.to_vec());
+        self.write_xattrs(inode, &xattrs)
+    }
+
+    // Şifreli dizinde oluşturulan inode'a dizinin politikasını yeni bir nonce ile miras bırakır.
+    fn inherit_encryption(&mut self, dir: u64, ino: u64, inode: &mut Inode) -> Result<(), VfsError> {
+        let Some(encryption) = self.encryption.as_mut() else { return Ok(()) };
+        if !encryption.manager.is_encrypted(dir) {
+            return Ok(());
+        }
+        let nonce = (encryption.nonce_source)();
+        encryption.manager.inherit_context(dir, ino, nonce).map_err(map_fs_error_to_vfs_error)?;
+        self.store_encryption_context(ino, inode)
+    }
+
+    // Şifreli inode'un anahtarı yüklü değilse PermissionDenied (ENOKEY) döner.
+    fn check_encryption_key(&mut self, ino: u64, inode: &Inode) -> Result<(), VfsError> {
+        self.load_encryption_context(ino, inode)?;
+        match self.encryption.as_ref() {
+            Some(encryption) if !encryption.manager.is_key_available(ino) => Err(VfsError::PermissionDenied),
+            _ => Ok(()),
+        }
+    }
+
+    // İçeriği şifreli mi? Dizin verisi şifrelenmez, yalnızca girdi adları şifrelenir.
+    fn encrypts_contents(&mut self, ino: u64, inode: &Inode) -> Result<bool, VfsError> {
+        self.load_encryption_context(ino, inode)?;
+        let encrypted = self.encryption.as_ref().is_some_and(|e| e.manager.is_encrypted(ino));
+        Ok(encrypted && file_type_of(inode.mode) != Some(FileType::Directory))
+    }
+
+    fn encrypt_contents(&self, ino: u64, index: u64, data: &mut [u8]) -> Result<(), VfsError> {
+        let Some(encryption) = self.encryption.as_ref() else { return Ok(()) };
+        encryption.manager.encrypt_block(ino, index, data).map_err(map_fs_error_to_vfs_error)
+    }
+
+    fn decrypt_contents(&self, ino: u64, index: u64, data: &mut [u8]) -> Result<(), VfsError> {
+        let Some(encryption) = self.encryption.as_ref() else { return Ok(()) };
+        encryption.manager.decrypt_block(ino, index, data).map_err(map_fs_error_to_vfs_error)
+    }
+
+    // Şifreli dizine yalnızca aynı politikayla şifrelenmiş inode taşınabilir (fscrypt gibi).
+    fn can_move_into(&mut self, dir: u64, ino: u64) -> Result<bool, VfsError> {
+        let inode = self.live_inode(ino)?;
+        self.load_encryption_context(ino, &inode)?;
+        let Some(encryption) = self.encryption.as_ref() else { return Ok(true) };
+        Ok(match encryption.manager.get_context(dir) {
+            Some(context) => encryption.manager.get_context(ino).is_some_and(|c| c.policy == context.policy),
+            None => true,
+        })
+    }
+
+    // Sıfırlanmış yeni blok
+    fn alloc_block(&mut self) -> Result<u64, VfsError> {
+        let block = self.free_space.allocate_block().map_err(map_fs_error_to_vfs_error)? as u64;
+        self.write_bitmap_block(self.bitmap_index(block))?;
+        let zero = vec![0u8; self.block_size];
+        self.write_block(block, &zero)?;
+        self.dirty = true;
+        Ok(block)
+    }
+
+    fn free_block(&mut self, block: u64) -> Result<(), VfsError> {
+        self.free_space.release_block(block as usize).map_err(map_fs_error_to_vfs_error)?;
+        self.write_bitmap_block(self.bitmap_index(block))?;
+        self.dirty = true;
+        Ok(())
+    }
+
+    // `block`un bitini taşıyan bitmap bloğunun sırası
+    fn bitmap_index(&self, block: u64) -> u64 {
+        block / (self.block_size as u64 * 8)
+    }
+
+    // Bitmap'in `index`inci bloğunu aygıta yazar.
+    fn write_bitmap_block(&mut self, index: u64) -> Result<(), VfsError> {
+        let bitmap = self.free_space.raw_bitmap_data();
+        let start = cmp::min(index as usize * self.block_size, bitmap.len());
+        let end = cmp::min(start + self.block_size, bitmap.len());
+        let mut chunk = vec![0u8; self.block_size];
+        chunk[..end - start].copy_from_slice(&bitmap[start..end]);
+        self.write_block(self.superblock.block_bitmap_start + index, &chunk)
+    }
+
+    fn count_free_blocks(&self) -> u64 {
+        let used: u64 = self.free_space.raw_bitmap_data().iter().map(|b| b.count_ones() as u64).sum();
+        self.superblock.blocks_count - used
+    }
+
+    fn pointers_per_block(&self) -> u64 {
+        self.block_size as u64 / 8
+    }
+
+    fn read_pointer(&mut self, block: u64, index: u64) -> Result<u64, VfsError> {
+        let mut raw = [0u8; 8];
+        self.read_bytes(block, index as usize * 8, &mut raw)?;
+        Ok(u64::from_le_bytes(raw))
+    }
+
+    fn write_pointer(&mut self, block: u64, index: u64, value: u64) -> Result<(), VfsError> {
+        self.write_bytes(block, index as usize * 8, &value.to_le_bytes())
+    }
+
+    // İşaretçi bloğundaki `index`. girdiyi verir; `allocate` ile eksikse yeni blok ayırır.
+    fn pointer_slot(&mut self, inode: &mut Inode, table: u64, index: u64, allocate: bool) -> Result<u64, VfsError> {
+        let value = self.read_pointer(table, index)?;
+        if value != 0 || !allocate {
+            return Ok(value);
+        }
+        let block = self.alloc_block()?;
+        inode.blocks += 1;
+        self.write_pointer(table, index, block)?;
+        Ok(block)
+    }
+
+    // Dosyanın `index`. bloğunun aygıttaki numarası; ayrılmamışsa ve `allocate` yoksa 0.
+    fn bmap(&mut self, inode: &mut Inode, index: u64, allocate: bool) -> Result<u64, VfsError> {
+        let per_block = self.pointers_per_block();
+        if index < DIRECT_BLOCKS {
+            let slot = index as usize;
+            if inode.direct_blocks[slot] == 0 && allocate {
+                inode.direct_blocks[slot] = self.alloc_block()?;
+                inode.blocks += 1;
+            }
+            return Ok(inode.direct_blocks[slot]);
+        }
+        let index = index - DIRECT_BLOCKS;
+        if index < per_block {
+            if inode.indirect_block == 0 {
+                if !allocate {
+                    return Ok(0);
+                }
+                inode.indirect_block = self.alloc_block()?;
+                inode.blocks += 1;
+            }
+            let table = inode.indirect_block;
+            return self.pointer_slot(inode, table, index, allocate);
+        }
+        let index = index - per_block;
+        if index >= per_block * per_block {
+            return Err(VfsError::NoSpace); // Dosya boyutu sınırı
+        }
+        if inode.double_indirect_block == 0 {
+            if !allocate {
+                return Ok(0);
+            }
+            inode.double_indirect_block = self.alloc_block()?;
+            inode.blocks += 1;
+        }
+        let outer = inode.double_indirect_block;
+        let table = self.pointer_slot(inode, outer, index / per_block, allocate)?;
+        if table == 0 {
+            return Ok(0);
+        }
+        self.pointer_slot(inode, table, index % per_block, allocate)
+    }
+
+    fn read_data(&mut self, ino: u64, inode: &mut Inode, offset: u64, buf: &mut [u8]) -> Result<usize, VfsError> {
+        if offset >= inode.size {
+            return Ok(0);
+        }
+        let encrypted = self.encrypts_contents(ino, inode)?;
+        let total = cmp::min(buf.len() as u64, inode.size - offset) as usize;
+        let block_size = self.block_size as u64;
+        let mut scratch = vec![0u8; self.block_size];
+        let mut done = 0;
+        while done < total {
+            let position = offset + done as u64;
+            let within = (position % block_size) as usize;
+            let count = cmp::min(total - done, self.block_size - within);
+            let index = position / block_size;
+            let block = self.bmap(inode, index, false)?;
+            if block == 0 {
+                buf[done..done + count].fill(0);
+            } else {
+                self.read_block(block, &mut scratch)?;
+                if encrypted {
+                    self.decrypt_contents(ino, index, &mut scratch)?;
+                }
+                buf[done..done + count].copy_from_slice(&scratch[within..within + count]);
+            }
+            done += count;
+        }
+        Ok(total)
+    }
+
+    // Veriyi yazar ve boyutu günceller; inode'u çağıran yazar.
+    fn write_data(&mut self, ino: u64, inode: &mut Inode, offset: u64, data: &[u8]) -> Result<usize, VfsError> {
+        offset.checked_add(data.len() as u64).ok_or(VfsError::NoSpace)?;
+        let encrypted = self.encrypts_contents(ino, inode)?;
+        let block_size = self.block_size as u64;
+        let mut scratch = vec![0u8; self.block_size];
+        let mut done = 0;
+        while done < data.len() {
+            let position = offset + done as u64;
+            let within = (position % block_size) as usize;
+            let count = cmp::min(data.len() - done, self.block_size - within);
+            let index = position / block_size;
+            // Yeni ayrılan blok sıfırdır; şifreli dosyada şifresi çözülmeden sıfır kabul edilir
+            let fresh = encrypted && self.bmap(inode, index, false)? == 0;
+            let block = match self.bmap(inode, index, true) {
+                Ok(block) => block,
+                Err(_) if done > 0 => {
+                    // Kısmi yazma: yazılanı kaydet
+                    inode.size = cmp::max(inode.size, position);
+                    return Ok(done);
+                }
+                Err(e) => return Err(e),
+            };
+            if fresh {
+                scratch.fill(0);
+            } else if count < self.block_size {
+                self.read_block(block, &mut scratch)?;
+                if encrypted {
+                    self.decrypt_contents(ino, index, &mut scratch)?;
+                }
+            }
+            scratch[within..within + count].copy_from_slice(&data[done..done + count]);
+            if encrypted {
+                self.encrypt_contents(ino, index, &mut scratch)?;
+            }
+            self.write_block(block, &scratch)?;
+            done += count;
+        }
+        inode.size = cmp::max(inode.size, offset + data.len() as u64);
+        Ok(done)
+    }
+
+    // İşaretçi bloğunda `keep` ve sonrasındaki girdilerin gösterdiği blokları bırakır
+    // (`depth` 2 ise girdiler de işaretçi bloğudur). Blok tamamen boşaldıysa true döner.
+    fn free_table(&mut self, inode: &mut Inode, table: u64, keep: u64, depth: u32) -> Result<bool, VfsError> {
+        let per_block = self.pointers_per_block();
+        let span = if depth == 2 { per_block } else { 1 };
+        for i in 0..per_block {
+            let entry = self.read_pointer(table, i)?;
+            if entry == 0 {
+                continue;
+            }
+            let first = i * span;
+            if depth == 2 {
+                if first + span <= keep {
+                    continue;
+                }
+                if self.free_table(inode, entry, keep.saturating_sub(first), 1)? {
+                    self.free_block(entry)?;
+                    inode.blocks -= 1;
+                    self.write_pointer(table, i, 0)?;
+                }
+            } else if first >= keep {
+                self.free_block(entry)?;
+                inode.blocks -= 1;
+                self.write_pointer(table, i, 0)?;
+            }
+        }
+        Ok(keep == 0)
+    }
+
+    // Dosyayı `size`a kırpar veya (seyrek olarak) uzatır.
+    fn truncate(&mut self, ino: u64, inode: &mut Inode, size: u64) -> Result<(), VfsError> {
+        let block_size = self.block_size as u64;
+        let per_block = self.pointers_per_block();
+        if size < inode.size {
+            let keep = size.div_ceil(block_size);
+            for i in keep.min(DIRECT_BLOCKS)..DIRECT_BLOCKS {
+                let block = inode.direct_blocks[i as usize];
+                if block != 0 {
+                    self.free_block(block)?;
+                    inode.blocks -= 1;
+                    inode.direct_blocks[i as usize] = 0;
+                }
+            }
+            if inode.indirect_block != 0 {
+                let table = inode.indirect_block;
+                if self.free_table(inode, table, keep.saturating_sub(DIRECT_BLOCKS), 1)? {
+                    self.free_block(table)?;
+                    inode.blocks -= 1;
+                    inode.indirect_block = 0;
+                }
+            }
+            if inode.double_indirect_block != 0 {
+                let table = inode.double_indirect_block;
+                if self.free_table(inode, table, keep.saturating_sub(DIRECT_BLOCKS + per_block), 2)? {
+                    self.free_block(table)?;
+                    inode.blocks -= 1;
+                    inode.double_indirect_block = 0;
+                }
+            }
+            // Son bloğun kalan kısmı sıfırlanır; sonradan uzatılan dosyada eski veri görünmez
+            let within = (size % block_size) as usize;
+            if within != 0 {
+                let index = size / block_size;
+                let block = self.bmap(inode, index, false)?;
+                if block != 0 && self.encrypts_contents(ino, inode)? {
+                    let mut scratch = vec![0u8; self.block_size];
+                    self.read_block(block, &mut scratch)?;
+                    self.decrypt_contents(ino, index, &mut scratch)?;
+                    scratch[within..].fill(0);
+                    self.encrypt_contents(ino, index, &mut scratch)?;
+                    self.write_block(block, &scratch)?;
+                } else if block != 0 {
+                    let zero = vec![0u8; self.block_size - within];
+                    self.write_bytes(block, within, &zero)?;
+                }
+            }
+        }
+        inode.size = size;
+        Ok(())
+    }
+
+    // Girdileri okur; şifreli dizinde adların şifresi çözülür (anahtar yüklü değilse kodlanmış
+    // adlar olduğu gibi döner).
+    fn read_dir_entries(&mut self, dir: u64, inode: &mut Inode) -> Result<Vec<(String, u64, FileType)>, VfsError> {
+        let mut data = vec![0u8; inode.size as usize];
+        let count = self.read_data(dir, inode, 0, &mut data)?;
+        data.truncate(count);
+        let corrupt = || VfsError::InvalidData(String::from("Corrupted directory entry"));
+        let mut entries = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            if pos + 10 > data.len() {
+                return Err(corrupt());
+            }
+            let mut raw = [0u8; 8];
+            raw.copy_from_slice(&data[pos..pos + 8]);
+            let ino = u64::from_le_bytes(raw);
+            let file_type = match data[pos + 8] {
+                1 => FileType::File,
+                2 => FileType::Directory,
+                3 => FileType::Symlink,
+                _ => return Err(corrupt()),
+            };
+            let len = data[pos + 9] as usize;
+            let name = data.get(pos + 10..pos + 10 + len).ok_or_else(corrupt)?;
+            let name = core::str::from_utf8(name).map_err(|_| corrupt())?;
+            check_name(name).map_err(|_| corrupt())?;
+            let name = self.decrypt_name(dir, name)?;
+            check_name(&name).map_err(|_| corrupt())?;
+            entries.push((name, ino, file_type));
+            pos += 10 + len;
+        }
+        Ok(entries)
+    }
+
+    fn write_dir_entries(&mut self, dir: u64, inode: &mut Inode, entries: &[(String, u64, FileType)]) -> Result<(), VfsError> {
+        let mut data = Vec::new();
+        for (name, ino, file_type) in entries.iter() {
+            let name = self.encrypt_name(dir, name)?;
+            data.extend_from_slice(&ino.to_le_bytes());
+            data.push(type_code(*file_type));
+            data.push(name.len() as u8);
+            data.extend_from_slice(name.as_bytes());
+        }
+        if self.write_data(dir, inode, 0, &data)? < data.len() {
+            return Err(VfsError::NoSpace);
+        }
+        self.truncate(dir, inode, data.len() as u64)
+    }
+
+    fn encrypt_name(&self, dir: u64, name: &str) -> Result<String, VfsError> {
+        match self.encryption.as_ref() {
+            Some(encryption) => encryption.manager.encrypt_filename(dir, name).map_err(map_fs_error_to_vfs_error),
+            None => Ok(name.to_string()),
+        }
+    }
+
+    fn decrypt_name(&self, dir: u64, name: &str) -> Result<String, VfsError> {
+        let Some(encryption) = self.encryption.as_ref() else { return Ok(name.to_string()) };
+        match encryption.manager.decrypt_filename(dir, name) {
+            Err(FileSystemError::KeyNotAvailable(_)) => Ok(name.to_string()),
+            result => result.map_err(map_fs_error_to_vfs_error),
+        }
+    }
+
+    fn read_xattrs(&mut self, inode: &Inode) -> Result<BTreeMap<String, Vec<u8>>, VfsError> {
+        let mut xattrs = BTreeMap::new();
+        if inode.xattr_block == 0 {
+            return Ok(xattrs);
+        }
+        let mut data = vec![0u8; self.block_size];
+        self.read_block(inode.xattr_block, &mut data)?;
+        let corrupt = || VfsError::InvalidData(String::from("Corrupted extended attribute block"));
+        let count = u16::from_le_bytes([data[0], data[1]]);
+        let mut pos = 2;
+        for _ in 0..count {
+            let header = data.get(pos..pos + 3).ok_or_else(corrupt)?;
+            let name_len = header[0] as usize;
+            let value_len = u16::from_le_bytes([header[1], header[2]]) as usize;
+            let name = data.get(pos + 3..pos + 3 + name_len).ok_or_else(corrupt)?;
+            let name = core::str::from_utf8(name).map_err(|_| corrupt())?.to_string();
+            let value = data.get(pos + 3 + name_len..pos + 3 + name_len + value_len).ok_or_else(corrupt)?.to_vec();
+            xattrs.insert(name, value);
+            pos += 3 + name_len + value_len;
+        }
+        Ok(xattrs)
+    }
+
+    // Öznitelikleri yazar; boşsa bloğu bırakır. Inode'u çağıran yazar.
+    fn write_xattrs(&mut self, inode: &mut Inode, xattrs: &BTreeMap<String, Vec<u8>>) -> Result<(), VfsError> {
+        if xattrs.is_empty() {
+            if inode.xattr_block != 0 {
+                self.free_block(inode.xattr_block)?;
+                inode.xattr_block = 0;
+            }
+            return Ok(());
+        }
+        let mut data = Vec::with_capacity(self.block_size);
+        data.extend_from_slice(&(xattrs.len() as u16).to_le_bytes());
+        for (name, value) in xattrs.iter() {
+            data.push(name.len() as u8);
+            data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            data.extend_from_slice(name.as_bytes());
+            data.extend_from_slice(value);
+        }
+        if data.len() > self.block_size {
+            return Err(VfsError::NoSpace);
+        }
+        data.resize(self.block_size, 0);
+        if inode.xattr_block == 0 {
+            inode.xattr_block = self.alloc_block()?;
+        }
+        let block = inode.xattr_block;
+        self.write_block(block, &data)
+    }
+
+    // `dir` dizininin alt ağacı `target`i içeriyor mu (kendisi dahil)?
+    fn subtree_contains(&mut self, dir: u64, target: u64) -> Result<bool, VfsError> {
+        if dir == target {
+            return Ok(true);
+        }
+        let mut inode = self.live_inode(dir)?;
+        for (_, child, file_type) in self.read_dir_entries(dir, &mut inode)? {
+            if file_type == FileType::Directory && self.subtree_contains(child, target)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn metadata(&self, ino: u64, inode: &Inode) -> Result<Metadata, VfsError> {
+        let file_type = file_type_of(inode.mode).ok_or_else(|| VfsError::InvalidData(format!("Inode {} has unknown type", ino)))?;
+        let mut meta = Metadata::new(file_type, inode.size);
+        meta.mode = (inode.mode & 0o7777) as u32;
+        meta.uid = inode.uid;
+        meta.gid = inode.gid;
+        meta.nlink = inode.links;
+        meta.inode = ino;
+        meta.atime = inode.atime;
+        meta.mtime = inode.mtime;
+        meta.ctime = inode.ctime;
+        Ok(meta)
+    }
+
+    // Süperblok sayaçlarını aygıta yazar ve aygıt önbelleğini boşaltır (bitmap zaten yazılıdır).
+    fn flush(&mut self) -> Result<(), VfsError> {
+        if self.dirty {
+            self.superblock.free_blocks_count = self.count_free_blocks();
+            let superblock = self.superblock;
+            superblock.save_to_device(&mut self.device).map_err(map_fs_error_to_vfs_error)?;
+            self.dirty = false;
+        }
+        self.device.flush().map_err(map_block_device_error_to_vfs_error)
+    }
+}
+
+/// SADAK düğümü (inode numarasıyla).
+pub struct SadakNode<D: BlockDevice + Send + 'static> {
+    shared: Arc<SadakShared<D>>,
+    ino: u64,
+}
+
+impl<D: BlockDevice + Send + 'static> SadakNode<D> {
+    fn now(&self) -> u64 {
+        self.shared.clock.load(Ordering::Relaxed)
+    }
+
+    fn check_writable(&self) -> Result<(), VfsError> {
+        if self.shared.read_only {
+            return Err(VfsError::ReadOnly);
+        }
+        Ok(())
+    }
+
+    fn node(&self, ino: u64) -> NodeRef {
+        Arc::new(SadakNode { shared: self.shared.clone(), ino })
+    }
+
+    // Dizine yeni inode'lu bir girdi ekler.
+    fn add_child(&self, name: &str, mut child: Inode, data: &[u8]) -> Result<NodeRef, VfsError> {
+        self.check_writable()?;
+        check_name(name)?;
+        let now = self.now();
+        let file_type = file_type_of(child.mode).ok_or(VfsError::NotSupported)?;
+        let mut state = self.shared.state.lock();
+        let mut dir = state.live_inode(self.ino)?;
+        if file_type_of(dir.mode) != Some(FileType::Directory) {
+            return Err(VfsError::NotDirectory);
+        }
+        let mut entries = state.read_dir_entries(self.ino, &mut dir)?;
+        if entries.iter().any(|(n, _, _)| n == name) {
+            return Err(VfsError::AlreadyExists);
+        }
+        child.project_id = dir.project_id; // Proje kotası üst dizinden devralınır
+        child.atime = now;
+        child.mtime = now;
+        child.ctime = now;
+        let ino = state.alloc_inode(&child)?;
+        let written = state
+            .inherit_encryption(self.ino, ino, &mut child)
+            .and_then(|_| state.write_data(ino, &mut child, 0, data))
+            .and_then(|_| state.write_inode(ino, &child));
+        if let Err(e) = written {
+            state.free_inode(ino, child)?;
+            return Err(e);
+        }
+        entries.push((name.to_string(), ino, file_type));
+        if let Err(e) = state.write_dir_entries(self.ino, &mut dir, &entries) {
+            state.free_inode(ino, child)?;
+            return Err(e);
+        }
+        if file_type == FileType::Directory {
+            dir.links += 1;
+        }
+        dir.mtime = now;
+        dir.ctime = now;
+        state.write_inode(self.ino, &dir)?;
+        Ok(self.node(ino))
+    }
+}
+
+impl<D: BlockDevice + Send + 'static> VfsNode for SadakNode<D> {
+    fn metadata(&self) -> Result<Metadata, VfsError> {
+        let mut state = self.shared.state.lock();
+        let inode = state.live_inode(self.ino)?;
+        state.metadata(self.ino, &inode)
+    }
+
+    fn set_metadata(&self, changes: &SetMetadata) -> Result<(), VfsError> {
+        self.check_writable()?;
+        let now = self.now();
+        let mut state = self.shared.state.lock();
+        let mut inode = state.live_inode(self.ino)?;
+        if let Some(size) = changes.size {
+            match file_type_of(inode.mode) {
+                Some(FileType::File) => {
+                    state.truncate(self.ino, &mut inode, size)?;
+                    inode.mtime = now;
+                }
+                Some(FileType::Directory) => return Err(VfsError::IsDirectory),
+                _ => return Err(VfsError::NotSupported),
+            }
+        }
+        if let Some(mode) = changes.mode {
+            inode.mode = (inode.mode & S_IFMT) | (mode as u16 & 0o7777);
+        }
+        if let Some(uid) = changes.uid {
+            inode.uid = uid;
+        }
+        if let Some(gid) = changes.gid {
+            inode.gid = gid;
+        }
+        if let Some(atime) = changes.atime {
+            inode.atime = atime;
+        }
+        if let Some(mtime) = changes.mtime {
+            inode.mtime = mtime;
+        }
+        inode.ctime = now;
+        state.write_inode(self.ino, &inode)
+    }
+
+    fn open(&self, flags: u32) -> Result<Box<dyn VfsFile>, VfsError> {
+        let mut state = self.shared.state.lock();
+        let inode = state.live_inode(self.ino)?;
+        match file_type_of(inode.mode) {
+            Some(FileType::File) if inode.links > 0 => {}
+            Some(FileType::File) => return Err(VfsError::NotFound), // Yetim: yalnızca açık tanıtıcılardan erişilir
+            Some(FileType::Directory) => return Err(VfsError::IsDirectory),
+            _ => return Err(VfsError::NotSupported),
+        }
+        if flags_allow_write(flags) || flags & O_TRUNC != 0 {
+            self.check_writable()?;
+        }
+        state.check_encryption_key(self.ino, &inode)?;
+        *state.open_files.entry(self.ino).or_insert(0) += 1;
+        Ok(Box::new(SadakFile { shared: self.shared.clone(), ino: self.ino, flags }))
+    }
+
+    fn lookup(&self, name: &str) -> Result<NodeRef, VfsError> {
+        let mut state = self.shared.state.lock();
+        let mut dir = state.live_inode(self.ino)?;
+        if file_type_of(dir.mode) != Some(FileType::Directory) {
+            return Err(VfsError::NotDirectory);
+        }
+        let entries = state.read_dir_entries(self.ino, &mut dir)?;
+        let ino = entries.iter().find(|(n, _, _)| n == name).map(|(_, ino, _)| *ino).ok_or(VfsError::NotFound)?;
+        Ok(self.node(ino))
+    }
+
+    fn read_dir(&self) -> Result<ReadDir, VfsError> {
+        let mut state = self.shared.state.lock();
+        let mut dir = state.live_inode(self.ino)?;
+        if file_type_of(dir.mode) != Some(FileType::Directory) {
+            return Err(VfsError::NotDirectory);
+        }
+        let entries = state.read_dir_entries(self.ino, &mut dir)?;
+        Ok(ReadDir::new(entries.into_iter().map(|(name, inode, file_type)| DirEntry { name, file_type, inode }).collect()))
+    }
+
+    fn create(&self, name: &str, file_type: FileType, mode: u32) -> Result<NodeRef, VfsError> {
+        if file_type == FileType::Symlink {
+            return Err(VfsError::NotSupported);
+        }
+        let mut child = Inode::new(type_bits(file_type) | (mode as u16 & 0o7777), 0, 0);
+        if file_type == FileType::Directory {
+            child.links = 2;
+        }
+        self.add_child(name, child, &[])
+    }
+
+    fn symlink(&self, name: &str, target: &str) -> Result<NodeRef, VfsError> {
+        if target.is_empty() {
+            return Err(VfsError::InvalidPath(target.to_string()));
+        }
+        self.add_child(name, Inode::new(S_IFLNK | 0o777, 0, 0), target.as_bytes())
+    }
+
+    fn readlink(&self) -> Result<String, VfsError> {
+        let mut state = self.shared.state.lock();
+        let mut inode = state.live_inode(self.ino)?;
+        if file_type_of(inode.mode) != Some(FileType::Symlink) {
+            return Err(VfsError::NotSupported);
+        }
+        let mut data = vec![0u8; inode.size as usize];
+        let count = state.read_data(self.ino, &mut inode, 0, &mut data)?;
+        data.truncate(count);
+        String::from_utf8(data).map_err(|_| VfsError::InvalidData(String::from("Symlink target is not UTF-8")))
+    }
+
+    fn unlink(&self, name: &str) -> Result<(), VfsError> {
+        self.check_writable()?;
+        let now = self.now();
+        let mut state = self.shared.state.lock();
+        let mut dir = state.live_inode(self.ino)?;
+        if file_type_of(dir.mode) != Some(FileType::Directory) {
+            return Err(VfsError::NotDirectory);
+        }
+        let mut entries = state.read_dir_entries(self.ino, &mut dir)?;
+        let index = entries.iter().position(|(n, _, _)| n == name).ok_or(VfsError::NotFound)?;
+        let (_, ino, file_type) = entries[index].clone();
+        let mut child = state.live_inode(ino)?;
+        if file_type == FileType::Directory && !state.read_dir_entries(ino, &mut child)?.is_empty() {
+            return Err(VfsError::NotEmpty);
+        }
+        entries.remove(index);
+        state.write_dir_entries(self.ino, &mut dir, &entries)?;
+        if file_type == FileType::Directory {
+            dir.links -= 1;
+        }
+        dir.mtime = now;
+        dir.ctime = now;
+        state.write_inode(self.ino, &dir)?;
+        state.release_inode(ino, child)
+    }
+
+    fn rename(&self, old_name: &str, new_parent: &dyn VfsNode, new_name: &str) -> Result<(), VfsError> {
+        self.check_writable()?;
+        let target = new_parent.as_any().downcast_ref::<SadakNode<D>>().ok_or(VfsError::CrossDevice)?;
+        if !Arc::ptr_eq(&self.shared, &target.shared) {
+            return Err(VfsError::CrossDevice);
+        }
+        check_name(new_name)?;
+        let now = self.now();
+        let same_dir = self.ino == target.ino;
+        let mut state = self.shared.state.lock();
+
+        let mut source = state.live_inode(self.ino)?;
+        if file_type_of(source.mode) != Some(FileType::Directory) {
+            return Err(VfsError::NotDirectory);
+        }
+        let mut source_entries = state.read_dir_entries(self.ino, &mut source)?;
+        let index = source_entries.iter().position(|(n, _, _)| n == old_name).ok_or(VfsError::NotFound)?;
+        let (_, ino, file_type) = source_entries[index].clone();
+        if same_dir && old_name == new_name {
+            return Ok(());
+        }
+        let is_dir = file_type == FileType::Directory;
+        // Dizin kendi alt ağacına taşınamaz
+        if is_dir && state.subtree_contains(ino, target.ino)? {
+            return Err(VfsError::InvalidPath(new_name.to_string()));
+        }
+
+        let mut dest = if same_dir { source } else { state.live_inode(target.ino)? };
+        if file_type_of(dest.mode) != Some(FileType::Directory) {
+            return Err(VfsError::NotDirectory);
+        }
+        let mut dest_entries = if same_dir { source_entries.clone() } else { state.read_dir_entries(target.ino, &mut dest)? };
+        if !same_dir && !state.can_move_into(target.ino, ino)? {
+            return Err(VfsError::CrossDevice);
+        }
+
+        // Hedef varsa POSIX gibi değiştirilir (boş dizin veya dizin olmayan)
+        let mut replaced = None;
+        if let Some(existing) = dest_entries.iter().position(|(n, _, _)| n == new_name) {
+            let (_, existing_ino, existing_type) = dest_entries[existing].clone();
+            if existing_ino == ino {
+                return Ok(()); // Aynı dosyaya iki ad (olmamalı): değişiklik yok
+            }
+            let existing_is_dir = existing_type == FileType::Directory;
+            if existing_is_dir != is_dir {
+                return Err(if existing_is_dir { VfsError::IsDirectory } else { VfsError::NotDirectory });
+            }
+            let mut existing_inode = state.live_inode(existing_ino)?;
+            if existing_is_dir && !state.read_dir_entries(existing_ino, &mut existing_inode)?.is_empty() {
+                return Err(VfsError::NotEmpty);
+            }
+            dest_entries.remove(existing);
+            replaced = Some((existing_ino, existing_inode, existing_is_dir));
+        }
+
+        if same_dir {
+            let position = dest_entries.iter().position(|(n, _, _)| n == old_name).ok_or(VfsError::NotFound)?;
+            dest_entries[position].0 = new_name.to_string();
+            if let Some((_, _, true)) = replaced {
+                dest.links -= 1;
+            }
+            dest.mtime = now;
+            dest.ctime = now;
+            state.write_dir_entries(self.ino, &mut dest, &dest_entries)?;
+            state.write_inode(self.ino, &dest)?;
+        } else {
+            source_entries.remove(index);
+            dest_entries.push((new_name.to_string(), ino, file_type));
+            if is_dir {
+                source.links -= 1;
+                dest.links += 1;
+            }
+            if let Some((_, _, true)) = replaced {
+                dest.links -= 1;
+            }
+            source.mtime = now;
+            source.ctime = now;
+            dest.mtime = now;
+            dest.ctime = now;
+            state.write_dir_entries(target.ino, &mut dest, &dest_entries)?;
+            state.write_inode(target.ino, &dest)?;
+            state.write_dir_entries(self.ino, &mut source, &source_entries)?;
+            state.write_inode(self.ino, &source)?;
+        }
+        if let Some((existing_ino, existing_inode, _)) = replaced {
+            state.release_inode(existing_ino, existing_inode)?;
+        }
+        let mut moved = state.live_inode(ino)?;
+        moved.ctime = now;
+        state.write_inode(ino, &moved)
+    }
+
+    fn get_xattr(&self, name: &str) -> Result<Vec<u8>, VfsError> {
+        if name == ENCRYPTION_XATTR {
+            return Err(VfsError::NoAttribute);
+        }
+        let mut state = self.shared.state.lock();
+        let inode = state.live_inode(self.ino)?;
+        state.read_xattrs(&inode)?.remove(name).ok_or(VfsError::NoAttribute)
+    }
+
+    fn set_xattr(&self, name: &str, value: &[u8], flags: u32) -> Result<(), VfsError> {
+        self.check_writable()?;
+        if name.is_empty() || name.len() > MAX_NAME_LEN {
+            return Err(VfsError::InvalidPath(name.to_string()));
+        }
+        if name == ENCRYPTION_XATTR {
+            return Err(VfsError::PermissionDenied);
+        }
+        if value.len() > u16::MAX as usize {
+            return Err(VfsError::NoSpace);
+        }
+        let now = self.now();
+        let mut state = self.shared.state.lock();
+        let mut inode = state.live_inode(self.ino)?;
+        let mut xattrs = state.read_xattrs(&inode)?;
+        let exists = xattrs.contains_key(name);
+        if flags & XATTR_CREATE != 0 && exists {
+            return Err(VfsError::AlreadyExists);
+        }
+        if flags & XATTR_REPLACE != 0 && !exists {
+            return Err(VfsError::NoAttribute);
+        }
+        xattrs.insert(name.to_string(), value.to_vec());
+        state.write_xattrs(&mut inode, &xattrs)?;
+        inode.ctime = now;
+        state.write_inode(self.ino, &inode)
+    }
+
+    fn list_xattr(&self) -> Result<Vec<String>, VfsError> {
+        let mut state = self.shared.state.lock();
+        let inode = state.live_inode(self.ino)?;
+        Ok(state.read_xattrs(&inode)?.into_keys().filter(|name| name != ENCRYPTION_XATTR).collect())
+    }
+
+    fn remove_xattr(&self, name: &str) -> Result<(), VfsError> {
+        self.check_writable()?;
+        if name == ENCRYPTION_XATTR {
+            return Err(VfsError::PermissionDenied);
+        }
+        let now = self.now();
+        let mut state = self.shared.state.lock();
+        let mut inode = state.live_inode(self.ino)?;
+        let mut xattrs = state.read_xattrs(&inode)?;
+        xattrs.remove(name).ok_or(VfsError::NoAttribute)?;
+        state.write_xattrs(&mut inode, &xattrs)?;
+        inode.ctime = now;
+        state.write_inode(self.ino, &inode)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// SADAK'ta açık dosya. Erişim zamanı okumalarda güncellenmez (noatime). Açık kaldıkça
+/// inode'u silinse de yeniden kullanılmaz.
+struct SadakFile<D: BlockDevice + Send + 'static> {
+    shared: Arc<SadakShared<D>>,
+    ino: u64,
+    flags: u32,
+}
+
+impl<D: BlockDevice + Send + 'static> VfsFile for SadakFile<D> {
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> Result<usize, VfsError> {
+        if self.flags & O_ACCMODE == O_WRONLY {
+            return Err(VfsError::InvalidDescriptor);
+        }
+        let mut state = self.shared.state.lock();
+        let mut inode = state.live_inode(self.ino)?;
+        state.read_data(self.ino, &mut inode, offset, buf)
+    }
+
+    fn write_at(&mut self, offset: u64, buf: &[u8]) -> Result<usize, VfsError> {
+        if !flags_allow_write(self.flags) {
+            return Err(VfsError::InvalidDescriptor);
+        }
+        if self.shared.read_only {
+            return Err(VfsError::ReadOnly);
+        }
+        let now = self.shared.clock.load(Ordering::Relaxed);
+        let mut state = self.shared.state.lock();
+        let mut inode = state.live_inode(self.ino)?;
+        let offset = if self.flags & O_APPEND != 0 { inode.size } else { offset };
+        let written = state.write_data(self.ino, &mut inode, offset, buf);
+        // Kısmi yazmada da ayrılan bloklar inode'a kaydedilir
+        inode.mtime = now;
+        inode.ctime = now;
+        state.write_inode(self.ino, &inode)?;
+        written
+    }
+
+    fn metadata(&self) -> Result<Metadata, VfsError> {
+        let mut state = self.shared.state.lock();
+        let inode = state.live_inode(self.ino)?;
+        state.metadata(self.ino, &inode)
+    }
+
+    fn set_len(&mut self, size: u64) -> Result<(), VfsError> {
+        if !flags_allow_write(self.flags) && self.flags & O_TRUNC == 0 {
+            return Err(VfsError::InvalidDescriptor);
+        }
+        if self.shared.read_only {
+            return Err(VfsError::ReadOnly);
+        }
+        let now = self.shared.clock.load(Ordering::Relaxed);
+        let mut state = self.shared.state.lock();
+        let mut inode = state.live_inode(self.ino)?;
+        state.truncate(self.ino, &mut inode, size)?;
+        inode.mtime = now;
+        inode.ctime = now;
+        state.write_inode(self.ino, &inode)
+    }
+
+    fn sync(&mut self) -> Result<(), VfsError> {
+        self.shared.state.lock().flush()
+    }
+}
+
+impl<D: BlockDevice + Send + 'static> Drop for SadakFile<D> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock();
+        let Some(count) = state.open_files.get_mut(&self.ino) else { return };
+        *count -= 1;
+        if *count > 0 {
+            return;
+        }
+        state.open_files.remove(&self.ino);
+        // Son tanıtıcı: silinmiş dosyanın inode'u şimdi boşaltılır (hata olursa bağlanırken boşaltılır)
+        if let Ok(inode) = state.live_inode(self.ino) {
+            if inode.links == 0 && !self.shared.read_only {
+                let _ = state.free_inode(self.ino, inode);
+            }
+        }
+    }
+}
+
+
+/// Ana makinedeki SADAK imajını açar (FUSE bağlayıcısı ve imaj aracı için); blok boyutu
+/// süperbloktan okunur.
+#[cfg(feature = "std")]
+pub fn open_image(path: &str, read_only: bool) -> Result<HostFileBlockDevice, FileSystemError> {
+    let mut probe = HostFileBlockDevice::open(path, 512, true).map_err(map_block_device_error_to_fs_error)?;
+    let block_size = Superblock::load_from_device(&mut probe)?.block_size as usize;
+    HostFileBlockDevice::open(path, block_size, read_only).map_err(map_block_device_error_to_fs_error)
+}
+
+/// Verilen boyutta boş (seyrek) bir imaj oluşturur; var olan dosyanın üzerine yazar.
+#[cfg(feature = "std")]
+pub fn create_image(path: &str, block_size: usize, block_count: u64) -> Result<HostFileBlockDevice, FileSystemError> {
+    HostFileBlockDevice::create(path, block_size, block_count).map_err(map_block_device_error_to_fs_error)
+}
+
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+    use crate::encryption::MASTER_KEY_SIZE;
+    use crate::testdevice::SharedMemDevice;
+    use crate::vfs::{Vfs, O_CREAT, O_RDONLY, O_RDWR};
+
+    fn small_config() -> DeviceConfig {
+        let mut config = DeviceConfig::new("img");
+        config.block_size = 512;
+        config.total_blocks = 4096;
+        config.inodes_count = 64;
+        config
+    }
+
+    fn formatted() -> (SharedMemDevice, Arc<SadakFs<SharedMemDevice>>) {
+        let device = SharedMemDevice::new(4096, 512);
+        let fs = Arc::new(SadakFs::format(device.clone(), &small_config(), DeviceType::Other, 7, 1000).unwrap());
+        (device, fs)
+    }
+
+    fn read_all(vfs: &Vfs, path: &str) -> Result<Vec<u8>, VfsError> {
+        let mut file = vfs.open(path, O_RDONLY, 0)?;
+        let mut data = vec![0u8; file.metadata()?.size as usize];
+        let count = file.read_at(0, &mut data)?;
+        data.truncate(count);
+        Ok(data)
+    }
+
+    #[test]
+    fn test_format_mount_and_persist() -> Result<(), VfsError> {
+        let (device, fs) = formatted();
+        let initial = fs.stats();
+        assert_eq!((initial.inodes, initial.free_inodes), (64, 63));
+        let vfs = Vfs::new();
+        vfs.mount("/", fs.clone())?;
+        assert_eq!(vfs.metadata("/")?.mtime, 1000);
+
+        fs.set_time(2000);
+        vfs.mkdir("/etc", 0o755)?;
+        vfs.open("/etc/hostname", O_WRONLY | O_CREAT, 0o644)?.write_at(0, b"sahne\n")?;
+        vfs.symlink("../etc/hostname", "/name")?;
+        vfs.set_metadata("/etc/hostname", &SetMetadata { uid: Some(1000), gid: Some(100), ..SetMetadata::default() })?;
+        vfs.set_xattr("/etc/hostname", "user.kaynak", b"imaj", 0)?;
+
+        // Büyük dosya: doğrudan, dolaylı ve çift dolaylı bloklar (512 bayt blok, 64 işaretçi)
+        let big: Vec<u8> = (0..(12 + 64 + 10) * 512 + 100).map(|i| (i % 253) as u8).collect();
+        vfs.open("/big", O_RDWR | O_CREAT, 0o600)?.write_at(0, &big)?;
+        vfs.sync()?;
+        let used = initial.free_blocks - fs.stats().free_blocks;
+        // 86 veri bloğu (+1 kısmi), 1 dolaylı, 1+1 çift dolaylı, dizin ve bağ blokları, xattr bloğu
+        assert!((91..=95).contains(&used), "used {}", used);
+
+        // Yeniden bağlayınca her şey yerinde
+        drop(vfs);
+        drop(fs);
+        let fs = Arc::new(SadakFs::mount(device.clone(), true).map_err(map_fs_error_to_vfs_error)?);
+        let vfs = Vfs::new();
+        vfs.mount("/", fs.clone())?;
+        assert_eq!(read_all(&vfs, "/etc/hostname")?, b"sahne\n");
+        assert_eq!(read_all(&vfs, "/big")?, big);
+        assert_eq!(vfs.readlink("/name")?, "../etc/hostname");
+        let meta = vfs.metadata("/etc/hostname")?;
+        assert_eq!((meta.uid, meta.gid, meta.mode, meta.mtime), (1000, 100, 0o644, 2000));
+        assert_eq!(vfs.get_xattr("/etc/hostname", "user.kaynak")?, b"imaj");
+        assert_eq!(vfs.metadata("/")?.nlink, 3);
+        let names: Vec<String> = vfs.read_dir("/")?.map(|e| e.name).collect();
+        assert_eq!(names, vec!["big", "etc", "name"]);
+        assert!(matches!(vfs.mkdir("/tmp", 0o755), Err(VfsError::ReadOnly)));
+
+        // Biçimlendirme geometriyi denetler
+        let wrong = SharedMemDevice::new(100, 512);
+        assert!(matches!(SadakFs::format(wrong, &small_config(), DeviceType::Other, 0, 0), Err(FileSystemError::NoSpace(_))));
+        let mut config = small_config();
+        config.total_blocks = 2048;
+        assert!(SadakFs::mount_with_config(device.clone(), &config).is_err());
+        assert!(SadakFs::mount_with_config(device, &small_config()).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncate_unlink_rename_and_xattrs_free_space() -> Result<(), VfsError> {
+        let (_device, fs) = formatted();
+        let vfs = Vfs::new();
+        vfs.mount("/", fs.clone())?;
+        let baseline = fs.stats();
+
+        let data = vec![0xabu8; 100 * 512];
+        let mut file = vfs.open("/f", O_RDWR | O_CREAT, 0o644)?;
+        file.write_at(0, &data)?;
+        // Kırpma sonrası uzatılan kısım sıfır okunur
+        file.set_len(700)?;
+        file.set_len(2000)?;
+        let mut back = vec![0xffu8; 2000];
+        assert_eq!(file.read_at(0, &mut back)?, 2000);
+        assert!(back[..700].iter().all(|&b| b == 0xab) && back[700..].iter().all(|&b| b == 0));
+        // Seyrek yazma
+        file.write_at(40 * 512, b"son")?;
+        assert_eq!(file.metadata()?.size, 40 * 512 + 3);
+        drop(file);
+
+        vfs.mkdir("/a", 0o755)?;
+        vfs.mkdir("/a/b", 0o755)?;
+        vfs.mkdir("/c", 0o755)?;
+        assert!(matches!(vfs.rename("/a", "/a/b/a"), Err(VfsError::InvalidPath(_))));
+        vfs.rename("/a/b", "/c/b")?;
+        assert_eq!((vfs.metadata("/a")?.nlink, vfs.metadata("/c")?.nlink), (2, 3));
+        vfs.rename("/f", "/c/b/g")?;
+        assert_eq!(read_all(&vfs, "/c/b/g")?[..3], [0xab, 0xab, 0xab]);
+        vfs.open("/h", O_WRONLY | O_CREAT, 0o644)?.write_at(0, b"h")?;
+        vfs.rename("/h", "/c/b/g")?; // Üzerine yazar
+        assert_eq!(read_all(&vfs, "/c/b/g")?, b"h");
+        assert!(matches!(vfs.rename("/c/b/g", "/a"), Err(VfsError::IsDirectory)));
+        assert!(matches!(vfs.unlink("/c"), Err(VfsError::NotEmpty)));
+
+        vfs.set_xattr("/c", "user.a", &[1; 100], XATTR_CREATE)?;
+        vfs.set_xattr("/c", "user.b", b"2", 0)?;
+        assert!(matches!(vfs.set_xattr("/c", "user.a", b"x", XATTR_CREATE), Err(VfsError::AlreadyExists)));
+        assert!(matches!(vfs.set_xattr("/c", "user.big", &[0; 600], 0), Err(VfsError::NoSpace)));
+        assert_eq!(vfs.list_xattr("/c")?, vec!["user.a", "user.b"]);
+        vfs.remove_xattr("/c", "user.a")?;
+        vfs.remove_xattr("/c", "user.b")?;
+        assert!(matches!(vfs.remove_xattr("/c", "user.b"), Err(VfsError::NoAttribute)));
+
+        for path in ["/c/b/g", "/c/b", "/c", "/a"] {
+            vfs.unlink(path)?;
+        }
+        assert!(matches!(vfs.metadata("/c"), Err(VfsError::NotFound)));
+        vfs.sync()?;
+        // Tüm bloklar ve inode'lar geri döndü
+        assert_eq!(fs.stats(), baseline);
+        assert!(matches!(vfs.mkdir("/x/y", 0o755), Err(VfsError::NotFound)));
+        assert!(matches!(vfs.mkdir(&format!("/{}", "n".repeat(300)), 0o755), Err(VfsError::InvalidPath(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unlinked_open_file_and_inode_count() -> Result<(), VfsError> {
+        let (device, fs) = formatted();
+        let vfs = Vfs::new();
+        vfs.mount("/", fs.clone())?;
+        let baseline = fs.stats();
+
+        // Silinen dosyanın açık tanıtıcısı kendi verisini görür; inode yeni dosyaya verilmez
+        let mut old = vfs.open("/eski", O_RDWR | O_CREAT, 0o644)?;
+        old.write_at(0, b"eski veri")?;
+        vfs.unlink("/eski")?;
+        vfs.open("/yeni", O_RDWR | O_CREAT, 0o644)?.write_at(0, b"yeni veri")?;
+        old.write_at(0, b"ESKI")?;
+        old.set_len(2)?;
+        assert_eq!(read_all(&vfs, "/yeni")?, b"yeni veri");
+        let mut back = [0u8; 16];
+        assert_eq!(old.read_at(0, &mut back)?, 2);
+        assert_eq!((&back[..2], old.metadata()?.nlink), (&b"ES"[..], 0));
+        assert_eq!(fs.stats().free_inodes, baseline.free_inodes - 2);
+        // Son tanıtıcı kapanınca inode ve blokları boşalır
+        drop(old);
+        assert_eq!(fs.stats().free_inodes, baseline.free_inodes - 1);
+        vfs.unlink("/yeni")?;
+        assert_eq!(fs.stats(), baseline);
+
+        // Kapanmadan kalan yetim ve bozuk boş inode sayacı bağlanırken düzeltilir
+        let mut orphan = vfs.open("/yetim", O_RDWR | O_CREAT, 0o644)?;
+        orphan.write_at(0, &[7; 3 * 512])?;
+        vfs.unlink("/yetim")?;
+        let mut superblock = fs.superblock();
+        superblock.free_inodes_count = 0;
+        superblock.save_to_device(&mut device.clone()).map_err(map_fs_error_to_vfs_error)?;
+        core::mem::forget(orphan);
+        drop(vfs);
+        drop(fs);
+        let read_only = SadakFs::mount(device.clone(), true).map_err(map_fs_error_to_vfs_error)?;
+        assert_eq!(read_only.stats().free_inodes, baseline.free_inodes - 1);
+        let fs = SadakFs::mount(device, false).map_err(map_fs_error_to_vfs_error)?;
+        assert_eq!(fs.stats(), baseline);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsynced_allocations_and_mount_checks() -> Result<(), VfsError> {
+        let (device, fs) = formatted();
+        let vfs = Vfs::new();
+        vfs.mount("/", fs.clone())?;
+        let first: Vec<u8> = (0..20 * 512).map(|i| (i % 251) as u8).collect();
+        vfs.open("/ilk", O_RDWR | O_CREAT, 0o644)?.write_at(0, &first)?;
+        // sync yok: bitmap yine de aygıtta, yeni ayırmalar eski dosyanın bloklarını almaz
+        drop(vfs);
+        drop(fs);
+        let fs = Arc::new(SadakFs::mount(device.clone(), false).map_err(map_fs_error_to_vfs_error)?);
+        let vfs = Vfs::new();
+        vfs.mount("/", fs.clone())?;
+        vfs.open("/ikinci", O_RDWR | O_CREAT, 0o644)?.write_at(0, &[0xEE; 20 * 512])?;
+        assert_eq!(read_all(&vfs, "/ilk")?, first);
+        vfs.sync()?;
+        drop(vfs);
+        drop(fs);
+
+        // Bilinmeyen yerleşim sürümü ve aygıttan büyük birim bağlanmaz
+        let mut raw = device.clone();
+        let good = Superblock::load_from_device(&mut raw).map_err(map_fs_error_to_vfs_error)?;
+        let mut old = good;
+        old.version = 1;
+        old.save_to_device(&mut raw).map_err(map_fs_error_to_vfs_error)?;
+        assert!(matches!(SadakFs::mount(device.clone(), true), Err(FileSystemError::NotSupported(_))));
+        let mut big = good;
+        big.blocks_count = 8192;
+        big.save_to_device(&mut raw).map_err(map_fs_error_to_vfs_error)?;
+        assert!(matches!(SadakFs::mount(device.clone(), true), Err(FileSystemError::Corrupted { .. })));
+        let mut outside = good;
+        outside.inode_table_start = 4090;
+        outside.save_to_device(&mut raw).map_err(map_fs_error_to_vfs_error)?;
+        assert!(matches!(SadakFs::mount(device.clone(), true), Err(FileSystemError::Corrupted { .. })));
+        good.save_to_device(&mut raw).map_err(map_fs_error_to_vfs_error)?;
+        let fs = Arc::new(SadakFs::mount(device, false).map_err(map_fs_error_to_vfs_error)?);
+
+        // Diskteki geçersiz dizin girdisi adı bozulma sayılır
+        {
+            let mut state = fs.shared.state.lock();
+            let mut root = state.read_inode(good.root_inode)?;
+            let mut entries = state.read_dir_entries(good.root_inode, &mut root)?;
+            entries.push((String::from(".."), good.root_inode, FileType::Directory));
+            state.write_dir_entries(good.root_inode, &mut root, &entries)?;
+            state.write_inode(good.root_inode, &root)?;
+        }
+        let vfs = Vfs::new();
+        vfs.mount("/", fs)?;
+        assert!(vfs.read_dir("/").is_err());
+        Ok(())
+    }
+
+    // Her çağrıda farklı nonce veren deterministik kaynak
+    fn nonces(seed: u8) -> Box<dyn FnMut() -> [u8; FILE_NONCE_SIZE] + Send> {
+        let mut counter = seed;
+        Box::new(move || {
+            counter = counter.wrapping_add(1);
+            [counter; FILE_NONCE_SIZE]
+        })
+    }
+
+    #[test]
+    fn test_encrypted_directory_survives_remount() -> Result<(), VfsError> {
+        let keyring = Arc::new(Mutex::new(Keyring::new()));
+        let master_key: Vec<u8> = (0..MASTER_KEY_SIZE as u8).collect();
+        let key_id = keyring.lock().add_key(&master_key).map_err(map_fs_error_to_vfs_error)?;
+        let secret: Vec<u8> = b"gizli icerik ".iter().cycle().take(700).copied().collect();
+
+        let (device, fs) = formatted();
+        fs.enable_encryption(keyring.clone(), nonces(0));
+        let vfs = Vfs::new();
+        vfs.mount("/", fs.clone())?;
+        vfs.mkdir("/kasa", 0o700)?;
+        fs.set_encryption_policy(vfs.metadata("/kasa")?.inode, EncryptionPolicy::new(key_id))?;
+        vfs.open("/kasa/notlar.txt", O_WRONLY | O_CREAT, 0o600)?.write_at(0, &secret)?;
+        vfs.symlink("notlar.txt", "/kasa/bag")?;
+        vfs.open("/acik", O_WRONLY | O_CREAT, 0o644)?.write_at(0, b"acik")?;
+        // Şifresiz dosya şifreli dizine taşınamaz; bağlam özniteliği görünmez ve korunur
+        assert!(matches!(vfs.rename("/acik", "/kasa/acik"), Err(VfsError::CrossDevice)));
+        assert!(vfs.list_xattr("/kasa/notlar.txt")?.is_empty());
+        assert!(matches!(vfs.remove_xattr("/kasa", ENCRYPTION_XATTR), Err(VfsError::PermissionDenied)));
+        assert!(fs.set_encryption_policy(vfs.metadata("/")?.inode, EncryptionPolicy::new(key_id)).is_err()); // Boş olmayan dizin
+        vfs.sync()?;
+        drop(vfs);
+        drop(fs);
+
+        // Aygıtta ne içerik ne de dosya adı açık metin olarak bulunur
+        let raw: Vec<u8> = device.lock().blocks.concat();
+        assert!(!raw.windows(13).any(|w| w == b"gizli icerik "));
+        assert!(!raw.windows(10).any(|w| w == b"notlar.txt"));
+
+        // Yeniden bağlayınca bağlamlar öznitelikten yüklenir
+        let fs = Arc::new(SadakFs::mount(device.clone(), false).map_err(map_fs_error_to_vfs_error)?);
+        fs.enable_encryption(keyring.clone(), nonces(100));
+        let vfs = Vfs::new();
+        vfs.mount("/", fs.clone())?;
+        let names: Vec<String> = vfs.read_dir("/kasa")?.map(|e| e.name).collect();
+        assert_eq!(names, vec!["bag", "notlar.txt"]);
+        assert_eq!(read_all(&vfs, "/kasa/notlar.txt")?, secret);
+        assert_eq!(vfs.readlink("/kasa/bag")?, "notlar.txt");
+        let mut file = vfs.open("/kasa/notlar.txt", O_RDWR, 0)?;
+        file.write_at(698, b"!!!")?;
+        file.set_len(600)?;
+        file.set_len(1024)?;
+        let mut back = vec![0xffu8; 1024];
+        assert_eq!(file.read_at(0, &mut back)?, 1024);
+        assert!(back[..600] == secret[..600] && back[600..].iter().all(|&b| b == 0));
+        drop(file);
+        vfs.sync()?;
+        drop(vfs);
+        drop(fs);
+
+        // Anahtar yokken adlar kodlanmış görünür ve içerik okunamaz
+        let fs = Arc::new(SadakFs::mount(device.clone(), true).map_err(map_fs_error_to_vfs_error)?);
+        fs.enable_encryption(Arc::new(Mutex::new(Keyring::new())), nonces(200));
+        let vfs = Vfs::new();
+        vfs.mount("/", fs)?;
+        let entries: Vec<DirEntry> = vfs.read_dir("/kasa")?.collect();
+        assert_eq!(entries.len(), 2);
+        let file = entries.iter().find(|e| e.file_type == FileType::File).unwrap();
+        assert_ne!(file.name, "notlar.txt");
+        assert!(matches!(vfs.open(&format!("/kasa/{}", file.name), O_RDONLY, 0), Err(VfsError::PermissionDenied)));
+        // Şifreleme etkin değilken şifreli dizine erişilmez
+        let vfs = Vfs::new();
+        vfs.mount("/", Arc::new(SadakFs::mount(device, true).map_err(map_fs_error_to_vfs_error)?))?;
+        assert_eq!(read_all(&vfs, "/acik")?, b"acik");
+        assert!(matches!(vfs.read_dir("/kasa"), Err(VfsError::PermissionDenied)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_image_file_round_trip() -> Result<(), FileSystemError> {
+        let path = std::env::temp_dir().join(format!("sadakfs-test-{}.img", std::process::id()));
+        let path = path.to_str().unwrap();
+        let image = create_image(path, 512, 4096)?;
+        let fs = SadakFs::format(image, &small_config(), DeviceType::Other, 1, 0)?;
+        let mut file = fs.root().create("veri", FileType::File, 0o644).map_err(map_vfs_error_to_fs_error)?.open(O_RDWR).map_err(map_vfs_error_to_fs_error)?;
+        file.write_at(0, b"imaj").map_err(map_vfs_error_to_fs_error)?;
+        fs.sync().map_err(map_vfs_error_to_fs_error)?;
+        drop((file, fs));
+
+        let fs = SadakFs::mount(open_image(path, true)?, true)?;
+        let mut buf = [0u8; 8];
+        let count = fs.root().lookup("veri").and_then(|n| n.open(O_RDONLY)).and_then(|mut f| f.read_at(0, &mut buf)).map_err(map_vfs_error_to_fs_error)?;
+        assert_eq!(&buf[..count], b"imaj");
+        assert!(open_image(path, true)?.write_block(0, &[0; 512]).is_err());
+        std::fs::remove_file(path).unwrap();
+        Ok(())
+    }
+}
+
+// Removed redundant print module and panic handler boilerplate.
+// The empty lib module configuration is kept.
+#[cfg(not(any(feature = "std", test)))] // Only when not building std or test
+pub mod lib {} // Keep the empty lib module if needed for the crate structure
@@ -27,6 +27,11 @@ use core::result::Result; // Use core::result::Result
 use core::fmt; // For fmt::Display
 use core::ops::Drop; // For Drop trait
 
+// alloc types used by the TRIM payload helpers and error messages
+use alloc::vec::Vec;
+use alloc::string::{String, ToString};
+use alloc::format;
+
 // Import the standard BlockDevice trait and its error type
 use crate::blockdevice::{BlockDevice, BlockDeviceError}; // Assuming these are in crate::blockdevice
 
@@ -34,18 +39,53 @@ use crate::blockdevice::{BlockDevice, BlockDeviceError}; // Assuming these are i
 use crate::config::SataConfig;
 
 
+// ATA DATA SET MANAGEMENT (TRIM) definitions (ACS-3, 7.10)
+pub const ATA_CMD_DATA_SET_MANAGEMENT: u8 = 0x06; // Command opcode
+pub const ATA_DSM_FEATURE_TRIM: u16 = 0x0001; // Features register: TRIM bit
+pub const ATA_TRIM_MAX_RANGE_BLOCKS: u64 = 0xFFFF; // Range length is a 16-bit field
+pub const ATA_TRIM_ENTRIES_PER_SECTOR: usize = 64; // 8-byte entries per 512-byte payload sector
+pub const ATA_TRIM_PAYLOAD_SECTOR_SIZE: usize = 512;
+
+/// Builds the DSM TRIM payload for a block range.
+/// Each 8-byte little-endian entry holds a 48-bit starting LBA and a 16-bit block count;
+/// longer ranges are split into several entries and the payload is zero-padded to whole
+/// 512-byte sectors (zero entries are ignored by the drive).
+///
+/// # Returns
+///
+/// The payload bytes (empty if `block_count` is 0).
+pub fn build_trim_ranges(start_lba: u64, block_count: u64) -> Vec<u8> {
+    let mut payload = Vec::new(); // Requires alloc
+    let mut lba = start_lba;
+    let mut remaining = block_count;
+    while remaining > 0 {
+        let len = core::cmp::min(remaining, ATA_TRIM_MAX_RANGE_BLOCKS);
+        let entry = (lba & 0x0000_FFFF_FFFF_FFFF) | (len << 48);
+        payload.extend_from_slice(&entry.to_le_bytes());
+        lba += len;
+        remaining -= len;
+    }
+    let padded_len = (payload.len() + ATA_TRIM_PAYLOAD_SECTOR_SIZE - 1) / ATA_TRIM_PAYLOAD_SECTOR_SIZE * ATA_TRIM_PAYLOAD_SECTOR_SIZE;
+    payload.resize(padded_len, 0);
+    payload
+}
+
+/// Decodes a DSM TRIM payload back into (start LBA, block count) ranges, skipping empty entries.
+pub fn parse_trim_ranges(payload: &[u8]) -> Vec<(u64, u64)> {
+    payload
+        .chunks_exact(8)
+        .map(|entry| u64::from_le_bytes(entry.try_into().unwrap()))
+        .filter(|entry| (entry >> 48) != 0)
+        .map(|entry| (entry & 0x0000_FFFF_FFFF_FFFF, entry >> 48))
+        .collect() // Requires alloc
+}
+
+
 // Removed custom SataError enum and its From implementations to BlockDeviceError
 // We will map SahneError/io::Error directly to BlockDeviceError::IoError
 // and handle other logical errors as BlockDeviceError variants (e.g., InvalidParameter, DeviceError if they exist in the trait).
 
 
-// Helper function to map SahneError to FileSystemError (copied from other files)
-#[cfg(not(feature = "std"))]
-fn map_sahne_error_to_fs_error(e: SahneError) -> FileSystemError {
-    FileSystemError::IOError(format!("SahneError: {:?}", e)) // Using Debug format for SahneError
-    // TODO: Implement a proper mapping based on SahneError variants
-}
-
 // Helper function to map std::io::Error to FileSystemError (copied from other files)
 #[cfg(feature = "std")]
 fn map_std_io_error_to_fs_error(e: StdIOError) -> FileSystemError {
@@ -68,7 +108,7 @@ fn map_block_device_error_to_fs_error(e: BlockDeviceError) -> FileSystemError {
              map_std_io_error_to_fs_error(io_err)
              #[cfg(not(feature = "std"))]
              // In no_std, BlockDeviceError::IoError wraps SahneError
-             map_sahne_error_to_fs_error(io_err)
+             FileSystemError::from(io_err)
         },
         BlockDeviceError::BlockSizeError(msg) => FileSystemError::InvalidData(format!("Block size mismatch or error: {}", msg)), // Map BlockSizeError to InvalidData
         // Map other BlockDeviceError variants if they exist in the standardized trait
@@ -294,7 +334,7 @@ impl<RWS: Read + Write + Seek + Drop> SataDevice<RWS> {
              return Err(map_block_device_error_to_fs_error(BlockDeviceError::BlockSizeError(String::from("Block size cannot be zero.")))); // Requires alloc
          }
          let flags = fs::O_RDWR | fs::O_CREAT;
-         let handle = fs::open(path, flags).map_err(|e| map_sahne_error_to_fs_error(e))?; // Map SahneError to FileSystemError
+         let handle = fs::open(path, flags)?; // Map SahneError to FileSystemError
 
          // Get or set file size for SahneResourceReadWriteSeek
          // In Sahne64, setting file size might require fs::ftruncate or similar.
@@ -307,7 +347,7 @@ impl<RWS: Read + Write + Seek + Drop> SataDevice<RWS> {
               Err(e) => {
                   // If fstat fails, try to release the handle and return error
                   let _ = resource::release(handle);
-                  return Err(map_sahne_error_to_fs_error(e)); // Map SahneError to FileSystemError
+                  return Err(FileSystemError::from(e)); // Map SahneError to FileSystemError
               }
          };
 
@@ -316,7 +356,7 @@ impl<RWS: Read + Write + Seek + Drop> SataDevice<RWS> {
               #[cfg(not(feature = "no_fs_ftruncate"))] // Assume ftruncate is available unless this feature is set
               {
                    crate::println!("INFO: Resizing SATA device file from {} to {}", actual_size, file_size); // Use no_std print
-                   if let Err(e) = fs::ftruncate(handle, file_size as usize).map_err(|e| map_sahne_error_to_fs_error(e)) {
+                   if let Err(e) = fs::ftruncate(handle, file_size as usize).map_err(|e| FileSystemError::from(e)) {
                         // Error setting file size. Decide if fatal or warning.
                         // For a simulation, maybe warn and continue, assuming writes will extend.
                          crate::eprintln!("WARN: Failed to set SATA device file size to {}. Error: {:?}", file_size, e); // Use no_std print
@@ -431,6 +471,544 @@ impl<RWS: Read + Write + Seek + Drop> BlockDevice for SataDevice<RWS> { // Imple
     fn block_count(&self) -> u64 { // Return u64 for block_count (assuming trait includes this)
         self.config.block_count
     }
+
+    /// Discards a block range with ATA DATA SET MANAGEMENT (TRIM).
+    /// The TRIM payload is built exactly as it would be sent to the drive; this file-backed
+    /// simulation then applies each range by zero-filling it (deterministic read-zero after TRIM).
+    fn discard_blocks(&mut self, start_block: u64, block_count: u64) -> Result<(), BlockDeviceError> {
+        let in_bounds = start_block.checked_add(block_count).map_or(false, |end| end <= self.config.block_count);
+        if !in_bounds {
+            return Err(BlockDeviceError::InvalidParameter(format!("Discard range {}+{} is out of bounds. Total blocks: {}", start_block, block_count, self.config.block_count))); // Requires alloc
+        }
+
+        let payload = build_trim_ranges(start_block, block_count);
+        let zero_block = vec![0u8; self.config.block_size as usize]; // Requires alloc
+        for (lba, len) in parse_trim_ranges(&payload) {
+            for block_id in lba..lba + len {
+                self.write_block(block_id, &zero_block)?;
+            }
+        }
+
+        Ok(()) // Return success
+    }
+}
+
+
+// ---------------------------------------------------------------------------------------------
+// AHCI / ATA command layer
+//
+// Commands are expressed the way an AHCI HBA sees them: a Register Host-to-Device FIS in the
+// command table, an optional data buffer described by the PRDT, and the Register Device-to-Host
+// FIS the device posts into the received-FIS area on completion. `AhciPort` is the boundary to
+// the HBA (a real port driver or a simulated port in tests); `AhciSataDevice` implements the ATA
+// commands on top of it and exposes the drive as a BlockDevice.
+// ---------------------------------------------------------------------------------------------
+
+// FIS types (SATA 3.x, 10.5)
+pub const FIS_TYPE_REG_H2D: u8 = 0x27;
+pub const FIS_TYPE_REG_D2H: u8 = 0x34;
+pub const FIS_REG_H2D_SIZE: usize = 20;
+pub const FIS_REG_D2H_SIZE: usize = 20;
+const FIS_H2D_COMMAND_BIT: u8 = 0x80; // "C" bit: the FIS updates the Command register
+
+// ATA commands (ACS-3)
+pub const ATA_CMD_IDENTIFY_DEVICE: u8 = 0xEC;
+pub const ATA_CMD_READ_DMA_EXT: u8 = 0x25;
+pub const ATA_CMD_WRITE_DMA_EXT: u8 = 0x35;
+pub const ATA_CMD_FLUSH_CACHE_EXT: u8 = 0xEA;
+pub const ATA_CMD_SMART: u8 = 0xB0;
+pub const ATA_SMART_READ_DATA: u16 = 0xD0; // Features register for SMART READ DATA
+pub const ATA_SMART_LBA_SIGNATURE: u64 = 0x00C2_4F00; // LBA mid = 0x4F, LBA high = 0xC2
+
+// Status register bits
+pub const ATA_STATUS_ERR: u8 = 0x01;
+pub const ATA_STATUS_DRQ: u8 = 0x08;
+pub const ATA_STATUS_DF: u8 = 0x20;
+pub const ATA_STATUS_DRDY: u8 = 0x40;
+pub const ATA_STATUS_BSY: u8 = 0x80;
+
+// Error register bits
+pub const ATA_ERROR_ABRT: u8 = 0x04; // Command aborted
+pub const ATA_ERROR_IDNF: u8 = 0x10; // Address not found
+pub const ATA_ERROR_UNC: u8 = 0x40; // Uncorrectable data
+pub const ATA_ERROR_ICRC: u8 = 0x80; // Interface CRC error
+
+pub const ATA_DEVICE_LBA: u8 = 0x40; // Device register: LBA addressing
+pub const ATA_IDENTIFY_SIZE: usize = 512;
+pub const ATA_SMART_DATA_SIZE: usize = 512;
+/// Largest transfer of one READ/WRITE DMA EXT command (count 0 encodes 65536 sectors).
+pub const ATA_MAX_SECTORS_PER_COMMAND: u64 = 65536;
+
+/// Register Host-to-Device FIS (command FIS).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterH2dFis {
+    pub command: u8,
+    pub features: u16,
+    pub lba: u64, // 48-bit
+    pub count: u16,
+    pub device: u8,
+    pub control: u8,
+}
+
+impl RegisterH2dFis {
+    /// Command FIS with LBA addressing.
+    pub fn command(command: u8, features: u16, lba: u64, count: u16) -> Self {
+        RegisterH2dFis { command, features, lba, count, device: ATA_DEVICE_LBA, control: 0 }
+    }
+
+    pub fn to_bytes(&self) -> [u8; FIS_REG_H2D_SIZE] {
+        let mut fis = [0u8; FIS_REG_H2D_SIZE];
+        fis[0] = FIS_TYPE_REG_H2D;
+        fis[1] = FIS_H2D_COMMAND_BIT;
+        fis[2] = self.command;
+        fis[3] = self.features as u8;
+        fis[4] = self.lba as u8;
+        fis[5] = (self.lba >> 8) as u8;
+        fis[6] = (self.lba >> 16) as u8;
+        fis[7] = self.device;
+        fis[8] = (self.lba >> 24) as u8;
+        fis[9] = (self.lba >> 32) as u8;
+        fis[10] = (self.lba >> 40) as u8;
+        fis[11] = (self.features >> 8) as u8;
+        fis[12] = self.count as u8;
+        fis[13] = (self.count >> 8) as u8;
+        fis[15] = self.control;
+        fis
+    }
+
+    /// Decodes a command FIS (as the device side of a port does).
+    pub fn parse(fis: &[u8]) -> Option<Self> {
+        if fis.len() < FIS_REG_H2D_SIZE || fis[0] != FIS_TYPE_REG_H2D || fis[1] & FIS_H2D_COMMAND_BIT == 0 {
+            return None;
+        }
+        let lba = fis[4] as u64 | (fis[5] as u64) << 8 | (fis[6] as u64) << 16 | (fis[8] as u64) << 24 | (fis[9] as u64) << 32 | (fis[10] as u64) << 40;
+        Some(RegisterH2dFis {
+            command: fis[2],
+            features: fis[3] as u16 | (fis[11] as u16) << 8,
+            lba,
+            count: fis[12] as u16 | (fis[13] as u16) << 8,
+            device: fis[7],
+            control: fis[15],
+        })
+    }
+}
+
+/// Register Device-to-Host FIS (command completion status).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterD2hFis {
+    pub status: u8,
+    pub error: u8,
+    pub lba: u64,
+    pub count: u16,
+    pub device: u8,
+}
+
+impl RegisterD2hFis {
+    /// Successful completion (DRDY set).
+    pub fn ok() -> Self {
+        RegisterD2hFis { status: ATA_STATUS_DRDY, error: 0, lba: 0, count: 0, device: 0 }
+    }
+
+    /// Failed completion with the given error register; `lba` reports the failing sector.
+    pub fn failed(error: u8, lba: u64) -> Self {
+        RegisterD2hFis { status: ATA_STATUS_DRDY | ATA_STATUS_ERR, error, lba, count: 0, device: ATA_DEVICE_LBA }
+    }
+
+    pub fn to_bytes(&self) -> [u8; FIS_REG_D2H_SIZE] {
+        let mut fis = [0u8; FIS_REG_D2H_SIZE];
+        fis[0] = FIS_TYPE_REG_D2H;
+        fis[1] = 0x40; // Interrupt bit
+        fis[2] = self.status;
+        fis[3] = self.error;
+        fis[4] = self.lba as u8;
+        fis[5] = (self.lba >> 8) as u8;
+        fis[6] = (self.lba >> 16) as u8;
+        fis[7] = self.device;
+        fis[8] = (self.lba >> 24) as u8;
+        fis[9] = (self.lba >> 32) as u8;
+        fis[10] = (self.lba >> 40) as u8;
+        fis[12] = self.count as u8;
+        fis[13] = (self.count >> 8) as u8;
+        fis
+    }
+
+    pub fn parse(fis: &[u8]) -> Option<Self> {
+        if fis.len() < FIS_REG_D2H_SIZE || fis[0] != FIS_TYPE_REG_D2H {
+            return None;
+        }
+        let lba = fis[4] as u64 | (fis[5] as u64) << 8 | (fis[6] as u64) << 16 | (fis[8] as u64) << 24 | (fis[9] as u64) << 32 | (fis[10] as u64) << 40;
+        Some(RegisterD2hFis { status: fis[2], error: fis[3], lba, count: fis[12] as u16 | (fis[13] as u16) << 8, device: fis[7] })
+    }
+
+    /// True if the device reported an error or a device fault.
+    pub fn is_error(&self) -> bool {
+        self.status & (ATA_STATUS_ERR | ATA_STATUS_DF) != 0
+    }
+}
+
+/// Data phase of a command, as described by the PRDT.
+pub enum AhciData<'a> {
+    None,
+    In(&'a mut [u8]), // Device to host (read)
+    Out(&'a [u8]), // Host to device (write)
+}
+
+/// Errors of the AHCI/ATA layer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AtaError {
+    /// The HBA port failed (link down, task file error interrupt, timeout...).
+    Port(String),
+    /// The device completed the command with ERR/DF set.
+    Command { command: u8, status: u8, error: u8, lba: u64 },
+    /// IDENTIFY DEVICE data is unusable.
+    InvalidIdentify(String),
+    /// A feature is not supported by the drive (e.g. no LBA48, no TRIM, SMART disabled).
+    NotSupported(&'static str),
+    /// SMART data structure checksum mismatch.
+    SmartChecksum,
+    /// LBA range outside the drive.
+    OutOfRange { lba: u64, count: u64 },
+}
+
+impl fmt::Display for AtaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AtaError::Port(msg) => write!(f, "AHCI port error: {}", msg),
+            AtaError::Command { command, status, error, lba } => write!(
+                f,
+                "ATA command 0x{:02X} failed (status 0x{:02X}, error 0x{:02X}, LBA {})",
+                command, status, error, lba
+            ),
+            AtaError::InvalidIdentify(msg) => write!(f, "Invalid IDENTIFY DEVICE data: {}", msg),
+            AtaError::NotSupported(what) => write!(f, "ATA feature not supported: {}", what),
+            AtaError::SmartChecksum => write!(f, "SMART data checksum mismatch"),
+            AtaError::OutOfRange { lba, count } => write!(f, "LBA range {}+{} is out of bounds", lba, count),
+        }
+    }
+}
+
+/// Maps AtaError to the BlockDeviceError kinds callers can act on.
+fn map_ata_error_to_block_device_error(e: AtaError) -> BlockDeviceError {
+    match e {
+        AtaError::OutOfRange { .. } => BlockDeviceError::InvalidParameter(e.to_string()),
+        AtaError::Command { error, .. } if error & ATA_ERROR_IDNF != 0 => BlockDeviceError::InvalidParameter(e.to_string()),
+        AtaError::NotSupported(_) => BlockDeviceError::NotSupported(e.to_string()),
+        other => BlockDeviceError::DeviceError(other.to_string()),
+    }
+}
+
+/// One AHCI port: builds the command header/table for the FIS and data buffer, issues the
+/// command slot and waits for completion.
+pub trait AhciPort {
+    /// Executes a command.
+    ///
+    /// # Arguments
+    ///
+    /// * `command_fis`: Register H2D FIS placed at the start of the command table.
+    /// * `data`: Data phase (PRDT); `AhciData::In` buffers are filled by the device.
+    ///
+    /// # Returns
+    ///
+    /// The Register D2H FIS from the received-FIS area, or AtaError::Port if the HBA reported
+    /// a port-level failure.
+    fn execute(&mut self, command_fis: &[u8; FIS_REG_H2D_SIZE], data: AhciData<'_>) -> Result<[u8; FIS_REG_D2H_SIZE], AtaError>;
+}
+
+/// Parsed IDENTIFY DEVICE data (ACS-3, 7.12.7).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtaIdentify {
+    pub model: String,
+    pub serial: String,
+    pub firmware: String,
+    pub lba48: bool,
+    /// Addressable logical sectors.
+    pub sector_count: u64,
+    pub logical_sector_size: u32,
+    pub physical_sector_size: u32,
+    pub trim_supported: bool,
+    pub trim_zeroes: bool, // Read after TRIM returns zeros
+    pub max_dsm_blocks: u16, // 512-byte DSM payload blocks per command (0: unspecified)
+    pub smart_supported: bool,
+    pub smart_enabled: bool,
+    pub write_cache_enabled: bool,
+    pub rotation_rate: u16, // 1: non-rotating (SSD)
+}
+
+impl AtaIdentify {
+    /// Parses the 256-word IDENTIFY DEVICE block.
+    pub fn parse(data: &[u8]) -> Result<Self, AtaError> {
+        if data.len() < ATA_IDENTIFY_SIZE {
+            return Err(AtaError::InvalidIdentify(format!("{} bytes", data.len())));
+        }
+        let word = |n: usize| u16::from_le_bytes([data[n * 2], data[n * 2 + 1]]);
+        // ATA strings store two characters per word, first character in the high byte
+        let string = |first: usize, last: usize| -> String {
+            let mut s = String::new();
+            for n in first..=last {
+                let w = word(n);
+                s.push((w >> 8) as u8 as char);
+                s.push((w & 0xFF) as u8 as char);
+            }
+            String::from(s.trim())
+        };
+
+        let lba48 = word(83) & (1 << 10) != 0;
+        let sector_count = if lba48 {
+            word(100) as u64 | (word(101) as u64) << 16 | (word(102) as u64) << 32 | (word(103) as u64) << 48
+        } else {
+            word(60) as u64 | (word(61) as u64) << 16
+        };
+        if sector_count == 0 {
+            return Err(AtaError::InvalidIdentify(String::from("zero capacity")));
+        }
+
+        // Word 106: valid if bit 14 set and bit 15 clear
+        let sector_info = word(106);
+        let (mut logical, mut physical) = (512u32, 512u32);
+        if sector_info & 0xC000 == 0x4000 {
+            if sector_info & (1 << 12) != 0 {
+                logical = (word(117) as u32 | (word(118) as u32) << 16) * 2; // Size in words
+            }
+            if sector_info & (1 << 13) != 0 {
+                physical = logical << (sector_info & 0x0F);
+            } else {
+                physical = logical;
+            }
+        }
+        if logical < 512 || !logical.is_power_of_two() {
+            return Err(AtaError::InvalidIdentify(format!("logical sector size {}", logical)));
+        }
+
+        Ok(AtaIdentify {
+            model: string(27, 46),
+            serial: string(10, 19),
+            firmware: string(23, 26),
+            lba48,
+            sector_count,
+            logical_sector_size: logical,
+            physical_sector_size: physical,
+            trim_supported: word(169) & 1 != 0,
+            trim_zeroes: word(69) & (1 << 5) != 0,
+            max_dsm_blocks: word(105),
+            smart_supported: word(82) & 1 != 0,
+            smart_enabled: word(85) & 1 != 0,
+            write_cache_enabled: word(85) & (1 << 5) != 0,
+            rotation_rate: word(217),
+        })
+    }
+}
+
+/// One SMART attribute entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SmartAttribute {
+    pub id: u8,
+    pub flags: u16,
+    pub current: u8, // Normalized value
+    pub worst: u8,
+    pub raw: u64, // 48-bit raw value
+}
+
+/// SMART READ DATA structure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmartData {
+    pub attributes: Vec<SmartAttribute>,
+}
+
+pub const SMART_ATTR_REALLOCATED_SECTORS: u8 = 5;
+pub const SMART_ATTR_POWER_ON_HOURS: u8 = 9;
+pub const SMART_ATTR_TEMPERATURE: u8 = 194;
+
+impl SmartData {
+    /// Parses the 512-byte SMART data: 30 attribute slots of 12 bytes from offset 2 and a
+    /// checksum byte that makes the sum of all bytes zero.
+    pub fn parse(data: &[u8]) -> Result<Self, AtaError> {
+        if data.len() < ATA_SMART_DATA_SIZE {
+            return Err(AtaError::SmartChecksum);
+        }
+        let sum = data[..ATA_SMART_DATA_SIZE].iter().fold(0u8, |acc, b| acc.wrapping_add(*b));
+        if sum != 0 {
+            return Err(AtaError::SmartChecksum);
+        }
+        let attributes = data[2..2 + 30 * 12]
+            .chunks_exact(12)
+            .filter(|entry| entry[0] != 0)
+            .map(|entry| {
+                let mut raw = [0u8; 8];
+                raw[..6].copy_from_slice(&entry[5..11]);
+                SmartAttribute {
+                    id: entry[0],
+                    flags: u16::from_le_bytes([entry[1], entry[2]]),
+                    current: entry[3],
+                    worst: entry[4],
+                    raw: u64::from_le_bytes(raw),
+                }
+            })
+            .collect(); // Requires alloc
+        Ok(SmartData { attributes })
+    }
+
+    /// Looks up an attribute by id.
+    pub fn attribute(&self, id: u8) -> Option<&SmartAttribute> {
+        self.attributes.iter().find(|a| a.id == id)
+    }
+
+    /// Current temperature in °C (attribute 194, lowest raw byte).
+    pub fn temperature_celsius(&self) -> Option<u8> {
+        self.attribute(SMART_ATTR_TEMPERATURE).map(|a| a.raw as u8)
+    }
+}
+
+/// SATA drive driven through ATA commands on an AHCI port.
+pub struct AhciSataDevice<P: AhciPort> {
+    port: P,
+    identify: AtaIdentify,
+}
+
+impl<P: AhciPort> AhciSataDevice<P> {
+    /// Identifies the drive on the port. LBA48 (READ/WRITE DMA EXT) is required.
+    ///
+    /// # Returns
+    ///
+    /// The device or AtaError (Port, Command, InvalidIdentify, NotSupported if no LBA48).
+    pub fn new(mut port: P) -> Result<Self, AtaError> {
+        let mut data = vec![0u8; ATA_IDENTIFY_SIZE]; // Requires alloc
+        Self::issue(&mut port, RegisterH2dFis::command(ATA_CMD_IDENTIFY_DEVICE, 0, 0, 0), AhciData::In(&mut data))?;
+        let identify = AtaIdentify::parse(&data)?;
+        if !identify.lba48 {
+            return Err(AtaError::NotSupported("48-bit LBA"));
+        }
+        Ok(AhciSataDevice { port, identify })
+    }
+
+    /// IDENTIFY DEVICE data read at initialization.
+    pub fn identify(&self) -> &AtaIdentify {
+        &self.identify
+    }
+
+    /// Gives access to the port.
+    pub fn port_mut(&mut self) -> &mut P {
+        &mut self.port
+    }
+
+    fn issue(port: &mut P, fis: RegisterH2dFis, data: AhciData<'_>) -> Result<RegisterD2hFis, AtaError> {
+        let raw = port.execute(&fis.to_bytes(), data)?;
+        let status = RegisterD2hFis::parse(&raw).ok_or_else(|| AtaError::Port(String::from("Malformed D2H register FIS")))?;
+        if status.is_error() {
+            return Err(AtaError::Command { command: fis.command, status: status.status, error: status.error, lba: status.lba });
+        }
+        Ok(status)
+    }
+
+    fn check_range(&self, lba: u64, count: u64) -> Result<(), AtaError> {
+        if lba.checked_add(count).map_or(true, |end| end > self.identify.sector_count) {
+            return Err(AtaError::OutOfRange { lba, count });
+        }
+        Ok(())
+    }
+
+    /// READ DMA EXT; `buf` must hold a whole number of logical sectors. Large reads are split
+    /// into commands of at most 65536 sectors.
+    pub fn read_dma_ext(&mut self, lba: u64, buf: &mut [u8]) -> Result<(), AtaError> {
+        let sector = self.identify.logical_sector_size as usize;
+        let count = (buf.len() / sector) as u64;
+        self.check_range(lba, count)?;
+        for (i, chunk) in buf.chunks_mut(ATA_MAX_SECTORS_PER_COMMAND as usize * sector).enumerate() {
+            let chunk_lba = lba + i as u64 * ATA_MAX_SECTORS_PER_COMMAND;
+            let sectors = (chunk.len() / sector) as u64;
+            let fis = RegisterH2dFis::command(ATA_CMD_READ_DMA_EXT, 0, chunk_lba, sectors as u16); // 65536 encodes as 0
+            Self::issue(&mut self.port, fis, AhciData::In(chunk))?;
+        }
+        Ok(())
+    }
+
+    /// WRITE DMA EXT; `buf` must hold a whole number of logical sectors.
+    pub fn write_dma_ext(&mut self, lba: u64, buf: &[u8]) -> Result<(), AtaError> {
+        let sector = self.identify.logical_sector_size as usize;
+        let count = (buf.len() / sector) as u64;
+        self.check_range(lba, count)?;
+        for (i, chunk) in buf.chunks(ATA_MAX_SECTORS_PER_COMMAND as usize * sector).enumerate() {
+            let chunk_lba = lba + i as u64 * ATA_MAX_SECTORS_PER_COMMAND;
+            let sectors = (chunk.len() / sector) as u64;
+            let fis = RegisterH2dFis::command(ATA_CMD_WRITE_DMA_EXT, 0, chunk_lba, sectors as u16);
+            Self::issue(&mut self.port, fis, AhciData::Out(chunk))?;
+        }
+        Ok(())
+    }
+
+    /// FLUSH CACHE EXT: writes the drive's volatile cache to the medium.
+    pub fn flush_cache_ext(&mut self) -> Result<(), AtaError> {
+        Self::issue(&mut self.port, RegisterH2dFis::command(ATA_CMD_FLUSH_CACHE_EXT, 0, 0, 0), AhciData::None).map(|_| ())
+    }
+
+    /// DATA SET MANAGEMENT with the TRIM bit. The payload is split so a command never carries
+    /// more 512-byte blocks than IDENTIFY word 105 allows.
+    pub fn trim(&mut self, lba: u64, count: u64) -> Result<(), AtaError> {
+        if !self.identify.trim_supported {
+            return Err(AtaError::NotSupported("DATA SET MANAGEMENT TRIM"));
+        }
+        self.check_range(lba, count)?;
+        let payload = build_trim_ranges(lba, count);
+        let max_blocks = core::cmp::max(1, self.identify.max_dsm_blocks as usize);
+        for chunk in payload.chunks(max_blocks * ATA_TRIM_PAYLOAD_SECTOR_SIZE) {
+            let blocks = (chunk.len() / ATA_TRIM_PAYLOAD_SECTOR_SIZE) as u16;
+            let fis = RegisterH2dFis::command(ATA_CMD_DATA_SET_MANAGEMENT, ATA_DSM_FEATURE_TRIM, 0, blocks);
+            Self::issue(&mut self.port, fis, AhciData::Out(chunk))?;
+        }
+        Ok(())
+    }
+
+    /// SMART READ DATA.
+    pub fn smart_read_data(&mut self) -> Result<SmartData, AtaError> {
+        if !self.identify.smart_supported || !self.identify.smart_enabled {
+            return Err(AtaError::NotSupported("SMART"));
+        }
+        let mut data = vec![0u8; ATA_SMART_DATA_SIZE]; // Requires alloc
+        let fis = RegisterH2dFis::command(ATA_CMD_SMART, ATA_SMART_READ_DATA, ATA_SMART_LBA_SIGNATURE, 1);
+        Self::issue(&mut self.port, fis, AhciData::In(&mut data))?;
+        SmartData::parse(&data)
+    }
+
+    fn check_buffer(&self, len: usize) -> Result<(), BlockDeviceError> {
+        if len != self.identify.logical_sector_size as usize {
+            return Err(BlockDeviceError::BlockSizeError(format!(
+                "Buffer size ({}) must match block size ({}).",
+                len, self.identify.logical_sector_size
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl<P: AhciPort> BlockDevice for AhciSataDevice<P> {
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> Result<(), BlockDeviceError> {
+        self.check_buffer(buf.len())?;
+        self.read_dma_ext(block_id, buf).map_err(map_ata_error_to_block_device_error)
+    }
+
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> Result<(), BlockDeviceError> {
+        self.check_buffer(buf.len())?;
+        self.write_dma_ext(block_id, buf).map_err(map_ata_error_to_block_device_error)
+    }
+
+    fn block_size(&self) -> usize {
+        self.identify.logical_sector_size as usize
+    }
+
+    fn block_count(&self) -> u64 {
+        self.identify.sector_count
+    }
+
+    /// DSM TRIM if the drive supports it; otherwise a no-op (discard is advisory).
+    fn discard_blocks(&mut self, start_block: u64, block_count: u64) -> Result<(), BlockDeviceError> {
+        if !self.identify.trim_supported {
+            return Ok(());
+        }
+        self.trim(start_block, block_count).map_err(map_ata_error_to_block_device_error)
+    }
+
+    fn flush(&mut self) -> Result<(), BlockDeviceError> {
+        self.flush_cache_ext().map_err(map_ata_error_to_block_device_error)
+    }
 }
 
 
@@ -649,6 +1227,265 @@ mod tests {
     }
 
 
+    #[test]
+    fn test_trim_payload_encoding() {
+        // Single small range: one entry, padded to a full sector
+        let payload = build_trim_ranges(0x1234, 8);
+        assert_eq!(payload.len(), ATA_TRIM_PAYLOAD_SECTOR_SIZE);
+        assert_eq!(&payload[0..8], &(0x1234u64 | (8u64 << 48)).to_le_bytes());
+        assert!(payload[8..].iter().all(|b| *b == 0));
+        assert_eq!(parse_trim_ranges(&payload), vec![(0x1234, 8)]);
+
+        // Ranges longer than 0xFFFF blocks are split
+        let payload = build_trim_ranges(100, 0x1_0001);
+        assert_eq!(parse_trim_ranges(&payload), vec![(100, 0xFFFF), (100 + 0xFFFF, 2)]);
+
+        // More than 64 entries spill into a second sector
+        let payload = build_trim_ranges(0, ATA_TRIM_MAX_RANGE_BLOCKS * 65);
+        assert_eq!(payload.len(), 2 * ATA_TRIM_PAYLOAD_SECTOR_SIZE);
+        assert_eq!(parse_trim_ranges(&payload).len(), 65);
+
+        assert!(build_trim_ranges(5, 0).is_empty());
+    }
+
+    #[test]
+    fn test_sata_device_discard() -> Result<(), BlockDeviceError> { // Return BlockDeviceError
+        let test_file_path = Path::new("test_sata_discard.img");
+        let config = SataConfig { device_id: 3, block_size: 512, block_count: 16 }; // Use SataConfig
+        let mut device = SataDevice::open_file(test_file_path.to_str().unwrap(), config)?;
+
+        let data = vec![0x5Au8; 512]; // Requires alloc
+        for block_id in 0..16 {
+            device.write_block(block_id, &data)?;
+        }
+        device.discard_blocks(4, 3)?;
+
+        let mut read_buf = vec![0u8; 512]; // Requires alloc
+        for block_id in 0..16 {
+            device.read_block(block_id, &mut read_buf)?;
+            let expected = if (4..7).contains(&block_id) { vec![0u8; 512] } else { data.clone() };
+            assert_eq!(read_buf, expected);
+        }
+
+        let result_oob = device.discard_blocks(15, 2);
+        assert!(matches!(result_oob, Err(BlockDeviceError::InvalidParameter(_))));
+
+        remove_file(test_file_path).expect("Test dosyası silinemedi");
+        Ok(()) // Return Ok from test function
+    }
+
+
+    // Simulated AHCI port with an SSD behind it: decodes command FISes, moves data through the
+    // PRDT buffer and posts D2H register FISes.
+    struct SimAhciPort {
+        sectors: Vec<u8>,
+        sector_count: u64,
+        logical_size: usize,
+        cache_dirty: bool,
+        flushes: u32,
+        trimmed: Vec<(u64, u64)>,
+        dsm_commands: u32,
+        unreadable_lba: Option<u64>,
+        corrupt_smart: bool,
+    }
+
+    impl SimAhciPort {
+        fn new(sector_count: u64) -> Self {
+            SimAhciPort {
+                sectors: vec![0u8; sector_count as usize * 512],
+                sector_count,
+                logical_size: 512,
+                cache_dirty: false,
+                flushes: 0,
+                trimmed: Vec::new(),
+                dsm_commands: 0,
+                unreadable_lba: None,
+                corrupt_smart: false,
+            }
+        }
+
+        fn identify_data(&self) -> Vec<u8> {
+            let mut words = [0u16; 256];
+            let mut put_string = |first: usize, len_words: usize, text: &str| {
+                let mut bytes = text.as_bytes().to_vec();
+                bytes.resize(len_words * 2, b' ');
+                for i in 0..len_words {
+                    words[first + i] = (bytes[i * 2] as u16) << 8 | bytes[i * 2 + 1] as u16;
+                }
+            };
+            put_string(10, 10, "SIM0001");
+            put_string(23, 4, "1.0");
+            put_string(27, 20, "SADAK Simulated SSD");
+            words[49] = 1 << 9 | 1 << 8; // LBA, DMA
+            words[69] = 1 << 14 | 1 << 5; // Deterministic zeroes after TRIM
+            words[82] = 1 << 5 | 1; // Write cache, SMART supported
+            words[83] = 1 << 14 | 1 << 10; // LBA48
+            words[85] = 1 << 5 | 1; // Write cache, SMART enabled
+            words[105] = 1; // One DSM payload block per command
+            words[106] = 0x4000 | 1 << 13 | 3; // 8 logical sectors per physical sector
+            words[169] = 1; // TRIM
+            words[217] = 1; // Non-rotating
+            for i in 0..4 {
+                words[100 + i] = (self.sector_count >> (16 * i)) as u16;
+            }
+            words.iter().flat_map(|w| w.to_le_bytes()).collect()
+        }
+
+        fn smart_data(&self) -> Vec<u8> {
+            let mut data = vec![0u8; 512];
+            let attributes: [(u8, u64); 3] = [(5, 2), (9, 1234), (194, 41)];
+            for (i, (id, raw)) in attributes.iter().enumerate() {
+                let entry = &mut data[2 + i * 12..14 + i * 12];
+                entry[0] = *id;
+                entry[3] = 100;
+                entry[4] = 99;
+                entry[5..11].copy_from_slice(&raw.to_le_bytes()[..6]);
+            }
+            let sum = data.iter().fold(0u8, |acc, b| acc.wrapping_add(*b));
+            data[511] = 0u8.wrapping_sub(sum);
+            if self.corrupt_smart {
+                data[100] ^= 1;
+            }
+            data
+        }
+    }
+
+    impl AhciPort for SimAhciPort {
+        fn execute(&mut self, command_fis: &[u8; FIS_REG_H2D_SIZE], data: AhciData<'_>) -> Result<[u8; FIS_REG_D2H_SIZE], AtaError> {
+            let fis = RegisterH2dFis::parse(command_fis).ok_or_else(|| AtaError::Port(String::from("bad command FIS")))?;
+            let count = if fis.count == 0 { 65536 } else { fis.count as u64 };
+            let status = match (fis.command, data) {
+                (ATA_CMD_IDENTIFY_DEVICE, AhciData::In(buf)) => {
+                    buf.copy_from_slice(&self.identify_data());
+                    RegisterD2hFis::ok()
+                }
+                (ATA_CMD_READ_DMA_EXT, AhciData::In(buf)) => {
+                    if fis.lba + count > self.sector_count || buf.len() as u64 != count * 512 {
+                        RegisterD2hFis::failed(ATA_ERROR_IDNF | ATA_ERROR_ABRT, fis.lba)
+                    } else if let Some(bad) = self.unreadable_lba.filter(|bad| (fis.lba..fis.lba + count).contains(bad)) {
+                        RegisterD2hFis::failed(ATA_ERROR_UNC, bad)
+                    } else {
+                        let start = fis.lba as usize * 512;
+                        buf.copy_from_slice(&self.sectors[start..start + buf.len()]);
+                        RegisterD2hFis::ok()
+                    }
+                }
+                (ATA_CMD_WRITE_DMA_EXT, AhciData::Out(buf)) => {
+                    if fis.lba + count > self.sector_count || buf.len() as u64 != count * 512 {
+                        RegisterD2hFis::failed(ATA_ERROR_IDNF | ATA_ERROR_ABRT, fis.lba)
+                    } else {
+                        let start = fis.lba as usize * 512;
+                        self.sectors[start..start + buf.len()].copy_from_slice(buf);
+                        self.cache_dirty = true;
+                        RegisterD2hFis::ok()
+                    }
+                }
+                (ATA_CMD_FLUSH_CACHE_EXT, AhciData::None) => {
+                    self.cache_dirty = false;
+                    self.flushes += 1;
+                    RegisterD2hFis::ok()
+                }
+                (ATA_CMD_DATA_SET_MANAGEMENT, AhciData::Out(payload)) if fis.features & ATA_DSM_FEATURE_TRIM != 0 => {
+                    if payload.len() != count as usize * 512 || count > 1 {
+                        RegisterD2hFis::failed(ATA_ERROR_ABRT, 0)
+                    } else {
+                        self.dsm_commands += 1;
+                        for (lba, len) in parse_trim_ranges(payload) {
+                            let start = lba as usize * 512;
+                            self.sectors[start..start + len as usize * 512].fill(0);
+                            self.trimmed.push((lba, len));
+                        }
+                        RegisterD2hFis::ok()
+                    }
+                }
+                (ATA_CMD_SMART, AhciData::In(buf)) if fis.features == ATA_SMART_READ_DATA && fis.lba == ATA_SMART_LBA_SIGNATURE => {
+                    buf.copy_from_slice(&self.smart_data());
+                    RegisterD2hFis::ok()
+                }
+                _ => RegisterD2hFis::failed(ATA_ERROR_ABRT, 0),
+            };
+            Ok(status.to_bytes())
+        }
+    }
+
+    #[test]
+    fn test_ahci_identify_and_fis_layout() {
+        let fis = RegisterH2dFis::command(ATA_CMD_READ_DMA_EXT, 0, 0x0000_1234_5678_9ABC, 8);
+        let bytes = fis.to_bytes();
+        assert_eq!(bytes[0], FIS_TYPE_REG_H2D);
+        assert_eq!(bytes[2], ATA_CMD_READ_DMA_EXT);
+        assert_eq!(&bytes[4..7], &[0xBC, 0x9A, 0x78]);
+        assert_eq!(&bytes[8..11], &[0x56, 0x34, 0x12]);
+        assert_eq!(RegisterH2dFis::parse(&bytes), Some(fis));
+
+        let device = AhciSataDevice::new(SimAhciPort::new(4096)).unwrap();
+        let identify = device.identify();
+        assert_eq!(identify.model, "SADAK Simulated SSD");
+        assert_eq!(identify.serial, "SIM0001");
+        assert_eq!(identify.firmware, "1.0");
+        assert!(identify.lba48 && identify.trim_supported && identify.trim_zeroes);
+        assert_eq!(identify.sector_count, 4096);
+        assert_eq!(identify.logical_sector_size, 512);
+        assert_eq!(identify.physical_sector_size, 4096);
+        assert_eq!(identify.rotation_rate, 1);
+        assert_eq!(device.block_size(), 512);
+        assert_eq!(device.block_count(), 4096);
+    }
+
+    #[test]
+    fn test_ahci_read_write_flush_trim() -> Result<(), BlockDeviceError> {
+        let mut device = AhciSataDevice::new(SimAhciPort::new(4096)).unwrap();
+        let data: Vec<u8> = (0..512).map(|i| (i % 251) as u8).collect();
+        for lba in 0..200 {
+            device.write_block(lba, &data)?;
+        }
+        let mut read_buf = vec![0u8; 512];
+        device.read_block(150, &mut read_buf)?;
+        assert_eq!(read_buf, data);
+
+        assert!(device.port_mut().cache_dirty);
+        device.flush()?;
+        assert!(!device.port_mut().cache_dirty);
+        assert_eq!(device.port_mut().flushes, 1);
+
+        // 100 blocks fit in a single range of one DSM command
+        device.discard_blocks(10, 100)?;
+        assert_eq!(device.port_mut().trimmed, vec![(10, 100)]);
+        device.read_block(50, &mut read_buf)?;
+        assert_eq!(read_buf, vec![0u8; 512]);
+        device.read_block(110, &mut read_buf)?;
+        assert_eq!(read_buf, data);
+
+        // Multi-sector transfer through the ATA API
+        let mut multi = vec![0u8; 4 * 512];
+        device.read_dma_ext(108, &mut multi).unwrap();
+        assert_eq!(&multi[..512], &vec![0u8; 512][..]);
+        assert_eq!(&multi[2 * 512..3 * 512], &data[..]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ahci_smart_and_errors() {
+        let mut device = AhciSataDevice::new(SimAhciPort::new(1024)).unwrap();
+        let smart = device.smart_read_data().unwrap();
+        assert_eq!(smart.attributes.len(), 3);
+        assert_eq!(smart.attribute(SMART_ATTR_POWER_ON_HOURS).unwrap().raw, 1234);
+        assert_eq!(smart.attribute(SMART_ATTR_REALLOCATED_SECTORS).unwrap().worst, 99);
+        assert_eq!(smart.temperature_celsius(), Some(41));
+        device.port_mut().corrupt_smart = true;
+        assert_eq!(device.smart_read_data(), Err(AtaError::SmartChecksum));
+
+        // Uncorrectable sector: ATA error surfaces as a device error with the failing LBA
+        device.port_mut().unreadable_lba = Some(77);
+        let mut buf = vec![0u8; 512];
+        assert!(matches!(device.read_dma_ext(77, &mut buf), Err(AtaError::Command { error: ATA_ERROR_UNC, lba: 77, .. })));
+        assert!(matches!(device.read_block(77, &mut buf), Err(BlockDeviceError::DeviceError(_))));
+
+        // Out of range and bad buffer size
+        assert!(matches!(device.read_block(1024, &mut buf), Err(BlockDeviceError::InvalidParameter(_))));
+        assert!(matches!(device.write_block(0, &[0u8; 100]), Err(BlockDeviceError::BlockSizeError(_))));
+    }
+
     // TODO: Add tests specifically for the no_std implementation using a mock Sahne64 environment.
     // This requires simulating resource acquire/release, fs::fstat, fs::ftruncate, fs::read_at, fs::write_at, fs::lseek.
     // Test cases should cover opening resources, block reads/writes, invalid block sizes/IDs, and simulated IO errors.
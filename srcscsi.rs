@@ -0,0 +1,716 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// SCSI command set helpers shared by SCSI-based transports (USB Mass Storage, SAS, UFS).
+//
+// - `Cdb` builds Command Descriptor Blocks (SPC/SBC); READ/WRITE pick the 10 or 16 byte
+//   variant depending on the LBA and transfer length.
+// - `SenseData` decodes fixed (0x70/0x71) and descriptor (0x72/0x73) format sense data.
+// - `InquiryData` and `ReadCapacity` decode the standard INQUIRY and READ CAPACITY responses.
+// - `build_unmap_parameter_list` and `ModeSense` cover thin provisioning and the Caching mode page.
+// - `ScsiStatus` decodes the SAM status byte returned by SCSI transports (SAS SSP, UFS).
+// All multi-byte CDB and response fields are big-endian.
+
+use crate::blockdevice::BlockDeviceError; // Assuming BlockDeviceError is in crate::blockdevice
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+
+use core::fmt;
+use core::result::Result;
+
+// Operation codes (SPC-4 / SBC-3)
+pub const SCSI_TEST_UNIT_READY: u8 = 0x00;
+pub const SCSI_REQUEST_SENSE: u8 = 0x03;
+pub const SCSI_INQUIRY: u8 = 0x12;
+pub const SCSI_READ_CAPACITY_10: u8 = 0x25;
+pub const SCSI_READ_10: u8 = 0x28;
+pub const SCSI_WRITE_10: u8 = 0x2A;
+pub const SCSI_READ_16: u8 = 0x88;
+pub const SCSI_WRITE_16: u8 = 0x8A;
+pub const SCSI_SERVICE_ACTION_IN_16: u8 = 0x9E;
+pub const SCSI_SAI_READ_CAPACITY_16: u8 = 0x10; // Service action for READ CAPACITY(16)
+pub const SCSI_SYNCHRONIZE_CACHE_10: u8 = 0x35;
+pub const SCSI_SYNCHRONIZE_CACHE_16: u8 = 0x91;
+pub const SCSI_UNMAP: u8 = 0x42;
+pub const SCSI_MODE_SENSE_10: u8 = 0x5A;
+
+// MODE SENSE pages
+pub const SCSI_MODE_PAGE_CACHING: u8 = 0x08;
+pub const SCSI_MODE_PAGE_ALL: u8 = 0x3F;
+pub const SCSI_MODE_PAGE_CONTROL_CURRENT: u8 = 0x00;
+
+// SAM-5 status codes
+pub const SCSI_STATUS_GOOD: u8 = 0x00;
+pub const SCSI_STATUS_CHECK_CONDITION: u8 = 0x02;
+pub const SCSI_STATUS_CONDITION_MET: u8 = 0x04;
+pub const SCSI_STATUS_BUSY: u8 = 0x08;
+pub const SCSI_STATUS_RESERVATION_CONFLICT: u8 = 0x18;
+pub const SCSI_STATUS_TASK_SET_FULL: u8 = 0x28;
+pub const SCSI_STATUS_ACA_ACTIVE: u8 = 0x30;
+pub const SCSI_STATUS_TASK_ABORTED: u8 = 0x40;
+
+// Response sizes requested by the helpers
+pub const SCSI_SENSE_BUFFER_SIZE: usize = 18; // Fixed format sense data
+pub const SCSI_INQUIRY_SIZE: usize = 36; // Standard INQUIRY data
+pub const SCSI_READ_CAPACITY_10_SIZE: usize = 8;
+pub const SCSI_READ_CAPACITY_16_SIZE: usize = 32;
+pub const SCSI_MODE_SENSE_CACHING_SIZE: usize = 8 + 20; // Header (10) + Caching page, no block descriptors
+pub const SCSI_UNMAP_HEADER_SIZE: usize = 8;
+pub const SCSI_UNMAP_DESCRIPTOR_SIZE: usize = 16;
+
+// READ(10)/WRITE(10) limits: 32-bit LBA, 16-bit transfer length
+const CDB10_MAX_LBA: u64 = u32::MAX as u64;
+const CDB10_MAX_BLOCKS: u32 = u16::MAX as u32;
+
+/// A SCSI Command Descriptor Block (up to 16 bytes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cdb {
+    bytes: [u8; 16],
+    len: u8,
+}
+
+impl Cdb {
+    fn new(opcode: u8, len: u8) -> Self {
+        let mut bytes = [0u8; 16];
+        bytes[0] = opcode;
+        Cdb { bytes, len }
+    }
+
+    /// Returns the CDB bytes (6, 10 or 16 bytes).
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+
+    /// Returns the operation code.
+    pub fn opcode(&self) -> u8 {
+        self.bytes[0]
+    }
+
+    /// TEST UNIT READY (6).
+    pub fn test_unit_ready() -> Self {
+        Cdb::new(SCSI_TEST_UNIT_READY, 6)
+    }
+
+    /// REQUEST SENSE (6) in fixed format.
+    pub fn request_sense(allocation_length: u8) -> Self {
+        let mut cdb = Cdb::new(SCSI_REQUEST_SENSE, 6);
+        cdb.bytes[4] = allocation_length;
+        cdb
+    }
+
+    /// Standard INQUIRY (6).
+    pub fn inquiry(allocation_length: u8) -> Self {
+        let mut cdb = Cdb::new(SCSI_INQUIRY, 6);
+        cdb.bytes[4] = allocation_length;
+        cdb
+    }
+
+    /// READ CAPACITY (10).
+    pub fn read_capacity_10() -> Self {
+        Cdb::new(SCSI_READ_CAPACITY_10, 10)
+    }
+
+    /// READ CAPACITY (16) (SERVICE ACTION IN).
+    pub fn read_capacity_16(allocation_length: u32) -> Self {
+        let mut cdb = Cdb::new(SCSI_SERVICE_ACTION_IN_16, 16);
+        cdb.bytes[1] = SCSI_SAI_READ_CAPACITY_16;
+        cdb.bytes[10..14].copy_from_slice(&allocation_length.to_be_bytes());
+        cdb
+    }
+
+    /// READ (10) or READ (16), whichever can address the range.
+    pub fn read(lba: u64, blocks: u32) -> Self {
+        Cdb::read_write(SCSI_READ_10, SCSI_READ_16, lba, blocks)
+    }
+
+    /// WRITE (10) or WRITE (16), whichever can address the range.
+    pub fn write(lba: u64, blocks: u32) -> Self {
+        Cdb::read_write(SCSI_WRITE_10, SCSI_WRITE_16, lba, blocks)
+    }
+
+    /// READ (16) regardless of the range (SBC drives are expected to support it).
+    pub fn read_16(lba: u64, blocks: u32) -> Self {
+        Cdb::rw_16(SCSI_READ_16, lba, blocks, false)
+    }
+
+    /// WRITE (16); `fua` forces the data to the medium before completion.
+    pub fn write_16(lba: u64, blocks: u32, fua: bool) -> Self {
+        Cdb::rw_16(SCSI_WRITE_16, lba, blocks, fua)
+    }
+
+    /// SYNCHRONIZE CACHE (10). A `blocks` value of 0 covers everything from `lba` to the end.
+    pub fn synchronize_cache_10(lba: u32, blocks: u16) -> Self {
+        let mut cdb = Cdb::new(SCSI_SYNCHRONIZE_CACHE_10, 10);
+        cdb.bytes[2..6].copy_from_slice(&lba.to_be_bytes());
+        cdb.bytes[7..9].copy_from_slice(&blocks.to_be_bytes());
+        cdb
+    }
+
+    /// SYNCHRONIZE CACHE (16). A `blocks` value of 0 covers everything from `lba` to the end.
+    pub fn synchronize_cache_16(lba: u64, blocks: u32) -> Self {
+        let mut cdb = Cdb::new(SCSI_SYNCHRONIZE_CACHE_16, 16);
+        cdb.bytes[2..10].copy_from_slice(&lba.to_be_bytes());
+        cdb.bytes[10..14].copy_from_slice(&blocks.to_be_bytes());
+        cdb
+    }
+
+    /// UNMAP (10) with a parameter list of `parameter_list_length` bytes.
+    pub fn unmap(parameter_list_length: u16) -> Self {
+        let mut cdb = Cdb::new(SCSI_UNMAP, 10);
+        cdb.bytes[7..9].copy_from_slice(&parameter_list_length.to_be_bytes());
+        cdb
+    }
+
+    /// MODE SENSE (10) for the current values of `page`, without block descriptors (DBD).
+    pub fn mode_sense_10(page: u8, allocation_length: u16) -> Self {
+        let mut cdb = Cdb::new(SCSI_MODE_SENSE_10, 10);
+        cdb.bytes[1] = 0x08; // DBD
+        cdb.bytes[2] = SCSI_MODE_PAGE_CONTROL_CURRENT << 6 | (page & 0x3F);
+        cdb.bytes[7..9].copy_from_slice(&allocation_length.to_be_bytes());
+        cdb
+    }
+
+    /// Logical block address field of a READ/WRITE/SYNCHRONIZE CACHE CDB (10 or 16 byte).
+    pub fn lba(&self) -> u64 {
+        match self.len {
+            10 => u32::from_be_bytes([self.bytes[2], self.bytes[3], self.bytes[4], self.bytes[5]]) as u64,
+            16 => {
+                let mut lba = [0u8; 8];
+                lba.copy_from_slice(&self.bytes[2..10]);
+                u64::from_be_bytes(lba)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Transfer length field (blocks) of a READ/WRITE/SYNCHRONIZE CACHE CDB (10 or 16 byte).
+    pub fn transfer_length(&self) -> u32 {
+        match self.len {
+            10 => u16::from_be_bytes([self.bytes[7], self.bytes[8]]) as u32,
+            16 => u32::from_be_bytes([self.bytes[10], self.bytes[11], self.bytes[12], self.bytes[13]]),
+            _ => 0,
+        }
+    }
+
+    fn rw_16(opcode: u8, lba: u64, blocks: u32, fua: bool) -> Self {
+        let mut cdb = Cdb::new(opcode, 16);
+        if fua {
+            cdb.bytes[1] = 0x08;
+        }
+        cdb.bytes[2..10].copy_from_slice(&lba.to_be_bytes());
+        cdb.bytes[10..14].copy_from_slice(&blocks.to_be_bytes());
+        cdb
+    }
+
+    fn read_write(opcode10: u8, opcode16: u8, lba: u64, blocks: u32) -> Self {
+        let last_lba = lba + (blocks as u64).saturating_sub(1);
+        if last_lba <= CDB10_MAX_LBA && blocks <= CDB10_MAX_BLOCKS {
+            let mut cdb = Cdb::new(opcode10, 10);
+            cdb.bytes[2..6].copy_from_slice(&(lba as u32).to_be_bytes());
+            cdb.bytes[7..9].copy_from_slice(&(blocks as u16).to_be_bytes());
+            cdb
+        } else {
+            let mut cdb = Cdb::new(opcode16, 16);
+            cdb.bytes[2..10].copy_from_slice(&lba.to_be_bytes());
+            cdb.bytes[10..14].copy_from_slice(&blocks.to_be_bytes());
+            cdb
+        }
+    }
+}
+
+/// Sense key (SPC-4, table 48).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SenseKey {
+    NoSense,
+    RecoveredError,
+    NotReady,
+    MediumError,
+    HardwareError,
+    IllegalRequest,
+    UnitAttention,
+    DataProtect,
+    BlankCheck,
+    VendorSpecific,
+    CopyAborted,
+    AbortedCommand,
+    VolumeOverflow,
+    Miscompare,
+    Completed,
+    Reserved(u8),
+}
+
+impl SenseKey {
+    pub fn from_u8(value: u8) -> Self {
+        match value & 0x0F {
+            0x0 => SenseKey::NoSense,
+            0x1 => SenseKey::RecoveredError,
+            0x2 => SenseKey::NotReady,
+            0x3 => SenseKey::MediumError,
+            0x4 => SenseKey::HardwareError,
+            0x5 => SenseKey::IllegalRequest,
+            0x6 => SenseKey::UnitAttention,
+            0x7 => SenseKey::DataProtect,
+            0x8 => SenseKey::BlankCheck,
+            0x9 => SenseKey::VendorSpecific,
+            0xA => SenseKey::CopyAborted,
+            0xB => SenseKey::AbortedCommand,
+            0xD => SenseKey::VolumeOverflow,
+            0xE => SenseKey::Miscompare,
+            0xF => SenseKey::Completed,
+            other => SenseKey::Reserved(other),
+        }
+    }
+
+    pub fn to_u8(&self) -> u8 {
+        match self {
+            SenseKey::NoSense => 0x0,
+            SenseKey::RecoveredError => 0x1,
+            SenseKey::NotReady => 0x2,
+            SenseKey::MediumError => 0x3,
+            SenseKey::HardwareError => 0x4,
+            SenseKey::IllegalRequest => 0x5,
+            SenseKey::UnitAttention => 0x6,
+            SenseKey::DataProtect => 0x7,
+            SenseKey::BlankCheck => 0x8,
+            SenseKey::VendorSpecific => 0x9,
+            SenseKey::CopyAborted => 0xA,
+            SenseKey::AbortedCommand => 0xB,
+            SenseKey::VolumeOverflow => 0xD,
+            SenseKey::Miscompare => 0xE,
+            SenseKey::Completed => 0xF,
+            SenseKey::Reserved(value) => value & 0x0F,
+        }
+    }
+}
+
+/// Decoded sense data: sense key, additional sense code/qualifier and the INFORMATION field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SenseData {
+    pub key: SenseKey,
+    pub asc: u8,
+    pub ascq: u8,
+    pub information: Option<u64>, // Usually the failing LBA
+}
+
+impl SenseData {
+    /// Parses fixed or descriptor format sense data. Returns None for unknown response codes.
+    pub fn parse(raw: &[u8]) -> Option<Self> {
+        let response_code = *raw.first()? & 0x7F;
+        match response_code {
+            0x70 | 0x71 => {
+                if raw.len() < 14 {
+                    return None;
+                }
+                let valid = raw[0] & 0x80 != 0;
+                let information = u32::from_be_bytes([raw[3], raw[4], raw[5], raw[6]]) as u64;
+                Some(SenseData {
+                    key: SenseKey::from_u8(raw[2]),
+                    asc: raw[12],
+                    ascq: raw[13],
+                    information: if valid { Some(information) } else { None },
+                })
+            }
+            0x72 | 0x73 => {
+                if raw.len() < 8 {
+                    return None;
+                }
+                // Walk the descriptor list looking for the Information descriptor (type 0x00)
+                let end = core::cmp::min(raw.len(), 8 + raw[7] as usize);
+                let mut information = None;
+                let mut offset = 8;
+                while offset + 2 <= end {
+                    let descriptor_type = raw[offset];
+                    let length = raw[offset + 1] as usize;
+                    if descriptor_type == 0x00 && length == 0x0A && offset + 12 <= end && raw[offset + 2] & 0x80 != 0 {
+                        let mut value = [0u8; 8];
+                        value.copy_from_slice(&raw[offset + 4..offset + 12]);
+                        information = Some(u64::from_be_bytes(value));
+                    }
+                    offset += 2 + length;
+                }
+                Some(SenseData { key: SenseKey::from_u8(raw[1]), asc: raw[2], ascq: raw[3], information })
+            }
+            _ => None,
+        }
+    }
+
+    /// Builds fixed format sense data (used by simulated targets).
+    pub fn to_fixed_bytes(&self) -> [u8; SCSI_SENSE_BUFFER_SIZE] {
+        let mut out = [0u8; SCSI_SENSE_BUFFER_SIZE];
+        out[0] = 0x70;
+        out[2] = self.key.to_u8();
+        if let Some(information) = self.information {
+            out[0] |= 0x80;
+            out[3..7].copy_from_slice(&(information as u32).to_be_bytes());
+        }
+        out[7] = (SCSI_SENSE_BUFFER_SIZE - 8) as u8; // Additional sense length
+        out[12] = self.asc;
+        out[13] = self.ascq;
+        out
+    }
+
+    /// True for a UNIT ATTENTION (e.g. power on or medium changed) that a retry will clear.
+    pub fn is_unit_attention(&self) -> bool {
+        self.key == SenseKey::UnitAttention
+    }
+}
+
+impl fmt::Display for SenseData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sense key {:?}, ASC/ASCQ {:#04x}/{:#04x}", self.key, self.asc, self.ascq)?;
+        if let Some(information) = self.information {
+            write!(f, ", information {}", information)?;
+        }
+        Ok(())
+    }
+}
+
+/// Maps CHECK CONDITION sense data to the closest BlockDeviceError.
+pub fn sense_to_block_device_error(sense: &SenseData) -> BlockDeviceError {
+    match (sense.key, sense.asc) {
+        (SenseKey::IllegalRequest, 0x20) => BlockDeviceError::NotSupported(format!("SCSI invalid command operation code: {}", sense)),
+        (SenseKey::IllegalRequest, _) => BlockDeviceError::InvalidParameter(format!("SCSI illegal request: {}", sense)),
+        (SenseKey::DataProtect, _) => BlockDeviceError::PermissionDenied(format!("SCSI data protect: {}", sense)),
+        (SenseKey::NotReady, 0x3A) => BlockDeviceError::DeviceNotFound(format!("SCSI medium not present: {}", sense)),
+        (SenseKey::NotReady, 0x04) => BlockDeviceError::TimedOut, // Logical unit not ready (becoming ready, format in progress...)
+        (SenseKey::MediumError, _) => BlockDeviceError::DeviceError(format!("SCSI medium error: {}", sense)),
+        (SenseKey::HardwareError, _) => BlockDeviceError::DeviceError(format!("SCSI hardware error: {}", sense)),
+        _ => BlockDeviceError::DeviceError(format!("SCSI check condition: {}", sense)),
+    }
+}
+
+/// SAM status byte returned at the end of a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScsiStatus {
+    Good,
+    CheckCondition,
+    ConditionMet,
+    Busy,
+    ReservationConflict,
+    TaskSetFull,
+    AcaActive,
+    TaskAborted,
+    Reserved(u8),
+}
+
+impl ScsiStatus {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            SCSI_STATUS_GOOD => ScsiStatus::Good,
+            SCSI_STATUS_CHECK_CONDITION => ScsiStatus::CheckCondition,
+            SCSI_STATUS_CONDITION_MET => ScsiStatus::ConditionMet,
+            SCSI_STATUS_BUSY => ScsiStatus::Busy,
+            SCSI_STATUS_RESERVATION_CONFLICT => ScsiStatus::ReservationConflict,
+            SCSI_STATUS_TASK_SET_FULL => ScsiStatus::TaskSetFull,
+            SCSI_STATUS_ACA_ACTIVE => ScsiStatus::AcaActive,
+            SCSI_STATUS_TASK_ABORTED => ScsiStatus::TaskAborted,
+            other => ScsiStatus::Reserved(other),
+        }
+    }
+
+    pub fn to_u8(&self) -> u8 {
+        match self {
+            ScsiStatus::Good => SCSI_STATUS_GOOD,
+            ScsiStatus::CheckCondition => SCSI_STATUS_CHECK_CONDITION,
+            ScsiStatus::ConditionMet => SCSI_STATUS_CONDITION_MET,
+            ScsiStatus::Busy => SCSI_STATUS_BUSY,
+            ScsiStatus::ReservationConflict => SCSI_STATUS_RESERVATION_CONFLICT,
+            ScsiStatus::TaskSetFull => SCSI_STATUS_TASK_SET_FULL,
+            ScsiStatus::AcaActive => SCSI_STATUS_ACA_ACTIVE,
+            ScsiStatus::TaskAborted => SCSI_STATUS_TASK_ABORTED,
+            ScsiStatus::Reserved(value) => *value,
+        }
+    }
+
+    /// True for statuses that mean "try again later" rather than a failed command.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ScsiStatus::Busy | ScsiStatus::TaskSetFull)
+    }
+}
+
+/// Builds the UNMAP parameter list for a block range. Each 16-byte block descriptor covers up
+/// to u32::MAX blocks; at most `max_descriptors` descriptors are emitted (0: unlimited).
+///
+/// # Returns
+///
+/// The parameter list and the number of blocks it covers (less than `block_count` when the
+/// descriptor limit was reached; the caller issues another UNMAP for the rest).
+pub fn build_unmap_parameter_list(start_lba: u64, block_count: u64, max_descriptors: usize) -> (Vec<u8>, u64) {
+    let mut descriptors = Vec::new(); // Requires alloc
+    let mut lba = start_lba;
+    let mut remaining = block_count;
+    let mut count = 0;
+    while remaining > 0 && (max_descriptors == 0 || count < max_descriptors) {
+        let len = core::cmp::min(remaining, u32::MAX as u64);
+        descriptors.extend_from_slice(&lba.to_be_bytes());
+        descriptors.extend_from_slice(&(len as u32).to_be_bytes());
+        descriptors.extend_from_slice(&[0u8; 4]); // Reserved
+        lba += len;
+        remaining -= len;
+        count += 1;
+    }
+    let mut list = Vec::with_capacity(SCSI_UNMAP_HEADER_SIZE + descriptors.len());
+    list.extend_from_slice(&((SCSI_UNMAP_HEADER_SIZE - 2 + descriptors.len()) as u16).to_be_bytes()); // UNMAP data length
+    list.extend_from_slice(&(descriptors.len() as u16).to_be_bytes()); // Block descriptor data length
+    list.extend_from_slice(&[0u8; 4]);
+    list.extend_from_slice(&descriptors);
+    (list, block_count - remaining)
+}
+
+/// Decodes an UNMAP parameter list into (lba, blocks) ranges (used by simulated targets).
+pub fn parse_unmap_parameter_list(raw: &[u8]) -> Option<Vec<(u64, u32)>> {
+    if raw.len() < SCSI_UNMAP_HEADER_SIZE {
+        return None;
+    }
+    let descriptor_length = u16::from_be_bytes([raw[2], raw[3]]) as usize;
+    let descriptors = raw.get(SCSI_UNMAP_HEADER_SIZE..SCSI_UNMAP_HEADER_SIZE + descriptor_length)?;
+    Some(
+        descriptors
+            .chunks_exact(SCSI_UNMAP_DESCRIPTOR_SIZE)
+            .map(|d| {
+                let mut lba = [0u8; 8];
+                lba.copy_from_slice(&d[0..8]);
+                (u64::from_be_bytes(lba), u32::from_be_bytes([d[8], d[9], d[10], d[11]]))
+            })
+            .collect(),
+    )
+}
+
+/// MODE SENSE (10) response reduced to what a block driver needs: the write-protect bit of
+/// the header and the Caching mode page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeSense {
+    pub write_protected: bool, // WP bit of the device-specific parameter
+    pub write_cache_enabled: bool, // WCE
+    pub read_cache_disabled: bool, // RCD
+}
+
+impl ModeSense {
+    /// Parses MODE SENSE (10) data; the Caching page is looked up after any block descriptors.
+    pub fn parse_10(raw: &[u8]) -> Option<Self> {
+        if raw.len() < 8 {
+            return None;
+        }
+        let data_length = u16::from_be_bytes([raw[0], raw[1]]) as usize + 2;
+        let end = core::cmp::min(raw.len(), data_length);
+        let write_protected = raw[3] & 0x80 != 0;
+        let block_descriptor_length = u16::from_be_bytes([raw[6], raw[7]]) as usize;
+        let mut offset = 8 + block_descriptor_length;
+        while offset + 2 <= end {
+            let page_code = raw[offset] & 0x3F;
+            let subpage_format = raw[offset] & 0x40 != 0;
+            let (page_length, header) = if subpage_format {
+                if offset + 4 > end {
+                    break;
+                }
+                (u16::from_be_bytes([raw[offset + 2], raw[offset + 3]]) as usize, 4)
+            } else {
+                (raw[offset + 1] as usize, 2)
+            };
+            if page_code == SCSI_MODE_PAGE_CACHING && !subpage_format && offset + 3 <= end {
+                let flags = raw[offset + 2];
+                return Some(ModeSense {
+                    write_protected,
+                    write_cache_enabled: flags & 0x04 != 0,
+                    read_cache_disabled: flags & 0x01 != 0,
+                });
+            }
+            offset += header + page_length;
+        }
+        None
+    }
+
+    /// Builds a MODE SENSE (10) response carrying the Caching page (used by simulated targets).
+    pub fn to_bytes_10(&self) -> [u8; SCSI_MODE_SENSE_CACHING_SIZE] {
+        let mut out = [0u8; SCSI_MODE_SENSE_CACHING_SIZE];
+        out[0..2].copy_from_slice(&((SCSI_MODE_SENSE_CACHING_SIZE - 2) as u16).to_be_bytes());
+        if self.write_protected {
+            out[3] = 0x80;
+        }
+        out[8] = SCSI_MODE_PAGE_CACHING;
+        out[9] = 0x12; // Page length
+        out[10] = if self.write_cache_enabled { 0x04 } else { 0 } | if self.read_cache_disabled { 0x01 } else { 0 };
+        out
+    }
+}
+
+/// Standard INQUIRY data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InquiryData {
+    pub peripheral_device_type: u8, // 0x00 = direct access block device
+    pub removable: bool,
+    pub vendor: String,
+    pub product: String,
+    pub revision: String,
+}
+
+impl InquiryData {
+    /// Parses standard INQUIRY data (at least 36 bytes).
+    pub fn parse(raw: &[u8]) -> Option<Self> {
+        if raw.len() < SCSI_INQUIRY_SIZE {
+            return None;
+        }
+        let text = |bytes: &[u8]| String::from_utf8_lossy(bytes).trim_end_matches(|c| c == ' ' || c == '\0').to_string();
+        Some(InquiryData {
+            peripheral_device_type: raw[0] & 0x1F,
+            removable: raw[1] & 0x80 != 0,
+            vendor: text(&raw[8..16]),
+            product: text(&raw[16..32]),
+            revision: text(&raw[32..36]),
+        })
+    }
+}
+
+/// Capacity reported by READ CAPACITY (10) or (16).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadCapacity {
+    pub block_count: u64, // Last LBA + 1
+    pub block_size: u32,
+    pub protection_enabled: bool, // PROT_EN (16 only)
+    pub protection_type: u8, // P_TYPE + 1 when protection is enabled (16 only)
+    pub thin_provisioned: bool, // LBPME: logical block provisioning management enabled (16 only)
+    pub unmapped_reads_zero: bool, // LBPRZ (16 only)
+}
+
+impl ReadCapacity {
+    /// Parses READ CAPACITY (10) data. A last LBA of 0xFFFFFFFF means READ CAPACITY (16) is required.
+    pub fn parse_10(raw: &[u8]) -> Option<Self> {
+        if raw.len() < SCSI_READ_CAPACITY_10_SIZE {
+            return None;
+        }
+        let last_lba = u32::from_be_bytes([raw[0], raw[1], raw[2], raw[3]]) as u64;
+        Some(ReadCapacity {
+            block_count: last_lba + 1,
+            block_size: u32::from_be_bytes([raw[4], raw[5], raw[6], raw[7]]),
+            protection_enabled: false,
+            protection_type: 0,
+            thin_provisioned: false,
+            unmapped_reads_zero: false,
+        })
+    }
+
+    /// True when READ CAPACITY (10) could not report the full capacity.
+    pub fn needs_16(&self) -> bool {
+        self.block_count > CDB10_MAX_LBA
+    }
+
+    /// Parses READ CAPACITY (16) data.
+    pub fn parse_16(raw: &[u8]) -> Option<Self> {
+        if raw.len() < 16 {
+            return None;
+        }
+        let mut last_lba = [0u8; 8];
+        last_lba.copy_from_slice(&raw[0..8]);
+        let protection_enabled = raw[12] & 0x01 != 0;
+        Some(ReadCapacity {
+            block_count: u64::from_be_bytes(last_lba) + 1,
+            block_size: u32::from_be_bytes([raw[8], raw[9], raw[10], raw[11]]),
+            protection_enabled,
+            protection_type: if protection_enabled { ((raw[12] >> 1) & 0x07) + 1 } else { 0 },
+            thin_provisioned: raw[14] & 0x80 != 0,
+            unmapped_reads_zero: raw[14] & 0x40 != 0,
+        })
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_write_cdb_selection() {
+        let cdb = Cdb::read(0x1234, 8);
+        assert_eq!(cdb.as_bytes(), &[SCSI_READ_10, 0, 0, 0, 0x12, 0x34, 0, 0, 8, 0]);
+
+        // LBA beyond 32 bits needs READ(16)
+        let cdb = Cdb::write(0x1_0000_0000, 2);
+        assert_eq!(cdb.as_bytes().len(), 16);
+        assert_eq!(cdb.opcode(), SCSI_WRITE_16);
+        assert_eq!(&cdb.as_bytes()[2..10], &0x1_0000_0000u64.to_be_bytes());
+        assert_eq!(&cdb.as_bytes()[10..14], &2u32.to_be_bytes());
+
+        // Transfer length beyond 16 bits also needs the 16-byte variant
+        assert_eq!(Cdb::read(0, 0x1_0000).opcode(), SCSI_READ_16);
+        assert_eq!(Cdb::read_capacity_16(32).as_bytes()[1], SCSI_SAI_READ_CAPACITY_16);
+    }
+
+    #[test]
+    fn test_sense_parsing() {
+        let sense = SenseData { key: SenseKey::IllegalRequest, asc: 0x21, ascq: 0x00, information: Some(99) };
+        assert_eq!(SenseData::parse(&sense.to_fixed_bytes()), Some(sense));
+        assert!(matches!(sense_to_block_device_error(&sense), BlockDeviceError::InvalidParameter(_)));
+
+        // Descriptor format with an Information descriptor
+        let raw = [0x72, 0x03, 0x11, 0x00, 0, 0, 0, 12, 0x00, 0x0A, 0x80, 0, 0, 0, 0, 0, 0, 0, 0x10, 0x00];
+        let parsed = SenseData::parse(&raw).unwrap();
+        assert_eq!(parsed.key, SenseKey::MediumError);
+        assert_eq!((parsed.asc, parsed.ascq), (0x11, 0x00));
+        assert_eq!(parsed.information, Some(0x1000));
+
+        assert_eq!(SenseData::parse(&[0x00; 18]), None);
+
+        // Specific kinds for unsupported opcodes and a logical unit that is becoming ready
+        let invalid_opcode = SenseData { key: SenseKey::IllegalRequest, asc: 0x20, ascq: 0, information: None };
+        assert!(matches!(sense_to_block_device_error(&invalid_opcode), BlockDeviceError::NotSupported(_)));
+        let becoming_ready = SenseData { key: SenseKey::NotReady, asc: 0x04, ascq: 0x01, information: None };
+        assert!(matches!(sense_to_block_device_error(&becoming_ready), BlockDeviceError::TimedOut));
+        assert_eq!(ScsiStatus::from_u8(0x28), ScsiStatus::TaskSetFull);
+        assert!(ScsiStatus::TaskSetFull.is_retryable());
+    }
+
+    #[test]
+    fn test_unmap_and_mode_sense() {
+        let cdb = Cdb::synchronize_cache_16(0, 0);
+        assert_eq!((cdb.opcode(), cdb.lba(), cdb.transfer_length()), (SCSI_SYNCHRONIZE_CACHE_16, 0, 0));
+        assert_eq!(Cdb::write_16(5, 1, true).as_bytes()[1], 0x08);
+        assert_eq!(Cdb::read_16(7, 3).lba(), 7);
+
+        // A range larger than one descriptor, limited to a single descriptor per command
+        let (list, covered) = build_unmap_parameter_list(100, u32::MAX as u64 + 10, 1);
+        assert_eq!(covered, u32::MAX as u64);
+        assert_eq!(parse_unmap_parameter_list(&list), Some(vec![(100, u32::MAX)]));
+        let (list, covered) = build_unmap_parameter_list(100, u32::MAX as u64 + 10, 0);
+        assert_eq!(covered, u32::MAX as u64 + 10);
+        assert_eq!(parse_unmap_parameter_list(&list), Some(vec![(100, u32::MAX), (100 + u32::MAX as u64, 10)]));
+        assert_eq!(Cdb::unmap(list.len() as u16).as_bytes()[7..9], (list.len() as u16).to_be_bytes());
+
+        let mode = ModeSense { write_protected: true, write_cache_enabled: true, read_cache_disabled: false };
+        assert_eq!(ModeSense::parse_10(&mode.to_bytes_10()), Some(mode));
+        assert_eq!(Cdb::mode_sense_10(SCSI_MODE_PAGE_CACHING, 28).as_bytes()[2], SCSI_MODE_PAGE_CACHING);
+    }
+
+    #[test]
+    fn test_capacity_and_inquiry_parsing() {
+        let cap = ReadCapacity::parse_10(&[0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 2, 0]).unwrap();
+        assert!(cap.needs_16());
+
+        let mut raw = [0u8; 32];
+        raw[0..8].copy_from_slice(&0x2_0000_0000u64.to_be_bytes());
+        raw[8..12].copy_from_slice(&4096u32.to_be_bytes());
+        raw[12] = 0x03; // PROT_EN, P_TYPE 1 -> type 2
+        raw[14] = 0xC0; // LBPME, LBPRZ
+        let cap = ReadCapacity::parse_16(&raw).unwrap();
+        assert_eq!(cap.block_count, 0x2_0000_0001);
+        assert_eq!(cap.block_size, 4096);
+        assert_eq!((cap.protection_enabled, cap.protection_type), (true, 2));
+        assert!(cap.thin_provisioned && cap.unmapped_reads_zero);
+
+        let mut inquiry = [b' '; 36];
+        inquiry[0] = 0x00;
+        inquiry[1] = 0x80;
+        inquiry[8..13].copy_from_slice(b"SADAK");
+        inquiry[16..21].copy_from_slice(b"Flash");
+        inquiry[32..36].copy_from_slice(b"1.00");
+        let data = InquiryData::parse(&inquiry).unwrap();
+        assert_eq!((data.vendor.as_str(), data.product.as_str(), data.revision.as_str()), ("SADAK", "Flash", "1.00"));
+        assert!(data.removable);
+    }
+}
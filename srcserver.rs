@@ -0,0 +1,1110 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// Kullanıcı alanı dosya sistemi sunucusu
+// SADAK, Sahne Karnal'da kullanıcı alanında çalışır; istemciler dosya sistemine doğrudan
+// bağlanmak yerine bu modüldeki mesaj tabanlı protokolü kullanır.
+//
+// Çerçeve (tüm sayılar little-endian):
+//
+//   0  magic       4 bayt "SDKP"
+//   4  version     u8   protokol sürümü (PROTOCOL_VERSION)
+//   5  opcode      u8   işlem kodu; yanıtlarda 0x80 biti de ayarlı
+//   6  reserved    u16  sıfır
+//   8  request_id  u32  istemcinin verdiği kimlik; yanıt aynı kimliği taşır
+//   12 length      u32  yük uzunluğu
+//   16 payload
+//
+// Yanıt yükü i32 durumla başlar: 0 başarı (ardından işlemin sonucu), aksi halde POSIX errno
+// (ardından hata metni). Metinler u16 uzunluk + UTF-8, bayt dizileri u32 uzunluk + veri olarak yazılır.
+//
+// - `Request` / `Response`: mesajlar ve kodlamaları.
+// - `Session`: bir bağlantının durumu (açık dosya tanıtıcıları); çerçeveleri `Vfs` üzerinde yürütür.
+// - `Server`: bağlı SADAK ad alanını paylaşır, her bağlantı için bir `Session` açar.
+// - `Transport`: çerçeve taşıyıcısı. `Loopback` (süreç içi, iş parçacığı gerektirmez), std ile
+//   `ChannelTransport` (mpsc kanalı) ve `StreamTransport` (Unix soketi gibi akışlar).
+// - `Client`: istemci kitaplığı; istek kimlikleriyle ardışık (pipelined) isteklere izin verir.
+
+use crate::vfs::{map_vfs_error_to_fs_error, DirEntry, FileType, Metadata, Vfs, VfsFile};
+use crate::FileSystemError;
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use alloc::format;
+
+use core::cmp;
+use core::result::Result;
+
+pub const PROTOCOL_MAGIC: [u8; 4] = *b"SDKP";
+pub const PROTOCOL_VERSION: u8 = 1;
+/// Desteklenen en eski sürüm.
+pub const MIN_PROTOCOL_VERSION: u8 = 1;
+pub const HEADER_SIZE: usize = 16;
+/// Tek bir READ/WRITE isteğinde taşınan en fazla veri; istemci daha büyük yazmaları böler.
+pub const MAX_IO_SIZE: usize = 1024 * 1024;
+/// Kabul edilen en büyük yük (veri + alanlar).
+pub const MAX_PAYLOAD_SIZE: usize = MAX_IO_SIZE + 4096;
+/// READDIR yanıtı başına varsayılan girdi sayısı.
+pub const DEFAULT_READDIR_PAGE: u32 = 256;
+/// READDIR yanıtı başına en fazla girdi; istemcinin istediği daha büyük değerler buna indirilir.
+pub const MAX_READDIR_PAGE: u32 = 1024;
+
+const RESPONSE_FLAG: u8 = 0x80;
+
+// İşlem kodları
+pub const OP_HELLO: u8 = 0;
+pub const OP_OPEN: u8 = 1;
+pub const OP_READ: u8 = 2;
+pub const OP_WRITE: u8 = 3;
+pub const OP_STAT: u8 = 4;
+pub const OP_READDIR: u8 = 5;
+pub const OP_RENAME: u8 = 6;
+pub const OP_UNLINK: u8 = 7;
+pub const OP_FSYNC: u8 = 8;
+pub const OP_CLOSE: u8 = 9;
+
+fn malformed(what: &str) -> FileSystemError {
+    FileSystemError::InvalidData(format!("Malformed protocol frame: {}", what))
+}
+
+/// Çerçeve başlığı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameHeader {
+    pub version: u8,
+    pub opcode: u8,
+    pub response: bool,
+    pub request_id: u32,
+    pub length: u32,
+}
+
+impl FrameHeader {
+    pub fn parse(bytes: &[u8]) -> Result<Self, FileSystemError> {
+        if bytes.len() < HEADER_SIZE {
+            return Err(malformed("short header"));
+        }
+        if bytes[0..4] != PROTOCOL_MAGIC {
+            return Err(malformed("bad magic"));
+        }
+        let length = u32::from_le_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]);
+        if length as usize > MAX_PAYLOAD_SIZE {
+            return Err(malformed("payload too large"));
+        }
+        Ok(FrameHeader {
+            version: bytes[4],
+            opcode: bytes[5] & !RESPONSE_FLAG,
+            response: bytes[5] & RESPONSE_FLAG != 0,
+            request_id: u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]),
+            length,
+        })
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&PROTOCOL_MAGIC);
+        out.push(self.version);
+        out.push(if self.response { self.opcode | RESPONSE_FLAG } else { self.opcode });
+        out.extend_from_slice(&[0, 0]);
+        out.extend_from_slice(&self.request_id.to_le_bytes());
+        out.extend_from_slice(&self.length.to_le_bytes());
+    }
+}
+
+/// Tam bir çerçeveyi başlık ve yük olarak ayırır.
+pub fn split_frame(frame: &[u8]) -> Result<(FrameHeader, &[u8]), FileSystemError> {
+    let header = FrameHeader::parse(frame)?;
+    if frame.len() != HEADER_SIZE + header.length as usize {
+        return Err(malformed("length does not match header"));
+    }
+    Ok((header, &frame[HEADER_SIZE..]))
+}
+
+fn build_frame(version: u8, opcode: u8, response: bool, request_id: u32, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(HEADER_SIZE + payload.len());
+    FrameHeader { version, opcode, response, request_id, length: payload.len() as u32 }.write(&mut frame);
+    frame.extend_from_slice(payload);
+    frame
+}
+
+// İstemciden gelen `offset`ten itibaren `len` baytlık aralık u64'e sığmalıdır
+fn check_io_range(offset: u64, len: usize) -> Result<(), FileSystemError> {
+    offset
+        .checked_add(len as u64)
+        .map(|_| ())
+        .ok_or_else(|| FileSystemError::InvalidParameter(format!("Offset {} + {} bytes overflows", offset, len)))
+}
+
+// Yük yazıcı
+struct Encoder {
+    out: Vec<u8>,
+}
+
+impl Encoder {
+    fn new() -> Self {
+        Encoder { out: Vec::new() }
+    }
+
+    fn u8(&mut self, v: u8) {
+        self.out.push(v);
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.out.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn i32(&mut self, v: i32) {
+        self.out.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn u64(&mut self, v: u64) {
+        self.out.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn str(&mut self, s: &str) -> Result<(), FileSystemError> {
+        // Uzunluk alanı 16 bit; daha uzun metinler kırpılmaz, reddedilir
+        let len = u16::try_from(s.len())
+            .map_err(|_| FileSystemError::NameTooLong(format!("String of {} bytes exceeds {}", s.len(), u16::MAX)))?;
+        self.out.extend_from_slice(&len.to_le_bytes());
+        self.out.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+
+    fn bytes(&mut self, data: &[u8]) {
+        self.u32(data.len() as u32);
+        self.out.extend_from_slice(data);
+    }
+}
+
+// Yük okuyucu
+struct Decoder<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], FileSystemError> {
+        if self.data.len() < count {
+            return Err(malformed("truncated payload"));
+        }
+        let (head, tail) = self.data.split_at(count);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, FileSystemError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, FileSystemError> {
+        let b = self.take(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, FileSystemError> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn i32(&mut self) -> Result<i32, FileSystemError> {
+        Ok(self.u32()? as i32)
+    }
+
+    fn u64(&mut self) -> Result<u64, FileSystemError> {
+        let b = self.take(8)?;
+        let mut v = [0u8; 8];
+        v.copy_from_slice(b);
+        Ok(u64::from_le_bytes(v))
+    }
+
+    fn str(&mut self) -> Result<String, FileSystemError> {
+        let len = self.u16()? as usize;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| malformed("invalid UTF-8 string"))
+    }
+
+    fn bytes(&mut self) -> Result<Vec<u8>, FileSystemError> {
+        let len = self.u32()? as usize;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    fn finish(&self) -> Result<(), FileSystemError> {
+        if self.data.is_empty() {
+            Ok(())
+        } else {
+            Err(malformed("trailing bytes"))
+        }
+    }
+}
+
+fn file_type_code(file_type: FileType) -> u8 {
+    match file_type {
+        FileType::File => 0,
+        FileType::Directory => 1,
+        FileType::Symlink => 2,
+    }
+}
+
+fn file_type_from_code(code: u8) -> Result<FileType, FileSystemError> {
+    match code {
+        0 => Ok(FileType::File),
+        1 => Ok(FileType::Directory),
+        2 => Ok(FileType::Symlink),
+        _ => Err(malformed("unknown file type")),
+    }
+}
+
+/// İstemci istekleri.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    /// Sürüm anlaşması: sunucu aralıktaki en yüksek desteklediği sürümü seçer.
+    Hello { min_version: u8, max_version: u8 },
+    /// Dosyayı açar (vfs O_* bayrakları); yanıt `Handle`.
+    Open { path: String, flags: u32, mode: u32 },
+    /// En fazla `length` bayt okur (MAX_IO_SIZE ile sınırlı); yanıt `Data`, dosya sonunda boş.
+    Read { handle: u64, offset: u64, length: u32 },
+    /// Yanıt `Written`.
+    Write { handle: u64, offset: u64, data: Vec<u8> },
+    /// Yanıt `Metadata`.
+    Stat { path: String },
+    /// `start`. girdiden itibaren en fazla `max_entries` girdi; yanıt `Entries`.
+    ReadDir { path: String, start: u32, max_entries: u32 },
+    Rename { from: String, to: String },
+    Unlink { path: String },
+    Fsync { handle: u64 },
+    Close { handle: u64 },
+}
+
+impl Request {
+    pub fn opcode(&self) -> u8 {
+        match self {
+            Request::Hello { .. } => OP_HELLO,
+            Request::Open { .. } => OP_OPEN,
+            Request::Read { .. } => OP_READ,
+            Request::Write { .. } => OP_WRITE,
+            Request::Stat { .. } => OP_STAT,
+            Request::ReadDir { .. } => OP_READDIR,
+            Request::Rename { .. } => OP_RENAME,
+            Request::Unlink { .. } => OP_UNLINK,
+            Request::Fsync { .. } => OP_FSYNC,
+            Request::Close { .. } => OP_CLOSE,
+        }
+    }
+
+    /// İsteği `request_id` kimliğiyle bir çerçeveye kodlar. 65535 bayttan uzun yollar
+    /// NameTooLong ile reddedilir.
+    pub fn encode(&self, version: u8, request_id: u32) -> Result<Vec<u8>, FileSystemError> {
+        let mut e = Encoder::new();
+        match self {
+            Request::Hello { min_version, max_version } => {
+                e.u8(*min_version);
+                e.u8(*max_version);
+            }
+            Request::Open { path, flags, mode } => {
+                e.str(path)?;
+                e.u32(*flags);
+                e.u32(*mode);
+            }
+            Request::Read { handle, offset, length } => {
+                e.u64(*handle);
+                e.u64(*offset);
+                e.u32(*length);
+            }
+            Request::Write { handle, offset, data } => {
+                e.u64(*handle);
+                e.u64(*offset);
+                e.bytes(data);
+            }
+            Request::Stat { path } | Request::Unlink { path } => e.str(path)?,
+            Request::ReadDir { path, start, max_entries } => {
+                e.str(path)?;
+                e.u32(*start);
+                e.u32(*max_entries);
+            }
+            Request::Rename { from, to } => {
+                e.str(from)?;
+                e.str(to)?;
+            }
+            Request::Fsync { handle } | Request::Close { handle } => e.u64(*handle),
+        }
+        Ok(build_frame(version, self.opcode(), false, request_id, &e.out))
+    }
+
+    /// İstek yükünü çözer.
+    pub fn decode(opcode: u8, payload: &[u8]) -> Result<Self, FileSystemError> {
+        let mut d = Decoder { data: payload };
+        let request = match opcode {
+            OP_HELLO => Request::Hello { min_version: d.u8()?, max_version: d.u8()? },
+            OP_OPEN => Request::Open { path: d.str()?, flags: d.u32()?, mode: d.u32()? },
+            OP_READ => Request::Read { handle: d.u64()?, offset: d.u64()?, length: d.u32()? },
+            OP_WRITE => Request::Write { handle: d.u64()?, offset: d.u64()?, data: d.bytes()? },
+            OP_STAT => Request::Stat { path: d.str()? },
+            OP_READDIR => Request::ReadDir { path: d.str()?, start: d.u32()?, max_entries: d.u32()? },
+            OP_RENAME => Request::Rename { from: d.str()?, to: d.str()? },
+            OP_UNLINK => Request::Unlink { path: d.str()? },
+            OP_FSYNC => Request::Fsync { handle: d.u64()? },
+            OP_CLOSE => Request::Close { handle: d.u64()? },
+            other => return Err(FileSystemError::NotSupported(format!("Unknown opcode {}", other))),
+        };
+        d.finish()?;
+        Ok(request)
+    }
+}
+
+/// Sunucu yanıtları.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    Hello { version: u8, max_io: u32 },
+    Handle(u64),
+    Data(Vec<u8>),
+    Written(u32),
+    Metadata(Metadata),
+    /// `more`: dizinde bu sayfadan sonra girdi var.
+    Entries { entries: Vec<DirEntry>, more: bool },
+    /// Sonuç taşımayan işlemler (rename, unlink, fsync, close).
+    Done,
+    /// POSIX errno ve hata metni.
+    Error { errno: i32, message: String },
+}
+
+impl Response {
+    pub fn from_error(e: &FileSystemError) -> Self {
+        Response::Error { errno: e.errno(), message: format!("{}", e) }
+    }
+
+    /// Hata yanıtını FileSystemError'a, diğerlerini kendisine çevirir.
+    pub fn into_result(self) -> Result<Response, FileSystemError> {
+        match self {
+            Response::Error { errno, message } => Err(FileSystemError::from_errno(errno, message)),
+            other => Ok(other),
+        }
+    }
+
+    /// `opcode` isteğinin yanıtını bir çerçeveye kodlar. 65535 bayttan uzun metinler
+    /// NameTooLong ile reddedilir.
+    pub fn encode(&self, version: u8, opcode: u8, request_id: u32) -> Result<Vec<u8>, FileSystemError> {
+        let mut e = Encoder::new();
+        match self {
+            Response::Error { errno, message } => {
+                e.i32(*errno);
+                e.str(message)?;
+            }
+            ok => {
+                e.i32(0);
+                match ok {
+                    Response::Hello { version, max_io } => {
+                        e.u8(*version);
+                        e.u32(*max_io);
+                    }
+                    Response::Handle(handle) => e.u64(*handle),
+                    Response::Data(data) => e.bytes(data),
+                    Response::Written(count) => e.u32(*count),
+                    Response::Metadata(meta) => {
+                        e.u8(file_type_code(meta.file_type));
+                        e.u32(meta.mode);
+                        e.u32(meta.uid);
+                        e.u32(meta.gid);
+                        e.u32(meta.nlink);
+                        e.u64(meta.size);
+                        e.u64(meta.inode);
+                        e.u64(meta.atime);
+                        e.u64(meta.mtime);
+                        e.u64(meta.ctime);
+                    }
+                    Response::Entries { entries, more } => {
+                        e.u32(entries.len() as u32);
+                        for entry in entries.iter() {
+                            e.str(&entry.name)?;
+                            e.u8(file_type_code(entry.file_type));
+                            e.u64(entry.inode);
+                        }
+                        e.u8(*more as u8);
+                    }
+                    Response::Done | Response::Error { .. } => {}
+                }
+            }
+        }
+        Ok(build_frame(version, opcode, true, request_id, &e.out))
+    }
+
+    /// `opcode` isteğinin yanıt yükünü çözer.
+    pub fn decode(opcode: u8, payload: &[u8]) -> Result<Self, FileSystemError> {
+        let mut d = Decoder { data: payload };
+        let status = d.i32()?;
+        if status != 0 {
+            let message = d.str()?;
+            d.finish()?;
+            return Ok(Response::Error { errno: status, message });
+        }
+        let response = match opcode {
+            OP_HELLO => Response::Hello { version: d.u8()?, max_io: d.u32()? },
+            OP_OPEN => Response::Handle(d.u64()?),
+            OP_READ => Response::Data(d.bytes()?),
+            OP_WRITE => Response::Written(d.u32()?),
+            OP_STAT => {
+                let file_type = file_type_from_code(d.u8()?)?;
+                Response::Metadata(Metadata {
+                    file_type,
+                    mode: d.u32()?,
+                    uid: d.u32()?,
+                    gid: d.u32()?,
+                    nlink: d.u32()?,
+                    size: d.u64()?,
+                    inode: d.u64()?,
+                    atime: d.u64()?,
+                    mtime: d.u64()?,
+                    ctime: d.u64()?,
+                })
+            }
+            OP_READDIR => {
+                let count = d.u32()? as usize;
+                let mut entries = Vec::with_capacity(cmp::min(count, DEFAULT_READDIR_PAGE as usize));
+                for _ in 0..count {
+                    let name = d.str()?;
+                    let file_type = file_type_from_code(d.u8()?)?;
+                    entries.push(DirEntry { name, file_type, inode: d.u64()? });
+                }
+                Response::Entries { entries, more: d.u8()? != 0 }
+            }
+            OP_RENAME | OP_UNLINK | OP_FSYNC | OP_CLOSE => Response::Done,
+            other => return Err(FileSystemError::NotSupported(format!("Unknown opcode {}", other))),
+        };
+        d.finish()?;
+        Ok(response)
+    }
+}
+
+/// Bir bağlantının sunucu tarafı durumu: anlaşılan sürüm ve açık dosyalar.
+/// Oturum kapanınca (drop) açık dosyalar da kapanır.
+pub struct Session {
+    vfs: Arc<Vfs>,
+    version: u8,
+    files: BTreeMap<u64, Box<dyn VfsFile>>,
+    next_handle: u64,
+}
+
+impl Session {
+    pub fn new(vfs: Arc<Vfs>) -> Self {
+        Session { vfs, version: PROTOCOL_VERSION, files: BTreeMap::new(), next_handle: 1 }
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn open_files(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Bir istek çerçevesini yürütür ve yanıt çerçevesini döndürür. İşlem hataları yanıtın
+    /// içinde errno olarak döner; başlığı çözülemeyen çerçeveler hata olarak döner ve
+    /// bağlantı kapatılmalıdır.
+    pub fn handle_frame(&mut self, frame: &[u8]) -> Result<Vec<u8>, FileSystemError> {
+        let (header, payload) = split_frame(frame)?;
+        if header.response {
+            return Err(malformed("response frame sent to server"));
+        }
+        let response = if header.opcode != OP_HELLO
+            && (header.version < MIN_PROTOCOL_VERSION || header.version > PROTOCOL_VERSION)
+        {
+            Response::from_error(&FileSystemError::NotSupported(format!("Protocol version {}", header.version)))
+        } else {
+            match Request::decode(header.opcode, payload).and_then(|request| self.execute(request)) {
+                Ok(response) => response,
+                Err(e) => Response::from_error(&e),
+            }
+        };
+        // Kodlanamayan yanıt (ör. çok uzun hata metni) yerine kodlama hatası errno ile döner
+        response
+            .encode(self.version, header.opcode, header.request_id)
+            .or_else(|e| Response::from_error(&e).encode(self.version, header.opcode, header.request_id))
+    }
+
+    fn file(&mut self, handle: u64) -> Result<&mut Box<dyn VfsFile>, FileSystemError> {
+        self.files
+            .get_mut(&handle)
+            .ok_or_else(|| FileSystemError::InvalidParameter(format!("Invalid handle {}", handle)))
+    }
+
+    /// İsteği `Vfs` üzerinde yürütür.
+    pub fn execute(&mut self, request: Request) -> Result<Response, FileSystemError> {
+        match request {
+            Request::Hello { min_version, max_version } => {
+                let version = cmp::min(max_version, PROTOCOL_VERSION);
+                if version < cmp::max(min_version, MIN_PROTOCOL_VERSION) {
+                    return Err(FileSystemError::NotSupported(format!(
+                        "Protocol versions {}..={} (server supports {}..={})",
+                        min_version, max_version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION
+                    )));
+                }
+                self.version = version;
+                Ok(Response::Hello { version, max_io: MAX_IO_SIZE as u32 })
+            }
+            Request::Open { path, flags, mode } => {
+                let file = self.vfs.open(&path, flags, mode).map_err(map_vfs_error_to_fs_error)?;
+                let handle = self.next_handle;
+                self.next_handle += 1;
+                self.files.insert(handle, file);
+                Ok(Response::Handle(handle))
+            }
+            Request::Read { handle, offset, length } => {
+                let mut data = vec![0u8; cmp::min(length as usize, MAX_IO_SIZE)];
+                check_io_range(offset, data.len())?;
+                let file = self.file(handle)?;
+                let mut done = 0;
+                while done < data.len() {
+                    let count = file.read_at(offset + done as u64, &mut data[done..]).map_err(map_vfs_error_to_fs_error)?;
+                    if count == 0 {
+                        break;
+                    }
+                    done += count;
+                }
+                data.truncate(done);
+                Ok(Response::Data(data))
+            }
+            Request::Write { handle, offset, data } => {
+                if data.len() > MAX_IO_SIZE {
+                    return Err(FileSystemError::InvalidParameter(format!("Write of {} bytes exceeds {}", data.len(), MAX_IO_SIZE)));
+                }
+                check_io_range(offset, data.len())?;
+                let file = self.file(handle)?;
+                let mut done = 0;
+                while done < data.len() {
+                    let count = file.write_at(offset + done as u64, &data[done..]).map_err(map_vfs_error_to_fs_error)?;
+                    if count == 0 {
+                        break;
+                    }
+                    done += count;
+                }
+                Ok(Response::Written(done as u32))
+            }
+            Request::Stat { path } => Ok(Response::Metadata(self.vfs.metadata(&path).map_err(map_vfs_error_to_fs_error)?)),
+            Request::ReadDir { path, start, max_entries } => {
+                let max_entries = if max_entries == 0 { DEFAULT_READDIR_PAGE } else { cmp::min(max_entries, MAX_READDIR_PAGE) } as usize;
+                let mut iter = self.vfs.read_dir(&path).map_err(map_vfs_error_to_fs_error)?.skip(start as usize);
+                let entries: Vec<DirEntry> = iter.by_ref().take(max_entries).collect();
+                let more = iter.next().is_some();
+                Ok(Response::Entries { entries, more })
+            }
+            Request::Rename { from, to } => {
+                self.vfs.rename(&from, &to).map_err(map_vfs_error_to_fs_error)?;
+                Ok(Response::Done)
+            }
+            Request::Unlink { path } => {
+                self.vfs.unlink(&path).map_err(map_vfs_error_to_fs_error)?;
+                Ok(Response::Done)
+            }
+            Request::Fsync { handle } => {
+                self.file(handle)?.sync().map_err(map_vfs_error_to_fs_error)?;
+                Ok(Response::Done)
+            }
+            Request::Close { handle } => {
+                self.files
+                    .remove(&handle)
+                    .ok_or_else(|| FileSystemError::InvalidParameter(format!("Invalid handle {}", handle)))?;
+                Ok(Response::Done)
+            }
+        }
+    }
+}
+
+/// Çerçeve taşıyıcısı (ileti sırası korunur).
+pub trait Transport: Send {
+    fn send(&mut self, frame: &[u8]) -> Result<(), FileSystemError>;
+
+    /// Sıradaki çerçeveyi bekler; karşı taraf kapandıysa None döner.
+    fn recv(&mut self) -> Result<Option<Vec<u8>>, FileSystemError>;
+}
+
+/// Bağlı SADAK ad alanını istemcilere sunar.
+pub struct Server {
+    vfs: Arc<Vfs>,
+}
+
+impl Server {
+    pub fn new(vfs: Arc<Vfs>) -> Self {
+        Server { vfs }
+    }
+
+    pub fn session(&self) -> Session {
+        Session::new(self.vfs.clone())
+    }
+
+    /// Bağlantı kapanana kadar istekleri yanıtlar. Çözülemeyen bir çerçeve bağlantıyı
+    /// hatayla sonlandırır.
+    pub fn serve<T: Transport + ?Sized>(&self, transport: &mut T) -> Result<(), FileSystemError> {
+        let mut session = self.session();
+        while let Some(frame) = transport.recv()? {
+            let response = session.handle_frame(&frame)?;
+            transport.send(&response)?;
+        }
+        Ok(())
+    }
+}
+
+/// Süreç içi taşıyıcı: gönderilen her istek hemen kendi oturumunda yürütülür ve yanıt
+/// `recv` için sıraya alınır. İş parçacığı gerektirmez (no_std ortamında sınama için).
+pub struct Loopback {
+    session: Session,
+    responses: VecDeque<Vec<u8>>,
+}
+
+impl Loopback {
+    pub fn new(server: &Server) -> Self {
+        Loopback { session: server.session(), responses: VecDeque::new() }
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+}
+
+impl Transport for Loopback {
+    fn send(&mut self, frame: &[u8]) -> Result<(), FileSystemError> {
+        let response = self.session.handle_frame(frame)?;
+        self.responses.push_back(response);
+        Ok(())
+    }
+
+    fn recv(&mut self) -> Result<Option<Vec<u8>>, FileSystemError> {
+        Ok(self.responses.pop_front())
+    }
+}
+
+/// mpsc kanalı üzerinden taşıyıcı; `channel` iki ucu birlikte oluşturur.
+#[cfg(feature = "std")]
+pub struct ChannelTransport {
+    sender: std::sync::mpsc::Sender<Vec<u8>>,
+    receiver: std::sync::mpsc::Receiver<Vec<u8>>,
+}
+
+#[cfg(feature = "std")]
+pub fn channel() -> (ChannelTransport, ChannelTransport) {
+    let (a_sender, b_receiver) = std::sync::mpsc::channel();
+    let (b_sender, a_receiver) = std::sync::mpsc::channel();
+    (
+        ChannelTransport { sender: a_sender, receiver: a_receiver },
+        ChannelTransport { sender: b_sender, receiver: b_receiver },
+    )
+}
+
+#[cfg(feature = "std")]
+impl Transport for ChannelTransport {
+    fn send(&mut self, frame: &[u8]) -> Result<(), FileSystemError> {
+        self.sender
+            .send(frame.to_vec())
+            .map_err(|_| FileSystemError::IOError(String::from("Channel closed")))
+    }
+
+    fn recv(&mut self) -> Result<Option<Vec<u8>>, FileSystemError> {
+        Ok(self.receiver.recv().ok())
+    }
+}
+
+/// Bayt akışı üzerinden taşıyıcı (ör. `UnixStream`); çerçeveler başlıktaki uzunlukla ayrılır.
+#[cfg(feature = "std")]
+pub struct StreamTransport<S: std::io::Read + std::io::Write + Send> {
+    stream: S,
+}
+
+#[cfg(feature = "std")]
+impl<S: std::io::Read + std::io::Write + Send> StreamTransport<S> {
+    pub fn new(stream: S) -> Self {
+        StreamTransport { stream }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: std::io::Read + std::io::Write + Send> Transport for StreamTransport<S> {
+    fn send(&mut self, frame: &[u8]) -> Result<(), FileSystemError> {
+        self.stream.write_all(frame)?;
+        self.stream.flush()?;
+        Ok(())
+    }
+
+    fn recv(&mut self) -> Result<Option<Vec<u8>>, FileSystemError> {
+        let mut frame = vec![0u8; HEADER_SIZE];
+        // Başlığın ilk baytında akış sonu: bağlantı düzgün kapandı
+        let mut filled = 0;
+        while filled < HEADER_SIZE {
+            let count = self.stream.read(&mut frame[filled..])?;
+            if count == 0 {
+                return if filled == 0 { Ok(None) } else { Err(malformed("connection closed inside a frame")) };
+            }
+            filled += count;
+        }
+        let header = FrameHeader::parse(&frame)?;
+        frame.resize(HEADER_SIZE + header.length as usize, 0);
+        self.stream.read_exact(&mut frame[HEADER_SIZE..])?;
+        Ok(Some(frame))
+    }
+}
+
+/// İstemci kitaplığı. Her istek yeni bir kimlik alır; `send` + `wait` ile birden çok istek
+/// yanıt beklemeden gönderilebilir, yanıtlar hangi sırayla gelirse gelsin kimlikle eşleştirilir.
+pub struct Client<T: Transport> {
+    transport: T,
+    version: u8,
+    max_io: usize,
+    next_id: u32,
+    // Gönderilmiş ama yanıtı alınmamış isteklerin işlem kodları
+    in_flight: BTreeMap<u32, u8>,
+    // Başka bir kimlik beklenirken gelen yanıtlar
+    received: BTreeMap<u32, Response>,
+}
+
+impl<T: Transport> Client<T> {
+    /// Bağlanır ve sürüm anlaşması yapar.
+    pub fn connect(transport: T) -> Result<Self, FileSystemError> {
+        let mut client = Client {
+            transport,
+            version: PROTOCOL_VERSION,
+            max_io: MAX_IO_SIZE,
+            next_id: 1,
+            in_flight: BTreeMap::new(),
+            received: BTreeMap::new(),
+        };
+        match client.call(Request::Hello { min_version: MIN_PROTOCOL_VERSION, max_version: PROTOCOL_VERSION })? {
+            Response::Hello { version, max_io } => {
+                client.version = version;
+                client.max_io = (max_io as usize).clamp(1, MAX_IO_SIZE);
+            }
+            other => return Err(unexpected(&other)),
+        }
+        Ok(client)
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn into_transport(self) -> T {
+        self.transport
+    }
+
+    /// İsteği gönderir ve kimliğini döndürür.
+    pub fn send(&mut self, request: &Request) -> Result<u32, FileSystemError> {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        self.transport.send(&request.encode(self.version, id)?)?;
+        self.in_flight.insert(id, request.opcode());
+        Ok(id)
+    }
+
+    /// `id` isteğinin yanıtını bekler. Hata yanıtları `Err` olarak döner.
+    pub fn wait(&mut self, id: u32) -> Result<Response, FileSystemError> {
+        loop {
+            if let Some(response) = self.received.remove(&id) {
+                return response.into_result();
+            }
+            if !self.in_flight.contains_key(&id) {
+                return Err(FileSystemError::InvalidParameter(format!("No request with id {}", id)));
+            }
+            let frame = self
+                .transport
+                .recv()?
+                .ok_or_else(|| FileSystemError::IOError(String::from("Server closed the connection")))?;
+            let (header, payload) = split_frame(&frame)?;
+            let opcode = self
+                .in_flight
+                .remove(&header.request_id)
+                .ok_or_else(|| malformed("response to an unknown request"))?;
+            if !header.response || header.opcode != opcode {
+                return Err(malformed("response does not match the request"));
+            }
+            self.received.insert(header.request_id, Response::decode(opcode, payload)?);
+        }
+    }
+
+    pub fn call(&mut self, request: Request) -> Result<Response, FileSystemError> {
+        let id = self.send(&request)?;
+        self.wait(id)
+    }
+
+    pub fn open(&mut self, path: &str, flags: u32, mode: u32) -> Result<u64, FileSystemError> {
+        match self.call(Request::Open { path: String::from(path), flags, mode })? {
+            Response::Handle(handle) => Ok(handle),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// `buf`i doldurana veya dosya sonuna gelene kadar okur.
+    pub fn read(&mut self, handle: u64, offset: u64, buf: &mut [u8]) -> Result<usize, FileSystemError> {
+        let mut done = 0;
+        while done < buf.len() {
+            let length = cmp::min(buf.len() - done, self.max_io) as u32;
+            let data = match self.call(Request::Read { handle, offset: offset + done as u64, length })? {
+                Response::Data(data) => data,
+                other => return Err(unexpected(&other)),
+            };
+            if data.is_empty() || data.len() > length as usize {
+                break;
+            }
+            buf[done..done + data.len()].copy_from_slice(&data);
+            done += data.len();
+        }
+        Ok(done)
+    }
+
+    /// Tümünü yazar; büyük veriler MAX_IO_SIZE parçalara bölünür.
+    pub fn write(&mut self, handle: u64, offset: u64, data: &[u8]) -> Result<usize, FileSystemError> {
+        let mut done = 0;
+        while done < data.len() {
+            let end = cmp::min(data.len(), done + self.max_io);
+            let request = Request::Write { handle, offset: offset + done as u64, data: data[done..end].to_vec() };
+            match self.call(request)? {
+                Response::Written(0) => break,
+                Response::Written(count) => done += count as usize,
+                other => return Err(unexpected(&other)),
+            }
+        }
+        Ok(done)
+    }
+
+    pub fn stat(&mut self, path: &str) -> Result<Metadata, FileSystemError> {
+        match self.call(Request::Stat { path: String::from(path) })? {
+            Response::Metadata(meta) => Ok(meta),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Dizinin tüm girdilerini `page_size`lık sayfalarla okur (0: sunucu varsayılanı).
+    pub fn read_dir(&mut self, path: &str, page_size: u32) -> Result<Vec<DirEntry>, FileSystemError> {
+        let mut all = Vec::new();
+        loop {
+            let request = Request::ReadDir { path: String::from(path), start: all.len() as u32, max_entries: page_size };
+            match self.call(request)? {
+                Response::Entries { entries, more } => {
+                    let empty = entries.is_empty();
+                    all.extend(entries);
+                    if !more || empty {
+                        return Ok(all);
+                    }
+                }
+                other => return Err(unexpected(&other)),
+            }
+        }
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), FileSystemError> {
+        self.call(Request::Rename { from: String::from(from), to: String::from(to) }).map(|_| ())
+    }
+
+    pub fn unlink(&mut self, path: &str) -> Result<(), FileSystemError> {
+        self.call(Request::Unlink { path: String::from(path) }).map(|_| ())
+    }
+
+    pub fn fsync(&mut self, handle: u64) -> Result<(), FileSystemError> {
+        self.call(Request::Fsync { handle }).map(|_| ())
+    }
+
+    pub fn close(&mut self, handle: u64) -> Result<(), FileSystemError> {
+        self.call(Request::Close { handle }).map(|_| ())
+    }
+}
+
+fn unexpected(response: &Response) -> FileSystemError {
+    malformed(&format!("unexpected response {:?}", response))
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+    use crate::error::errno;
+    use crate::vfs::{FileSystem, MemFs, O_CREAT, O_RDONLY, O_RDWR};
+
+    fn namespace() -> Arc<Vfs> {
+        let vfs = Arc::new(Vfs::new());
+        vfs.mount("/", Arc::new(MemFs::new()) as Arc<dyn FileSystem>).unwrap();
+        vfs.mkdir("/docs", 0o755).unwrap();
+        vfs
+    }
+
+    #[test]
+    fn test_frame_encoding_round_trip() {
+        let requests = [
+            Request::Hello { min_version: 1, max_version: 3 },
+            Request::Open { path: String::from("/docs/a.txt"), flags: O_RDWR | O_CREAT, mode: 0o640 },
+            Request::Read { handle: 7, offset: 1 << 40, length: 4096 },
+            Request::Write { handle: 7, offset: 12, data: b"veri".to_vec() },
+            Request::Stat { path: String::from("/ç") },
+            Request::ReadDir { path: String::from("/"), start: 2, max_entries: 10 },
+            Request::Rename { from: String::from("/a"), to: String::from("/b") },
+            Request::Unlink { path: String::from("/b") },
+            Request::Fsync { handle: 7 },
+            Request::Close { handle: 7 },
+        ];
+        for (id, request) in requests.iter().enumerate() {
+            let frame = request.encode(PROTOCOL_VERSION, id as u32).unwrap();
+            let (header, payload) = split_frame(&frame).unwrap();
+            assert_eq!((header.request_id, header.response), (id as u32, false));
+            assert_eq!(&Request::decode(header.opcode, payload).unwrap(), request);
+        }
+
+        let mut meta = Metadata::new(FileType::Symlink, 9);
+        meta.inode = 42;
+        meta.mtime = 1_700_000_000;
+        let responses = [
+            (OP_HELLO, Response::Hello { version: 1, max_io: 65536 }),
+            (OP_OPEN, Response::Handle(3)),
+            (OP_READ, Response::Data(vec![1, 2, 3])),
+            (OP_WRITE, Response::Written(3)),
+            (OP_STAT, Response::Metadata(meta)),
+            (
+                OP_READDIR,
+                Response::Entries {
+                    entries: vec![DirEntry { name: String::from("x"), file_type: FileType::Directory, inode: 5 }],
+                    more: true,
+                },
+            ),
+            (OP_UNLINK, Response::Done),
+            (OP_STAT, Response::Error { errno: errno::ENOENT, message: String::from("Not found: /x") }),
+        ];
+        for (opcode, response) in responses.iter() {
+            let frame = response.encode(PROTOCOL_VERSION, *opcode, 99).unwrap();
+            let (header, payload) = split_frame(&frame).unwrap();
+            assert!(header.response);
+            assert_eq!(header.opcode, *opcode);
+            assert_eq!(&Response::decode(*opcode, payload).unwrap(), response);
+        }
+
+        // Bozuk çerçeveler
+        let frame = Request::Stat { path: String::from("/x") }.encode(PROTOCOL_VERSION, 1).unwrap();
+        assert!(split_frame(&frame[..frame.len() - 1]).is_err());
+        let mut bad = frame.clone();
+        bad[0] = b'X';
+        assert!(split_frame(&bad).is_err());
+        assert!(Request::decode(OP_STAT, &[5, 0, b'/']).is_err());
+        assert!(Request::decode(OP_FSYNC, &[0u8; 9]).is_err());
+        assert!(matches!(Request::decode(77, &[]), Err(FileSystemError::NotSupported(_))));
+
+        // Desteklenmeyen sürümle gelen istek errno ile yanıtlanır, bağlantı sürer
+        let mut session = Session::new(namespace());
+        let reply = session.handle_frame(&Request::Stat { path: String::from("/") }.encode(9, 5).unwrap()).unwrap();
+        let (header, payload) = split_frame(&reply).unwrap();
+        assert_eq!(header.request_id, 5);
+        assert!(matches!(Response::decode(header.opcode, payload).unwrap(), Response::Error { errno: errno::EOPNOTSUPP, .. }));
+        let reply = session.handle_frame(&Request::Hello { min_version: 2, max_version: 4 }.encode(2, 6).unwrap()).unwrap();
+        let (header, payload) = split_frame(&reply).unwrap();
+        assert!(matches!(Response::decode(header.opcode, payload).unwrap(), Response::Error { .. }));
+    }
+
+    #[test]
+    fn test_untrusted_requests_are_bounded() {
+        // 16 bitlik uzunluğa sığmayan metinler kırpılmaz, reddedilir
+        let long = "a".repeat(u16::MAX as usize + 1);
+        assert!(matches!(Request::Stat { path: long.clone() }.encode(PROTOCOL_VERSION, 1), Err(FileSystemError::NameTooLong(_))));
+        let error = Response::Error { errno: errno::EIO, message: long };
+        assert!(matches!(error.encode(PROTOCOL_VERSION, OP_STAT, 1), Err(FileSystemError::NameTooLong(_))));
+
+        let mut session = Session::new(namespace());
+        let handle = match session.execute(Request::Open { path: String::from("/docs/f"), flags: O_CREAT | O_RDWR, mode: 0o644 }).unwrap() {
+            Response::Handle(handle) => handle,
+            other => panic!("unexpected {:?}", other),
+        };
+        // u64'ü taşan aralıklar
+        let write = Request::Write { handle, offset: u64::MAX - 1, data: b"veri".to_vec() };
+        assert!(matches!(session.execute(write), Err(FileSystemError::InvalidParameter(_))));
+        let read = Request::Read { handle, offset: u64::MAX, length: 4096 };
+        assert!(matches!(session.execute(read), Err(FileSystemError::InvalidParameter(_))));
+
+        // İstemcinin istediği sayfa boyutu sunucu sınırına indirilir
+        for i in 0..MAX_READDIR_PAGE + 10 {
+            session.vfs.mkdir(&format!("/docs/d{}", i), 0o755).unwrap();
+        }
+        let request = Request::ReadDir { path: String::from("/docs"), start: 0, max_entries: u32::MAX };
+        match session.execute(request).unwrap() {
+            Response::Entries { entries, more } => {
+                assert_eq!(entries.len(), MAX_READDIR_PAGE as usize);
+                assert!(more);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_client_over_loopback_and_channel() {
+        let vfs = namespace();
+        let server = Server::new(vfs.clone());
+
+        // Süreç içi, iş parçacığı olmadan
+        let mut client = Client::connect(Loopback::new(&server)).unwrap();
+        assert_eq!(client.version(), PROTOCOL_VERSION);
+        let handle = client.open("/docs/a.txt", O_RDWR | O_CREAT, 0o600).unwrap();
+        let data: Vec<u8> = (0..3 * MAX_IO_SIZE / 2).map(|i| (i % 251) as u8).collect();
+        assert_eq!(client.write(handle, 0, &data).unwrap(), data.len());
+        client.fsync(handle).unwrap();
+        let mut back = vec![0u8; data.len() + 10];
+        assert_eq!(client.read(handle, 0, &mut back).unwrap(), data.len());
+        assert_eq!(&back[..data.len()], &data[..]);
+        let meta = client.stat("/docs/a.txt").unwrap();
+        assert_eq!((meta.size, meta.mode, meta.file_type), (data.len() as u64, 0o600, FileType::File));
+        assert_eq!(client.transport.session().open_files(), 1);
+        client.close(handle).unwrap();
+        assert_eq!(client.transport.session().open_files(), 0);
+        assert!(matches!(client.close(handle), Err(FileSystemError::InvalidParameter(_))));
+
+        // Sunucu tarafındaki hatalar türlü hatalara döner
+        assert!(matches!(client.stat("/missing"), Err(FileSystemError::NotFound(_))));
+        assert!(matches!(client.open("/docs", O_RDONLY, 0), Err(FileSystemError::IsDirectory(_))));
+        assert!(matches!(client.unlink("/docs"), Err(FileSystemError::NotEmpty(_))));
+
+        // mpsc kanalı ve ayrı bir sunucu iş parçacığı
+        let (client_end, mut server_end) = channel();
+        let thread_server = Server::new(vfs.clone());
+        let worker = std::thread::spawn(move || thread_server.serve(&mut server_end));
+        let mut client = Client::connect(client_end).unwrap();
+        for i in 0..5 {
+            let h = client.open(&format!("/docs/f{}", i), O_RDWR | O_CREAT, 0o644).unwrap();
+            client.write(h, 0, b"x").unwrap();
+            client.close(h).unwrap();
+        }
+        let names: Vec<String> = client.read_dir("/docs", 2).unwrap().into_iter().map(|e| e.name).collect();
+        assert_eq!(names, vec!["a.txt", "f0", "f1", "f2", "f3", "f4"]);
+        client.rename("/docs/f0", "/docs/g0").unwrap();
+        client.unlink("/docs/f1").unwrap();
+        assert!(vfs.metadata("/docs/g0").is_ok());
+        assert!(vfs.metadata("/docs/f1").is_err());
+
+        // Ardışık istekler: yanıtlar kimlikle eşleşir, bekleme sırası önemli değil
+        let first = client.send(&Request::Stat { path: String::from("/docs/g0") }).unwrap();
+        let second = client.send(&Request::Stat { path: String::from("/nope") }).unwrap();
+        assert!(matches!(client.wait(second), Err(FileSystemError::NotFound(_))));
+        assert!(matches!(client.wait(first), Ok(Response::Metadata(_))));
+        assert!(client.wait(first).is_err());
+
+        drop(client);
+        worker.join().unwrap().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket_stream() {
+        let vfs = namespace();
+        let (client_socket, server_socket) = std::os::unix::net::UnixStream::pair().unwrap();
+        let server = Server::new(vfs.clone());
+        let worker = std::thread::spawn(move || server.serve(&mut StreamTransport::new(server_socket)));
+        let mut client = Client::connect(StreamTransport::new(client_socket)).unwrap();
+        let handle = client.open("/docs/s.bin", O_RDWR | O_CREAT, 0o644).unwrap();
+        assert_eq!(client.write(handle, 5, b"soket").unwrap(), 5);
+        let mut buf = [0xffu8; 16];
+        assert_eq!(client.read(handle, 0, &mut buf).unwrap(), 10);
+        assert_eq!(&buf[..10], b"\0\0\0\0\0soket");
+        client.close(handle).unwrap();
+        drop(client);
+        worker.join().unwrap().unwrap();
+    }
+}
+
+// Removed redundant print module and panic handler boilerplate.
+// The empty lib module configuration is kept.
+#[cfg(not(any(feature = "std", test)))] // Only when not building std or test
+pub mod lib {} // Keep the empty lib module if needed for the crate structure
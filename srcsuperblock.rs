@@ -20,6 +20,10 @@ use core::fmt; // For Debug, Display
 // Import the standard BlockDevice trait and its error type
 use crate::blockdevice::{BlockDevice, BlockDeviceError}; // Assuming these are in crate::blockdevice
 
+// Device geometry from the SADAK configuration (srcconfig.rs)
+use crate::config::{map_config_error_to_fs_error, DeviceConfig};
+use crate::inodetable::Inode;
+
 
 // Import alloc for String and format! for error messages
 use alloc::string::{String, ToString};
@@ -29,6 +33,9 @@ use alloc::format;
 // Define the location of the Superblock on the block device
 const SUPERBLOCK_BLOCK_ID: u64 = 0; // Superblock is typically located at block 0
 const SUPERBLOCK_MAGIC: u32 = 0x5ADAKF5B; // Example SADAK filesystem magic number (SADAK FS BLK)
+/// On-disk layout version written by `Superblock::new`. Mounting rejects other versions.
+/// 2: inodes carry double indirect and xattr block pointers (see crate::inodetable, crate::sadakfs).
+pub const SUPERBLOCK_VERSION: u32 = 2;
 
 
 // Depolama aygıtı türleri
@@ -87,7 +94,7 @@ impl Superblock {
     ) -> Self { // Return Self
         Superblock {
             magic: SUPERBLOCK_MAGIC, // Use the defined magic number
-            version: 1, // Initial version
+            version: SUPERBLOCK_VERSION,
             block_size,
             inode_size,
             blocks_count,
@@ -141,8 +148,53 @@ impl Superblock {
          // Mark as dirty?
     }
 
+    /// Creates the Superblock of a fresh filesystem (mkfs) from a configured device:
+    /// geometry and area locations come from `DeviceConfig::layout`.
+    pub fn from_config(device: &DeviceConfig, device_type: DeviceType, device_id: u64) -> Result<Self, FileSystemError> {
+        let layout = device.layout().map_err(map_config_error_to_fs_error)?;
+        let mut superblock = Superblock::new(
+            device.block_size,
+            Inode::size() as u32,
+            device.total_blocks,
+            device.inodes_count as u64,
+            device_type,
+            device_id,
+            layout.free_space_map,
+            layout.inode_table,
+            layout.data_blocks,
+        );
+        superblock.root_inode = device.root_inode as u64;
+        // Metadata areas are never free
+        superblock.free_blocks_count = layout.data_blocks_count;
+        Ok(superblock)
+    }
+
+    /// Checks at mount time that the on-disk geometry matches the configured device.
+    pub fn check_config(&self, device: &DeviceConfig) -> Result<(), FileSystemError> {
+        let layout = device.layout().map_err(map_config_error_to_fs_error)?;
+        let fields: [(&str, u64, u64); 6] = [
+            ("block_size", self.block_size as u64, device.block_size as u64),
+            ("total_blocks", self.blocks_count, device.total_blocks),
+            ("inodes_count", self.inodes_count, device.inodes_count as u64),
+            ("free_space_map_location", self.block_bitmap_start, layout.free_space_map),
+            ("inode_table_location", self.inode_table_start, layout.inode_table),
+            ("data_blocks_location", self.data_blocks_start, layout.data_blocks),
+        ];
+        for &(field, on_disk, configured) in fields.iter() {
+            if on_disk != configured {
+                return Err(FileSystemError::SuperblockError(format!(
+                    "{}: {} is {} on disk but {} in the configuration",
+                    device.name, field, on_disk, configured
+                )));
+            }
+        }
+        Ok(())
+    }
+
     /// Loads the Superblock from the specified block device.
     /// Assumes the Superblock is located at SUPERBLOCK_BLOCK_ID.
+    /// SUPERBLOCK_BLOCK_ID is relative to the start of the file system; on a partitioned disk,
+    /// pass the partition's device (e.g. from `crate::partition::open_sadak_partition`).
     ///
     /// # Arguments
     ///
@@ -0,0 +1,104 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg(all(test, feature = "std"))] // Yalnızca testlerde derlenir
+
+// Testler için ortak bellek içi blok aygıtları.
+//
+// - `MemDevice`: blokları doğrudan erişilebilir bir vektörde tutar; okuma sayacı ve hata
+//   enjeksiyonu (`failed`) vardır. Testler `blocks`'u doğrudan bozabilir veya inceleyebilir.
+// - `SharedMemDevice`: klonları aynı `MemDevice`'ı görür (yeniden bağlama senaryoları için).
+
+use crate::blockdevice::{BlockDevice, BlockDeviceError};
+
+use std::string::String;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::vec::Vec;
+
+/// Bellek içi blok aygıtı.
+pub struct MemDevice {
+    pub blocks: Vec<Vec<u8>>,
+    pub block_size: usize,
+    /// Başarılı `read_block` çağrılarının sayısı.
+    pub reads: u64,
+    /// true iken tüm okuma ve yazmalar DeviceError ile başarısız olur.
+    pub failed: bool,
+}
+
+impl MemDevice {
+    /// Sıfırlarla dolu `block_count` bloklu bir aygıt oluşturur.
+    pub fn new(block_count: usize, block_size: usize) -> Self {
+        MemDevice { blocks: vec![vec![0u8; block_size]; block_count], block_size, reads: 0, failed: false }
+    }
+
+    fn check(&self, block_id: u64, len: usize) -> Result<(), BlockDeviceError> {
+        if self.failed {
+            return Err(BlockDeviceError::DeviceError(String::from("simulated failure")));
+        }
+        if block_id >= self.blocks.len() as u64 {
+            return Err(BlockDeviceError::InvalidParameter(format!("Block ID {} is out of bounds. Total blocks: {}", block_id, self.blocks.len())));
+        }
+        if len != self.block_size {
+            return Err(BlockDeviceError::BlockSizeError(format!("Buffer size ({}) must match block size ({}).", len, self.block_size)));
+        }
+        Ok(())
+    }
+}
+
+impl BlockDevice for MemDevice {
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> Result<(), BlockDeviceError> {
+        self.check(block_id, buf.len())?;
+        self.reads += 1;
+        buf.copy_from_slice(&self.blocks[block_id as usize]);
+        Ok(())
+    }
+
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> Result<(), BlockDeviceError> {
+        self.check(block_id, buf.len())?;
+        self.blocks[block_id as usize].copy_from_slice(buf);
+        Ok(())
+    }
+
+    fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    fn block_count(&self) -> u64 {
+        self.blocks.len() as u64
+    }
+}
+
+/// Klonları aynı blokları paylaşan bellek içi aygıt.
+#[derive(Clone)]
+pub struct SharedMemDevice {
+    inner: Arc<Mutex<MemDevice>>,
+    block_size: usize,
+    block_count: u64,
+}
+
+impl SharedMemDevice {
+    pub fn new(block_count: usize, block_size: usize) -> Self {
+        SharedMemDevice { inner: Arc::new(Mutex::new(MemDevice::new(block_count, block_size))), block_size, block_count: block_count as u64 }
+    }
+
+    /// Paylaşılan aygıta doğrudan erişim (blokları incelemek veya bozmak için).
+    pub fn lock(&self) -> MutexGuard<'_, MemDevice> {
+        self.inner.lock().unwrap()
+    }
+}
+
+impl BlockDevice for SharedMemDevice {
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> Result<(), BlockDeviceError> {
+        self.lock().read_block(block_id, buf)
+    }
+
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> Result<(), BlockDeviceError> {
+        self.lock().write_block(block_id, buf)
+    }
+
+    fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    fn block_count(&self) -> u64 {
+        self.block_count
+    }
+}
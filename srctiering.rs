@@ -0,0 +1,728 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// Depolama katmanlama (tiering): hızlı (NVMe/SSD) ve yavaş (HDD) iki aygıtı tek bir SADAK
+// hacmi olarak sunar.
+//
+// - Mantıksal adres alanı `extent_blocks` bloklık kapsamlara (extent) bölünür; her kapsam ilk
+//   yazıldığında bir katmana yerleştirilir. Eşleme tablosu hızlı katmanın başında saklanır.
+// - Yerleşim ipuçları (`set_hint`): meta veri (süper blok, bitmap, inode tablosu) hızlı katmana
+//   sabitlenir; küçük dosyalar hızlı katmanı tercih eder; diğer veri hızlı katmanda
+//   `fast_reserve_extents` kadar boş yer bırakılarak yerleştirilir.
+// - Her kapsam için erişim istatistiği (ısı ve son erişim zamanı) tutulur. `migrate_step`
+//   arka planda çağrılır: `cold_after` süresince erişilmeyen kapsamları yavaş katmana indirir,
+//   ısısı `hot_threshold`'u geçen kapsamları hızlı katmana çıkarır; ısı her adımda yarıya iner.
+// - `tier_usage` katman başına kullanımı bildirir.
+
+use crate::blockdevice::{BlockDevice, BlockDeviceError}; // Assuming these are in crate::blockdevice
+use crate::superblock::DeviceType;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::format;
+
+use core::fmt;
+use core::result::Result;
+
+pub const TIER_MAP_MAGIC: &[u8; 8] = b"SDKTIER\0";
+const TIER_MAP_HEADER_SIZE: usize = 32;
+const TIER_MAP_ENTRY_SIZE: usize = 4;
+
+// Eşleme girdisi: bit 31 katman, bit 29-30 ipucu, bit 0-28 fiziksel kapsam + 1 (0 = eşlenmemiş)
+const MAP_TIER_SLOW: u32 = 1 << 31;
+const MAP_HINT_SHIFT: u32 = 29;
+const MAP_PHYSICAL_MASK: u32 = (1 << 29) - 1;
+
+/// Depolama katmanı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tier {
+    Fast,
+    Slow,
+}
+
+impl Tier {
+    /// Aygıt türünün doğal katmanı: flash tabanlı aygıtlar hızlı, diğerleri yavaş.
+    pub fn for_device_type(device_type: DeviceType) -> Tier {
+        match device_type {
+            DeviceType::NVMe | DeviceType::SSD | DeviceType::UFS => Tier::Fast,
+            _ => Tier::Slow,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Tier::Fast => 0,
+            Tier::Slow => 1,
+        }
+    }
+}
+
+/// Yerleşim ipucu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TierHint {
+    /// Genel veri: sıcaklığa göre taşınır.
+    Data,
+    /// Küçük dosya: hızlı katmanı tercih eder, soğuyunca indirilebilir.
+    SmallFile,
+    /// Meta veri: hızlı katmana sabitlenir, hiç indirilmez.
+    Metadata,
+}
+
+impl TierHint {
+    fn to_bits(self) -> u32 {
+        match self {
+            TierHint::Data => 0,
+            TierHint::SmallFile => 1,
+            TierHint::Metadata => 2,
+        }
+    }
+
+    fn from_bits(bits: u32) -> TierHint {
+        match bits {
+            1 => TierHint::SmallFile,
+            2 => TierHint::Metadata,
+            _ => TierHint::Data,
+        }
+    }
+}
+
+/// Yerleşim ve taşıma politikası.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TierPolicy {
+    /// Bu boyuttan (bayt) küçük dosyalar hızlı katmanı tercih eder (`hint_for_file`).
+    pub small_file_bytes: u64,
+    /// Genel veri yerleştirilirken hızlı katmanda boş bırakılan kapsam sayısı.
+    pub fast_reserve_extents: u64,
+    /// Yavaş katmandaki bir kapsamın yükseltilmesi için gereken ısı.
+    pub hot_threshold: u32,
+    /// Bu süre boyunca erişilmeyen hızlı katman kapsamı soğuk sayılır.
+    pub cold_after: u64,
+}
+
+impl Default for TierPolicy {
+    fn default() -> Self {
+        TierPolicy { small_file_bytes: 64 * 1024, fast_reserve_extents: 4, hot_threshold: 8, cold_after: 3600 }
+    }
+}
+
+/// Katmanlama hataları.
+#[derive(Debug)]
+pub enum TierError {
+    /// Bir katmanın aygıtından gelen hata.
+    Device { tier: Tier, error: BlockDeviceError },
+    /// Hızlı katmandaki eşleme tablosu okunamadı veya bozuk.
+    InvalidMap(String),
+    /// Geçersiz argüman.
+    InvalidParameter(String),
+}
+
+impl fmt::Display for TierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TierError::Device { tier, error } => write!(f, "{:?} katman aygıt hatası: {:?}", tier, error),
+            TierError::InvalidMap(msg) => write!(f, "Geçersiz katman eşleme tablosu: {}", msg),
+            TierError::InvalidParameter(msg) => write!(f, "Geçersiz parametre: {}", msg),
+        }
+    }
+}
+
+/// TierError'ı BlockDeviceError'a çevirir.
+pub fn map_tier_error_to_block_device_error(e: TierError) -> BlockDeviceError {
+    match e {
+        TierError::Device { error, .. } => error,
+        TierError::InvalidParameter(msg) => BlockDeviceError::InvalidParameter(msg),
+        other => BlockDeviceError::DeviceError(format!("{}", other)),
+    }
+}
+
+/// Bir katmanın kullanım bilgisi.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TierUsage {
+    pub tier: Tier,
+    pub device_type: DeviceType,
+    /// Verilere ayrılabilen kapsam sayısı (eşleme tablosu hariç).
+    pub total_extents: u64,
+    pub used_extents: u64,
+    pub extent_bytes: u64,
+}
+
+impl TierUsage {
+    pub fn total_bytes(&self) -> u64 {
+        self.total_extents * self.extent_bytes
+    }
+
+    pub fn used_bytes(&self) -> u64 {
+        self.used_extents * self.extent_bytes
+    }
+}
+
+/// Bir `migrate_step` çağrısının sonucu.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub promoted: u64,
+    pub demoted: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct ExtentStats {
+    heat: u32,
+    last_access: u64,
+}
+
+/// İki katmanlı hacim; kendisi de bir BlockDevice'tır.
+pub struct TieredVolume<F: BlockDevice, S: BlockDevice> {
+    fast: F,
+    slow: S,
+    device_types: [DeviceType; 2],
+    block_size: usize,
+    extent_blocks: u64,
+    map_extents: u64,       // Hızlı katmanın başında eşleme tablosuna ayrılan kapsamlar
+    map: Vec<u32>,          // Mantıksal kapsam -> eşleme girdisi
+    free: [Vec<bool>; 2],   // Katman başına fiziksel kapsam boş mu?
+    stats: Vec<ExtentStats>,
+    policy: TierPolicy,
+    clock: u64,
+    map_dirty: bool,
+}
+
+impl<F: BlockDevice, S: BlockDevice> TieredVolume<F, S> {
+    /// Yeni bir katmanlı hacim oluşturur ve boş eşleme tablosunu hızlı katmana yazar.
+    ///
+    /// # Arguments
+    ///
+    /// * `fast`, `fast_type`: Hızlı katman aygıtı ve türü.
+    /// * `slow`, `slow_type`: Yavaş katman aygıtı ve türü.
+    /// * `extent_blocks`: Taşıma birimi (blok).
+    pub fn create(fast: F, fast_type: DeviceType, slow: S, slow_type: DeviceType, extent_blocks: u64, policy: TierPolicy) -> Result<Self, TierError> {
+        if Tier::for_device_type(fast_type) == Tier::Slow && Tier::for_device_type(slow_type) == Tier::Fast {
+            return Err(TierError::InvalidParameter(format!("{:?} hızlı, {:?} yavaş katman olamaz", fast_type, slow_type)));
+        }
+        let mut volume = TieredVolume::layout(fast, fast_type, slow, slow_type, extent_blocks, policy)?;
+        volume.save_map()?;
+        Ok(volume)
+    }
+
+    /// Hızlı katmandaki eşleme tablosundan mevcut bir hacmi açar. Erişim istatistikleri
+    /// kalıcı değildir; açılışta sıfırdan başlar.
+    pub fn open(mut fast: F, fast_type: DeviceType, slow: S, slow_type: DeviceType, policy: TierPolicy) -> Result<Self, TierError> {
+        let block_size = fast.block_size();
+        let mut header = vec![0u8; block_size]; // Requires alloc
+        fast.read_block(0, &mut header).map_err(|error| TierError::Device { tier: Tier::Fast, error })?;
+        if block_size < TIER_MAP_HEADER_SIZE || &header[0..8] != TIER_MAP_MAGIC {
+            return Err(TierError::InvalidMap(String::from("imza yok")));
+        }
+        let extent_blocks = u64::from_le_bytes(header[8..16].try_into().unwrap_or([0; 8]));
+        let logical_extents = u64::from_le_bytes(header[16..24].try_into().unwrap_or([0; 8]));
+
+        let mut volume = TieredVolume::layout(fast, fast_type, slow, slow_type, extent_blocks, policy)?;
+        if logical_extents != volume.map.len() as u64 {
+            return Err(TierError::InvalidMap(format!("{} kapsam kayıtlı, aygıtlar {} kapsam sunuyor", logical_extents, volume.map.len())));
+        }
+        let map_bytes = TIER_MAP_HEADER_SIZE + volume.map.len() * TIER_MAP_ENTRY_SIZE;
+        let map_blocks = (map_bytes + block_size - 1) / block_size;
+        let mut raw = vec![0u8; map_blocks * block_size]; // Requires alloc
+        for (i, chunk) in raw.chunks_mut(block_size).enumerate() {
+            volume.fast.read_block(i as u64, chunk).map_err(|error| TierError::Device { tier: Tier::Fast, error })?;
+        }
+        for extent in 0..volume.map.len() {
+            let offset = TIER_MAP_HEADER_SIZE + extent * TIER_MAP_ENTRY_SIZE;
+            let entry = u32::from_le_bytes([raw[offset], raw[offset + 1], raw[offset + 2], raw[offset + 3]]);
+            if let Some((tier, physical)) = decode_entry(entry) {
+                let slot = volume.free[tier.index()].get_mut(physical as usize).filter(|free| **free);
+                match slot {
+                    Some(free) => *free = false,
+                    None => return Err(TierError::InvalidMap(format!("kapsam {} geçersiz veya çift eşlenmiş", extent))),
+                }
+            }
+            volume.map[extent] = entry;
+        }
+        volume.map_dirty = false;
+        Ok(volume)
+    }
+
+    // Kapasiteleri hesaplayıp boş bir hacim yapısı kurar.
+    fn layout(fast: F, fast_type: DeviceType, slow: S, slow_type: DeviceType, extent_blocks: u64, policy: TierPolicy) -> Result<Self, TierError> {
+        let block_size = fast.block_size();
+        if slow.block_size() != block_size {
+            return Err(TierError::InvalidParameter(format!("Katman blok boyutları farklı: {} / {}", block_size, slow.block_size())));
+        }
+        if extent_blocks == 0 || block_size < TIER_MAP_HEADER_SIZE {
+            return Err(TierError::InvalidParameter(String::from("Kapsam veya blok boyutu geçersiz")));
+        }
+        let fast_total = fast.block_count() / extent_blocks;
+        let slow_total = slow.block_count() / extent_blocks;
+        // Eşleme tablosu mantıksal kapsam sayısına bağlı; sabit noktaya kadar yinele
+        let extent_bytes = extent_blocks * block_size as u64;
+        let mut map_extents = 1;
+        loop {
+            let logical = fast_total.saturating_sub(map_extents) + slow_total;
+            let needed = ((TIER_MAP_HEADER_SIZE as u64 + logical * TIER_MAP_ENTRY_SIZE as u64) + extent_bytes - 1) / extent_bytes;
+            if needed <= map_extents {
+                break;
+            }
+            map_extents = needed;
+        }
+        if fast_total <= map_extents || slow_total == 0 || fast_total + slow_total > MAP_PHYSICAL_MASK as u64 {
+            return Err(TierError::InvalidParameter(format!("Katman boyutları uygun değil ({} / {} kapsam)", fast_total, slow_total)));
+        }
+        let fast_data = fast_total - map_extents;
+        let logical = (fast_data + slow_total) as usize;
+        Ok(TieredVolume {
+            fast,
+            slow,
+            device_types: [fast_type, slow_type],
+            block_size,
+            extent_blocks,
+            map_extents,
+            map: vec![0; logical],
+            free: [vec![true; fast_data as usize], vec![true; slow_total as usize]],
+            stats: vec![ExtentStats::default(); logical],
+            policy,
+            clock: 0,
+            map_dirty: true,
+        })
+    }
+
+    /// Eşleme tablosunu hızlı katmanın başına yazar.
+    pub fn save_map(&mut self) -> Result<(), TierError> {
+        let block_size = self.block_size;
+        let map_bytes = TIER_MAP_HEADER_SIZE + self.map.len() * TIER_MAP_ENTRY_SIZE;
+        let map_blocks = (map_bytes + block_size - 1) / block_size;
+        let mut raw = vec![0u8; map_blocks * block_size]; // Requires alloc
+        raw[0..8].copy_from_slice(TIER_MAP_MAGIC);
+        raw[8..16].copy_from_slice(&self.extent_blocks.to_le_bytes());
+        raw[16..24].copy_from_slice(&(self.map.len() as u64).to_le_bytes());
+        for (extent, entry) in self.map.iter().enumerate() {
+            let offset = TIER_MAP_HEADER_SIZE + extent * TIER_MAP_ENTRY_SIZE;
+            raw[offset..offset + 4].copy_from_slice(&entry.to_le_bytes());
+        }
+        for (i, chunk) in raw.chunks(block_size).enumerate() {
+            self.fast.write_block(i as u64, chunk).map_err(|error| TierError::Device { tier: Tier::Fast, error })?;
+        }
+        self.map_dirty = false;
+        Ok(())
+    }
+
+    pub fn policy(&self) -> &TierPolicy {
+        &self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: TierPolicy) {
+        self.policy = policy;
+    }
+
+    /// Erişim istatistiklerinde kullanılan zamanı günceller (saniye veya tik, `cold_after` ile aynı birim).
+    pub fn set_time(&mut self, now: u64) {
+        self.clock = now;
+    }
+
+    /// Dosya boyutuna göre ipucu: küçük dosyalar hızlı katmanı tercih eder.
+    pub fn hint_for_file(&self, file_bytes: u64) -> TierHint {
+        if file_bytes <= self.policy.small_file_bytes {
+            TierHint::SmallFile
+        } else {
+            TierHint::Data
+        }
+    }
+
+    /// Blok aralığının kapsadığı kapsamlara yerleşim ipucu verir. Yanlış katmandaki
+    /// meta veri ve küçük dosya kapsamları sonraki `migrate_step` çağrılarında yükseltilir.
+    pub fn set_hint(&mut self, start_block: u64, block_count: u64, hint: TierHint) -> Result<(), TierError> {
+        self.check_range(start_block, block_count)?;
+        if block_count == 0 {
+            return Ok(());
+        }
+        let first = start_block / self.extent_blocks;
+        let last = (start_block + block_count - 1) / self.extent_blocks;
+        for extent in first..=last {
+            let entry = &mut self.map[extent as usize];
+            *entry = (*entry & !(3 << MAP_HINT_SHIFT)) | (hint.to_bits() << MAP_HINT_SHIFT);
+        }
+        self.map_dirty = true;
+        Ok(())
+    }
+
+    /// Mantıksal bloğun şu an bulunduğu katman (eşlenmemişse None).
+    pub fn tier_of(&self, block: u64) -> Option<Tier> {
+        self.map.get((block / self.extent_blocks) as usize).and_then(|entry| decode_entry(*entry)).map(|(tier, _)| tier)
+    }
+
+    /// Katman başına kullanım.
+    pub fn tier_usage(&self) -> [TierUsage; 2] {
+        let usage = |tier: Tier| {
+            let free = &self.free[tier.index()];
+            TierUsage {
+                tier,
+                device_type: self.device_types[tier.index()],
+                total_extents: free.len() as u64,
+                used_extents: free.iter().filter(|f| !**f).count() as u64,
+                extent_bytes: self.extent_blocks * self.block_size as u64,
+            }
+        };
+        [usage(Tier::Fast), usage(Tier::Slow)]
+    }
+
+    /// Alttaki aygıtları geri verir (önce `flush` çağrılmalı).
+    pub fn into_inner(self) -> (F, S) {
+        (self.fast, self.slow)
+    }
+
+    /// Arka plan taşıma adımı: en fazla `max_extents` kapsam taşır. Önce soğuk kapsamlar
+    /// indirilir, ardından sıcak ve ipucu hızlı katmanı isteyen kapsamlar yükseltilir.
+    pub fn migrate_step(&mut self, max_extents: u64) -> Result<MigrationReport, TierError> {
+        let mut report = MigrationReport::default();
+        let mut budget = max_extents;
+
+        // İndirme: en uzun süredir erişilmeyen önce
+        let mut cold: Vec<(u64, usize)> = (0..self.map.len())
+            .filter(|&e| self.extent_tier(e) == Some(Tier::Fast) && self.extent_hint(e) != TierHint::Metadata && self.is_cold(e))
+            .map(|e| (self.stats[e].last_access, e))
+            .collect();
+        cold.sort();
+        for (_, extent) in cold {
+            if budget == 0 || !self.free[Tier::Slow.index()].contains(&true) {
+                break;
+            }
+            self.move_extent(extent, Tier::Slow)?;
+            report.demoted += 1;
+            budget -= 1;
+        }
+
+        // Yükseltme: önce meta veri, sonra yakın zamanda erişilen küçük dosyalar ve sıcak
+        // kapsamlar (ısıya göre). Soğuk kapsam yükseltilmez; aksi halde indirilen kapsam geri gelir.
+        let mut hot: Vec<(u32, u32, usize)> = (0..self.map.len())
+            .filter(|&e| self.extent_tier(e) == Some(Tier::Slow))
+            .filter(|&e| match self.extent_hint(e) {
+                TierHint::Metadata => true,
+                TierHint::SmallFile => !self.is_cold(e),
+                TierHint::Data => !self.is_cold(e) && self.stats[e].heat >= self.policy.hot_threshold,
+            })
+            .map(|e| (u32::MAX - self.extent_hint(e).to_bits(), u32::MAX - self.stats[e].heat, e))
+            .collect();
+        hot.sort();
+        for (_, _, extent) in hot {
+            if budget == 0 || !self.free[Tier::Fast.index()].contains(&true) {
+                break;
+            }
+            self.move_extent(extent, Tier::Fast)?;
+            report.promoted += 1;
+            budget -= 1;
+        }
+
+        for stats in self.stats.iter_mut() {
+            stats.heat /= 2;
+        }
+        if report != MigrationReport::default() {
+            self.save_map()?;
+        }
+        Ok(report)
+    }
+
+    fn extent_tier(&self, extent: usize) -> Option<Tier> {
+        decode_entry(self.map[extent]).map(|(tier, _)| tier)
+    }
+
+    fn is_cold(&self, extent: usize) -> bool {
+        self.clock.saturating_sub(self.stats[extent].last_access) >= self.policy.cold_after
+    }
+
+    fn extent_hint(&self, extent: usize) -> TierHint {
+        TierHint::from_bits((self.map[extent] >> MAP_HINT_SHIFT) & 3)
+    }
+
+    // Fiziksel kapsam ayırır.
+    fn allocate(&mut self, tier: Tier) -> Option<u64> {
+        let free = &mut self.free[tier.index()];
+        let physical = free.iter().position(|f| *f)?;
+        free[physical] = false;
+        Some(physical as u64)
+    }
+
+    // Yeni yazılan kapsam için katman seçer.
+    fn place(&mut self, extent: usize) -> Result<(Tier, u64), TierError> {
+        let fast_free = self.free[Tier::Fast.index()].iter().filter(|f| **f).count() as u64;
+        let prefer_fast = match self.extent_hint(extent) {
+            TierHint::Metadata | TierHint::SmallFile => fast_free > 0,
+            TierHint::Data => fast_free > self.policy.fast_reserve_extents,
+        };
+        let order = if prefer_fast { [Tier::Fast, Tier::Slow] } else { [Tier::Slow, Tier::Fast] };
+        for tier in order {
+            if let Some(physical) = self.allocate(tier) {
+                self.map[extent] = encode_entry(tier, physical, self.map[extent]);
+                self.map_dirty = true;
+                return Ok((tier, physical));
+            }
+        }
+        // Mantıksal kapasite fiziksel kapasiteye eşit olduğundan buraya gelinmemeli
+        Err(TierError::InvalidMap(String::from("boş fiziksel kapsam kalmadı")))
+    }
+
+    fn move_extent(&mut self, extent: usize, to: Tier) -> Result<(), TierError> {
+        let (from, source) = match decode_entry(self.map[extent]) {
+            Some(found) => found,
+            None => return Ok(()),
+        };
+        let target = match self.allocate(to) {
+            Some(target) => target,
+            None => return Ok(()),
+        };
+        let mut buf = vec![0u8; self.block_size]; // Requires alloc
+        for i in 0..self.extent_blocks {
+            let copied = self
+                .read_physical(from, source * self.extent_blocks + i, &mut buf)
+                .and_then(|_| self.write_physical(to, target * self.extent_blocks + i, &buf));
+            if let Err(e) = copied {
+                self.free[to.index()][target as usize] = true;
+                return Err(e);
+            }
+        }
+        self.map[extent] = encode_entry(to, target, self.map[extent]);
+        self.free[from.index()][source as usize] = true;
+        self.map_dirty = true;
+        Ok(())
+    }
+
+    fn physical_block(&self, tier: Tier, block: u64) -> u64 {
+        match tier {
+            Tier::Fast => self.map_extents * self.extent_blocks + block,
+            Tier::Slow => block,
+        }
+    }
+
+    fn read_physical(&mut self, tier: Tier, block: u64, buf: &mut [u8]) -> Result<(), TierError> {
+        let lba = self.physical_block(tier, block);
+        match tier {
+            Tier::Fast => self.fast.read_block(lba, buf),
+            Tier::Slow => self.slow.read_block(lba, buf),
+        }
+        .map_err(|error| TierError::Device { tier, error })
+    }
+
+    fn write_physical(&mut self, tier: Tier, block: u64, buf: &[u8]) -> Result<(), TierError> {
+        let lba = self.physical_block(tier, block);
+        match tier {
+            Tier::Fast => self.fast.write_block(lba, buf),
+            Tier::Slow => self.slow.write_block(lba, buf),
+        }
+        .map_err(|error| TierError::Device { tier, error })
+    }
+
+    fn touch(&mut self, extent: usize) {
+        let stats = &mut self.stats[extent];
+        stats.heat = stats.heat.saturating_add(1);
+        stats.last_access = self.clock;
+    }
+
+    fn check_range(&self, start_block: u64, block_count: u64) -> Result<(), TierError> {
+        let total = self.map.len() as u64 * self.extent_blocks;
+        if start_block.checked_add(block_count).map_or(true, |end| end > total) {
+            return Err(TierError::InvalidParameter(format!("Block range {}+{} is out of bounds. Total blocks: {}", start_block, block_count, total)));
+        }
+        Ok(())
+    }
+
+    fn read_logical(&mut self, block: u64, buf: &mut [u8]) -> Result<(), TierError> {
+        self.check_range(block, 1)?;
+        let extent = (block / self.extent_blocks) as usize;
+        self.touch(extent);
+        match decode_entry(self.map[extent]) {
+            Some((tier, physical)) => self.read_physical(tier, physical * self.extent_blocks + block % self.extent_blocks, buf),
+            None => {
+                // Hiç yazılmamış kapsam sıfır okunur
+                buf.fill(0);
+                Ok(())
+            }
+        }
+    }
+
+    fn write_logical(&mut self, block: u64, buf: &[u8]) -> Result<(), TierError> {
+        self.check_range(block, 1)?;
+        let extent = (block / self.extent_blocks) as usize;
+        self.touch(extent);
+        let (tier, physical) = match decode_entry(self.map[extent]) {
+            Some(found) => found,
+            None => {
+                let placed = self.place(extent)?;
+                // Yeni kapsamın yazılmayan blokları sıfır okunmalı
+                let zero = vec![0u8; self.block_size]; // Requires alloc
+                for i in 0..self.extent_blocks {
+                    if i != block % self.extent_blocks {
+                        self.write_physical(placed.0, placed.1 * self.extent_blocks + i, &zero)?;
+                    }
+                }
+                placed
+            }
+        };
+        self.write_physical(tier, physical * self.extent_blocks + block % self.extent_blocks, buf)
+    }
+}
+
+fn decode_entry(entry: u32) -> Option<(Tier, u64)> {
+    let physical = entry & MAP_PHYSICAL_MASK;
+    if physical == 0 {
+        return None;
+    }
+    let tier = if entry & MAP_TIER_SLOW != 0 { Tier::Slow } else { Tier::Fast };
+    Some((tier, (physical - 1) as u64))
+}
+
+// İpucu bitlerini koruyarak yeni konumu kodlar.
+fn encode_entry(tier: Tier, physical: u64, previous: u32) -> u32 {
+    let tier_bit = if tier == Tier::Slow { MAP_TIER_SLOW } else { 0 };
+    (previous & (3 << MAP_HINT_SHIFT)) | tier_bit | (physical as u32 + 1)
+}
+
+impl<F: BlockDevice, S: BlockDevice> BlockDevice for TieredVolume<F, S> {
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> Result<(), BlockDeviceError> {
+        self.read_logical(block_id, buf).map_err(map_tier_error_to_block_device_error)
+    }
+
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> Result<(), BlockDeviceError> {
+        self.write_logical(block_id, buf).map_err(map_tier_error_to_block_device_error)
+    }
+
+    fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    fn block_count(&self) -> u64 {
+        self.map.len() as u64 * self.extent_blocks
+    }
+
+    fn discard_blocks(&mut self, start_block: u64, block_count: u64) -> Result<(), BlockDeviceError> {
+        self.check_range(start_block, block_count).map_err(map_tier_error_to_block_device_error)?;
+        let end = start_block + block_count;
+        let mut block = start_block;
+        while block < end {
+            let extent = (block / self.extent_blocks) as usize;
+            let offset = block % self.extent_blocks;
+            let run = core::cmp::min(self.extent_blocks - offset, end - block);
+            if let Some((tier, physical)) = decode_entry(self.map[extent]) {
+                let lba = self.physical_block(tier, physical * self.extent_blocks + offset);
+                match tier {
+                    Tier::Fast => self.fast.discard_blocks(lba, run)?,
+                    Tier::Slow => self.slow.discard_blocks(lba, run)?,
+                }
+                if run == self.extent_blocks {
+                    // Tüm kapsam boşaldı: fiziksel alan serbest kalır
+                    self.map[extent] &= 3 << MAP_HINT_SHIFT;
+                    self.free[tier.index()][physical as usize] = true;
+                    self.map_dirty = true;
+                }
+            }
+            block += run;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), BlockDeviceError> {
+        if self.map_dirty {
+            self.save_map().map_err(map_tier_error_to_block_device_error)?;
+        }
+        self.fast.flush()?;
+        self.slow.flush()
+    }
+}
+
+
+#[cfg(test)]
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
+mod tests {
+    use super::*;
+    use crate::testdevice::MemDevice;
+
+    fn policy() -> TierPolicy {
+        TierPolicy { small_file_bytes: 4096, fast_reserve_extents: 2, hot_threshold: 3, cold_after: 100 }
+    }
+
+    // 8 kapsamlık (1 tablo + 7 veri) hızlı, 32 kapsamlık yavaş katman; kapsam 4 blok
+    fn volume() -> TieredVolume<MemDevice, MemDevice> {
+        TieredVolume::create(MemDevice::new(32, 512), DeviceType::NVMe, MemDevice::new(128, 512), DeviceType::HDD, 4, policy()).unwrap()
+    }
+
+    #[test]
+    fn test_placement_and_usage() -> Result<(), BlockDeviceError> {
+        let mut volume = volume();
+        assert_eq!(volume.block_count(), (7 + 32) * 4);
+        volume.set_hint(0, 8, TierHint::Metadata).unwrap();
+        for block in 0..volume.block_count() {
+            volume.write_block(block, &[block as u8; 512])?;
+        }
+        // Meta veri ve ilk veri hızlı katmana; ayrılan yedek dolunca yavaş katmana
+        assert_eq!(volume.tier_of(0), Some(Tier::Fast));
+        assert_eq!(volume.tier_of(4), Some(Tier::Fast));
+        assert_eq!(volume.tier_of(volume.block_count() - 1), Some(Tier::Fast));
+        let [fast, slow] = volume.tier_usage();
+        assert_eq!((fast.device_type, fast.total_extents, fast.used_extents), (DeviceType::NVMe, 7, 7));
+        assert_eq!((slow.used_extents, slow.used_bytes()), (32, 32 * 4 * 512));
+        assert_eq!(volume.hint_for_file(1000), TierHint::SmallFile);
+
+        // Tüm kapsamı discard etmek fiziksel alanı serbest bırakır; okuma sıfır döner
+        volume.discard_blocks(20, 4)?;
+        let mut buf = vec![0xFFu8; 512];
+        volume.read_block(21, &mut buf)?;
+        assert!(buf.iter().all(|b| *b == 0));
+        assert_eq!(volume.tier_of(20), None);
+
+        // Eşleme tablosu kalıcıdır
+        volume.flush()?;
+        let (fast_dev, slow_dev) = volume.into_inner();
+        let mut volume = TieredVolume::open(fast_dev, DeviceType::NVMe, slow_dev, DeviceType::HDD, policy()).unwrap();
+        for block in (0..volume.block_count()).filter(|b| !(20..24).contains(b)) {
+            volume.read_block(block, &mut buf)?;
+            assert_eq!(buf, vec![block as u8; 512], "blok {}", block);
+        }
+        assert_eq!(volume.tier_usage()[1].used_extents, 31); // Discard edilen kapsam yavaş katmandaydı
+        Ok(())
+    }
+
+    #[test]
+    fn test_cold_demotion_and_hot_promotion() -> Result<(), BlockDeviceError> {
+        let mut volume = volume();
+        volume.set_hint(0, 4, TierHint::Metadata).unwrap();
+        // 20 kapsam yazılır: 0-4 hızlı (2 kapsam yedekte kalır), 5-19 yavaş
+        for block in 0..80 {
+            volume.write_block(block, &[block as u8; 512])?;
+        }
+        let hot_block = 60;
+        assert_eq!(volume.tier_of(hot_block), Some(Tier::Slow));
+
+        // Zaman ilerler: yalnızca meta veri ve sıcak blok erişilir
+        volume.set_time(500);
+        let mut buf = vec![0u8; 512];
+        for _ in 0..5 {
+            volume.read_block(0, &mut buf)?;
+            volume.read_block(hot_block, &mut buf)?;
+        }
+        let report = volume.migrate_step(16).unwrap();
+        assert_eq!(report, MigrationReport { promoted: 1, demoted: 4 });
+        assert_eq!(volume.tier_of(0), Some(Tier::Fast)); // Meta veri sabit
+        assert_eq!(volume.tier_of(hot_block), Some(Tier::Fast));
+        assert_eq!(volume.tier_of(4), Some(Tier::Slow));
+        volume.read_block(hot_block, &mut buf)?;
+        assert_eq!(buf, vec![hot_block as u8; 512]);
+        volume.read_block(4, &mut buf)?;
+        assert_eq!(buf, vec![4u8; 512]);
+
+        // Küçük dosya ipucu, erişilen yavaş katman kapsamını yükseltir
+        // (önceki sıcak kapsam artık soğuk olduğundan indirilir)
+        volume.set_time(1000);
+        volume.set_hint(40, 1, TierHint::SmallFile).unwrap();
+        volume.read_block(40, &mut buf)?;
+        assert_eq!(volume.migrate_step(16).unwrap(), MigrationReport { promoted: 1, demoted: 1 });
+        assert_eq!((volume.tier_of(40), volume.tier_of(hot_block)), (Some(Tier::Fast), Some(Tier::Slow)));
+        let usage = volume.tier_usage();
+        assert_eq!((usage[0].used_extents, usage[1].used_extents), (2, 18));
+        Ok(())
+    }
+}
+
+
+// Removed redundant print module and panic handler boilerplate.
+// The empty lib module configuration is kept.
+#[cfg(not(any(feature = "std", test)))] // Only when not building std or test
+pub mod lib {} // Keep the empty lib module if needed for the crate structure
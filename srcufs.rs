@@ -1,550 +1,1200 @@
 #![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
-#![cfg_attr(not(feature = "std")), no_std)] // Standart kütüphaneye ihtiyacımız yok
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
 
 // no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
-#[cfg_attr(not(feature = "std")), macro_use]
+#[cfg_attr(not(feature = "std"), macro_use)]
 extern crate alloc;
 
+// UFS (Universal Flash Storage) driver built on UPIUs.
+//
+// - Every transaction with the device is a UFS Protocol Information Unit: a 32-byte header
+//   (12 common bytes + 20 transaction specific bytes) and an optional data segment.
+// - The host controller is reached through the `UfsHost` trait: one UTP transfer request
+//   (request UPIU + PRDT data buffer) in, the response UPIU out. A real UFSHCI driver and the
+//   `UfsSimulator` below both implement it.
+// - SCSI commands (srcscsi.rs) travel in COMMAND UPIUs; QUERY REQUEST UPIUs read descriptors,
+//   attributes and flags (device initialization via fDeviceInit).
+// - Each enabled logical unit is exposed as its own BlockDevice; they share the device.
 
-// Gerekli Sahne64 modülleri ve yapılarını içeri aktar (assume these are defined elsewhere)
-// Removed redundant imports like fs, memory, process, sync, kernel, arch, SahneError
-use crate::FileSystemError; // Assuming FileSystemError is in crate
+use crate::blockdevice::{BlockDevice, BlockDeviceError}; // Assuming these are in crate::blockdevice
+use crate::scsi::{
+    build_unmap_parameter_list, parse_unmap_parameter_list, sense_to_block_device_error, Cdb, InquiryData, ReadCapacity,
+    ScsiStatus, SenseData, SenseKey, SCSI_INQUIRY, SCSI_INQUIRY_SIZE, SCSI_READ_10, SCSI_READ_16,
+    SCSI_READ_CAPACITY_16_SIZE, SCSI_REQUEST_SENSE, SCSI_SERVICE_ACTION_IN_16, SCSI_SYNCHRONIZE_CACHE_10,
+    SCSI_TEST_UNIT_READY, SCSI_UNMAP, SCSI_WRITE_10, SCSI_WRITE_16,
+};
 
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use alloc::format;
 
-// Core library imports
-use core::mem; // For size_of, transmute
-use core::result::Result; // Use core::result::Result
-use core::fmt; // For Debug, Display
+use core::fmt;
+use core::result::Result;
+
+use spin::Mutex;
+
+// UPIU transaction codes (JESD220, 10.5)
+pub const UPIU_NOP_OUT: u8 = 0x00;
+pub const UPIU_COMMAND: u8 = 0x01;
+pub const UPIU_QUERY_REQUEST: u8 = 0x16;
+pub const UPIU_NOP_IN: u8 = 0x20;
+pub const UPIU_RESPONSE: u8 = 0x21;
+pub const UPIU_QUERY_RESPONSE: u8 = 0x36;
+pub const UPIU_REJECT: u8 = 0x3F;
+
+pub const UPIU_HEADER_SIZE: usize = 32;
+
+// COMMAND UPIU flags
+pub const UPIU_FLAG_READ: u8 = 0x40;
+pub const UPIU_FLAG_WRITE: u8 = 0x20;
+pub const UPIU_FLAG_SIMPLE_TASK: u8 = 0x00;
+
+// RESPONSE UPIU "response" field
+pub const UPIU_RESPONSE_TARGET_SUCCESS: u8 = 0x00;
+pub const UPIU_RESPONSE_TARGET_FAILURE: u8 = 0x01;
+
+// Query functions and opcodes
+pub const QUERY_FUNCTION_STANDARD_READ: u8 = 0x01;
+pub const QUERY_FUNCTION_STANDARD_WRITE: u8 = 0x81;
+pub const QUERY_OPCODE_READ_DESCRIPTOR: u8 = 0x01;
+pub const QUERY_OPCODE_WRITE_DESCRIPTOR: u8 = 0x02;
+pub const QUERY_OPCODE_READ_ATTRIBUTE: u8 = 0x03;
+pub const QUERY_OPCODE_WRITE_ATTRIBUTE: u8 = 0x04;
+pub const QUERY_OPCODE_READ_FLAG: u8 = 0x05;
+pub const QUERY_OPCODE_SET_FLAG: u8 = 0x06;
+pub const QUERY_OPCODE_CLEAR_FLAG: u8 = 0x07;
+
+// Query response codes
+pub const QUERY_RESPONSE_SUCCESS: u8 = 0x00;
+pub const QUERY_RESPONSE_NOT_READABLE: u8 = 0xF6;
+pub const QUERY_RESPONSE_NOT_WRITEABLE: u8 = 0xF7;
+pub const QUERY_RESPONSE_INVALID_LENGTH: u8 = 0xF9;
+pub const QUERY_RESPONSE_INVALID_INDEX: u8 = 0xFC;
+pub const QUERY_RESPONSE_INVALID_IDN: u8 = 0xFD;
+pub const QUERY_RESPONSE_INVALID_OPCODE: u8 = 0xFE;
+
+// Descriptor IDNs
+pub const UFS_DESC_DEVICE: u8 = 0x00;
+pub const UFS_DESC_UNIT: u8 = 0x02;
+pub const UFS_DESC_STRING: u8 = 0x05;
+pub const UFS_DESC_MAX_SIZE: usize = 255;
+pub const UFS_DEVICE_DESC_SIZE: usize = 0x40;
+pub const UFS_UNIT_DESC_SIZE: usize = 0x2D;
+
+// Flag IDNs
+pub const UFS_FLAG_DEVICE_INIT: u8 = 0x01;
+
+// Attribute IDNs
+pub const UFS_ATTR_BOOT_LUN_EN: u8 = 0x00;
+pub const UFS_ATTR_CURRENT_POWER_MODE: u8 = 0x02;
+
+// Number of logical units a device may report (bNumberLU)
+pub const UFS_MAX_LUNS: u8 = 32;
+// fDeviceInit polls before giving up
+const UFS_DEVICE_INIT_POLLS: usize = 16;
+// Retries for UNIT ATTENTION / BUSY / TASK SET FULL
+const UFS_COMMAND_RETRIES: usize = 4;
+// UNMAP block descriptors per command
+pub const UFS_UNMAP_MAX_DESCRIPTORS: usize = 64;
+// Largest data transfer of one COMMAND UPIU issued by the driver
+pub const UFS_MAX_TRANSFER_BYTES: usize = 512 * 1024;
+
+/// Errors of the UFS driver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UfsError {
+    /// The host controller failed the transfer request (OCS not SUCCESS, link down...).
+    Host(String),
+    /// A UPIU that is too short or of an unexpected type.
+    InvalidUpiu(String),
+    /// The response did not carry the task tag of the request.
+    TaskTagMismatch { expected: u8, received: u8 },
+    /// The device rejected the UPIU (REJECT UPIU).
+    Rejected,
+    /// The query request failed with the given query response code.
+    QueryFailed { opcode: u8, idn: u8, response: u8 },
+    /// Descriptor data is unusable.
+    InvalidDescriptor(String),
+    /// The SCSI command completed with CHECK CONDITION.
+    CheckCondition(SenseData),
+    /// SCSI status other than GOOD/CHECK CONDITION that retrying did not clear.
+    Status(ScsiStatus),
+    /// The RESPONSE UPIU reported a target failure without a SCSI status.
+    TargetFailure,
+    /// fDeviceInit did not clear.
+    DeviceInitTimeout,
+    /// Logical unit missing or disabled.
+    LogicalUnitNotAvailable(u8),
+    /// Block range outside the logical unit.
+    OutOfRange { lba: u64, blocks: u64 },
+    /// Transfer buffer that is not a whole number of blocks.
+    Misaligned { len: usize, block_size: usize },
+}
 
+impl fmt::Display for UfsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UfsError::Host(msg) => write!(f, "UFS host error: {}", msg),
+            UfsError::InvalidUpiu(msg) => write!(f, "Invalid UPIU: {}", msg),
+            UfsError::TaskTagMismatch { expected, received } => {
+                write!(f, "UPIU task tag mismatch (expected {}, received {})", expected, received)
+            }
+            UfsError::Rejected => write!(f, "UPIU rejected by the device"),
+            UfsError::QueryFailed { opcode, idn, response } => write!(
+                f,
+                "UFS query opcode 0x{:02X} IDN 0x{:02X} failed with response 0x{:02X}",
+                opcode, idn, response
+            ),
+            UfsError::InvalidDescriptor(msg) => write!(f, "Invalid UFS descriptor: {}", msg),
+            UfsError::CheckCondition(sense) => write!(f, "SCSI check condition: {}", sense),
+            UfsError::Status(status) => write!(f, "SCSI status {:?}", status),
+            UfsError::TargetFailure => write!(f, "UFS target failure"),
+            UfsError::DeviceInitTimeout => write!(f, "fDeviceInit did not clear"),
+            UfsError::LogicalUnitNotAvailable(lun) => write!(f, "Logical unit {} is not available", lun),
+            UfsError::OutOfRange { lba, blocks } => write!(f, "Block range {}+{} is out of bounds", lba, blocks),
+            UfsError::Misaligned { len, block_size } => {
+                write!(f, "Buffer length ({}) must be a multiple of the block size ({})", len, block_size)
+            }
+        }
+    }
+}
 
-// Import the standard BlockDevice trait and its error type
-use crate::blockdevice::{BlockDevice, BlockDeviceError}; // Assuming these are in crate::blockdevice
+/// Maps UfsError to BlockDeviceError; sense data picks the specific kind.
+fn map_ufs_error_to_block_device_error(e: UfsError) -> BlockDeviceError {
+    match e {
+        UfsError::CheckCondition(sense) => sense_to_block_device_error(&sense),
+        UfsError::Status(status) if status.is_retryable() => BlockDeviceError::TimedOut,
+        UfsError::DeviceInitTimeout => BlockDeviceError::TimedOut,
+        UfsError::LogicalUnitNotAvailable(_) => BlockDeviceError::DeviceNotFound(e.to_string()),
+        UfsError::OutOfRange { .. } => BlockDeviceError::InvalidParameter(e.to_string()),
+        UfsError::Misaligned { .. } => BlockDeviceError::BlockSizeError(e.to_string()),
+        other => BlockDeviceError::DeviceError(other.to_string()),
+    }
+}
 
+/// A UFS Protocol Information Unit: header plus data segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upiu {
+    pub header: [u8; UPIU_HEADER_SIZE],
+    pub data: Vec<u8>,
+}
 
-// Import alloc for String and format! for error messages
-use alloc::string::{String, ToString};
-use alloc::format;
+impl Upiu {
+    fn new(transaction_type: u8, flags: u8, lun: u8, task_tag: u8) -> Self {
+        let mut header = [0u8; UPIU_HEADER_SIZE];
+        header[0] = transaction_type;
+        header[1] = flags;
+        header[2] = lun;
+        header[3] = task_tag;
+        Upiu { header, data: Vec::new() }
+    }
+
+    pub fn transaction_type(&self) -> u8 {
+        self.header[0] & 0x3F
+    }
+
+    pub fn flags(&self) -> u8 {
+        self.header[1]
+    }
+
+    pub fn lun(&self) -> u8 {
+        self.header[2]
+    }
 
+    pub fn task_tag(&self) -> u8 {
+        self.header[3]
+    }
+
+    /// Query function (query UPIUs) / task management function.
+    pub fn function(&self) -> u8 {
+        self.header[5]
+    }
 
-// Define the location of the Superblock on the block device
-const SUPERBLOCK_BLOCK_ID: u64 = 0; // Superblock is typically located at block 0
-const SUPERBLOCK_MAGIC: u32 = 0x5ADAKF5B; // Example SADAK filesystem magic number (SADAK FS BLK)
+    pub fn response(&self) -> u8 {
+        self.header[6]
+    }
 
+    pub fn status(&self) -> u8 {
+        self.header[7]
+    }
+
+    fn set_data(&mut self, data: Vec<u8>) {
+        self.header[10..12].copy_from_slice(&(data.len() as u16).to_be_bytes());
+        self.data = data;
+    }
 
-// Depolama aygıtı türleri
-#[derive(Debug, PartialEq, Clone, Copy)] // Add Clone, Copy for easier handling/testing
-pub enum DeviceType {
-    HDD,
-    SSD,
-    NVMe,
-    SATA,
-    SAS,
-    UFS,
-    EMMC,
-    USB,
-    Other,
+    /// NOP OUT, used to check that the device answers.
+    pub fn nop_out(task_tag: u8) -> Self {
+        Upiu::new(UPIU_NOP_OUT, 0, 0, task_tag)
+    }
+
+    /// COMMAND UPIU carrying a SCSI CDB. The data itself moves through the PRDT.
+    pub fn command(lun: u8, task_tag: u8, cdb: &Cdb, flags: u8, expected_length: u32) -> Self {
+        let mut upiu = Upiu::new(UPIU_COMMAND, flags | UPIU_FLAG_SIMPLE_TASK, lun, task_tag);
+        upiu.header[4] = 0x00; // Command set type: SCSI
+        upiu.header[12..16].copy_from_slice(&expected_length.to_be_bytes());
+        upiu.header[16..16 + cdb.as_bytes().len()].copy_from_slice(cdb.as_bytes());
+        upiu
+    }
+
+    /// QUERY REQUEST UPIU.
+    pub fn query_request(task_tag: u8, function: u8, request: &QueryRequest, data: Vec<u8>) -> Self {
+        let mut upiu = Upiu::new(UPIU_QUERY_REQUEST, 0, 0, task_tag);
+        upiu.header[5] = function;
+        request.write_fields(&mut upiu.header);
+        upiu.set_data(data);
+        upiu
+    }
+
+    /// RESPONSE UPIU for a SCSI command (used by simulated devices).
+    pub fn command_response(request: &Upiu, status: ScsiStatus, sense: Option<&SenseData>, residual: u32) -> Self {
+        let mut upiu = Upiu::new(UPIU_RESPONSE, 0, request.lun(), request.task_tag());
+        upiu.header[6] = UPIU_RESPONSE_TARGET_SUCCESS;
+        upiu.header[7] = status.to_u8();
+        upiu.header[12..16].copy_from_slice(&residual.to_be_bytes());
+        if let Some(sense) = sense {
+            let fixed = sense.to_fixed_bytes();
+            let mut data = Vec::with_capacity(2 + fixed.len());
+            data.extend_from_slice(&(fixed.len() as u16).to_be_bytes()); // Sense data length
+            data.extend_from_slice(&fixed);
+            upiu.set_data(data);
+        }
+        upiu
+    }
+
+    /// QUERY RESPONSE UPIU (used by simulated devices).
+    pub fn query_response(request: &Upiu, response: u8, fields: &QueryRequest, data: Vec<u8>) -> Self {
+        let mut upiu = Upiu::new(UPIU_QUERY_RESPONSE, 0, 0, request.task_tag());
+        upiu.header[5] = request.function();
+        upiu.header[6] = response;
+        fields.write_fields(&mut upiu.header);
+        upiu.set_data(data);
+        upiu
+    }
+
+    /// Serializes header and data segment.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(UPIU_HEADER_SIZE + self.data.len()); // Requires alloc
+        out.extend_from_slice(&self.header);
+        out.extend_from_slice(&self.data);
+        out
+    }
+
+    /// Parses a UPIU; the data segment length comes from header bytes 10-11.
+    pub fn parse(raw: &[u8]) -> Result<Self, UfsError> {
+        if raw.len() < UPIU_HEADER_SIZE {
+            return Err(UfsError::InvalidUpiu(format!("{} bytes", raw.len())));
+        }
+        let mut header = [0u8; UPIU_HEADER_SIZE];
+        header.copy_from_slice(&raw[..UPIU_HEADER_SIZE]);
+        let data_length = u16::from_be_bytes([header[10], header[11]]) as usize;
+        let data = raw
+            .get(UPIU_HEADER_SIZE..UPIU_HEADER_SIZE + data_length)
+            .ok_or_else(|| UfsError::InvalidUpiu(format!("data segment of {} bytes is truncated", data_length)))?;
+        Ok(Upiu { header, data: data.to_vec() })
+    }
+
+    /// Expected data transfer length of a COMMAND UPIU.
+    pub fn expected_length(&self) -> u32 {
+        u32::from_be_bytes([self.header[12], self.header[13], self.header[14], self.header[15]])
+    }
+
+    /// CDB of a COMMAND UPIU (16 bytes, padded).
+    pub fn cdb(&self) -> &[u8] {
+        &self.header[16..32]
+    }
+
+    /// Sense data carried by a RESPONSE UPIU.
+    pub fn sense(&self) -> Option<SenseData> {
+        if self.data.len() < 2 {
+            return None;
+        }
+        let length = u16::from_be_bytes([self.data[0], self.data[1]]) as usize;
+        SenseData::parse(self.data.get(2..2 + length)?)
+    }
 }
 
-// Süperblok yapısı (On-disk structure)
-#[repr(C)] // Use C representation for compatibility with C or raw memory layout
-// #[repr(packed)] // Avoid packed unless absolutely necessary, as it can lead to unaligned access issues
-#[derive(Debug, Clone, Copy, PartialEq)] // Derive necessary traits
-pub struct Superblock {
-    pub magic: u32,           // Dosya sistemi sihirli sayısı (SUPERBLOCK_MAGIC)
-    pub version: u32,         // Dosya sistemi versiyonu
-    pub block_size: u32,      // Blok boyutu (bayt) - Logical Block Size
-    pub inode_size: u32,      // Inode boyutu (bayt)
-    pub blocks_count: u64,    // Toplam blok sayısı (filesystem size in blocks)
-    pub inodes_count: u64,    // Toplam inode sayısı
-    pub free_blocks_count: u64, // Boş blok sayısı (runtime/volatile, updated in memory)
-    pub free_inodes_count: u64, // Boş inode sayısı (runtime/volatile, updated in memory)
-    pub root_inode: u64,      // Kök dizinin inode numarası (usually 1)
-    pub block_bitmap_start: u64, // Blok bitmap'inin başlangıç blok numarası
-    pub inode_table_start: u64, // Inode tablosunun başlangıç blok numarası
-    pub data_blocks_start: u64, // Veri bloklarının başlangıç blok numarası
-    pub device_type: DeviceType, // Depolama aygıtı türü
-    pub device_id: u64,         // Aygıt kimliği (örneğin, UUID veya seri numarası)
-    // Add checksums, timestamps, state flags, etc.
-    checksum: u32, // CRC32 or similar checksum for integrity verification
-    last_mounted_time: u64, // Unix timestamp of last mount (for fsck)
-    fs_state: u32, // Filesystem state (cleanly unmounted, needs checking, etc.)
-    padding: [u8; ...], // Padding to fill up to a block size if needed
+/// Transaction specific fields of a QUERY REQUEST / QUERY RESPONSE UPIU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryRequest {
+    pub opcode: u8,
+    pub idn: u8,
+    pub index: u8,
+    pub selector: u8,
+    pub length: u16, // Descriptor length
+    pub value: u32, // Attribute value / flag value (bit 0)
 }
 
-impl Superblock {
-    /// Creates a new in-memory Superblock instance for a fresh filesystem.
-    pub fn new(
-        block_size: u32,
-        inode_size: u32,
-        blocks_count: u64,
-        inodes_count: u64,
-        device_type: DeviceType,
-        device_id: u64,
-        // Add start block locations for key areas as parameters
-        block_bitmap_start: u64,
-        inode_table_start: u64,
-        data_blocks_start: u64,
-    ) -> Self { // Return Self
-        Superblock {
-            magic: SUPERBLOCK_MAGIC, // Use the defined magic number
-            version: 1, // Initial version
-            block_size,
-            inode_size,
-            blocks_count,
-            inodes_count,
-            free_blocks_count: blocks_count, // Initially all blocks are free
-            free_inodes_count: inodes_count, // Initially all inodes are free
-            root_inode: 1, // Root inode is typically 1
-            block_bitmap_start,
-            inode_table_start,
-            data_blocks_start,
-            device_type,
-            device_id,
-            // Initialize other fields...
-            checksum: 0, // Placeholder
-            last_mounted_time: 0, // Placeholder
-            fs_state: 0, // Placeholder
-            padding: [0u8; ...], // Placeholder
-        }
-    }
-
-    /// Returns the size of the Superblock structure in bytes.
-    pub fn size() -> usize { // Make it an associated function as it doesn't need a Self instance
-        mem::size_of::<Superblock>()
-    }
-
-    /// Checks if the Superblock has a valid magic number.
-    pub fn is_valid(&self) -> bool { // Takes &self
-        self.magic == SUPERBLOCK_MAGIC // Check against the defined magic number
-    }
-
-    /// Calculates and returns the expected number of blocks required for the Superblock.
-    /// Assumes Superblock is always stored in a whole number of blocks.
-    pub fn blocks_needed(&self) -> u64 {
-         let sb_size = Superblock::size();
-         // Use self.block_size for the actual block size of this filesystem
-         let block_size_bytes = self.block_size as usize;
-         // Calculate blocks needed, rounding up
-         ((sb_size + block_size_bytes - 1) / block_size_bytes) as u64
-    }
-
-
-    /// Updates the free block count in the Superblock.
-    pub fn update_free_blocks(&mut self, free_blocks_count: u64) { // Takes &mut self
-        self.free_blocks_count = free_blocks_count;
-        // Mark as dirty if tracking state?
-    }
-
-    /// Updates the free inode count in the Superblock.
-    pub fn update_free_inodes(&mut self, free_inodes_count: u64) { // Takes &mut self
-        self.free_inodes_count = free_inodes_count;
-         // Mark as dirty?
-    }
-
-    /// Loads the Superblock from the specified block device.
-    /// Assumes the Superblock is located at SUPERBLOCK_BLOCK_ID.
+impl QueryRequest {
+    pub fn read_descriptor(idn: u8, index: u8, selector: u8) -> Self {
+        QueryRequest { opcode: QUERY_OPCODE_READ_DESCRIPTOR, idn, index, selector, length: UFS_DESC_MAX_SIZE as u16, value: 0 }
+    }
+
+    pub fn read_attribute(idn: u8, index: u8, selector: u8) -> Self {
+        QueryRequest { opcode: QUERY_OPCODE_READ_ATTRIBUTE, idn, index, selector, length: 0, value: 0 }
+    }
+
+    pub fn write_attribute(idn: u8, index: u8, selector: u8, value: u32) -> Self {
+        QueryRequest { opcode: QUERY_OPCODE_WRITE_ATTRIBUTE, idn, index, selector, length: 0, value }
+    }
+
+    pub fn flag(opcode: u8, idn: u8) -> Self {
+        QueryRequest { opcode, idn, index: 0, selector: 0, length: 0, value: 0 }
+    }
+
+    fn write_fields(&self, header: &mut [u8; UPIU_HEADER_SIZE]) {
+        header[12] = self.opcode;
+        header[13] = self.idn;
+        header[14] = self.index;
+        header[15] = self.selector;
+        header[18..20].copy_from_slice(&self.length.to_be_bytes());
+        header[20..24].copy_from_slice(&self.value.to_be_bytes());
+    }
+
+    pub fn parse(upiu: &Upiu) -> Self {
+        let h = &upiu.header;
+        QueryRequest {
+            opcode: h[12],
+            idn: h[13],
+            index: h[14],
+            selector: h[15],
+            length: u16::from_be_bytes([h[18], h[19]]),
+            value: u32::from_be_bytes([h[20], h[21], h[22], h[23]]),
+        }
+    }
+}
+
+/// Data phase of a UTP transfer request, described by the PRDT.
+pub enum UfsData<'a> {
+    None,
+    In(&'a mut [u8]), // Device to host
+    Out(&'a [u8]), // Host to device
+}
+
+/// A UFS host controller: runs one UTP transfer request.
+pub trait UfsHost {
+    /// Sends a request UPIU and returns the response UPIU.
     ///
     /// # Arguments
     ///
-    /// * `device`: A mutable reference to the block device to read from.
+    /// * `request`: Serialized request UPIU (command descriptor).
+    /// * `data`: PRDT data buffer; `UfsData::In` buffers are filled by the device.
     ///
     /// # Returns
     ///
-    /// A Result containing the loaded and validated Superblock, or a FileSystemError.
-    pub fn load_from_device(device: &mut impl BlockDevice) -> Result<Self, FileSystemError> { // Return Result<Self, FileSystemError>
-         let sb_size = Superblock::size();
-         let device_block_size = device.block_size();
-
-         // Check if device block size is large enough to hold the Superblock
-         if device_block_size < sb_size {
-             return Err(FileSystemError::InvalidData(format!(
-                 "Device block size ({}) is smaller than Superblock size ({}).",
-                 device_block_size, sb_size
-             ))); // Requires alloc
-         }
-
-         // Allocate a buffer to read the Superblock data (needs to be device block size)
-         // If Superblock spans multiple blocks, need a buffer large enough for all SB blocks.
-         // For simplicity, assume Superblock fits within one device block for now.
-         // If not, read multiple blocks. Let's use Superblock::blocks_needed().
-         let sb_blocks_needed = Superblock::new( // Need config for blocks_needed, but config is from SB. This is a circular dependency.
-                                                // Let's assume for loading we can determine device block size independently
-                                                // and allocate a buffer that fits the *minimum* expected SB size or device block size.
-                                                // A safer approach is to read one block, check basic magic/block_size,
-                                                // then read remaining blocks if needed.
-                                                // Let's assume Superblock fits in Block 0, and allocate a buffer of Block 0 size.
-
-         // Read Block 0 to get the potential Superblock data
-         let mut buffer = alloc::vec![0u8; device_block_size]; // Requires alloc
-         device.read_block(SUPERBLOCK_BLOCK_ID, &mut buffer).map_err(|e| map_block_device_error_to_fs_error(e))?; // Map BlockDeviceError
-
-         // Transmute the buffer into a Superblock struct.
-         // This is UNSAFE and requires careful consideration of alignment and padding.
-         // The buffer must be at least `size_of::<Superblock>()` bytes and aligned correctly.
-         // `#[repr(C)]` helps with layout but doesn't guarantee alignment within the buffer.
-         // Assuming the buffer is correctly aligned for the struct members by the block device read.
-         if buffer.len() < sb_size {
-              return Err(FileSystemError::InvalidData(format!("Buffer size ({}) is too small for Superblock ({}).", buffer.len(), sb_size))); // Requires alloc
-         }
-
-         let superblock = unsafe {
-             // Create a pointer to the start of the buffer
-             let ptr = buffer.as_ptr() as *const Superblock;
-             // Dereference the pointer to get the Superblock struct
-             // This is safe IF the buffer contains valid Superblock data and is correctly aligned.
-             // It's UNSAFE because we cannot guarantee these conditions from `read_block`.
-             ptr::read_unaligned(ptr) // Use read_unaligned to handle potential alignment issues
-         };
-
-
-         // Validate the loaded Superblock
-         if !superblock.is_valid() {
-             return Err(FileSystemError::InvalidData(format!(
-                 "Invalid Superblock magic number: Expected {}, found {}",
-                 SUPERBLOCK_MAGIC, superblock.magic
-             ))); // Requires alloc
-         }
-
-         // Basic consistency checks (optional but recommended)
-         // E.g., block_size > 0, inode_size > 0, blocks_count matches device size if known, etc.
-          if superblock.block_size == 0 || superblock.inode_size == 0 || superblock.blocks_count == 0 || superblock.inodes_count == 0 {
-               return Err(FileSystemError::InvalidData(String::from("Superblock contains zero or invalid counts/sizes."))); // Requires alloc
-          }
-
-
-         Ok(superblock) // Return the loaded and validated Superblock
-    }
-
-    /// Saves the Superblock to the specified block device.
-    /// Assumes the Superblock is written to SUPERBLOCK_BLOCK_ID.
-    ///
-    /// # Arguments
-    ///
-    /// * `device`: A mutable reference to the block device to write to.
+    /// The serialized response UPIU, or UfsError::Host if the controller failed the request.
+    fn transfer(&mut self, request: &[u8], data: UfsData<'_>) -> Result<Vec<u8>, UfsError>;
+}
+
+/// Parsed device descriptor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceDescriptor {
+    pub number_lu: u8,
+    pub spec_version: u16,
+    pub manufacturer_name_index: u8,
+    pub product_name_index: u8,
+    pub serial_number_index: u8,
+    pub manufacturer_id: u16,
+}
+
+impl DeviceDescriptor {
+    pub fn parse(raw: &[u8]) -> Result<Self, UfsError> {
+        if raw.len() < 0x1A || raw[1] != UFS_DESC_DEVICE {
+            return Err(UfsError::InvalidDescriptor(String::from("device descriptor")));
+        }
+        Ok(DeviceDescriptor {
+            number_lu: raw[0x06],
+            spec_version: u16::from_be_bytes([raw[0x10], raw[0x11]]),
+            manufacturer_name_index: raw[0x14],
+            product_name_index: raw[0x15],
+            serial_number_index: raw[0x16],
+            manufacturer_id: u16::from_be_bytes([raw[0x18], raw[0x19]]),
+        })
+    }
+}
+
+/// Parsed unit descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnitDescriptor {
+    pub unit_index: u8,
+    pub enabled: bool,
+    pub boot_lun_id: u8,
+    pub write_protect: u8, // 0: none, 1: power-on write protect, 2: permanent
+    pub logical_block_size: u32,
+    pub logical_block_count: u64,
+    pub erase_block_size: u32, // In logical blocks
+    pub provisioning_type: u8, // 0: full, 2/3: thin provisioning
+}
+
+impl UnitDescriptor {
+    pub fn parse(raw: &[u8]) -> Result<Self, UfsError> {
+        if raw.len() < 0x18 || raw[1] != UFS_DESC_UNIT {
+            return Err(UfsError::InvalidDescriptor(String::from("unit descriptor")));
+        }
+        if raw[0x0A] < 9 || raw[0x0A] > 16 {
+            return Err(UfsError::InvalidDescriptor(format!("bLogicalBlockSize {}", raw[0x0A])));
+        }
+        let mut count = [0u8; 8];
+        count.copy_from_slice(&raw[0x0B..0x13]);
+        Ok(UnitDescriptor {
+            unit_index: raw[0x02],
+            enabled: raw[0x03] != 0,
+            boot_lun_id: raw[0x04],
+            write_protect: raw[0x05],
+            logical_block_size: 1 << raw[0x0A],
+            logical_block_count: u64::from_be_bytes(count),
+            erase_block_size: u32::from_be_bytes([raw[0x13], raw[0x14], raw[0x15], raw[0x16]]),
+            provisioning_type: raw[0x17],
+        })
+    }
+
+    /// True when the unit supports UNMAP (thin provisioning).
+    pub fn thin_provisioned(&self) -> bool {
+        self.provisioning_type == 0x02 || self.provisioning_type == 0x03
+    }
+}
+
+/// Decodes a string descriptor (UTF-16BE).
+pub fn parse_string_descriptor(raw: &[u8]) -> Result<String, UfsError> {
+    if raw.len() < 2 || raw[1] != UFS_DESC_STRING || (raw[0] as usize) > raw.len() {
+        return Err(UfsError::InvalidDescriptor(String::from("string descriptor")));
+    }
+    let units: Vec<u16> = raw[2..raw[0] as usize].chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+    Ok(String::from_utf16_lossy(&units))
+}
+
+/// A UFS device: the host controller plus the descriptors read at initialization.
+pub struct UfsDevice<H: UfsHost> {
+    host: H,
+    next_tag: u8,
+    device_descriptor: DeviceDescriptor,
+    units: Vec<UnitDescriptor>,
+}
+
+/// Device shared by its logical unit block devices.
+pub type SharedUfsDevice<H> = Arc<Mutex<UfsDevice<H>>>;
+
+impl<H: UfsHost> UfsDevice<H> {
+    /// Initializes the device: NOP OUT/NOP IN, fDeviceInit (set, then poll until the device
+    /// clears it), device descriptor and the unit descriptor of every logical unit.
     ///
     /// # Returns
     ///
-    /// A Result indicating success or a FileSystemError.
-    pub fn save_to_device(&self, device: &mut impl BlockDevice) -> Result<(), FileSystemError> { // Takes &self, return Result<(), FileSystemError>
-         let sb_size = Superblock::size();
-         let device_block_size = device.block_size();
+    /// The device or a UfsError.
+    pub fn new(host: H) -> Result<Self, UfsError> {
+        let mut device = UfsDevice {
+            host,
+            next_tag: 0,
+            device_descriptor: DeviceDescriptor {
+                number_lu: 0,
+                spec_version: 0,
+                manufacturer_name_index: 0,
+                product_name_index: 0,
+                serial_number_index: 0,
+                manufacturer_id: 0,
+            },
+            units: Vec::new(),
+        };
+        device.nop()?;
+
+        device.set_flag(UFS_FLAG_DEVICE_INIT)?;
+        let mut initialized = false;
+        for _ in 0..UFS_DEVICE_INIT_POLLS {
+            if !device.read_flag(UFS_FLAG_DEVICE_INIT)? {
+                initialized = true;
+                break;
+            }
+        }
+        if !initialized {
+            return Err(UfsError::DeviceInitTimeout);
+        }
+
+        let raw = device.read_descriptor(UFS_DESC_DEVICE, 0, 0)?;
+        device.device_descriptor = DeviceDescriptor::parse(&raw)?;
+        for lun in 0..core::cmp::min(device.device_descriptor.number_lu, UFS_MAX_LUNS) {
+            let raw = device.read_descriptor(UFS_DESC_UNIT, lun, 0)?;
+            let unit = UnitDescriptor::parse(&raw)?;
+            device.units.push(unit);
+        }
+        Ok(device)
+    }
+
+    /// Device descriptor read at initialization.
+    pub fn device_descriptor(&self) -> &DeviceDescriptor {
+        &self.device_descriptor
+    }
+
+    /// Unit descriptors of the logical units, indexed by LUN.
+    pub fn units(&self) -> &[UnitDescriptor] {
+        &self.units
+    }
+
+    /// Gives access to the host controller.
+    pub fn host_mut(&mut self) -> &mut H {
+        &mut self.host
+    }
+
+    /// Makes the device shareable by its logical unit block devices.
+    pub fn into_shared(self) -> SharedUfsDevice<H> {
+        Arc::new(Mutex::new(self))
+    }
+
+    /// Product name from the string descriptor referenced by the device descriptor.
+    pub fn product_name(&mut self) -> Result<String, UfsError> {
+        let index = self.device_descriptor.product_name_index;
+        let raw = self.read_descriptor(UFS_DESC_STRING, index, 0)?;
+        parse_string_descriptor(&raw)
+    }
+
+    /// NOP OUT / NOP IN round trip.
+    pub fn nop(&mut self) -> Result<(), UfsError> {
+        let tag = self.tag();
+        let response = self.exchange(&Upiu::nop_out(tag), UfsData::None)?;
+        if response.transaction_type() != UPIU_NOP_IN {
+            return Err(UfsError::InvalidUpiu(format!("expected NOP IN, got 0x{:02X}", response.transaction_type())));
+        }
+        Ok(())
+    }
+
+    /// READ DESCRIPTOR query.
+    pub fn read_descriptor(&mut self, idn: u8, index: u8, selector: u8) -> Result<Vec<u8>, UfsError> {
+        let response = self.query(QUERY_FUNCTION_STANDARD_READ, QueryRequest::read_descriptor(idn, index, selector), Vec::new())?;
+        Ok(response.data)
+    }
 
-         // Check if device block size is large enough
-         if device_block_size < sb_size {
-             return Err(FileSystemError::InvalidData(format!(
-                 "Device block size ({}) is smaller than Superblock size ({}). Cannot save.",
-                 device_block_size, sb_size
-             ))); // Requires alloc
-         }
+    /// READ ATTRIBUTE query.
+    pub fn read_attribute(&mut self, idn: u8, index: u8, selector: u8) -> Result<u32, UfsError> {
+        let response = self.query(QUERY_FUNCTION_STANDARD_READ, QueryRequest::read_attribute(idn, index, selector), Vec::new())?;
+        Ok(QueryRequest::parse(&response).value)
+    }
 
-         // Allocate a buffer to hold the Superblock data (needs to be at least sb_size)
-         // Pad to device block size if writing a full block.
-         let mut buffer = alloc::vec![0u8; device_block_size]; // Requires alloc
+    /// WRITE ATTRIBUTE query.
+    pub fn write_attribute(&mut self, idn: u8, index: u8, selector: u8, value: u32) -> Result<(), UfsError> {
+        let request = QueryRequest::write_attribute(idn, index, selector, value);
+        self.query(QUERY_FUNCTION_STANDARD_WRITE, request, Vec::new()).map(|_| ())
+    }
 
+    /// READ FLAG query.
+    pub fn read_flag(&mut self, idn: u8) -> Result<bool, UfsError> {
+        let response = self.query(QUERY_FUNCTION_STANDARD_READ, QueryRequest::flag(QUERY_OPCODE_READ_FLAG, idn), Vec::new())?;
+        Ok(QueryRequest::parse(&response).value & 1 != 0)
+    }
 
-         // Transmute the Superblock struct into a byte slice.
-         // This is UNSAFE and requires careful consideration of alignment and padding.
-         // The buffer must be large enough and the struct's layout must match the byte representation.
-         // Assuming the struct can be safely represented as a byte sequence.
-         let sb_bytes = unsafe {
-             // Get a pointer to the Superblock struct
-             let ptr = self as *const Superblock;
-             // Create a slice from the pointer with the size of the struct
-             // This is safe IF the struct's memory is valid and accessible as bytes.
-             // It's UNSAFE because we are asserting the byte representation.
-             core::slice::from_raw_parts(ptr as *const u8, sb_size)
-         };
+    /// SET FLAG query.
+    pub fn set_flag(&mut self, idn: u8) -> Result<(), UfsError> {
+        self.query(QUERY_FUNCTION_STANDARD_WRITE, QueryRequest::flag(QUERY_OPCODE_SET_FLAG, idn), Vec::new()).map(|_| ())
+    }
 
-         // Copy the Superblock bytes into the buffer
-         buffer[..sb_size].copy_from_slice(sb_bytes);
+    /// Runs a SCSI command on a logical unit, retrying through UNIT ATTENTION and BUSY /
+    /// TASK SET FULL. Returns the number of data bytes moved (expected length - residual).
+    pub fn scsi_command(&mut self, lun: u8, cdb: &Cdb, mut data: UfsData<'_>) -> Result<usize, UfsError> {
+        let (flags, expected) = match &data {
+            UfsData::None => (0, 0),
+            UfsData::In(buf) => (UPIU_FLAG_READ, buf.len() as u32),
+            UfsData::Out(buf) => (UPIU_FLAG_WRITE, buf.len() as u32),
+        };
+        let mut last_error = UfsError::Status(ScsiStatus::Busy);
+        for _ in 0..UFS_COMMAND_RETRIES {
+            let tag = self.tag();
+            let phase = match &mut data {
+                UfsData::None => UfsData::None,
+                UfsData::In(buf) => UfsData::In(&mut buf[..]),
+                UfsData::Out(buf) => UfsData::Out(buf),
+            };
+            let response = self.exchange(&Upiu::command(lun, tag, cdb, flags, expected), phase)?;
+            if response.transaction_type() != UPIU_RESPONSE {
+                return Err(UfsError::InvalidUpiu(format!("expected RESPONSE, got 0x{:02X}", response.transaction_type())));
+            }
+            if response.response() != UPIU_RESPONSE_TARGET_SUCCESS {
+                return Err(UfsError::TargetFailure);
+            }
+            match ScsiStatus::from_u8(response.status()) {
+                ScsiStatus::Good | ScsiStatus::ConditionMet => {
+                    let residual = u32::from_be_bytes([response.header[12], response.header[13], response.header[14], response.header[15]]);
+                    return Ok(expected.saturating_sub(residual) as usize);
+                }
+                ScsiStatus::CheckCondition => {
+                    let sense = response
+                        .sense()
+                        .ok_or_else(|| UfsError::InvalidUpiu(String::from("CHECK CONDITION without sense data")))?;
+                    if !sense.is_unit_attention() {
+                        return Err(UfsError::CheckCondition(sense));
+                    }
+                    last_error = UfsError::CheckCondition(sense);
+                }
+                status if status.is_retryable() => last_error = UfsError::Status(status),
+                status => return Err(UfsError::Status(status)),
+            }
+        }
+        Err(last_error)
+    }
 
+    fn query(&mut self, function: u8, request: QueryRequest, data: Vec<u8>) -> Result<Upiu, UfsError> {
+        let tag = self.tag();
+        let response = self.exchange(&Upiu::query_request(tag, function, &request, data), UfsData::None)?;
+        if response.transaction_type() != UPIU_QUERY_RESPONSE {
+            return Err(UfsError::InvalidUpiu(format!("expected QUERY RESPONSE, got 0x{:02X}", response.transaction_type())));
+        }
+        if response.response() != QUERY_RESPONSE_SUCCESS {
+            return Err(UfsError::QueryFailed { opcode: request.opcode, idn: request.idn, response: response.response() });
+        }
+        Ok(response)
+    }
 
-         // Write the buffer to the Superblock block on the device
-         device.write_block(SUPERBLOCK_BLOCK_ID, &buffer).map_err(|e| map_block_device_error_to_fs_error(e))?; // Map BlockDeviceError
+    fn tag(&mut self) -> u8 {
+        let tag = self.next_tag;
+        self.next_tag = self.next_tag.wrapping_add(1);
+        tag
+    }
 
+    // Sends a request and checks the response belongs to it.
+    fn exchange(&mut self, request: &Upiu, data: UfsData<'_>) -> Result<Upiu, UfsError> {
+        let raw = self.host.transfer(&request.to_bytes(), data)?;
+        let response = Upiu::parse(&raw)?;
+        if response.transaction_type() == UPIU_REJECT {
+            return Err(UfsError::Rejected);
+        }
+        if response.task_tag() != request.task_tag() {
+            return Err(UfsError::TaskTagMismatch { expected: request.task_tag(), received: response.task_tag() });
+        }
+        Ok(response)
+    }
+}
+
+/// One logical unit of a UFS device, exposed as a block device.
+pub struct UfsLogicalUnit<H: UfsHost> {
+    device: SharedUfsDevice<H>,
+    lun: u8,
+    unit: UnitDescriptor,
+    capacity: ReadCapacity,
+}
+
+impl<H: UfsHost> UfsLogicalUnit<H> {
+    /// Opens logical unit `lun`: it must be enabled in its unit descriptor; TEST UNIT READY
+    /// and READ CAPACITY (16) confirm the SCSI view of the unit.
+    pub fn open(device: SharedUfsDevice<H>, lun: u8) -> Result<Self, UfsError> {
+        let (unit, capacity) = {
+            let mut dev = device.lock();
+            let unit = *dev.units.get(lun as usize).ok_or(UfsError::LogicalUnitNotAvailable(lun))?;
+            if !unit.enabled {
+                return Err(UfsError::LogicalUnitNotAvailable(lun));
+            }
+            dev.scsi_command(lun, &Cdb::test_unit_ready(), UfsData::None)?;
+            let mut raw = [0u8; SCSI_READ_CAPACITY_16_SIZE];
+            let received = dev.scsi_command(lun, &Cdb::read_capacity_16(SCSI_READ_CAPACITY_16_SIZE as u32), UfsData::In(&mut raw))?;
+            let capacity = ReadCapacity::parse_16(&raw[..received])
+                .ok_or_else(|| UfsError::InvalidUpiu(format!("Short READ CAPACITY(16) response: {} bytes", received)))?;
+            (unit, capacity)
+        };
+        if capacity.block_size != unit.logical_block_size || capacity.block_size as usize > UFS_MAX_TRANSFER_BYTES {
+            return Err(UfsError::InvalidDescriptor(format!(
+                "LU {}: block size {} does not match unit descriptor ({})",
+                lun, capacity.block_size, unit.logical_block_size
+            )));
+        }
+        Ok(UfsLogicalUnit { device, lun, unit, capacity })
+    }
+
+    /// Opens every enabled logical unit of the device.
+    pub fn open_all(device: &SharedUfsDevice<H>) -> Result<Vec<Self>, UfsError> {
+        let luns: Vec<u8> = device.lock().units.iter().filter(|u| u.enabled).map(|u| u.unit_index).collect();
+        luns.into_iter().map(|lun| UfsLogicalUnit::open(device.clone(), lun)).collect()
+    }
 
-         Ok(()) // Save operation successful
+    pub fn lun(&self) -> u8 {
+        self.lun
     }
 
+    pub fn unit_descriptor(&self) -> &UnitDescriptor {
+        &self.unit
+    }
+
+    pub fn capacity(&self) -> &ReadCapacity {
+        &self.capacity
+    }
 
-    // ... other functions ...
-    // Add update methods for other fields as needed
-    // Add checksum calculation/verification methods
-    // Add state flag manipulation methods
+    /// INQUIRY data of the logical unit.
+    pub fn inquiry(&mut self) -> Result<InquiryData, UfsError> {
+        let mut raw = [0u8; SCSI_INQUIRY_SIZE];
+        let received = self.device.lock().scsi_command(self.lun, &Cdb::inquiry(SCSI_INQUIRY_SIZE as u8), UfsData::In(&mut raw))?;
+        InquiryData::parse(&raw[..received]).ok_or_else(|| UfsError::InvalidUpiu(format!("Short INQUIRY response: {} bytes", received)))
+    }
+
+    /// READ of whole blocks starting at `lba`.
+    pub fn read_blocks(&mut self, lba: u64, buf: &mut [u8]) -> Result<(), UfsError> {
+        let block_size = self.capacity.block_size as usize;
+        self.check_blocks(lba, buf.len())?;
+        let chunk_bytes = UFS_MAX_TRANSFER_BYTES / block_size * block_size;
+        let mut device = self.device.lock();
+        for (i, chunk) in buf.chunks_mut(chunk_bytes).enumerate() {
+            let chunk_lba = lba + (i * chunk_bytes / block_size) as u64;
+            let blocks = (chunk.len() / block_size) as u32;
+            let received = device.scsi_command(self.lun, &Cdb::read(chunk_lba, blocks), UfsData::In(chunk))?;
+            if received != blocks as usize * block_size {
+                return Err(UfsError::InvalidUpiu(format!("Short read: {} bytes", received)));
+            }
+        }
+        Ok(())
+    }
+
+    /// WRITE of whole blocks starting at `lba`.
+    pub fn write_blocks(&mut self, lba: u64, buf: &[u8]) -> Result<(), UfsError> {
+        let block_size = self.capacity.block_size as usize;
+        self.check_blocks(lba, buf.len())?;
+        let chunk_bytes = UFS_MAX_TRANSFER_BYTES / block_size * block_size;
+        let mut device = self.device.lock();
+        for (i, chunk) in buf.chunks(chunk_bytes).enumerate() {
+            let chunk_lba = lba + (i * chunk_bytes / block_size) as u64;
+            let blocks = (chunk.len() / block_size) as u32;
+            device.scsi_command(self.lun, &Cdb::write(chunk_lba, blocks), UfsData::Out(chunk))?;
+        }
+        Ok(())
+    }
+
+    /// UNMAP a block range (thin provisioned units only).
+    pub fn unmap(&mut self, lba: u64, blocks: u64) -> Result<(), UfsError> {
+        self.check_range(lba, blocks)?;
+        let mut device = self.device.lock();
+        let mut done = 0;
+        while done < blocks {
+            let (list, covered) = build_unmap_parameter_list(lba + done, blocks - done, UFS_UNMAP_MAX_DESCRIPTORS);
+            device.scsi_command(self.lun, &Cdb::unmap(list.len() as u16), UfsData::Out(&list))?;
+            done += covered;
+        }
+        Ok(())
+    }
+
+    /// SYNCHRONIZE CACHE (10) over the whole unit.
+    pub fn synchronize_cache(&mut self) -> Result<(), UfsError> {
+        self.device.lock().scsi_command(self.lun, &Cdb::synchronize_cache_10(0, 0), UfsData::None).map(|_| ())
+    }
+
+    // The CDB transfer length counts whole blocks: a partial trailing block is rejected, not dropped.
+    fn check_blocks(&self, lba: u64, len: usize) -> Result<(), UfsError> {
+        let block_size = self.capacity.block_size as usize;
+        if len % block_size != 0 {
+            return Err(UfsError::Misaligned { len, block_size });
+        }
+        self.check_range(lba, (len / block_size) as u64)
+    }
+
+    fn check_range(&self, lba: u64, blocks: u64) -> Result<(), UfsError> {
+        if lba.checked_add(blocks).map_or(true, |end| end > self.capacity.block_count) {
+            return Err(UfsError::OutOfRange { lba, blocks });
+        }
+        Ok(())
+    }
+
+    fn check_buffer(&self, len: usize) -> Result<(), BlockDeviceError> {
+        if len != self.capacity.block_size as usize {
+            return Err(BlockDeviceError::BlockSizeError(format!(
+                "Buffer size ({}) must match block size ({}).",
+                len, self.capacity.block_size
+            )));
+        }
+        Ok(())
+    }
 }
 
-// Helper function to map std::io::Error to BlockDeviceError (for std tests and implementation)
-#[cfg(feature = "std")]
-impl From<std::io::Error> for BlockDeviceError {
-     fn from(error: std::io::Error) -> Self {
-         BlockDeviceError::IoError(error) // Assuming BlockDeviceError::IoError wraps std::io::Error in std
-     }
+impl<H: UfsHost> BlockDevice for UfsLogicalUnit<H> {
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> Result<(), BlockDeviceError> {
+        self.check_buffer(buf.len())?;
+        self.read_blocks(block_id, buf).map_err(map_ufs_error_to_block_device_error)
+    }
+
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> Result<(), BlockDeviceError> {
+        self.check_buffer(buf.len())?;
+        self.write_blocks(block_id, buf).map_err(map_ufs_error_to_block_device_error)
+    }
+
+    fn block_size(&self) -> usize {
+        self.capacity.block_size as usize
+    }
+
+    fn block_count(&self) -> u64 {
+        self.capacity.block_count
+    }
+
+    /// UNMAP on thin provisioned units; otherwise a no-op (discard is advisory).
+    fn discard_blocks(&mut self, start_block: u64, block_count: u64) -> Result<(), BlockDeviceError> {
+        if !self.unit.thin_provisioned() {
+            return Ok(());
+        }
+        self.unmap(start_block, block_count).map_err(map_ufs_error_to_block_device_error)
+    }
+
+    fn flush(&mut self) -> Result<(), BlockDeviceError> {
+        self.synchronize_cache().map_err(map_ufs_error_to_block_device_error)
+    }
+}
+
+/// Configuration of one simulated logical unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulatedLu {
+    pub block_size_shift: u8, // bLogicalBlockSize (12 = 4096 bytes)
+    pub block_count: u64,
+    pub write_protected: bool,
+    pub thin_provisioned: bool,
+}
+
+/// In-memory UFS device answering UPIUs: device/unit/string descriptors, fDeviceInit,
+/// attributes, and SCSI commands on every logical unit.
+pub struct UfsSimulator {
+    units: Vec<(SimulatedLu, Vec<u8>)>,
+    product_name: String,
+    device_init_polls: u32, // READ FLAG fDeviceInit answers "still set" this many times
+    device_init: bool,
+    attributes: [u32; 0x20],
+    pending_unit_attention: Vec<bool>, // Per LU, reported once after power on
+    syncs: u32,
+    unmapped: Vec<(u8, u64, u32)>,
+    tag_offset: u8, // Non-zero: answer with a wrong task tag
+}
+
+impl UfsSimulator {
+    /// A device with the given logical units, all reporting a power-on UNIT ATTENTION first.
+    pub fn new(units: &[SimulatedLu]) -> Self {
+        UfsSimulator {
+            units: units
+                .iter()
+                .map(|lu| (*lu, vec![0u8; (lu.block_count as usize) << lu.block_size_shift]))
+                .collect(),
+            product_name: String::from("SADAK UFS"),
+            device_init_polls: 2,
+            device_init: false,
+            attributes: [0u32; 0x20],
+            pending_unit_attention: vec![true; units.len()],
+            syncs: 0,
+            unmapped: Vec::new(),
+            tag_offset: 0,
+        }
+    }
+
+    /// Makes responses carry a wrong task tag (host/driver mismatch testing).
+    pub fn corrupt_task_tags(&mut self, offset: u8) {
+        self.tag_offset = offset;
+    }
+
+    /// SYNCHRONIZE CACHE commands received.
+    pub fn sync_count(&self) -> u32 {
+        self.syncs
+    }
+
+    /// UNMAP ranges received as (lun, lba, blocks).
+    pub fn unmapped(&self) -> &[(u8, u64, u32)] {
+        &self.unmapped
+    }
+
+    fn device_descriptor(&self) -> Vec<u8> {
+        let mut desc = vec![0u8; UFS_DEVICE_DESC_SIZE];
+        desc[0x00] = UFS_DEVICE_DESC_SIZE as u8;
+        desc[0x01] = UFS_DESC_DEVICE;
+        desc[0x06] = self.units.len() as u8;
+        desc[0x10..0x12].copy_from_slice(&0x0310u16.to_be_bytes()); // UFS 3.1
+        desc[0x15] = 1; // iProductName
+        desc[0x18..0x1A].copy_from_slice(&0x01CEu16.to_be_bytes());
+        desc
+    }
+
+    fn unit_descriptor(&self, lun: usize) -> Vec<u8> {
+        let (lu, _) = &self.units[lun];
+        let mut desc = vec![0u8; UFS_UNIT_DESC_SIZE];
+        desc[0x00] = UFS_UNIT_DESC_SIZE as u8;
+        desc[0x01] = UFS_DESC_UNIT;
+        desc[0x02] = lun as u8;
+        desc[0x03] = 1; // bLUEnable
+        desc[0x05] = if lu.write_protected { 2 } else { 0 };
+        desc[0x0A] = lu.block_size_shift;
+        desc[0x0B..0x13].copy_from_slice(&lu.block_count.to_be_bytes());
+        desc[0x13..0x17].copy_from_slice(&1024u32.to_be_bytes());
+        desc[0x17] = if lu.thin_provisioned { 0x03 } else { 0x00 };
+        desc
+    }
+
+    fn string_descriptor(&self) -> Vec<u8> {
+        let mut desc = vec![0u8, UFS_DESC_STRING];
+        for unit in self.product_name.encode_utf16() {
+            desc.extend_from_slice(&unit.to_be_bytes());
+        }
+        desc[0] = desc.len() as u8;
+        desc
+    }
+
+    fn handle_query(&mut self, request: &Upiu) -> Upiu {
+        let mut fields = QueryRequest::parse(request);
+        let (response, data) = match fields.opcode {
+            QUERY_OPCODE_READ_DESCRIPTOR => {
+                let desc = match fields.idn {
+                    UFS_DESC_DEVICE => Some(self.device_descriptor()),
+                    UFS_DESC_UNIT if (fields.index as usize) < self.units.len() => Some(self.unit_descriptor(fields.index as usize)),
+                    UFS_DESC_STRING if fields.index == 1 => Some(self.string_descriptor()),
+                    _ => None,
+                };
+                match desc {
+                    Some(mut desc) => {
+                        desc.truncate(fields.length as usize);
+                        fields.length = desc.len() as u16;
+                        (QUERY_RESPONSE_SUCCESS, desc)
+                    }
+                    None if fields.idn == UFS_DESC_UNIT || fields.idn == UFS_DESC_STRING => (QUERY_RESPONSE_INVALID_INDEX, Vec::new()),
+                    None => (QUERY_RESPONSE_INVALID_IDN, Vec::new()),
+                }
+            }
+            QUERY_OPCODE_READ_ATTRIBUTE => match self.attributes.get(fields.idn as usize) {
+                Some(value) => {
+                    fields.value = *value;
+                    (QUERY_RESPONSE_SUCCESS, Vec::new())
+                }
+                None => (QUERY_RESPONSE_INVALID_IDN, Vec::new()),
+            },
+            QUERY_OPCODE_WRITE_ATTRIBUTE => match self.attributes.get_mut(fields.idn as usize) {
+                Some(value) => {
+                    *value = fields.value;
+                    (QUERY_RESPONSE_SUCCESS, Vec::new())
+                }
+                None => (QUERY_RESPONSE_INVALID_IDN, Vec::new()),
+            },
+            QUERY_OPCODE_SET_FLAG if fields.idn == UFS_FLAG_DEVICE_INIT => {
+                self.device_init = true;
+                (QUERY_RESPONSE_SUCCESS, Vec::new())
+            }
+            QUERY_OPCODE_READ_FLAG if fields.idn == UFS_FLAG_DEVICE_INIT => {
+                // Initialization completes after a few polls
+                if self.device_init && self.device_init_polls > 0 {
+                    self.device_init_polls -= 1;
+                } else {
+                    self.device_init = false;
+                }
+                fields.value = self.device_init as u32;
+                (QUERY_RESPONSE_SUCCESS, Vec::new())
+            }
+            QUERY_OPCODE_READ_FLAG | QUERY_OPCODE_SET_FLAG | QUERY_OPCODE_CLEAR_FLAG => (QUERY_RESPONSE_INVALID_IDN, Vec::new()),
+            _ => (QUERY_RESPONSE_INVALID_OPCODE, Vec::new()),
+        };
+        Upiu::query_response(request, response, &fields, data)
+    }
+
+    fn handle_command(&mut self, request: &Upiu, data: UfsData<'_>) -> Upiu {
+        let lun = request.lun() as usize;
+        let fail = |key: SenseKey, asc: u8| {
+            let sense = SenseData { key, asc, ascq: 0, information: None };
+            Upiu::command_response(request, ScsiStatus::CheckCondition, Some(&sense), request.expected_length())
+        };
+        if lun >= self.units.len() {
+            return fail(SenseKey::IllegalRequest, 0x25); // LOGICAL UNIT NOT SUPPORTED
+        }
+        let cdb = request.cdb();
+        if self.pending_unit_attention[lun] && cdb[0] != SCSI_INQUIRY && cdb[0] != SCSI_REQUEST_SENSE {
+            self.pending_unit_attention[lun] = false;
+            return fail(SenseKey::UnitAttention, 0x29); // POWER ON OCCURRED
+        }
+        let (lu, storage) = &mut self.units[lun];
+        let block_size = 1usize << lu.block_size_shift;
+        let (lba, blocks) = match cdb[0] {
+            SCSI_READ_10 | SCSI_WRITE_10 | SCSI_SYNCHRONIZE_CACHE_10 => (
+                u32::from_be_bytes([cdb[2], cdb[3], cdb[4], cdb[5]]) as u64,
+                u16::from_be_bytes([cdb[7], cdb[8]]) as u64,
+            ),
+            SCSI_READ_16 | SCSI_WRITE_16 => {
+                let mut lba = [0u8; 8];
+                lba.copy_from_slice(&cdb[2..10]);
+                (u64::from_be_bytes(lba), u32::from_be_bytes([cdb[10], cdb[11], cdb[12], cdb[13]]) as u64)
+            }
+            _ => (0, 0),
+        };
+        let in_range = lba + blocks <= lu.block_count;
+        let good = |transferred: usize| {
+            Upiu::command_response(request, ScsiStatus::Good, None, request.expected_length() - transferred as u32)
+        };
+        match (cdb[0], data) {
+            (SCSI_TEST_UNIT_READY, UfsData::None) => good(0),
+            (SCSI_INQUIRY, UfsData::In(buf)) => {
+                let mut inquiry = [b' '; SCSI_INQUIRY_SIZE];
+                inquiry[0] = 0x00;
+                inquiry[2] = 0x06;
+                inquiry[8..13].copy_from_slice(b"SADAK");
+                inquiry[16..19].copy_from_slice(b"UFS");
+                inquiry[32..36].copy_from_slice(b"0310");
+                let len = core::cmp::min(buf.len(), SCSI_INQUIRY_SIZE);
+                buf[..len].copy_from_slice(&inquiry[..len]);
+                good(len)
+            }
+            (SCSI_SERVICE_ACTION_IN_16, UfsData::In(buf)) => {
+                let mut raw = [0u8; SCSI_READ_CAPACITY_16_SIZE];
+                raw[0..8].copy_from_slice(&(lu.block_count - 1).to_be_bytes());
+                raw[8..12].copy_from_slice(&(block_size as u32).to_be_bytes());
+                raw[14] = if lu.thin_provisioned { 0xC0 } else { 0 };
+                let len = core::cmp::min(buf.len(), raw.len());
+                buf[..len].copy_from_slice(&raw[..len]);
+                good(len)
+            }
+            (SCSI_READ_10, UfsData::In(buf)) | (SCSI_READ_16, UfsData::In(buf)) => {
+                if !in_range || buf.len() != blocks as usize * block_size {
+                    return fail(SenseKey::IllegalRequest, 0x21); // LBA OUT OF RANGE
+                }
+                let start = lba as usize * block_size;
+                buf.copy_from_slice(&storage[start..start + buf.len()]);
+                good(buf.len())
+            }
+            (SCSI_WRITE_10, UfsData::Out(buf)) | (SCSI_WRITE_16, UfsData::Out(buf)) => {
+                if lu.write_protected {
+                    return fail(SenseKey::DataProtect, 0x27); // WRITE PROTECTED
+                }
+                if !in_range || buf.len() != blocks as usize * block_size {
+                    return fail(SenseKey::IllegalRequest, 0x21);
+                }
+                let start = lba as usize * block_size;
+                storage[start..start + buf.len()].copy_from_slice(buf);
+                good(buf.len())
+            }
+            (SCSI_SYNCHRONIZE_CACHE_10, UfsData::None) => {
+                self.syncs += 1;
+                good(0)
+            }
+            (SCSI_UNMAP, UfsData::Out(list)) if lu.thin_provisioned => match parse_unmap_parameter_list(list) {
+                Some(ranges) if ranges.iter().all(|(lba, n)| lba + *n as u64 <= lu.block_count) => {
+                    for (lba, n) in ranges {
+                        let start = lba as usize * block_size;
+                        storage[start..start + n as usize * block_size].fill(0);
+                        self.unmapped.push((lun as u8, lba, n));
+                    }
+                    good(list.len())
+                }
+                _ => fail(SenseKey::IllegalRequest, 0x26), // INVALID FIELD IN PARAMETER LIST
+            },
+            _ => fail(SenseKey::IllegalRequest, 0x20), // INVALID COMMAND OPERATION CODE
+        }
+    }
 }
-// Helper function to map SahneError to BlockDeviceError (for no_std implementation)
-#[cfg(not(feature = "std"))]
-impl From<crate::SahneError> for BlockDeviceError {
-     fn from(error: crate::SahneError) -> Self {
-         BlockDeviceError::IoError(error) // Assuming BlockDeviceError::IoError wraps SahneError in no_std
-     }
+
+impl UfsHost for UfsSimulator {
+    fn transfer(&mut self, request: &[u8], data: UfsData<'_>) -> Result<Vec<u8>, UfsError> {
+        let request = Upiu::parse(request)?;
+        let mut response = match request.transaction_type() {
+            UPIU_NOP_OUT => Upiu::new(UPIU_NOP_IN, 0, 0, request.task_tag()),
+            UPIU_QUERY_REQUEST => self.handle_query(&request),
+            UPIU_COMMAND => self.handle_command(&request, data),
+            _ => Upiu::new(UPIU_REJECT, 0, request.lun(), request.task_tag()),
+        };
+        response.header[3] = response.header[3].wrapping_add(self.tag_offset);
+        Ok(response.to_bytes())
+    }
 }
 
 
 #[cfg(test)]
-#[cfg(feature = "std")] // Use std for easier file/mock block device tests
+#[cfg(feature = "std")] // Standart kütüphane özelliği aktifse çalışır
 mod tests {
-    use super::*;
-    use alloc::vec::Vec; // For Vec
-    use std::io::{Read, Write, Seek, Cursor}; // For File/Cursor traits
-    use std::fs::{remove_file, OpenOptions}; // For creating/managing test files
-    use std::path::Path;
-    use alloc::string::ToString; // For to_string()
-    use core::io::{ReadExt, WriteExt, SeekFrom}; // For read_exact, write_all, SeekFrom
-
-
-    // Mock BlockDevice for testing Superblock persistence
-    struct MockBlockDevice {
-        cursor: Cursor<Vec<u8>>, // In-memory buffer
-        block_size: usize,
-         // Add fields to simulate errors if needed
-         simulate_io_error_on_read: bool,
-         simulate_io_error_on_write: bool,
-         simulate_invalid_data_on_read: bool,
-         simulate_invalid_size_on_write: bool,
-         simulate_block_size: usize, // Simulate a different block size than actual buffer if needed for tests
-    }
-
-    impl MockBlockDevice {
-        fn new(total_blocks: u64, block_size: usize) -> Self {
-             let total_size = total_blocks as usize * block_size;
-             let initial_data = vec![0u8; total_size]; // Requires alloc
-             MockBlockDevice {
-                 cursor: Cursor::new(initial_data),
-                 block_size,
-                 simulate_io_error_on_read: false,
-                 simulate_io_error_on_write: false,
-                 simulate_invalid_data_on_read: false,
-                 simulate_invalid_size_on_write: false,
-                 simulate_block_size: block_size, // Default to actual size
-             }
-        }
-
-        // Helper to manually get the underlying data (for inspection)
-        fn get_data(&self) -> &[u8] {
-            self.cursor.get_ref().as_slice()
-        }
-
-        // Helper to simulate errors
-        fn set_simulate_io_error_on_read(&mut self, value: bool) { self.simulate_io_error_on_read = value; }
-        fn set_simulate_io_error_on_write(&mut self, value: bool) { self.simulate_io_error_on_write = value; }
-        fn set_simulate_invalid_data_on_read(&mut self, value: bool) { self.simulate_invalid_data_on_read = value; }
-        fn set_simulate_invalid_size_on_write(&mut self, value: bool) { self.simulate_invalid_size_on_write = value; }
-        fn set_simulate_block_size(&mut self, size: usize) { self.simulate_block_size = size; } // For testing SB size mismatch
-
-
-    }
-
-    impl BlockDevice for MockBlockDevice {
-        fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> Result<(), BlockDeviceError> {
-             if self.simulate_io_error_on_read {
-                 return Err(BlockDeviceError::IoError(std::io::Error::new(std::io::ErrorKind::Other, "Simulated read error"))); // Use std error in std test
-             }
-             if self.simulate_invalid_data_on_read {
-                 // Simulate reading invalid data that would fail SB validation later
-                 if buf.len() >= 4 { buf[0..4].copy_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF]); } // Invalid magic
-             }
-              if buf.len() != self.block_size {
-                   return Err(BlockDeviceError::BlockSizeError(format!("Mock buffer size ({}) != block size ({})", buf.len(), self.block_size)));
-              }
-
-
-            let offset = block_id * self.block_size as u64;
-            self.cursor.seek(SeekFrom::Start(offset))
-                .map_err(|e| BlockDeviceError::IoError(e))?;
-            self.cursor.read_exact(buf)
-                .map_err(|e| BlockDeviceError::IoError(e))?;
-            Ok(())
-        }
-
-        fn write_block(&mut self, block_id: u64, buf: &[u8]) -> Result<(), BlockDeviceError> {
-             if self.simulate_io_error_on_write {
-                 return Err(BlockDeviceError::IoError(std::io::Error::new(std::io::ErrorKind::Other, "Simulated write error"))); // Use std error in std test
-             }
-             if self.simulate_invalid_size_on_write {
-                 // Simulate a block size mismatch error during write
-                 if buf.len() != self.block_size {
-                      return Err(BlockDeviceError::BlockSizeError(format!("Mock write size ({}) != block size ({})", buf.len(), self.block_size)));
-                 }
-             }
-              if buf.len() != self.block_size {
-                   return Err(BlockDeviceError::BlockSizeError(format!("Mock buffer size ({}) != block size ({})", buf.len(), self.block_size)));
-              }
-
-            let offset = block_id * self.block_size as u64;
-            self.cursor.seek(SeekFrom::Start(offset))
-                .map_err(|e| BlockDeviceError::IoError(e))?;
-            self.cursor.write_all(buf)
-                .map_err(|e| BlockDeviceError::IoError(e))?;
-            Ok(())
-        }
-
-        fn block_size(&self) -> usize {
-            self.simulate_block_size // Return simulated block size for testing SB size mismatch
-        }
-
-         // Add block_count if BlockDevice trait includes it
-         fn block_count(&self) -> u64 {
-              (self.cursor.get_ref().len() / self.block_size) as u64
-         }
-         // Add size() if BlockDevice trait includes it
-          fn size(&self) -> u64 {
-               self.cursor.get_ref().len() as u64
-          }
-    }
-
-
-    #[test]
-    fn test_superblock_persistence() -> Result<(), FileSystemError> { // Return FileSystemError
-        let block_size: u32 = 512;
-        let block_size_usize = block_size as usize;
-        let blocks_count: u64 = 1000;
-        let inode_size: u32 = 128;
-        let inodes_count: u64 = 500;
-        let device_type = DeviceType::SSD;
-        let device_id: u64 = 12345;
-        let block_bitmap_start: u64 = 1; // Example location
-        let inode_table_start: u64 = 10; // Example location
-        let data_blocks_start: u64 = 50; // Example location
-
-
-        // Create a new Superblock in memory
-        let original_sb = Superblock::new(
-            block_size,
-            inode_size,
-            blocks_count,
-            inodes_count,
-            device_type,
-            device_id,
-             block_bitmap_start,
-             inode_table_start,
-             data_blocks_start,
-        );
-
-        // Superblock should be initially valid
-         assert!(original_sb.is_valid());
-         assert_eq!(original_sb.magic, SUPERBLOCK_MAGIC);
-         assert_eq!(original_sb.free_blocks_count, blocks_count);
-         assert_eq!(original_sb.free_inodes_count, inodes_count);
-
-
-        // Create a mock block device (in-memory)
-        // Device needs to be large enough to hold the Superblock (at least 1 block)
-        let mut mock_device = MockBlockDevice::new(10, block_size_usize); // 10 blocks of device block size
-
-
-        // Save the Superblock to the mock device
-        original_sb.save_to_device(&mut mock_device).map_err(|e| map_block_device_error_to_fs_error(e))?; // Use map_block_device_error_to_fs_error
-
-
-        // Verify the raw data in the mock device at block 0 (Superblock location)
-         let mut read_buffer = alloc::vec![0u8; block_size_usize]; // Buffer to read the block
-         mock_device.read_block(SUPERBLOCK_BLOCK_ID, &mut read_buffer).map_err(|e| map_block_device_error_to_fs_error(e))?; // Read the block
-
-         // Compare the start of the read buffer with the expected Superblock bytes
-         let sb_size = Superblock::size();
-         let original_sb_bytes = unsafe {
-              let ptr = &original_sb as *const Superblock as *const u8;
-              core::slice::from_raw_parts(ptr, sb_size)
-         };
-         assert_eq!(&read_buffer[..sb_size], original_sb_bytes); // Check if the bytes match
-
-
-        // Create a new mock device (or reset the old one) to simulate loading from disk
-        let mut mock_device_load = MockBlockDevice::new(10, block_size_usize); // Fresh device
-
-
-        // Write the original Superblock data to the new mock device's block 0
-        // This simulates the state of the device after a previous save
-         let sb_bytes_to_write = unsafe {
-              let ptr = &original_sb as *const Superblock as *const u8;
-              core::slice::from_raw_parts(ptr, sb_size)
-         };
-          let mut write_buffer = alloc::vec![0u8; block_size_usize];
-          write_buffer[..sb_size].copy_from_slice(sb_bytes_to_write);
-         mock_device_load.write_block(SUPERBLOCK_BLOCK_ID, &write_buffer).map_err(|e| map_block_device_error_to_fs_error(e))?; // Write the SB data
-
-
-        // Load the Superblock from the mock device
-        let loaded_sb = Superblock::load_from_device(&mut mock_device_load).map_err(|e| map_block_device_error_to_fs_error(e))?; // Use load_from_device
-
-
-        // Verify the loaded Superblock matches the original
-        assert_eq!(loaded_sb, original_sb);
-
-
-         // Test loading from a device with invalid magic number
-          let mut mock_device_invalid = MockBlockDevice::new(10, block_size_usize);
-          mock_device_invalid.set_simulate_invalid_data_on_read(true); // Simulate invalid data
-          let result_load_invalid = Superblock::load_from_device(&mut mock_device_invalid);
-          assert!(result_load_invalid.is_err());
-           match result_load_invalid.unwrap_err() {
-               FileSystemError::InvalidData(msg) => {
-                   assert!(msg.contains("Invalid Superblock magic number"));
-               },
-               _ => panic!("Beklenenden farklı hata türü: {:?}", result_load_invalid.unwrap_err()),
-           }
-
-          // Test loading from a device with insufficient block size
-          let mut mock_device_small_block = MockBlockDevice::new(10, Superblock::size() / 2); // Device block size smaller than SB size
-          let result_load_small_block = Superblock::load_from_device(&mut mock_device_small_block);
-           assert!(result_load_small_block.is_err());
-           match result_load_small_block.unwrap_err() {
-               FileSystemError::InvalidData(msg) => {
-                   assert!(msg.contains("Device block size") && msg.contains("is smaller than Superblock size"));
-               },
-               _ => panic!("Beklenenden farklı hata türü: {:?}", result_load_small_block.unwrap_err()),
-           }
-
-
-          // Test save to a device with insufficient block size
-          let mut mock_device_save_small_block = MockBlockDevice::new(10, Superblock::size() / 2); // Device block size smaller than SB size
-          let result_save_small_block = original_sb.save_to_device(&mut mock_device_save_small_block);
-           assert!(result_save_small_block.is_err());
-           match result_save_small_block.unwrap_err() {
-               FileSystemError::InvalidData(msg) => {
-                   assert!(msg.contains("Device block size") && msg.contains("is smaller than Superblock size. Cannot save."));
-               },
-               _ => panic!("Beklenenden farklı hata türü: {:?}", result_save_small_block.unwrap_err()),
-           }
-
-
-          // Test load/save with simulated IO errors
-           let mut mock_device_io_error = MockBlockDevice::new(10, block_size_usize);
-           mock_device_io_error.set_simulate_io_error_on_read(true);
-           let result_load_io_error = Superblock::load_from_device(&mut mock_device_io_error);
-           assert!(result_load_io_error.is_err());
-            match result_load_io_error.unwrap_err() {
-               FileSystemError::IOError(_) => { /* Expected IO error */ },
-               _ => panic!("Beklenenden farklı hata türü: {:?}", result_load_io_error.unwrap_err()),
-            }
+    use super::*;
+
+    fn two_lu_device() -> SharedUfsDevice<UfsSimulator> {
+        let simulator = UfsSimulator::new(&[
+            SimulatedLu { block_size_shift: 12, block_count: 256, write_protected: false, thin_provisioned: true },
+            SimulatedLu { block_size_shift: 9, block_count: 64, write_protected: true, thin_provisioned: false },
+        ]);
+        UfsDevice::new(simulator).unwrap().into_shared()
+    }
 
-            let mut mock_device_io_error_save = MockBlockDevice::new(10, block_size_usize);
-            mock_device_io_error_save.set_simulate_io_error_on_write(true);
-            let result_save_io_error = original_sb.save_to_device(&mut mock_device_io_error_save);
-            assert!(result_save_io_error.is_err());
-             match result_save_io_error.unwrap_err() {
-                FileSystemError::IOError(_) => { /* Expected IO error */ },
-                _ => panic!("Beklenenden farklı hata türü: {:?}", result_save_io_error.unwrap_err()),
-             }
+    #[test]
+    fn test_upiu_framing_and_descriptors() {
+        let cdb = Cdb::read(0x10, 2);
+        let upiu = Upiu::command(3, 7, &cdb, UPIU_FLAG_READ, 8192);
+        let parsed = Upiu::parse(&upiu.to_bytes()).unwrap();
+        assert_eq!((parsed.transaction_type(), parsed.lun(), parsed.task_tag()), (UPIU_COMMAND, 3, 7));
+        assert_eq!(parsed.expected_length(), 8192);
+        assert_eq!(&parsed.cdb()[..10], cdb.as_bytes());
+
+        let sense = SenseData { key: SenseKey::MediumError, asc: 0x11, ascq: 0, information: Some(4) };
+        let response = Upiu::command_response(&parsed, ScsiStatus::CheckCondition, Some(&sense), 0);
+        assert_eq!(Upiu::parse(&response.to_bytes()).unwrap().sense(), Some(sense));
+        assert!(Upiu::parse(&response.to_bytes()[..40]).is_err()); // Truncated data segment
+
+        let device = two_lu_device();
+        let mut dev = device.lock();
+        assert_eq!(dev.device_descriptor().number_lu, 2);
+        assert_eq!(dev.device_descriptor().spec_version, 0x0310);
+        assert_eq!(dev.product_name().unwrap(), "SADAK UFS");
+        let units = dev.units().to_vec();
+        assert_eq!((units[0].logical_block_size, units[0].logical_block_count), (4096, 256));
+        assert!(units[0].thin_provisioned() && !units[1].thin_provisioned());
+        assert_eq!(units[1].write_protect, 2);
+        assert!(!dev.read_flag(UFS_FLAG_DEVICE_INIT).unwrap()); // Cleared by initialization
+
+        dev.write_attribute(UFS_ATTR_BOOT_LUN_EN, 0, 0, 1).unwrap();
+        assert_eq!(dev.read_attribute(UFS_ATTR_BOOT_LUN_EN, 0, 0).unwrap(), 1);
+        match dev.read_descriptor(UFS_DESC_UNIT, 9, 0) {
+            Err(UfsError::QueryFailed { response: QUERY_RESPONSE_INVALID_INDEX, .. }) => {}
+            other => panic!("Beklenenden farklı sonuç: {:?}", other),
+        }
+    }
 
+    #[test]
+    fn test_logical_units_as_block_devices() -> Result<(), BlockDeviceError> {
+        let device = two_lu_device();
+        let mut units = UfsLogicalUnit::open_all(&device).map_err(map_ufs_error_to_block_device_error)?;
+        assert_eq!(units.len(), 2);
+        let (lu0, rest) = units.split_at_mut(1);
+        let (lu0, lu1) = (&mut lu0[0], &mut rest[0]);
+        assert_eq!((lu0.block_size(), lu0.block_count()), (4096, 256));
+        assert_eq!((lu1.block_size(), lu1.block_count()), (512, 64));
+        assert_eq!(lu0.inquiry().unwrap().product, "UFS");
+
+        let data: Vec<u8> = (0..4096).map(|i| (i % 253) as u8).collect();
+        for lba in 0..16 {
+            lu0.write_block(lba, &data)?;
+        }
+        let mut buf = vec![0u8; 4096];
+        lu0.read_block(15, &mut buf)?;
+        assert_eq!(buf, data);
+
+        // Logical units are independent address spaces
+        let mut small = vec![0u8; 512];
+        lu1.read_block(15, &mut small)?;
+        assert_eq!(small, vec![0u8; 512]);
+
+        lu0.discard_blocks(2, 3)?;
+        lu0.read_block(3, &mut buf)?;
+        assert_eq!(buf, vec![0u8; 4096]);
+        lu0.flush()?;
+        lu1.discard_blocks(0, 1)?; // Not thin provisioned: no UNMAP sent
+        {
+            let mut dev = device.lock();
+            assert_eq!(dev.host_mut().unmapped(), &[(0, 2, 3)]);
+            assert_eq!(dev.host_mut().sync_count(), 1);
+        }
 
-        Ok(()) // Return Ok from test function
-    }
+        // Write protected unit and device-side range check
+        match lu1.write_block(0, &[1u8; 512]) {
+            Err(BlockDeviceError::PermissionDenied(_)) => {}
+            other => panic!("Beklenenden farklı sonuç: {:?}", other),
+        }
+        match device.lock().scsi_command(0, &Cdb::read(256, 1), UfsData::In(&mut buf)) {
+            Err(UfsError::CheckCondition(sense)) => assert_eq!((sense.key, sense.asc), (SenseKey::IllegalRequest, 0x21)),
+            other => panic!("Beklenenden farklı sonuç: {:?}", other),
+        }
+        assert!(matches!(lu0.read_block(256, &mut buf), Err(BlockDeviceError::InvalidParameter(_))));
+        assert!(matches!(lu0.read_blocks(0, &mut vec![0u8; 4096 + 512]), Err(UfsError::Misaligned { len: 4608, block_size: 4096 })));
+        assert!(matches!(lu1.read_block(0, &mut [0u8; 100]), Err(BlockDeviceError::BlockSizeError(_))));
+        Ok(())
+    }
 
+    #[test]
+    fn test_ufs_protocol_errors() {
+        let device = two_lu_device();
+        assert!(matches!(UfsLogicalUnit::open(device.clone(), 5), Err(UfsError::LogicalUnitNotAvailable(5))));
 
-    // TODO: Add tests for Superblock::blocks_needed() if the logic becomes more complex (e.g., padding).
+        device.lock().host_mut().corrupt_task_tags(1);
+        assert!(matches!(device.lock().nop(), Err(UfsError::TaskTagMismatch { .. })));
+        device.lock().host_mut().corrupt_task_tags(0);
+        assert!(device.lock().nop().is_ok());
 
+        // Unknown opcode through the SCSI layer maps to NotSupported
+        let err = device.lock().scsi_command(0, &Cdb::request_sense(18), UfsData::None).unwrap_err();
+        assert!(matches!(map_ufs_error_to_block_device_error(err), BlockDeviceError::NotSupported(_)));
+    }
 }
 
+
 // Removed redundant print module and panic handler boilerplate.
 // The empty lib module configuration is kept.
 #[cfg(not(any(feature = "std", test)))] // Only when not building std or test
@@ -10,6 +10,11 @@ extern crate alloc;
 // Gerekli Sahne64 modülleri ve yapılarını içeri aktar (assume these are defined elsewhere)
 // Removed redundant imports like fs, memory, process, sync, kernel, arch
 use crate::{error::SahneError, FileSystemError}; // Assuming SahneError and FileSystemError are in crate::error or crate
+use crate::blockdevice::{BlockDevice, BlockDeviceError}; // Assuming these are in crate::blockdevice
+use crate::scsi::{
+    sense_to_block_device_error, Cdb, InquiryData, ReadCapacity, SenseData, SenseKey,
+    SCSI_INQUIRY_SIZE, SCSI_READ_CAPACITY_10_SIZE, SCSI_READ_CAPACITY_16_SIZE, SCSI_SENSE_BUFFER_SIZE,
+}; // Shared SCSI command set helpers
 
 
 // Core library imports
@@ -83,6 +88,22 @@ mod usb {
         timeout_ms: u32, // API might use u64 or Duration
     ) -> Result<usize, SahneError> { unimplemented!() } // Placeholder
 
+    /// Performs a control transfer with an OUT (or no) data stage on the default pipe.
+    /// Returns the number of data bytes sent.
+    pub fn control_out(
+        device_handle: u32,
+        request_type: u8,
+        request: u8,
+        value: u16,
+        index: u16,
+        buffer_ptr: *const u8,
+        buffer_len: u32,
+        timeout_ms: u32,
+    ) -> Result<usize, SahneError> { Err(SahneError::NotSupported) } // Not yet provided by the kernel USB API
+
+    /// Clears a halt (STALL) condition on the given endpoint (CLEAR_FEATURE(ENDPOINT_HALT)).
+    pub fn clear_halt(device_handle: u32, endpoint: u8) -> Result<(), SahneError> { Err(SahneError::NotSupported) } // Not yet provided by the kernel USB API
+
     /// Closes the USB device handle.
     /// Returns Result<(), SahneError> on success or error.
     pub fn close_device(device_handle: u32) -> Result<(), SahneError> { unimplemented!() } // Placeholder
@@ -102,6 +123,11 @@ pub enum UsbError {
     TransferError(String), // Generic transfer error with description (Requires alloc)
     InvalidEndpoint, // Attempted R/W on invalid endpoint type (IN/OUT)
     ClosedHandle, // Attempted operation on a closed or invalid handle
+    Stall, // Endpoint halted (STALL handshake)
+    TagMismatch { expected: u32, received: u32 }, // CSW tag does not match the CBW tag
+    InvalidCsw(String), // CSW with wrong size, signature or status value
+    PhaseError, // CSW status 2: the device requires reset recovery
+    CommandFailed(Option<SenseData>), // CSW status 1 with the sense data from REQUEST SENSE
     // Add other specific USB errors as needed (e.g., PermissionDenied, BusError)
 }
 
@@ -117,6 +143,12 @@ impl fmt::Display for UsbError {
             UsbError::TransferError(msg) => write!(f, "USB Transfer Error: {}", msg),
             UsbError::InvalidEndpoint => write!(f, "Invalid USB Endpoint"),
             UsbError::ClosedHandle => write!(f, "Operation on Closed USB Handle"),
+            UsbError::Stall => write!(f, "USB Endpoint Stalled"),
+            UsbError::TagMismatch { expected, received } => write!(f, "CSW tag mismatch (expected {:#x}, received {:#x})", expected, received),
+            UsbError::InvalidCsw(msg) => write!(f, "Invalid CSW: {}", msg),
+            UsbError::PhaseError => write!(f, "Bulk-Only Transport phase error"),
+            UsbError::CommandFailed(Some(sense)) => write!(f, "SCSI command failed: {}", sense),
+            UsbError::CommandFailed(None) => write!(f, "SCSI command failed (no sense data)"),
         }
     }
 }
@@ -155,6 +187,9 @@ fn map_usb_error_to_fs_error(e: UsbError) -> FileSystemError {
         UsbError::InvalidEndpoint => FileSystemError::InvalidParameter(String::from("Invalid USB Endpoint")), // Requires alloc
         UsbError::ClosedHandle => FileSystemError::DeviceError(String::from("Operation on Closed USB Handle")), // Requires alloc
         UsbError::TransferError(msg) => FileSystemError::IOError(format!("USB Transfer Error: {}", msg)), // Requires alloc
+        UsbError::Stall | UsbError::TagMismatch { .. } | UsbError::InvalidCsw(_) | UsbError::PhaseError | UsbError::CommandFailed(_) => {
+            FileSystemError::IOError(format!("USB Mass Storage Error: {}", e)) // Requires alloc
+        }
         #[cfg(feature = "std")]
         UsbError::RusbError(rusb_err) => {
             // Map specific rusb errors to FileSystemError variants where possible
@@ -336,12 +371,481 @@ impl UsbDevice {
         }
     }
 
-    // Add other USB transfer types (control, interrupt, isochronous) as needed.
+    /// Performs a control transfer with an OUT (or no) data stage, e.g. a class-specific request.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the number of data bytes sent, or a UsbError.
+    pub fn control_out(&mut self, request_type: u8, request: u8, value: u16, index: u16, data: &[u8]) -> Result<usize, UsbError> {
+        let timeout_duration = Duration::from_millis(TIMEOUT_MS);
+
+        #[cfg(feature = "std")]
+        {
+            self.handle.write_control(request_type, request, value, index, data, timeout_duration).map_err(UsbError::RusbError)
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            let control_result = unsafe {
+                crate::usb::control_out(
+                    self.handle.0,
+                    request_type,
+                    request,
+                    value,
+                    index,
+                    data.as_ptr(),
+                    data.len() as u32,
+                    TIMEOUT_MS as u32,
+                )
+            };
+            control_result.map_err(UsbError::SahneError)
+        }
+    }
+
+    /// Clears a halt (STALL) condition on an endpoint.
+    pub fn clear_halt(&mut self, endpoint: u8) -> Result<(), UsbError> {
+        #[cfg(feature = "std")]
+        {
+            self.handle.clear_halt(endpoint).map_err(UsbError::RusbError)
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            crate::usb::clear_halt(self.handle.0, endpoint).map_err(UsbError::SahneError)
+        }
+    }
+
+    // Add other USB transfer types (interrupt, isochronous) as needed.
     // Add methods for getting device descriptors, configuration descriptors, etc.
     // Add methods for claiming interfaces, setting altsettings.
+}
+
+
+// USB Mass Storage Class, Bulk-Only Transport (USB MSC BOT 1.0) with the SCSI transparent command set.
+//
+// Every command is a three stage exchange on the bulk endpoints:
+//   1. Command Block Wrapper (CBW, 31 bytes) on bulk OUT, carrying a tag and the SCSI CDB.
+//   2. Optional data stage (bulk IN or OUT).
+//   3. Command Status Wrapper (CSW, 13 bytes) on bulk IN, echoing the tag.
+// A STALL in the data stage is cleared and the CSW is still read. An invalid CSW, a tag mismatch
+// or a phase error triggers reset recovery (Bulk-Only Mass Storage Reset + clear both halts).
+// A failed command (CSW status 1) is followed by REQUEST SENSE to learn why.
+
+const MSC_CBW_SIGNATURE: u32 = 0x4342_5355; // "USBC" (little-endian)
+const MSC_CSW_SIGNATURE: u32 = 0x5342_5355; // "USBS" (little-endian)
+const MSC_CBW_SIZE: usize = 31;
+const MSC_CSW_SIZE: usize = 13;
+const MSC_CBW_FLAG_DATA_IN: u8 = 0x80;
+const MSC_CSW_STATUS_PASSED: u8 = 0x00;
+const MSC_CSW_STATUS_FAILED: u8 = 0x01;
+const MSC_CSW_STATUS_PHASE_ERROR: u8 = 0x02;
+const MSC_REQUEST_TYPE_CLASS_INTERFACE: u8 = 0x21; // Host-to-device, class, interface
+const MSC_REQUEST_BULK_ONLY_RESET: u8 = 0xFF;
+const MSC_MAX_TRANSFER_BYTES: usize = 64 * 1024; // Per READ/WRITE command
+const MSC_UNIT_READY_RETRIES: usize = 5;
+
+/// Command Block Wrapper.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CommandBlockWrapper {
+    tag: u32,
+    data_transfer_length: u32,
+    flags: u8, // Bit 7: direction (1 = device-to-host)
+    lun: u8,
+    cdb: Cdb,
+}
+
+impl CommandBlockWrapper {
+    fn to_bytes(&self) -> [u8; MSC_CBW_SIZE] {
+        let mut out = [0u8; MSC_CBW_SIZE];
+        let cdb = self.cdb.as_bytes();
+        out[0..4].copy_from_slice(&MSC_CBW_SIGNATURE.to_le_bytes());
+        out[4..8].copy_from_slice(&self.tag.to_le_bytes());
+        out[8..12].copy_from_slice(&self.data_transfer_length.to_le_bytes());
+        out[12] = self.flags;
+        out[13] = self.lun & 0x0F;
+        out[14] = cdb.len() as u8;
+        out[15..15 + cdb.len()].copy_from_slice(cdb);
+        out
+    }
+}
+
+/// Command Status Wrapper.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CommandStatusWrapper {
+    tag: u32,
+    data_residue: u32,
+    status: u8,
+}
+
+impl CommandStatusWrapper {
+    // Returns None if the CSW is not "valid" (size or signature wrong, BOT 6.3.1).
+    fn parse(raw: &[u8]) -> Option<Self> {
+        if raw.len() != MSC_CSW_SIZE || u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) != MSC_CSW_SIGNATURE {
+            return None;
+        }
+        Some(CommandStatusWrapper {
+            tag: u32::from_le_bytes([raw[4], raw[5], raw[6], raw[7]]),
+            data_residue: u32::from_le_bytes([raw[8], raw[9], raw[10], raw[11]]),
+            status: raw[12],
+        })
+    }
+}
+
+/// The bulk pipe pair and class requests a Bulk-Only Transport needs.
+/// Implemented by `UsbMscInterface` on real hardware and by fake endpoints in tests.
+pub trait BulkOnlyTransport {
+    /// Bulk OUT transfer. Returns the number of bytes sent.
+    fn bulk_out(&mut self, data: &[u8]) -> Result<usize, UsbError>;
+    /// Bulk IN transfer. Returns the number of bytes received (a short packet ends the transfer).
+    /// A halted endpoint reports `UsbError::Stall`.
+    fn bulk_in(&mut self, buf: &mut [u8]) -> Result<usize, UsbError>;
+    /// Issues the class-specific Bulk-Only Mass Storage Reset request.
+    fn bulk_only_reset(&mut self) -> Result<(), UsbError>;
+    /// Clears a halt on the bulk IN endpoint.
+    fn clear_halt_in(&mut self) -> Result<(), UsbError>;
+    /// Clears a halt on the bulk OUT endpoint.
+    fn clear_halt_out(&mut self) -> Result<(), UsbError>;
+}
+
+/// A mass storage interface of an opened `UsbDevice`.
+pub struct UsbMscInterface {
+    device: UsbDevice,
+    interface: u8,
+    bulk_in: u8, // IN endpoint address (>= 0x80)
+    bulk_out: u8, // OUT endpoint address (< 0x80)
+}
+
+impl UsbMscInterface {
+    /// Wraps an opened device using the given interface number and bulk endpoint addresses
+    /// (taken from the interface descriptor).
+    pub fn new(device: UsbDevice, interface: u8, bulk_in: u8, bulk_out: u8) -> Self {
+        UsbMscInterface { device, interface, bulk_in, bulk_out }
+    }
+}
+
+// A halted endpoint shows up as a pipe error in rusb.
+fn normalize_stall(e: UsbError) -> UsbError {
+    match e {
+        #[cfg(feature = "std")]
+        UsbError::RusbError(RusbError::Pipe) => UsbError::Stall,
+        other => other,
+    }
+}
+
+impl BulkOnlyTransport for UsbMscInterface {
+    fn bulk_out(&mut self, data: &[u8]) -> Result<usize, UsbError> {
+        self.device.write_bulk(self.bulk_out, data).map_err(normalize_stall)
+    }
+
+    fn bulk_in(&mut self, buf: &mut [u8]) -> Result<usize, UsbError> {
+        self.device.read_bulk(self.bulk_in, buf).map_err(normalize_stall)
+    }
+
+    fn bulk_only_reset(&mut self) -> Result<(), UsbError> {
+        self.device
+            .control_out(MSC_REQUEST_TYPE_CLASS_INTERFACE, MSC_REQUEST_BULK_ONLY_RESET, 0, self.interface as u16, &[])
+            .map(|_| ())
+    }
+
+    fn clear_halt_in(&mut self) -> Result<(), UsbError> {
+        self.device.clear_halt(self.bulk_in)
+    }
+
+    fn clear_halt_out(&mut self) -> Result<(), UsbError> {
+        self.device.clear_halt(self.bulk_out)
+    }
+}
+
+// Data stage of a command.
+enum DataStage<'a> {
+    None,
+    In(&'a mut [u8]),
+    Out(&'a [u8]),
+}
+
+/// A SCSI direct-access logical unit behind a Bulk-Only Transport, exposed as a block device.
+pub struct UsbMassStorageDevice<T: BulkOnlyTransport> {
+    transport: T,
+    lun: u8,
+    next_tag: u32,
+    inquiry: InquiryData,
+    capacity: ReadCapacity,
+}
+
+impl<T: BulkOnlyTransport> UsbMassStorageDevice<T> {
+    /// Brings up logical unit `lun`: INQUIRY, TEST UNIT READY (retrying through UNIT ATTENTION
+    /// and "becoming ready") and READ CAPACITY (16 when the 10 byte variant cannot report the size).
+    ///
+    /// # Returns
+    ///
+    /// The ready device, or a UsbError (CommandFailed carries the sense data).
+    pub fn new(transport: T, lun: u8) -> Result<Self, UsbError> {
+        let mut device = UsbMassStorageDevice {
+            transport,
+            lun,
+            next_tag: 1,
+            inquiry: InquiryData {
+                peripheral_device_type: 0,
+                removable: false,
+                vendor: String::new(),
+                product: String::new(),
+                revision: String::new(),
+            },
+            capacity: ReadCapacity {
+                block_count: 0,
+                block_size: 0,
+                protection_enabled: false,
+                protection_type: 0,
+                thin_provisioned: false,
+                unmapped_reads_zero: false,
+            },
+        };
+
+        // INQUIRY
+        let mut raw = [0u8; SCSI_INQUIRY_SIZE];
+        let received = device.command(&Cdb::inquiry(SCSI_INQUIRY_SIZE as u8), DataStage::In(&mut raw))?;
+        device.inquiry = InquiryData::parse(&raw[..received])
+            .ok_or_else(|| UsbError::TransferError(format!("Short INQUIRY response: {} bytes", received)))?;
+        if device.inquiry.peripheral_device_type != 0x00 {
+            return Err(UsbError::TransferError(format!(
+                "LUN {} is not a direct access block device (type {:#x})",
+                lun, device.inquiry.peripheral_device_type
+            )));
+        }
+
+        device.wait_until_ready()?;
+        device.capacity = device.read_capacity()?;
+        if device.capacity.block_size == 0 || device.capacity.block_size as usize > MSC_MAX_TRANSFER_BYTES {
+            return Err(UsbError::TransferError(format!("Unsupported block size {}", device.capacity.block_size)));
+        }
+        Ok(device)
+    }
+
+    /// Returns the INQUIRY data of the logical unit.
+    pub fn inquiry(&self) -> &InquiryData {
+        &self.inquiry
+    }
+
+    /// Returns the capacity reported by READ CAPACITY.
+    pub fn capacity(&self) -> &ReadCapacity {
+        &self.capacity
+    }
+
+    /// Gives access to the transport (e.g. for inspection in tests).
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Issues TEST UNIT READY.
+    pub fn test_unit_ready(&mut self) -> Result<(), UsbError> {
+        self.command(&Cdb::test_unit_ready(), DataStage::None).map(|_| ())
+    }
+
+    /// Issues REQUEST SENSE and decodes the returned sense data.
+    pub fn request_sense(&mut self) -> Result<Option<SenseData>, UsbError> {
+        let mut raw = [0u8; SCSI_SENSE_BUFFER_SIZE];
+        let received = self.transport_command(&Cdb::request_sense(SCSI_SENSE_BUFFER_SIZE as u8), DataStage::In(&mut raw))?;
+        Ok(SenseData::parse(&raw[..received]))
+    }
+
+    /// Performs reset recovery: Bulk-Only Mass Storage Reset, then clears both endpoint halts.
+    pub fn reset_recovery(&mut self) -> Result<(), UsbError> {
+        self.transport.bulk_only_reset()?;
+        self.transport.clear_halt_in()?;
+        self.transport.clear_halt_out()
+    }
+
+    fn wait_until_ready(&mut self) -> Result<(), UsbError> {
+        let mut last_error = UsbError::Timeout;
+        for _ in 0..MSC_UNIT_READY_RETRIES {
+            match self.test_unit_ready() {
+                Ok(()) => return Ok(()),
+                Err(UsbError::CommandFailed(Some(sense)))
+                    if sense.is_unit_attention() || (sense.key == SenseKey::NotReady && sense.asc == 0x04) =>
+                {
+                    last_error = UsbError::CommandFailed(Some(sense)); // Power-on/reset or becoming ready: retry
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error)
+    }
+
+    fn read_capacity(&mut self) -> Result<ReadCapacity, UsbError> {
+        let mut raw = [0u8; SCSI_READ_CAPACITY_10_SIZE];
+        let received = self.command(&Cdb::read_capacity_10(), DataStage::In(&mut raw))?;
+        let capacity = ReadCapacity::parse_10(&raw[..received])
+            .ok_or_else(|| UsbError::TransferError(format!("Short READ CAPACITY(10) response: {} bytes", received)))?;
+        if !capacity.needs_16() {
+            return Ok(capacity);
+        }
+        let mut raw = [0u8; SCSI_READ_CAPACITY_16_SIZE];
+        let received = self.command(&Cdb::read_capacity_16(SCSI_READ_CAPACITY_16_SIZE as u32), DataStage::In(&mut raw))?;
+        ReadCapacity::parse_16(&raw[..received])
+            .ok_or_else(|| UsbError::TransferError(format!("Short READ CAPACITY(16) response: {} bytes", received)))
+    }
+
+    // Runs a command; on CHECK CONDITION fetches the sense data with REQUEST SENSE.
+    fn command(&mut self, cdb: &Cdb, data: DataStage) -> Result<usize, UsbError> {
+        match self.transport_command(cdb, data) {
+            Err(UsbError::CommandFailed(_)) => {
+                let sense = self.request_sense()?;
+                Err(UsbError::CommandFailed(sense))
+            }
+            other => other,
+        }
+    }
+
+    // Reset recovery after a transport failure; the original error is what the caller needs,
+    // so a failing reset (device gone) does not replace it.
+    fn recover(&mut self, error: UsbError) -> UsbError {
+        let _ = self.reset_recovery();
+        error
+    }
+
+    // CBW -> data -> CSW. Returns the number of data bytes moved.
+    fn transport_command(&mut self, cdb: &Cdb, data: DataStage) -> Result<usize, UsbError> {
+        let tag = self.next_tag;
+        self.next_tag = self.next_tag.wrapping_add(1);
+        let (data_transfer_length, flags) = match &data {
+            DataStage::None => (0, 0),
+            DataStage::In(buf) => (buf.len() as u32, MSC_CBW_FLAG_DATA_IN),
+            DataStage::Out(buf) => (buf.len() as u32, 0),
+        };
+        let cbw = CommandBlockWrapper { tag, data_transfer_length, flags, lun: self.lun, cdb: *cdb };
+
+        // 1. Command
+        match self.transport.bulk_out(&cbw.to_bytes()) {
+            Ok(MSC_CBW_SIZE) => {}
+            Ok(sent) => return Err(self.recover(UsbError::TransferError(format!("Short CBW write: {} bytes", sent)))),
+            Err(e) => return Err(self.recover(e)),
+        }
+
+        // 2. Data: a STALL ends the data stage early, the CSW tells what happened
+        let mut transferred = 0;
+        match data {
+            DataStage::None => {}
+            DataStage::In(buf) => match self.transport.bulk_in(buf) {
+                Ok(received) => transferred = received,
+                Err(UsbError::Stall) => self.transport.clear_halt_in()?,
+                Err(e) => return Err(self.recover(e)),
+            },
+            DataStage::Out(buf) => match self.transport.bulk_out(buf) {
+                Ok(sent) => transferred = sent,
+                Err(UsbError::Stall) => self.transport.clear_halt_out()?,
+                Err(e) => return Err(self.recover(e)),
+            },
+        }
 
-    // TODO: Add a higher-level layer (e.g., UsbMassStorageDevice)
-    // that uses UsbDevice to implement the BlockDevice trait.
+        // 3. Status: a stalled bulk IN is cleared once and the CSW read again
+        let mut raw = [0u8; MSC_CSW_SIZE];
+        let mut result = self.transport.bulk_in(&mut raw);
+        if let Err(UsbError::Stall) = result {
+            self.transport.clear_halt_in()?;
+            result = self.transport.bulk_in(&mut raw);
+        }
+        let received = match result {
+            Ok(received) => received,
+            Err(e) => return Err(self.recover(e)),
+        };
+        let csw = match CommandStatusWrapper::parse(&raw[..received]) {
+            Some(csw) => csw,
+            None => return Err(self.recover(UsbError::InvalidCsw(format!("{} bytes, bad size or signature", received)))),
+        };
+        if csw.tag != tag {
+            return Err(self.recover(UsbError::TagMismatch { expected: tag, received: csw.tag }));
+        }
+        match csw.status {
+            MSC_CSW_STATUS_PASSED => Ok(transferred),
+            MSC_CSW_STATUS_FAILED => Err(UsbError::CommandFailed(None)),
+            MSC_CSW_STATUS_PHASE_ERROR => Err(self.recover(UsbError::PhaseError)),
+            other => Err(self.recover(UsbError::InvalidCsw(format!("status {:#x}", other)))),
+        }
+    }
+
+    // Validates a block range and buffer length for a read/write.
+    fn check_transfer(&self, block_id: u64, len: usize) -> Result<(), BlockDeviceError> {
+        let block_size = self.capacity.block_size as usize;
+        if len == 0 || len % block_size != 0 {
+            return Err(BlockDeviceError::BlockSizeError(format!(
+                "Buffer length ({}) must be a non-zero multiple of device block size ({}).",
+                len, block_size
+            )));
+        }
+        let blocks = (len / block_size) as u64;
+        if block_id.checked_add(blocks).map_or(true, |end| end > self.capacity.block_count) {
+            return Err(BlockDeviceError::InvalidParameter(format!(
+                "Block range {}+{} is out of bounds. Total blocks: {}",
+                block_id, blocks, self.capacity.block_count
+            )));
+        }
+        Ok(())
+    }
+
+    fn chunk_bytes(&self) -> usize {
+        let block_size = self.capacity.block_size as usize;
+        MSC_MAX_TRANSFER_BYTES / block_size * block_size
+    }
+}
+
+/// Maps a USB/BOT error to a BlockDeviceError; SCSI sense data picks the specific kind.
+fn map_usb_error_to_block_device_error(e: UsbError) -> BlockDeviceError {
+    match e {
+        UsbError::CommandFailed(Some(sense)) => sense_to_block_device_error(&sense),
+        UsbError::Timeout => BlockDeviceError::TimedOut,
+        UsbError::DeviceNotFound => BlockDeviceError::DeviceNotFound(String::from("USB Device Not Found")),
+        UsbError::InvalidEndpoint => BlockDeviceError::InvalidParameter(String::from("Invalid USB Endpoint")),
+        other => BlockDeviceError::DeviceError(format!("USB mass storage error: {}", other)),
+    }
+}
+
+impl<T: BulkOnlyTransport> BlockDevice for UsbMassStorageDevice<T> {
+    /// Reads one or more blocks with READ(10)/READ(16), split into transfers of at most 64 KiB.
+    fn read_block(&mut self, block_id: u64, buf: &mut [u8]) -> Result<(), BlockDeviceError> {
+        self.check_transfer(block_id, buf.len())?;
+        let block_size = self.capacity.block_size as usize;
+        let chunk_bytes = self.chunk_bytes();
+        let mut lba = block_id;
+        for chunk in buf.chunks_mut(chunk_bytes) {
+            let blocks = (chunk.len() / block_size) as u32;
+            let expected = chunk.len();
+            let received = self
+                .command(&Cdb::read(lba, blocks), DataStage::In(chunk))
+                .map_err(map_usb_error_to_block_device_error)?;
+            if received != expected {
+                return Err(BlockDeviceError::DeviceError(format!("Short read at LBA {}: {} of {} bytes", lba, received, expected)));
+            }
+            lba += blocks as u64;
+        }
+        Ok(())
+    }
+
+    /// Writes one or more blocks with WRITE(10)/WRITE(16).
+    fn write_block(&mut self, block_id: u64, buf: &[u8]) -> Result<(), BlockDeviceError> {
+        self.check_transfer(block_id, buf.len())?;
+        let block_size = self.capacity.block_size as usize;
+        let chunk_bytes = self.chunk_bytes();
+        let mut lba = block_id;
+        for chunk in buf.chunks(chunk_bytes) {
+            let blocks = (chunk.len() / block_size) as u32;
+            let sent = self
+                .command(&Cdb::write(lba, blocks), DataStage::Out(chunk))
+                .map_err(map_usb_error_to_block_device_error)?;
+            if sent != chunk.len() {
+                return Err(BlockDeviceError::DeviceError(format!("Short write at LBA {}: {} of {} bytes", lba, sent, chunk.len())));
+            }
+            lba += blocks as u64;
+        }
+        Ok(())
+    }
+
+    /// Returns the logical block length reported by READ CAPACITY.
+    fn block_size(&self) -> usize {
+        self.capacity.block_size as usize
+    }
+
+    /// Returns the number of logical blocks (last LBA + 1).
+    fn block_count(&self) -> u64 {
+        self.capacity.block_count
+    }
 }
 
 
@@ -355,6 +859,7 @@ mod tests {
     use alloc::string::ToString;
     use alloc::vec::Vec;
 
+    use crate::scsi::*; // Opcodes for the fake SCSI target
 
     // Mock Sahne64 USB API functions for no_std testing
     #[cfg(not(feature = "std"))] // Only compile mocks in no_std test
@@ -550,6 +1055,354 @@ mod tests {
     }
 
 
+    // Faults the scripted endpoint injects into the next command with a given opcode
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Fault {
+        WrongCswTag, // CSW echoes a different tag
+        PhaseError, // CSW status 2
+        StallCsw, // First attempt to read the CSW stalls
+    }
+
+    enum FakePhase {
+        Command,
+        DataIn(Vec<u8>),
+        DataOut { lba: u64, len: usize },
+        Status,
+    }
+
+    /// Scripted fake bulk endpoint pair in front of an in-memory (sparse) SCSI disk.
+    struct FakeMscEndpoint {
+        block_size: usize,
+        block_count: u64,
+        blocks: std::collections::HashMap<u64, Vec<u8>>,
+        write_protected: bool,
+        unit_attention: bool, // Reported by the first command after power-on
+        faults: std::collections::VecDeque<(u8, Fault)>,
+        phase: FakePhase,
+        tag: u32,
+        status: u8,
+        residue: u32,
+        fault: Option<Fault>,
+        sense: SenseData,
+        halted_in: bool,
+        halted_out: bool,
+        opcodes: Vec<u8>, // Every CDB opcode received
+        events: Vec<&'static str>, // "reset", "clear_in", "clear_out"
+    }
+
+    impl FakeMscEndpoint {
+        fn new(block_size: usize, block_count: u64) -> Self {
+            FakeMscEndpoint {
+                block_size,
+                block_count,
+                blocks: std::collections::HashMap::new(),
+                write_protected: false,
+                unit_attention: false,
+                faults: std::collections::VecDeque::new(),
+                phase: FakePhase::Command,
+                tag: 0,
+                status: 0,
+                residue: 0,
+                fault: None,
+                sense: SenseData { key: SenseKey::NoSense, asc: 0, ascq: 0, information: None },
+                halted_in: false,
+                halted_out: false,
+                opcodes: Vec::new(),
+                events: Vec::new(),
+            }
+        }
+
+        fn fail(&mut self, key: SenseKey, asc: u8, length: usize, data_in: bool) {
+            self.sense = SenseData { key, asc, ascq: 0, information: None };
+            self.status = 1;
+            self.residue = length as u32;
+            self.phase = FakePhase::Status;
+            if length > 0 {
+                // BOT 6.7: the device halts the data pipe instead of moving data
+                if data_in { self.halted_in = true } else { self.halted_out = true }
+            }
+        }
+
+        fn respond(&mut self, mut data: Vec<u8>, length: usize) {
+            data.truncate(length);
+            self.residue = (length - data.len()) as u32;
+            self.status = 0;
+            self.phase = FakePhase::DataIn(data);
+        }
+
+        fn handle_command(&mut self, length: usize, data_in: bool, cdb: &[u8]) {
+            let opcode = cdb[0];
+            if self.unit_attention && opcode != SCSI_INQUIRY && opcode != SCSI_REQUEST_SENSE {
+                self.unit_attention = false;
+                return self.fail(SenseKey::UnitAttention, 0x29, length, data_in); // Power on occurred
+            }
+            let (lba, count) = match opcode {
+                SCSI_READ_10 | SCSI_WRITE_10 => (
+                    u32::from_be_bytes([cdb[2], cdb[3], cdb[4], cdb[5]]) as u64,
+                    u16::from_be_bytes([cdb[7], cdb[8]]) as u64,
+                ),
+                SCSI_READ_16 | SCSI_WRITE_16 => (
+                    u64::from_be_bytes(cdb[2..10].try_into().unwrap()),
+                    u32::from_be_bytes([cdb[10], cdb[11], cdb[12], cdb[13]]) as u64,
+                ),
+                _ => (0, 0),
+            };
+            match opcode {
+                SCSI_TEST_UNIT_READY => {
+                    self.status = 0;
+                    self.residue = 0;
+                    self.phase = FakePhase::Status;
+                }
+                SCSI_INQUIRY => {
+                    let mut data = vec![b' '; 36];
+                    data[0] = 0x00; // Direct access block device
+                    data[1] = 0x80; // Removable
+                    data[8..13].copy_from_slice(b"SADAK");
+                    data[16..26].copy_from_slice(b"Fake Stick");
+                    data[32..36].copy_from_slice(b"0.01");
+                    self.respond(data, length);
+                }
+                SCSI_REQUEST_SENSE => {
+                    let data = self.sense.to_fixed_bytes().to_vec();
+                    self.sense = SenseData { key: SenseKey::NoSense, asc: 0, ascq: 0, information: None };
+                    self.respond(data, length);
+                }
+                SCSI_READ_CAPACITY_10 => {
+                    let last_lba = core::cmp::min(self.block_count - 1, u32::MAX as u64) as u32;
+                    let mut data = last_lba.to_be_bytes().to_vec();
+                    data.extend_from_slice(&(self.block_size as u32).to_be_bytes());
+                    self.respond(data, length);
+                }
+                SCSI_SERVICE_ACTION_IN_16 if cdb[1] == SCSI_SAI_READ_CAPACITY_16 => {
+                    let mut data = vec![0u8; 32];
+                    data[0..8].copy_from_slice(&(self.block_count - 1).to_be_bytes());
+                    data[8..12].copy_from_slice(&(self.block_size as u32).to_be_bytes());
+                    self.respond(data, length);
+                }
+                SCSI_READ_10 | SCSI_READ_16 | SCSI_WRITE_10 | SCSI_WRITE_16 => {
+                    let is_write = opcode == SCSI_WRITE_10 || opcode == SCSI_WRITE_16;
+                    if lba + count > self.block_count {
+                        return self.fail(SenseKey::IllegalRequest, 0x21, length, data_in); // LBA out of range
+                    }
+                    if is_write && self.write_protected {
+                        return self.fail(SenseKey::DataProtect, 0x27, length, data_in); // Write protected
+                    }
+                    if is_write {
+                        self.phase = FakePhase::DataOut { lba, len: length };
+                    } else {
+                        let mut data = Vec::with_capacity(length);
+                        for block in lba..lba + count {
+                            match self.blocks.get(&block) {
+                                Some(content) => data.extend_from_slice(content),
+                                None => data.extend(std::iter::repeat(0u8).take(self.block_size)),
+                            }
+                        }
+                        self.respond(data, length);
+                    }
+                }
+                _ => self.fail(SenseKey::IllegalRequest, 0x20, length, data_in), // Invalid command operation code
+            }
+        }
+    }
+
+    impl BulkOnlyTransport for FakeMscEndpoint {
+        fn bulk_out(&mut self, data: &[u8]) -> Result<usize, UsbError> {
+            if self.halted_out {
+                return Err(UsbError::Stall);
+            }
+            match core::mem::replace(&mut self.phase, FakePhase::Command) {
+                FakePhase::Command => {
+                    assert_eq!(data.len(), MSC_CBW_SIZE, "CBW must be 31 bytes");
+                    assert_eq!(u32::from_le_bytes(data[0..4].try_into().unwrap()), MSC_CBW_SIGNATURE);
+                    self.tag = u32::from_le_bytes(data[4..8].try_into().unwrap());
+                    let length = u32::from_le_bytes(data[8..12].try_into().unwrap()) as usize;
+                    let data_in = data[12] & MSC_CBW_FLAG_DATA_IN != 0;
+                    let cdb = data[15..15 + data[14] as usize].to_vec();
+                    self.opcodes.push(cdb[0]);
+                    self.fault = self.faults.iter().position(|(op, _)| *op == cdb[0]).and_then(|i| self.faults.remove(i)).map(|(_, f)| f);
+                    self.handle_command(length, data_in, &cdb);
+                    Ok(MSC_CBW_SIZE)
+                }
+                FakePhase::DataOut { lba, len } => {
+                    assert_eq!(data.len(), len);
+                    for (i, block) in data.chunks(self.block_size).enumerate() {
+                        self.blocks.insert(lba + i as u64, block.to_vec());
+                    }
+                    self.status = 0;
+                    self.residue = 0;
+                    self.phase = FakePhase::Status;
+                    Ok(data.len())
+                }
+                other => {
+                    self.phase = other;
+                    Err(UsbError::TransferError(String::from("unexpected OUT transfer")))
+                }
+            }
+        }
+
+        fn bulk_in(&mut self, buf: &mut [u8]) -> Result<usize, UsbError> {
+            if self.halted_in {
+                return Err(UsbError::Stall);
+            }
+            match core::mem::replace(&mut self.phase, FakePhase::Command) {
+                FakePhase::DataIn(data) => {
+                    let n = core::cmp::min(buf.len(), data.len());
+                    buf[..n].copy_from_slice(&data[..n]);
+                    self.phase = FakePhase::Status;
+                    Ok(n)
+                }
+                FakePhase::Status => {
+                    if self.fault == Some(Fault::StallCsw) {
+                        self.fault = None;
+                        self.halted_in = true;
+                        self.phase = FakePhase::Status;
+                        return Err(UsbError::Stall);
+                    }
+                    let tag = if self.fault == Some(Fault::WrongCswTag) { self.tag ^ 0xFFFF } else { self.tag };
+                    let status = if self.fault == Some(Fault::PhaseError) { 2 } else { self.status };
+                    buf[0..4].copy_from_slice(&MSC_CSW_SIGNATURE.to_le_bytes());
+                    buf[4..8].copy_from_slice(&tag.to_le_bytes());
+                    buf[8..12].copy_from_slice(&self.residue.to_le_bytes());
+                    buf[12] = status;
+                    Ok(MSC_CSW_SIZE)
+                }
+                other => {
+                    self.phase = other;
+                    Err(UsbError::Timeout) // Device NAKs: nothing to send
+                }
+            }
+        }
+
+        fn bulk_only_reset(&mut self) -> Result<(), UsbError> {
+            self.phase = FakePhase::Command;
+            self.events.push("reset");
+            Ok(())
+        }
+
+        fn clear_halt_in(&mut self) -> Result<(), UsbError> {
+            self.halted_in = false;
+            self.events.push("clear_in");
+            Ok(())
+        }
+
+        fn clear_halt_out(&mut self) -> Result<(), UsbError> {
+            self.halted_out = false;
+            self.events.push("clear_out");
+            Ok(())
+        }
+    }
+
+    fn pattern(len: usize, seed: u8) -> Vec<u8> {
+        (0..len).map(|i| (i as u8).wrapping_mul(13).wrapping_add(seed)).collect()
+    }
+
+    #[test]
+    fn test_msc_init_and_read_write() -> Result<(), BlockDeviceError> {
+        let mut endpoint = FakeMscEndpoint::new(512, 2048);
+        endpoint.unit_attention = true;
+        let mut device = UsbMassStorageDevice::new(endpoint, 0).map_err(map_usb_error_to_block_device_error)?;
+
+        assert_eq!(device.inquiry().vendor, "SADAK");
+        assert_eq!(device.inquiry().product, "Fake Stick");
+        assert!(device.inquiry().removable);
+        assert_eq!((device.block_size(), device.block_count()), (512, 2048));
+        // UNIT ATTENTION on the first TEST UNIT READY: sense fetched, command retried
+        assert_eq!(
+            device.transport().opcodes,
+            vec![SCSI_INQUIRY, SCSI_TEST_UNIT_READY, SCSI_REQUEST_SENSE, SCSI_TEST_UNIT_READY, SCSI_READ_CAPACITY_10]
+        );
+
+        // 200 blocks = 100 KiB: split into 64 KiB + 36 KiB commands
+        let data = pattern(200 * 512, 7);
+        device.write_block(100, &data)?;
+        let mut read_back = vec![0u8; data.len()];
+        device.read_block(100, &mut read_back)?;
+        assert_eq!(read_back, data);
+        let io: Vec<u8> = device.transport().opcodes[5..].to_vec();
+        assert_eq!(io, vec![SCSI_WRITE_10, SCSI_WRITE_10, SCSI_READ_10, SCSI_READ_10]);
+
+        // Driver-side validation
+        assert!(matches!(device.read_block(2047, &mut vec![0u8; 1024]), Err(BlockDeviceError::InvalidParameter(_))));
+        assert!(matches!(device.write_block(0, &[0u8; 100]), Err(BlockDeviceError::BlockSizeError(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_msc_read_capacity_16_and_read_16() -> Result<(), BlockDeviceError> {
+        let endpoint = FakeMscEndpoint::new(4096, 0x1_0000_0100); // Larger than READ CAPACITY(10) can report
+        let mut device = UsbMassStorageDevice::new(endpoint, 0).map_err(map_usb_error_to_block_device_error)?;
+        assert_eq!(device.block_count(), 0x1_0000_0100);
+        assert_eq!(device.block_size(), 4096);
+        assert!(device.transport().opcodes.contains(&SCSI_SERVICE_ACTION_IN_16));
+
+        let data = pattern(2 * 4096, 3);
+        device.write_block(0x1_0000_0000, &data)?;
+        let mut read_back = vec![0u8; data.len()];
+        device.read_block(0x1_0000_0000, &mut read_back)?;
+        assert_eq!(read_back, data);
+        let opcodes = &device.transport().opcodes;
+        assert_eq!(&opcodes[opcodes.len() - 2..], &[SCSI_WRITE_16, SCSI_READ_16]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_msc_check_condition_errors() -> Result<(), BlockDeviceError> {
+        let mut endpoint = FakeMscEndpoint::new(512, 64);
+        endpoint.write_protected = true;
+        let mut device = UsbMassStorageDevice::new(endpoint, 0).map_err(map_usb_error_to_block_device_error)?;
+
+        // Write to a protected medium: OUT pipe stalls, CSW fails, REQUEST SENSE -> DATA PROTECT
+        match device.write_block(0, &[0u8; 512]) {
+            Err(BlockDeviceError::PermissionDenied(_)) => {}
+            other => panic!("Beklenenden farklı sonuç: {:?}", other),
+        }
+        assert_eq!(device.transport().events, vec!["clear_out"]);
+
+        // Device-side LBA check (bypassing the driver's own bounds check): IN pipe stalls
+        let mut buf = [0u8; 512];
+        match device.command(&Cdb::read(64, 1), DataStage::In(&mut buf)) {
+            Err(UsbError::CommandFailed(Some(sense))) => {
+                assert_eq!((sense.key, sense.asc), (SenseKey::IllegalRequest, 0x21));
+                assert!(matches!(sense_to_block_device_error(&sense), BlockDeviceError::InvalidParameter(_)));
+            }
+            other => panic!("Beklenenden farklı sonuç: {:?}", other),
+        }
+        assert_eq!(device.transport().events, vec!["clear_out", "clear_in"]);
+
+        // The device still works afterwards
+        device.read_block(63, &mut buf)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_msc_tag_check_and_reset_recovery() -> Result<(), BlockDeviceError> {
+        let endpoint = FakeMscEndpoint::new(512, 64);
+        let mut device = UsbMassStorageDevice::new(endpoint, 0).map_err(map_usb_error_to_block_device_error)?;
+        device.write_block(5, &pattern(512, 1))?;
+        let mut buf = vec![0u8; 512];
+
+        // CSW with the wrong tag: error + reset recovery
+        device.transport.faults.push_back((SCSI_READ_10, Fault::WrongCswTag));
+        match device.read_block(5, &mut buf) {
+            Err(BlockDeviceError::DeviceError(msg)) => assert!(msg.contains("tag mismatch")),
+            other => panic!("Beklenenden farklı sonuç: {:?}", other),
+        }
+        assert_eq!(device.transport().events, vec!["reset", "clear_in", "clear_out"]);
+
+        // Phase error: reset recovery again
+        device.transport.faults.push_back((SCSI_READ_10, Fault::PhaseError));
+        assert!(device.read_block(5, &mut buf).is_err());
+        assert_eq!(device.transport().events.len(), 6);
+
+        // A stalled CSW is cleared and re-read without a reset
+        device.transport.faults.push_back((SCSI_READ_10, Fault::StallCsw));
+        device.read_block(5, &mut buf)?;
+        assert_eq!(buf, pattern(512, 1));
+        assert_eq!(device.transport().events.last(), Some(&"clear_in"));
+        assert_eq!(device.transport().events.len(), 7);
+        Ok(())
+    }
+
     // TODO: Add tests for timeout errors (requires mocking or configuring device/API).
     // TODO: Add tests for transfer errors (requires mocking API).
     // TODO: Add test for operation on closed handle (hard to test directly due to Drop).
@@ -0,0 +1,1053 @@
+#![allow(dead_code)] // Henüz kullanılmayan kodlar için uyarı vermesin
+#![cfg_attr(not(feature = "std"), no_std)] // Standart kütüphaneye ihtiyaç duymuyoruz
+
+// no_std ortamında alloc crate'inden gelen yapıları kullanabilmek için
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+
+// ZIP arşivi yazıcısı (cihaz üzerinde paketleme için).
+//
+// - Girdiler "stored" veya "deflate" yöntemiyle yazılır. DEFLATE sıkıştırıcı LZ77 (hash
+//   zinciri) + sabit Huffman kodları kullanır; sıkıştırmanın işe yaramadığı bloklar "stored"
+//   blok olarak yazılır. `add_file` ile verilen girdi hiç küçülmüyorsa tümüyle stored yazılır.
+// - Akışlı yazma (`start_file` / `write_data` / `finish_file`): veri parça parça sıkıştırılıp
+//   hedefe yazılır. Varsayılan olarak CRC ve boyutlar girdi bitince yerel başlığa geri
+//   yazılır; veri tanımlayıcı kipinde (genel amaçlı bayrak bit 3) başlığa dokunulmaz, değerler
+//   verinin ardından bir veri tanımlayıcısına yazılır.
+// - ZIP64: boyutu 4 GiB'ı aşabilecek girdiler (`large_file`) yerel başlıkta ZIP64 alanı taşır;
+//   merkezi dizinde taşan alanlar, 65535'i aşan girdi sayısı veya 4 GiB ötesindeki merkezi
+//   dizin için ZIP64 uç kaydı ve bulucu yazılır.
+// - Zaman damgası (DOS zamanı + "UT" alanı), Unix izinleri ("version made by" = Unix, harici
+//   öznitelikler) ve sahiplik ("ux" alanı) SADAK inode'larından / VFS meta verisinden taşınır.
+// - `ZipWriter::append` var olan bir arşive yerinde ekleme yapar: yeni girdiler eski merkezi
+//   dizinin yerine yazılır, bitişte eski girdilerin merkezi dizin kayıtları olduğu gibi
+//   (harici öznitelikler, ek alanlar, yorumlar dahil) kopyalanır ve yenileri ardına eklenir.
+
+use crate::archivefs::{
+    locate_central_directory, read_central_directory, read_exact_at, unix_to_dos_time, ArchiveError, ZipEntry,
+    CENTRAL_HEADER_SIGNATURE, DIST_BASE, DIST_EXTRA, END_OF_CENTRAL_DIRECTORY_SIGNATURE, EXTRA_EXTENDED_TIMESTAMP,
+    EXTRA_UNIX_OWNER, EXTRA_ZIP64, HOST_UNIX, LENGTH_BASE, LENGTH_EXTRA, LOCAL_HEADER_SIGNATURE, METHOD_DEFLATE,
+    METHOD_STORED, S_IFDIR, S_IFLNK, WINDOW_SIZE, ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE, ZIP64_LOCATOR_SIGNATURE,
+};
+use crate::crypto::Crc32; // Assuming Crc32 is in crate::crypto
+use crate::inodetable::Inode;
+use crate::vfs::{FileType, Metadata, Vfs, VfsError, VfsFile, O_RDONLY};
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+
+use core::cmp;
+use core::mem;
+use core::result::Result;
+
+/// Veri tanımlayıcısı imzası.
+pub const DATA_DESCRIPTOR_SIGNATURE: u32 = 0x0807_4b50;
+
+const ZIP64_LIMIT: u64 = 0xFFFF_FFFF;
+const ZIP64_ENTRY_LIMIT: u64 = 0xFFFF;
+
+// "version needed to extract" değerleri
+const VERSION_STORED: u16 = 10;
+const VERSION_DEFLATE: u16 = 20;
+const VERSION_ZIP64: u16 = 45;
+// Unix üzerinde, ZIP 6.3 belirtimine göre
+const VERSION_MADE_BY: u16 = (HOST_UNIX << 8) | 63;
+
+// Genel amaçlı bayraklar
+const FLAG_DATA_DESCRIPTOR: u16 = 0x0008;
+const FLAG_UTF8: u16 = 0x0800;
+
+const S_IFREG: u32 = 0o100000;
+
+// MS-DOS dizin özniteliği (harici özniteliklerin alt baytı)
+const DOS_DIRECTORY: u32 = 0x10;
+
+// `add_from_vfs` okuma arabelleği
+const COPY_BUFFER_LEN: usize = 64 * 1024;
+
+// ---------------------------------------------------------------------------------------------
+// DEFLATE (RFC 1951) sıkıştırıcı
+
+// Sıkıştırılan blok boyutu; stored blok sınırının (65535) altında kalır.
+const BLOCK_SIZE: usize = 32 * 1024;
+const HASH_BITS: u32 = 15;
+const HASH_SIZE: usize = 1 << HASH_BITS;
+const MAX_CHAIN: usize = 64;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const NO_POSITION: u32 = u32::MAX;
+
+#[derive(Debug, Clone, Copy)]
+enum Token {
+    Literal(u8),
+    Match { length: u16, distance: u16 },
+}
+
+fn hash3(data: &[u8], pos: usize) -> usize {
+    (((data[pos] as usize) << 10) ^ ((data[pos + 1] as usize) << 5) ^ data[pos + 2] as usize) & (HASH_SIZE - 1)
+}
+
+fn length_index(length: usize) -> usize {
+    if length == MAX_MATCH {
+        28
+    } else {
+        LENGTH_BASE[..28].iter().rposition(|&base| base as usize <= length).unwrap_or(0)
+    }
+}
+
+fn distance_index(distance: usize) -> usize {
+    DIST_BASE.iter().rposition(|&base| base as usize <= distance).unwrap_or(0)
+}
+
+// Sabit Huffman literal/uzunluk kodu: (kod, bit sayısı).
+fn fixed_literal_code(symbol: u16) -> (u16, u32) {
+    match symbol {
+        0..=143 => (0x30 + symbol, 8),
+        144..=255 => (0x190 + symbol - 144, 9),
+        256..=279 => (symbol - 256, 7),
+        _ => (0xC0 + symbol - 280, 8),
+    }
+}
+
+fn reverse_bits(code: u16, length: u32) -> u32 {
+    (code.reverse_bits() >> (16 - length)) as u32
+}
+
+/// Akışlı DEFLATE sıkıştırıcı. Girdi `BLOCK_SIZE`'lık bloklar hâlinde sıkıştırılır; son
+/// 32 KiB geçmiş sonraki bloğun eşleşmeleri için tutulur.
+pub struct Deflater {
+    // Geçmiş (en çok WINDOW_SIZE) + henüz sıkıştırılmamış girdi
+    window: Vec<u8>,
+    pending_start: usize,
+    bits: u64,
+    bit_count: u32,
+    out: Vec<u8>,
+    finished: bool,
+}
+
+impl Deflater {
+    pub fn new() -> Self {
+        Deflater { window: Vec::new(), pending_start: 0, bits: 0, bit_count: 0, out: Vec::new(), finished: false }
+    }
+
+    /// Girdi ekler; dolan bloklar sıkıştırılır.
+    pub fn write(&mut self, data: &[u8]) {
+        debug_assert!(!self.finished);
+        self.window.extend_from_slice(data);
+        while self.window.len() - self.pending_start >= BLOCK_SIZE {
+            self.compress_block(BLOCK_SIZE, false);
+        }
+    }
+
+    /// Kalan girdiyi son blok olarak sıkıştırır ve akışı bayt sınırına hizalar.
+    pub fn finish(&mut self) {
+        if self.finished {
+            return;
+        }
+        let remaining = self.window.len() - self.pending_start;
+        self.compress_block(remaining, true);
+        self.align();
+        self.finished = true;
+    }
+
+    /// Şimdiye kadar üretilen çıktıyı alır.
+    pub fn take_output(&mut self) -> Vec<u8> {
+        mem::take(&mut self.out)
+    }
+
+    fn write_bits(&mut self, value: u32, count: u32) {
+        self.bits |= (value as u64) << self.bit_count;
+        self.bit_count += count;
+        while self.bit_count >= 8 {
+            self.out.push(self.bits as u8);
+            self.bits >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    fn align(&mut self) {
+        if self.bit_count > 0 {
+            self.out.push(self.bits as u8);
+            self.bits = 0;
+            self.bit_count = 0;
+        }
+    }
+
+    fn compress_block(&mut self, length: usize, last: bool) {
+        let start = self.pending_start;
+        let end = start + length;
+        let tokens = self.find_matches(start, end);
+
+        // Sabit Huffman ile blok boyutu (bit) ve stored blok boyutu karşılaştırılır
+        let fixed_bits: usize = 3 + 7 + tokens
+            .iter()
+            .map(|token| match *token {
+                Token::Literal(byte) => fixed_literal_code(byte as u16).1 as usize,
+                Token::Match { length, distance } => {
+                    let li = length_index(length as usize);
+                    let di = distance_index(distance as usize);
+                    fixed_literal_code(257 + li as u16).1 as usize + LENGTH_EXTRA[li] as usize + 5 + DIST_EXTRA[di] as usize
+                }
+            })
+            .sum::<usize>();
+        let stored_bits = 3 + 7 + 32 + length * 8;
+
+        self.write_bits(last as u32, 1);
+        if stored_bits < fixed_bits {
+            self.write_bits(0, 2);
+            self.align();
+            let len = length as u16;
+            self.out.extend_from_slice(&len.to_le_bytes());
+            self.out.extend_from_slice(&(!len).to_le_bytes());
+            self.out.extend_from_slice(&self.window[start..end]);
+        } else {
+            self.write_bits(1, 2);
+            for token in tokens {
+                match token {
+                    Token::Literal(byte) => self.write_symbol(byte as u16),
+                    Token::Match { length, distance } => {
+                        let li = length_index(length as usize);
+                        self.write_symbol(257 + li as u16);
+                        self.write_bits((length - LENGTH_BASE[li]) as u32, LENGTH_EXTRA[li] as u32);
+                        let di = distance_index(distance as usize);
+                        self.write_bits(reverse_bits(di as u16, 5), 5);
+                        self.write_bits((distance - DIST_BASE[di]) as u32, DIST_EXTRA[di] as u32);
+                    }
+                }
+            }
+            self.write_symbol(256);
+        }
+
+        // Yalnızca son WINDOW_SIZE bayt geçmiş olarak kalır
+        self.pending_start = end;
+        if self.pending_start > WINDOW_SIZE {
+            let drop = self.pending_start - WINDOW_SIZE;
+            self.window.drain(..drop);
+            self.pending_start -= drop;
+        }
+    }
+
+    fn write_symbol(&mut self, symbol: u16) {
+        let (code, length) = fixed_literal_code(symbol);
+        self.write_bits(reverse_bits(code, length), length);
+    }
+
+    // [start, end) aralığını açgözlü LZ77 ile belirteçlere ayırır; eşleşmeler blok sonunu aşmaz.
+    fn find_matches(&self, start: usize, end: usize) -> Vec<Token> {
+        let data = &self.window[..];
+        let mut head = vec![NO_POSITION; HASH_SIZE]; // Requires alloc
+        let mut prev = vec![NO_POSITION; end]; // Requires alloc
+        let mut insert = |head: &mut Vec<u32>, prev: &mut Vec<u32>, pos: usize| {
+            if pos + MIN_MATCH <= data.len() {
+                let h = hash3(data, pos);
+                prev[pos] = head[h];
+                head[h] = pos as u32;
+            }
+        };
+        for pos in start.saturating_sub(WINDOW_SIZE)..start {
+            insert(&mut head, &mut prev, pos);
+        }
+
+        let mut tokens = Vec::with_capacity(end - start);
+        let mut pos = start;
+        while pos < end {
+            let max_length = cmp::min(MAX_MATCH, end - pos);
+            let mut best_length = 0;
+            let mut best_distance = 0;
+            if max_length >= MIN_MATCH {
+                let mut candidate = head[hash3(data, pos)];
+                let mut chain = 0;
+                while candidate != NO_POSITION && chain < MAX_CHAIN {
+                    let candidate_pos = candidate as usize;
+                    let distance = pos - candidate_pos;
+                    if distance > WINDOW_SIZE {
+                        break;
+                    }
+                    if data[candidate_pos + best_length] == data[pos + best_length] || best_length == 0 {
+                        let length = data[candidate_pos..]
+                            .iter()
+                            .zip(&data[pos..pos + max_length])
+                            .take_while(|(a, b)| a == b)
+                            .count();
+                        if length > best_length {
+                            best_length = length;
+                            best_distance = distance;
+                            if length == max_length {
+                                break;
+                            }
+                        }
+                    }
+                    candidate = prev[candidate_pos];
+                    chain += 1;
+                }
+            }
+            if best_length >= MIN_MATCH {
+                tokens.push(Token::Match { length: best_length as u16, distance: best_distance as u16 });
+                for p in pos..pos + best_length {
+                    insert(&mut head, &mut prev, p);
+                }
+                pos += best_length;
+            } else {
+                tokens.push(Token::Literal(data[pos]));
+                insert(&mut head, &mut prev, pos);
+                pos += 1;
+            }
+        }
+        tokens
+    }
+}
+
+impl Default for Deflater {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bellekteki veriyi ham DEFLATE akışına sıkıştırır.
+pub fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut deflater = Deflater::new();
+    deflater.write(data);
+    deflater.finish();
+    deflater.take_output()
+}
+
+// ---------------------------------------------------------------------------------------------
+// Arşiv yazıcısı
+
+/// ZIP64 yapılarının ne zaman yazılacağı.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zip64Mode {
+    /// Yalnızca gerektiğinde (taşan alanlar, `large_file` girdileri).
+    Auto,
+    /// Her girdide ve uç kayıtta (ZIP64 bekleyen araçlar için).
+    Always,
+}
+
+/// Tek bir girdinin yazım seçenekleri.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryOptions {
+    /// METHOD_STORED veya METHOD_DEFLATE.
+    pub method: u16,
+    /// Değiştirilme zamanı (Unix zamanı).
+    pub mtime: u64,
+    /// İzin bitleri (0o7777); tür bitleri girdi türünden eklenir.
+    pub permissions: u32,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+    /// Girdi 4 GiB'ı aşabilir: yerel başlığa ZIP64 alanı yazılır. Akışlı yazımda boyut önceden
+    /// bilinmediği için büyük girdilerde açıkça verilmelidir.
+    pub large_file: bool,
+}
+
+impl Default for EntryOptions {
+    fn default() -> Self {
+        EntryOptions { method: METHOD_DEFLATE, mtime: 0, permissions: 0o644, uid: None, gid: None, large_file: false }
+    }
+}
+
+impl EntryOptions {
+    /// VFS meta verisinden (izinler, sahiplik, mtime) seçenekler.
+    pub fn from_metadata(meta: &Metadata) -> Self {
+        EntryOptions {
+            method: METHOD_DEFLATE,
+            mtime: meta.mtime,
+            permissions: meta.mode & 0o7777,
+            uid: Some(meta.uid),
+            gid: Some(meta.gid),
+            large_file: meta.size >= ZIP64_LIMIT,
+        }
+    }
+
+    /// SADAK inode'undan seçenekler.
+    pub fn from_inode(inode: &Inode) -> Self {
+        // packed yapı: alanlar kopyalanarak okunur
+        let (mode, uid, gid, mtime, size) = (inode.mode, inode.uid, inode.gid, inode.mtime, inode.size);
+        EntryOptions {
+            method: METHOD_DEFLATE,
+            mtime,
+            permissions: mode as u32 & 0o7777,
+            uid: Some(uid),
+            gid: Some(gid),
+            large_file: size >= ZIP64_LIMIT,
+        }
+    }
+}
+
+// Yazılmakta olan girdi.
+struct CurrentEntry {
+    entry: ZipEntry,
+    zip64_local: bool,
+    // Yerel başlıktaki ZIP64 alanının verisinin konumu
+    zip64_field_offset: u64,
+    crc: Crc32,
+    deflater: Option<Deflater>,
+}
+
+/// ZIP arşivi yazıcısı. Hedef, rastgele erişimli yazılabilir bir `VfsFile`'dır.
+pub struct ZipWriter {
+    sink: Box<dyn VfsFile>,
+    position: u64,
+    entries: Vec<ZipEntry>,
+    current: Option<CurrentEntry>,
+    zip64_mode: Zip64Mode,
+    data_descriptors: bool,
+    // Eklemede eski girdilerin ham merkezi dizin kayıtları (finish'te aynen yazılır)
+    preserved_directory: Vec<u8>,
+    preserved_entries: usize,
+}
+
+impl ZipWriter {
+    /// Boş bir arşiv başlatır; hedefin mevcut içeriği atılır.
+    pub fn new(mut sink: Box<dyn VfsFile>) -> Result<Self, ArchiveError> {
+        sink.set_len(0).map_err(ArchiveError::Io)?;
+        Ok(ZipWriter {
+            sink,
+            position: 0,
+            entries: Vec::new(),
+            current: None,
+            zip64_mode: Zip64Mode::Auto,
+            data_descriptors: false,
+            preserved_directory: Vec::new(),
+            preserved_entries: 0,
+        })
+    }
+
+    /// Var olan bir arşive ekleme için açar. Yeni girdiler eski merkezi dizinin başından
+    /// itibaren yazılır; `finish` çağrılana kadar arşiv geçersizdir. Eski girdilerin merkezi
+    /// dizin kayıtları değiştirilmeden korunur.
+    pub fn append(mut sink: Box<dyn VfsFile>) -> Result<Self, ArchiveError> {
+        // Merkezi dizin burada doğrulanır (sınırlar ve kayıt uzunlukları)
+        let entries = read_central_directory(sink.as_mut())?;
+        let location = locate_central_directory(sink.as_mut())?;
+        if entries.iter().any(|e| e.local_header_offset >= location.offset) {
+            return Err(ArchiveError::InvalidData(String::from("Girdi merkezi dizinin ardında")));
+        }
+        let mut directory = vec![0u8; location.size as usize]; // Requires alloc
+        read_exact_at(sink.as_mut(), location.offset, &mut directory)?;
+        let records_len = central_records_len(&directory, entries.len())?;
+        directory.truncate(records_len);
+        Ok(ZipWriter {
+            sink,
+            position: location.offset,
+            preserved_entries: entries.len(),
+            entries,
+            current: None,
+            zip64_mode: Zip64Mode::Auto,
+            data_descriptors: false,
+            preserved_directory: directory,
+        })
+    }
+
+    pub fn set_zip64_mode(&mut self, mode: Zip64Mode) {
+        self.zip64_mode = mode;
+    }
+
+    /// Açıkken yeni girdilerin CRC ve boyutları yerel başlığa geri yazılmaz; verinin ardından
+    /// bir veri tanımlayıcısına yazılır (salt ileri yazılan hedefler için uyumlu düzen).
+    pub fn set_data_descriptors(&mut self, enabled: bool) {
+        self.data_descriptors = enabled;
+    }
+
+    /// Arşivdeki (ve yazılmış) girdiler.
+    pub fn entries(&self) -> &[ZipEntry] {
+        &self.entries
+    }
+
+    /// Akışlı bir dosya girdisi başlatır; açık girdi varsa önce o bitirilir.
+    pub fn start_file(&mut self, name: &str, options: &EntryOptions) -> Result<(), ArchiveError> {
+        self.start_entry(name, options, options.method, S_IFREG | (options.permissions & 0o7777))
+    }
+
+    /// Açık girdiye veri ekler.
+    pub fn write_data(&mut self, data: &[u8]) -> Result<(), ArchiveError> {
+        let mut current = self.current.take().ok_or_else(|| ArchiveError::InvalidData(String::from("Açık girdi yok")))?;
+        current.crc.update(data);
+        current.entry.uncompressed_size += data.len() as u64;
+        let result = match current.deflater.as_mut() {
+            Some(deflater) => {
+                deflater.write(data);
+                let output = deflater.take_output();
+                current.entry.compressed_size += output.len() as u64;
+                self.write_all(&output)
+            }
+            None => {
+                current.entry.compressed_size += data.len() as u64;
+                self.write_all(data)
+            }
+        };
+        self.current = Some(current);
+        result
+    }
+
+    /// Açık girdiyi bitirir: CRC ve boyutlar yerel başlığa ya da veri tanımlayıcısına yazılır.
+    pub fn finish_file(&mut self) -> Result<(), ArchiveError> {
+        let mut current = match self.current.take() {
+            Some(current) => current,
+            None => return Ok(()),
+        };
+        if let Some(deflater) = current.deflater.as_mut() {
+            deflater.finish();
+            let output = deflater.take_output();
+            current.entry.compressed_size += output.len() as u64;
+            self.write_all(&output)?;
+        }
+        let mut entry = current.entry;
+        entry.crc32 = current.crc.finalize();
+        if !current.zip64_local && (entry.uncompressed_size >= ZIP64_LIMIT || entry.compressed_size >= ZIP64_LIMIT) {
+            return Err(ArchiveError::Unsupported(format!("{} 4 GiB'ı aşıyor; large_file seçeneği gerekli", entry.name)));
+        }
+
+        if self.data_descriptors {
+            let mut descriptor = Vec::with_capacity(24);
+            put32(&mut descriptor, DATA_DESCRIPTOR_SIGNATURE);
+            put32(&mut descriptor, entry.crc32);
+            if current.zip64_local {
+                put64(&mut descriptor, entry.compressed_size);
+                put64(&mut descriptor, entry.uncompressed_size);
+            } else {
+                put32(&mut descriptor, entry.compressed_size as u32);
+                put32(&mut descriptor, entry.uncompressed_size as u32);
+            }
+            self.write_all(&descriptor)?;
+        } else {
+            let mut fields = Vec::with_capacity(12);
+            put32(&mut fields, entry.crc32);
+            if current.zip64_local {
+                put32(&mut fields, ZIP64_LIMIT as u32);
+                put32(&mut fields, ZIP64_LIMIT as u32);
+                let mut sizes = Vec::with_capacity(16);
+                put64(&mut sizes, entry.uncompressed_size);
+                put64(&mut sizes, entry.compressed_size);
+                self.write_all_at(current.zip64_field_offset, &sizes)?;
+            } else {
+                put32(&mut fields, entry.compressed_size as u32);
+                put32(&mut fields, entry.uncompressed_size as u32);
+            }
+            self.write_all_at(entry.local_header_offset + 14, &fields)?;
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Bellekteki veriyi tek seferde ekler. Deflate istenmiş ama veri küçülmüyorsa stored yazılır.
+    pub fn add_file(&mut self, name: &str, options: &EntryOptions, data: &[u8]) -> Result<(), ArchiveError> {
+        let compressed = if options.method == METHOD_DEFLATE { Some(deflate(data)) } else { None };
+        let (method, payload) = match compressed {
+            Some(ref compressed) if compressed.len() < data.len() => (METHOD_DEFLATE, &compressed[..]),
+            _ => (METHOD_STORED, data),
+        };
+        self.start_entry(name, options, method, S_IFREG | (options.permissions & 0o7777))?;
+        if let Some(current) = self.current.as_mut() {
+            // Veri zaten sıkıştırıldı
+            current.deflater = None;
+            current.crc.update(data);
+            current.entry.uncompressed_size = data.len() as u64;
+            current.entry.compressed_size = payload.len() as u64;
+        }
+        self.write_all(payload)?;
+        self.finish_file()
+    }
+
+    /// Dizin girdisi ekler (ad '/' ile biter).
+    pub fn add_directory(&mut self, name: &str, options: &EntryOptions) -> Result<(), ArchiveError> {
+        let mut name = name.trim_end_matches('/').to_string();
+        name.push('/');
+        self.start_entry(&name, options, METHOD_STORED, S_IFDIR | (options.permissions & 0o7777))?;
+        self.finish_file()
+    }
+
+    /// Sembolik bağ ekler; hedef, girdinin içeriği olarak saklanır.
+    pub fn add_symlink(&mut self, name: &str, target: &str, options: &EntryOptions) -> Result<(), ArchiveError> {
+        self.start_entry(name, options, METHOD_STORED, S_IFLNK | 0o777)?;
+        self.write_data(target.as_bytes())?;
+        self.finish_file()
+    }
+
+    /// VFS'teki bir dosyayı, sembolik bağı veya dizini (özyinelemeli, ada göre sıralı) izinleri,
+    /// sahipliği ve zaman damgasıyla ekler. `archive_name` boşsa dizinin içeriği arşiv köküne
+    /// eklenir. Eklenen girdi sayısını döndürür.
+    pub fn add_from_vfs(&mut self, vfs: &Vfs, path: &str, archive_name: &str) -> Result<usize, ArchiveError> {
+        let meta = vfs.metadata(path).map_err(ArchiveError::Io)?;
+        let options = EntryOptions::from_metadata(&meta);
+        match meta.file_type {
+            FileType::Symlink => {
+                let target = vfs.readlink(path).map_err(ArchiveError::Io)?;
+                self.add_symlink(archive_name, &target, &options)?;
+                Ok(1)
+            }
+            FileType::File => {
+                let mut file = vfs.open(path, O_RDONLY, 0).map_err(ArchiveError::Io)?;
+                self.start_file(archive_name, &options)?;
+                let mut buf = vec![0u8; COPY_BUFFER_LEN]; // Requires alloc
+                let mut offset = 0u64;
+                loop {
+                    let count = file.read_at(offset, &mut buf).map_err(ArchiveError::Io)?;
+                    if count == 0 {
+                        break;
+                    }
+                    self.write_data(&buf[..count])?;
+                    offset += count as u64;
+                }
+                self.finish_file()?;
+                Ok(1)
+            }
+            FileType::Directory => {
+                let mut added = 0;
+                if !archive_name.is_empty() {
+                    self.add_directory(archive_name, &options)?;
+                    added += 1;
+                }
+                let children: Vec<String> = vfs.read_dir(path).map_err(ArchiveError::Io)?.map(|e| e.name).collect();
+                for child in children {
+                    let child_path = format!("{}/{}", path.trim_end_matches('/'), child);
+                    let child_name = if archive_name.is_empty() {
+                        child
+                    } else {
+                        format!("{}/{}", archive_name.trim_end_matches('/'), child)
+                    };
+                    added += self.add_from_vfs(vfs, &child_path, &child_name)?;
+                }
+                Ok(added)
+            }
+        }
+    }
+
+    /// Merkezi dizini ve uç kayıtları yazar, hedefi arşiv sonunda keser ve döndürür.
+    pub fn finish(mut self) -> Result<Box<dyn VfsFile>, ArchiveError> {
+        self.finish_file()?;
+        let always = self.zip64_mode == Zip64Mode::Always;
+        let directory_offset = self.position;
+        let mut directory = mem::take(&mut self.preserved_directory);
+        for entry in &self.entries[self.preserved_entries..] {
+            write_central_record(&mut directory, entry, always);
+        }
+        let directory_size = directory.len() as u64;
+        self.write_all(&directory)?;
+
+        let count = self.entries.len() as u64;
+        let zip64 = always || count >= ZIP64_ENTRY_LIMIT || directory_offset >= ZIP64_LIMIT || directory_size >= ZIP64_LIMIT;
+        let mut tail = Vec::with_capacity(98);
+        if zip64 {
+            let record_offset = self.position;
+            put32(&mut tail, ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+            put64(&mut tail, 44); // Kaydın bu alandan sonraki boyutu
+            put16(&mut tail, VERSION_MADE_BY);
+            put16(&mut tail, VERSION_ZIP64);
+            put32(&mut tail, 0); // Bu diskin numarası
+            put32(&mut tail, 0); // Merkezi dizinin başladığı disk
+            put64(&mut tail, count);
+            put64(&mut tail, count);
+            put64(&mut tail, directory_size);
+            put64(&mut tail, directory_offset);
+
+            put32(&mut tail, ZIP64_LOCATOR_SIGNATURE);
+            put32(&mut tail, 0);
+            put64(&mut tail, record_offset);
+            put32(&mut tail, 1); // Toplam disk sayısı
+        }
+        let short_count = if zip64 { cmp::min(count, ZIP64_ENTRY_LIMIT) } else { count } as u16;
+        put32(&mut tail, END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+        put16(&mut tail, 0);
+        put16(&mut tail, 0);
+        put16(&mut tail, short_count);
+        put16(&mut tail, short_count);
+        put32(&mut tail, if zip64 { ZIP64_LIMIT } else { directory_size } as u32);
+        put32(&mut tail, if zip64 { ZIP64_LIMIT } else { directory_offset } as u32);
+        put16(&mut tail, 0); // Yorum uzunluğu
+        self.write_all(&tail)?;
+
+        // Eklemede eski merkezi dizin yeni sondan uzun olabilir
+        self.sink.set_len(self.position).map_err(ArchiveError::Io)?;
+        self.sink.sync().map_err(ArchiveError::Io)?;
+        Ok(self.sink)
+    }
+
+    // Ad doğrulaması, yerel başlık ve açık girdi durumu.
+    fn start_entry(&mut self, name: &str, options: &EntryOptions, method: u16, unix_mode: u32) -> Result<(), ArchiveError> {
+        self.finish_file()?;
+        validate_name(name)?;
+        if self.entries.iter().any(|e| e.name == name) {
+            return Err(ArchiveError::InvalidData(format!("Girdi zaten var: {}", name)));
+        }
+        if method != METHOD_STORED && method != METHOD_DEFLATE {
+            return Err(ArchiveError::Unsupported(format!("Sıkıştırma yöntemi {}", method)));
+        }
+
+        let mut flags = 0;
+        if !name.is_ascii() {
+            flags |= FLAG_UTF8;
+        }
+        if self.data_descriptors {
+            flags |= FLAG_DATA_DESCRIPTOR;
+        }
+        let zip64_local = options.large_file || self.zip64_mode == Zip64Mode::Always;
+        let entry = ZipEntry {
+            name: name.to_string(),
+            method,
+            flags,
+            crc32: 0,
+            compressed_size: 0,
+            uncompressed_size: 0,
+            local_header_offset: self.position,
+            mtime: options.mtime,
+            unix_mode: Some(unix_mode),
+            uid: options.uid,
+            gid: options.gid,
+        };
+
+        let (date, time) = unix_to_dos_time(entry.mtime);
+        let mut extra = Vec::new();
+        if zip64_local {
+            put16(&mut extra, EXTRA_ZIP64);
+            put16(&mut extra, 16);
+            put64(&mut extra, 0);
+            put64(&mut extra, 0);
+        }
+        write_owner_fields(&mut extra, &entry);
+
+        let mut header = Vec::with_capacity(30 + name.len() + extra.len());
+        put32(&mut header, LOCAL_HEADER_SIGNATURE);
+        put16(&mut header, version_needed(method, zip64_local));
+        put16(&mut header, flags);
+        put16(&mut header, method);
+        put16(&mut header, time);
+        put16(&mut header, date);
+        put32(&mut header, 0); // CRC-32, bitişte
+        let size_placeholder = if zip64_local && self.data_descriptors { ZIP64_LIMIT as u32 } else { 0 };
+        put32(&mut header, size_placeholder);
+        put32(&mut header, size_placeholder);
+        put16(&mut header, name.len() as u16);
+        put16(&mut header, extra.len() as u16);
+        header.extend_from_slice(name.as_bytes());
+        header.extend_from_slice(&extra);
+        let zip64_field_offset = self.position + 30 + name.len() as u64 + 4;
+        self.write_all(&header)?;
+
+        self.current = Some(CurrentEntry {
+            entry,
+            zip64_local,
+            zip64_field_offset,
+            crc: Crc32::new(),
+            deflater: if method == METHOD_DEFLATE { Some(Deflater::new()) } else { None },
+        });
+        Ok(())
+    }
+
+    fn write_all(&mut self, data: &[u8]) -> Result<(), ArchiveError> {
+        self.write_all_at(self.position, data)?;
+        self.position += data.len() as u64;
+        Ok(())
+    }
+
+    fn write_all_at(&mut self, offset: u64, data: &[u8]) -> Result<(), ArchiveError> {
+        let mut done = 0;
+        while done < data.len() {
+            let count = self.sink.write_at(offset + done as u64, &data[done..]).map_err(ArchiveError::Io)?;
+            if count == 0 {
+                return Err(ArchiveError::Io(VfsError::NoSpace));
+            }
+            done += count;
+        }
+        Ok(())
+    }
+}
+
+fn put16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn version_needed(method: u16, zip64: bool) -> u16 {
+    if zip64 {
+        VERSION_ZIP64
+    } else if method == METHOD_DEFLATE {
+        VERSION_DEFLATE
+    } else {
+        VERSION_STORED
+    }
+}
+
+// Girdi adları göreli olmalı; `..` bileşeni ve ters bölü kabul edilmez.
+fn validate_name(name: &str) -> Result<(), ArchiveError> {
+    let invalid = name.is_empty()
+        || name.len() > 0xFFFF
+        || name.starts_with('/')
+        || name.contains('\\')
+        || name.contains('\0')
+        || name.split('/').any(|component| component == "..");
+    if invalid {
+        Err(ArchiveError::InvalidData(format!("Geçersiz girdi adı: {}", name)))
+    } else {
+        Ok(())
+    }
+}
+
+// "UT" (yalnızca mtime) ve sahiplik varsa "ux" ek alanları.
+fn write_owner_fields(extra: &mut Vec<u8>, entry: &ZipEntry) {
+    put16(extra, EXTRA_EXTENDED_TIMESTAMP);
+    put16(extra, 5);
+    extra.push(1);
+    put32(extra, cmp::min(entry.mtime, u32::MAX as u64) as u32);
+    if let (Some(uid), Some(gid)) = (entry.uid, entry.gid) {
+        put16(extra, EXTRA_UNIX_OWNER);
+        put16(extra, 11);
+        extra.push(1);
+        extra.push(4);
+        put32(extra, uid);
+        extra.push(4);
+        put32(extra, gid);
+    }
+}
+
+// İlk `count` merkezi dizin kaydının toplam uzunluğu.
+fn central_records_len(directory: &[u8], count: usize) -> Result<usize, ArchiveError> {
+    let le16 = |offset: usize| u16::from_le_bytes([directory[offset], directory[offset + 1]]) as usize;
+    let mut pos = 0;
+    for index in 0..count {
+        if pos + 46 > directory.len() {
+            return Err(ArchiveError::InvalidData(format!("Merkezi dizin girdisi {} kesik", index)));
+        }
+        pos += 46 + le16(pos + 28) + le16(pos + 30) + le16(pos + 32);
+    }
+    if pos > directory.len() {
+        return Err(ArchiveError::InvalidData(format!("Merkezi dizin girdisi {} kesik", count - 1)));
+    }
+    Ok(pos)
+}
+
+fn write_central_record(out: &mut Vec<u8>, entry: &ZipEntry, always_zip64: bool) {
+    let large_uncompressed = always_zip64 || entry.uncompressed_size >= ZIP64_LIMIT;
+    let large_compressed = always_zip64 || entry.compressed_size >= ZIP64_LIMIT;
+    let large_offset = always_zip64 || entry.local_header_offset >= ZIP64_LIMIT;
+    let zip64 = large_uncompressed || large_compressed || large_offset;
+
+    let mut extra = Vec::new();
+    if zip64 {
+        let mut data = Vec::with_capacity(24);
+        if large_uncompressed {
+            put64(&mut data, entry.uncompressed_size);
+        }
+        if large_compressed {
+            put64(&mut data, entry.compressed_size);
+        }
+        if large_offset {
+            put64(&mut data, entry.local_header_offset);
+        }
+        put16(&mut extra, EXTRA_ZIP64);
+        put16(&mut extra, data.len() as u16);
+        extra.extend_from_slice(&data);
+    }
+    write_owner_fields(&mut extra, entry);
+
+    let (date, time) = unix_to_dos_time(entry.mtime);
+    let (made_by, external_attributes) = match entry.unix_mode {
+        Some(mode) => (VERSION_MADE_BY, (mode << 16) | if entry.is_dir() { DOS_DIRECTORY } else { 0 }),
+        None => (VERSION_DEFLATE, if entry.is_dir() { DOS_DIRECTORY } else { 0 }),
+    };
+    put32(out, CENTRAL_HEADER_SIGNATURE);
+    put16(out, made_by);
+    put16(out, version_needed(entry.method, zip64));
+    put16(out, entry.flags);
+    put16(out, entry.method);
+    put16(out, time);
+    put16(out, date);
+    put32(out, entry.crc32);
+    put32(out, if large_compressed { ZIP64_LIMIT } else { entry.compressed_size } as u32);
+    put32(out, if large_uncompressed { ZIP64_LIMIT } else { entry.uncompressed_size } as u32);
+    put16(out, entry.name.len() as u16);
+    put16(out, extra.len() as u16);
+    put16(out, 0); // Yorum uzunluğu
+    put16(out, 0); // Disk numarası
+    put16(out, 0); // Dahili öznitelikler
+    put32(out, external_attributes);
+    put32(out, if large_offset { ZIP64_LIMIT } else { entry.local_header_offset } as u32);
+    out.extend_from_slice(entry.name.as_bytes());
+    out.extend_from_slice(&extra);
+}
+
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+    use crate::archivefs::{inflate, ArchiveFs};
+    use crate::vfs::{FileSystem, MemFs, O_RDWR};
+    use alloc::sync::Arc;
+
+    fn sample_text() -> Vec<u8> {
+        let mut text = Vec::new();
+        for i in 0..3000 {
+            text.extend_from_slice(format!("satır {}: SADAK varlık paketi\n", i % 97).as_bytes());
+        }
+        text
+    }
+
+    // Sıkıştırılamayan sözde rastgele veri (xorshift)
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state = 0x2545_f491_u32;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect()
+    }
+
+    fn empty_sink(fs: &MemFs) -> Box<dyn VfsFile> {
+        fs.add_file("/paket.zip", Vec::new()).unwrap().open(O_RDWR).unwrap()
+    }
+
+    fn options(mtime: u64, permissions: u32) -> EntryOptions {
+        EntryOptions { mtime, permissions, uid: Some(1000), gid: Some(100), ..EntryOptions::default() }
+    }
+
+    #[test]
+    fn test_deflate_round_trip() {
+        let mut long = sample_text();
+        long.extend_from_slice(&noise(50_000));
+        long.extend_from_slice(&sample_text()[..20_000]);
+        for data in [Vec::new(), b"a".to_vec(), b"merhaba merhaba merhaba!".to_vec(), vec![0u8; 100_000], noise(70_000), long] {
+            let compressed = deflate(&data);
+            assert_eq!(inflate(&compressed).unwrap(), data);
+        }
+        let text = sample_text();
+        assert!(deflate(&text).len() * 5 < text.len());
+        // Sıkıştırılamayan veri stored bloklarla yazılır
+        assert!(deflate(&noise(70_000)).len() <= 70_000 + 3 * 5 + 1);
+    }
+
+    #[test]
+    fn test_write_and_mount_round_trip() -> Result<(), ArchiveError> {
+        let text = sample_text();
+        let random = noise(5000);
+        for (zip64_mode, descriptors) in [(Zip64Mode::Auto, false), (Zip64Mode::Always, false), (Zip64Mode::Auto, true), (Zip64Mode::Always, true)] {
+            let fs = MemFs::new();
+            let mut writer = ZipWriter::new(empty_sink(&fs))?;
+            writer.set_zip64_mode(zip64_mode);
+            writer.set_data_descriptors(descriptors);
+            writer.add_file("mimetype", &EntryOptions { method: METHOD_STORED, ..options(1715949020, 0o644) }, b"application/zip")?;
+            writer.add_directory("varlik", &options(1715949020, 0o750))?;
+            writer.add_file("varlik/metin.txt", &options(1715949020, 0o640), &text)?;
+            writer.add_file("varlik/gurultu.bin", &options(1715949022, 0o600), &random)?;
+            writer.start_file("varlik/akis.txt", &options(1715949024, 0o644))?;
+            for chunk in text.chunks(7000) {
+                writer.write_data(chunk)?;
+            }
+            writer.add_symlink("kisayol", "varlik/metin.txt", &options(1715949020, 0o777))?;
+            let sink = writer.finish()?;
+
+            let archive = ArchiveFs::open(sink)?;
+            let entries = archive.entries();
+            assert_eq!(entries.len(), 6);
+            assert_eq!(entries[2].method, METHOD_DEFLATE);
+            assert!(entries[2].compressed_size * 5 < text.len() as u64);
+            assert_eq!(entries[3].method, METHOD_STORED); // Küçülmedi
+            assert_eq!(entries[4].flags & FLAG_DATA_DESCRIPTOR != 0, descriptors);
+            assert_eq!(archive.read_entry("varlik/metin.txt").map_err(ArchiveError::Io)?, text);
+            assert_eq!(archive.read_entry("varlik/akis.txt").map_err(ArchiveError::Io)?, text);
+            assert_eq!(archive.read_entry("varlik/gurultu.bin").map_err(ArchiveError::Io)?, random);
+            assert_eq!(archive.read_entry("mimetype").map_err(ArchiveError::Io)?, b"application/zip");
+
+            let root = archive.root();
+            let dir = root.lookup("varlik").map_err(ArchiveError::Io)?.metadata().map_err(ArchiveError::Io)?;
+            assert_eq!((dir.file_type, dir.mode, dir.uid, dir.gid), (FileType::Directory, 0o750, 1000, 100));
+            let file = root.lookup("varlik").and_then(|d| d.lookup("gurultu.bin")).and_then(|f| f.metadata()).map_err(ArchiveError::Io)?;
+            assert_eq!((file.mode, file.mtime, file.size), (0o600, 1715949022, 5000));
+            assert_eq!(root.lookup("kisayol").and_then(|l| l.readlink()).map_err(ArchiveError::Io)?, "varlik/metin.txt");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_append_in_place() -> Result<(), ArchiveError> {
+        let fs = MemFs::new();
+        let mut writer = ZipWriter::new(empty_sink(&fs))?;
+        writer.add_file("bir.txt", &options(1715949020, 0o644), b"birinci girdi")?;
+        writer.add_file("iki.txt", &options(1715949020, 0o644), &sample_text())?;
+        let sink = writer.finish()?;
+
+        let mut writer = ZipWriter::append(sink)?;
+        assert_eq!(writer.entries().len(), 2);
+        assert!(matches!(writer.add_file("bir.txt", &options(0, 0o644), b"x"), Err(ArchiveError::InvalidData(_))));
+        assert!(matches!(writer.add_file("../kacak", &options(0, 0o644), b"x"), Err(ArchiveError::InvalidData(_))));
+        writer.add_file("üç.txt", &options(1715949030, 0o600), b"eklenen girdi")?;
+        let sink = writer.finish()?;
+
+        let archive = ArchiveFs::open(sink)?;
+        let names: Vec<&str> = archive.entries().iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["bir.txt", "iki.txt", "üç.txt"]);
+        assert_eq!(archive.read_entry("bir.txt").map_err(ArchiveError::Io)?, b"birinci girdi");
+        assert_eq!(archive.read_entry("iki.txt").map_err(ArchiveError::Io)?, sample_text());
+        assert_eq!(archive.read_entry("üç.txt").map_err(ArchiveError::Io)?, b"eklenen girdi");
+        assert_eq!(archive.entries()[0].uid, Some(1000));
+        Ok(())
+    }
+
+    #[test]
+    fn test_append_preserves_existing_central_records() -> Result<(), ArchiveError> {
+        let fs = MemFs::new();
+        let mut writer = ZipWriter::new(empty_sink(&fs))?;
+        writer.add_file("bir.txt", &options(1715949020, 0o644), b"birinci girdi")?;
+        let mut sink = writer.finish()?;
+
+        // Yazıcının üretmediği alanlar: dahili öznitelik ve DOS "arşiv" biti
+        let location = locate_central_directory(sink.as_mut())?;
+        let mut record = vec![0u8; location.size as usize];
+        read_exact_at(sink.as_mut(), location.offset, &mut record)?;
+        record[36] = 1;
+        record[38] |= 0x20;
+        sink.write_at(location.offset, &record).map_err(ArchiveError::Io)?;
+
+        let mut writer = ZipWriter::append(sink)?;
+        writer.add_file("iki.txt", &options(1715949030, 0o600), b"eklenen girdi")?;
+        let mut sink = writer.finish()?;
+        let location = locate_central_directory(sink.as_mut())?;
+        let mut directory = vec![0u8; location.size as usize];
+        read_exact_at(sink.as_mut(), location.offset, &mut directory)?;
+        assert_eq!(&directory[..record.len()], &record[..]);
+        assert_eq!(ArchiveFs::open(sink)?.read_entry("iki.txt").map_err(ArchiveError::Io)?, b"eklenen girdi");
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_from_vfs_preserves_metadata() -> Result<(), VfsError> {
+        let vfs = Vfs::new();
+        let source = Arc::new(MemFs::new());
+        source.set_time(1715949020);
+        source.add_file("/varlik/a.txt", b"A dosyasi".to_vec())?;
+        source.add_file("/varlik/alt/b.txt", sample_text())?;
+        vfs.mount("/", source.clone())?;
+        vfs.symlink("alt/b.txt", "/varlik/bag")?;
+        vfs.set_metadata("/varlik/a.txt", &crate::vfs::SetMetadata { mode: Some(0o600), uid: Some(7), ..Default::default() })?;
+
+        let fs = MemFs::new();
+        let mut writer = ZipWriter::new(empty_sink(&fs)).map_err(crate::archivefs::map_archive_error_to_vfs_error)?;
+        assert_eq!(writer.add_from_vfs(&vfs, "/varlik", "").map_err(crate::archivefs::map_archive_error_to_vfs_error)?, 4);
+        let archive = ArchiveFs::open(writer.finish().map_err(crate::archivefs::map_archive_error_to_vfs_error)?)
+            .map_err(crate::archivefs::map_archive_error_to_vfs_error)?;
+        let names: Vec<&str> = archive.entries().iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["a.txt", "alt/", "alt/b.txt", "bag"]);
+        let a = archive.root().lookup("a.txt")?.metadata()?;
+        assert_eq!((a.mode, a.uid, a.mtime), (0o600, 7, 1715949020));
+        assert_eq!(archive.read_entry("alt/b.txt")?, sample_text());
+        assert_eq!(archive.root().lookup("bag")?.readlink()?, "alt/b.txt");
+        Ok(())
+    }
+}
+
+
+// Removed redundant print module and panic handler boilerplate.
+// The empty lib module configuration is kept.
+#[cfg(not(any(feature = "std", test)))] // Only when not building std or test
+pub mod lib {} // Keep the empty lib module if needed for the crate structure
@@ -29,6 +29,10 @@ use crate::FileSystemError; // Assuming FileSystemError is in crate
 use crate::superblock::Superblock; // Assuming Superblock is in crate::superblock
 
 
+// Import quota accounting for inode allocation
+use crate::quota::{QuotaManager, QuotaOwner};
+
+
 // Import spin for synchronization (if InodeTable is shared)
 #[cfg(feature = "spin")]
 use spin::Mutex; // Or Spinlock
@@ -70,6 +74,7 @@ pub struct Inode {
     pub mode: u16,       // File mode (permissions, type - e.g., 0o755, S_IFREG, S_IFDIR)
     pub uid: u32,        // User ID
     pub gid: u32,        // Group ID
+    pub project_id: u32, // Project ID (used for project quotas, 0 = no project)
     pub links: u32,      // Number of hard links to this inode
     pub size: u64,       // File size (bytes)
     pub blocks: u64,     // Number of data blocks used by this inode (in filesystem block size)
@@ -80,9 +85,9 @@ pub struct Inode {
     // Let's assume a simple scheme: N direct pointers.
     // Adjust size/types based on desired addressable space and filesystem structure.
     pub direct_blocks: [u64; 12], // Example: 12 direct data block pointers
-     pub indirect_block: u64, // Example: Pointer to a block containing more block pointers
-     pub double_indirect_block: u64, // Example: Pointer to a block containing indirect block pointers
-    // ... other fields ...
+    pub indirect_block: u64, // Pointer to a block containing more block pointers (0 = none)
+    pub double_indirect_block: u64, // Pointer to a block containing indirect block pointers (0 = none)
+    pub xattr_block: u64, // Block holding the extended attributes (0 = none), see crate::sadakfs
 }
 
 impl Inode {
@@ -92,6 +97,7 @@ impl Inode {
             mode,
             uid,
             gid,
+            project_id: 0, // Inherited from the parent directory by the caller if needed
             links: 1, // Typically 1 link when created (from directory entry)
             size: 0,
             blocks: 0,
@@ -99,8 +105,9 @@ impl Inode {
             mtime: 0, // Placeholder
             ctime: 0, // Placeholder
             direct_blocks: [0; 12], // Initialize direct pointers to 0 (invalid block ID)
-             indirect_block: 0, // Placeholder
-             double_indirect_block: 0, // Placeholder
+            indirect_block: 0,
+            double_indirect_block: 0,
+            xattr_block: 0,
         }
     }
 
@@ -148,6 +155,9 @@ impl Inode {
         buffer[offset..offset + mem::size_of_val(&inode.gid)].copy_from_slice(&inode.gid.to_le_bytes());
         offset += mem::size_of_val(&inode.gid);
 
+        buffer[offset..offset + mem::size_of_val(&inode.project_id)].copy_from_slice(&inode.project_id.to_le_bytes());
+        offset += mem::size_of_val(&inode.project_id);
+
         buffer[offset..offset + mem::size_of_val(&inode.links)].copy_from_slice(&inode.links.to_le_bytes());
         offset += mem::size_of_val(&inode.links);
 
@@ -172,11 +182,11 @@ impl Inode {
             offset += mem::size_of_val(ptr);
         }
 
-        // Handle indirect pointers if they exist...
-         if let Some(indirect) = inode.indirect_block {
-             buffer[offset..offset + mem::size_of_val(&indirect)].copy_from_slice(&indirect.to_le_bytes());
-             offset += mem::size_of_val(&indirect);
-         }
+        // Indirect, double indirect and xattr block pointers
+        for ptr in [inode.indirect_block, inode.double_indirect_block, inode.xattr_block] {
+            buffer[offset..offset + mem::size_of_val(&ptr)].copy_from_slice(&ptr.to_le_bytes());
+            offset += mem::size_of_val(&ptr);
+        }
 
 
         Ok(()) // Serialization successful
@@ -218,6 +228,9 @@ impl Inode {
         let gid = u32::from_le_bytes(buffer[offset..offset + mem::size_of::<u32>()].try_into().unwrap());
         offset += mem::size_of::<u32>();
 
+        let project_id = u32::from_le_bytes(buffer[offset..offset + mem::size_of::<u32>()].try_into().unwrap());
+        offset += mem::size_of::<u32>();
+
         let links = u32::from_le_bytes(buffer[offset..offset + mem::size_of::<u32>()].try_into().unwrap());
         offset += mem::size_of::<u32>();
 
@@ -242,14 +255,18 @@ impl Inode {
             offset += mem::size_of::<u64>();
         }
 
-        // Handle indirect pointers if they exist...
-         let indirect_block = u64::from_le_bytes(buffer[offset..offset + mem::size_of::<u64>()].try_into().unwrap());
-         offset += mem::size_of::<u64>();
+        let indirect_block = u64::from_le_bytes(buffer[offset..offset + mem::size_of::<u64>()].try_into().unwrap());
+        offset += mem::size_of::<u64>();
+
+        let double_indirect_block = u64::from_le_bytes(buffer[offset..offset + mem::size_of::<u64>()].try_into().unwrap());
+        offset += mem::size_of::<u64>();
+
+        let xattr_block = u64::from_le_bytes(buffer[offset..offset + mem::size_of::<u64>()].try_into().unwrap());
 
 
         Ok(Inode {
-            mode, uid, gid, links, size, blocks, atime, mtime, ctime, direct_blocks,
-            // indirect_block,
+            mode, uid, gid, project_id, links, size, blocks, atime, mtime, ctime, direct_blocks,
+            indirect_block, double_indirect_block, xattr_block,
         })
     }
 
@@ -497,10 +514,52 @@ impl InodeTable {
         self.inodes.get_mut(index) // Accesses in-memory Vec
     }
 
-    // TODO: Add methods for inode allocation and deallocation.
-    // This involves finding a free inode in the in-memory table, marking it as used,
-    // and updating free inode counts (in Superblock and potentially in-memory cache).
-    // Allocation might need synchronization if multiple threads allocate concurrently.
+    /// Allocates the first free inode (mode == 0) and charges it to the owner's
+    /// user, group and project inode quotas.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode`: File mode of the new inode (must be non-zero).
+    /// * `owner`: The uid/gid/project the inode belongs to.
+    /// * `quota`: The quota manager that tracks usage for this filesystem.
+    /// * `now`: The current time (Unix seconds), stored as a/m/ctime and used for grace periods.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the inode number, FileSystemError::QuotaExceeded, or
+    /// FileSystemError::InodeError if the table is full.
+    pub fn allocate_inode(&mut self, mode: u16, owner: &QuotaOwner, quota: &mut QuotaManager, now: u64) -> Result<usize, FileSystemError> {
+        if mode == 0 {
+            return Err(FileSystemError::InvalidParameter(String::from("Inode mode 0 is reserved for free inodes.")));
+        }
+        let index = self.inodes.iter().position(|inode| inode.mode == 0)
+            .ok_or_else(|| FileSystemError::InodeError(String::from("No free inodes available.")))?;
+
+        quota.charge_inode(owner, now)?;
+
+        let mut inode = Inode::new(mode, owner.uid, owner.gid);
+        inode.project_id = owner.project_id;
+        inode.atime = now;
+        inode.mtime = now;
+        inode.ctime = now;
+        self.inodes[index] = inode;
+        Ok(index)
+    }
+
+    /// Frees an inode and refunds it to its owner's inode quotas.
+    /// The inode's data blocks must already have been released (see
+    /// `FreeSpaceManager::release_block_charged`).
+    pub fn free_inode(&mut self, index: usize, quota: &mut QuotaManager) -> Result<(), FileSystemError> {
+        let inode = self.inodes.get_mut(index)
+            .ok_or_else(|| FileSystemError::InodeError(format!("Inode {} is out of bounds.", index)))?;
+        if inode.mode == 0 {
+            return Err(FileSystemError::InodeError(format!("Inode {} is already free.", index)));
+        }
+        quota.release_inode(&QuotaOwner::of(inode));
+        *inode = Inode::new(0, 0, 0);
+        inode.links = 0;
+        Ok(())
+    }
 
     // TODO: Add methods for updating inodes (requires getting mutable reference and then saving).
 
@@ -657,6 +716,7 @@ mod tests {
             mode: 0o644,
             uid: 100,
             gid: 200,
+            project_id: 7,
              links: 2,
             size: 4096,
             blocks: 8, // Assuming 512 byte blocks for inode count
@@ -664,7 +724,9 @@ mod tests {
              mtime: 1678886500,
              ctime: 1678886600,
             direct_blocks: [10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21], // Example block pointers
-             // indirect_block: 30,
+            indirect_block: 30,
+            double_indirect_block: 31,
+            xattr_block: 32,
         };
         let inode_size = Inode::size();
         let mut buffer = vec![0u8; inode_size]; // Requires alloc
@@ -850,8 +912,32 @@ mod tests {
     }
 
 
+    #[test]
+    fn test_inode_allocation_charges_quota() -> Result<(), FileSystemError> {
+        use crate::quota::{QuotaLimits, QuotaType};
+
+        let mut table = InodeTable::new(vec![Inode::new(0, 0, 0); 3]); // Requires alloc
+        let mut quota = QuotaManager::new();
+        let owner = QuotaOwner { uid: 1000, gid: 100, project_id: 42 };
+        quota.set_limits(QuotaType::Project, 42, QuotaLimits { inode_hard: 1, ..Default::default() });
+
+        let index = table.allocate_inode(0o100644, &owner, &mut quota, 1_700_000_000)?;
+        let inode = *table.get_inode(index).unwrap();
+        let (project_id, mtime) = (inode.project_id, inode.mtime); // Copy out of the packed struct
+        assert_eq!(project_id, 42);
+        assert_eq!(mtime, 1_700_000_000);
+
+        // The project's inode hard limit is reached, although free inodes remain
+        assert!(matches!(table.allocate_inode(0o100644, &owner, &mut quota, 0), Err(FileSystemError::QuotaExceeded(_))));
+
+        table.free_inode(index, &mut quota)?;
+        assert_eq!(quota.get(QuotaType::User, 1000).unwrap().inodes_used, 0);
+        assert!(table.free_inode(index, &mut quota).is_err());
+        assert_eq!(table.allocate_inode(0o040755, &owner, &mut quota, 0)?, index);
+        Ok(())
+    }
+
     // TODO: Add tests for InodeTable::get_inode and get_inode_mut with bounds check.
-    // TODO: Add tests for Inode allocation and deallocation (when implemented).
     // TODO: Add tests for Inode block pointer management (when implemented).
     // TODO: Consider tests for concurrency if Mutex is added around InodeTable.
 }